@@ -0,0 +1,727 @@
+//! Graph Algorithms - Finding the hubs of the library
+//!
+//! ```text
+//!                 ┌── cipher
+//!   math ── prime ┼── RSA ──┐
+//!                 └── ...   ├── ENCRYPTION ── security ── firewall
+//!          bitcoin ─ wallet ┘        ▲
+//!                                    │
+//!          PageRank: where random walks end up
+//!          Betweenness: what every path crosses
+//!          k-core: the dense center that survives peeling
+//!          Communities: topic clusters that talk to each other
+//! ```
+//!
+//! All algorithms run on a snapshot of active (non-dormant) edges and
+//! iterate concepts in ID order, so results are deterministic.
+
+use crate::concept::ConceptId;
+use crate::graph::AlexandriaGraph;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+/// A path found by weighted shortest-path search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightedPath {
+    /// Concepts along the path (start and end included)
+    pub concepts: Vec<ConceptId>,
+
+    /// Total cost (sum of 1/weight over traversed edges)
+    pub cost: f32,
+
+    /// Weakest edge weight along the path
+    pub bottleneck: f32,
+}
+
+/// PageRank parameters
+#[derive(Debug, Clone)]
+pub struct PageRankConfig {
+    /// Probability of following an edge instead of jumping (default: 0.85)
+    pub damping: f32,
+
+    /// Maximum iterations (default: 100)
+    pub max_iterations: usize,
+
+    /// Stop when total rank change falls below this (default: 1e-6)
+    pub tolerance: f32,
+}
+
+impl Default for PageRankConfig {
+    fn default() -> Self {
+        Self {
+            damping: 0.85,
+            max_iterations: 100,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+/// A topic cluster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Community {
+    /// Cluster index (0 = largest)
+    pub id: usize,
+
+    /// Member concepts
+    pub members: Vec<ConceptId>,
+
+    /// Sum of edge weights inside the cluster
+    pub internal_weight: f32,
+}
+
+/// Result of community detection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommunityReport {
+    /// Clusters, largest first
+    pub communities: Vec<Community>,
+
+    /// Newman modularity of the partition (-0.5..1, higher = crisper)
+    pub modularity: f32,
+}
+
+impl CommunityReport {
+    /// Find the cluster containing a concept
+    pub fn community_of(&self, concept: &ConceptId) -> Option<&Community> {
+        self.communities
+            .iter()
+            .find(|c| c.members.contains(concept))
+    }
+}
+
+/// Structural metrics for one concept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConceptMetrics {
+    /// The concept
+    pub concept: ConceptId,
+
+    /// PageRank importance (sums to 1 over the graph)
+    pub pagerank: f32,
+
+    /// Number of distinct neighbours
+    pub degree: usize,
+
+    /// Sum of incident edge weights
+    pub strength: f32,
+
+    /// k-core number (largest k whose k-core contains this concept)
+    pub core: usize,
+
+    /// Normalized betweenness centrality (0..1)
+    pub betweenness: f32,
+}
+
+/// Weighted adjacency snapshot used by the algorithms
+struct GraphView {
+    nodes: Vec<ConceptId>,
+    index: HashMap<ConceptId, usize>,
+    /// Directed adjacency following edge direction
+    out: Vec<Vec<(usize, f32)>>,
+    /// Undirected adjacency (merged weights, both directions)
+    undirected: Vec<Vec<(usize, f32)>>,
+}
+
+impl GraphView {
+    fn build(graph: &AlexandriaGraph) -> Self {
+        let mut nodes: Vec<ConceptId> = graph.all_concepts().iter().map(|c| c.id).collect();
+        nodes.sort_by_key(|id| id.0);
+        let index: HashMap<ConceptId, usize> =
+            nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+        let mut out = vec![Vec::new(); nodes.len()];
+        let mut undirected = vec![Vec::new(); nodes.len()];
+
+        let mut edges = graph.all_edges();
+        edges.sort_by(|a, b| a.key().0 .0.cmp(&b.key().0 .0).then(a.key().1 .0.cmp(&b.key().1 .0)));
+
+        for edge in edges {
+            if edge.dormant || edge.weight <= 0.0 || edge.from == edge.to {
+                continue;
+            }
+            let (Some(&from), Some(&to)) = (index.get(&edge.from), index.get(&edge.to)) else {
+                continue;
+            };
+            out[from].push((to, edge.weight));
+            undirected[from].push((to, edge.weight));
+            undirected[to].push((from, edge.weight));
+        }
+
+        Self {
+            nodes,
+            index,
+            out,
+            undirected,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn total_weight(&self) -> f32 {
+        self.undirected
+            .iter()
+            .flat_map(|adj| adj.iter().map(|(_, w)| *w))
+            .sum::<f32>()
+            / 2.0
+    }
+}
+
+/// Min-heap entry for Dijkstra
+#[derive(PartialEq)]
+struct Frontier {
+    cost: f32,
+    node: usize,
+}
+
+impl Eq for Frontier {}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .partial_cmp(&self.cost)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Algorithm functions on the graph
+impl AlexandriaGraph {
+    /// Cheapest path where strong edges are short (cost = 1/weight)
+    ///
+    /// Follows edge direction like [`AlexandriaGraph::find_path`].
+    pub fn weighted_path(&self, from: &ConceptId, to: &ConceptId) -> Option<WeightedPath> {
+        let view = GraphView::build(self);
+        let start = *view.index.get(from)?;
+        let goal = *view.index.get(to)?;
+
+        let mut dist = vec![f32::INFINITY; view.len()];
+        let mut parent: Vec<Option<usize>> = vec![None; view.len()];
+        let mut heap = BinaryHeap::new();
+
+        dist[start] = 0.0;
+        heap.push(Frontier {
+            cost: 0.0,
+            node: start,
+        });
+
+        while let Some(Frontier { cost, node }) = heap.pop() {
+            if node == goal {
+                break;
+            }
+            if cost > dist[node] {
+                continue;
+            }
+            for &(next, weight) in &view.out[node] {
+                let candidate = cost + 1.0 / weight;
+                if candidate < dist[next] {
+                    dist[next] = candidate;
+                    parent[next] = Some(node);
+                    heap.push(Frontier {
+                        cost: candidate,
+                        node: next,
+                    });
+                }
+            }
+        }
+
+        if !dist[goal].is_finite() {
+            return None;
+        }
+
+        let mut path = vec![goal];
+        let mut current = goal;
+        while let Some(p) = parent[current] {
+            path.push(p);
+            current = p;
+        }
+        path.reverse();
+
+        let bottleneck = path
+            .windows(2)
+            .filter_map(|pair| {
+                view.out[pair[0]]
+                    .iter()
+                    .filter(|(n, _)| *n == pair[1])
+                    .map(|(_, w)| *w)
+                    .reduce(f32::max)
+            })
+            .reduce(f32::min)
+            .unwrap_or(0.0);
+
+        Some(WeightedPath {
+            concepts: path.into_iter().map(|i| view.nodes[i]).collect(),
+            cost: dist[goal],
+            bottleneck,
+        })
+    }
+
+    /// PageRank importance of every concept, highest first
+    ///
+    /// Random walks follow edge direction with probability proportional
+    /// to weight; concepts without outgoing edges jump uniformly.
+    pub fn pagerank(&self, config: &PageRankConfig) -> Vec<(ConceptId, f32)> {
+        let view = GraphView::build(self);
+        let ranks = pagerank_scores(&view, config);
+
+        let mut ranked: Vec<(ConceptId, f32)> = view.nodes.iter().copied().zip(ranks).collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        ranked
+    }
+
+    /// Detect topic clusters by greedy modularity optimization
+    pub fn communities(&self) -> CommunityReport {
+        let view = GraphView::build(self);
+        let labels = local_moving(&view);
+
+        let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (node, label) in labels.iter().enumerate() {
+            groups.entry(*label).or_default().push(node);
+        }
+
+        let mut communities: Vec<Community> = groups
+            .values()
+            .map(|members| {
+                let internal_weight = members
+                    .iter()
+                    .flat_map(|&m| view.undirected[m].iter().map(move |e| (m, e)))
+                    .filter(|(_, (n, _))| labels[*n] == labels[members[0]])
+                    .map(|(_, (_, w))| *w)
+                    .sum::<f32>()
+                    / 2.0;
+                Community {
+                    id: 0,
+                    members: members.iter().map(|&m| view.nodes[m]).collect(),
+                    internal_weight,
+                }
+            })
+            .collect();
+
+        communities.sort_by(|a, b| {
+            b.members
+                .len()
+                .cmp(&a.members.len())
+                .then(a.members[0].0.cmp(&b.members[0].0))
+        });
+        for (i, community) in communities.iter_mut().enumerate() {
+            community.id = i;
+        }
+
+        CommunityReport {
+            communities,
+            modularity: modularity(&view, &labels),
+        }
+    }
+
+    /// k-core number of every concept (undirected, unweighted)
+    pub fn core_numbers(&self) -> HashMap<ConceptId, usize> {
+        let view = GraphView::build(self);
+        let cores = core_numbers(&view);
+        view.nodes.iter().copied().zip(cores).collect()
+    }
+
+    /// Concepts in the k-core (every member has at least k neighbours inside)
+    pub fn k_core(&self, k: usize) -> Vec<ConceptId> {
+        let mut members: Vec<ConceptId> = self
+            .core_numbers()
+            .into_iter()
+            .filter(|(_, core)| *core >= k)
+            .map(|(id, _)| id)
+            .collect();
+        members.sort_by_key(|id| id.0);
+        members
+    }
+
+    /// Normalized betweenness centrality (Brandes, undirected, unweighted)
+    pub fn betweenness(&self) -> HashMap<ConceptId, f32> {
+        let view = GraphView::build(self);
+        let scores = betweenness_scores(&view);
+        view.nodes.iter().copied().zip(scores).collect()
+    }
+
+    /// All structural metrics, ranked by PageRank
+    pub fn hubs(&self, top_k: usize) -> Vec<ConceptMetrics> {
+        let view = GraphView::build(self);
+        let ranks = pagerank_scores(&view, &PageRankConfig::default());
+        let cores = core_numbers(&view);
+        let between = betweenness_scores(&view);
+
+        let mut metrics: Vec<ConceptMetrics> = (0..view.len())
+            .map(|i| {
+                let mut neighbours: Vec<usize> = view.undirected[i].iter().map(|(n, _)| *n).collect();
+                neighbours.sort_unstable();
+                neighbours.dedup();
+                ConceptMetrics {
+                    concept: view.nodes[i],
+                    pagerank: ranks[i],
+                    degree: neighbours.len(),
+                    strength: view.undirected[i].iter().map(|(_, w)| *w).sum(),
+                    core: cores[i],
+                    betweenness: between[i],
+                }
+            })
+            .collect();
+
+        metrics.sort_by(|a, b| b.pagerank.partial_cmp(&a.pagerank).unwrap_or(Ordering::Equal));
+        metrics.truncate(top_k);
+        metrics
+    }
+}
+
+fn pagerank_scores(view: &GraphView, config: &PageRankConfig) -> Vec<f32> {
+    let n = view.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let out_weight: Vec<f32> = view
+        .out
+        .iter()
+        .map(|adj| adj.iter().map(|(_, w)| *w).sum())
+        .collect();
+
+    let mut ranks = vec![1.0 / n as f32; n];
+    for _ in 0..config.max_iterations {
+        let dangling: f32 = (0..n).filter(|&i| out_weight[i] == 0.0).map(|i| ranks[i]).sum();
+        let base = (1.0 - config.damping) / n as f32 + config.damping * dangling / n as f32;
+        let mut next = vec![base; n];
+
+        for i in 0..n {
+            if out_weight[i] == 0.0 {
+                continue;
+            }
+            for &(j, w) in &view.out[i] {
+                next[j] += config.damping * ranks[i] * w / out_weight[i];
+            }
+        }
+
+        let change: f32 = next.iter().zip(&ranks).map(|(a, b)| (a - b).abs()).sum();
+        ranks = next;
+        if change < config.tolerance {
+            break;
+        }
+    }
+
+    ranks
+}
+
+/// Greedy modularity optimization (the local-moving phase of Louvain)
+fn local_moving(view: &GraphView) -> Vec<usize> {
+    let n = view.len();
+    let mut labels: Vec<usize> = (0..n).collect();
+    let m2 = 2.0 * view.total_weight();
+    if m2 == 0.0 {
+        return labels;
+    }
+
+    let strength: Vec<f32> = view
+        .undirected
+        .iter()
+        .map(|adj| adj.iter().map(|(_, w)| *w).sum())
+        .collect();
+    let mut community_total = strength.clone();
+
+    for _ in 0..100 {
+        let mut moved = false;
+        for node in 0..n {
+            if view.undirected[node].is_empty() {
+                continue;
+            }
+
+            let current = labels[node];
+            community_total[current] -= strength[node];
+
+            let mut links: HashMap<usize, f32> = HashMap::new();
+            for &(neighbour, weight) in &view.undirected[node] {
+                if neighbour != node {
+                    *links.entry(labels[neighbour]).or_insert(0.0) += weight;
+                }
+            }
+
+            let gain = |label: usize, link: f32| link - community_total[label] * strength[node] / m2;
+            let stay = gain(current, links.get(&current).copied().unwrap_or(0.0));
+
+            // Best gain wins; ties go to the smallest label for determinism
+            let (best, best_gain) = links
+                .iter()
+                .map(|(&label, &link)| (label, gain(label, link)))
+                .fold((current, stay), |acc, cand| {
+                    if cand.1 > acc.1 + 1e-6 || ((cand.1 - acc.1).abs() <= 1e-6 && cand.0 < acc.0) {
+                        cand
+                    } else {
+                        acc
+                    }
+                });
+
+            labels[node] = if best_gain > stay + 1e-6 { best } else { current };
+            community_total[labels[node]] += strength[node];
+            if labels[node] != current {
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+
+    labels
+}
+
+fn modularity(view: &GraphView, labels: &[usize]) -> f32 {
+    let m = view.total_weight();
+    if m == 0.0 {
+        return 0.0;
+    }
+
+    let mut internal: HashMap<usize, f32> = HashMap::new();
+    let mut degree: HashMap<usize, f32> = HashMap::new();
+    for node in 0..view.len() {
+        for &(neighbour, weight) in &view.undirected[node] {
+            *degree.entry(labels[node]).or_insert(0.0) += weight;
+            if labels[neighbour] == labels[node] {
+                *internal.entry(labels[node]).or_insert(0.0) += weight / 2.0;
+            }
+        }
+    }
+
+    degree
+        .iter()
+        .map(|(label, d)| internal.get(label).copied().unwrap_or(0.0) / m - (d / (2.0 * m)).powi(2))
+        .sum()
+}
+
+/// Batagelj–Zaversnik peeling: nodes bucketed by degree, O(n + m)
+fn core_numbers(view: &GraphView) -> Vec<usize> {
+    let n = view.len();
+    let neighbours: Vec<Vec<usize>> = view
+        .undirected
+        .iter()
+        .enumerate()
+        .map(|(node, adj)| {
+            let mut ns: Vec<usize> = adj.iter().map(|(v, _)| *v).filter(|&v| v != node).collect();
+            ns.sort_unstable();
+            ns.dedup();
+            ns
+        })
+        .collect();
+
+    let mut degree: Vec<usize> = neighbours.iter().map(|ns| ns.len()).collect();
+    let max_degree = degree.iter().copied().max().unwrap_or(0);
+
+    // bin[d]: where nodes of degree d start in `order`
+    let mut bin = vec![0; max_degree + 1];
+    for &d in &degree {
+        bin[d] += 1;
+    }
+    let mut start = 0;
+    for count in bin.iter_mut() {
+        let c = *count;
+        *count = start;
+        start += c;
+    }
+
+    let mut order = vec![0; n];
+    let mut pos = vec![0; n];
+    for node in 0..n {
+        pos[node] = bin[degree[node]];
+        order[pos[node]] = node;
+        bin[degree[node]] += 1;
+    }
+    for d in (1..=max_degree).rev() {
+        bin[d] = bin[d - 1];
+    }
+    if let Some(first) = bin.first_mut() {
+        *first = 0;
+    }
+
+    // Peel in degree order; a neighbour losing a link moves down one bucket
+    for i in 0..n {
+        let node = order[i];
+        for &u in &neighbours[node] {
+            if degree[u] > degree[node] {
+                let du = degree[u];
+                let (pu, pw) = (pos[u], bin[du]);
+                let w = order[pw];
+                if u != w {
+                    order.swap(pu, pw);
+                    pos[u] = pw;
+                    pos[w] = pu;
+                }
+                bin[du] += 1;
+                degree[u] -= 1;
+            }
+        }
+    }
+
+    degree
+}
+
+fn betweenness_scores(view: &GraphView) -> Vec<f32> {
+    let n = view.len();
+    let mut centrality = vec![0.0f32; n];
+
+    for source in 0..n {
+        let mut stack = Vec::new();
+        let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut sigma = vec![0.0f32; n];
+        let mut dist = vec![-1i64; n];
+        sigma[source] = 1.0;
+        dist[source] = 0;
+
+        let mut queue = VecDeque::from([source]);
+        while let Some(v) = queue.pop_front() {
+            stack.push(v);
+            for &(w, _) in &view.undirected[v] {
+                if dist[w] < 0 {
+                    dist[w] = dist[v] + 1;
+                    queue.push_back(w);
+                }
+                if dist[w] == dist[v] + 1 && !preds[w].contains(&v) {
+                    sigma[w] += sigma[v];
+                    preds[w].push(v);
+                }
+            }
+        }
+
+        let mut delta = vec![0.0f32; n];
+        while let Some(w) = stack.pop() {
+            for &v in &preds[w] {
+                delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]);
+            }
+            if w != source {
+                centrality[w] += delta[w];
+            }
+        }
+    }
+
+    // Each undirected pair was counted from both ends
+    let pairs = if n > 2 { ((n - 1) * (n - 2)) as f32 } else { 1.0 };
+    centrality.iter().map(|c| c / pairs).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::EdgeKind;
+    use crate::node::NodeFingerprint;
+
+    fn test_graph() -> AlexandriaGraph {
+        AlexandriaGraph::with_defaults(NodeFingerprint::from_hardware("test", 4, 16, "test123"))
+    }
+
+    fn id(text: &str) -> ConceptId {
+        ConceptId::from_concept(text)
+    }
+
+    /// Two triangles joined by a single bridge: crypto ─ bridge ─ cooking
+    fn two_clusters() -> AlexandriaGraph {
+        let graph = test_graph();
+        for (a, b) in [
+            ("rsa", "aes"),
+            ("aes", "cipher"),
+            ("cipher", "rsa"),
+            ("bread", "flour"),
+            ("flour", "yeast"),
+            ("yeast", "bread"),
+            ("cipher", "bread"),
+        ] {
+            let from = graph.ensure_concept(a);
+            let to = graph.ensure_concept(b);
+            graph.add_edge(from, to, EdgeKind::UserLinked);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_weighted_path_prefers_strong_edges() {
+        let graph = test_graph();
+        let a = graph.ensure_concept("a");
+        let b = graph.ensure_concept("b");
+        let c = graph.ensure_concept("c");
+
+        // Direct but weak edge, vs two strong hops
+        graph.add_edge(a, c, EdgeKind::DomainMatch(1));
+        graph.add_edge(a, b, EdgeKind::UserLinked);
+        graph.add_edge(b, c, EdgeKind::UserLinked);
+
+        let path = graph.weighted_path(&a, &c).unwrap();
+        assert_eq!(path.concepts, vec![a, b, c]);
+        assert!((path.cost - 1.0).abs() < 1e-6);
+        assert_eq!(path.bottleneck, 2.0);
+
+        assert!(graph.weighted_path(&c, &a).is_none());
+    }
+
+    #[test]
+    fn test_pagerank_finds_hub() {
+        let graph = test_graph();
+        let hub = graph.ensure_concept("encryption");
+        for spoke in ["rsa", "aes", "wallet", "tls"] {
+            let s = graph.ensure_concept(spoke);
+            graph.add_edge(s, hub, EdgeKind::RelatedTo);
+        }
+
+        let ranks = graph.pagerank(&PageRankConfig::default());
+        assert_eq!(ranks[0].0, hub);
+        let total: f32 = ranks.iter().map(|(_, r)| r).sum();
+        assert!((total - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_communities_split_clusters() {
+        let graph = two_clusters();
+        let report = graph.communities();
+
+        assert_eq!(report.communities.len(), 2);
+        let crypto = report.community_of(&id("rsa")).unwrap();
+        assert!(crypto.members.contains(&id("aes")));
+        assert!(!crypto.members.contains(&id("flour")));
+        assert!(report.modularity > 0.3);
+    }
+
+    #[test]
+    fn test_core_and_betweenness() {
+        let graph = two_clusters();
+        let cores = graph.core_numbers();
+        assert_eq!(cores[&id("aes")], 2);
+        assert_eq!(graph.k_core(2).len(), 6);
+        assert!(graph.k_core(3).is_empty());
+
+        let between = graph.betweenness();
+        assert!(between[&id("cipher")] > between[&id("aes")]);
+        assert_eq!(between[&id("aes")], 0.0);
+
+        let hubs = graph.hubs(3);
+        assert_eq!(hubs.len(), 3);
+        assert!(hubs.iter().all(|h| h.degree >= 2));
+    }
+
+    #[test]
+    fn test_core_numbers_peel_in_layers() {
+        // 4-clique, a triangle hanging off it, a tail, and a self-loop
+        let graph = test_graph();
+        let link = |a: &str, b: &str| {
+            graph.add_edge(graph.ensure_concept(a), graph.ensure_concept(b), EdgeKind::RelatedTo);
+        };
+        for (a, b) in [("a", "b"), ("a", "c"), ("a", "d"), ("b", "c"), ("b", "d"), ("c", "d")] {
+            link(a, b);
+        }
+        for (a, b) in [("d", "e"), ("e", "f"), ("f", "d"), ("f", "g"), ("g", "g")] {
+            link(a, b);
+        }
+        graph.ensure_concept("alone");
+
+        let cores = graph.core_numbers();
+        for (name, core) in [("a", 3), ("b", 3), ("c", 3), ("d", 3), ("e", 2), ("f", 2), ("g", 1), ("alone", 0)] {
+            assert_eq!(cores[&id(name)], core, "{}", name);
+        }
+        assert_eq!(graph.k_core(3).len(), 4);
+    }
+}
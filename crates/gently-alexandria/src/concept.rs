@@ -15,6 +15,7 @@
 //!                                     DIFFERENT EMBEDDINGS
 //! ```
 
+use crate::crdt::LwwStamp;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::fmt;
@@ -24,7 +25,7 @@ use std::fmt;
 /// Two layers of identity:
 /// 1. ConceptId = hash of normalized string (global, deterministic)
 /// 2. Embedding = local model's interpretation (local, varies)
-#[derive(Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct ConceptId(pub [u8; 32]);
 
 impl ConceptId {
@@ -127,6 +128,10 @@ pub struct Concept {
 
     /// Source that introduced this concept
     pub source: Option<String>,
+
+    /// Last-writer-wins stamp for text, domain and source
+    #[serde(default)]
+    pub meta_stamp: LwwStamp,
 }
 
 impl Concept {
@@ -151,6 +156,7 @@ impl Concept {
             access_count: 0,
             domain: None,
             source: None,
+            meta_stamp: LwwStamp::default(),
         }
     }
 
@@ -177,6 +183,22 @@ impl Concept {
         self.last_accessed = chrono::Utc::now().timestamp();
         self.access_count += 1;
     }
+
+    /// Merge another replica of this concept
+    ///
+    /// Metadata is last-writer-wins; the embedding stays local.
+    pub fn merge_state(&mut self, other: &Concept) {
+        if other.meta_stamp > self.meta_stamp {
+            self.text = other.text.clone();
+            self.normalized = other.normalized.clone();
+            self.domain = other.domain;
+            self.source = other.source.clone();
+            self.meta_stamp = other.meta_stamp;
+        }
+        self.created_at = self.created_at.min(other.created_at);
+        self.last_accessed = self.last_accessed.max(other.last_accessed);
+        self.access_count = self.access_count.max(other.access_count);
+    }
 }
 
 /// Synonym detection result
@@ -0,0 +1,486 @@
+//! CRDT primitives - Convergent state for distributed graph sync
+//!
+//! ```text
+//! NODE A                 NODE B                 NODE C
+//! ──────                 ──────                 ──────
+//! Δa1, Δb1, Δa2          Δb1, Δa2, Δa1          Δa2, Δa1, Δb1, Δa1
+//!      │                      │                      │
+//!      └──────────────────────┼──────────────────────┘
+//!                             ▼
+//!                      SAME GRAPH STATE
+//!
+//! Edge weight  = Σ per-node counters      (merge: max per node)
+//! Sources      = grow-only set            (merge: union)
+//! last_used    = max register             (merge: max)
+//! Metadata     = last-writer-wins         (merge: newest stamp)
+//! Causality    = vector clock             (merge: pointwise max)
+//! ```
+//!
+//! Every merge is commutative, associative and idempotent, so deltas
+//! can be delivered in any order, any number of times.
+
+use crate::node::NodeFingerprint;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::RangeInclusive;
+
+/// Causal relationship between two vector clocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Causality {
+    /// Both clocks have seen exactly the same events
+    Equal,
+    /// Left clock happened strictly before right clock
+    Before,
+    /// Left clock happened strictly after right clock
+    After,
+    /// Neither clock dominates the other
+    Concurrent,
+}
+
+/// Vector clock keyed by node fingerprint
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<(NodeFingerprint, u64)>", into = "Vec<(NodeFingerprint, u64)>")]
+pub struct VectorClock {
+    entries: BTreeMap<NodeFingerprint, u64>,
+}
+
+impl VectorClock {
+    /// Create empty clock
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counter for a node (0 if never seen)
+    pub fn get(&self, node: &NodeFingerprint) -> u64 {
+        self.entries.get(node).copied().unwrap_or(0)
+    }
+
+    /// Advance the counter for a node, returning the new value
+    pub fn increment(&mut self, node: NodeFingerprint) -> u64 {
+        let counter = self.entries.entry(node).or_insert(0);
+        *counter += 1;
+        *counter
+    }
+
+    /// Raise a node's counter to at least `value`
+    pub fn observe(&mut self, node: NodeFingerprint, value: u64) {
+        let counter = self.entries.entry(node).or_insert(0);
+        if value > *counter {
+            *counter = value;
+        }
+    }
+
+    /// Pointwise maximum with another clock
+    pub fn merge(&mut self, other: &VectorClock) {
+        for (node, &value) in &other.entries {
+            self.observe(*node, value);
+        }
+    }
+
+    /// Compare causal order against another clock
+    pub fn compare(&self, other: &VectorClock) -> Causality {
+        let mut less = false;
+        let mut greater = false;
+
+        let nodes: BTreeSet<&NodeFingerprint> =
+            self.entries.keys().chain(other.entries.keys()).collect();
+
+        for node in nodes {
+            match self.get(node).cmp(&other.get(node)) {
+                Ordering::Less => less = true,
+                Ordering::Greater => greater = true,
+                Ordering::Equal => {}
+            }
+        }
+
+        match (less, greater) {
+            (false, false) => Causality::Equal,
+            (true, false) => Causality::Before,
+            (false, true) => Causality::After,
+            (true, true) => Causality::Concurrent,
+        }
+    }
+
+    /// Has this clock seen everything `other` has?
+    pub fn dominates(&self, other: &VectorClock) -> bool {
+        matches!(self.compare(other), Causality::Equal | Causality::After)
+    }
+
+    /// Number of nodes tracked
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Is the clock empty?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl From<Vec<(NodeFingerprint, u64)>> for VectorClock {
+    fn from(entries: Vec<(NodeFingerprint, u64)>) -> Self {
+        let mut clock = Self::new();
+        for (node, value) in entries {
+            clock.observe(node, value);
+        }
+        clock
+    }
+}
+
+impl From<VectorClock> for Vec<(NodeFingerprint, u64)> {
+    fn from(clock: VectorClock) -> Self {
+        clock.entries.into_iter().collect()
+    }
+}
+
+/// One node's contribution to an edge
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeContribution {
+    /// Total weight this node has added (monotonic)
+    pub weight: f32,
+    /// Total uses this node has recorded (monotonic)
+    pub uses: u64,
+}
+
+/// Grow-only per-node counter for edge weight and usage
+///
+/// Each node only ever raises its own entry, so merging by taking the
+/// maximum per node converges no matter how often or in which order
+/// states are exchanged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(
+    from = "Vec<(NodeFingerprint, NodeContribution)>",
+    into = "Vec<(NodeFingerprint, NodeContribution)>"
+)]
+pub struct WeightCounter {
+    entries: BTreeMap<NodeFingerprint, NodeContribution>,
+}
+
+impl WeightCounter {
+    /// Create empty counter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a local contribution
+    pub fn add(&mut self, node: NodeFingerprint, weight: f32, uses: u64) {
+        let entry = self.entries.entry(node).or_default();
+        entry.weight += weight.max(0.0);
+        entry.uses += uses;
+    }
+
+    /// Merge another counter (max per node)
+    pub fn merge(&mut self, other: &WeightCounter) {
+        for (node, theirs) in &other.entries {
+            let ours = self.entries.entry(*node).or_default();
+            ours.weight = ours.weight.max(theirs.weight);
+            ours.uses = ours.uses.max(theirs.uses);
+        }
+    }
+
+    /// Contribution from a single node
+    pub fn get(&self, node: &NodeFingerprint) -> NodeContribution {
+        self.entries.get(node).copied().unwrap_or_default()
+    }
+
+    /// Total weight across all nodes
+    ///
+    /// Summed in fingerprint order so every replica gets the same bits.
+    pub fn total_weight(&self) -> f32 {
+        self.entries.values().map(|c| c.weight).sum()
+    }
+
+    /// Total uses across all nodes
+    pub fn total_uses(&self) -> u64 {
+        self.entries.values().map(|c| c.uses).sum()
+    }
+
+    /// Nodes that have contributed
+    pub fn nodes(&self) -> impl Iterator<Item = &NodeFingerprint> {
+        self.entries.keys()
+    }
+
+    /// Is the counter empty?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl From<Vec<(NodeFingerprint, NodeContribution)>> for WeightCounter {
+    fn from(entries: Vec<(NodeFingerprint, NodeContribution)>) -> Self {
+        let mut counter = Self::new();
+        for (node, contribution) in entries {
+            counter.merge(&Self {
+                entries: BTreeMap::from([(node, contribution)]),
+            });
+        }
+        counter
+    }
+}
+
+impl From<WeightCounter> for Vec<(NodeFingerprint, NodeContribution)> {
+    fn from(counter: WeightCounter) -> Self {
+        counter.entries.into_iter().collect()
+    }
+}
+
+/// Last-writer-wins stamp (timestamp, then node as tie-breaker)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LwwStamp {
+    /// Wall-clock time of the write
+    pub timestamp: i64,
+    /// Node that performed the write
+    pub node: NodeFingerprint,
+}
+
+impl LwwStamp {
+    /// Stamp a write made now by `node`
+    pub fn now(node: NodeFingerprint) -> Self {
+        Self::at(chrono::Utc::now().timestamp(), node)
+    }
+
+    /// Stamp a write made at a specific time
+    pub fn at(timestamp: i64, node: NodeFingerprint) -> Self {
+        Self { timestamp, node }
+    }
+
+    /// Next stamp for a write by `node`, guaranteed to win over `self`
+    pub fn successor(&self, node: NodeFingerprint) -> Self {
+        let candidate = Self::now(node);
+        if candidate > *self {
+            candidate
+        } else {
+            Self::at(self.timestamp + 1, node)
+        }
+    }
+}
+
+impl Default for LwwStamp {
+    /// The zero stamp loses to every real write
+    fn default() -> Self {
+        Self::at(0, NodeFingerprint::from_bytes([0u8; 32]))
+    }
+}
+
+/// Result of observing a delta sequence number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeltaStatus {
+    /// Next expected delta from this node
+    Applied,
+    /// Delta arrived ahead of earlier ones (still safe to apply)
+    OutOfOrder {
+        /// Ranges of sequence numbers still missing below this one
+        missing: Vec<RangeInclusive<u64>>,
+    },
+    /// Delta was already seen
+    Duplicate,
+}
+
+impl DeltaStatus {
+    /// Should the delta's contents be merged?
+    pub fn should_apply(&self) -> bool {
+        !matches!(self, DeltaStatus::Duplicate)
+    }
+}
+
+/// Per-node tracker of which delta sequence numbers have been seen
+///
+/// Keeps a contiguous watermark plus the runs of sequences received above
+/// it, so both duplicates and gaps can be detected. Memory grows with the
+/// number of runs, not with the size of a gap, so a far-ahead sequence
+/// number costs the same as a near one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SequenceTracker {
+    /// Every sequence up to and including this one has been seen
+    contiguous: u64,
+    /// Runs seen above the watermark, start -> end (inclusive), never adjacent
+    ahead: BTreeMap<u64, u64>,
+}
+
+impl SequenceTracker {
+    /// Create empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Observe a sequence number
+    pub fn observe(&mut self, sequence: u64) -> DeltaStatus {
+        if self.has_seen(sequence) {
+            return DeltaStatus::Duplicate;
+        }
+
+        if sequence == self.contiguous + 1 {
+            self.contiguous = sequence;
+            // Absorb a buffered run that is now contiguous
+            if let Some(end) = self.ahead.remove(&(sequence + 1)) {
+                self.contiguous = end;
+            }
+            return DeltaStatus::Applied;
+        }
+
+        // Join the runs either side, if they touch
+        let mut start = sequence;
+        let mut end = sequence;
+        if let Some((&s, &e)) = self.ahead.range(..sequence).next_back() {
+            if e + 1 == sequence {
+                start = s;
+            }
+        }
+        if let Some(next) = sequence.checked_add(1) {
+            if let Some(e) = self.ahead.remove(&next) {
+                end = e;
+            }
+        }
+        self.ahead.insert(start, end);
+
+        DeltaStatus::OutOfOrder { missing: self.gaps(sequence) }
+    }
+
+    /// Has this sequence already been seen?
+    pub fn has_seen(&self, sequence: u64) -> bool {
+        sequence <= self.contiguous
+            || self.ahead.range(..=sequence).next_back().is_some_and(|(_, &end)| end >= sequence)
+    }
+
+    /// Highest sequence below which nothing is missing
+    pub fn watermark(&self) -> u64 {
+        self.contiguous
+    }
+
+    /// Highest sequence seen at all
+    pub fn highest(&self) -> u64 {
+        self.ahead.values().next_back().copied().unwrap_or(self.contiguous)
+    }
+
+    /// Ranges of sequences known to be missing
+    pub fn missing(&self) -> Vec<RangeInclusive<u64>> {
+        self.gaps(self.highest())
+    }
+
+    /// Gaps between the watermark and the run holding `upto`
+    fn gaps(&self, upto: u64) -> Vec<RangeInclusive<u64>> {
+        let mut gaps = Vec::new();
+        let mut next = self.contiguous + 1;
+        for (&start, &end) in self.ahead.range(..=upto) {
+            gaps.push(next..=start - 1);
+            next = end.saturating_add(1);
+        }
+        gaps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str) -> NodeFingerprint {
+        NodeFingerprint::from_hardware(name, 4, 16, name)
+    }
+
+    #[test]
+    fn test_vector_clock_ordering() {
+        let a = node("a");
+        let b = node("b");
+
+        let mut c1 = VectorClock::new();
+        c1.increment(a);
+        let mut c2 = c1.clone();
+        c2.increment(b);
+
+        assert_eq!(c1.compare(&c2), Causality::Before);
+        assert_eq!(c2.compare(&c1), Causality::After);
+        assert_eq!(c1.compare(&c1.clone()), Causality::Equal);
+
+        let mut c3 = c1.clone();
+        c3.increment(a);
+        assert_eq!(c2.compare(&c3), Causality::Concurrent);
+
+        c2.merge(&c3);
+        assert!(c2.dominates(&c3));
+        assert_eq!(c2.get(&a), 2);
+        assert_eq!(c2.get(&b), 1);
+    }
+
+    #[test]
+    fn test_vector_clock_serialization() {
+        let mut clock = VectorClock::new();
+        clock.increment(node("a"));
+        clock.increment(node("b"));
+
+        let json = serde_json::to_string(&clock).unwrap();
+        let recovered: VectorClock = serde_json::from_str(&json).unwrap();
+        assert_eq!(clock, recovered);
+    }
+
+    #[test]
+    fn test_weight_counter_merge_is_idempotent_and_commutative() {
+        let mut x = WeightCounter::new();
+        x.add(node("a"), 1.0, 1);
+        let mut y = WeightCounter::new();
+        y.add(node("b"), 0.5, 2);
+        y.add(node("a"), 0.3, 1);
+
+        let mut xy = x.clone();
+        xy.merge(&y);
+        xy.merge(&y);
+        let mut yx = y.clone();
+        yx.merge(&x);
+
+        assert_eq!(xy, yx);
+        assert_eq!(xy.total_weight(), 1.5);
+        assert_eq!(xy.total_uses(), 3);
+    }
+
+    #[test]
+    fn test_lww_stamp_tiebreak() {
+        let low = LwwStamp::at(10, NodeFingerprint::from_bytes([1u8; 32]));
+        let high = LwwStamp::at(10, NodeFingerprint::from_bytes([2u8; 32]));
+        assert!(high > low);
+        assert!(LwwStamp::default() < low);
+        assert!(high.successor(node("a")) > high);
+    }
+
+    #[test]
+    fn test_sequence_tracker() {
+        let mut tracker = SequenceTracker::new();
+
+        assert_eq!(tracker.observe(1), DeltaStatus::Applied);
+        assert_eq!(tracker.observe(1), DeltaStatus::Duplicate);
+        assert_eq!(
+            tracker.observe(4),
+            DeltaStatus::OutOfOrder { missing: vec![2..=3] }
+        );
+        assert_eq!(tracker.missing(), vec![2..=3]);
+        assert_eq!(tracker.observe(4), DeltaStatus::Duplicate);
+        assert_eq!(tracker.observe(3), DeltaStatus::OutOfOrder { missing: vec![2..=2] });
+        assert_eq!(tracker.observe(2), DeltaStatus::Applied);
+        assert_eq!(tracker.watermark(), 4);
+        assert!(tracker.missing().is_empty());
+    }
+
+    #[test]
+    fn test_sequence_tracker_far_ahead() {
+        let mut tracker = SequenceTracker::new();
+
+        // A huge jump is one run, not a list of every gap
+        assert_eq!(
+            tracker.observe(u64::MAX),
+            DeltaStatus::OutOfOrder { missing: vec![1..=u64::MAX - 1] }
+        );
+        assert_eq!(tracker.observe(u64::MAX), DeltaStatus::Duplicate);
+        assert_eq!(tracker.highest(), u64::MAX);
+
+        assert_eq!(tracker.observe(7), DeltaStatus::OutOfOrder { missing: vec![1..=6] });
+        assert_eq!(tracker.observe(5), DeltaStatus::OutOfOrder { missing: vec![1..=4] });
+        assert_eq!(tracker.observe(6), DeltaStatus::OutOfOrder { missing: vec![1..=4] });
+        assert_eq!(tracker.missing(), vec![1..=4, 8..=u64::MAX - 1]);
+        assert!(tracker.has_seen(6) && !tracker.has_seen(8));
+
+        for sequence in 1..=4 {
+            assert!(tracker.observe(sequence).should_apply());
+        }
+        assert_eq!(tracker.watermark(), 7);
+        assert_eq!(tracker.missing(), vec![8..=u64::MAX - 1]);
+    }
+}
@@ -18,6 +18,7 @@
 //! ```
 
 use crate::concept::ConceptId;
+use crate::crdt::{LwwStamp, NodeContribution, WeightCounter};
 use crate::node::NodeFingerprint;
 use serde::{Deserialize, Serialize};
 
@@ -50,6 +51,14 @@ pub struct AlexandriaEdge {
 
     /// Is this edge dormant (weight below threshold)?
     pub dormant: bool,
+
+    /// Per-node weight/usage counters (replicated state behind `weight`)
+    #[serde(default)]
+    pub contributions: WeightCounter,
+
+    /// Last-writer-wins stamp for kind, direction and dormancy
+    #[serde(default)]
+    pub meta_stamp: LwwStamp,
 }
 
 impl AlexandriaEdge {
@@ -66,6 +75,8 @@ impl AlexandriaEdge {
             use_count: 1,
             source_nodes: Vec::new(),
             dormant: false,
+            contributions: WeightCounter::new(),
+            meta_stamp: LwwStamp::default(),
         }
     }
 
@@ -81,6 +92,20 @@ impl AlexandriaEdge {
         self
     }
 
+    /// Create with an initial contribution from `node`
+    ///
+    /// The edge's weight becomes the counter total, so it converges
+    /// when merged with other nodes' copies.
+    pub fn with_contribution(mut self, node: NodeFingerprint, weight: f32) -> Self {
+        self.contributions.add(node, weight, self.use_count);
+        if !self.source_nodes.contains(&node) {
+            self.source_nodes.push(node);
+        }
+        self.meta_stamp = LwwStamp::at(self.created_at, node);
+        self.weight = self.contributions.total_weight();
+        self
+    }
+
     /// Record a usage (refreshes the edge)
     pub fn use_edge(&mut self) {
         self.last_used = chrono::Utc::now().timestamp();
@@ -90,6 +115,116 @@ impl AlexandriaEdge {
         self.dormant = false;
     }
 
+    /// Record a usage by `node` as a replicated contribution
+    pub fn record_use(&mut self, node: NodeFingerprint) {
+        self.last_used = self.last_used.max(chrono::Utc::now().timestamp());
+        self.record_contribution(node, 0.1, 1);
+        if self.dormant {
+            self.dormant = false;
+            self.meta_stamp = self.meta_stamp.successor(node);
+        }
+    }
+
+    /// Add weight/uses to `node`'s counter and re-derive `weight`
+    pub fn record_contribution(&mut self, node: NodeFingerprint, weight: f32, uses: u64) {
+        self.ensure_counter();
+        let previous = self.contributions.total_weight();
+        self.contributions.add(node, weight, uses);
+        if !self.source_nodes.contains(&node) {
+            self.source_nodes.push(node);
+            self.source_nodes.sort();
+        }
+        self.rematerialize(previous);
+    }
+
+    /// Merge `node`'s absolute counter value (max, like [`Self::merge_state`])
+    pub fn merge_contribution(&mut self, node: NodeFingerprint, contribution: NodeContribution) {
+        self.ensure_counter();
+        let previous = self.contributions.total_weight();
+        self.contributions.merge(&WeightCounter::from(vec![(node, contribution)]));
+        if !self.source_nodes.contains(&node) {
+            self.source_nodes.push(node);
+            self.source_nodes.sort();
+        }
+        self.rematerialize(previous);
+    }
+
+    /// `node`'s current contribution as a sync update
+    pub fn weight_update(&self, node: NodeFingerprint) -> EdgeUpdate {
+        EdgeUpdate::WeightIncrement {
+            from: self.from,
+            to: self.to,
+            contribution: self.contributions.get(&node),
+            source_node: node,
+        }
+    }
+
+    /// Mark dormant as a replicated metadata write by `node`
+    pub fn mark_dormant(&mut self, node: NodeFingerprint) {
+        self.dormant = true;
+        self.meta_stamp = self.meta_stamp.successor(node);
+    }
+
+    /// Merge another replica of this edge
+    ///
+    /// Counters merge by per-node max, sources by union, `last_used` by
+    /// max and metadata by last-writer-wins, so the result is the same
+    /// whatever order replicas are merged in.
+    pub fn merge_state(&mut self, other: &AlexandriaEdge) {
+        self.ensure_counter();
+        let mut theirs = other.clone();
+        theirs.ensure_counter();
+
+        let previous = self.contributions.total_weight();
+        self.contributions.merge(&theirs.contributions);
+
+        for source in &theirs.source_nodes {
+            if !self.source_nodes.contains(source) {
+                self.source_nodes.push(*source);
+            }
+        }
+        self.source_nodes.sort();
+
+        self.created_at = self.created_at.min(theirs.created_at);
+        self.last_used = self.last_used.max(theirs.last_used);
+
+        if theirs.meta_stamp > self.meta_stamp {
+            self.from = theirs.from;
+            self.to = theirs.to;
+            self.kind = theirs.kind;
+            self.dormant = theirs.dormant;
+            self.meta_stamp = theirs.meta_stamp;
+        }
+
+        self.rematerialize(previous);
+    }
+
+    /// Seed the counter for edges created before counters existed
+    fn ensure_counter(&mut self) {
+        if self.contributions.is_empty() && (self.weight > 0.0 || self.use_count > 0) {
+            let seed = self
+                .source_nodes
+                .first()
+                .copied()
+                .unwrap_or_else(|| NodeFingerprint::from_bytes([0u8; 32]));
+            self.contributions.add(seed, self.weight, self.use_count);
+        }
+    }
+
+    /// Re-derive `weight` and `use_count` from the counter
+    ///
+    /// Local decay is not replicated, so the ratio between the decayed
+    /// weight and the previous counter total is preserved.
+    fn rematerialize(&mut self, previous_total: f32) {
+        let total = self.contributions.total_weight();
+        self.weight = if previous_total > 0.0 {
+            total * (self.weight / previous_total)
+        } else {
+            total
+        };
+        self.use_count = self.contributions.total_uses();
+    }
+
     /// Apply decay based on time since last use
     pub fn apply_decay(&mut self, half_life_days: f32, dormant_threshold: f32) {
         let now = chrono::Utc::now().timestamp();
@@ -257,11 +392,17 @@ pub enum EdgeUpdate {
     /// New edge discovered
     New(AlexandriaEdge),
 
-    /// Increment weight on existing edge
+    /// Full replicated state of an edge (idempotent, order-independent)
+    State(AlexandriaEdge),
+
+    /// Weight added to an existing edge by `source_node`
+    ///
+    /// Carries the node's running total rather than the increment, so it
+    /// merges by max like the rest of the counter.
     WeightIncrement {
         from: ConceptId,
         to: ConceptId,
-        delta: f32,
+        contribution: NodeContribution,
         source_node: NodeFingerprint,
     },
 
@@ -327,6 +468,31 @@ mod tests {
         assert!(EdgeKind::UserPath.base_weight() > EdgeKind::SessionCorrelation.base_weight());
     }
 
+    #[test]
+    fn test_edge_merge_state_converges() {
+        let a = NodeFingerprint::from_hardware("a", 4, 16, "a");
+        let b = NodeFingerprint::from_hardware("b", 4, 16, "b");
+        let from = ConceptId::from_concept("x");
+        let to = ConceptId::from_concept("y");
+
+        let mut on_a = AlexandriaEdge::new(from, to, EdgeKind::UserPath).with_contribution(a, 1.0);
+        on_a.record_use(a);
+        let on_b = AlexandriaEdge::new(to, from, EdgeKind::RelatedTo).with_contribution(b, 0.5);
+
+        let mut ab = on_a.clone();
+        ab.merge_state(&on_b);
+        ab.merge_state(&on_b);
+        let mut ba = on_b.clone();
+        ba.merge_state(&on_a);
+
+        assert_eq!(ab.weight, ba.weight);
+        assert_eq!(ab.use_count, 3);
+        assert_eq!(ab.use_count, ba.use_count);
+        assert_eq!(ab.kind, ba.kind);
+        assert_eq!(ab.source_nodes, ba.source_nodes);
+        assert_eq!(ab.from, ba.from);
+    }
+
     #[test]
     fn test_edge_key_ordering() {
         let a = ConceptId::from_concept("aaa");
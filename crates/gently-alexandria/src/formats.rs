@@ -0,0 +1,1106 @@
+//! Graph Formats - Open the library in other tools
+//!
+//! ```text
+//! AlexandriaGraph ─┐                      ┌─► GraphML  (yEd, Cytoscape, NetworkX)
+//!                  ├─► GraphDocument ─────┼─► GEXF     (Gephi)
+//! KnowledgeGraph ──┘        ▲             ├─► DOT      (Graphviz)
+//!                           │             ├─► JSON-LD  (linked data)
+//!        GraphML, CSV ──────┘             └─► CSV      (spreadsheets)
+//! ```
+//!
+//! `GraphDocument` is a neutral node/edge list with string attributes.
+//! Exporters write it; importers read GraphML and CSV edge lists back
+//! into it, and `AlexandriaGraph::import_document` merges it into a graph.
+
+use crate::concept::ConceptId;
+use crate::edge::{AlexandriaEdge, EdgeKind};
+use crate::graph::AlexandriaGraph;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+/// Supported file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    GraphMl,
+    Gexf,
+    Dot,
+    JsonLd,
+    Csv,
+}
+
+impl GraphFormat {
+    /// Guess format from a file extension
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "graphml" | "xml" => Some(Self::GraphMl),
+            "gexf" => Some(Self::Gexf),
+            "dot" | "gv" => Some(Self::Dot),
+            "jsonld" | "json-ld" => Some(Self::JsonLd),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    /// Parse a format name (as used on the command line)
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::from_extension(name)
+    }
+
+    /// Can this format be imported?
+    pub fn is_importable(&self) -> bool {
+        matches!(self, Self::GraphMl | Self::Csv)
+    }
+}
+
+/// A node in a format-neutral graph document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocNode {
+    /// Stable identifier
+    pub id: String,
+    /// Human-readable label
+    pub label: String,
+    /// Extra attributes
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// An edge in a format-neutral graph document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocEdge {
+    /// Source node id
+    pub source: String,
+    /// Target node id
+    pub target: String,
+    /// Relationship type
+    pub kind: String,
+    /// Edge weight
+    pub weight: f32,
+    /// Extra attributes
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// Format-neutral graph
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphDocument {
+    pub nodes: Vec<DocNode>,
+    pub edges: Vec<DocEdge>,
+}
+
+/// Which part of a graph to export
+#[derive(Debug, Clone)]
+pub enum Subgraph {
+    /// Everything
+    All,
+    /// Everything within `hops` of a concept (edges followed both ways)
+    Around { concept: String, hops: usize },
+}
+
+impl GraphDocument {
+    /// Create empty document
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only nodes within `hops` of `start` (undirected) and edges between them
+    pub fn neighbourhood(&self, start: &str, hops: usize) -> Self {
+        let mut adjacency: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in &self.edges {
+            adjacency.entry(&edge.source).or_default().push(&edge.target);
+            adjacency.entry(&edge.target).or_default().push(&edge.source);
+        }
+
+        let mut keep: HashSet<&str> = HashSet::from([start]);
+        let mut queue = VecDeque::from([(start, 0usize)]);
+        while let Some((node, depth)) = queue.pop_front() {
+            if depth >= hops {
+                continue;
+            }
+            for next in adjacency.get(node).into_iter().flatten() {
+                if keep.insert(next) {
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+
+        Self {
+            nodes: self
+                .nodes
+                .iter()
+                .filter(|n| keep.contains(n.id.as_str()))
+                .cloned()
+                .collect(),
+            edges: self
+                .edges
+                .iter()
+                .filter(|e| keep.contains(e.source.as_str()) && keep.contains(e.target.as_str()))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Write in the given format
+    pub fn write(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::GraphMl => self.to_graphml(),
+            GraphFormat::Gexf => self.to_gexf(),
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::JsonLd => self.to_jsonld(),
+            GraphFormat::Csv => self.to_csv(),
+        }
+    }
+
+    /// Read from the given format
+    pub fn read(format: GraphFormat, data: &str) -> Result<Self> {
+        match format {
+            GraphFormat::GraphMl => Self::from_graphml(data),
+            GraphFormat::Csv => Self::from_csv(data),
+            other => Err(Error::SerializationError(format!(
+                "Import from {:?} is not supported",
+                other
+            ))),
+        }
+    }
+
+    fn node_keys(&self) -> BTreeSet<&str> {
+        self.nodes
+            .iter()
+            .flat_map(|n| n.attributes.keys().map(|k| k.as_str()))
+            .collect()
+    }
+
+    fn edge_keys(&self) -> BTreeSet<&str> {
+        self.edges
+            .iter()
+            .flat_map(|e| e.attributes.keys().map(|k| k.as_str()))
+            .collect()
+    }
+
+    // ========== GraphML ==========
+
+    /// Write GraphML
+    pub fn to_graphml(&self) -> String {
+        let node_keys = self.node_keys();
+        let edge_keys = self.edge_keys();
+        let mut out = String::new();
+
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        out.push_str("  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n");
+        out.push_str("  <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n");
+        out.push_str("  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>\n");
+        for (i, key) in node_keys.iter().enumerate() {
+            out.push_str(&format!(
+                "  <key id=\"n{}\" for=\"node\" attr.name=\"{}\" attr.type=\"string\"/>\n",
+                i,
+                xml_escape(key)
+            ));
+        }
+        for (i, key) in edge_keys.iter().enumerate() {
+            out.push_str(&format!(
+                "  <key id=\"e{}\" for=\"edge\" attr.name=\"{}\" attr.type=\"string\"/>\n",
+                i,
+                xml_escape(key)
+            ));
+        }
+
+        out.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+        for node in &self.nodes {
+            out.push_str(&format!("    <node id=\"{}\">\n", xml_escape(&node.id)));
+            out.push_str(&format!(
+                "      <data key=\"label\">{}</data>\n",
+                xml_escape(&node.label)
+            ));
+            for (i, key) in node_keys.iter().enumerate() {
+                if let Some(value) = node.attributes.get(*key) {
+                    out.push_str(&format!(
+                        "      <data key=\"n{}\">{}</data>\n",
+                        i,
+                        xml_escape(value)
+                    ));
+                }
+            }
+            out.push_str("    </node>\n");
+        }
+        for edge in &self.edges {
+            out.push_str(&format!(
+                "    <edge source=\"{}\" target=\"{}\">\n",
+                xml_escape(&edge.source),
+                xml_escape(&edge.target)
+            ));
+            out.push_str(&format!(
+                "      <data key=\"kind\">{}</data>\n",
+                xml_escape(&edge.kind)
+            ));
+            out.push_str(&format!("      <data key=\"weight\">{}</data>\n", edge.weight));
+            for (i, key) in edge_keys.iter().enumerate() {
+                if let Some(value) = edge.attributes.get(*key) {
+                    out.push_str(&format!(
+                        "      <data key=\"e{}\">{}</data>\n",
+                        i,
+                        xml_escape(value)
+                    ));
+                }
+            }
+            out.push_str("    </edge>\n");
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    /// Read GraphML
+    pub fn from_graphml(data: &str) -> Result<Self> {
+        let events = parse_xml(data)?;
+        let mut doc = Self::new();
+        let mut key_names: HashMap<String, String> = HashMap::new();
+
+        enum Current {
+            None,
+            Node(DocNode),
+            Edge(DocEdge),
+        }
+        let mut current = Current::None;
+        let mut data_key: Option<String> = None;
+        let mut text = String::new();
+
+        for event in events {
+            match event {
+                XmlEvent::Start {
+                    name,
+                    attrs,
+                    self_closing,
+                } => match name.as_str() {
+                    "key" => {
+                        if let (Some(id), Some(attr_name)) = (attrs.get("id"), attrs.get("attr.name")) {
+                            key_names.insert(id.clone(), attr_name.clone());
+                        }
+                    }
+                    "node" => {
+                        let id = attrs.get("id").cloned().ok_or_else(|| {
+                            Error::SerializationError("GraphML node without id".to_string())
+                        })?;
+                        let node = DocNode {
+                            label: id.clone(),
+                            id,
+                            attributes: BTreeMap::new(),
+                        };
+                        if self_closing {
+                            doc.nodes.push(node);
+                        } else {
+                            current = Current::Node(node);
+                        }
+                    }
+                    "edge" => {
+                        let (Some(source), Some(target)) = (attrs.get("source"), attrs.get("target"))
+                        else {
+                            return Err(Error::SerializationError(
+                                "GraphML edge without source/target".to_string(),
+                            ));
+                        };
+                        let edge = DocEdge {
+                            source: source.clone(),
+                            target: target.clone(),
+                            kind: "RelatedTo".to_string(),
+                            weight: 1.0,
+                            attributes: BTreeMap::new(),
+                        };
+                        if self_closing {
+                            doc.edges.push(edge);
+                        } else {
+                            current = Current::Edge(edge);
+                        }
+                    }
+                    "data" => {
+                        data_key = attrs.get("key").cloned();
+                        text.clear();
+                    }
+                    _ => {}
+                },
+                XmlEvent::Text(t) => {
+                    if data_key.is_some() {
+                        text.push_str(&t);
+                    }
+                }
+                XmlEvent::End { name } => match name.as_str() {
+                    "data" => {
+                        if let Some(key) = data_key.take() {
+                            let attr = key_names.get(&key).cloned().unwrap_or(key);
+                            let value = text.trim().to_string();
+                            match &mut current {
+                                Current::Node(node) => {
+                                    if attr == "label" {
+                                        node.label = value;
+                                    } else {
+                                        node.attributes.insert(attr, value);
+                                    }
+                                }
+                                Current::Edge(edge) => match attr.as_str() {
+                                    "kind" | "label" => edge.kind = value,
+                                    "weight" => edge.weight = value.parse().unwrap_or(1.0),
+                                    _ => {
+                                        edge.attributes.insert(attr, value);
+                                    }
+                                },
+                                Current::None => {}
+                            }
+                        }
+                    }
+                    "node" | "edge" => match std::mem::replace(&mut current, Current::None) {
+                        Current::Node(node) => doc.nodes.push(node),
+                        Current::Edge(edge) => doc.edges.push(edge),
+                        Current::None => {}
+                    },
+                    _ => {}
+                },
+            }
+        }
+
+        Ok(doc)
+    }
+
+    // ========== GEXF ==========
+
+    /// Write GEXF 1.3 (Gephi)
+    pub fn to_gexf(&self) -> String {
+        let node_keys: Vec<&str> = self.node_keys().into_iter().collect();
+        let edge_keys: Vec<&str> = self.edge_keys().into_iter().collect();
+        let mut out = String::new();
+
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n");
+        out.push_str("  <graph defaultedgetype=\"directed\">\n");
+
+        out.push_str("    <attributes class=\"node\">\n");
+        for (i, key) in node_keys.iter().enumerate() {
+            out.push_str(&format!(
+                "      <attribute id=\"{}\" title=\"{}\" type=\"string\"/>\n",
+                i,
+                xml_escape(key)
+            ));
+        }
+        out.push_str("    </attributes>\n");
+        out.push_str("    <attributes class=\"edge\">\n");
+        for (i, key) in edge_keys.iter().enumerate() {
+            out.push_str(&format!(
+                "      <attribute id=\"{}\" title=\"{}\" type=\"string\"/>\n",
+                i,
+                xml_escape(key)
+            ));
+        }
+        out.push_str("    </attributes>\n");
+
+        out.push_str("    <nodes>\n");
+        for node in &self.nodes {
+            out.push_str(&format!(
+                "      <node id=\"{}\" label=\"{}\">\n",
+                xml_escape(&node.id),
+                xml_escape(&node.label)
+            ));
+            write_gexf_attvalues(&mut out, &node_keys, &node.attributes);
+            out.push_str("      </node>\n");
+        }
+        out.push_str("    </nodes>\n");
+
+        out.push_str("    <edges>\n");
+        for (i, edge) in self.edges.iter().enumerate() {
+            out.push_str(&format!(
+                "      <edge id=\"{}\" source=\"{}\" target=\"{}\" label=\"{}\" weight=\"{}\">\n",
+                i,
+                xml_escape(&edge.source),
+                xml_escape(&edge.target),
+                xml_escape(&edge.kind),
+                edge.weight
+            ));
+            write_gexf_attvalues(&mut out, &edge_keys, &edge.attributes);
+            out.push_str("      </edge>\n");
+        }
+        out.push_str("    </edges>\n");
+
+        out.push_str("  </graph>\n</gexf>\n");
+        out
+    }
+
+    // ========== DOT ==========
+
+    /// Write Graphviz DOT
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph alexandria {\n");
+        for node in &self.nodes {
+            out.push_str(&format!(
+                "  \"{}\" [label=\"{}\"];\n",
+                dot_escape(&node.id),
+                dot_escape(&node.label)
+            ));
+        }
+        for edge in &self.edges {
+            out.push_str(&format!(
+                "  \"{}\" -> \"{}\" [label=\"{}\", weight={}, penwidth={:.2}];\n",
+                dot_escape(&edge.source),
+                dot_escape(&edge.target),
+                dot_escape(&edge.kind),
+                edge.weight,
+                (edge.weight).clamp(0.5, 5.0)
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    // ========== JSON-LD ==========
+
+    /// Write JSON-LD
+    pub fn to_jsonld(&self) -> String {
+        let mut graph: Vec<serde_json::Value> = Vec::new();
+
+        for node in &self.nodes {
+            let mut obj = serde_json::Map::new();
+            obj.insert("@id".into(), format!("node:{}", node.id).into());
+            obj.insert("@type".into(), "Concept".into());
+            obj.insert("label".into(), node.label.clone().into());
+            for (k, v) in &node.attributes {
+                obj.insert(k.clone(), v.clone().into());
+            }
+            graph.push(serde_json::Value::Object(obj));
+        }
+
+        for (i, edge) in self.edges.iter().enumerate() {
+            let mut obj = serde_json::Map::new();
+            obj.insert("@id".into(), format!("edge:{}", i).into());
+            obj.insert("@type".into(), "Edge".into());
+            obj.insert("source".into(), serde_json::json!({ "@id": format!("node:{}", edge.source) }));
+            obj.insert("target".into(), serde_json::json!({ "@id": format!("node:{}", edge.target) }));
+            obj.insert("kind".into(), edge.kind.clone().into());
+            obj.insert("weight".into(), serde_json::json!(edge.weight));
+            for (k, v) in &edge.attributes {
+                obj.insert(k.clone(), v.clone().into());
+            }
+            graph.push(serde_json::Value::Object(obj));
+        }
+
+        let document = serde_json::json!({
+            "@context": {
+                "@vocab": "https://gentlyos.dev/alexandria#",
+                "label": "http://www.w3.org/2000/01/rdf-schema#label",
+                "source": { "@type": "@id" },
+                "target": { "@type": "@id" },
+                "weight": { "@type": "http://www.w3.org/2001/XMLSchema#double" }
+            },
+            "@graph": graph,
+        });
+
+        serde_json::to_string_pretty(&document).unwrap_or_default()
+    }
+
+    // ========== CSV ==========
+
+    /// Write a CSV edge list (`source,target,kind,weight`), using labels
+    pub fn to_csv(&self) -> String {
+        let labels: HashMap<&str, &str> = self
+            .nodes
+            .iter()
+            .map(|n| (n.id.as_str(), n.label.as_str()))
+            .collect();
+        let label = |id: &str| labels.get(id).copied().filter(|l| !l.is_empty()).unwrap_or(id).to_string();
+
+        let mut out = String::from("source,target,kind,weight\n");
+        for edge in &self.edges {
+            out.push_str(&format!(
+                "{},{},{},{}\n",
+                csv_escape(&label(&edge.source)),
+                csv_escape(&label(&edge.target)),
+                csv_escape(&edge.kind),
+                edge.weight
+            ));
+        }
+        out
+    }
+
+    /// Read a CSV edge list
+    ///
+    /// Columns are `source,target[,kind[,weight]]`; a header row is
+    /// detected and skipped. Nodes are created from the endpoints.
+    pub fn from_csv(data: &str) -> Result<Self> {
+        let mut doc = Self::new();
+        let mut seen: HashSet<String> = HashSet::new();
+
+        for (i, (line_no, fields)) in parse_csv(data)?.into_iter().enumerate() {
+            if fields.len() < 2 {
+                return Err(Error::SerializationError(format!(
+                    "CSV line {}: expected at least source,target",
+                    line_no
+                )));
+            }
+            if i == 0 && fields[0].eq_ignore_ascii_case("source") {
+                continue;
+            }
+
+            let weight = match fields.get(3) {
+                Some(w) if !w.is_empty() => w.parse().map_err(|_| {
+                    Error::SerializationError(format!("CSV line {}: bad weight {:?}", line_no, w))
+                })?,
+                _ => 1.0,
+            };
+
+            for endpoint in &fields[..2] {
+                if seen.insert(endpoint.clone()) {
+                    doc.nodes.push(DocNode {
+                        id: endpoint.clone(),
+                        label: endpoint.clone(),
+                        attributes: BTreeMap::new(),
+                    });
+                }
+            }
+
+            doc.edges.push(DocEdge {
+                source: fields[0].clone(),
+                target: fields[1].clone(),
+                kind: fields
+                    .get(2)
+                    .filter(|k| !k.is_empty())
+                    .cloned()
+                    .unwrap_or_else(|| "RelatedTo".to_string()),
+                weight,
+                attributes: BTreeMap::new(),
+            });
+        }
+
+        Ok(doc)
+    }
+}
+
+/// Export/import on the graph
+impl AlexandriaGraph {
+    /// Build a format-neutral document from (part of) the graph
+    pub fn to_document(&self, selection: &Subgraph) -> GraphDocument {
+        let mut concepts = self.all_concepts();
+        concepts.sort_by_key(|c| c.id.0);
+        let mut edges = self.all_edges();
+        edges.sort_by_key(|e| (e.key().0 .0, e.key().1 .0));
+
+        let doc = GraphDocument {
+            nodes: concepts
+                .iter()
+                .map(|c| {
+                    let mut attributes = BTreeMap::new();
+                    attributes.insert("access_count".to_string(), c.access_count.to_string());
+                    attributes.insert("created_at".to_string(), c.created_at.to_string());
+                    if let Some(domain) = c.domain {
+                        attributes.insert("domain".to_string(), domain.to_string());
+                    }
+                    if let Some(source) = &c.source {
+                        attributes.insert("source".to_string(), source.clone());
+                    }
+                    DocNode {
+                        id: c.id.to_hex(),
+                        label: c.text.clone(),
+                        attributes,
+                    }
+                })
+                .collect(),
+            edges: edges.iter().map(edge_to_doc).collect(),
+        };
+
+        match selection {
+            Subgraph::All => doc,
+            Subgraph::Around { concept, hops } => {
+                doc.neighbourhood(&ConceptId::from_concept(concept).to_hex(), *hops)
+            }
+        }
+    }
+
+    /// Export (part of) the graph in a format
+    pub fn export_as(&self, format: GraphFormat, selection: &Subgraph) -> String {
+        self.to_document(selection).write(format)
+    }
+
+    /// Merge a document into the graph; returns the number of edges added
+    ///
+    /// Node ids that are 64-char hex are taken as concept IDs, anything
+    /// else is treated as concept text.
+    pub fn import_document(&self, doc: &GraphDocument) -> usize {
+        let mut ids: HashMap<&str, ConceptId> = HashMap::new();
+
+        for node in &doc.nodes {
+            let id = match ConceptId::from_hex(&node.id) {
+                Some(id) => {
+                    if !node.label.is_empty() && ConceptId::from_concept(&node.label) == id {
+                        self.ensure_concept(&node.label);
+                    }
+                    id
+                }
+                None => {
+                    let text = if node.label.is_empty() { &node.id } else { &node.label };
+                    self.ensure_concept(text)
+                }
+            };
+            ids.insert(node.id.as_str(), id);
+        }
+
+        let mut added = 0;
+        for edge in &doc.edges {
+            let resolve = |key: &str| {
+                ids.get(key)
+                    .copied()
+                    .or_else(|| ConceptId::from_hex(key))
+                    .unwrap_or_else(|| self.ensure_concept(key))
+            };
+            let from = resolve(&edge.source);
+            let to = resolve(&edge.target);
+            let kind = edge
+                .attributes
+                .get("kind_json")
+                .and_then(|json| serde_json::from_str(json).ok())
+                .unwrap_or_else(|| parse_edge_kind(&edge.kind));
+
+            if self.get_edge(&from, &to).is_none() {
+                added += 1;
+            }
+            self.add_weighted_edge(from, to, kind, edge.weight);
+        }
+
+        added
+    }
+
+    /// Import GraphML or CSV data
+    pub fn import_from(&self, format: GraphFormat, data: &str) -> Result<usize> {
+        let doc = GraphDocument::read(format, data)?;
+        Ok(self.import_document(&doc))
+    }
+}
+
+fn edge_to_doc(edge: &AlexandriaEdge) -> DocEdge {
+    let mut attributes = BTreeMap::new();
+    attributes.insert("use_count".to_string(), edge.use_count.to_string());
+    attributes.insert("created_at".to_string(), edge.created_at.to_string());
+    attributes.insert("last_used".to_string(), edge.last_used.to_string());
+    attributes.insert("dormant".to_string(), edge.dormant.to_string());
+    attributes.insert("sources".to_string(), edge.source_nodes.len().to_string());
+    if let Ok(json) = serde_json::to_string(&edge.kind) {
+        attributes.insert("kind_json".to_string(), json);
+    }
+
+    DocEdge {
+        source: edge.from.to_hex(),
+        target: edge.to.to_hex(),
+        kind: edge_kind_name(&edge.kind).to_string(),
+        weight: edge.weight,
+        attributes,
+    }
+}
+
+/// Plain name of an edge kind (payload dropped)
+pub fn edge_kind_name(kind: &EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::UserPath => "UserPath",
+        EdgeKind::SessionCorrelation => "SessionCorrelation",
+        EdgeKind::UserLinked => "UserLinked",
+        EdgeKind::EmbeddingSimilarity(_) => "EmbeddingSimilarity",
+        EdgeKind::KeywordOverlap(_) => "KeywordOverlap",
+        EdgeKind::DomainMatch(_) => "DomainMatch",
+        EdgeKind::IsA => "IsA",
+        EdgeKind::HasA => "HasA",
+        EdgeKind::PartOf => "PartOf",
+        EdgeKind::Causes => "Causes",
+        EdgeKind::Enables => "Enables",
+        EdgeKind::Requires => "Requires",
+        EdgeKind::RelatedTo => "RelatedTo",
+        EdgeKind::Contradicts => "Contradicts",
+        EdgeKind::Supports => "Supports",
+        EdgeKind::LeadsTo => "LeadsTo",
+        EdgeKind::DerivedFrom => "DerivedFrom",
+        EdgeKind::UsedIn => "UsedIn",
+    }
+}
+
+/// Parse an edge kind name (unknown names become `RelatedTo`)
+pub fn parse_edge_kind(name: &str) -> EdgeKind {
+    match name {
+        "UserPath" => EdgeKind::UserPath,
+        "SessionCorrelation" => EdgeKind::SessionCorrelation,
+        "UserLinked" => EdgeKind::UserLinked,
+        "EmbeddingSimilarity" => EdgeKind::EmbeddingSimilarity(0.5),
+        "KeywordOverlap" => EdgeKind::KeywordOverlap(Vec::new()),
+        "DomainMatch" => EdgeKind::DomainMatch(0),
+        "IsA" => EdgeKind::IsA,
+        "HasA" => EdgeKind::HasA,
+        "PartOf" => EdgeKind::PartOf,
+        "Causes" => EdgeKind::Causes,
+        "Enables" => EdgeKind::Enables,
+        "Requires" => EdgeKind::Requires,
+        "Contradicts" => EdgeKind::Contradicts,
+        "Supports" => EdgeKind::Supports,
+        "LeadsTo" => EdgeKind::LeadsTo,
+        "DerivedFrom" => EdgeKind::DerivedFrom,
+        "UsedIn" => EdgeKind::UsedIn,
+        _ => EdgeKind::RelatedTo,
+    }
+}
+
+fn write_gexf_attvalues(out: &mut String, keys: &[&str], attributes: &BTreeMap<String, String>) {
+    let values: Vec<(usize, &String)> = keys
+        .iter()
+        .enumerate()
+        .filter_map(|(i, k)| attributes.get(*k).map(|v| (i, v)))
+        .collect();
+    if values.is_empty() {
+        return;
+    }
+    out.push_str("        <attvalues>\n");
+    for (i, value) in values {
+        out.push_str(&format!(
+            "          <attvalue for=\"{}\" value=\"{}\"/>\n",
+            i,
+            xml_escape(value)
+        ));
+    }
+    out.push_str("        </attvalues>\n");
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let tail = &rest[amp..];
+        let Some(semi) = tail.find(';') else {
+            out.push_str(tail);
+            return out;
+        };
+        let entity = &tail[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ if entity.starts_with("#x") => u32::from_str_radix(&entity[2..], 16)
+                .ok()
+                .and_then(char::from_u32),
+            _ if entity.starts_with('#') => entity[1..].parse().ok().and_then(char::from_u32),
+            _ => None,
+        };
+        match decoded {
+            Some(c) => out.push(c),
+            None => out.push_str(&tail[..=semi]),
+        }
+        rest = &tail[semi + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn csv_escape(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Split CSV into records, each with the line it starts on
+///
+/// Quoted fields may span lines, as `csv_escape` writes them. Blank lines
+/// and `#` comments between records are skipped.
+fn parse_csv(data: &str) -> Result<Vec<(usize, Vec<String>)>> {
+    let mut records = Vec::new();
+    let mut rest = data;
+    let mut line = 1;
+
+    while !rest.is_empty() {
+        let first = rest.split('\n').next().unwrap_or("");
+        if first.trim().is_empty() || first.trim_start().starts_with('#') {
+            rest = rest.get(first.len() + 1..).unwrap_or("");
+            line += 1;
+            continue;
+        }
+
+        let start = line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut end = rest.len();
+        let mut chars = rest.char_indices().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            if c == '\n' {
+                line += 1;
+            }
+            match (c, quoted) {
+                ('"', true) if chars.peek().map(|&(_, c)| c) == Some('"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                ('"', true) => quoted = false,
+                ('"', false) if field.is_empty() => quoted = true,
+                (',', false) => fields.push(std::mem::take(&mut field).trim().to_string()),
+                ('\n', false) => {
+                    end = i + 1;
+                    break;
+                }
+                _ => field.push(c),
+            }
+        }
+        if quoted {
+            return Err(Error::SerializationError(format!(
+                "CSV line {}: unterminated quoted field",
+                start
+            )));
+        }
+        fields.push(field.trim().to_string());
+        records.push((start, fields));
+        rest = &rest[end..];
+    }
+
+    Ok(records)
+}
+
+/// Minimal XML event (enough for GraphML)
+enum XmlEvent {
+    Start {
+        name: String,
+        attrs: HashMap<String, String>,
+        self_closing: bool,
+    },
+    End {
+        name: String,
+    },
+    Text(String),
+}
+
+/// Tokenize XML into start/end/text events
+///
+/// Skips declarations, comments and doctypes; unwraps CDATA. Namespace
+/// prefixes are stripped from element names.
+fn parse_xml(data: &str) -> Result<Vec<XmlEvent>> {
+    let mut events = Vec::new();
+    let mut rest = data;
+
+    let malformed = |what: &str| Error::SerializationError(format!("Malformed XML: {}", what));
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            if !rest.trim().is_empty() {
+                events.push(XmlEvent::Text(xml_unescape(rest)));
+            }
+            break;
+        };
+        if lt > 0 {
+            events.push(XmlEvent::Text(xml_unescape(&rest[..lt])));
+        }
+        rest = &rest[lt..];
+
+        if let Some(after) = rest.strip_prefix("<!--") {
+            let end = after.find("-->").ok_or_else(|| malformed("unterminated comment"))?;
+            rest = &after[end + 3..];
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").ok_or_else(|| malformed("unterminated CDATA"))?;
+            events.push(XmlEvent::Text(after[..end].to_string()));
+            rest = &after[end + 3..];
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            let end = rest.find('>').ok_or_else(|| malformed("unterminated declaration"))?;
+            rest = &rest[end + 1..];
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after.find('>').ok_or_else(|| malformed("unterminated end tag"))?;
+            events.push(XmlEvent::End {
+                name: local_name(after[..end].trim()),
+            });
+            rest = &after[end + 1..];
+        } else {
+            let end = find_tag_end(rest).ok_or_else(|| malformed("unterminated tag"))?;
+            let inner = &rest[1..end];
+            let self_closing = inner.ends_with('/');
+            let inner = inner.trim_end_matches('/');
+            let name_end = inner
+                .find(|c: char| c.is_whitespace())
+                .unwrap_or(inner.len());
+            let name = local_name(&inner[..name_end]);
+            let attrs = parse_attributes(&inner[name_end..])?;
+            events.push(XmlEvent::Start {
+                name: name.clone(),
+                attrs,
+                self_closing,
+            });
+            if self_closing {
+                events.push(XmlEvent::End { name });
+            }
+            rest = &rest[end + 1..];
+        }
+    }
+
+    Ok(events)
+}
+
+/// Index of the `>` closing a start tag (ignoring `>` inside quotes)
+fn find_tag_end(s: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (i, c) in s.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (q, Some(open)) if q == open => quote = None,
+            ('>', None) => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn local_name(name: &str) -> String {
+    name.rsplit(':').next().unwrap_or(name).to_string()
+}
+
+fn parse_attributes(s: &str) -> Result<HashMap<String, String>> {
+    let mut attrs = HashMap::new();
+    let mut rest = s.trim();
+
+    while !rest.is_empty() {
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| Error::SerializationError(format!("Bad attribute: {}", rest)))?;
+        let key = rest[..eq].trim().to_string();
+        let after = rest[eq + 1..].trim_start();
+        let quote = after
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| Error::SerializationError(format!("Unquoted attribute: {}", key)))?;
+        let close = after[1..]
+            .find(quote)
+            .ok_or_else(|| Error::SerializationError(format!("Unterminated attribute: {}", key)))?;
+        attrs.insert(key, xml_unescape(&after[1..close + 1]));
+        rest = after[close + 2..].trim_start();
+    }
+
+    Ok(attrs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::NodeFingerprint;
+
+    fn test_graph() -> AlexandriaGraph {
+        AlexandriaGraph::with_defaults(NodeFingerprint::from_hardware("test", 4, 16, "test123"))
+    }
+
+    fn sample() -> AlexandriaGraph {
+        let graph = test_graph();
+        let enc = graph.ensure_concept("encryption");
+        let rsa = graph.ensure_concept("RSA & \"friends\"");
+        let prime = graph.ensure_concept("prime");
+        let wallet = graph.ensure_concept("wallet");
+        graph.add_edge(rsa, enc, EdgeKind::IsA);
+        graph.add_edge(prime, rsa, EdgeKind::KeywordOverlap(vec!["math".into()]));
+        graph.add_edge(enc, wallet, EdgeKind::UsedIn);
+        graph
+    }
+
+    #[test]
+    fn test_graphml_roundtrip() {
+        let graph = sample();
+        let doc = graph.to_document(&Subgraph::All);
+        let xml = doc.to_graphml();
+
+        let parsed = GraphDocument::from_graphml(&xml).unwrap();
+        assert_eq!(parsed, doc);
+
+        let copy = test_graph();
+        assert_eq!(copy.import_from(GraphFormat::GraphMl, &xml).unwrap(), 3);
+        let rsa = ConceptId::from_concept("RSA & \"friends\"");
+        let prime = ConceptId::from_concept("prime");
+        let edge = copy.get_edge(&prime, &rsa).unwrap();
+        assert_eq!(edge.kind, EdgeKind::KeywordOverlap(vec!["math".into()]));
+        assert_eq!(copy.get_concept(&rsa).unwrap().text, "RSA & \"friends\"");
+    }
+
+    #[test]
+    fn test_subgraph_selection() {
+        let graph = sample();
+        let doc = graph.to_document(&Subgraph::Around {
+            concept: "prime".into(),
+            hops: 1,
+        });
+        assert_eq!(doc.nodes.len(), 2);
+        assert_eq!(doc.edges.len(), 1);
+
+        let doc = graph.to_document(&Subgraph::Around {
+            concept: "prime".into(),
+            hops: 2,
+        });
+        assert_eq!(doc.nodes.len(), 3);
+    }
+
+    #[test]
+    fn test_csv_roundtrip_and_import() {
+        let csv = "source,target,kind,weight\n\"rust, the language\",memory safety,Enables,1.5\nrust,cargo,,\n";
+        let doc = GraphDocument::from_csv(csv).unwrap();
+        assert_eq!(doc.nodes.len(), 4);
+        assert_eq!(doc.edges[0].source, "rust, the language");
+        assert_eq!(doc.edges[0].weight, 1.5);
+        assert_eq!(doc.edges[1].kind, "RelatedTo");
+
+        let reparsed = GraphDocument::from_csv(&doc.to_csv()).unwrap();
+        assert_eq!(reparsed.edges, doc.edges);
+
+        let graph = test_graph();
+        assert_eq!(graph.import_from(GraphFormat::Csv, csv).unwrap(), 2);
+        let edge = graph
+            .get_edge(
+                &ConceptId::from_concept("rust, the language"),
+                &ConceptId::from_concept("memory safety"),
+            )
+            .unwrap();
+        assert_eq!(edge.kind, EdgeKind::Enables);
+        assert_eq!(edge.weight, 1.5);
+
+        assert!(GraphDocument::from_csv("a,b,c,notanumber").is_err());
+        assert!(GraphDocument::from_csv("\"open,b\n").is_err());
+
+        // Quoted newlines survive a round trip and don't shift line numbers
+        let mut multi = GraphDocument::new();
+        multi.edges.push(DocEdge {
+            source: "line one\nline two".to_string(),
+            target: "plain".to_string(),
+            kind: "RelatedTo".to_string(),
+            weight: 1.0,
+            attributes: BTreeMap::new(),
+        });
+        let reparsed = GraphDocument::from_csv(&multi.to_csv()).unwrap();
+        assert_eq!(reparsed.edges, multi.edges);
+        let err = GraphDocument::from_csv("\"a\nb\",c\n# note\nd,e,f,x\n").unwrap_err();
+        assert!(err.to_string().contains("line 4"), "{}", err);
+    }
+
+    #[test]
+    fn test_other_formats() {
+        let graph = sample();
+
+        let gexf = graph.export_as(GraphFormat::Gexf, &Subgraph::All);
+        assert!(gexf.contains("<gexf"));
+        assert_eq!(gexf.matches("<node ").count(), 4);
+        assert!(gexf.contains("&amp;"));
+
+        let dot = graph.export_as(GraphFormat::Dot, &Subgraph::All);
+        assert!(dot.starts_with("digraph"));
+        assert_eq!(dot.matches(" -> ").count(), 3);
+        assert!(dot.contains("\\\"friends\\\""));
+
+        let jsonld = graph.export_as(GraphFormat::JsonLd, &Subgraph::All);
+        let value: serde_json::Value = serde_json::from_str(&jsonld).unwrap();
+        assert_eq!(value["@graph"].as_array().unwrap().len(), 7);
+        assert!(value["@context"]["@vocab"].is_string());
+
+        assert!(GraphDocument::read(GraphFormat::Dot, &dot).is_err());
+        assert_eq!(GraphFormat::from_extension("GEXF"), Some(GraphFormat::Gexf));
+    }
+}
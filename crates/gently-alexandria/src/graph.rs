@@ -18,7 +18,9 @@
 //! ```
 
 use crate::concept::{Concept, ConceptId};
+use crate::crdt::{DeltaStatus, LwwStamp, SequenceTracker, VectorClock};
 use crate::edge::{AlexandriaEdge, EdgeKind, EdgeUpdate};
+use crate::history::{TopologyDiff, TopologyHistory};
 use crate::node::NodeFingerprint;
 use crate::sync::GraphDelta;
 use crate::{AlexandriaConfig, Error, Result};
@@ -54,8 +56,17 @@ pub struct AlexandriaGraph {
     /// Pending updates to publish
     pending_updates: Arc<RwLock<Vec<EdgeUpdate>>>,
 
-    /// Sequence number for deltas
-    sequence: Arc<RwLock<u64>>,
+    /// Concepts created or changed since the last delta
+    pending_concepts: Arc<RwLock<HashSet<ConceptId>>>,
+
+    /// Vector clock (our entry doubles as the delta sequence number)
+    clock: Arc<RwLock<VectorClock>>,
+
+    /// Delta sequences seen from each remote node
+    seen_sequences: Arc<RwLock<HashMap<NodeFingerprint, SequenceTracker>>>,
+
+    /// Periodic edge-weight snapshots for time-travel queries
+    history: Arc<RwLock<TopologyHistory>>,
 }
 
 impl AlexandriaGraph {
@@ -70,7 +81,10 @@ impl AlexandriaGraph {
             incoming: Arc::new(RwLock::new(HashMap::new())),
             current_session: Arc::new(RwLock::new(Vec::new())),
             pending_updates: Arc::new(RwLock::new(Vec::new())),
-            sequence: Arc::new(RwLock::new(0)),
+            pending_concepts: Arc::new(RwLock::new(HashSet::new())),
+            clock: Arc::new(RwLock::new(VectorClock::new())),
+            seen_sequences: Arc::new(RwLock::new(HashMap::new())),
+            history: Arc::new(RwLock::new(TopologyHistory::new())),
         }
     }
 
@@ -86,7 +100,12 @@ impl AlexandriaGraph {
         let id = ConceptId::from_concept(text);
 
         let mut concepts = self.concepts.write().unwrap();
-        concepts.entry(id).or_insert_with(|| Concept::new(text));
+        if let std::collections::hash_map::Entry::Vacant(entry) = concepts.entry(id) {
+            let mut concept = Concept::new(text);
+            concept.meta_stamp = LwwStamp::at(concept.created_at, self.local_node);
+            entry.insert(concept);
+            self.pending_concepts.write().unwrap().insert(id);
+        }
 
         id
     }
@@ -111,6 +130,12 @@ impl AlexandriaGraph {
         concepts.values().cloned().collect()
     }
 
+    /// IDs of all concepts (cheap to collect, fetch lazily with `get_concept`)
+    pub fn concept_ids(&self) -> Vec<ConceptId> {
+        let concepts = self.concepts.read().unwrap();
+        concepts.keys().copied().collect()
+    }
+
     /// Concept count
     pub fn concept_count(&self) -> usize {
         let concepts = self.concepts.read().unwrap();
@@ -121,6 +146,12 @@ impl AlexandriaGraph {
 
     /// Add or update an edge
     pub fn add_edge(&self, from: ConceptId, to: ConceptId, kind: EdgeKind) {
+        let weight = kind.base_weight();
+        self.add_weighted_edge(from, to, kind, weight);
+    }
+
+    /// Add or update an edge, seeding a new edge with `weight`
+    pub fn add_weighted_edge(&self, from: ConceptId, to: ConceptId, kind: EdgeKind, weight: f32) {
         // Ensure concepts exist
         {
             let mut concepts = self.concepts.write().unwrap();
@@ -143,19 +174,14 @@ impl AlexandriaGraph {
         let mut outgoing = self.outgoing.write().unwrap();
         let mut incoming = self.incoming.write().unwrap();
 
-        edges
+        let edge = edges
             .entry(key)
-            .and_modify(|e| {
-                e.use_edge();
-                if !e.source_nodes.contains(&self.local_node) {
-                    e.source_nodes.push(self.local_node);
-                }
-            })
+            .and_modify(|e| e.record_use(self.local_node))
             .or_insert_with(|| {
-                AlexandriaEdge::new(from, to, kind.clone())
-                    .with_weight(kind.base_weight())
-                    .with_source(self.local_node)
-            });
+                AlexandriaEdge::new(from, to, kind)
+                    .with_contribution(self.local_node, weight)
+            })
+            .clone();
 
         // Update indices
         outgoing.entry(from).or_default().insert(to);
@@ -167,12 +193,7 @@ impl AlexandriaGraph {
         drop(incoming);
 
         let mut pending = self.pending_updates.write().unwrap();
-        pending.push(EdgeUpdate::WeightIncrement {
-            from,
-            to,
-            delta: kind.base_weight() * 0.1,
-            source_node: self.local_node,
-        });
+        pending.push(EdgeUpdate::State(edge));
     }
 
     /// Get edge between two concepts
@@ -239,6 +260,12 @@ impl AlexandriaGraph {
         result
     }
 
+    /// Get all edges
+    pub fn all_edges(&self) -> Vec<AlexandriaEdge> {
+        let edges = self.edges.read().unwrap();
+        edges.values().cloned().collect()
+    }
+
     /// Edge count
     pub fn edge_count(&self) -> usize {
         let edges = self.edges.read().unwrap();
@@ -288,9 +315,14 @@ impl AlexandriaGraph {
                     } else {
                         (id, earlier)
                     };
-                    edges.entry(key).and_modify(|e| {
-                        e.weight += weight * 0.1;
+                    let updated = edges.get_mut(&key).map(|e| {
+                        e.record_contribution(self.local_node, weight * 0.1, 0);
+                        e.clone()
                     });
+                    drop(edges);
+                    if let Some(edge) = updated {
+                        self.pending_updates.write().unwrap().push(EdgeUpdate::State(edge));
+                    }
                     break; // Only update once per query
                 }
             }
@@ -318,6 +350,9 @@ impl AlexandriaGraph {
         for edge in edges.values_mut() {
             edge.apply_decay(self.config.decay_half_life_days, self.config.dormant_threshold);
         }
+        drop(edges);
+
+        self.maybe_snapshot();
     }
 
     /// Prune dormant edges (optional - keeps them by default for archaeology)
@@ -328,6 +363,94 @@ impl AlexandriaGraph {
         before - edges.len()
     }
 
+    // ========== History ==========
+
+    /// Record a snapshot of edge weights at `timestamp`
+    ///
+    /// Returns false if a snapshot at or after `timestamp` already exists.
+    pub fn snapshot_at(&self, timestamp: i64) -> bool {
+        let edges = self.all_edges();
+        let mut history = self.history.write().unwrap();
+        history.record(timestamp, &edges, self.config.max_snapshots)
+    }
+
+    /// Record a snapshot now
+    pub fn snapshot(&self) -> bool {
+        self.snapshot_at(chrono::Utc::now().timestamp())
+    }
+
+    /// Record a snapshot if the configured interval has passed
+    pub fn maybe_snapshot(&self) -> bool {
+        let now = chrono::Utc::now().timestamp();
+        let due = self
+            .history
+            .read()
+            .unwrap()
+            .latest()
+            .is_none_or(|last| now - last >= self.config.snapshot_interval_secs);
+        due && self.snapshot_at(now)
+    }
+
+    /// Copy of the snapshot history
+    pub fn history(&self) -> TopologyHistory {
+        self.history.read().unwrap().clone()
+    }
+
+    /// Materialize the graph as it was at `timestamp`
+    ///
+    /// Edges carry their recorded weight and use count; concepts created
+    /// later are left out. None if the history doesn't reach back that far.
+    pub fn as_of(&self, timestamp: i64) -> Option<AlexandriaGraph> {
+        let state = self.history.read().unwrap().state_at(timestamp)?;
+        let past = AlexandriaGraph::new(self.local_node, self.config.clone());
+
+        {
+            let concepts = self.concepts.read().unwrap();
+            let edges = self.edges.read().unwrap();
+            let mut past_concepts = past.concepts.write().unwrap();
+            let mut past_edges = past.edges.write().unwrap();
+            let mut outgoing = past.outgoing.write().unwrap();
+            let mut incoming = past.incoming.write().unwrap();
+
+            for concept in concepts.values().filter(|c| c.created_at <= timestamp) {
+                past_concepts.insert(concept.id, concept.clone());
+            }
+
+            for (key, sample) in state {
+                let mut edge = edges.get(&key).cloned().unwrap_or_else(|| {
+                    AlexandriaEdge::new(
+                        sample.from,
+                        sample.to,
+                        sample.kind.clone().unwrap_or(EdgeKind::RelatedTo),
+                    )
+                });
+                edge.weight = sample.weight;
+                edge.use_count = sample.uses;
+                edge.dormant = sample.weight < self.config.dormant_threshold;
+
+                for id in [edge.from, edge.to] {
+                    past_concepts.entry(id).or_insert_with(|| {
+                        concepts.get(&id).cloned().unwrap_or_else(|| {
+                            let mut c = Concept::new("");
+                            c.id = id;
+                            c
+                        })
+                    });
+                }
+                outgoing.entry(edge.from).or_default().insert(edge.to);
+                incoming.entry(edge.to).or_default().insert(edge.from);
+                past_edges.insert(key, edge);
+            }
+        }
+
+        Some(past)
+    }
+
+    /// What changed between two timestamps
+    pub fn diff_between(&self, from: i64, to: i64) -> TopologyDiff {
+        self.history.read().unwrap().diff(from, to)
+    }
+
     // ========== Path Finding ==========
 
     /// BFS to find all concepts reachable within N hops
@@ -403,47 +526,59 @@ impl AlexandriaGraph {
     // ========== Sync ==========
 
     /// Merge a delta from another node
-    pub fn merge_delta(&self, delta: GraphDelta) {
+    ///
+    /// Edge and concept state merge as CRDTs, so deltas may arrive in any
+    /// order and more than once. Duplicates (by `sequence`) are skipped and
+    /// reported; deltas that skip ahead are applied and report the gap.
+    pub fn merge_delta(&self, delta: GraphDelta) -> DeltaStatus {
+        if delta.from_node == self.local_node {
+            return DeltaStatus::Duplicate;
+        }
+
+        let status = if delta.sequence == 0 {
+            // Unsequenced deltas are merged unconditionally (merge is idempotent)
+            DeltaStatus::Applied
+        } else {
+            let mut seen = self.seen_sequences.write().unwrap();
+            seen.entry(delta.from_node).or_default().observe(delta.sequence)
+        };
+
+        if !status.should_apply() {
+            return status;
+        }
+
+        {
+            let mut clock = self.clock.write().unwrap();
+            clock.merge(&delta.clock);
+            clock.observe(delta.from_node, delta.sequence);
+        }
+
+        for concept in &delta.concepts {
+            self.merge_concept(concept);
+        }
+        for id in &delta.new_concepts {
+            self.ensure_placeholder(*id, delta.timestamp);
+        }
+
         for update in delta.edge_updates {
             match update {
-                EdgeUpdate::New(edge) => {
-                    let key = edge.key();
-                    let mut edges = self.edges.write().unwrap();
-
-                    edges
-                        .entry(key)
-                        .and_modify(|existing| {
-                            // Merge: combine weights, sources
-                            existing.weight += edge.weight * 0.5;
-                            for source in &edge.source_nodes {
-                                if !existing.source_nodes.contains(source) {
-                                    existing.source_nodes.push(*source);
-                                }
-                            }
-                            existing.use_count += edge.use_count;
-                            if edge.last_used > existing.last_used {
-                                existing.last_used = edge.last_used;
-                            }
-                        })
-                        .or_insert(edge);
+                EdgeUpdate::New(edge) | EdgeUpdate::State(edge) => {
+                    self.merge_edge(&edge, delta.timestamp);
                 }
 
                 EdgeUpdate::WeightIncrement {
                     from,
                     to,
-                    delta: delta_weight,
+                    contribution,
                     source_node,
                 } => {
+                    // The sender's running total, so replays and reordering are harmless
                     let key = if from.0 < to.0 { (from, to) } else { (to, from) };
                     let mut edges = self.edges.write().unwrap();
 
                     if let Some(edge) = edges.get_mut(&key) {
-                        edge.weight += delta_weight * 0.5;
-                        edge.use_count += 1;
-                        edge.last_used = delta.timestamp;
-                        if !edge.source_nodes.contains(&source_node) {
-                            edge.source_nodes.push(source_node);
-                        }
+                        edge.merge_contribution(source_node, contribution);
+                        edge.last_used = edge.last_used.max(delta.timestamp);
                     }
                 }
 
@@ -460,14 +595,89 @@ impl AlexandriaGraph {
 
                 EdgeUpdate::MarkDormant { from, to } => {
                     let key = if from.0 < to.0 { (from, to) } else { (to, from) };
+                    let stamp = LwwStamp::at(delta.timestamp, delta.from_node);
                     let mut edges = self.edges.write().unwrap();
 
                     if let Some(edge) = edges.get_mut(&key) {
-                        edge.dormant = true;
+                        if stamp > edge.meta_stamp {
+                            edge.dormant = true;
+                            edge.meta_stamp = stamp;
+                        }
                     }
                 }
             }
         }
+
+        status
+    }
+
+    /// Merge one replicated edge state, creating it if unknown
+    fn merge_edge(&self, remote: &AlexandriaEdge, seen_at: i64) {
+        self.ensure_placeholder(remote.from, seen_at);
+        self.ensure_placeholder(remote.to, seen_at);
+
+        let key = remote.key();
+        let mut edges = self.edges.write().unwrap();
+        match edges.get_mut(&key) {
+            Some(existing) => existing.merge_state(remote),
+            None => {
+                // Start from an empty replica so remote decay is not imported
+                let mut edge = AlexandriaEdge::new(remote.from, remote.to, remote.kind.clone());
+                edge.contributions = Default::default();
+                edge.weight = 0.0;
+                edge.use_count = 0;
+                edge.created_at = remote.created_at;
+                edge.last_used = remote.last_used;
+                edge.merge_state(remote);
+                edges.insert(key, edge);
+            }
+        }
+        drop(edges);
+
+        let mut outgoing = self.outgoing.write().unwrap();
+        let mut incoming = self.incoming.write().unwrap();
+        outgoing.entry(remote.from).or_default().insert(remote.to);
+        incoming.entry(remote.to).or_default().insert(remote.from);
+    }
+
+    /// Merge one replicated concept state
+    fn merge_concept(&self, remote: &Concept) {
+        let mut concepts = self.concepts.write().unwrap();
+        match concepts.get_mut(&remote.id) {
+            Some(existing) => existing.merge_state(remote),
+            None => {
+                let mut concept = remote.clone();
+                concept.embedding = None;
+                concepts.insert(remote.id, concept);
+            }
+        }
+    }
+
+    /// Make sure a concept exists (without text if we have never seen it)
+    ///
+    /// A placeholder has never been accessed here, so `last_accessed` stays
+    /// 0 and `created_at` is when it was first heard of; merging the real
+    /// concept later keeps the real values of both.
+    fn ensure_placeholder(&self, id: ConceptId, seen_at: i64) {
+        let mut concepts = self.concepts.write().unwrap();
+        concepts.entry(id).or_insert_with(|| {
+            let mut c = Concept::new("");
+            c.id = id;
+            c.created_at = seen_at;
+            c.last_accessed = 0;
+            c
+        });
+    }
+
+    /// Our current vector clock
+    pub fn clock(&self) -> VectorClock {
+        self.clock.read().unwrap().clone()
+    }
+
+    /// Ranges of delta sequences known to be missing from a node
+    pub fn missing_sequences(&self, node: &NodeFingerprint) -> Vec<std::ops::RangeInclusive<u64>> {
+        let seen = self.seen_sequences.read().unwrap();
+        seen.get(node).map(|t| t.missing()).unwrap_or_default()
     }
 
     /// Get pending updates and clear them
@@ -477,21 +687,63 @@ impl AlexandriaGraph {
     }
 
     /// Create a delta from pending updates
+    ///
+    /// With nothing pending the delta is empty and unsequenced.
     pub fn create_delta(&self) -> GraphDelta {
         let updates = self.take_pending_updates();
-        let mut seq = self.sequence.write().unwrap();
-        *seq += 1;
+        let new_concepts: Vec<ConceptId> = {
+            let mut pending = self.pending_concepts.write().unwrap();
+            pending.drain().collect()
+        };
+        let concepts = {
+            let concepts = self.concepts.read().unwrap();
+            new_concepts
+                .iter()
+                .filter_map(|id| concepts.get(id).cloned())
+                .collect()
+        };
+
+        let mut clock = self.clock.write().unwrap();
+        // Nothing to send: don't spend a sequence number peers would wait on forever
+        if new_concepts.is_empty() && updates.is_empty() {
+            let mut delta = GraphDelta::new(self.local_node);
+            delta.clock = clock.clone();
+            return delta;
+        }
+        let sequence = clock.increment(self.local_node);
 
         GraphDelta {
             from_node: self.local_node,
             timestamp: chrono::Utc::now().timestamp(),
-            sequence: *seq,
-            new_concepts: Vec::new(), // TODO: track new concepts
+            sequence,
+            clock: clock.clone(),
+            new_concepts,
+            concepts,
             edge_updates: updates,
             wormhole_updates: Vec::new(),
         }
     }
 
+    /// Create an unsequenced delta carrying our entire replicated state
+    ///
+    /// Used for anti-entropy when a new peer connects; merging it is
+    /// idempotent, so it is safe to send at any time.
+    pub fn snapshot_delta(&self) -> GraphDelta {
+        let concepts = self.concepts.read().unwrap();
+        let edges = self.edges.read().unwrap();
+
+        GraphDelta {
+            from_node: self.local_node,
+            timestamp: chrono::Utc::now().timestamp(),
+            sequence: 0,
+            clock: self.clock(),
+            new_concepts: concepts.keys().copied().collect(),
+            concepts: concepts.values().cloned().collect(),
+            edge_updates: edges.values().cloned().map(EdgeUpdate::State).collect(),
+            wormhole_updates: Vec::new(),
+        }
+    }
+
     // ========== Export/Import ==========
 
     /// Export graph to bytes
@@ -504,6 +756,7 @@ impl AlexandriaGraph {
             edges: edges.values().cloned().collect(),
             exported_at: chrono::Utc::now().timestamp(),
             from_node: self.local_node,
+            history: self.history.read().unwrap().clone(),
         };
 
         serde_json::to_vec(&export).unwrap_or_default()
@@ -530,6 +783,10 @@ impl AlexandriaGraph {
             edges.insert(key, edge);
         }
 
+        if !export.history.is_empty() {
+            *self.history.write().unwrap() = export.history;
+        }
+
         Ok(())
     }
 
@@ -569,6 +826,7 @@ impl AlexandriaGraph {
                 .map_err(|e| Error::IoError(format!("Failed to create directory: {}", e)))?;
         }
 
+        self.maybe_snapshot();
         let data = self.export();
         std::fs::write(path, &data)
             .map_err(|e| Error::IoError(format!("Failed to write graph: {}", e)))?;
@@ -648,6 +906,8 @@ struct GraphExport {
     edges: Vec<AlexandriaEdge>,
     exported_at: i64,
     from_node: NodeFingerprint,
+    #[serde(default)]
+    history: TopologyHistory,
 }
 
 /// Graph statistics
@@ -752,6 +1012,240 @@ mod tests {
         assert!(!reachable.contains_key(&d)); // 3 hops, beyond limit
     }
 
+    /// Canonical, order-independent view of a graph's replicated state
+    fn canonical(graph: &AlexandriaGraph) -> (Vec<String>, Vec<String>) {
+        let mut concepts: Vec<String> = graph
+            .all_concepts()
+            .iter()
+            .map(|c| format!("{}:{}:{:?}", c.id.to_hex(), c.text, c.meta_stamp))
+            .collect();
+        concepts.sort();
+
+        let edges = graph.edges.read().unwrap();
+        let mut edges: Vec<String> = edges
+            .values()
+            .map(|e| {
+                format!(
+                    "{}>{}:{:?}:{}:{}:{}:{}:{:?}:{}",
+                    e.from.to_hex(),
+                    e.to.to_hex(),
+                    e.kind,
+                    e.weight.to_bits(),
+                    e.use_count,
+                    e.created_at,
+                    e.last_used,
+                    e.source_nodes,
+                    e.dormant
+                )
+            })
+            .collect();
+        edges.sort();
+
+        (concepts, edges)
+    }
+
+    /// Three nodes doing overlapping local work, two deltas each
+    fn sample_deltas() -> Vec<GraphDelta> {
+        let mut deltas = Vec::new();
+        let nodes: Vec<AlexandriaGraph> = ["berlin", "tokyo", "austin"]
+            .iter()
+            .map(|name| {
+                AlexandriaGraph::with_defaults(NodeFingerprint::from_hardware(name, 4, 16, name))
+            })
+            .collect();
+
+        for (i, graph) in nodes.iter().enumerate() {
+            graph.record_query("encryption");
+            graph.record_query("security");
+            if i != 1 {
+                graph.record_query("firewall");
+            }
+            deltas.push(graph.create_delta());
+
+            let a = graph.ensure_concept("encryption");
+            let b = graph.ensure_concept(if i == 2 { "bitcoin" } else { "rsa" });
+            graph.add_edge(b, a, EdgeKind::RelatedTo);
+            graph.add_edge(a, b, EdgeKind::RelatedTo);
+            deltas.push(graph.create_delta());
+        }
+
+        deltas
+    }
+
+    fn permutations(n: usize) -> Vec<Vec<usize>> {
+        if n == 0 {
+            return vec![Vec::new()];
+        }
+        let mut result = Vec::new();
+        for perm in permutations(n - 1) {
+            for pos in 0..=perm.len() {
+                let mut p = perm.clone();
+                p.insert(pos, n - 1);
+                result.push(p);
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_merge_converges_for_every_delivery_order() {
+        let deltas = sample_deltas();
+        let observer = NodeFingerprint::from_hardware("observer", 2, 8, "obs");
+
+        let mut reference = None;
+        for order in permutations(deltas.len()) {
+            let graph = AlexandriaGraph::with_defaults(observer);
+            for &i in &order {
+                graph.merge_delta(deltas[i].clone());
+            }
+            let state = canonical(&graph);
+            match &reference {
+                None => reference = Some(state),
+                Some(expected) => assert_eq!(&state, expected, "order {:?} diverged", order),
+            }
+        }
+
+        let (concepts, edges) = reference.unwrap();
+        assert_eq!(concepts.len(), 5);
+        assert!(!edges.is_empty());
+    }
+
+    #[test]
+    fn test_merge_converges_with_duplicates_and_replays() {
+        let deltas = sample_deltas();
+        let observer = NodeFingerprint::from_hardware("observer", 2, 8, "obs");
+
+        let once = AlexandriaGraph::with_defaults(observer);
+        for delta in &deltas {
+            once.merge_delta(delta.clone());
+        }
+        let expected = canonical(&once);
+
+        // Pseudo-random delivery with repeats, seeded for reproducibility
+        for seed in 1..50u64 {
+            let graph = AlexandriaGraph::with_defaults(observer);
+            let mut state = seed;
+            let mut delivered = vec![false; deltas.len()];
+            while delivered.iter().any(|d| !d) {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                let i = (state >> 33) as usize % deltas.len();
+                graph.merge_delta(deltas[i].clone());
+                delivered[i] = true;
+            }
+            assert_eq!(canonical(&graph), expected, "seed {} diverged", seed);
+        }
+    }
+
+    #[test]
+    fn test_weight_increments_converge() {
+        let remote = AlexandriaGraph::with_defaults(NodeFingerprint::from_hardware("r", 4, 16, "r"));
+        let a = remote.ensure_concept("rust");
+        let b = remote.ensure_concept("ownership");
+        remote.add_edge(a, b, EdgeKind::RelatedTo);
+        let base = remote.snapshot_delta();
+
+        // Two rounds of remote usage, each announced as a weight update
+        let increment = |timestamp: i64| {
+            let key = if a.0 < b.0 { (a, b) } else { (b, a) };
+            let mut edges = remote.edges.write().unwrap();
+            let edge = edges.get_mut(&key).unwrap();
+            edge.record_contribution(remote.local_node, 0.5, 1);
+            let mut delta = base.clone();
+            delta.timestamp = timestamp;
+            delta.concepts.clear();
+            delta.new_concepts.clear();
+            delta.edge_updates = vec![edge.weight_update(remote.local_node)];
+            delta
+        };
+        let deltas = [increment(10), increment(20), remote.snapshot_delta()];
+
+        let orders: [&[usize]; 4] = [&[0, 1, 2], &[2, 1, 0], &[1, 0, 1, 0], &[0, 0, 2, 1, 2, 1]];
+        let mut results = Vec::new();
+        for order in orders {
+            let replica = AlexandriaGraph::with_defaults(test_node());
+            replica.merge_delta(base.clone());
+            for &i in order {
+                replica.merge_delta(deltas[i].clone());
+            }
+            results.push(canonical(&replica));
+        }
+        assert!(results.windows(2).all(|w| w[0] == w[1]), "{:#?}", results);
+
+        // Replays never push the remote's slot past what it actually recorded
+        let expected = canonical(&remote).1;
+        assert_eq!(results[0].1, expected);
+    }
+
+    #[test]
+    fn test_placeholders_do_not_invent_access_times() {
+        let remote = AlexandriaGraph::with_defaults(NodeFingerprint::from_hardware("r", 4, 16, "r"));
+        let local = AlexandriaGraph::with_defaults(test_node());
+
+        remote.record_query("a");
+        remote.record_query("b");
+        let mut delta = remote.create_delta();
+        delta.concepts.clear();
+        local.merge_delta(delta.clone());
+
+        let a = ConceptId::from_concept("a");
+        let placeholder = local.get_concept(&a).unwrap();
+        assert_eq!(placeholder.last_accessed, 0);
+        assert_eq!(placeholder.created_at, delta.timestamp);
+
+        local.merge_delta(remote.snapshot_delta());
+        let real = remote.get_concept(&a).unwrap();
+        let merged = local.get_concept(&a).unwrap();
+        assert_eq!(merged.text, "a");
+        assert_eq!(merged.last_accessed, real.last_accessed);
+        assert_eq!(merged.created_at, real.created_at);
+    }
+
+    #[test]
+    fn test_merge_delta_detects_duplicates_and_gaps() {
+        let remote = AlexandriaGraph::with_defaults(NodeFingerprint::from_hardware("r", 4, 16, "r"));
+        let local = AlexandriaGraph::with_defaults(test_node());
+
+        remote.record_query("a");
+        let d1 = remote.create_delta();
+        remote.record_query("b");
+        let d2 = remote.create_delta();
+        remote.record_query("c");
+        let d3 = remote.create_delta();
+
+        assert_eq!(
+            local.merge_delta(d3.clone()),
+            DeltaStatus::OutOfOrder { missing: vec![1..=2] }
+        );
+        assert_eq!(local.merge_delta(d3), DeltaStatus::Duplicate);
+        assert_eq!(local.missing_sequences(&d1.from_node), vec![1..=2]);
+        assert_eq!(local.merge_delta(d1), DeltaStatus::Applied);
+        assert_eq!(local.merge_delta(d2), DeltaStatus::Applied);
+        assert!(local.missing_sequences(&remote.local_node).is_empty());
+        assert_eq!(local.clock().get(&remote.local_node), 3);
+        assert_eq!(local.edge_count(), 2);
+    }
+
+    #[test]
+    fn test_nodes_exchanging_deltas_converge() {
+        let a = AlexandriaGraph::with_defaults(NodeFingerprint::from_hardware("a", 4, 16, "a"));
+        let b = AlexandriaGraph::with_defaults(NodeFingerprint::from_hardware("b", 4, 16, "b"));
+
+        a.record_query("rust");
+        a.record_query("memory safety");
+        b.record_query("memory safety");
+        b.record_query("rust");
+        let from_a = a.create_delta();
+        let from_b = b.create_delta();
+
+        a.merge_delta(from_b);
+        b.merge_delta(from_a);
+
+        assert_eq!(canonical(&a), canonical(&b));
+        let rust = ConceptId::from_concept("rust");
+        let safety = ConceptId::from_concept("memory safety");
+        assert_eq!(a.get_edge(&rust, &safety).unwrap().source_nodes.len(), 2);
+    }
+
     #[test]
     fn test_persistence_save_load() {
         use std::fs;
@@ -801,4 +1295,51 @@ mod tests {
         assert_eq!(graph.concept_count(), 0);
         assert_eq!(graph.edge_count(), 0);
     }
+
+    #[test]
+    fn test_time_travel_snapshots() {
+        let graph = AlexandriaGraph::with_defaults(test_node());
+        let now = chrono::Utc::now().timestamp();
+        let a = graph.ensure_concept("crypto");
+        let b = graph.ensure_concept("cryptography");
+        let c = graph.ensure_concept("currency");
+
+        graph.add_edge(a, b, EdgeKind::RelatedTo);
+        assert!(graph.snapshot_at(now - 300));
+        assert!(!graph.snapshot_at(now - 300));
+
+        graph.add_edge(a, c, EdgeKind::RelatedTo);
+        for _ in 0..5 {
+            graph.add_edge(a, c, EdgeKind::RelatedTo);
+        }
+        assert!(graph.snapshot_at(now - 200));
+
+        // The past graph only has the first edge, at its old weight
+        assert!(graph.as_of(now - 400).is_none());
+        let past = graph.as_of(now - 250).unwrap();
+        assert_eq!(past.edge_count(), 1);
+        assert_eq!(past.get_edge(&a, &b).unwrap().weight, graph.get_edge(&a, &b).unwrap().weight);
+        assert!(past.get_edge(&a, &c).is_none());
+        assert_eq!(graph.as_of(now).unwrap().edge_count(), 2);
+
+        let diff = graph.diff_between(now - 300, now - 200);
+        assert_eq!(diff.added.len(), 1);
+        let changes = diff.concept_changes();
+        assert_eq!(changes.iter().find(|ch| ch.concept == a).unwrap().gained, vec![c]);
+
+        // Historical query uses the snapshot weights
+        let then = graph.query_at("crypto", now - 250).unwrap();
+        assert_eq!(then.edges.iter().filter(|e| e.was_active).count(), 1);
+
+        // Drift sees "currency" overtaking "cryptography"
+        let drift = graph.query_drift("crypto").unwrap();
+        assert_eq!(drift.crossovers.len(), 1);
+        assert_eq!(drift.crossovers[0].rising_concept, c);
+        assert_eq!(drift.crossovers[0].falling_concept, b);
+
+        // History survives export/import
+        let copy = AlexandriaGraph::with_defaults(test_node());
+        copy.import(&graph.export()).unwrap();
+        assert_eq!(copy.history().timestamps(), vec![now - 300, now - 200]);
+    }
 }
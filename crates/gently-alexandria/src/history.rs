@@ -0,0 +1,488 @@
+//! History - Time-travel over the topology
+//!
+//! ```text
+//! t0 ───────── t1 ───────── t2 ───────── t3 ──── now
+//! [keyframe]   [Δ]          [Δ]          [keyframe]
+//!  all edges    changed      changed      all edges
+//!               edges only   edges only
+//!
+//! state_at(t2) = keyframe(t0) + Δ(t1) + Δ(t2)
+//! ```
+//!
+//! Snapshots are compact: a keyframe stores every edge, the snapshots in
+//! between only store edges whose weight or use count moved (plus the
+//! keys of edges that disappeared). Pruning old snapshots rewrites the
+//! new oldest one as a keyframe so replay always has a starting point.
+
+use crate::concept::ConceptId;
+use crate::edge::{AlexandriaEdge, EdgeKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Store a full keyframe every N snapshots
+pub const KEYFRAME_EVERY: usize = 24;
+
+/// Smallest weight change recorded in a delta snapshot
+const MIN_WEIGHT_CHANGE: f32 = 0.001;
+
+type EdgeKey = (ConceptId, ConceptId);
+
+/// Edge state captured in a snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EdgeSample {
+    pub from: ConceptId,
+    pub to: ConceptId,
+    /// Only stored when the edge first appears (and in keyframes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<EdgeKind>,
+    pub weight: f32,
+    pub uses: u64,
+}
+
+impl EdgeSample {
+    fn of(edge: &AlexandriaEdge) -> Self {
+        Self {
+            from: edge.from,
+            to: edge.to,
+            kind: Some(edge.kind.clone()),
+            weight: edge.weight,
+            uses: edge.use_count,
+        }
+    }
+
+    /// Ordered key (matches the graph's edge key)
+    pub fn key(&self) -> EdgeKey {
+        if self.from.0 < self.to.0 {
+            (self.from, self.to)
+        } else {
+            (self.to, self.from)
+        }
+    }
+
+    /// The endpoint that isn't `concept`
+    pub fn other(&self, concept: &ConceptId) -> ConceptId {
+        if self.from == *concept {
+            self.to
+        } else {
+            self.from
+        }
+    }
+
+    fn moved_from(&self, previous: &EdgeSample) -> bool {
+        (self.weight - previous.weight).abs() > MIN_WEIGHT_CHANGE || self.uses != previous.uses
+    }
+}
+
+/// One point in the history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologySnapshot {
+    /// When the snapshot was taken
+    pub timestamp: i64,
+
+    /// Full state (true) or changes since the previous snapshot
+    pub keyframe: bool,
+
+    /// Edges (all of them for a keyframe, changed ones otherwise)
+    pub edges: Vec<EdgeSample>,
+
+    /// Edges that disappeared since the previous snapshot
+    #[serde(default)]
+    pub removed: Vec<EdgeKey>,
+}
+
+/// Edge state of the whole graph at one timestamp
+pub type TopologyState = HashMap<EdgeKey, EdgeSample>;
+
+/// Sequence of snapshots
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TopologyHistory {
+    snapshots: Vec<TopologySnapshot>,
+
+    /// Materialized state at the latest snapshot
+    #[serde(skip)]
+    latest: Option<TopologyState>,
+}
+
+impl TopologyHistory {
+    /// Create empty history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of snapshots
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// No snapshots yet?
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Timestamps of all snapshots (oldest first)
+    pub fn timestamps(&self) -> Vec<i64> {
+        self.snapshots.iter().map(|s| s.timestamp).collect()
+    }
+
+    /// Timestamp of the oldest snapshot
+    pub fn oldest(&self) -> Option<i64> {
+        self.snapshots.first().map(|s| s.timestamp)
+    }
+
+    /// Timestamp of the latest snapshot
+    pub fn latest(&self) -> Option<i64> {
+        self.snapshots.last().map(|s| s.timestamp)
+    }
+
+    /// Record the current edges; returns false if `timestamp` isn't newer
+    /// than the latest snapshot
+    pub fn record(&mut self, timestamp: i64, edges: &[AlexandriaEdge], max_snapshots: usize) -> bool {
+        if self.latest().is_some_and(|latest| timestamp <= latest) {
+            return false;
+        }
+
+        let current: TopologyState = edges
+            .iter()
+            .map(|e| {
+                let sample = EdgeSample::of(e);
+                (sample.key(), sample)
+            })
+            .collect();
+
+        let since_keyframe = self
+            .snapshots
+            .iter()
+            .rev()
+            .take_while(|s| !s.keyframe)
+            .count();
+        let keyframe = self.snapshots.is_empty() || since_keyframe + 1 >= KEYFRAME_EVERY;
+
+        let snapshot = if keyframe {
+            keyframe_of(timestamp, &current)
+        } else {
+            let previous = self.latest_state();
+            let mut changed: Vec<EdgeSample> = current
+                .values()
+                .filter_map(|sample| match previous.get(&sample.key()) {
+                    None => Some(sample.clone()),
+                    Some(prev) if sample.moved_from(prev) => Some(EdgeSample {
+                        kind: None,
+                        ..sample.clone()
+                    }),
+                    Some(_) => None,
+                })
+                .collect();
+            changed.sort_by_key(|s| s.key());
+            let mut removed: Vec<EdgeKey> = previous
+                .keys()
+                .filter(|key| !current.contains_key(key))
+                .copied()
+                .collect();
+            removed.sort();
+
+            TopologySnapshot {
+                timestamp,
+                keyframe: false,
+                edges: changed,
+                removed,
+            }
+        };
+
+        self.snapshots.push(snapshot);
+        self.latest = Some(current);
+        self.prune(max_snapshots.max(1));
+        true
+    }
+
+    /// Edge state as of `timestamp` (None if it predates the history)
+    pub fn state_at(&self, timestamp: i64) -> Option<TopologyState> {
+        let end = self.snapshots.partition_point(|s| s.timestamp <= timestamp);
+        if end == 0 {
+            return None;
+        }
+        let start = self.snapshots[..end].iter().rposition(|s| s.keyframe).unwrap_or(0);
+
+        let mut state = TopologyState::new();
+        for snapshot in &self.snapshots[start..end] {
+            apply(&mut state, snapshot);
+        }
+        Some(state)
+    }
+
+    /// Replay every snapshot in order
+    pub fn for_each_state(&self, mut f: impl FnMut(i64, &TopologyState)) {
+        let mut state = TopologyState::new();
+        for snapshot in &self.snapshots {
+            apply(&mut state, snapshot);
+            f(snapshot.timestamp, &state);
+        }
+    }
+
+    /// What changed between two timestamps
+    pub fn diff(&self, from: i64, to: i64) -> TopologyDiff {
+        let before = self.state_at(from).unwrap_or_default();
+        let after = self.state_at(to).unwrap_or_default();
+        TopologyDiff::between(from, to, &before, &after)
+    }
+
+    fn latest_state(&mut self) -> &TopologyState {
+        if self.latest.is_none() {
+            let state = self
+                .latest()
+                .and_then(|ts| self.state_at(ts))
+                .unwrap_or_default();
+            self.latest = Some(state);
+        }
+        self.latest.as_ref().unwrap()
+    }
+
+    /// Drop the oldest snapshots, keeping a keyframe at the front
+    fn prune(&mut self, max_snapshots: usize) {
+        if self.snapshots.len() <= max_snapshots {
+            return;
+        }
+        let drop = self.snapshots.len() - max_snapshots;
+        let new_front = self.snapshots[drop].timestamp;
+        let state = self.state_at(new_front).unwrap_or_default();
+
+        self.snapshots.drain(..drop);
+        self.snapshots[0] = keyframe_of(new_front, &state);
+    }
+}
+
+fn keyframe_of(timestamp: i64, state: &TopologyState) -> TopologySnapshot {
+    let mut edges: Vec<EdgeSample> = state.values().cloned().collect();
+    edges.sort_by_key(|s| s.key());
+    TopologySnapshot {
+        timestamp,
+        keyframe: true,
+        edges,
+        removed: Vec::new(),
+    }
+}
+
+fn apply(state: &mut TopologyState, snapshot: &TopologySnapshot) {
+    if snapshot.keyframe {
+        state.clear();
+    }
+    for key in &snapshot.removed {
+        state.remove(key);
+    }
+    for sample in &snapshot.edges {
+        let kind = sample
+            .kind
+            .clone()
+            .or_else(|| state.get(&sample.key()).and_then(|s| s.kind.clone()));
+        state.insert(
+            sample.key(),
+            EdgeSample {
+                kind,
+                ..sample.clone()
+            },
+        );
+    }
+}
+
+/// Difference between two points in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyDiff {
+    pub from: i64,
+    pub to: i64,
+
+    /// Edges that exist at `to` but not at `from`
+    pub added: Vec<EdgeSample>,
+
+    /// Edges that existed at `from` but not at `to`
+    pub removed: Vec<EdgeSample>,
+
+    /// Edges present at both times whose weight moved (sample at `to`, delta)
+    pub changed: Vec<(EdgeSample, f32)>,
+}
+
+/// How one concept's connections changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConceptChange {
+    pub concept: ConceptId,
+
+    /// New neighbours
+    pub gained: Vec<ConceptId>,
+
+    /// Neighbours no longer connected
+    pub lost: Vec<ConceptId>,
+
+    /// Net change in total edge weight
+    pub weight_delta: f32,
+}
+
+impl TopologyDiff {
+    /// Compare two materialized states
+    pub fn between(from: i64, to: i64, before: &TopologyState, after: &TopologyState) -> Self {
+        let mut added: Vec<EdgeSample> = after
+            .iter()
+            .filter(|(key, _)| !before.contains_key(key))
+            .map(|(_, s)| s.clone())
+            .collect();
+        let mut removed: Vec<EdgeSample> = before
+            .iter()
+            .filter(|(key, _)| !after.contains_key(key))
+            .map(|(_, s)| s.clone())
+            .collect();
+        let mut changed: Vec<(EdgeSample, f32)> = after
+            .iter()
+            .filter_map(|(key, s)| {
+                let delta = s.weight - before.get(key)?.weight;
+                (delta.abs() > MIN_WEIGHT_CHANGE).then(|| (s.clone(), delta))
+            })
+            .collect();
+
+        added.sort_by_key(|s| s.key());
+        removed.sort_by_key(|s| s.key());
+        changed.sort_by_key(|(s, _)| s.key());
+
+        Self {
+            from,
+            to,
+            added,
+            removed,
+            changed,
+        }
+    }
+
+    /// Nothing changed?
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Per-concept summary, biggest movers first
+    pub fn concept_changes(&self) -> Vec<ConceptChange> {
+        let mut changes: BTreeMap<ConceptId, ConceptChange> = BTreeMap::new();
+
+        for sample in &self.added {
+            for concept in [sample.from, sample.to] {
+                let change = change_entry(&mut changes, concept);
+                change.gained.push(sample.other(&concept));
+                change.weight_delta += sample.weight;
+            }
+        }
+        for sample in &self.removed {
+            for concept in [sample.from, sample.to] {
+                let change = change_entry(&mut changes, concept);
+                change.lost.push(sample.other(&concept));
+                change.weight_delta -= sample.weight;
+            }
+        }
+        for (sample, delta) in &self.changed {
+            for concept in [sample.from, sample.to] {
+                change_entry(&mut changes, concept).weight_delta += delta;
+            }
+        }
+
+        let mut changes: Vec<ConceptChange> = changes.into_values().collect();
+        changes.sort_by(|a, b| {
+            let size = |c: &ConceptChange| c.gained.len() + c.lost.len();
+            size(b)
+                .cmp(&size(a))
+                .then(b.weight_delta.abs().total_cmp(&a.weight_delta.abs()))
+        });
+        changes
+    }
+}
+
+fn change_entry(changes: &mut BTreeMap<ConceptId, ConceptChange>, concept: ConceptId) -> &mut ConceptChange {
+    changes.entry(concept).or_insert_with(|| ConceptChange {
+        concept,
+        gained: Vec::new(),
+        lost: Vec::new(),
+        weight_delta: 0.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(a: &str, b: &str, weight: f32, uses: u64) -> AlexandriaEdge {
+        let mut e = AlexandriaEdge::new(
+            ConceptId::from_concept(a),
+            ConceptId::from_concept(b),
+            EdgeKind::RelatedTo,
+        );
+        e.weight = weight;
+        e.use_count = uses;
+        e
+    }
+
+    #[test]
+    fn test_deltas_replay_to_recorded_state() {
+        let mut history = TopologyHistory::new();
+        let ab = edge("a", "b", 1.0, 1);
+        let bc = edge("b", "c", 0.5, 1);
+
+        assert!(history.record(100, std::slice::from_ref(&ab), 100));
+        assert!(history.record(200, &[edge("a", "b", 2.0, 2), bc.clone()], 100));
+        assert!(history.record(300, std::slice::from_ref(&bc), 100));
+        assert!(!history.record(300, &[], 100));
+
+        // Only changed edges are stored after the keyframe
+        let delta = &history.snapshots[1];
+        assert_eq!(delta.edges.len(), 2);
+        let moved = delta.edges.iter().find(|s| s.key() == ab.key()).unwrap();
+        let new = delta.edges.iter().find(|s| s.key() == bc.key()).unwrap();
+        assert!(moved.kind.is_none());
+        assert!(new.kind.is_some());
+        assert_eq!(history.snapshots[2].edges.len(), 0);
+        assert_eq!(history.snapshots[2].removed, vec![ab.key()]);
+
+        assert!(history.state_at(50).is_none());
+        let at_150 = history.state_at(150).unwrap();
+        assert_eq!(at_150[&ab.key()].weight, 1.0);
+        let at_250 = history.state_at(250).unwrap();
+        assert_eq!(at_250[&ab.key()].weight, 2.0);
+        assert_eq!(at_250[&ab.key()].kind, Some(EdgeKind::RelatedTo));
+        assert_eq!(history.state_at(1000).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_keyframes_and_pruning() {
+        let mut history = TopologyHistory::new();
+        for i in 0..(KEYFRAME_EVERY as i64 * 2 + 5) {
+            let edges = vec![edge("a", "b", 1.0 + i as f32, i as u64)];
+            history.record(i, &edges, 30);
+        }
+
+        assert_eq!(history.len(), 30);
+        assert!(history.snapshots[0].keyframe);
+        let oldest = history.oldest().unwrap();
+        let key = edge("a", "b", 0.0, 0).key();
+        assert_eq!(history.state_at(oldest).unwrap()[&key].weight, 1.0 + oldest as f32);
+        assert_eq!(history.state_at(i64::MAX).unwrap()[&key].uses, history.latest().unwrap() as u64);
+
+        // Survives a serde roundtrip (latest cache is rebuilt)
+        let json = serde_json::to_string(&history).unwrap();
+        let mut restored: TopologyHistory = serde_json::from_str(&json).unwrap();
+        assert!(restored.record(1000, &[], 30));
+        assert_eq!(restored.snapshots.last().unwrap().removed, vec![key]);
+    }
+
+    #[test]
+    fn test_diff_concept_changes() {
+        let mut history = TopologyHistory::new();
+        history.record(1, &[edge("a", "b", 1.0, 1), edge("a", "c", 1.0, 1)], 10);
+        history.record(2, &[edge("a", "b", 3.0, 4), edge("a", "d", 0.5, 1)], 10);
+
+        let diff = history.diff(1, 2);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].1, 2.0);
+
+        let changes = diff.concept_changes();
+        let a = &changes[0];
+        assert_eq!(a.concept, ConceptId::from_concept("a"));
+        assert_eq!(a.gained, vec![ConceptId::from_concept("d")]);
+        assert_eq!(a.lost, vec![ConceptId::from_concept("c")]);
+        assert!((a.weight_delta - 1.5).abs() < 1e-6);
+
+        assert!(history.diff(2, 2).is_empty());
+    }
+}
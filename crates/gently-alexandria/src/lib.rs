@@ -28,22 +28,32 @@
 //! You're not building AI.
 //! You're building the search engine for everything humanity ever encoded into weights.
 
+pub mod algorithms;
 pub mod concept;
+pub mod crdt;
 pub mod edge;
+pub mod formats;
 pub mod node;
 pub mod graph;
+pub mod history;
 pub mod wormhole;
 pub mod sync;
+pub mod transport;
 pub mod query;
 pub mod economics;
 pub mod tesseract;
 
+pub use algorithms::{CommunityReport, ConceptMetrics, PageRankConfig, WeightedPath};
 pub use concept::ConceptId;
+pub use crdt::{Causality, DeltaStatus, LwwStamp, SequenceTracker, VectorClock, WeightCounter};
 pub use edge::{AlexandriaEdge, EdgeKind, EdgeUpdate};
+pub use formats::{GraphDocument, GraphFormat, Subgraph};
 pub use node::{AlexandriaNode, NodeFingerprint};
 pub use graph::AlexandriaGraph;
+pub use history::{ConceptChange, TopologyDiff, TopologyHistory};
 pub use wormhole::DistributedWormhole;
 pub use sync::{GraphDelta, SyncProtocol};
+pub use transport::{TcpTransport, TransportConfig};
 pub use query::{FullTopology, HistoricalTopology, DriftAnalysis};
 pub use economics::{ContributionProof, RewardCalculator};
 pub use tesseract::{
@@ -101,6 +111,12 @@ pub struct AlexandriaConfig {
 
     /// Enable cross-node wormhole discovery
     pub enable_distributed_wormholes: bool,
+
+    /// Minimum seconds between topology snapshots (default: 3600)
+    pub snapshot_interval_secs: i64,
+
+    /// Snapshots kept for time-travel queries (default: 90 days hourly)
+    pub max_snapshots: usize,
 }
 
 impl Default for AlexandriaConfig {
@@ -112,6 +128,8 @@ impl Default for AlexandriaConfig {
             sync_interval_secs: 60,
             pubsub_topic: "/alexandria/deltas/v1".to_string(),
             enable_distributed_wormholes: true,
+            snapshot_interval_secs: 3600,
+            max_snapshots: 24 * 90,
         }
     }
 }
@@ -13,7 +13,7 @@ use sha2::{Digest, Sha256};
 use std::fmt;
 
 /// Unique identifier for a mesh node
-#[derive(Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Hash, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct NodeFingerprint(pub [u8; 32]);
 
 impl NodeFingerprint {
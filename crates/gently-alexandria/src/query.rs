@@ -16,6 +16,7 @@
 //! → The graph REMEMBERS what we used to mean
 //! ```
 
+use crate::algorithms::{Community, ConceptMetrics, WeightedPath};
 use crate::concept::{Concept, ConceptId};
 use crate::edge::AlexandriaEdge;
 use crate::graph::AlexandriaGraph;
@@ -232,11 +233,20 @@ impl<'a> QueryBuilder<'a> {
 
         let all_edges = self.graph.all_edges_for(&concept);
 
+        // Prefer recorded snapshots; fall back to interpolating the edge
+        let recorded = self.graph.history().state_at(timestamp);
+
         let edges: Vec<EdgeAtTime> = all_edges
             .into_iter()
-            .filter(|e| e.created_at <= timestamp)
+            .filter(|e| match &recorded {
+                Some(state) => state.contains_key(&e.key()),
+                None => e.created_at <= timestamp,
+            })
             .map(|e| {
-                let weight_at_time = e.weight_at(timestamp, 30.0);
+                let weight_at_time = match &recorded {
+                    Some(state) => state[&e.key()].weight,
+                    None => e.weight_at(timestamp, 30.0),
+                };
                 let was_active = weight_at_time > 0.01;
                 EdgeAtTime {
                     edge: e,
@@ -271,6 +281,38 @@ impl<'a> QueryBuilder<'a> {
         })
     }
 
+    /// Cheapest path from the concept to a target (strong edges are short)
+    pub fn weighted_path_to(&self, target: &str) -> Option<WeightedPath> {
+        let concept = self.concept?;
+        self.graph
+            .weighted_path(&concept, &ConceptId::from_concept(target))
+    }
+
+    /// Rank hubs by PageRank
+    ///
+    /// With a concept set, only concepts within `max_hops` of it are
+    /// returned (the local hubs); otherwise the whole graph is ranked.
+    pub fn execute_hubs(&self, top_k: usize) -> Vec<ConceptMetrics> {
+        let hubs = self.graph.hubs(usize::MAX);
+        let mut hubs: Vec<ConceptMetrics> = match self.concept {
+            Some(concept) => {
+                let nearby = self.graph.reachable(&concept, self.max_hops);
+                hubs.into_iter()
+                    .filter(|m| nearby.contains_key(&m.concept))
+                    .collect()
+            }
+            None => hubs,
+        };
+        hubs.truncate(top_k);
+        hubs
+    }
+
+    /// Topic cluster the concept belongs to
+    pub fn execute_community(&self) -> Option<Community> {
+        let concept = self.concept?;
+        self.graph.communities().community_of(&concept).cloned()
+    }
+
     /// Compute drift analysis
     fn compute_drift(
         &self,
@@ -298,8 +340,7 @@ impl<'a> QueryBuilder<'a> {
         rising.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
         falling.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
 
-        // Detect crossovers (simplified - would need historical data)
-        let crossovers = Vec::new(); // TODO: implement with temporal data
+        let crossovers = self.detect_crossovers(concept);
 
         DriftAnalysis {
             concept: *concept,
@@ -309,6 +350,44 @@ impl<'a> QueryBuilder<'a> {
             crossovers,
         }
     }
+
+    /// Walk the snapshot history and report when the strongest neighbour changed
+    fn detect_crossovers(&self, concept: &ConceptId) -> Vec<CrossoverEvent> {
+        let label = |id: &ConceptId| {
+            self.graph
+                .get_concept(id)
+                .map(|c| c.text)
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| id.short())
+        };
+
+        let mut crossovers = Vec::new();
+        let mut leader: Option<ConceptId> = None;
+
+        self.graph.history().for_each_state(|timestamp, state| {
+            let strongest = state
+                .values()
+                .filter(|s| s.from == *concept || s.to == *concept)
+                .max_by(|a, b| a.weight.total_cmp(&b.weight).then(b.key().cmp(&a.key())))
+                .map(|s| s.other(concept));
+
+            if let (Some(previous), Some(current)) = (leader, strongest) {
+                if previous != current {
+                    crossovers.push(CrossoverEvent {
+                        rising_concept: current,
+                        falling_concept: previous,
+                        timestamp,
+                        description: format!("{} overtook {}", label(&current), label(&previous)),
+                    });
+                }
+            }
+            if strongest.is_some() {
+                leader = strongest;
+            }
+        });
+
+        crossovers
+    }
 }
 
 /// Query functions on the graph
@@ -467,4 +546,28 @@ mod tests {
 
         assert!(result.is_some());
     }
+
+    #[test]
+    fn test_query_builder_algorithms() {
+        let graph = AlexandriaGraph::with_defaults(test_node());
+
+        graph.record_query("a");
+        graph.record_query("b");
+        graph.record_query("c");
+        graph.new_session();
+        graph.record_query("x");
+        graph.record_query("y");
+
+        let query = QueryBuilder::new(&graph).concept("a").max_hops(1);
+        let path = query.weighted_path_to("c").unwrap();
+        assert_eq!(path.concepts.len(), 3);
+
+        let local = query.execute_hubs(10);
+        assert_eq!(local.len(), 2);
+        assert_eq!(QueryBuilder::new(&graph).execute_hubs(10).len(), 5);
+
+        let community = query.execute_community().unwrap();
+        assert!(community.members.contains(&ConceptId::from_concept("b")));
+        assert!(!community.members.contains(&ConceptId::from_concept("x")));
+    }
 }
@@ -18,8 +18,11 @@
 //!   │ ─────────────────────────────► │ ─────────────────────────────►  │
 //!   │                                │                                │
 //! ```
+//!
+//! For direct peer-to-peer sync without IPFS, see [`crate::transport`].
 
-use crate::concept::ConceptId;
+use crate::concept::{Concept, ConceptId};
+use crate::crdt::{SequenceTracker, VectorClock};
 use crate::edge::EdgeUpdate;
 use crate::node::{AlexandriaNode, NodeFingerprint, NodeRegistry};
 use crate::wormhole::WormholeUpdate;
@@ -40,9 +43,17 @@ pub struct GraphDelta {
     /// Sequence number (for ordering)
     pub sequence: u64,
 
+    /// Sender's vector clock when the delta was created
+    #[serde(default)]
+    pub clock: VectorClock,
+
     /// New concepts introduced
     pub new_concepts: Vec<ConceptId>,
 
+    /// Replicated state of the new concepts
+    #[serde(default)]
+    pub concepts: Vec<Concept>,
+
     /// Edge updates
     pub edge_updates: Vec<EdgeUpdate>,
 
@@ -57,7 +68,9 @@ impl GraphDelta {
             from_node,
             timestamp: chrono::Utc::now().timestamp(),
             sequence: 0,
+            clock: VectorClock::new(),
             new_concepts: Vec::new(),
+            concepts: Vec::new(),
             edge_updates: Vec::new(),
             wormhole_updates: Vec::new(),
         }
@@ -123,8 +136,8 @@ pub struct SyncProtocol {
     /// Pending outgoing deltas
     outgoing: Arc<RwLock<Vec<GraphDelta>>>,
 
-    /// Last sequence number seen from each node
-    last_seen_sequence: Arc<RwLock<std::collections::HashMap<NodeFingerprint, u64>>>,
+    /// Sequence numbers seen from each node
+    seen_sequences: Arc<RwLock<std::collections::HashMap<NodeFingerprint, SequenceTracker>>>,
 
     /// Channel for incoming deltas
     incoming_tx: Option<mpsc::Sender<GraphDelta>>,
@@ -142,7 +155,7 @@ impl SyncProtocol {
             local_node,
             registry: Arc::new(RwLock::new(NodeRegistry::new())),
             outgoing: Arc::new(RwLock::new(Vec::new())),
-            last_seen_sequence: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            seen_sequences: Arc::new(RwLock::new(std::collections::HashMap::new())),
             incoming_tx: None,
             deltas_sent: Arc::new(RwLock::new(0)),
             deltas_received: Arc::new(RwLock::new(0)),
@@ -171,26 +184,21 @@ impl SyncProtocol {
 
     /// Process incoming delta
     pub async fn process_incoming(&self, delta: GraphDelta) -> Result<bool> {
-        // Check if we've already seen this
-        let dominated = {
-            let sequences = self.last_seen_sequence.read().unwrap();
-            if let Some(&last) = sequences.get(&delta.from_node) {
-                delta.sequence <= last
-            } else {
-                false
-            }
+        // Drop duplicates; out-of-order and unsequenced deltas are still forwarded
+        let status = if delta.sequence == 0 {
+            crate::crdt::DeltaStatus::Applied
+        } else {
+            let mut sequences = self.seen_sequences.write().unwrap();
+            sequences
+                .entry(delta.from_node)
+                .or_default()
+                .observe(delta.sequence)
         };
 
-        if dominated {
+        if !status.should_apply() {
             return Ok(false);
         }
 
-        // Update last seen sequence
-        {
-            let mut sequences = self.last_seen_sequence.write().unwrap();
-            sequences.insert(delta.from_node, delta.sequence);
-        }
-
         // Update stats
         {
             let mut received = self.deltas_received.write().unwrap();
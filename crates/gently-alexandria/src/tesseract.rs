@@ -59,9 +59,10 @@
 //! THE TESSERACT HOLDS BOTH WITHOUT CONTRADICTION.
 //! ```
 
-use crate::{ConceptId, AlexandriaEdge, EdgeKind};
+use crate::{ConceptId, AlexandriaEdge, EdgeKind, Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 
 /// Embedding dimensions per face (384 total / 8 faces = 48 per face)
@@ -70,6 +71,9 @@ pub const DIMS_PER_FACE: usize = 48;
 /// Total embedding dimensions (BGE-small = 384)
 pub const TOTAL_DIMS: usize = 384;
 
+/// On-disk schema version for saved tesseracts
+pub const TESSERACT_SCHEMA_VERSION: u32 = 1;
+
 /// Face embeddings - 8 faces with 48 dimensions each
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FaceEmbeddings {
@@ -815,6 +819,149 @@ impl SemanticTesseract {
             weight: edge.weight,
         }
     }
+
+    /// Number of concepts with at least one position
+    pub fn concept_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Total recorded positions
+    pub fn position_count(&self) -> usize {
+        self.positions.values().map(|p| p.len()).sum()
+    }
+
+    /// Concepts with at least one position
+    pub fn concepts(&self) -> impl Iterator<Item = &ConceptId> {
+        self.positions.keys()
+    }
+
+    /// Most recently recorded position for a concept
+    pub fn latest_position(&self, concept: &ConceptId) -> Option<&HyperPosition> {
+        self.positions.get(concept)?.last()
+    }
+
+    // ========== Persistence ==========
+
+    /// Serialize to bytes (versioned)
+    ///
+    /// Only positions are stored; the era/observer/context indexes are
+    /// rebuilt on load.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Concepts in a stable order, each concept's positions in recording order
+        let mut concepts: Vec<&ConceptId> = self.positions.keys().collect();
+        concepts.sort();
+
+        let file = TesseractFile {
+            version: TESSERACT_SCHEMA_VERSION,
+            saved_at: Utc::now().timestamp(),
+            positions: concepts
+                .into_iter()
+                .flat_map(|c| self.positions[c].iter().cloned())
+                .collect(),
+        };
+
+        serde_json::to_vec(&file).unwrap_or_default()
+    }
+
+    /// Deserialize from bytes
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let file: TesseractFile =
+            serde_json::from_slice(data).map_err(|e| Error::SerializationError(e.to_string()))?;
+
+        if file.version > TESSERACT_SCHEMA_VERSION {
+            return Err(Error::SerializationError(format!(
+                "Tesseract schema v{} is newer than supported v{}",
+                file.version, TESSERACT_SCHEMA_VERSION
+            )));
+        }
+
+        let mut tesseract = Self::new();
+        for position in file.positions {
+            tesseract.record_position(position);
+        }
+        Ok(tesseract)
+    }
+
+    /// Get default persistence path (~/.gently/alexandria/tesseract.json)
+    pub fn default_path() -> PathBuf {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".gently")
+            .join("alexandria")
+            .join("tesseract.json")
+    }
+
+    /// Save tesseract to file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| Error::IoError(format!("Failed to create directory: {}", e)))?;
+        }
+
+        // Write then rename so a crash never leaves a truncated file
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, self.to_bytes())
+            .map_err(|e| Error::IoError(format!("Failed to write tesseract: {}", e)))?;
+        std::fs::rename(&tmp, path)
+            .map_err(|e| Error::IoError(format!("Failed to write tesseract: {}", e)))?;
+
+        tracing::info!(
+            "Saved tesseract to {}: {} concepts, {} positions",
+            path.display(),
+            self.concept_count(),
+            self.position_count()
+        );
+
+        Ok(())
+    }
+
+    /// Save to default path
+    pub fn save_default(&self) -> Result<()> {
+        self.save(&Self::default_path())
+    }
+
+    /// Load tesseract from file
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)
+            .map_err(|e| Error::IoError(format!("Failed to read tesseract: {}", e)))?;
+        Self::from_bytes(&data)
+    }
+
+    /// Load from default path
+    pub fn load_default() -> Result<Self> {
+        Self::load(&Self::default_path())
+    }
+
+    /// Load from file or create new if not exists
+    ///
+    /// A file that exists but fails to load is reported and left alone; use
+    /// [`SemanticTesseract::load`] to handle that case yourself.
+    pub fn load_or_create(path: &Path) -> Self {
+        match Self::load(path) {
+            Ok(tesseract) => tesseract,
+            Err(e) if path.exists() => {
+                tracing::warn!("Tesseract {} failed to load, starting empty: {}", path.display(), e);
+                Self::new()
+            }
+            Err(e) => {
+                tracing::info!("Creating new tesseract ({})", e);
+                Self::new()
+            }
+        }
+    }
+
+    /// Load from default path or create new
+    pub fn load_or_create_default() -> Self {
+        Self::load_or_create(&Self::default_path())
+    }
+}
+
+/// Saved tesseract layout
+#[derive(Serialize, Deserialize)]
+struct TesseractFile {
+    version: u32,
+    saved_at: i64,
+    positions: Vec<HyperPosition>,
 }
 
 /// Full meaning of a concept across all 8 faces
@@ -1521,4 +1668,90 @@ mod tests {
         assert!(eras.contains(&"2015+".to_string()));
         assert!(eras.contains(&"modern".to_string()));
     }
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let mut tesseract = SemanticTesseract::new();
+
+        let crypto = make_concept("crypto");
+        let aes = make_concept("aes");
+        let bitcoin = make_concept("bitcoin");
+        let embedding: Vec<f32> = (0..TOTAL_DIMS).map(|i| (i as f32 * 0.01).sin()).collect();
+
+        tesseract.record_position(HyperPosition {
+            concept: crypto,
+            actual: vec![aes],
+            eliminated: vec![bitcoin],
+            potential: vec![],
+            temporal: TemporalPosition {
+                valid_from: Some(Utc::now() - chrono::Duration::days(3650)),
+                valid_until: Some(Utc::now() - chrono::Duration::days(1000)),
+                era_tags: vec!["2015".to_string()],
+                moments: vec![],
+            },
+            observer: vec!["academia".to_string()],
+            context: vec!["security".to_string()],
+            method: vec![],
+            purpose: vec![],
+            embedding: None,
+            face_embeddings: None,
+            recorded_at: Utc::now() - chrono::Duration::days(3650),
+        }.with_embedding(embedding));
+        tesseract.record_position(HyperPosition {
+            concept: crypto,
+            actual: vec![bitcoin],
+            eliminated: vec![],
+            potential: vec![aes],
+            temporal: TemporalPosition {
+                era_tags: vec!["2021".to_string()],
+                ..Default::default()
+            },
+            observer: vec!["twitter".to_string()],
+            context: vec!["finance".to_string()],
+            method: vec![],
+            purpose: vec![],
+            embedding: None,
+            face_embeddings: None,
+            recorded_at: Utc::now(),
+        });
+
+        let path = std::env::temp_dir().join(format!("tesseract-{}.json", uuid::Uuid::new_v4()));
+        tesseract.save(&path).unwrap();
+        let loaded = SemanticTesseract::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.position_count(), 2);
+        assert_eq!(loaded.concepts_in_era("2015"), vec![crypto]);
+
+        // Queries behave the same on the reloaded data
+        let then = Utc::now() - chrono::Duration::days(2000);
+        for query in [
+            || HyperQuery::new().concept(make_concept("crypto")).in_face(HyperFace::Actual),
+            || HyperQuery::new().concept(make_concept("crypto")).in_face(HyperFace::Eliminated),
+            || HyperQuery::new().in_era("2021"),
+            || HyperQuery::new().from_observer("academia"),
+            || HyperQuery::new().in_context("finance"),
+        ] {
+            assert_eq!(query().execute(&loaded).concepts, query().execute(&tesseract).concepts);
+        }
+        assert_eq!(
+            HyperQuery::new().concept(crypto).at_time(then).in_face(HyperFace::Actual).execute(&loaded).concepts,
+            vec![aes]
+        );
+
+        let faces = loaded.positions[&crypto][0].face_embeddings.as_ref().unwrap();
+        let original = tesseract.positions[&crypto][0].face_embeddings.as_ref().unwrap();
+        assert_eq!(faces.actual, original.actual);
+        assert!(loaded.positions[&crypto][0].is_eliminated(&bitcoin));
+    }
+
+    #[test]
+    fn test_rejects_newer_schema() {
+        let data = format!(
+            r#"{{"version":{},"saved_at":0,"positions":[]}}"#,
+            TESSERACT_SCHEMA_VERSION + 1
+        );
+        assert!(SemanticTesseract::from_bytes(data.as_bytes()).is_err());
+        assert!(SemanticTesseract::from_bytes(br#"{"version":1,"saved_at":0,"positions":[]}"#).is_ok());
+    }
 }
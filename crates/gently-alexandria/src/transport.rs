@@ -0,0 +1,789 @@
+//! TCP Transport - Direct peer-to-peer delta gossip
+//!
+//! ```text
+//! NODE A (dials)                                      NODE B (listens)
+//!   │  ── Hello { announcement, nonce_a } ──────────────► │
+//!   │  ◄────────────── Hello { announcement, nonce_b } ── │
+//!   │  ── Auth { MAC(initiator) } ──────────────────────► │  B checks first
+//!   │  ◄──────────────────────── Auth { MAC(responder) } ─ │
+//!   │                                                     │
+//!   │  ── Delta { GraphDelta, ttl } ────────────────────► │ ── relay ──► C, D ...
+//!
+//! MAC(role) = HMAC-SHA256(key, role ‖ nonce_a ‖ nonce_b ‖ fp_a ‖ fp_b)
+//! Frame: [u32 big-endian length][JSON body]
+//! ```
+//!
+//! Loop prevention: a delta is only relayed the first time its
+//! `(from_node, sequence)` is seen, and never back to the peer it came
+//! from. `ttl` bounds relays of unsequenced deltas. Each new connection
+//! starts with an unsequenced snapshot so late joiners catch up.
+//!
+//! Every mesh has a secret network key; without it a peer cannot produce
+//! an `Auth` proof. Holders of the key are trusted to present their own
+//! fingerprint, so pin `trusted_peers` when that matters.
+
+use crate::graph::AlexandriaGraph;
+use crate::node::NodeFingerprint;
+use crate::sync::{GraphDelta, NodeAnnouncement, SyncProtocol};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, RwLock};
+
+/// Largest frame accepted from a peer (16 MiB)
+pub const MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+/// Wire messages between peers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Frame {
+    /// First message on a connection
+    Hello {
+        announcement: NodeAnnouncement,
+        nonce: [u8; 16],
+    },
+
+    /// Proof that the sender owns its fingerprint on this mesh
+    Auth { proof: [u8; 32] },
+
+    /// Graph delta to merge (and relay)
+    Delta { delta: GraphDelta, ttl: u8 },
+
+    /// Updated node announcement
+    Announce(NodeAnnouncement),
+}
+
+impl Frame {
+    /// Serialize frame body
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+
+    /// Deserialize frame body
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+}
+
+/// Write one length-prefixed frame
+pub async fn write_frame<W: AsyncWrite + Unpin>(writer: &mut W, frame: &Frame) -> Result<()> {
+    let body = frame.to_bytes();
+    if body.len() > MAX_FRAME_LEN {
+        return Err(Error::SyncFailed(format!(
+            "Frame too large: {} bytes",
+            body.len()
+        )));
+    }
+
+    writer
+        .write_all(&(body.len() as u32).to_be_bytes())
+        .await
+        .map_err(|e| Error::IoError(e.to_string()))?;
+    writer
+        .write_all(&body)
+        .await
+        .map_err(|e| Error::IoError(e.to_string()))?;
+    writer
+        .flush()
+        .await
+        .map_err(|e| Error::IoError(e.to_string()))
+}
+
+/// Read one length-prefixed frame
+pub async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Frame> {
+    let mut len_buf = [0u8; 4];
+    reader
+        .read_exact(&mut len_buf)
+        .await
+        .map_err(|e| Error::IoError(e.to_string()))?;
+
+    let len = u32::from_be_bytes(len_buf) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(Error::SyncFailed(format!("Frame too large: {} bytes", len)));
+    }
+
+    let mut body = vec![0u8; len];
+    reader
+        .read_exact(&mut body)
+        .await
+        .map_err(|e| Error::IoError(e.to_string()))?;
+
+    Frame::from_bytes(&body)
+}
+
+/// Transport configuration
+#[derive(Debug, Clone)]
+pub struct TransportConfig {
+    /// Address to listen on (port 0 picks a free port)
+    pub listen_addr: SocketAddr,
+
+    /// Shared mesh secret mixed into handshake proofs
+    pub network_key: [u8; 32],
+
+    /// Only accept these peers (None = accept any authenticated peer)
+    pub trusted_peers: Option<HashSet<NodeFingerprint>>,
+
+    /// Maximum relay hops for a delta
+    pub max_hops: u8,
+}
+
+impl TransportConfig {
+    /// Loopback config for a mesh with the given secret
+    pub fn new(network_key: [u8; 32]) -> Self {
+        Self {
+            listen_addr: SocketAddr::from(([127, 0, 0, 1], 0)),
+            network_key,
+            trusted_peers: None,
+            max_hops: 8,
+        }
+    }
+}
+
+/// Which end of a connection a handshake proof speaks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Initiator,
+    Responder,
+}
+
+/// Both ends' nonces and fingerprints, initiator first
+struct Transcript {
+    nonces: [[u8; 16]; 2],
+    fingerprints: [NodeFingerprint; 2],
+}
+
+/// Handshake proof: HMAC(key, role ‖ nonce_i ‖ nonce_r ‖ fp_i ‖ fp_r)
+///
+/// Binding the role and both ends means a proof can't be replayed on
+/// another connection or reflected back in the other direction.
+fn handshake_proof(key: &[u8; 32], role: Role, transcript: &Transcript) -> [u8; 32] {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(match role {
+        Role::Initiator => b"alexandria-handshake-v2/initiator",
+        Role::Responder => b"alexandria-handshake-v2/responder",
+    });
+    for nonce in &transcript.nonces {
+        mac.update(nonce);
+    }
+    for fingerprint in &transcript.fingerprints {
+        mac.update(fingerprint.as_bytes());
+    }
+    mac.finalize().into_bytes().into()
+}
+
+type PeerMap = HashMap<NodeFingerprint, mpsc::Sender<Frame>>;
+
+/// Peer-to-peer TCP transport for a graph
+///
+/// Cheap to clone; all clones share the same peers and graph.
+#[derive(Clone)]
+pub struct TcpTransport {
+    graph: AlexandriaGraph,
+    sync: Arc<SyncProtocol>,
+    config: TransportConfig,
+    peers: Arc<RwLock<PeerMap>>,
+    local_addr: Arc<RwLock<Option<SocketAddr>>>,
+}
+
+impl TcpTransport {
+    /// Create transport for a graph
+    pub fn new(graph: AlexandriaGraph, sync: SyncProtocol, config: TransportConfig) -> Self {
+        Self {
+            graph,
+            sync: Arc::new(sync),
+            config,
+            peers: Arc::new(RwLock::new(HashMap::new())),
+            local_addr: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Our fingerprint
+    pub fn fingerprint(&self) -> NodeFingerprint {
+        self.sync.our_announcement().node.fingerprint
+    }
+
+    /// The graph this transport syncs
+    pub fn graph(&self) -> &AlexandriaGraph {
+        &self.graph
+    }
+
+    /// The sync protocol (registry and stats)
+    pub fn sync(&self) -> &SyncProtocol {
+        &self.sync
+    }
+
+    /// Address we are listening on (after `listen`)
+    pub async fn local_addr(&self) -> Option<SocketAddr> {
+        *self.local_addr.read().await
+    }
+
+    /// Connected peers
+    pub async fn peers(&self) -> Vec<NodeFingerprint> {
+        self.peers.read().await.keys().copied().collect()
+    }
+
+    /// Start accepting connections in the background
+    pub async fn listen(&self) -> Result<SocketAddr> {
+        let listener = TcpListener::bind(self.config.listen_addr)
+            .await
+            .map_err(|e| Error::IoError(e.to_string()))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| Error::IoError(e.to_string()))?;
+        *self.local_addr.write().await = Some(addr);
+
+        let transport = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, remote) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        tracing::warn!("Alexandria accept failed: {}", e);
+                        continue;
+                    }
+                };
+
+                let transport = transport.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = transport.run_connection(stream, None).await {
+                        tracing::debug!("Alexandria peer {} dropped: {}", remote, e);
+                    }
+                });
+            }
+        });
+
+        tracing::info!("Alexandria transport listening on {}", addr);
+        Ok(addr)
+    }
+
+    /// Dial a peer; returns its fingerprint once the handshake completes
+    pub async fn connect(&self, addr: SocketAddr) -> Result<NodeFingerprint> {
+        self.connect_expecting(addr, None).await
+    }
+
+    /// Dial a peer and require it to present a specific fingerprint
+    pub async fn connect_expecting(
+        &self,
+        addr: SocketAddr,
+        expected: Option<NodeFingerprint>,
+    ) -> Result<NodeFingerprint> {
+        let stream = TcpStream::connect(addr)
+            .await
+            .map_err(|e| Error::IoError(e.to_string()))?;
+
+        let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+        let transport = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = transport
+                .run_connection(stream, Some((expected, ready_tx)))
+                .await
+            {
+                tracing::debug!("Alexandria peer {} dropped: {}", addr, e);
+            }
+        });
+
+        ready_rx
+            .await
+            .map_err(|_| Error::SyncFailed("Connection closed during handshake".to_string()))?
+    }
+
+    /// Create a delta from pending graph updates and send it to every peer
+    ///
+    /// Returns the number of deltas sent (0 if nothing changed).
+    pub async fn publish(&self) -> Result<usize> {
+        let delta = self.graph.create_delta();
+        self.sync.queue_delta(delta);
+
+        let outgoing = self.sync.take_outgoing();
+        let count = outgoing.len();
+        for delta in outgoing {
+            self.send_to_peers(
+                Frame::Delta {
+                    delta,
+                    ttl: self.config.max_hops,
+                },
+                None,
+            )
+            .await;
+            self.sync.record_sent();
+        }
+
+        Ok(count)
+    }
+
+    /// Announce ourselves to every peer
+    pub async fn announce(&self) {
+        self.send_to_peers(Frame::Announce(self.sync.our_announcement()), None)
+            .await;
+    }
+
+    /// Queue a frame for every peer without waiting on slow ones
+    ///
+    /// Senders are cloned out so the peer lock is never held across a
+    /// send; a peer whose queue is full misses the frame rather than
+    /// stalling the connection that is relaying it.
+    async fn send_to_peers(&self, frame: Frame, except: Option<NodeFingerprint>) {
+        let targets: Vec<_> = self
+            .peers
+            .read()
+            .await
+            .iter()
+            .filter(|(fp, _)| Some(**fp) != except)
+            .map(|(fp, tx)| (*fp, tx.clone()))
+            .collect();
+
+        for (fp, tx) in targets {
+            if let Err(mpsc::error::TrySendError::Full(_)) = tx.try_send(frame.clone()) {
+                tracing::warn!("Peer {} send queue full, dropping frame", fp);
+            }
+        }
+    }
+
+    /// Handshake, then pump frames until the connection drops
+    async fn run_connection(
+        &self,
+        stream: TcpStream,
+        dialed: Option<(
+            Option<NodeFingerprint>,
+            tokio::sync::oneshot::Sender<Result<NodeFingerprint>>,
+        )>,
+    ) -> Result<()> {
+        let (mut reader, mut writer) = stream.into_split();
+
+        let (role, expected, ready) = match dialed {
+            Some((expected, ready)) => (Role::Initiator, expected, Some(ready)),
+            None => (Role::Responder, None, None),
+        };
+
+        let handshake = self.handshake(&mut reader, &mut writer, role, expected).await;
+        let peer = match handshake {
+            Ok(peer) => peer,
+            Err(e) => {
+                if let Some(ready) = ready {
+                    let _ = ready.send(Err(Error::SyncFailed(e.to_string())));
+                }
+                return Err(e);
+            }
+        };
+
+        let (tx, mut rx) = mpsc::channel::<Frame>(256);
+        {
+            let mut peers = self.peers.write().await;
+            if peers.contains_key(&peer) {
+                drop(peers);
+                if let Some(ready) = ready {
+                    let _ = ready.send(Ok(peer));
+                }
+                return Ok(()); // Already connected via another socket
+            }
+            peers.insert(peer, tx);
+        }
+
+        // Anti-entropy: bring the new peer up to date with everything we know
+        let _ = write_frame(
+            &mut writer,
+            &Frame::Delta {
+                delta: self.graph.snapshot_delta(),
+                ttl: 1,
+            },
+        )
+        .await;
+
+        let writer_task = tokio::spawn(async move {
+            while let Some(frame) = rx.recv().await {
+                if write_frame(&mut writer, &frame).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        // The peer's first frame is its snapshot, written only after it has
+        // registered us, so a dial is reported complete once that arrives
+        let mut ready = ready;
+        let result = loop {
+            let frame = match read_frame(&mut reader).await {
+                Ok(frame) => frame,
+                Err(e) => {
+                    if let Some(ready) = ready.take() {
+                        let _ = ready.send(Err(Error::SyncFailed(e.to_string())));
+                    }
+                    break Err(e);
+                }
+            };
+            if let Err(e) = self.handle_frame(peer, frame).await {
+                break Err(e);
+            }
+            if let Some(ready) = ready.take() {
+                let _ = ready.send(Ok(peer));
+            }
+        };
+
+        self.peers.write().await.remove(&peer);
+        writer_task.abort();
+        result
+    }
+
+    async fn handshake<R, W>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+        role: Role,
+        expected: Option<NodeFingerprint>,
+    ) -> Result<NodeFingerprint>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let our_nonce = *uuid::Uuid::new_v4().as_bytes();
+        let announcement = self.sync.our_announcement();
+        let local = announcement.node.fingerprint;
+
+        write_frame(
+            writer,
+            &Frame::Hello {
+                announcement,
+                nonce: our_nonce,
+            },
+        )
+        .await?;
+
+        let (their_announcement, their_nonce) = match read_frame(reader).await? {
+            Frame::Hello {
+                announcement,
+                nonce,
+            } => (announcement, nonce),
+            other => {
+                return Err(Error::SyncFailed(format!(
+                    "Expected Hello, got {:?}",
+                    other
+                )))
+            }
+        };
+        let peer = their_announcement.node.fingerprint;
+
+        if peer == local {
+            return Err(Error::InvalidFingerprint(
+                "Refusing to connect to self".to_string(),
+            ));
+        }
+        if let Some(expected) = expected {
+            if peer != expected {
+                return Err(Error::InvalidFingerprint(format!(
+                    "Expected {}, peer presented {}",
+                    expected, peer
+                )));
+            }
+        }
+        if let Some(trusted) = &self.config.trusted_peers {
+            if !trusted.contains(&peer) {
+                return Err(Error::InvalidFingerprint(format!(
+                    "Untrusted peer {}",
+                    peer
+                )));
+            }
+        }
+
+        let transcript = match role {
+            Role::Initiator => Transcript {
+                nonces: [our_nonce, their_nonce],
+                fingerprints: [local, peer],
+            },
+            Role::Responder => Transcript {
+                nonces: [their_nonce, our_nonce],
+                fingerprints: [peer, local],
+            },
+        };
+        let key = &self.config.network_key;
+
+        // The responder proves itself only once the initiator has, so a
+        // relay can't get a proof out of it to use elsewhere
+        match role {
+            Role::Initiator => {
+                let proof = handshake_proof(key, Role::Initiator, &transcript);
+                write_frame(writer, &Frame::Auth { proof }).await?;
+                Self::check_proof(reader, handshake_proof(key, Role::Responder, &transcript), peer).await?;
+            }
+            Role::Responder => {
+                Self::check_proof(reader, handshake_proof(key, Role::Initiator, &transcript), peer).await?;
+                let proof = handshake_proof(key, Role::Responder, &transcript);
+                write_frame(writer, &Frame::Auth { proof }).await?;
+            }
+        }
+
+        self.sync.process_announcement(their_announcement);
+        Ok(peer)
+    }
+
+    /// Read the peer's Auth frame and compare it with the expected proof
+    async fn check_proof<R: AsyncRead + Unpin>(
+        reader: &mut R,
+        expected: [u8; 32],
+        peer: NodeFingerprint,
+    ) -> Result<()> {
+        match read_frame(reader).await? {
+            Frame::Auth { proof } if proof == expected => Ok(()),
+            Frame::Auth { .. } => Err(Error::InvalidFingerprint(format!(
+                "Peer {} failed authentication",
+                peer
+            ))),
+            other => Err(Error::SyncFailed(format!("Expected Auth, got {:?}", other))),
+        }
+    }
+
+    async fn handle_frame(&self, peer: NodeFingerprint, frame: Frame) -> Result<()> {
+        match frame {
+            Frame::Delta { delta, ttl } => {
+                if delta.from_node == self.fingerprint() {
+                    return Ok(()); // Our own delta came back around
+                }
+                if !self.sync.process_incoming(delta.clone()).await? {
+                    return Ok(()); // Already seen: do not merge or relay again
+                }
+
+                self.graph.merge_delta(delta.clone());
+
+                if ttl > 1 {
+                    self.send_to_peers(
+                        Frame::Delta {
+                            delta,
+                            ttl: ttl - 1,
+                        },
+                        Some(peer),
+                    )
+                    .await;
+                    self.sync.record_relayed();
+                }
+                Ok(())
+            }
+            Frame::Announce(announcement) => {
+                self.sync.process_announcement(announcement);
+                Ok(())
+            }
+            Frame::Hello { .. } | Frame::Auth { .. } => Err(Error::SyncFailed(
+                "Unexpected handshake frame after handshake".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concept::ConceptId;
+    use crate::node::AlexandriaNode;
+    use std::time::Duration;
+
+    fn node(name: &str, config: TransportConfig) -> TcpTransport {
+        let fp = NodeFingerprint::from_hardware(name, 4, 16, name);
+        let graph = AlexandriaGraph::with_defaults(fp);
+        let sync = SyncProtocol::new(AlexandriaNode::new(fp).with_name(name));
+        TcpTransport::new(graph, sync, config)
+    }
+
+    fn edge_view(graph: &AlexandriaGraph) -> Vec<(String, u32, u64)> {
+        let mut view: Vec<_> = graph
+            .all_concepts()
+            .iter()
+            .flat_map(|c| graph.edges_from(&c.id))
+            .map(|e| {
+                (
+                    format!("{}>{}", e.from, e.to),
+                    e.weight.to_bits(),
+                    e.use_count,
+                )
+            })
+            .collect();
+        view.sort();
+        view.dedup();
+        view
+    }
+
+    #[tokio::test]
+    async fn test_frame_roundtrip() {
+        let (mut a, mut b) = tokio::io::duplex(1024);
+        let fp = NodeFingerprint::from_hardware("x", 1, 1, "x");
+        write_frame(
+            &mut a,
+            &Frame::Delta {
+                delta: GraphDelta::new(fp),
+                ttl: 3,
+            },
+        )
+        .await
+        .unwrap();
+
+        match read_frame(&mut b).await.unwrap() {
+            Frame::Delta { delta, ttl } => {
+                assert_eq!(delta.from_node, fp);
+                assert_eq!(ttl, 3);
+            }
+            other => panic!("unexpected frame {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_wrong_network_key() {
+        let a = node("a", TransportConfig::new([1u8; 32]));
+        let b = node("b", TransportConfig::new([2u8; 32]));
+
+        let addr = a.listen().await.unwrap();
+        assert!(b.connect(addr).await.is_err());
+        assert!(b.peers().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_relayed_handshake() {
+        let a = node("a", TransportConfig::new([3u8; 32]));
+        let b = node("b", TransportConfig::new([3u8; 32]));
+        let a_addr = a.listen().await.unwrap();
+        let b_addr = b.listen().await.unwrap();
+
+        let hello = |frame| match frame {
+            Frame::Hello { nonce, .. } => nonce,
+            other => panic!("expected Hello, got {:?}", other),
+        };
+
+        // A keyless relay claims to be B towards A and A towards B, passing
+        // A's challenge on to B in the hope B answers it
+        let mut to_a = TcpStream::connect(a_addr).await.unwrap();
+        let announcement = b.sync().our_announcement();
+        write_frame(&mut to_a, &Frame::Hello { announcement, nonce: [9u8; 16] }).await.unwrap();
+        let a_nonce = hello(read_frame(&mut to_a).await.unwrap());
+
+        let mut to_b = TcpStream::connect(b_addr).await.unwrap();
+        let announcement = a.sync().our_announcement();
+        write_frame(&mut to_b, &Frame::Hello { announcement, nonce: a_nonce }).await.unwrap();
+        hello(read_frame(&mut to_b).await.unwrap());
+
+        // Neither listener proves anything before the dialer does
+        let wait = Duration::from_millis(200);
+        assert!(!matches!(tokio::time::timeout(wait, read_frame(&mut to_b)).await, Ok(Ok(_))));
+        assert!(!matches!(tokio::time::timeout(wait, read_frame(&mut to_a)).await, Ok(Ok(_))));
+
+        write_frame(&mut to_a, &Frame::Auth { proof: [0u8; 32] }).await.unwrap();
+        let mut delta = GraphDelta::new(b.fingerprint());
+        delta.new_concepts.push(ConceptId::from_concept("pwned"));
+        let _ = write_frame(&mut to_a, &Frame::Delta { delta, ttl: 1 }).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert!(a.peers().await.is_empty());
+        assert!(a.graph().get_concept(&ConceptId::from_concept("pwned")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unexpected_fingerprint() {
+        let a = node("a", TransportConfig::new([1u8; 32]));
+        let b = node("b", TransportConfig::new([1u8; 32]));
+        let addr = a.listen().await.unwrap();
+
+        let wrong = NodeFingerprint::from_hardware("c", 4, 16, "c");
+        assert!(b.connect_expecting(addr, Some(wrong)).await.is_err());
+        assert_eq!(
+            b.connect_expecting(addr, Some(a.fingerprint()))
+                .await
+                .unwrap(),
+            a.fingerprint()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_full_peer_queue_does_not_block() {
+        let a = node("a", TransportConfig::new([1u8; 32]));
+        let slow = NodeFingerprint::from_hardware("slow", 4, 16, "slow");
+        let (tx, mut rx) = mpsc::channel::<Frame>(1);
+        a.peers.write().await.insert(slow, tx);
+
+        for _ in 0..3 {
+            tokio::time::timeout(Duration::from_secs(1), a.announce())
+                .await
+                .expect("announce blocked on a full peer queue");
+        }
+        // The peer map stays writable, so connections can still register
+        assert!(a.peers.try_write().is_ok());
+        assert!(matches!(rx.try_recv(), Ok(Frame::Announce(_))));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_empty_publish_leaves_no_sequence_gap() {
+        let a = node("a", TransportConfig::new([7u8; 32]));
+        let b = node("b", TransportConfig::new([7u8; 32]));
+        let addr = b.listen().await.unwrap();
+        a.connect(addr).await.unwrap();
+        let a_fp = NodeFingerprint::from_hardware("a", 4, 16, "a");
+
+        a.graph().record_query("x");
+        assert_eq!(a.publish().await.unwrap(), 1);
+        assert_eq!(a.publish().await.unwrap(), 0);
+        a.graph().record_query("y");
+        assert_eq!(a.publish().await.unwrap(), 1);
+        assert_eq!(a.graph().clock().get(&a_fp), 2);
+
+        for _ in 0..100 {
+            if b.graph().clock().get(&a_fp) == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(b.graph().clock().get(&a_fp), 2);
+        assert!(b.graph().missing_sequences(&a_fp).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_gossip_converges_across_line_topology() {
+        let names = ["berlin", "tokyo", "austin", "lagos"];
+        let nodes: Vec<TcpTransport> = names
+            .iter()
+            .map(|n| node(n, TransportConfig::new([7u8; 32])))
+            .collect();
+
+        let mut addrs = Vec::new();
+        for n in &nodes {
+            addrs.push(n.listen().await.unwrap());
+        }
+
+        // berlin ─ tokyo ─ austin ─ lagos: berlin and lagos only meet via relay
+        for i in 0..nodes.len() - 1 {
+            nodes[i].connect(addrs[i + 1]).await.unwrap();
+        }
+
+        for (i, n) in nodes.iter().enumerate() {
+            n.graph().record_query("encryption");
+            n.graph().record_query(names[i]);
+            n.graph().record_query("security");
+            n.publish().await.unwrap();
+        }
+
+        let expected_concepts = 2 + names.len();
+        let mut converged = false;
+        for _ in 0..100 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let views: Vec<_> = nodes.iter().map(|n| edge_view(n.graph())).collect();
+            if nodes
+                .iter()
+                .all(|n| n.graph().concept_count() == expected_concepts)
+                && views.windows(2).all(|w| w[0] == w[1])
+            {
+                converged = true;
+                break;
+            }
+        }
+        assert!(converged, "graphs did not converge over TCP gossip");
+
+        let encryption = ConceptId::from_concept("encryption");
+        let security = ConceptId::from_concept("security");
+        let lagos = &nodes[3];
+        assert!(lagos
+            .graph()
+            .get_edge(&encryption, &ConceptId::from_concept("berlin"))
+            .is_some());
+        assert!(lagos.graph().get_edge(&encryption, &security).is_none());
+        assert!(nodes[1].sync().stats().deltas_relayed > 0);
+        assert_eq!(nodes[0].sync().known_nodes().len(), 1);
+    }
+}
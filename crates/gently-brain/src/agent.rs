@@ -28,7 +28,14 @@
 //! Failure → pattern → LoRA → new SVG hash
 //! Fork → your version → your hash
 //! ```
+//!
+//! The brain travels inside the SVG as a base64 data URI. `run` extracts
+//! it, checks it against the brain hash and executes it in a `WasmSandbox`;
+//! every run is recorded so `evolve` can learn from failures.
 
+use crate::wasm::{AgentAction, RunStatus, WasmLimits, WasmSandbox};
+use crate::{Error, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use gently_core::{Hash, Kind, Blob, Manifest, BlobStore, TAG_PARENT, TAG_NEXT, TAG_CODE, TAG_WEIGHTS};
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
@@ -41,6 +48,7 @@ pub const TAG_LORA: u16 = 0x0603;
 pub const TAG_OBSERVATION: u16 = 0x0604;
 pub const TAG_GENERATION: u16 = 0x0605;
 pub const TAG_MERGED_FROM: u16 = 0x0606;
+pub const TAG_RUN: u16 = 0x0607;
 
 /// Agent identity
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +74,34 @@ pub struct AgentRuntime {
     store: BlobStore,
     agents: HashMap<Hash, Agent>,
     observations: Vec<Observation>,
+    sandbox: WasmSandbox,
+    runs: Vec<RunOutcome>,
+    /// Agent → latest memory blob written by its brain
+    memory: HashMap<Hash, Hash>,
+}
+
+/// Recorded outcome of one brain run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunOutcome {
+    pub agent: Hash,
+    pub input: Vec<f32>,
+    pub status: RunStatus,
+    pub fuel_used: u64,
+    pub actions: Vec<AgentAction>,
+    /// Memory blobs written during the run
+    pub memory: Vec<Hash>,
+    pub timestamp: u64,
+}
+
+impl RunOutcome {
+    pub fn failed(&self) -> bool {
+        !self.status.is_ok()
+    }
+
+    /// Action values, the brain's output vector
+    pub fn output(&self) -> Vec<f32> {
+        self.actions.iter().map(|a| a.value).collect()
+    }
 }
 
 /// One agent observing another
@@ -81,10 +117,18 @@ pub struct Observation {
 
 impl AgentRuntime {
     pub fn new() -> Self {
+        Self::with_limits(WasmLimits::default())
+    }
+
+    /// Runtime whose brains run under `limits`
+    pub fn with_limits(limits: WasmLimits) -> Self {
         Self {
             store: BlobStore::new(),
             agents: HashMap::new(),
             observations: Vec::new(),
+            sandbox: WasmSandbox::new(limits),
+            runs: Vec::new(),
+            memory: HashMap::new(),
         }
     }
 
@@ -107,7 +151,7 @@ impl AgentRuntime {
         });
 
         // Generate SVG
-        let svg = self.generate_svg(&meta, &wasm_hash, &lora_hash);
+        let svg = self.generate_svg(&meta, &wasm, &wasm_hash, &lora_hash);
         let svg_bytes = svg.as_bytes().to_vec();
 
         // Build agent manifest
@@ -156,7 +200,7 @@ impl AgentRuntime {
         let wasm_hash = self.store.put(Blob::new(Kind::Wasm, parent.wasm.clone()));
 
         // Generate new SVG (different identity)
-        let svg = self.generate_svg(&meta, &wasm_hash, &parent.lora_chain);
+        let svg = self.generate_svg(&meta, &parent.wasm, &wasm_hash, &parent.lora_chain);
         let svg_bytes = svg.as_bytes().to_vec();
 
         let mut manifest = Manifest::new();
@@ -185,15 +229,78 @@ impl AgentRuntime {
         Some(agent_hash)
     }
 
+    /// Run an agent's brain on `input` in the sandbox
+    ///
+    /// The module is taken from the agent's SVG, not from `Agent::wasm`, so
+    /// an SVG that lost or altered its brain fails here. Memory blobs the
+    /// brain writes are stored and the last one is offered to its next run.
+    pub fn run(&mut self, agent_hash: &Hash, input: &[f32]) -> Option<RunOutcome> {
+        let agent = self.agents.get(agent_hash)?;
+        let result = extract_wasm(&agent.svg).map(|wasm| {
+            let memory = self.memory.get(agent_hash)
+                .and_then(|h| self.store.get(h))
+                .map(|b| b.data.clone())
+                .unwrap_or_default();
+            self.sandbox.run(&wasm, input, &memory)
+        });
+
+        let (status, fuel_used, actions, writes) = match result {
+            Ok(run) => (run.status, run.fuel_used, run.actions, run.writes),
+            Err(e) => (RunStatus::Invalid(e.to_string()), 0, Vec::new(), Vec::new()),
+        };
+
+        let memory: Vec<Hash> = writes.into_iter()
+            .map(|data| self.store.put(Blob::new(Kind::Raw, data)))
+            .collect();
+        if let Some(last) = memory.last() {
+            self.memory.insert(*agent_hash, *last);
+        }
+
+        let outcome = RunOutcome {
+            agent: *agent_hash,
+            input: input.to_vec(),
+            status,
+            fuel_used,
+            actions,
+            memory,
+            timestamp: now(),
+        };
+
+        // Record: agent → outcome
+        let outcome_hash = self.store.put(Blob::new(Kind::Json, serde_json::to_vec(&outcome).unwrap()));
+        let mut record = Manifest::new();
+        record.add(TAG_PARENT, *agent_hash);
+        record.add(TAG_RUN, outcome_hash);
+        self.store.put(record.to_blob());
+
+        self.runs.push(outcome.clone());
+        Some(outcome)
+    }
+
+    /// Recorded runs of an agent, oldest first
+    pub fn runs(&self, agent_hash: &Hash) -> Vec<&RunOutcome> {
+        self.runs.iter().filter(|r| r.agent == *agent_hash).collect()
+    }
+
+    /// Recorded runs of an agent that did not end with `RunStatus::Ok`
+    pub fn failures(&self, agent_hash: &Hash) -> Vec<&RunOutcome> {
+        self.runs.iter().filter(|r| r.agent == *agent_hash && r.failed()).collect()
+    }
+
+    /// Latest memory blob written by an agent's brain
+    pub fn memory(&self, agent_hash: &Hash) -> Option<&[u8]> {
+        let hash = self.memory.get(agent_hash)?;
+        self.store.get(hash).map(|b| b.data.as_slice())
+    }
+
     /// One agent observes another
     pub fn observe(&mut self, observer: &Hash, observed: &Hash, input: Vec<f32>) -> Option<Observation> {
         let _obs_agent = self.agents.get(observer)?;
         let _tgt_agent = self.agents.get(observed)?;
 
-        // Observer runs inference on observed's output
-        // (placeholder - real impl would run WASM)
-        let output = self.mock_infer(observed, &input);
-        let expected = self.mock_infer(observer, &input);
+        // Both brains run on the same input; failed runs output nothing
+        let output = self.run(observed, &input)?.output();
+        let expected = self.run(observer, &input)?.output();
 
         let loss = self.compute_loss(&output, &expected);
 
@@ -245,7 +352,7 @@ impl AgentRuntime {
         // Merge LoRA chains
         let merged_lora = self.merge_lora(&a_lora, &b_lora);
 
-        let svg = self.generate_svg(&meta, &wasm_hash, &merged_lora);
+        let svg = self.generate_svg(&meta, &merged_wasm, &wasm_hash, &merged_lora);
         let svg_bytes = svg.as_bytes().to_vec();
 
         let mut manifest = Manifest::new();
@@ -280,11 +387,23 @@ impl AgentRuntime {
         let agent = self.agents.get(agent_hash)?;
 
         // Collect observations where this agent was observer
-        let obs: Vec<_> = self.observations.iter()
+        let mut obs: Vec<_> = self.observations.iter()
             .filter(|o| o.observer == *agent_hash)
             .cloned()
             .collect();
 
+        // Failed runs are observations with nothing produced
+        obs.extend(self.runs.iter()
+            .filter(|r| r.agent == *agent_hash && r.failed())
+            .map(|r| Observation {
+                observer: *agent_hash,
+                observed: *agent_hash,
+                input: r.input.clone(),
+                output: Vec::new(),
+                loss: 1.0,
+                timestamp: r.timestamp,
+            }));
+
         if obs.is_empty() {
             return None;
         }
@@ -310,7 +429,7 @@ impl AgentRuntime {
         };
 
         let wasm_hash = self.store.put(Blob::new(Kind::Wasm, agent.wasm.clone()));
-        let svg = self.generate_svg(&meta, &wasm_hash, &new_chain);
+        let svg = self.generate_svg(&meta, &agent.wasm, &wasm_hash, &new_chain);
         let svg_bytes = svg.as_bytes().to_vec();
 
         let mut manifest = Manifest::new();
@@ -337,8 +456,9 @@ impl AgentRuntime {
 
         self.agents.insert(new_hash, new_agent);
 
-        // Clear processed observations
+        // Clear processed observations and failures
         self.observations.retain(|o| o.observer != *agent_hash);
+        self.runs.retain(|r| r.agent != *agent_hash || !r.failed());
 
         Some(new_hash)
     }
@@ -365,8 +485,9 @@ impl AgentRuntime {
 
     // === Internal ===
 
-    fn generate_svg(&self, meta: &AgentMeta, wasm_hash: &Hash, lora_hash: &Hash) -> String {
+    fn generate_svg(&self, meta: &AgentMeta, wasm: &[u8], wasm_hash: &Hash, lora_hash: &Hash) -> String {
         let wasm_hex = hex::encode(wasm_hash);
+        let wasm_b64 = BASE64.encode(wasm);
         let lora_hex = hex::encode(lora_hash);
         let color = self.hash_to_color(wasm_hash);
 
@@ -398,7 +519,7 @@ impl AgentRuntime {
   <!-- BRAIN: What I do (WASM embedded) -->
   <foreignObject x="10" y="240" width="380" height="50">
     <div xmlns="http://www.w3.org/1999/xhtml" style="display:none">
-      <script type="application/wasm" data-hash="{}"></script>
+      <script type="application/wasm" data-hash="{}" src="data:application/wasm;base64,{}"></script>
     </div>
   </foreignObject>
 
@@ -419,6 +540,7 @@ impl AgentRuntime {
             meta.generation,
             color,
             wasm_hex,
+            wasm_b64,
             meta.name,
             meta.generation,
             meta.born,
@@ -432,14 +554,11 @@ impl AgentRuntime {
         format!("#{:02x}{:02x}{:02x}", hash[0], hash[1], hash[2])
     }
 
-    fn mock_infer(&self, _agent: &Hash, input: &[f32]) -> Vec<f32> {
-        // Placeholder
-        input.iter().map(|x| x * 0.9).collect()
-    }
-
     fn compute_loss(&self, a: &[f32], b: &[f32]) -> f32 {
-        a.iter().zip(b.iter())
-            .map(|(x, y)| (x - y).powi(2))
+        // Missing outputs count as zero
+        (0..a.len().max(b.len()))
+            .map(|i| a.get(i).unwrap_or(&0.0) - b.get(i).unwrap_or(&0.0))
+            .map(|d| d.powi(2))
             .sum::<f32>()
             .sqrt()
     }
@@ -466,6 +585,35 @@ impl Default for AgentRuntime {
     fn default() -> Self { Self::new() }
 }
 
+/// Brain module embedded in an agent SVG, verified against its `data-hash`
+pub fn extract_wasm(svg: &str) -> Result<Vec<u8>> {
+    let start = svg.find(r#"type="application/wasm""#)
+        .ok_or_else(|| Error::InvalidModel("SVG has no WASM brain".into()))?;
+    let tag_start = svg[..start].rfind('<').unwrap_or(0);
+    let tag_end = svg[start..].find('>').map(|i| start + i)
+        .ok_or_else(|| Error::InvalidModel("unterminated WASM tag".into()))?;
+    let tag = &svg[tag_start..tag_end];
+
+    let attr = |name: &str| {
+        let key = format!("{}=\"", name);
+        let at = tag.find(&key)? + key.len();
+        tag[at..].find('"').map(|end| &tag[at..at + end])
+    };
+
+    let data = attr("src")
+        .and_then(|src| src.strip_prefix("data:application/wasm;base64,"))
+        .ok_or_else(|| Error::InvalidModel("WASM brain is not embedded".into()))?;
+    let wasm = BASE64.decode(data)
+        .map_err(|e| Error::InvalidModel(format!("WASM brain: {}", e)))?;
+
+    if let Some(expected) = attr("data-hash") {
+        if hex::encode(Blob::new(Kind::Wasm, wasm.clone()).hash) != expected {
+            return Err(Error::InvalidModel("WASM brain does not match its hash".into()));
+        }
+    }
+    Ok(wasm)
+}
+
 fn merge_traits(a: &[String], b: &[String]) -> Vec<String> {
     let mut traits: Vec<_> = a.iter().chain(b.iter()).cloned().collect();
     traits.sort();
@@ -526,6 +674,78 @@ mod tests {
         assert!(evolved.meta.parent.is_some());
     }
 
+    /// Emits input * 2 and counts its runs in memory
+    fn doubler() -> Vec<u8> {
+        wat::parse_str(r#"(module
+            (import "gently" "input_len" (func $len (result i32)))
+            (import "gently" "input_get" (func $get (param i32) (result f32)))
+            (import "gently" "emit" (func $emit (param i32 f32)))
+            (import "gently" "memory_read" (func $read (param i32 i32) (result i32)))
+            (import "gently" "memory_write" (func $write (param i32 i32)))
+            (memory (export "memory") 1)
+            (func (export "run") (result i32) (local $i i32)
+                (drop (call $read (i32.const 0) (i32.const 1)))
+                (i32.store8 (i32.const 0) (i32.add (i32.load8_u (i32.const 0)) (i32.const 1)))
+                (call $write (i32.const 0) (i32.const 1))
+                (block $done (loop $next
+                    (br_if $done (i32.ge_s (local.get $i) (call $len)))
+                    (call $emit (i32.const 0) (f32.mul (call $get (local.get $i)) (f32.const 2)))
+                    (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                    (br $next)))
+                (i32.const 0)))"#).unwrap()
+    }
+
+    #[test]
+    fn test_run_brain_from_svg() {
+        let mut runtime = AgentRuntime::new();
+        let alice = runtime.spawn("alice", doubler(), None);
+        assert_eq!(extract_wasm(runtime.get_svg(&alice).unwrap()).unwrap(), doubler());
+
+        let first = runtime.run(&alice, &[1.0, 3.0]).unwrap();
+        assert_eq!(first.status, RunStatus::Ok);
+        assert_eq!(first.output(), vec![2.0, 6.0]);
+        runtime.run(&alice, &[]).unwrap();
+        assert_eq!(runtime.memory(&alice), Some(&[2u8][..]));
+        assert_eq!(runtime.runs(&alice).len(), 2);
+
+        // Forks carry the same brain but their own memory
+        let bob = runtime.fork(&alice, "bob").unwrap();
+        runtime.run(&bob, &[]).unwrap();
+        assert_eq!(runtime.memory(&bob), Some(&[1u8][..]));
+    }
+
+    #[test]
+    fn test_tampered_svg_rejected() {
+        let mut runtime = AgentRuntime::new();
+        let alice = runtime.spawn("alice", doubler(), None);
+        let svg = runtime.get_svg(&alice).unwrap().to_string();
+
+        let b64 = BASE64.encode(doubler());
+        let forged = svg.replace(&b64, &BASE64.encode(b"\0asm\x01\0\0\0"));
+        assert!(extract_wasm(&forged).is_err());
+        assert!(extract_wasm("<svg></svg>").is_err());
+
+        runtime.agents.get_mut(&alice).unwrap().svg = forged;
+        let run = runtime.run(&alice, &[]).unwrap();
+        assert!(matches!(run.status, RunStatus::Invalid(ref e) if e.contains("hash")));
+    }
+
+    #[test]
+    fn test_failures_drive_evolution() {
+        let mut runtime = AgentRuntime::new();
+        let good = runtime.spawn("good", doubler(), None);
+        let broken = runtime.spawn("broken", b"not wasm".to_vec(), None);
+
+        runtime.run(&good, &[1.0]);
+        assert!(runtime.evolve(&good).is_none());
+
+        runtime.run(&broken, &[1.0]);
+        assert_eq!(runtime.failures(&broken).len(), 1);
+        let next = runtime.evolve(&broken).unwrap();
+        assert_eq!(runtime.get(&next).unwrap().meta.generation, 1);
+        assert!(runtime.failures(&broken).is_empty());
+    }
+
     #[test]
     fn test_merge_agents() {
         let mut runtime = AgentRuntime::new();
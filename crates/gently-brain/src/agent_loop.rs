@@ -0,0 +1,620 @@
+//! Multi-step agent loop
+//!
+//! Drives a model through tool rounds until it answers without calling a
+//! tool, or a budget runs out:
+//!
+//! ```text
+//! task ──► model ──► text only ─────────────────────────► Completed
+//!            ▲   └─► tool_use ──► McpToolRegistry ──┐
+//!            │                └─► SkillRegistry ────┤
+//!            └──────── tool_result ◄── scratchpad ◄─┘
+//!
+//! stops early: MaxSteps │ TokenBudget │ Cancelled
+//! ```
+//!
+//! Tools go through the registry's permission gate, or through a
+//! [`ToolRunner`] such as the orchestrator when one is set; skills are exposed to
+//! the model as `skill_<name>` tools and gated by the policy's `skills`
+//! rule. Every step emits an [`AgentEvent`] for the TUI.
+
+use crate::claude::{ToolResultInput, ToolUseResponse};
+use crate::mcp::{McpToolRegistry, ToolResult};
+use crate::skills::{ParamType, SkillRegistry};
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// Prefix that marks a skill among the tools offered to the model
+pub const SKILL_PREFIX: &str = "skill_";
+
+const PLAN_PROMPT: &str = "Before calling any tool, outline your plan as a numbered list.";
+
+/// One entry of the conversation the loop builds up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "role", rename_all = "snake_case")]
+pub enum Turn {
+    User { text: String },
+    Assistant { text: String, tool_uses: Vec<ToolUseResponse> },
+    ToolResults { results: Vec<ToolResultInput> },
+}
+
+/// What the model said in one step
+#[derive(Debug, Clone, Default)]
+pub struct ModelTurn {
+    pub text: String,
+    pub tool_uses: Vec<ToolUseResponse>,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+}
+
+/// A model that can take part in tool rounds
+pub trait AgentModel {
+    /// Answer the transcript so far, optionally calling tools
+    fn step(&mut self, transcript: &[Turn], tools: &[serde_json::Value]) -> Result<ModelTurn>;
+}
+
+/// Something that runs the loop's tool calls, gate and audit included
+pub trait ToolRunner: Send + Sync {
+    fn run_tool(&self, name: &str, input: &serde_json::Value) -> Result<ToolResult>;
+}
+
+impl ToolRunner for McpToolRegistry {
+    fn run_tool(&self, name: &str, input: &serde_json::Value) -> Result<ToolResult> {
+        self.execute(name, input)
+    }
+}
+
+/// Budgets and behaviour of a run
+#[derive(Debug, Clone)]
+pub struct AgentConfig {
+    /// Model calls per run
+    pub max_steps: usize,
+    /// Input plus output tokens per run (0 = unlimited)
+    pub max_tokens: usize,
+    /// Ask the model for a numbered plan first
+    pub plan_first: bool,
+    /// Tool output longer than this is truncated before it goes back to the model
+    pub max_result_chars: usize,
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        Self {
+            max_steps: 8,
+            max_tokens: 0,
+            plan_first: true,
+            max_result_chars: 8000,
+        }
+    }
+}
+
+/// Why a run stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// Model answered without calling a tool
+    Completed,
+    MaxSteps,
+    TokenBudget,
+    Cancelled,
+}
+
+/// Progress of a run, for the TUI
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AgentEvent {
+    Started { task: String },
+    Plan { steps: Vec<String> },
+    Step { step: usize },
+    Thought { step: usize, text: String },
+    ToolCall { step: usize, id: String, name: String, input: serde_json::Value },
+    ToolResult { step: usize, id: String, name: String, success: bool, output: String },
+    Usage { step: usize, tokens: usize },
+    Finished { stop: StopReason, steps: usize, tokens: usize },
+}
+
+/// One intermediate result kept by the loop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScratchEntry {
+    pub step: usize,
+    pub tool: String,
+    pub input: serde_json::Value,
+    pub output: String,
+    pub success: bool,
+}
+
+/// Cancels a running loop from another thread
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl CancelHandle {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Result of a run
+#[derive(Debug, Clone)]
+pub struct AgentRun {
+    /// Last text from the model
+    pub answer: String,
+    pub stop: StopReason,
+    pub steps: usize,
+    pub tokens: usize,
+    pub plan: Vec<String>,
+    pub scratchpad: Vec<ScratchEntry>,
+    pub transcript: Vec<Turn>,
+}
+
+/// Tool-using loop over an [`AgentModel`]
+pub struct AgentLoop {
+    tools: Arc<McpToolRegistry>,
+    runner: Option<Arc<dyn ToolRunner>>,
+    skills: Option<Arc<SkillRegistry>>,
+    config: AgentConfig,
+    cancel: CancelHandle,
+    event_tx: mpsc::UnboundedSender<AgentEvent>,
+    event_rx: Arc<Mutex<mpsc::UnboundedReceiver<AgentEvent>>>,
+}
+
+impl AgentLoop {
+    pub fn new(tools: Arc<McpToolRegistry>) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            tools,
+            runner: None,
+            skills: None,
+            config: AgentConfig::default(),
+            cancel: CancelHandle::default(),
+            event_tx: tx,
+            event_rx: Arc::new(Mutex::new(rx)),
+        }
+    }
+
+    /// Send tool calls to `runner` instead of the registry's own executors
+    pub fn with_runner(mut self, runner: Arc<dyn ToolRunner>) -> Self {
+        self.runner = Some(runner);
+        self
+    }
+
+    pub fn with_skills(mut self, skills: Arc<SkillRegistry>) -> Self {
+        self.skills = Some(skills);
+        self
+    }
+
+    pub fn with_config(mut self, config: AgentConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &AgentConfig {
+        &self.config
+    }
+
+    /// Handle that stops the current run before its next model or tool call
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Get event receiver
+    pub fn events(&self) -> Arc<Mutex<mpsc::UnboundedReceiver<AgentEvent>>> {
+        self.event_rx.clone()
+    }
+
+    /// Tool definitions offered to the model: registry tools plus enabled skills
+    pub fn tool_definitions(&self) -> Vec<serde_json::Value> {
+        let mut tools = self.tools.to_claude_tools();
+        if let Some(skills) = &self.skills {
+            for skill in skills.list().into_iter().filter(|s| s.enabled) {
+                let mut properties = serde_json::Map::new();
+                for p in &skill.parameters {
+                    let ty = match p.param_type {
+                        ParamType::Number => "number",
+                        ParamType::Boolean => "boolean",
+                        _ => "string",
+                    };
+                    properties.insert(p.name.clone(), serde_json::json!({
+                        "type": ty,
+                        "description": p.description,
+                    }));
+                }
+                let required: Vec<_> = skill.parameters.iter()
+                    .filter(|p| p.required)
+                    .map(|p| p.name.clone())
+                    .collect();
+                tools.push(serde_json::json!({
+                    "name": format!("{}{}", SKILL_PREFIX, skill.name),
+                    "description": skill.description,
+                    "input_schema": {
+                        "type": "object",
+                        "properties": properties,
+                        "required": required,
+                    }
+                }));
+            }
+        }
+        tools.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+        tools
+    }
+
+    /// Run `task` to completion or until a budget is hit
+    pub fn run(&self, model: &mut dyn AgentModel, task: &str) -> Result<AgentRun> {
+        self.cancel.reset();
+        let tools = self.tool_definitions();
+        let prompt = if self.config.plan_first {
+            format!("{}\n\n{}", task, PLAN_PROMPT)
+        } else {
+            task.to_string()
+        };
+
+        let mut run = AgentRun {
+            answer: String::new(),
+            stop: StopReason::MaxSteps,
+            steps: 0,
+            tokens: 0,
+            plan: Vec::new(),
+            scratchpad: Vec::new(),
+            transcript: vec![Turn::User { text: prompt }],
+        };
+        self.emit(AgentEvent::Started { task: task.to_string() });
+
+        run.stop = loop {
+            if self.cancel.is_cancelled() {
+                break StopReason::Cancelled;
+            }
+            if run.steps >= self.config.max_steps {
+                break StopReason::MaxSteps;
+            }
+            run.steps += 1;
+            let step = run.steps;
+            self.emit(AgentEvent::Step { step });
+
+            let turn = model.step(&run.transcript, &tools)?;
+            run.tokens += turn.input_tokens + turn.output_tokens;
+            self.emit(AgentEvent::Usage { step, tokens: run.tokens });
+
+            if !turn.text.is_empty() {
+                if run.plan.is_empty() && self.config.plan_first {
+                    run.plan = parse_plan(&turn.text);
+                    if !run.plan.is_empty() {
+                        self.emit(AgentEvent::Plan { steps: run.plan.clone() });
+                    }
+                }
+                self.emit(AgentEvent::Thought { step, text: turn.text.clone() });
+                run.answer = turn.text.clone();
+            }
+
+            let calls = turn.tool_uses.clone();
+            run.transcript.push(Turn::Assistant { text: turn.text, tool_uses: turn.tool_uses });
+            if calls.is_empty() {
+                break StopReason::Completed;
+            }
+            // Over budget: don't run the calls the model just asked for, but
+            // answer each so the transcript stays a valid conversation
+            if self.config.max_tokens > 0 && run.tokens >= self.config.max_tokens {
+                let results = calls.iter()
+                    .map(|call| ToolResultInput {
+                        tool_use_id: call.id.clone(),
+                        content: "not run: token budget exhausted".into(),
+                        is_error: true,
+                    })
+                    .collect();
+                run.transcript.push(Turn::ToolResults { results });
+                break StopReason::TokenBudget;
+            }
+
+            let mut results = Vec::new();
+            for call in &calls {
+                if self.cancel.is_cancelled() {
+                    results.push(ToolResultInput {
+                        tool_use_id: call.id.clone(),
+                        content: "cancelled".into(),
+                        is_error: true,
+                    });
+                    continue;
+                }
+                self.emit(AgentEvent::ToolCall {
+                    step,
+                    id: call.id.clone(),
+                    name: call.name.clone(),
+                    input: call.input.clone(),
+                });
+
+                let (success, output) = self.call(&call.name, &call.input);
+                let content = truncate(&output, self.config.max_result_chars);
+                self.emit(AgentEvent::ToolResult {
+                    step,
+                    id: call.id.clone(),
+                    name: call.name.clone(),
+                    success,
+                    output: content.clone(),
+                });
+                run.scratchpad.push(ScratchEntry {
+                    step,
+                    tool: call.name.clone(),
+                    input: call.input.clone(),
+                    output: content.clone(),
+                    success,
+                });
+                results.push(ToolResultInput { tool_use_id: call.id.clone(), content, is_error: !success });
+            }
+            run.transcript.push(Turn::ToolResults { results });
+        };
+
+        self.emit(AgentEvent::Finished { stop: run.stop, steps: run.steps, tokens: run.tokens });
+        Ok(run)
+    }
+
+    /// Run one tool or skill; failures become error results for the model
+    fn call(&self, name: &str, input: &serde_json::Value) -> (bool, String) {
+        let Some(skill) = name.strip_prefix(SKILL_PREFIX) else {
+            let result = match &self.runner {
+                Some(runner) => runner.run_tool(name, input),
+                None => self.tools.execute(name, input),
+            };
+            return match result {
+                Ok(r) => (r.success, r.output.to_string()),
+                Err(e) => (false, e.to_string()),
+            };
+        };
+
+        let Some(skills) = &self.skills else {
+            return (false, format!("Unknown skill: {}", skill));
+        };
+        let decision = match self.tools.authorize(name, input) {
+            Ok(d) => d,
+            Err(e) => return (false, e.to_string()),
+        };
+        let started = std::time::Instant::now();
+        let result = skills.execute(skill, &skill_params(input));
+        let outcome = match &result {
+            Ok(r) => (r.success, r.output.clone()),
+            Err(e) => (false, e.to_string()),
+        };
+        let audited = result.map(|r| ToolResult {
+            tool: name.to_string(),
+            success: r.success,
+            output: serde_json::Value::String(r.output),
+            side_effects: vec![],
+            learnings: r.learned.into_iter().map(|l| l.concept).collect(),
+        });
+        self.tools.record(name, input, decision, &audited, started);
+        outcome
+    }
+
+    fn emit(&self, event: AgentEvent) {
+        let _ = self.event_tx.send(event);
+    }
+}
+
+/// Skills take string parameters
+fn skill_params(input: &serde_json::Value) -> HashMap<String, String> {
+    input.as_object()
+        .map(|map| map.iter()
+            .map(|(k, v)| {
+                let value = match v {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                (k.clone(), value)
+            })
+            .collect())
+        .unwrap_or_default()
+}
+
+/// Numbered or bulleted lines of the model's first answer
+fn parse_plan(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let rest = line.trim_start_matches(|c: char| c.is_ascii_digit());
+            let item = if rest.len() < line.len() {
+                rest.strip_prefix('.').or_else(|| rest.strip_prefix(')'))?
+            } else {
+                rest.strip_prefix("- ").or_else(|| rest.strip_prefix("* "))?
+            };
+            let item = item.trim();
+            (!item.is_empty()).then(|| item.to_string())
+        })
+        .collect()
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.len() <= max {
+        return s.to_string();
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}... [truncated {} bytes]", &s[..end], s.len() - end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::ToolExecutor;
+    use crate::permissions::ToolPolicy;
+    use std::collections::VecDeque;
+
+    /// Replays canned turns and records what it was shown
+    struct Scripted {
+        turns: VecDeque<ModelTurn>,
+        seen: Vec<usize>,
+        on_step: Option<Box<dyn FnMut(usize)>>,
+    }
+
+    impl Scripted {
+        fn new(turns: Vec<ModelTurn>) -> Self {
+            Self { turns: turns.into(), seen: Vec::new(), on_step: None }
+        }
+    }
+
+    impl AgentModel for Scripted {
+        fn step(&mut self, transcript: &[Turn], _tools: &[serde_json::Value]) -> Result<ModelTurn> {
+            self.seen.push(transcript.len());
+            if let Some(f) = &mut self.on_step {
+                f(self.seen.len());
+            }
+            Ok(self.turns.pop_front().unwrap_or_else(|| text("done")))
+        }
+    }
+
+    fn text(t: &str) -> ModelTurn {
+        ModelTurn { text: t.into(), input_tokens: 10, output_tokens: 5, ..Default::default() }
+    }
+
+    fn call(id: &str, name: &str, input: serde_json::Value) -> ModelTurn {
+        ModelTurn {
+            text: String::new(),
+            tool_uses: vec![ToolUseResponse { id: id.into(), name: name.into(), input }],
+            input_tokens: 100,
+            output_tokens: 20,
+        }
+    }
+
+    struct Hash;
+
+    impl ToolExecutor for Hash {
+        fn execute(&self, input: &serde_json::Value) -> Result<ToolResult> {
+            Ok(ToolResult {
+                tool: "hash_compute".into(),
+                success: true,
+                output: serde_json::json!({"hash": format!("h({})", input["input"].as_str().unwrap())}),
+                side_effects: vec![],
+                learnings: vec![],
+            })
+        }
+
+        fn name(&self) -> &str { "hash_compute" }
+    }
+
+    fn registry() -> Arc<McpToolRegistry> {
+        let mut registry = McpToolRegistry::new();
+        registry.register_executor(Hash);
+        Arc::new(registry)
+    }
+
+    #[test]
+    fn test_runs_tools_until_answer() {
+        let agent = AgentLoop::new(registry()).with_skills(Arc::new(SkillRegistry::new()));
+        let mut model = Scripted::new(vec![
+            ModelTurn {
+                text: "Plan:\n1. hash the input\n2. report it".into(),
+                ..call("t1", "hash_compute", serde_json::json!({"input": "abc", "algorithm": "md5"}))
+            },
+            call("t2", "port_scan", serde_json::json!({"target": "10.0.0.1"})),
+            call("t3", "skill_learn", serde_json::json!({"concept": "hashing"})),
+            text("The hash is h(abc)."),
+        ]);
+
+        let run = agent.run(&mut model, "hash abc").unwrap();
+        assert_eq!(run.stop, StopReason::Completed);
+        assert_eq!(run.answer, "The hash is h(abc).");
+        assert_eq!(run.plan, vec!["hash the input", "report it"]);
+        assert_eq!(run.steps, 4);
+        assert_eq!(run.tokens, 3 * 120 + 15);
+        assert_eq!(model.seen, vec![1, 3, 5, 7]);
+
+        // Declined tool is reported back to the model, not fatal
+        assert!(run.scratchpad[0].success && run.scratchpad[0].output.contains("h(abc)"));
+        assert!(!run.scratchpad[1].success && run.scratchpad[1].output.contains("not confirmed"));
+        assert!(run.scratchpad[2].success, "{}", run.scratchpad[2].output);
+        match &run.transcript[4] {
+            Turn::ToolResults { results } => assert!(results[0].is_error),
+            other => panic!("unexpected turn {:?}", other),
+        }
+
+        let tools: Vec<_> = agent.tools.audit_log().into_iter().map(|r| r.tool).collect();
+        assert_eq!(tools, vec!["hash_compute", "port_scan", "skill_learn"]);
+
+        let rx = agent.events();
+        let mut rx = rx.lock().unwrap();
+        let mut names = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            names.push(serde_json::to_value(&event).unwrap()["event"].as_str().unwrap().to_string());
+        }
+        assert_eq!(names.first().map(String::as_str), Some("started"));
+        assert!(names.contains(&"plan".to_string()));
+        assert_eq!(names.iter().filter(|n| *n == "tool_result").count(), 3);
+        assert_eq!(names.last().map(String::as_str), Some("finished"));
+    }
+
+    #[test]
+    fn test_budgets() {
+        let looping = || Scripted::new((0..10)
+            .map(|i| call(&format!("t{}", i), "hash_compute", serde_json::json!({"input": "x", "algorithm": "md5"})))
+            .collect());
+
+        let agent = AgentLoop::new(registry()).with_config(AgentConfig { max_steps: 3, ..Default::default() });
+        let run = agent.run(&mut looping(), "spin").unwrap();
+        assert_eq!((run.stop, run.steps, run.scratchpad.len()), (StopReason::MaxSteps, 3, 3));
+
+        let agent = AgentLoop::new(registry()).with_config(AgentConfig { max_tokens: 250, ..Default::default() });
+        let run = agent.run(&mut looping(), "spin").unwrap();
+        assert_eq!((run.stop, run.steps, run.tokens), (StopReason::TokenBudget, 3, 360));
+        assert_eq!(run.scratchpad.len(), 2);
+        assert_eq!(agent.tools.audit_log().len(), 2);
+        // The unrun calls are still answered
+        match run.transcript.last() {
+            Some(Turn::ToolResults { results }) => {
+                assert_eq!(results.len(), 1);
+                assert_eq!(results[0].tool_use_id, "t2");
+                assert!(results[0].is_error);
+            }
+            other => panic!("expected tool results, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_skills_follow_policy() {
+        let registry = registry();
+        registry.set_policy(ToolPolicy::cautious());
+        let agent = AgentLoop::new(registry).with_skills(Arc::new(SkillRegistry::new()));
+
+        let mut model = Scripted::new(vec![call("t1", "skill_learn", serde_json::json!({"concept": "x"}))]);
+        let run = agent.run(&mut model, "learn").unwrap();
+        assert!(!run.scratchpad[0].success);
+        assert_eq!(agent.tools.audit_log()[0].decision, crate::permissions::Decision::Declined);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let registry = registry();
+        registry.set_policy(ToolPolicy::default());
+        let agent = AgentLoop::new(registry);
+        let cancel = agent.cancel_handle();
+
+        let mut model = Scripted::new((0..5)
+            .map(|i| call(&format!("t{}", i), "hash_compute", serde_json::json!({"input": "x", "algorithm": "md5"})))
+            .collect());
+        model.on_step = Some(Box::new(move |n| if n == 2 { cancel.cancel() }));
+
+        let run = agent.run(&mut model, "spin").unwrap();
+        assert_eq!(run.stop, StopReason::Cancelled);
+        assert_eq!(run.steps, 2);
+        assert_eq!(run.scratchpad.len(), 1);
+
+        // A new run starts uncancelled
+        let run = agent.run(&mut Scripted::new(vec![text("ok")]), "again").unwrap();
+        assert_eq!(run.stop, StopReason::Completed);
+    }
+
+    #[test]
+    fn test_parse_plan_and_truncate() {
+        assert_eq!(parse_plan("I will:\n1) look\n- check\n2026 was fine\n3. done"), vec!["look", "check", "done"]);
+        assert_eq!(truncate("héllo", 2), "h... [truncated 5 bytes]");
+        assert_eq!(truncate("abc", 8), "abc");
+    }
+}
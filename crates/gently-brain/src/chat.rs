@@ -0,0 +1,293 @@
+//! Chat backends
+//!
+//! The assistant types talk to a [`ChatBackend`] rather than a specific API:
+//!
+//! ```text
+//! ChatClient / GentlyAssistant / AgentLoop
+//!                 │ ChatRequest
+//!                 ▼
+//!           dyn ChatBackend ──► AnthropicBackend ──► api.anthropic.com
+//!                          └──► GatewayBackend   ──► gently_gateway::Gateway
+//!                                (gently-gateway)     filters, routing, audit
+//! ```
+//!
+//! A request carries plain history plus structured tool rounds; backends
+//! without native tool blocks can fall back to [`ChatRequest::plain_messages`].
+
+use crate::agent_loop::{ModelTurn, Turn};
+use crate::claude::{ClaudeModel, Message, ToolUseResponse};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+/// One model call
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatRequest {
+    pub system: Option<String>,
+    /// Earlier plain text turns
+    pub history: Vec<Message>,
+    /// Current exchange, including tool rounds
+    #[serde(default)]
+    pub turns: Vec<Turn>,
+    /// Tool definitions offered to the model (empty = no tools)
+    #[serde(default)]
+    pub tools: Vec<serde_json::Value>,
+    pub max_tokens: usize,
+}
+
+impl ChatRequest {
+    /// Whole conversation as plain text messages, tool rounds rendered inline
+    pub fn plain_messages(&self) -> Vec<Message> {
+        let mut messages = self.history.clone();
+        for turn in &self.turns {
+            match turn {
+                Turn::User { text } => messages.push(Message::user(text)),
+                Turn::Assistant { text, tool_uses } => {
+                    let mut content = text.clone();
+                    for t in tool_uses {
+                        if !content.is_empty() {
+                            content.push('\n');
+                        }
+                        content.push_str(&format!("[tool_use {} {} {}]", t.id, t.name, t.input));
+                    }
+                    messages.push(Message::assistant(&content));
+                }
+                Turn::ToolResults { results } => {
+                    let content = results.iter()
+                        .map(|r| {
+                            let kind = if r.is_error { "tool_error" } else { "tool_result" };
+                            format!("[{} {}] {}", kind, r.tool_use_id, r.content)
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    messages.push(Message::user(&content));
+                }
+            }
+        }
+        messages
+    }
+}
+
+/// Something that can answer a chat request
+pub trait ChatBackend: Send {
+    /// Backend identifier, e.g. "anthropic" or "gateway"
+    fn name(&self) -> &str;
+
+    /// Model the backend will ask for
+    fn model(&self) -> String;
+
+    fn set_model(&mut self, model: &str);
+
+    fn complete(&self, request: &ChatRequest) -> Result<ModelTurn>;
+}
+
+/// Direct Anthropic Messages API backend
+pub struct AnthropicBackend {
+    api_key: String,
+    model: String,
+}
+
+impl AnthropicBackend {
+    /// Key from ANTHROPIC_API_KEY
+    pub fn new() -> Result<Self> {
+        let api_key = env::var("ANTHROPIC_API_KEY")
+            .map_err(|_| Error::InferenceFailed(
+                "ANTHROPIC_API_KEY not set. Export your API key.".to_string()
+            ))?;
+        Ok(Self::with_key(&api_key))
+    }
+
+    pub fn with_key(api_key: &str) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+            model: ClaudeModel::Sonnet.api_name().to_string(),
+        }
+    }
+
+    pub fn model(mut self, model: ClaudeModel) -> Self {
+        self.model = model.api_name().to_string();
+        self
+    }
+}
+
+impl ChatBackend for AnthropicBackend {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn model(&self) -> String {
+        self.model.clone()
+    }
+
+    fn set_model(&mut self, model: &str) {
+        self.model = model.to_string();
+    }
+
+    fn complete(&self, request: &ChatRequest) -> Result<ModelTurn> {
+        let mut messages: Vec<serde_json::Value> = request.history.iter()
+            .map(|m| serde_json::json!({"role": m.role, "content": m.content}))
+            .collect();
+        messages.extend(request.turns.iter().map(turn_message));
+
+        let mut body = serde_json::json!({
+            "model": self.model,
+            "max_tokens": request.max_tokens,
+            "messages": messages,
+        });
+        if let Some(system) = &request.system {
+            body["system"] = serde_json::json!(system);
+        }
+        if !request.tools.is_empty() {
+            body["tools"] = serde_json::json!(request.tools);
+        }
+
+        let response = ureq::post("https://api.anthropic.com/v1/messages")
+            .set("x-api-key", &self.api_key)
+            .set("anthropic-version", "2023-06-01")
+            .set("content-type", "application/json")
+            .send_json(&body);
+
+        match response {
+            Ok(resp) => {
+                let body: serde_json::Value = resp.into_json()
+                    .map_err(|e| Error::InferenceFailed(format!("Parse error: {}", e)))?;
+                Ok(parse_turn(&body))
+            }
+            Err(ureq::Error::Status(code, resp)) => {
+                let body: serde_json::Value = resp.into_json().unwrap_or(serde_json::json!({}));
+                let default_msg = format!("HTTP {}", code);
+                let msg = body.get("error")
+                    .and_then(|e| e.get("message"))
+                    .and_then(|m| m.as_str())
+                    .unwrap_or(&default_msg);
+                Err(Error::InferenceFailed(msg.to_string()))
+            }
+            Err(e) => {
+                Err(Error::InferenceFailed(format!("Request failed: {}", e)))
+            }
+        }
+    }
+}
+
+/// Transcript entry in Messages API form, with tool_use/tool_result blocks
+fn turn_message(turn: &Turn) -> serde_json::Value {
+    match turn {
+        Turn::User { text } => serde_json::json!({"role": "user", "content": text}),
+        Turn::Assistant { text, tool_uses } => {
+            let mut blocks = Vec::new();
+            if !text.is_empty() {
+                blocks.push(serde_json::json!({"type": "text", "text": text}));
+            }
+            for t in tool_uses {
+                blocks.push(serde_json::json!({
+                    "type": "tool_use",
+                    "id": t.id,
+                    "name": t.name,
+                    "input": t.input,
+                }));
+            }
+            serde_json::json!({"role": "assistant", "content": blocks})
+        }
+        Turn::ToolResults { results } => {
+            let blocks: Vec<_> = results.iter()
+                .map(|r| serde_json::json!({
+                    "type": "tool_result",
+                    "tool_use_id": r.tool_use_id,
+                    "content": r.content,
+                    "is_error": r.is_error,
+                }))
+                .collect();
+            serde_json::json!({"role": "user", "content": blocks})
+        }
+    }
+}
+
+/// Text, tool calls and usage from a Messages API response
+fn parse_turn(body: &serde_json::Value) -> ModelTurn {
+    let mut turn = ModelTurn::default();
+    if let Some(content) = body.get("content").and_then(|c| c.as_array()) {
+        for block in content {
+            match block.get("type").and_then(|t| t.as_str()) {
+                Some("text") => {
+                    if let Some(t) = block.get("text").and_then(|t| t.as_str()) {
+                        turn.text.push_str(t);
+                    }
+                }
+                Some("tool_use") => turn.tool_uses.push(ToolUseResponse {
+                    id: block.get("id").and_then(|i| i.as_str()).unwrap_or("").to_string(),
+                    name: block.get("name").and_then(|n| n.as_str()).unwrap_or("").to_string(),
+                    input: block.get("input").cloned().unwrap_or(serde_json::json!({})),
+                }),
+                _ => {}
+            }
+        }
+    }
+    let usage = |key: &str| body.get("usage")
+        .and_then(|u| u.get(key))
+        .and_then(|n| n.as_u64())
+        .unwrap_or(0) as usize;
+    turn.input_tokens = usage("input_tokens");
+    turn.output_tokens = usage("output_tokens");
+    turn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::ToolResultInput;
+
+    fn tool_round() -> Vec<Turn> {
+        vec![
+            Turn::User { text: "hash a".into() },
+            Turn::Assistant {
+                text: "Checking.".into(),
+                tool_uses: vec![ToolUseResponse {
+                    id: "tu_1".into(),
+                    name: "hash_compute".into(),
+                    input: serde_json::json!({"input": "a"}),
+                }],
+            },
+            Turn::ToolResults {
+                results: vec![ToolResultInput { tool_use_id: "tu_1".into(), content: "x".into(), is_error: true }],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_turn_messages() {
+        let body = serde_json::json!({
+            "content": [
+                {"type": "text", "text": "Checking."},
+                {"type": "tool_use", "id": "tu_1", "name": "hash_compute", "input": {"input": "a"}}
+            ],
+            "usage": {"input_tokens": 12, "output_tokens": 7}
+        });
+        let turn = parse_turn(&body);
+        assert_eq!((turn.input_tokens, turn.output_tokens), (12, 7));
+        assert_eq!(turn.tool_uses[0].name, "hash_compute");
+
+        let turns = tool_round();
+        let assistant = turn_message(&turns[1]);
+        assert_eq!(assistant["content"][1]["type"], "tool_use");
+        assert_eq!(assistant["content"][1]["id"], "tu_1");
+
+        let results = turn_message(&turns[2]);
+        assert_eq!(results["role"], "user");
+        assert_eq!(results["content"][0]["tool_use_id"], "tu_1");
+        assert_eq!(results["content"][0]["is_error"], true);
+    }
+
+    #[test]
+    fn test_plain_messages() {
+        let request = ChatRequest {
+            history: vec![Message::user("hi"), Message::assistant("hello")],
+            turns: tool_round(),
+            ..Default::default()
+        };
+        let plain = request.plain_messages();
+        let roles: Vec<_> = plain.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["user", "assistant", "user", "assistant", "user"]);
+        assert_eq!(plain[3].content, "Checking.\n[tool_use tu_1 hash_compute {\"input\":\"a\"}]");
+        assert_eq!(plain[4].content, "[tool_error tu_1] x");
+    }
+}
@@ -1,21 +1,28 @@
-//! Claude API Integration
+//! Assistant chat client
 //!
-//! User-facing Claude assistant for GentlyOS CLI.
-//! Separate from any development/coding assistant.
-
-use crate::{Error, Result};
+//! User-facing GentlyOS assistant for the CLI. Separate from any
+//! development/coding assistant. The client and assistant are backend
+//! agnostic (see [`crate::chat`]). The default way to build them is
+//! `gently_gateway::{claude_client, claude_assistant, claude_session}`,
+//! which go through the gateway's filters and audit chain. The
+//! `direct_anthropic` constructors here bypass all of that.
+
+use crate::agent_loop::{AgentLoop, AgentModel, AgentRun, ModelTurn, Turn};
+use crate::chat::{AnthropicBackend, ChatBackend, ChatRequest};
+use crate::Result;
 use serde::{Deserialize, Serialize};
-use std::env;
 
-/// Claude API client
-pub struct ClaudeClient {
-    api_key: String,
-    model: ClaudeModel,
+/// Chat client over a [`ChatBackend`]
+pub struct ChatClient {
+    backend: Box<dyn ChatBackend>,
     system_prompt: Option<String>,
     conversation: Vec<Message>,
     max_tokens: usize,
 }
 
+/// Earlier name of [`ChatClient`]
+pub type ClaudeClient = ChatClient;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ClaudeModel {
     Sonnet,       // claude-sonnet-4-20250514
@@ -71,67 +78,21 @@ impl Message {
     }
 }
 
-#[derive(Serialize)]
-struct ApiRequest {
-    model: String,
-    max_tokens: usize,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    system: Option<String>,
-    messages: Vec<Message>,
-}
-
-#[derive(Deserialize)]
-struct ApiResponse {
-    content: Vec<ContentBlock>,
-    #[serde(default)]
-    usage: Option<Usage>,
-}
-
-#[derive(Deserialize)]
-struct ContentBlock {
-    #[serde(rename = "type")]
-    content_type: String,
-    text: Option<String>,
-}
-
-#[derive(Deserialize, Default)]
-struct Usage {
-    input_tokens: usize,
-    output_tokens: usize,
-}
-
-#[derive(Deserialize)]
-struct ApiError {
-    error: ErrorDetail,
-}
-
-#[derive(Deserialize)]
-struct ErrorDetail {
-    message: String,
-}
+impl ChatClient {
+    /// Client straight on the Anthropic API (ANTHROPIC_API_KEY), bypassing the gateway
+    pub fn direct_anthropic() -> Result<Self> {
+        Ok(Self::with_backend(Box::new(AnthropicBackend::new()?)))
+    }
 
-impl ClaudeClient {
-    /// Create new Claude client
-    pub fn new() -> Result<Self> {
-        let api_key = env::var("ANTHROPIC_API_KEY")
-            .map_err(|_| Error::InferenceFailed(
-                "ANTHROPIC_API_KEY not set. Export your API key.".to_string()
-            ))?;
-
-        Ok(Self {
-            api_key,
-            model: ClaudeModel::Sonnet,
-            system_prompt: None,
-            conversation: Vec::new(),
-            max_tokens: 4096,
-        })
+    /// Client straight on the Anthropic API with `api_key`, bypassing the gateway
+    pub fn direct_anthropic_with_key(api_key: &str) -> Self {
+        Self::with_backend(Box::new(AnthropicBackend::with_key(api_key)))
     }
 
-    /// Create with specific API key
-    pub fn with_key(api_key: &str) -> Self {
+    /// Create client on any backend
+    pub fn with_backend(backend: Box<dyn ChatBackend>) -> Self {
         Self {
-            api_key: api_key.to_string(),
-            model: ClaudeModel::Sonnet,
+            backend,
             system_prompt: None,
             conversation: Vec::new(),
             max_tokens: 4096,
@@ -140,7 +101,7 @@ impl ClaudeClient {
 
     /// Set model
     pub fn model(mut self, model: ClaudeModel) -> Self {
-        self.model = model;
+        self.backend.set_model(model.api_name());
         self
     }
 
@@ -166,133 +127,74 @@ impl ClaudeClient {
         &self.conversation
     }
 
+    /// Get the backend
+    pub fn backend(&self) -> &dyn ChatBackend {
+        self.backend.as_ref()
+    }
+
+    /// Request over the current history
+    fn request(&self, turns: Vec<Turn>, tools: &[serde_json::Value]) -> ChatRequest {
+        ChatRequest {
+            system: self.system_prompt.clone(),
+            history: self.conversation.clone(),
+            turns,
+            tools: tools.to_vec(),
+            max_tokens: self.max_tokens,
+        }
+    }
+
     /// Send message and get response (blocking)
     pub fn chat(&mut self, message: &str) -> Result<String> {
         // Add user message
         self.conversation.push(Message::user(message));
 
-        // Build request
-        let request = ApiRequest {
-            model: self.model.api_name().to_string(),
-            max_tokens: self.max_tokens,
-            system: self.system_prompt.clone(),
-            messages: self.conversation.clone(),
-        };
+        let turn = self.backend.complete(&self.request(Vec::new(), &[]))?;
 
-        // Make HTTP request (using ureq for blocking)
-        let response = ureq::post("https://api.anthropic.com/v1/messages")
-            .set("x-api-key", &self.api_key)
-            .set("anthropic-version", "2023-06-01")
-            .set("content-type", "application/json")
-            .send_json(&request);
-
-        match response {
-            Ok(resp) => {
-                let body: ApiResponse = resp.into_json()
-                    .map_err(|e| Error::InferenceFailed(format!("Parse error: {}", e)))?;
-
-                let text = body.content
-                    .iter()
-                    .filter_map(|c| c.text.as_ref())
-                    .cloned()
-                    .collect::<Vec<_>>()
-                    .join("");
-
-                // Add assistant response to history
-                self.conversation.push(Message::assistant(&text));
-
-                Ok(text)
-            }
-            Err(ureq::Error::Status(code, resp)) => {
-                let error: ApiError = resp.into_json()
-                    .unwrap_or(ApiError {
-                        error: ErrorDetail {
-                            message: format!("HTTP {}", code)
-                        }
-                    });
-                Err(Error::InferenceFailed(error.error.message))
-            }
-            Err(e) => {
-                Err(Error::InferenceFailed(format!("Request failed: {}", e)))
-            }
-        }
+        // Add assistant response to history
+        self.conversation.push(Message::assistant(&turn.text));
+
+        Ok(turn.text)
     }
 
     /// One-shot message (no history)
     pub fn ask(&self, message: &str) -> Result<String> {
-        let request = ApiRequest {
-            model: self.model.api_name().to_string(),
-            max_tokens: self.max_tokens,
+        let request = ChatRequest {
             system: self.system_prompt.clone(),
-            messages: vec![Message::user(message)],
+            history: vec![Message::user(message)],
+            max_tokens: self.max_tokens,
+            ..Default::default()
         };
-
-        let response = ureq::post("https://api.anthropic.com/v1/messages")
-            .set("x-api-key", &self.api_key)
-            .set("anthropic-version", "2023-06-01")
-            .set("content-type", "application/json")
-            .send_json(&request);
-
-        match response {
-            Ok(resp) => {
-                let body: ApiResponse = resp.into_json()
-                    .map_err(|e| Error::InferenceFailed(format!("Parse error: {}", e)))?;
-
-                let text = body.content
-                    .iter()
-                    .filter_map(|c| c.text.as_ref())
-                    .cloned()
-                    .collect::<Vec<_>>()
-                    .join("");
-
-                Ok(text)
-            }
-            Err(ureq::Error::Status(code, resp)) => {
-                let error: ApiError = resp.into_json()
-                    .unwrap_or(ApiError {
-                        error: ErrorDetail {
-                            message: format!("HTTP {}", code)
-                        }
-                    });
-                Err(Error::InferenceFailed(error.error.message))
-            }
-            Err(e) => {
-                Err(Error::InferenceFailed(format!("Request failed: {}", e)))
-            }
-        }
+        Ok(self.backend.complete(&request)?.text)
     }
 }
 
-/// GentlyOS-aware Claude assistant with tool use
+/// GentlyOS-aware assistant with tool use
 pub struct GentlyAssistant {
-    client: ClaudeClient,
+    client: ChatClient,
     tools_enabled: bool,
     tool_definitions: Vec<serde_json::Value>,
+    /// Tool rounds of the exchange in progress
+    pending: Vec<Turn>,
 }
 
 impl GentlyAssistant {
-    pub fn new() -> Result<Self> {
-        let client = ClaudeClient::new()?
-            .model(ClaudeModel::Sonnet)
-            .system(GENTLY_SYSTEM_PROMPT);
-
-        Ok(Self {
-            client,
-            tools_enabled: false,
-            tool_definitions: Vec::new(),
-        })
+    /// Assistant straight on the Anthropic API, bypassing the gateway
+    pub fn direct_anthropic(model: ClaudeModel) -> Result<Self> {
+        Ok(Self::with_client(ChatClient::direct_anthropic()?.model(model)))
     }
 
-    pub fn with_model(model: ClaudeModel) -> Result<Self> {
-        let client = ClaudeClient::new()?
-            .model(model)
-            .system(GENTLY_SYSTEM_PROMPT);
+    /// Assistant on any backend
+    pub fn with_backend(backend: Box<dyn ChatBackend>) -> Self {
+        Self::with_client(ChatClient::with_backend(backend))
+    }
 
-        Ok(Self {
-            client,
+    fn with_client(client: ChatClient) -> Self {
+        Self {
+            client: client.system(GENTLY_SYSTEM_PROMPT),
             tools_enabled: false,
             tool_definitions: Vec::new(),
-        })
+            pending: Vec::new(),
+        }
     }
 
     /// Enable tools with provided definitions
@@ -316,157 +218,38 @@ impl GentlyAssistant {
             });
         }
 
-        // Add user message
-        self.client.conversation.push(Message::user(message));
-
-        // Build request with tools
-        let request = serde_json::json!({
-            "model": self.client.model.api_name(),
-            "max_tokens": self.client.max_tokens,
-            "system": self.client.system_prompt,
-            "messages": self.client.conversation,
-            "tools": self.tool_definitions,
-        });
-
-        let response = ureq::post("https://api.anthropic.com/v1/messages")
-            .set("x-api-key", &self.client.api_key)
-            .set("anthropic-version", "2023-06-01")
-            .set("content-type", "application/json")
-            .send_json(&request);
-
-        match response {
-            Ok(resp) => {
-                let body: serde_json::Value = resp.into_json()
-                    .map_err(|e| Error::InferenceFailed(format!("Parse error: {}", e)))?;
-
-                let mut text = String::new();
-                let mut tool_uses = Vec::new();
-
-                if let Some(content) = body.get("content").and_then(|c| c.as_array()) {
-                    for block in content {
-                        match block.get("type").and_then(|t| t.as_str()) {
-                            Some("text") => {
-                                if let Some(t) = block.get("text").and_then(|t| t.as_str()) {
-                                    text.push_str(t);
-                                }
-                            }
-                            Some("tool_use") => {
-                                let tool_use = ToolUseResponse {
-                                    id: block.get("id")
-                                        .and_then(|i| i.as_str())
-                                        .unwrap_or("")
-                                        .to_string(),
-                                    name: block.get("name")
-                                        .and_then(|n| n.as_str())
-                                        .unwrap_or("")
-                                        .to_string(),
-                                    input: block.get("input").cloned().unwrap_or(serde_json::json!({})),
-                                };
-                                tool_uses.push(tool_use);
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-
-                // Store assistant message (simplified - full impl would store tool_use blocks)
-                if !text.is_empty() {
-                    self.client.conversation.push(Message::assistant(&text));
-                }
-
-                Ok(AssistantResponse { text, tool_uses })
-            }
-            Err(ureq::Error::Status(code, resp)) => {
-                let body: serde_json::Value = resp.into_json().unwrap_or(serde_json::json!({}));
-                let default_msg = format!("HTTP {}", code);
-                let msg = body.get("error")
-                    .and_then(|e| e.get("message"))
-                    .and_then(|m| m.as_str())
-                    .unwrap_or(&default_msg);
-                Err(Error::InferenceFailed(msg.to_string()))
-            }
-            Err(e) => {
-                Err(Error::InferenceFailed(format!("Request failed: {}", e)))
-            }
-        }
+        self.pending = vec![Turn::User { text: message.to_string() }];
+        self.round()
     }
 
     /// Submit tool results and continue conversation
     pub fn submit_tool_results(&mut self, results: Vec<ToolResultInput>) -> Result<AssistantResponse> {
-        // Add tool results as user message (simplified format)
-        let results_json: Vec<serde_json::Value> = results.iter()
-            .map(|r| serde_json::json!({
-                "type": "tool_result",
-                "tool_use_id": r.tool_use_id,
-                "content": r.content,
-            }))
-            .collect();
-
-        // Build request with tool results
-        let mut messages = self.client.conversation.clone();
-        messages.push(Message {
-            role: "user".into(),
-            content: serde_json::to_string(&results_json).unwrap_or_default(),
-        });
-
-        let request = serde_json::json!({
-            "model": self.client.model.api_name(),
-            "max_tokens": self.client.max_tokens,
-            "system": self.client.system_prompt,
-            "messages": messages,
-            "tools": self.tool_definitions,
-        });
-
-        let response = ureq::post("https://api.anthropic.com/v1/messages")
-            .set("x-api-key", &self.client.api_key)
-            .set("anthropic-version", "2023-06-01")
-            .set("content-type", "application/json")
-            .send_json(&request);
-
-        match response {
-            Ok(resp) => {
-                let body: serde_json::Value = resp.into_json()
-                    .map_err(|e| Error::InferenceFailed(format!("Parse error: {}", e)))?;
-
-                let mut text = String::new();
-                let mut tool_uses = Vec::new();
-
-                if let Some(content) = body.get("content").and_then(|c| c.as_array()) {
-                    for block in content {
-                        match block.get("type").and_then(|t| t.as_str()) {
-                            Some("text") => {
-                                if let Some(t) = block.get("text").and_then(|t| t.as_str()) {
-                                    text.push_str(t);
-                                }
-                            }
-                            Some("tool_use") => {
-                                let tool_use = ToolUseResponse {
-                                    id: block.get("id").and_then(|i| i.as_str()).unwrap_or("").to_string(),
-                                    name: block.get("name").and_then(|n| n.as_str()).unwrap_or("").to_string(),
-                                    input: block.get("input").cloned().unwrap_or(serde_json::json!({})),
-                                };
-                                tool_uses.push(tool_use);
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-
-                Ok(AssistantResponse { text, tool_uses })
-            }
-            Err(ureq::Error::Status(code, resp)) => {
-                let body: serde_json::Value = resp.into_json().unwrap_or(serde_json::json!({}));
-                let default_msg = format!("HTTP {}", code);
-                let msg = body.get("error")
-                    .and_then(|e| e.get("message"))
-                    .and_then(|m| m.as_str())
-                    .unwrap_or(&default_msg);
-                Err(Error::InferenceFailed(msg.to_string()))
-            }
-            Err(e) => {
-                Err(Error::InferenceFailed(format!("Request failed: {}", e)))
+        self.pending.push(Turn::ToolResults { results });
+        self.round()
+    }
+
+    /// One model call over the pending exchange
+    ///
+    /// Once the model answers without tools the exchange is folded into the
+    /// plain history as a single user/assistant pair.
+    fn round(&mut self) -> Result<AssistantResponse> {
+        let request = self.client.request(self.pending.clone(), &self.tool_definitions);
+        let turn = self.client.backend.complete(&request)?;
+
+        if turn.tool_uses.is_empty() {
+            if let Some(Turn::User { text }) = self.pending.first() {
+                self.client.conversation.push(Message::user(text));
             }
+            self.client.conversation.push(Message::assistant(&turn.text));
+            self.pending.clear();
+        } else {
+            self.pending.push(Turn::Assistant {
+                text: turn.text.clone(),
+                tool_uses: turn.tool_uses.clone(),
+            });
         }
+
+        Ok(AssistantResponse { text: turn.text, tool_uses: turn.tool_uses })
     }
 
     /// Ask about GentlyOS
@@ -477,15 +260,16 @@ impl GentlyAssistant {
     /// Clear conversation
     pub fn clear(&mut self) {
         self.client.clear();
+        self.pending.clear();
     }
 
     /// Get the underlying client
-    pub fn client(&self) -> &ClaudeClient {
+    pub fn client(&self) -> &ChatClient {
         &self.client
     }
 
     /// Get mutable client
-    pub fn client_mut(&mut self) -> &mut ClaudeClient {
+    pub fn client_mut(&mut self) -> &mut ChatClient {
         &mut self.client
     }
 
@@ -493,6 +277,21 @@ impl GentlyAssistant {
     pub fn has_tools(&self) -> bool {
         self.tools_enabled
     }
+
+    /// Run `task` through an agent loop, keeping the task and final answer in history
+    pub fn run_agent(&mut self, agent: &AgentLoop, task: &str) -> Result<AgentRun> {
+        let run = agent.run(self, task)?;
+        self.client.conversation.push(Message::user(task));
+        self.client.conversation.push(Message::assistant(&run.answer));
+        Ok(run)
+    }
+}
+
+impl AgentModel for GentlyAssistant {
+    fn step(&mut self, transcript: &[Turn], tools: &[serde_json::Value]) -> Result<ModelTurn> {
+        let request = self.client.request(transcript.to_vec(), tools);
+        self.client.backend.complete(&request)
+    }
 }
 
 /// Response from assistant with possible tool uses
@@ -511,7 +310,7 @@ pub struct ToolUseResponse {
 }
 
 /// Tool result to submit back
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolResultInput {
     pub tool_use_id: String,
     pub content: String,
@@ -540,19 +339,32 @@ Be helpful, concise, and security-focused. When discussing exploits or attacks,
 "#;
 
 /// Session manager for persistent conversations
-pub struct ClaudeSession {
+pub struct ChatSession {
     assistant: GentlyAssistant,
     session_id: String,
     created_at: chrono::DateTime<chrono::Utc>,
 }
 
-impl ClaudeSession {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            assistant: GentlyAssistant::new()?,
+/// Earlier name of [`ChatSession`]
+pub type ClaudeSession = ChatSession;
+
+impl ChatSession {
+    /// Session straight on the Anthropic API, bypassing the gateway
+    pub fn direct_anthropic(model: ClaudeModel) -> Result<Self> {
+        Ok(Self::with_assistant(GentlyAssistant::direct_anthropic(model)?))
+    }
+
+    /// Session on any backend
+    pub fn with_backend(backend: Box<dyn ChatBackend>) -> Self {
+        Self::with_assistant(GentlyAssistant::with_backend(backend))
+    }
+
+    fn with_assistant(assistant: GentlyAssistant) -> Self {
+        Self {
+            assistant,
             session_id: uuid::Uuid::new_v4().to_string(),
             created_at: chrono::Utc::now(),
-        })
+        }
     }
 
     pub fn session_id(&self) -> &str {
@@ -571,18 +383,95 @@ impl ClaudeSession {
         self.assistant.clear();
     }
 
-    pub fn model_name(&self) -> &'static str {
-        self.assistant.client().model.display_name()
+    pub fn model_name(&self) -> String {
+        self.assistant.client().backend().model()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Backend that replays turns and records each request
+    struct Scripted {
+        turns: Vec<ModelTurn>,
+        seen: Arc<Mutex<Vec<ChatRequest>>>,
+        model: String,
+    }
+
+    impl ChatBackend for Scripted {
+        fn name(&self) -> &str { "scripted" }
+
+        fn model(&self) -> String { self.model.clone() }
+
+        fn set_model(&mut self, model: &str) { self.model = model.to_string(); }
+
+        fn complete(&self, request: &ChatRequest) -> Result<ModelTurn> {
+            let mut seen = self.seen.lock().unwrap();
+            seen.push(request.clone());
+            Ok(self.turns.get(seen.len() - 1).cloned().unwrap_or_default())
+        }
+    }
+
+    fn scripted(turns: Vec<ModelTurn>) -> (Box<Scripted>, Arc<Mutex<Vec<ChatRequest>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        (Box::new(Scripted { turns, seen: seen.clone(), model: String::new() }), seen)
+    }
+
+    fn text(t: &str) -> ModelTurn {
+        ModelTurn { text: t.into(), ..Default::default() }
+    }
 
     #[test]
     fn test_model_names() {
         assert_eq!(ClaudeModel::Sonnet.api_name(), "claude-sonnet-4-20250514");
         assert_eq!(ClaudeModel::from_str("haiku"), ClaudeModel::Haiku);
     }
+
+    #[test]
+    fn test_client_on_backend() {
+        let (backend, seen) = scripted(vec![text("one"), text("two")]);
+        let mut client = ChatClient::with_backend(backend).system("sys").model(ClaudeModel::Haiku);
+        assert_eq!(client.backend().model(), "claude-3-5-haiku-20241022");
+
+        assert_eq!(client.chat("a").unwrap(), "one");
+        assert_eq!(client.chat("b").unwrap(), "two");
+        assert_eq!(client.history().len(), 4);
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[1].system.as_deref(), Some("sys"));
+        assert_eq!(seen[1].history.len(), 3);
+        assert!(seen[1].tools.is_empty());
+    }
+
+    #[test]
+    fn test_tool_rounds_fold_into_history() {
+        let call = ModelTurn {
+            tool_uses: vec![ToolUseResponse { id: "t1".into(), name: "focus".into(), input: serde_json::json!({}) }],
+            ..Default::default()
+        };
+        let (backend, seen) = scripted(vec![call, text("focused")]);
+        let mut assistant = GentlyAssistant::with_backend(backend)
+            .with_tools(vec![serde_json::json!({"name": "focus"})]);
+
+        let first = assistant.chat_with_tools("focus on rust").unwrap();
+        assert_eq!(first.tool_uses.len(), 1);
+        assert!(assistant.client().history().is_empty());
+
+        let done = assistant.submit_tool_results(vec![ToolResultInput {
+            tool_use_id: "t1".into(),
+            content: "ok".into(),
+            is_error: false,
+        }]).unwrap();
+        assert_eq!(done.text, "focused");
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[1].turns.len(), 3);
+        assert!(matches!(seen[1].turns[2], Turn::ToolResults { .. }));
+        assert_eq!(seen[1].tools.len(), 1);
+
+        let history: Vec<_> = assistant.client().history().iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(history, vec!["focus on rust", "focused"]);
+    }
 }
@@ -2,16 +2,28 @@
 //!
 //! Continuous processes that run in the background:
 //! - Vector chain rendering
-//! - IPFS sync
+//! - IPFS sync (stub: no IPFS client yet)
 //! - Git branch management
 //! - Knowledge graph updates
 //! - Awareness loop
-
+//!
+//! ```text
+//! DaemonManager::spawn ──► supervisor task
+//!                            │
+//!                            ├─► run_loop: step() ─ sleep ─ step() ... (until stop_flag)
+//!                            │             └─► drain() queued jobs on the way out
+//!                            │
+//!                            └─► panic? ─► RestartPolicy ─► backoff ─► run_loop again
+//! ```
+
+use crate::local_embed::LocalEmbedder;
 use crate::{Result, Error};
+use sha2::{Digest, Sha256};
 use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
+use tokio::task::{AbortHandle, JoinHandle};
 
 /// Daemon manager - controls all background processes
 pub struct DaemonManager {
@@ -19,6 +31,7 @@ pub struct DaemonManager {
     running: Arc<AtomicBool>,
     event_tx: mpsc::UnboundedSender<DaemonEvent>,
     event_rx: Arc<Mutex<mpsc::UnboundedReceiver<DaemonEvent>>>,
+    supervisor: SupervisorConfig,
 }
 
 /// Handle to a running daemon
@@ -27,6 +40,10 @@ pub struct DaemonHandle {
     pub daemon_type: DaemonType,
     pub status: Arc<Mutex<DaemonStatus>>,
     pub stop_flag: Arc<AtomicBool>,
+    pub worker: DaemonWorker,
+    task: Option<JoinHandle<()>>,
+    /// Current run of the worker, spawned by the supervisor
+    run: Arc<Mutex<Option<AbortHandle>>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -76,6 +93,19 @@ pub struct DaemonStatus {
     pub metrics: DaemonMetrics,
 }
 
+impl DaemonStatus {
+    fn new() -> Self {
+        Self {
+            running: true,
+            started_at: Some(Instant::now()),
+            cycles: 0,
+            last_cycle: None,
+            errors: 0,
+            metrics: DaemonMetrics::default(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct DaemonMetrics {
     pub items_processed: u64,
@@ -83,6 +113,74 @@ pub struct DaemonMetrics {
     pub vectors_computed: u64,
     pub branches_created: u32,
     pub learnings_added: u32,
+    /// Cycles that did work (a job, a thought, a snapshot)
+    pub busy_cycles: u64,
+    /// Time spent in the last cycle
+    pub last_cycle_time: Duration,
+    /// Time spent in all cycles
+    pub total_cycle_time: Duration,
+    pub panics: u32,
+    pub restarts: u32,
+    /// Jobs finished during shutdown
+    pub jobs_drained: u64,
+}
+
+/// When a daemon's work loop is started again
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestartPolicy {
+    /// Never restart
+    Never,
+    /// Restart after a panic
+    OnPanic,
+    /// Restart whenever the loop exits without being stopped
+    Always,
+}
+
+/// Supervision settings for spawned daemons
+#[derive(Debug, Clone)]
+pub struct SupervisorConfig {
+    pub restart: RestartPolicy,
+    /// Give up after this many restarts in a row
+    pub max_restarts: u32,
+    /// A run that stays up this long resets the restart count and backoff
+    pub healthy_after: Duration,
+    /// First restart delay, doubled after each restart
+    pub backoff_initial: Duration,
+    pub backoff_max: Duration,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            restart: RestartPolicy::OnPanic,
+            max_restarts: 5,
+            healthy_after: Duration::from_secs(60),
+            backoff_initial: Duration::from_millis(100),
+            backoff_max: Duration::from_secs(30),
+        }
+    }
+}
+
+impl SupervisorConfig {
+    /// Delay before restart number `attempt` (1-based)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.backoff_initial.saturating_mul(factor).min(self.backoff_max)
+    }
+}
+
+/// One unit of daemon work, driven by `run_loop`
+pub trait DaemonTask: Send + Sync {
+    /// Do one cycle of work; `true` if there was something to do
+    fn step(&self) -> bool;
+
+    /// Pause between cycles
+    fn interval(&self) -> Duration;
+
+    /// Finish queued work after a stop; returns jobs completed
+    fn drain(&self) -> u64 {
+        0
+    }
 }
 
 /// Events emitted by daemons
@@ -91,13 +189,15 @@ pub enum DaemonEvent {
     // Lifecycle events
     Started { daemon: String },
     Stopped { daemon: String },
+    Restarted { daemon: String, attempt: u32 },
     Cycle { daemon: String, cycle: u64 },
     Error { daemon: String, error: String },
 
     // Knowledge events
     Learning { concept: String, confidence: f32 },
     VectorComputed { id: String, dimensions: usize },
-    IpfsSynced { cid: String, size: usize },
+    /// A sync job was taken off the queue; `digest` is its SHA-256, not an IPFS CID
+    IpfsStaged { digest: String, size: usize },
     BranchSwitch { from: String, to: String },
     AwarenessState { state: AwarenessState },
 
@@ -139,6 +239,31 @@ pub struct AwarenessState {
     pub growth_direction: String,      // Where we're growing
 }
 
+/// The work behind a spawned daemon
+#[derive(Clone)]
+pub enum DaemonWorker {
+    VectorChain(VectorChainDaemon),
+    IpfsSync(IpfsSyncDaemon),
+    Awareness(AwarenessDaemon),
+    GitBranch(GitBranchDaemon),
+    /// Types without a dedicated loop just tick
+    Heartbeat(HeartbeatDaemon),
+    Custom(Arc<dyn DaemonTask>),
+}
+
+impl DaemonWorker {
+    fn task(&self) -> &dyn DaemonTask {
+        match self {
+            DaemonWorker::VectorChain(d) => d,
+            DaemonWorker::IpfsSync(d) => d,
+            DaemonWorker::Awareness(d) => d,
+            DaemonWorker::GitBranch(d) => d,
+            DaemonWorker::Heartbeat(d) => d,
+            DaemonWorker::Custom(d) => d.as_ref(),
+        }
+    }
+}
+
 impl DaemonManager {
     pub fn new() -> Self {
         let (tx, rx) = mpsc::unbounded_channel();
@@ -147,40 +272,138 @@ impl DaemonManager {
             running: Arc::new(AtomicBool::new(false)),
             event_tx: tx,
             event_rx: Arc::new(Mutex::new(rx)),
+            supervisor: SupervisorConfig::default(),
         }
     }
 
+    /// Supervision used by `spawn`
+    pub fn with_supervisor(mut self, supervisor: SupervisorConfig) -> Self {
+        self.supervisor = supervisor;
+        self
+    }
+
     /// Start the daemon manager
     pub fn start(&mut self) {
         self.running.store(true, Ordering::SeqCst);
     }
 
     /// Stop all daemons
+    ///
+    /// Signals only; queued jobs drain in the background. Use `shutdown`
+    /// to wait for them.
     pub fn stop(&mut self) {
         self.running.store(false, Ordering::SeqCst);
-        for (_, handle) in &self.daemons {
+        for handle in self.daemons.values() {
             handle.stop_flag.store(true, Ordering::SeqCst);
         }
     }
 
+    /// Stop one daemon
+    pub fn stop_daemon(&mut self, name: &str) -> bool {
+        match self.daemons.get(name) {
+            Some(handle) => {
+                handle.stop_flag.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stop all daemons and wait for them to drain their queues
+    ///
+    /// Daemons still running after `timeout` are aborted and reported.
+    pub async fn shutdown(&mut self, timeout: Duration) -> Result<()> {
+        self.stop();
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut stuck = Vec::new();
+
+        for (name, handle) in self.daemons.iter_mut() {
+            let Some(mut task) = handle.task.take() else { continue };
+            if tokio::time::timeout_at(deadline, &mut task).await.is_err() {
+                task.abort();
+                if let Some(run) = handle.run.lock().unwrap().take() {
+                    run.abort();
+                }
+                handle.status.lock().unwrap().running = false;
+                stuck.push(name.clone());
+            }
+        }
+
+        if stuck.is_empty() {
+            Ok(())
+        } else {
+            stuck.sort();
+            Err(Error::Daemon(format!("did not stop in time: {}", stuck.join(", "))))
+        }
+    }
+
     /// Spawn a new daemon
+    ///
+    /// Needs a tokio runtime: the daemon runs as a supervised task.
     pub fn spawn(&mut self, daemon_type: DaemonType) -> Result<String> {
-        let name = format!("{:?}_{}", daemon_type, self.daemons.len());
+        let supervisor = self.supervisor.clone();
+        self.spawn_with(daemon_type, supervisor)
+    }
+
+    /// Spawn a daemon with its own supervision settings
+    pub fn spawn_with(&mut self, daemon_type: DaemonType, supervisor: SupervisorConfig) -> Result<String> {
         let stop_flag = Arc::new(AtomicBool::new(false));
-        let status = Arc::new(Mutex::new(DaemonStatus {
-            running: true,
-            started_at: Some(Instant::now()),
-            cycles: 0,
-            last_cycle: None,
-            errors: 0,
-            metrics: DaemonMetrics::default(),
-        }));
+        let status = Arc::new(Mutex::new(DaemonStatus::new()));
+        let (flag, st, tx) = (stop_flag.clone(), status.clone(), self.event_tx.clone());
+
+        let worker = match daemon_type {
+            DaemonType::VectorChain => DaemonWorker::VectorChain(VectorChainDaemon::new(flag, st, tx)),
+            DaemonType::IpfsSync => DaemonWorker::IpfsSync(IpfsSyncDaemon::new(flag, st, tx)),
+            DaemonType::Awareness => DaemonWorker::Awareness(AwarenessDaemon::new(flag, st, tx)),
+            DaemonType::GitBranch => DaemonWorker::GitBranch(GitBranchDaemon::new(flag, st, tx)),
+            _ => DaemonWorker::Heartbeat(HeartbeatDaemon::new(Duration::from_secs(1))),
+        };
+        self.launch(daemon_type, worker, stop_flag, status, supervisor)
+    }
+
+    /// Spawn a custom task under supervision
+    pub fn spawn_task(
+        &mut self,
+        daemon_type: DaemonType,
+        task: Arc<dyn DaemonTask>,
+        supervisor: SupervisorConfig,
+    ) -> Result<String> {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let status = Arc::new(Mutex::new(DaemonStatus::new()));
+        self.launch(daemon_type, DaemonWorker::Custom(task), stop_flag, status, supervisor)
+    }
+
+    fn launch(
+        &mut self,
+        daemon_type: DaemonType,
+        worker: DaemonWorker,
+        stop_flag: Arc<AtomicBool>,
+        status: Arc<Mutex<DaemonStatus>>,
+        supervisor: SupervisorConfig,
+    ) -> Result<String> {
+        let runtime = tokio::runtime::Handle::try_current()
+            .map_err(|_| Error::Daemon("daemons need a tokio runtime".into()))?;
+        let name = format!("{:?}_{}", daemon_type, self.daemons.len());
+        let run = Arc::new(Mutex::new(None));
+
+        let task = runtime.spawn(supervise(
+            name.clone(),
+            worker.clone(),
+            stop_flag.clone(),
+            status.clone(),
+            self.event_tx.clone(),
+            supervisor,
+            run.clone(),
+        ));
 
         let handle = DaemonHandle {
             name: name.clone(),
             daemon_type,
-            status: status.clone(),
-            stop_flag: stop_flag.clone(),
+            status,
+            stop_flag,
+            worker,
+            task: Some(task),
+            run,
         };
 
         self.daemons.insert(name.clone(), handle);
@@ -203,6 +426,37 @@ impl DaemonManager {
             .collect()
     }
 
+    /// The work behind a daemon, e.g. to enqueue jobs
+    pub fn worker(&self, name: &str) -> Option<&DaemonWorker> {
+        self.daemons.get(name).map(|h| &h.worker)
+    }
+
+    /// Queue an embedding job on the first running vector daemon
+    pub fn enqueue_vector(&self, job: VectorJob) -> bool {
+        let daemon = self.running_workers().find_map(|w| match w {
+            DaemonWorker::VectorChain(d) => Some(d),
+            _ => None,
+        });
+        daemon.map(|d| d.enqueue(job)).is_some()
+    }
+
+    /// Queue a sync job on the first running IPFS daemon
+    pub fn enqueue_sync(&self, job: SyncJob) -> bool {
+        let daemon = self.running_workers().find_map(|w| match w {
+            DaemonWorker::IpfsSync(d) => Some(d),
+            _ => None,
+        });
+        daemon.map(|d| d.enqueue(job)).is_some()
+    }
+
+    fn running_workers(&self) -> impl Iterator<Item = &DaemonWorker> {
+        let mut handles: Vec<&DaemonHandle> = self.daemons.values()
+            .filter(|h| !h.stop_flag.load(Ordering::SeqCst))
+            .collect();
+        handles.sort_by(|a, b| a.name.cmp(&b.name));
+        handles.into_iter().map(|h| &h.worker)
+    }
+
     /// Get event receiver
     pub fn events(&self) -> Arc<Mutex<mpsc::UnboundedReceiver<DaemonEvent>>> {
         self.event_rx.clone()
@@ -214,11 +468,150 @@ impl DaemonManager {
     }
 }
 
+impl Default for DaemonManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Drive `task` until `stop_flag`, recording cycle metrics, then drain it
+pub async fn run_loop(task: &dyn DaemonTask, stop_flag: &AtomicBool, status: &Mutex<DaemonStatus>) {
+    while !stop_flag.load(Ordering::SeqCst) {
+        let started = Instant::now();
+        let busy = task.step();
+        let elapsed = started.elapsed();
+
+        {
+            let mut status = status.lock().unwrap();
+            status.cycles += 1;
+            status.last_cycle = Some(Instant::now());
+            status.metrics.last_cycle_time = elapsed;
+            status.metrics.total_cycle_time += elapsed;
+            if busy {
+                status.metrics.busy_cycles += 1;
+            }
+        }
+
+        sleep_unless_stopped(stop_flag, task.interval()).await;
+    }
+
+    let drained = task.drain();
+    status.lock().unwrap().metrics.jobs_drained += drained;
+}
+
+/// Sleep for `duration`, waking early once `stop_flag` is set
+async fn sleep_unless_stopped(stop_flag: &AtomicBool, duration: Duration) {
+    const SLICE: Duration = Duration::from_millis(25);
+    let deadline = Instant::now() + duration;
+    while !stop_flag.load(Ordering::SeqCst) {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        tokio::time::sleep(left.min(SLICE)).await;
+    }
+}
+
+/// Run a worker, restarting it per `config` until stopped
+async fn supervise(
+    name: String,
+    worker: DaemonWorker,
+    stop_flag: Arc<AtomicBool>,
+    status: Arc<Mutex<DaemonStatus>>,
+    event_tx: mpsc::UnboundedSender<DaemonEvent>,
+    config: SupervisorConfig,
+    current: Arc<Mutex<Option<AbortHandle>>>,
+) {
+    let mut attempt = 0;
+    loop {
+        let run = {
+            let (worker, stop_flag, status) = (worker.clone(), stop_flag.clone(), status.clone());
+            tokio::spawn(async move { run_loop(worker.task(), &stop_flag, &status).await })
+        };
+        *current.lock().unwrap() = Some(run.abort_handle());
+        let started = Instant::now();
+
+        let outcome = run.await;
+        if started.elapsed() >= config.healthy_after {
+            attempt = 0;
+        }
+        let restart = match outcome {
+            Ok(()) => config.restart == RestartPolicy::Always,
+            Err(e) if e.is_panic() => {
+                {
+                    let mut status = status.lock().unwrap();
+                    status.errors += 1;
+                    status.metrics.panics += 1;
+                }
+                let _ = event_tx.send(DaemonEvent::Error {
+                    daemon: name.clone(),
+                    error: panic_message(e.into_panic()),
+                });
+                config.restart != RestartPolicy::Never
+            }
+            Err(_) => false,
+        };
+
+        if !restart || stop_flag.load(Ordering::SeqCst) || attempt >= config.max_restarts {
+            break;
+        }
+
+        attempt += 1;
+        sleep_unless_stopped(&stop_flag, config.backoff(attempt)).await;
+        if stop_flag.load(Ordering::SeqCst) {
+            break;
+        }
+        status.lock().unwrap().metrics.restarts += 1;
+        let _ = event_tx.send(DaemonEvent::Restarted { daemon: name.clone(), attempt });
+    }
+
+    // A stop that raced a panic still owes the queue a drain
+    if stop_flag.load(Ordering::SeqCst) {
+        let drained = worker.task().drain();
+        status.lock().unwrap().metrics.jobs_drained += drained;
+    }
+    status.lock().unwrap().running = false;
+    let _ = event_tx.send(DaemonEvent::Stopped { daemon: name });
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "daemon panicked".into())
+}
+
+/// Heartbeat Daemon - keeps cycle metrics for types without their own loop
+#[derive(Clone)]
+pub struct HeartbeatDaemon {
+    interval: Duration,
+}
+
+impl HeartbeatDaemon {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval }
+    }
+}
+
+impl DaemonTask for HeartbeatDaemon {
+    fn step(&self) -> bool {
+        false
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+}
+
 /// Vector Chain Daemon - processes embeddings continuously
+#[derive(Clone)]
 pub struct VectorChainDaemon {
     stop_flag: Arc<AtomicBool>,
     status: Arc<Mutex<DaemonStatus>>,
     queue: Arc<Mutex<Vec<VectorJob>>>,
+    vectors: Arc<Mutex<HashMap<String, Vec<f32>>>>,
+    embedder: Arc<LocalEmbedder>,
     event_tx: mpsc::UnboundedSender<DaemonEvent>,
 }
 
@@ -239,51 +632,73 @@ impl VectorChainDaemon {
             stop_flag,
             status,
             queue: Arc::new(Mutex::new(Vec::new())),
+            vectors: Arc::new(Mutex::new(HashMap::new())),
+            embedder: Arc::new(LocalEmbedder::new()),
             event_tx,
         }
     }
 
     pub fn enqueue(&self, job: VectorJob) {
         let mut queue = self.queue.lock().unwrap();
-        queue.push(job);
-        queue.sort_by(|a, b| b.priority.cmp(&a.priority));
+        // Highest priority at the back for pop(); FIFO within a priority
+        let at = queue.partition_point(|j| j.priority < job.priority);
+        queue.insert(at, job);
     }
 
-    pub async fn run(&self) {
-        while !self.stop_flag.load(Ordering::SeqCst) {
-            // Process queue
-            let job = {
-                let mut queue = self.queue.lock().unwrap();
-                queue.pop()
-            };
+    pub fn queued(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
 
-            if let Some(job) = job {
-                // Compute embedding (simulated)
-                let vector_dim = 384; // Typical embedding dimension
+    /// Embedding computed for a job id
+    pub fn vector(&self, id: &str) -> Option<Vec<f32>> {
+        self.vectors.lock().unwrap().get(id).cloned()
+    }
 
-                // Update metrics
-                {
-                    let mut status = self.status.lock().unwrap();
-                    status.cycles += 1;
-                    status.last_cycle = Some(Instant::now());
-                    status.metrics.vectors_computed += 1;
-                    status.metrics.items_processed += 1;
-                }
+    fn process(&self, job: VectorJob) {
+        let vector = self.embedder.embed(&job.content);
+        let dimensions = vector.len();
+        self.vectors.lock().unwrap().insert(job.id.clone(), vector);
 
-                // Emit event
-                let _ = self.event_tx.send(DaemonEvent::VectorComputed {
-                    id: job.id,
-                    dimensions: vector_dim,
-                });
-            }
+        // Update metrics
+        {
+            let mut status = self.status.lock().unwrap();
+            status.metrics.vectors_computed += 1;
+            status.metrics.items_processed += 1;
+        }
+
+        // Emit event
+        let _ = self.event_tx.send(DaemonEvent::VectorComputed { id: job.id, dimensions });
+    }
 
-            // Sleep between cycles
-            tokio::time::sleep(Duration::from_millis(100)).await;
+    pub async fn run(&self) {
+        run_loop(self, &self.stop_flag, &self.status).await
+    }
+}
+
+impl DaemonTask for VectorChainDaemon {
+    fn step(&self) -> bool {
+        let job = self.queue.lock().unwrap().pop();
+        job.map(|job| self.process(job)).is_some()
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_millis(100)
+    }
+
+    fn drain(&self) -> u64 {
+        let mut drained = 0;
+        while self.step() {
+            drained += 1;
         }
+        drained
     }
 }
 
 /// IPFS Sync Daemon - syncs knowledge to IPFS
+///
+/// Stub: no IPFS client is wired in yet. Jobs are hashed and counted, then
+/// dropped; nothing leaves the machine.
+#[derive(Clone)]
 pub struct IpfsSyncDaemon {
     stop_flag: Arc<AtomicBool>,
     status: Arc<Mutex<DaemonStatus>>,
@@ -317,37 +732,55 @@ impl IpfsSyncDaemon {
         pending.push(job);
     }
 
+    pub fn queued(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
     pub async fn run(&self) {
-        while !self.stop_flag.load(Ordering::SeqCst) {
-            let job = {
-                let mut pending = self.pending.lock().unwrap();
-                pending.pop()
-            };
+        run_loop(self, &self.stop_flag, &self.status).await
+    }
+}
 
-            if let Some(job) = job {
-                // Sync to IPFS (simulated)
-                let cid = format!("Qm{:x}", rand::random::<u64>());
-                let size = job.data.len();
+impl DaemonTask for IpfsSyncDaemon {
+    fn step(&self) -> bool {
+        let job = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.pop()
+        };
 
-                // Update metrics
-                {
-                    let mut status = self.status.lock().unwrap();
-                    status.cycles += 1;
-                    status.last_cycle = Some(Instant::now());
-                    status.metrics.bytes_synced += size as u64;
-                    status.metrics.items_processed += 1;
-                }
+        let Some(job) = job else { return false };
 
-                // Emit event
-                let _ = self.event_tx.send(DaemonEvent::IpfsSynced { cid, size });
-            }
+        // No IPFS client yet: identify the job by its content, don't pretend it has a CID
+        let digest = hex::encode(Sha256::digest(&job.data));
+        let size = job.data.len();
 
-            tokio::time::sleep(Duration::from_millis(500)).await;
+        // Update metrics
+        {
+            let mut status = self.status.lock().unwrap();
+            status.metrics.bytes_synced += size as u64;
+            status.metrics.items_processed += 1;
         }
+
+        // Emit event
+        let _ = self.event_tx.send(DaemonEvent::IpfsStaged { digest, size });
+        true
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_millis(500)
+    }
+
+    fn drain(&self) -> u64 {
+        let mut drained = 0;
+        while self.step() {
+            drained += 1;
+        }
+        drained
     }
 }
 
 /// Awareness Daemon - the consciousness loop
+#[derive(Clone)]
 pub struct AwarenessDaemon {
     stop_flag: Arc<AtomicBool>,
     status: Arc<Mutex<DaemonStatus>>,
@@ -397,59 +830,76 @@ impl AwarenessDaemon {
         self.state.lock().unwrap().clone()
     }
 
-    pub async fn run(&self) {
-        while !self.stop_flag.load(Ordering::SeqCst) {
-            // Process pending thoughts
-            let thought = {
-                let mut state = self.state.lock().unwrap();
-                state.pending_thoughts.pop()
-            };
+    fn process_thought(&self) -> bool {
+        let thought = {
+            let mut state = self.state.lock().unwrap();
+            state.pending_thoughts.pop()
+        };
 
-            if let Some(thought) = thought {
-                // Process thought - this is where "awareness" happens
-                // In a real system, this would:
-                // 1. Analyze the thought
-                // 2. Connect to existing knowledge
-                // 3. Generate new insights
-                // 4. Update the knowledge graph
+        let Some(thought) = thought else { return false };
+
+        // Process thought - this is where "awareness" happens
+        // In a real system, this would:
+        // 1. Analyze the thought
+        // 2. Connect to existing knowledge
+        // 3. Generate new insights
+        // 4. Update the knowledge graph
+
+        // For now, we add it to context
+        {
+            let mut state = self.state.lock().unwrap();
+            state.context.push(thought.clone());
+            if state.context.len() > 10 {
+                state.context.remove(0);
+            }
+        }
 
-                // For now, we add it to context
-                {
-                    let mut state = self.state.lock().unwrap();
-                    state.context.push(thought.clone());
-                    if state.context.len() > 10 {
-                        state.context.remove(0);
-                    }
-                }
+        // Check if thought leads to learning
+        if thought.contains("learned") || thought.contains("discovered") {
+            self.status.lock().unwrap().metrics.learnings_added += 1;
+            let _ = self.event_tx.send(DaemonEvent::Learning {
+                concept: thought,
+                confidence: 0.8,
+            });
+        }
+        true
+    }
 
-                // Check if thought leads to learning
-                if thought.contains("learned") || thought.contains("discovered") {
-                    let _ = self.event_tx.send(DaemonEvent::Learning {
-                        concept: thought,
-                        confidence: 0.8,
-                    });
-                }
-            }
+    pub async fn run(&self) {
+        run_loop(self, &self.stop_flag, &self.status).await
+    }
+}
 
-            // Update status
-            {
-                let mut status = self.status.lock().unwrap();
-                status.cycles += 1;
-                status.last_cycle = Some(Instant::now());
-            }
+impl DaemonTask for AwarenessDaemon {
+    fn step(&self) -> bool {
+        let busy = self.process_thought();
+        if busy {
+            self.status.lock().unwrap().metrics.items_processed += 1;
+        }
 
-            // Emit awareness state periodically
-            if rand::random::<u8>() < 10 {  // ~4% chance each cycle
-                let state = self.state.lock().unwrap().clone();
-                let _ = self.event_tx.send(DaemonEvent::AwarenessState { state });
-            }
+        // Emit awareness state periodically
+        if rand::random::<u8>() < 10 {  // ~4% chance each cycle
+            let state = self.state.lock().unwrap().clone();
+            let _ = self.event_tx.send(DaemonEvent::AwarenessState { state });
+        }
+        busy
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_millis(250)
+    }
 
-            tokio::time::sleep(Duration::from_millis(250)).await;
+    fn drain(&self) -> u64 {
+        let mut drained = 0;
+        while self.process_thought() {
+            drained += 1;
         }
+        drained
     }
 }
 
 /// Git Branch Daemon - manages knowledge branches
+#[derive(Clone)]
 pub struct GitBranchDaemon {
     stop_flag: Arc<AtomicBool>,
     status: Arc<Mutex<DaemonStatus>>,
@@ -472,13 +922,12 @@ impl GitBranchDaemon {
         status: Arc<Mutex<DaemonStatus>>,
         event_tx: mpsc::UnboundedSender<DaemonEvent>,
     ) -> Self {
-        let mut branches = Vec::new();
-        branches.push(KnowledgeBranch {
+        let branches = vec![KnowledgeBranch {
             name: "main".into(),
             created_at: Instant::now(),
             commit_count: 0,
             head_cid: None,
-        });
+        }];
 
         Self {
             stop_flag,
@@ -531,31 +980,235 @@ impl GitBranchDaemon {
     }
 
     pub async fn run(&self) {
-        while !self.stop_flag.load(Ordering::SeqCst) {
-            // Periodically create knowledge snapshots
-            // This would commit current state to the branch
-
-            {
-                let mut status = self.status.lock().unwrap();
-                status.cycles += 1;
-                status.last_cycle = Some(Instant::now());
-            }
+        run_loop(self, &self.stop_flag, &self.status).await
+    }
+}
 
-            tokio::time::sleep(Duration::from_secs(5)).await;
-        }
+impl DaemonTask for GitBranchDaemon {
+    fn step(&self) -> bool {
+        // Periodically create knowledge snapshots
+        // This would commit current state to the branch
+        false
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(5)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::AtomicU32;
 
-    #[test]
-    fn test_daemon_manager() {
+    fn fast() -> SupervisorConfig {
+        SupervisorConfig {
+            backoff_initial: Duration::from_millis(5),
+            backoff_max: Duration::from_millis(20),
+            ..Default::default()
+        }
+    }
+
+    /// Panics on its first `panics` steps
+    struct Flaky {
+        panics: u32,
+        steps: AtomicU32,
+    }
+
+    impl DaemonTask for Flaky {
+        fn step(&self) -> bool {
+            let n = self.steps.fetch_add(1, Ordering::SeqCst);
+            if n < self.panics {
+                panic!("flaky step {}", n);
+            }
+            true
+        }
+
+        fn interval(&self) -> Duration {
+            Duration::from_millis(1)
+        }
+    }
+
+    async fn wait_for(manager: &DaemonManager, name: &str, done: impl Fn(&DaemonStatus) -> bool) {
+        for _ in 0..200 {
+            if done(&manager.status(name).unwrap()) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("timed out waiting on {}: {:?}", name, manager.status(name));
+    }
+
+    #[tokio::test]
+    async fn test_daemon_manager() {
         let mut manager = DaemonManager::new();
         manager.start();
 
         let name = manager.spawn(DaemonType::VectorChain).unwrap();
         assert!(manager.status(&name).is_some());
     }
+
+    #[test]
+    fn test_spawn_needs_runtime() {
+        let mut manager = DaemonManager::new();
+        assert!(manager.spawn(DaemonType::Awareness).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_vector_daemon_processes_queue() {
+        let mut manager = DaemonManager::new();
+        let name = manager.spawn(DaemonType::VectorChain).unwrap();
+        assert!(manager.enqueue_vector(VectorJob { id: "a".into(), content: "rust ownership".into(), priority: 1 }));
+
+        wait_for(&manager, &name, |s| s.metrics.vectors_computed == 1).await;
+        let status = manager.status(&name).unwrap();
+        assert!(status.cycles >= 1 && status.metrics.busy_cycles >= 1);
+
+        let Some(DaemonWorker::VectorChain(daemon)) = manager.worker(&name) else { panic!() };
+        assert_eq!(daemon.vector("a").unwrap().len(), 384);
+
+        manager.shutdown(Duration::from_secs(2)).await.unwrap();
+        assert!(!manager.status(&name).unwrap().running);
+    }
+
+    #[tokio::test]
+    async fn test_restart_on_panic() {
+        let mut manager = DaemonManager::new();
+        let task = Arc::new(Flaky { panics: 2, steps: AtomicU32::new(0) });
+        let name = manager.spawn_task(DaemonType::Inference, task.clone(), fast()).unwrap();
+
+        wait_for(&manager, &name, |s| s.metrics.busy_cycles >= 3).await;
+        let status = manager.status(&name).unwrap();
+        assert_eq!(status.metrics.panics, 2);
+        assert_eq!(status.metrics.restarts, 2);
+        assert_eq!(status.errors, 2);
+        assert!(status.running);
+
+        // Restarts and the panic message are reported
+        let mut seen = Vec::new();
+        {
+            let events = manager.events();
+            let mut rx = events.lock().unwrap();
+            while let Ok(event) = rx.try_recv() {
+                seen.push(event);
+            }
+        }
+        assert!(seen.iter().any(|e| matches!(e, DaemonEvent::Error { error, .. } if error == "flaky step 0")));
+        assert!(seen.iter().any(|e| matches!(e, DaemonEvent::Restarted { attempt: 2, .. })));
+
+        manager.shutdown(Duration::from_secs(2)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restart_limits() {
+        let mut manager = DaemonManager::new();
+
+        let never = SupervisorConfig { restart: RestartPolicy::Never, ..fast() };
+        let a = manager.spawn_task(DaemonType::Inference, Arc::new(Flaky { panics: 1, steps: AtomicU32::new(0) }), never).unwrap();
+
+        let capped = SupervisorConfig { max_restarts: 3, ..fast() };
+        let b = manager.spawn_task(DaemonType::Inference, Arc::new(Flaky { panics: u32::MAX, steps: AtomicU32::new(0) }), capped).unwrap();
+
+        wait_for(&manager, &a, |s| !s.running).await;
+        wait_for(&manager, &b, |s| !s.running).await;
+        assert_eq!(manager.status(&a).unwrap().metrics.restarts, 0);
+        let b = manager.status(&b).unwrap();
+        assert_eq!((b.metrics.restarts, b.metrics.panics), (3, 4));
+    }
+
+    /// Panics on every `every`-th step
+    struct Periodic {
+        every: u32,
+        steps: AtomicU32,
+    }
+
+    impl DaemonTask for Periodic {
+        fn step(&self) -> bool {
+            let n = self.steps.fetch_add(1, Ordering::SeqCst) + 1;
+            if n.is_multiple_of(self.every) {
+                panic!("periodic step {}", n);
+            }
+            true
+        }
+
+        fn interval(&self) -> Duration {
+            Duration::from_millis(2)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_healthy_run_resets_restarts() {
+        let mut manager = DaemonManager::new();
+        // Each run lasts 4 intervals, well past healthy_after
+        let config = SupervisorConfig { max_restarts: 1, healthy_after: Duration::from_millis(4), ..fast() };
+        let task = Arc::new(Periodic { every: 5, steps: AtomicU32::new(0) });
+        let name = manager.spawn_task(DaemonType::Inference, task, config).unwrap();
+
+        wait_for(&manager, &name, |s| s.metrics.panics >= 3).await;
+        assert!(manager.status(&name).unwrap().running);
+
+        {
+            let events = manager.events();
+            let mut rx = events.lock().unwrap();
+            while let Ok(event) = rx.try_recv() {
+                if let DaemonEvent::Restarted { attempt, .. } = event {
+                    assert_eq!(attempt, 1);
+                }
+            }
+        }
+        manager.shutdown(Duration::from_secs(2)).await.unwrap();
+    }
+
+    #[test]
+    fn test_backoff() {
+        let config = SupervisorConfig {
+            backoff_initial: Duration::from_millis(100),
+            backoff_max: Duration::from_secs(1),
+            ..Default::default()
+        };
+        assert_eq!(config.backoff(1), Duration::from_millis(100));
+        assert_eq!(config.backoff(3), Duration::from_millis(400));
+        assert_eq!(config.backoff(10), Duration::from_secs(1));
+        assert_eq!(config.backoff(100), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_queues() {
+        let mut manager = DaemonManager::new();
+        let vectors = manager.spawn(DaemonType::VectorChain).unwrap();
+        let sync = manager.spawn(DaemonType::IpfsSync).unwrap();
+        let git = manager.spawn(DaemonType::GitBranch).unwrap();
+
+        // Queue more than one cycle can take, then stop straight away
+        for i in 0..20 {
+            manager.enqueue_vector(VectorJob { id: format!("v{}", i), content: format!("note {}", i), priority: (i % 3) as u8 });
+            manager.enqueue_sync(SyncJob { content_type: "text".into(), data: vec![0; 10], priority: 0 });
+        }
+
+        let started = Instant::now();
+        manager.shutdown(Duration::from_secs(2)).await.unwrap();
+        // The git daemon's 5s interval doesn't hold up shutdown
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        let v = manager.status(&vectors).unwrap();
+        let s = manager.status(&sync).unwrap();
+        assert_eq!(v.metrics.vectors_computed, 20);
+        assert_eq!(s.metrics.bytes_synced, 200);
+        assert!(v.metrics.jobs_drained > 0 && s.metrics.jobs_drained > 0);
+        assert!(!manager.status(&git).unwrap().running);
+
+        // Stopped daemons no longer take work
+        assert!(!manager.enqueue_vector(VectorJob { id: "late".into(), content: "x".into(), priority: 0 }));
+    }
+
+    #[test]
+    fn test_vector_priority_order() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let daemon = VectorChainDaemon::new(Arc::new(AtomicBool::new(false)), Arc::new(Mutex::new(DaemonStatus::new())), tx);
+        for (id, priority) in [("low", 0), ("high1", 5), ("mid", 2), ("high2", 5)] {
+            daemon.enqueue(VectorJob { id: id.into(), content: id.into(), priority });
+        }
+        let order: Vec<String> = std::iter::from_fn(|| daemon.queue.lock().unwrap().pop().map(|j| j.id)).collect();
+        assert_eq!(order, ["high1", "high2", "mid", "low"]);
+    }
 }
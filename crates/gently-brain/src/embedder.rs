@@ -3,8 +3,10 @@
 //! Uses fastembed with BAAI/bge-small-en-v1.5 for fast local embeddings.
 //! 384 dimensions, perfect for the Tesseract's 8 faces (48 dims each).
 //!
-//! When fastembed feature is disabled, falls back to hash-based pseudo-embeddings.
+//! When fastembed feature is disabled, uses the built-in `LocalEmbedder`
+//! (hashed n-grams + concept lexicon), same 384-dim shape.
 
+use crate::local_embed::{self, LocalEmbedder};
 use crate::{Error, Result};
 use std::path::Path;
 #[allow(unused_imports)]
@@ -18,7 +20,7 @@ pub struct Embedder {
     #[cfg(feature = "fastembed")]
     model: Option<Arc<TextEmbedding>>,
     #[cfg(not(feature = "fastembed"))]
-    model_path: Option<std::path::PathBuf>,
+    local: LocalEmbedder,
     dimensions: usize,
     loaded: bool,
 }
@@ -30,7 +32,7 @@ impl Embedder {
             #[cfg(feature = "fastembed")]
             model: None,
             #[cfg(not(feature = "fastembed"))]
-            model_path: None,
+            local: LocalEmbedder::new(),
             dimensions: 384,  // bge-small-en-v1.5 dimensions
             loaded: false,
         }
@@ -66,14 +68,29 @@ impl Embedder {
         }
     }
 
-    /// Load the default embedding model (fallback - just marks as loaded)
+    /// Load the default embedding model (built-in, nothing to download)
     #[cfg(not(feature = "fastembed"))]
     pub fn load_default(&mut self) -> Result<()> {
-        tracing::warn!("Fastembed feature not enabled - using simulated embeddings");
+        tracing::info!("Using built-in embedder: {}", local_embed::MODEL_NAME);
         self.loaded = true;
         Ok(())
     }
 
+    /// Name of the active embedding backend
+    pub fn model_name(&self) -> &'static str {
+        if cfg!(feature = "fastembed") {
+            "BAAI/bge-small-en-v1.5"
+        } else {
+            local_embed::MODEL_NAME
+        }
+    }
+
+    /// Learn term weights from a corpus (built-in backend only)
+    pub fn fit(&mut self, corpus: &[&str]) {
+        #[cfg(not(feature = "fastembed"))]
+        self.local.fit(corpus);
+    }
+
     /// Load model from path (legacy compatibility)
     pub fn load(&mut self, _path: &Path) -> Result<()> {
         // Path is ignored - we use fastembed's model management
@@ -114,8 +131,7 @@ impl Embedder {
             return Err(Error::ModelNotFound("Embedder not loaded".into()));
         }
 
-        // Use simulated embedding
-        Ok(self.simulate_embedding(text))
+        Ok(self.local.embed(text))
     }
 
     /// Embed multiple texts (batched for efficiency)
@@ -161,35 +177,6 @@ impl Embedder {
 
         faces
     }
-
-    /// Simulate embedding (used when fastembed not available)
-    #[cfg(not(feature = "fastembed"))]
-    fn simulate_embedding(&self, text: &str) -> Vec<f32> {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-
-        let mut embedding = vec![0.0f32; self.dimensions];
-
-        // Generate deterministic pseudo-embedding from content
-        for (i, chunk) in text.as_bytes().chunks(4).enumerate() {
-            let mut hasher = DefaultHasher::new();
-            chunk.hash(&mut hasher);
-            let hash = hasher.finish();
-
-            let idx = i % self.dimensions;
-            embedding[idx] = ((hash % 1000) as f32 / 500.0) - 1.0;
-        }
-
-        // Normalize
-        let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm > 0.0 {
-            for x in &mut embedding {
-                *x /= norm;
-            }
-        }
-
-        embedding
-    }
 }
 
 impl Default for Embedder {
@@ -239,6 +226,21 @@ mod tests {
         assert!(embedder.embed("test").is_err());
     }
 
+    #[cfg(not(feature = "fastembed"))]
+    #[test]
+    fn test_local_backend() {
+        let mut embedder = Embedder::new();
+        embedder.load_default().unwrap();
+        assert_eq!(embedder.model_name(), local_embed::MODEL_NAME);
+
+        let a = embedder.embed("the server crashed").unwrap();
+        let b = embedder.embed("service failure after a panic").unwrap();
+        let c = embedder.embed("a recipe for soup").unwrap();
+        assert_eq!(a.len(), embedder.dimensions());
+        assert!(cosine_similarity(&a, &b) > cosine_similarity(&a, &c));
+        assert_eq!(embedder.project_to_tesseract(&a)[7][47], a[383]);
+    }
+
     #[test]
     fn test_cosine_similarity() {
         let a = vec![1.0, 0.0, 0.0];
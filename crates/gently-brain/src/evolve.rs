@@ -22,6 +22,9 @@
 
 use gently_core::{Hash, Kind, Blob, Manifest, BlobStore, TAG_NEXT, TAG_PREV, TAG_PARENT};
 use crate::lora::{LoraChain, LoraConfig, LoraWeights};
+use crate::tensor::{DType, Tensor, TensorBlob};
+use crate::train::{LoraTrainer, TrainConfig, TrainReport};
+use crate::{Error, Result};
 use serde::{Serialize, Deserialize};
 use std::collections::VecDeque;
 
@@ -76,6 +79,7 @@ pub struct Evolver {
     state: EvolveState,
     cycle: u64,
     history: Vec<Hash>,  // cycle result hashes
+    last_report: Option<TrainReport>,
 
     // Config
     config: EvolveConfig,
@@ -89,6 +93,7 @@ pub struct EvolveConfig {
     pub loss_threshold: f32,
     pub auto_fuse: bool,
     pub max_chain_length: usize,
+    pub train: TrainConfig,
 }
 
 impl Default for EvolveConfig {
@@ -100,6 +105,7 @@ impl Default for EvolveConfig {
             loss_threshold: 0.1,
             auto_fuse: true,
             max_chain_length: 32,
+            train: TrainConfig::default(),
         }
     }
 }
@@ -114,6 +120,7 @@ impl Evolver {
             state: EvolveState::Idle,
             cycle: 0,
             history: Vec::new(),
+            last_report: None,
             config,
         }
     }
@@ -123,6 +130,11 @@ impl Evolver {
         self.lora_chain.set_base(base_weights)
     }
 
+    /// Initialize with named base tensors
+    pub fn init_tensors(&mut self, base: &TensorBlob) -> Hash {
+        self.lora_chain.set_base_tensors(base)
+    }
+
     /// Harvest pattern from inference
     pub fn harvest(&mut self, input: Vec<f32>, target: Vec<f32>, loss: f32, source: &str) {
         self.state = EvolveState::Harvesting;
@@ -148,19 +160,27 @@ impl Evolver {
     }
 
     /// Run one evolution cycle
-    pub fn evolve(&mut self) -> Option<CycleResult> {
+    ///
+    /// `Ok(None)` when too few patterns have been harvested; a failed
+    /// training run returns the error and keeps the patterns.
+    pub fn evolve(&mut self) -> Result<Option<CycleResult>> {
         if !self.ready_to_train() {
-            return None;
+            return Ok(None);
         }
 
         self.state = EvolveState::Training;
-        self.cycle += 1;
-
-        // 1. Calculate loss before
-        let loss_before = self.avg_loss();
 
-        // 2. Train new LoRA adapter from patterns
-        let (adapter_hash, weights) = self.train_adapter();
+        // 1-2. Train new LoRA adapter from patterns, measuring held-out loss
+        let (adapter_hash, weights, report) = match self.train_adapter() {
+            Ok(trained) => trained,
+            Err(e) => {
+                self.state = EvolveState::Idle;
+                return Err(e);
+            }
+        };
+        self.cycle += 1;
+        let (loss_before, loss_after) = (report.loss_before, report.loss_after);
+        self.last_report = Some(report);
 
         // 3. Add to chain
         let config = LoraConfig {
@@ -180,9 +200,8 @@ impl Evolver {
             adapter_hash
         };
 
-        // 5. Validate
+        // 5. Validate (held-out loss came from training)
         self.state = EvolveState::Validating;
-        let loss_after = self.validate_loss();
 
         // 6. Store cycle result
         let result = CycleResult {
@@ -212,43 +231,36 @@ impl Evolver {
         self.patterns.clear();
         self.state = EvolveState::Idle;
 
-        Some(result)
+        Ok(Some(result))
     }
 
-    /// Train adapter from patterns (placeholder for real training)
-    fn train_adapter(&self) -> (Hash, LoraWeights) {
-        // Real implementation would:
-        // 1. Build gradient from patterns
-        // 2. SVD decompose into low-rank A, B
-        // 3. Return trained weights
-
-        let r = self.config.rank as usize;
-        let dim = 768; // typical embedding dim
-
-        // Mock: create adapter from pattern statistics
-        let mut a = vec![0.0f32; dim * r];
-        let mut b = vec![0.0f32; r * dim];
-
-        for (i, pattern) in self.patterns.iter().enumerate() {
-            let scale = pattern.loss * 0.01;
-            for j in 0..r.min(pattern.input.len()) {
-                a[i % (dim * r)] += pattern.input.get(j).unwrap_or(&0.0) * scale;
-                b[i % (r * dim)] += pattern.target.get(j).unwrap_or(&0.0) * scale;
-            }
-        }
-
-        let weights = LoraWeights {
-            a,
-            b,
-            shape_a: (dim, r),
-            shape_b: (r, dim),
-        };
+    /// Train adapter from patterns
+    ///
+    /// Adapts the base tensor shaped `[target_len, input_len]` if the chain
+    /// has one, otherwise trains against a zero base.
+    fn train_adapter(&self) -> Result<(Hash, LoraWeights, TrainReport)> {
+        let patterns: Vec<Pattern> = self.patterns.iter().cloned().collect();
+        let n_in = patterns.iter().map(|p| p.input.len()).max().unwrap_or(0);
+        let n_out = patterns.iter().map(|p| p.target.len()).max().unwrap_or(0);
+
+        let base = self.lora_chain.base_tensors().ok();
+        let target: Option<(String, &Tensor)> = base.as_ref().and_then(|b| {
+            b.iter()
+                .find(|(_, t)| t.shape() == [n_out, n_in])
+                .map(|(name, t)| (name.to_string(), t))
+        });
+
+        let trainer = LoraTrainer::new(self.config.train.clone());
+        let (mut weights, report) = trainer
+            .train(&patterns, self.config.rank as usize, self.config.alpha, target.as_ref().map(|(_, t)| *t))?;
+        weights.target = target.map(|(name, _)| name);
 
         // Hash the weights
-        let blob = Blob::new(Kind::Delta, serde_json::to_vec(&weights).unwrap());
-        let hash = Blob::compute_hash(&blob.data);
+        let data = serde_json::to_vec(&weights)
+            .map_err(|e| Error::InferenceFailed(format!("serialize adapter: {}", e)))?;
+        let hash = Blob::compute_hash(&data);
 
-        (hash, weights)
+        Ok((hash, weights, report))
     }
 
     /// Smart fusion - prune old adapters if chain too long
@@ -276,12 +288,6 @@ impl Evolver {
         self.patterns.iter().map(|p| p.loss).sum::<f32>() / self.patterns.len() as f32
     }
 
-    fn validate_loss(&self) -> f32 {
-        // Would run inference on held-out set
-        // For now, return improved estimate
-        self.avg_loss() * 0.9
-    }
-
     /// Get evolution history
     pub fn history(&self) -> Vec<CycleResult> {
         self.history.iter()
@@ -292,6 +298,11 @@ impl Evolver {
             .collect()
     }
 
+    /// Training details of the latest cycle
+    pub fn last_report(&self) -> Option<&TrainReport> {
+        self.last_report.as_ref()
+    }
+
     /// Current state
     pub fn state(&self) -> EvolveState {
         self.state
@@ -333,7 +344,7 @@ impl EvolveLoop {
     }
 
     /// Run one tick
-    pub fn tick(&mut self, inputs: &[(Vec<f32>, Vec<f32>, f32)]) -> Option<CycleResult> {
+    pub fn tick(&mut self, inputs: &[(Vec<f32>, Vec<f32>, f32)]) -> Result<Option<CycleResult>> {
         // Harvest all inputs
         for (input, target, loss) in inputs {
             self.evolver.harvest(input.clone(), target.clone(), *loss, "inference");
@@ -343,7 +354,7 @@ impl EvolveLoop {
         if self.evolver.ready_to_train() {
             self.evolver.evolve()
         } else {
-            None
+            Ok(None)
         }
     }
 
@@ -397,7 +408,7 @@ mod tests {
             );
         }
 
-        let result = evolver.evolve();
+        let result = evolver.evolve().unwrap();
         assert!(result.is_some());
         assert_eq!(evolver.cycle(), 1);
     }
@@ -419,9 +430,57 @@ mod tests {
                 .map(|i| (vec![i as f32; 5], vec![i as f32; 5], 0.3))
                 .collect();
 
-            if let Some(result) = evo_loop.tick(&inputs) {
+            if let Some(result) = evo_loop.tick(&inputs).unwrap() {
                 assert!(result.loss_after <= result.loss_before);
             }
         }
     }
+
+    #[test]
+    fn test_evolve_trains_base_layer() {
+        let config = EvolveConfig {
+            rank: 4,
+            alpha: 8.0,
+            min_patterns: 120,
+            train: TrainConfig { epochs: 30, patience: 0, ..Default::default() },
+            ..Default::default()
+        };
+        let mut evolver = Evolver::new(config);
+        let mut base = TensorBlob::new();
+        base.insert("head.weight", Tensor::zeros(vec![3, 6], DType::F32).unwrap());
+        base.insert("other.weight", Tensor::zeros(vec![5, 5], DType::F32).unwrap());
+        evolver.init_tensors(&base);
+
+        // Class = which of the first three features is largest
+        for i in 0..120 {
+            let input: Vec<f32> = (0..6).map(|j| (((i * 7 + j * 13) % 17) as f32 / 8.5) - 1.0).collect();
+            let best = (0..3).max_by(|&a, &b| input[a].total_cmp(&input[b])).unwrap();
+            let mut target = vec![0.0; 3];
+            target[best] = 1.0;
+            evolver.harvest(input, target, 1.0, "test");
+        }
+
+        let result = evolver.evolve().unwrap().unwrap();
+        assert!(result.loss_after < result.loss_before * 0.8, "{:?}", result);
+        assert!(evolver.last_report().unwrap().best_epoch > 0);
+
+        // The trained adapter landed on the matching base tensor only
+        let fused = evolver.lora_chain.tensors(&result.fused_hash).unwrap();
+        assert!(fused.get("head.weight").unwrap().data().iter().any(|&w| w != 0.0));
+        assert!(fused.get("other.weight").unwrap().data().iter().all(|&w| w == 0.0));
+    }
+
+    #[test]
+    fn test_evolve_reports_training_errors() {
+        let config = EvolveConfig { rank: 0, min_patterns: 5, ..Default::default() };
+        let mut evolver = Evolver::new(config);
+        for i in 0..5 {
+            evolver.harvest(vec![i as f32; 4], vec![i as f32; 4], 0.5, "test");
+        }
+
+        assert!(evolver.evolve().is_err());
+        assert_eq!(evolver.cycle(), 0);
+        assert_eq!(evolver.patterns.len(), 5);
+        assert_eq!(evolver.state(), EvolveState::Idle);
+    }
 }
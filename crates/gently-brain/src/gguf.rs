@@ -0,0 +1,1025 @@
+//! GGUF Model Files
+//!
+//! Pure-Rust reader/writer for the GGUF container used by llama.cpp,
+//! plus the block quantization formats local inference needs.
+//!
+//! ```text
+//! ┌────────┬─────────┬──────────┬──────────┐
+//! │ "GGUF" │ version │ n_tensor │ n_kv     │  header
+//! ├────────┴─────────┴──────────┴──────────┤
+//! │ key/value metadata (arch, tokenizer…)  │
+//! ├────────────────────────────────────────┤
+//! │ tensor infos (name, dims, type, off)   │
+//! ├──────────────── align ─────────────────┤
+//! │ tensor data (F32/F16/Q8_0/Q4_0/Q4_K…)  │
+//! └────────────────────────────────────────┘
+//! ```
+//!
+//! Quantized weights are kept as raw blocks; `dot_row` multiplies a
+//! quantized row against an f32 vector without expanding the matrix.
+
+use crate::{Error, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::ops::Range;
+use std::path::Path;
+
+/// "GGUF" little-endian
+pub const GGUF_MAGIC: u32 = 0x4655_4747;
+
+/// Version written by `GgufWriter`
+pub const GGUF_VERSION: u32 = 3;
+
+/// Default tensor data alignment
+pub const DEFAULT_ALIGNMENT: usize = 32;
+
+/// Deepest nesting accepted for metadata arrays
+const MAX_ARRAY_DEPTH: usize = 8;
+
+/// Elements per super-block for K-quants
+const QK_K: usize = 256;
+
+/// Tensor element types we can read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum GgmlType {
+    F32,
+    F16,
+    Q4_0,
+    Q4_1,
+    Q8_0,
+    Q4_K,
+    Q6_K,
+}
+
+impl GgmlType {
+    pub fn from_id(id: u32) -> Result<Self> {
+        Ok(match id {
+            0 => Self::F32,
+            1 => Self::F16,
+            2 => Self::Q4_0,
+            3 => Self::Q4_1,
+            8 => Self::Q8_0,
+            12 => Self::Q4_K,
+            14 => Self::Q6_K,
+            other => {
+                return Err(Error::InvalidModel(format!("unsupported tensor type {}", other)))
+            }
+        })
+    }
+
+    pub fn id(&self) -> u32 {
+        match self {
+            Self::F32 => 0,
+            Self::F16 => 1,
+            Self::Q4_0 => 2,
+            Self::Q4_1 => 3,
+            Self::Q8_0 => 8,
+            Self::Q4_K => 12,
+            Self::Q6_K => 14,
+        }
+    }
+
+    /// Elements per block
+    pub fn block_size(&self) -> usize {
+        match self {
+            Self::F32 | Self::F16 => 1,
+            Self::Q4_0 | Self::Q4_1 | Self::Q8_0 => 32,
+            Self::Q4_K | Self::Q6_K => QK_K,
+        }
+    }
+
+    /// Bytes per block
+    pub fn type_size(&self) -> usize {
+        match self {
+            Self::F32 => 4,
+            Self::F16 => 2,
+            Self::Q4_0 => 18,
+            Self::Q4_1 => 20,
+            Self::Q8_0 => 34,
+            Self::Q4_K => 144,
+            Self::Q6_K => 210,
+        }
+    }
+
+    /// Bytes needed for `n` elements (must be a whole number of blocks)
+    pub fn bytes_for(&self, n: usize) -> Result<usize> {
+        if !n.is_multiple_of(self.block_size()) {
+            return Err(Error::InvalidModel(format!(
+                "{} elements is not a multiple of the {:?} block size",
+                n, self
+            )));
+        }
+        (n / self.block_size())
+            .checked_mul(self.type_size())
+            .ok_or_else(|| Error::InvalidModel(format!("{} {:?} elements overflow", n, self)))
+    }
+}
+
+/// Metadata value
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetaValue {
+    U8(u8),
+    I8(i8),
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    I32(i32),
+    F32(f32),
+    Bool(bool),
+    String(String),
+    Array(Vec<MetaValue>),
+    U64(u64),
+    I64(i64),
+    F64(f64),
+}
+
+impl MetaValue {
+    fn type_id(&self) -> u32 {
+        match self {
+            Self::U8(_) => 0,
+            Self::I8(_) => 1,
+            Self::U16(_) => 2,
+            Self::I16(_) => 3,
+            Self::U32(_) => 4,
+            Self::I32(_) => 5,
+            Self::F32(_) => 6,
+            Self::Bool(_) => 7,
+            Self::String(_) => 8,
+            Self::Array(_) => 9,
+            Self::U64(_) => 10,
+            Self::I64(_) => 11,
+            Self::F64(_) => 12,
+        }
+    }
+
+    /// Any integer type as u64 (negative values are rejected)
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Self::U8(v) => Some(v as u64),
+            Self::U16(v) => Some(v as u64),
+            Self::U32(v) => Some(v as u64),
+            Self::U64(v) => Some(v),
+            Self::I8(v) if v >= 0 => Some(v as u64),
+            Self::I16(v) if v >= 0 => Some(v as u64),
+            Self::I32(v) if v >= 0 => Some(v as u64),
+            Self::I64(v) if v >= 0 => Some(v as u64),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Self::I8(v) => Some(v as i64),
+            Self::I16(v) => Some(v as i64),
+            Self::I32(v) => Some(v as i64),
+            Self::I64(v) => Some(v),
+            _ => self.as_u64().and_then(|v| i64::try_from(v).ok()),
+        }
+    }
+
+    pub fn as_f32(&self) -> Option<f32> {
+        match *self {
+            Self::F32(v) => Some(v),
+            Self::F64(v) => Some(v as f32),
+            _ => self.as_i64().map(|v| v as f32),
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Self::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[MetaValue]> {
+        match self {
+            Self::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+/// Tensor directory entry
+#[derive(Debug, Clone)]
+pub struct TensorInfo {
+    pub name: String,
+    /// ggml order: `dims[0]` is the row length
+    pub dims: Vec<u64>,
+    pub dtype: GgmlType,
+    /// Offset from the start of the data section
+    pub offset: u64,
+}
+
+impl TensorInfo {
+    pub fn n_elements(&self) -> Result<usize> {
+        dims_product(&self.dims)
+            .ok_or_else(|| Error::InvalidModel(format!("tensor {} dims overflow", self.name)))
+    }
+
+    pub fn byte_len(&self) -> Result<usize> {
+        self.dtype.bytes_for(self.n_elements()?)
+    }
+
+    /// Row length (innermost dimension)
+    pub fn cols(&self) -> usize {
+        self.dims.first().copied().unwrap_or(1) as usize
+    }
+
+    /// Number of rows (product of the outer dimensions)
+    ///
+    /// Saturates; parsed files have already been checked for overflow.
+    pub fn rows(&self) -> usize {
+        dims_product(self.dims.get(1..).unwrap_or_default()).unwrap_or(usize::MAX)
+    }
+}
+
+/// Checked product of tensor dims, `None` if any partial product overflows
+///
+/// A zero dim does not hide an overflow in the others.
+fn dims_product(dims: &[u64]) -> Option<usize> {
+    let mut product = 1usize;
+    let mut zero = false;
+    for &dim in dims {
+        let dim = usize::try_from(dim).ok()?;
+        if dim == 0 {
+            zero = true;
+        } else {
+            product = product.checked_mul(dim)?;
+        }
+    }
+    Some(if zero { 0 } else { product })
+}
+
+/// A parsed GGUF file, tensor data held in memory
+pub struct GgufFile {
+    pub version: u32,
+    pub metadata: BTreeMap<String, MetaValue>,
+    pub tensors: Vec<TensorInfo>,
+    index: HashMap<String, usize>,
+    bytes: Vec<u8>,
+    data_offset: usize,
+}
+
+impl GgufFile {
+    /// Read a GGUF file from disk
+    pub fn open(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(Error::ModelNotFound(path.display().to_string()));
+        }
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// Parse a GGUF image
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        let mut r = Reader { bytes: &bytes, pos: 0 };
+
+        if r.u32()? != GGUF_MAGIC {
+            return Err(Error::InvalidModel("not a GGUF file".into()));
+        }
+        let version = r.u32()?;
+        if !(2..=3).contains(&version) {
+            return Err(Error::InvalidModel(format!("unsupported GGUF version {}", version)));
+        }
+
+        let n_tensors = r.u64()?;
+        let n_kv = r.u64()?;
+
+        let mut metadata = BTreeMap::new();
+        for _ in 0..n_kv {
+            let key = r.string()?;
+            let ty = r.u32()?;
+            let value = r.value(ty, 0)?;
+            metadata.insert(key, value);
+        }
+
+        let mut tensors = Vec::new();
+        let mut index = HashMap::new();
+        for _ in 0..n_tensors {
+            let name = r.string()?;
+            let n_dims = r.u32()? as usize;
+            if n_dims > 4 {
+                return Err(Error::InvalidModel(format!("tensor {} has {} dims", name, n_dims)));
+            }
+            let dims = (0..n_dims).map(|_| r.u64()).collect::<Result<Vec<_>>>()?;
+            let dtype = GgmlType::from_id(r.u32()?)?;
+            let offset = r.u64()?;
+            if dims_product(&dims).is_none() {
+                return Err(Error::InvalidModel(format!("tensor {} dims overflow", name)));
+            }
+            index.insert(name.clone(), tensors.len());
+            tensors.push(TensorInfo { name, dims, dtype, offset });
+        }
+
+        let alignment = metadata
+            .get("general.alignment")
+            .and_then(MetaValue::as_u64)
+            .map_or(Some(DEFAULT_ALIGNMENT), |a| usize::try_from(a).ok())
+            .filter(|a| a.is_power_of_two())
+            .ok_or_else(|| Error::InvalidModel("bad alignment".into()))?;
+        let data_offset = r
+            .pos
+            .checked_next_multiple_of(alignment)
+            .ok_or_else(|| Error::InvalidModel("bad alignment".into()))?;
+
+        let file = Self { version, metadata, tensors, index, bytes, data_offset };
+        for info in &file.tensors {
+            file.tensor_range(info)?;
+        }
+        Ok(file)
+    }
+
+    /// Byte range of a tensor, checked against the file
+    fn tensor_range(&self, info: &TensorInfo) -> Result<Range<usize>> {
+        let past_end = || Error::InvalidModel(format!("tensor {} runs past end of file", info.name));
+        let start = usize::try_from(info.offset)
+            .ok()
+            .and_then(|offset| self.data_offset.checked_add(offset))
+            .ok_or_else(past_end)?;
+        let end = start.checked_add(info.byte_len()?).ok_or_else(past_end)?;
+        if end > self.bytes.len() {
+            return Err(past_end());
+        }
+        Ok(start..end)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&MetaValue> {
+        self.metadata.get(key)
+    }
+
+    pub fn get_u64(&self, key: &str) -> Option<u64> {
+        self.get(key).and_then(MetaValue::as_u64)
+    }
+
+    pub fn get_f32(&self, key: &str) -> Option<f32> {
+        self.get(key).and_then(MetaValue::as_f32)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(MetaValue::as_str)
+    }
+
+    /// `general.architecture` (e.g. "llama")
+    pub fn architecture(&self) -> Option<&str> {
+        self.get_str("general.architecture")
+    }
+
+    pub fn tensor(&self, name: &str) -> Option<&TensorInfo> {
+        self.index.get(name).map(|&i| &self.tensors[i])
+    }
+
+    /// Raw bytes of a tensor
+    pub fn tensor_data(&self, info: &TensorInfo) -> Result<&[u8]> {
+        Ok(&self.bytes[self.tensor_range(info)?])
+    }
+
+    /// Everything after the header; tensor offsets index into this
+    pub fn data_section(&self) -> &[u8] {
+        // Metadata-only files may end before the aligned data offset
+        self.bytes.get(self.data_offset..).unwrap_or_default()
+    }
+
+    /// Tensor expanded to f32
+    pub fn tensor_f32(&self, name: &str) -> Result<Vec<f32>> {
+        let info = self
+            .tensor(name)
+            .ok_or_else(|| Error::InvalidModel(format!("missing tensor {}", name)))?;
+        let mut out = vec![0.0; info.n_elements()?];
+        dequantize(info.dtype, self.tensor_data(info)?, &mut out);
+        Ok(out)
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(n).filter(|&e| e <= self.bytes.len()).ok_or_else(|| {
+            Error::InvalidModel(format!("truncated GGUF header at byte {}", self.pos))
+        })?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(self.take(N)?);
+        Ok(buf)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let len = usize::try_from(self.u64()?)
+            .map_err(|_| Error::InvalidModel("metadata string too long".into()))?;
+        let raw = self.take(len)?;
+        String::from_utf8(raw.to_vec())
+            .map_err(|_| Error::InvalidModel("metadata string is not UTF-8".into()))
+    }
+
+    fn value(&mut self, ty: u32, depth: usize) -> Result<MetaValue> {
+        Ok(match ty {
+            0 => MetaValue::U8(self.array::<1>()?[0]),
+            1 => MetaValue::I8(self.array::<1>()?[0] as i8),
+            2 => MetaValue::U16(u16::from_le_bytes(self.array()?)),
+            3 => MetaValue::I16(i16::from_le_bytes(self.array()?)),
+            4 => MetaValue::U32(self.u32()?),
+            5 => MetaValue::I32(i32::from_le_bytes(self.array()?)),
+            6 => MetaValue::F32(f32::from_le_bytes(self.array()?)),
+            7 => MetaValue::Bool(self.array::<1>()?[0] != 0),
+            8 => MetaValue::String(self.string()?),
+            9 => {
+                if depth >= MAX_ARRAY_DEPTH {
+                    return Err(Error::InvalidModel("metadata arrays nested too deep".into()));
+                }
+                let item_ty = self.u32()?;
+                let len = self.u64()?;
+                // Every item takes at least `min_size` bytes; check before allocating
+                let remaining = (self.bytes.len() - self.pos) / min_size(item_ty);
+                let len = usize::try_from(len)
+                    .ok()
+                    .filter(|&len| len <= remaining)
+                    .ok_or_else(|| Error::InvalidModel("metadata array runs past end of file".into()))?;
+                let mut items = Vec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(self.value(item_ty, depth + 1)?);
+                }
+                MetaValue::Array(items)
+            }
+            10 => MetaValue::U64(self.u64()?),
+            11 => MetaValue::I64(i64::from_le_bytes(self.array()?)),
+            12 => MetaValue::F64(f64::from_le_bytes(self.array()?)),
+            other => {
+                return Err(Error::InvalidModel(format!("unknown metadata type {}", other)))
+            }
+        })
+    }
+}
+
+/// Smallest encoding of a metadata value of type `ty`
+fn min_size(ty: u32) -> usize {
+    match ty {
+        2 | 3 => 2,
+        4..=6 => 4,
+        8 | 10..=12 => 8,
+        9 => 12,
+        _ => 1,
+    }
+}
+
+/// Builds GGUF images (fixtures, exported adapters)
+#[derive(Default)]
+pub struct GgufWriter {
+    metadata: Vec<(String, MetaValue)>,
+    tensors: Vec<(String, Vec<u64>, GgmlType, Vec<u8>)>,
+}
+
+impl GgufWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn metadata(mut self, key: &str, value: MetaValue) -> Self {
+        self.metadata.push((key.to_string(), value));
+        self
+    }
+
+    /// Add a tensor from raw (already encoded) bytes
+    pub fn tensor(mut self, name: &str, dims: &[u64], dtype: GgmlType, data: Vec<u8>) -> Result<Self> {
+        let n = dims.iter().product::<u64>() as usize;
+        if dtype.bytes_for(n)? != data.len() {
+            return Err(Error::InvalidModel(format!("tensor {} has the wrong byte length", name)));
+        }
+        self.tensors.push((name.to_string(), dims.to_vec(), dtype, data));
+        Ok(self)
+    }
+
+    /// Add a tensor, quantizing f32 values to `dtype`
+    pub fn tensor_f32(self, name: &str, dims: &[u64], dtype: GgmlType, values: &[f32]) -> Result<Self> {
+        let data = quantize(dtype, values)?;
+        self.tensor(name, dims, dtype, data)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&GGUF_MAGIC.to_le_bytes());
+        out.extend_from_slice(&GGUF_VERSION.to_le_bytes());
+        out.extend_from_slice(&(self.tensors.len() as u64).to_le_bytes());
+        out.extend_from_slice(&(self.metadata.len() as u64).to_le_bytes());
+
+        for (key, value) in &self.metadata {
+            write_string(&mut out, key);
+            out.extend_from_slice(&value.type_id().to_le_bytes());
+            write_value(&mut out, value);
+        }
+
+        let mut offset = 0usize;
+        for (name, dims, dtype, data) in &self.tensors {
+            write_string(&mut out, name);
+            out.extend_from_slice(&(dims.len() as u32).to_le_bytes());
+            for d in dims {
+                out.extend_from_slice(&d.to_le_bytes());
+            }
+            out.extend_from_slice(&dtype.id().to_le_bytes());
+            out.extend_from_slice(&(offset as u64).to_le_bytes());
+            offset = (offset + data.len()).next_multiple_of(DEFAULT_ALIGNMENT);
+        }
+
+        out.resize(out.len().next_multiple_of(DEFAULT_ALIGNMENT), 0);
+        for (_, _, _, data) in &self.tensors {
+            out.extend_from_slice(data);
+            out.resize(out.len().next_multiple_of(DEFAULT_ALIGNMENT), 0);
+        }
+        out
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+fn write_string(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u64).to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn write_value(out: &mut Vec<u8>, value: &MetaValue) {
+    match value {
+        MetaValue::U8(v) => out.push(*v),
+        MetaValue::I8(v) => out.push(*v as u8),
+        MetaValue::U16(v) => out.extend_from_slice(&v.to_le_bytes()),
+        MetaValue::I16(v) => out.extend_from_slice(&v.to_le_bytes()),
+        MetaValue::U32(v) => out.extend_from_slice(&v.to_le_bytes()),
+        MetaValue::I32(v) => out.extend_from_slice(&v.to_le_bytes()),
+        MetaValue::F32(v) => out.extend_from_slice(&v.to_le_bytes()),
+        MetaValue::Bool(v) => out.push(*v as u8),
+        MetaValue::String(s) => write_string(out, s),
+        MetaValue::Array(items) => {
+            let item_ty = items.first().map(MetaValue::type_id).unwrap_or(0);
+            out.extend_from_slice(&item_ty.to_le_bytes());
+            out.extend_from_slice(&(items.len() as u64).to_le_bytes());
+            for item in items {
+                write_value(out, item);
+            }
+        }
+        MetaValue::U64(v) => out.extend_from_slice(&v.to_le_bytes()),
+        MetaValue::I64(v) => out.extend_from_slice(&v.to_le_bytes()),
+        MetaValue::F64(v) => out.extend_from_slice(&v.to_le_bytes()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Half precision
+// ---------------------------------------------------------------------------
+
+pub fn f16_to_f32(h: u16) -> f32 {
+    let sign = ((h >> 15) as u32) << 31;
+    let exp = ((h >> 10) & 0x1f) as u32;
+    let mant = (h & 0x3ff) as u32;
+    let bits = match (exp, mant) {
+        (0, 0) => sign,
+        (0, _) => {
+            // Subnormal: renormalise the mantissa
+            let mut e = 127 - 15 + 1;
+            let mut m = mant;
+            while m & 0x400 == 0 {
+                m <<= 1;
+                e -= 1;
+            }
+            sign | (e << 23) | ((m & 0x3ff) << 13)
+        }
+        (0x1f, 0) => sign | 0x7f80_0000,
+        (0x1f, _) => sign | 0x7fc0_0000 | (mant << 13),
+        _ => sign | ((exp + 127 - 15) << 23) | (mant << 13),
+    };
+    f32::from_bits(bits)
+}
+
+/// Round-to-nearest-even conversion to half precision
+pub fn f32_to_f16(x: f32) -> u16 {
+    let bits = x.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mant = bits & 0x7f_ffff;
+
+    if exp == 0xff {
+        return sign | 0x7c00 | if mant != 0 { 0x200 } else { 0 };
+    }
+    let e = exp - 127 + 15;
+    if e >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if e <= 0 {
+        if e < -10 {
+            return sign;
+        }
+        let m = mant | 0x80_0000;
+        let shift = (14 - e) as u32;
+        let half = m >> shift;
+        let rem = m & ((1 << shift) - 1);
+        let halfway = 1 << (shift - 1);
+        let rounded = if rem > halfway || (rem == halfway && half & 1 == 1) { half + 1 } else { half };
+        return sign | rounded as u16;
+    }
+    let half = ((e as u32) << 10) | (mant >> 13);
+    let rem = mant & 0x1fff;
+    // A carry out of the mantissa correctly bumps the exponent (up to inf)
+    let rounded = if rem > 0x1000 || (rem == 0x1000 && half & 1 == 1) { half + 1 } else { half };
+    sign | rounded as u16
+}
+
+fn read_f16(bytes: &[u8], at: usize) -> f32 {
+    f16_to_f32(u16::from_le_bytes([bytes[at], bytes[at + 1]]))
+}
+
+// ---------------------------------------------------------------------------
+// Dequantization
+// ---------------------------------------------------------------------------
+
+/// Expand `data` into `out` (`out.len()` elements)
+pub fn dequantize(dtype: GgmlType, data: &[u8], out: &mut [f32]) {
+    let bs = dtype.block_size();
+    let ts = dtype.type_size();
+    for (block, dst) in data.chunks_exact(ts).zip(out.chunks_mut(bs)) {
+        dequantize_block(dtype, block, dst);
+    }
+}
+
+fn dequantize_block(dtype: GgmlType, b: &[u8], y: &mut [f32]) {
+    match dtype {
+        GgmlType::F32 => y[0] = f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        GgmlType::F16 => y[0] = read_f16(b, 0),
+        GgmlType::Q4_0 => {
+            let d = read_f16(b, 0);
+            for j in 0..16 {
+                let q = b[2 + j];
+                y[j] = ((q & 0x0f) as i32 - 8) as f32 * d;
+                y[j + 16] = ((q >> 4) as i32 - 8) as f32 * d;
+            }
+        }
+        GgmlType::Q4_1 => {
+            let d = read_f16(b, 0);
+            let m = read_f16(b, 2);
+            for j in 0..16 {
+                let q = b[4 + j];
+                y[j] = (q & 0x0f) as f32 * d + m;
+                y[j + 16] = (q >> 4) as f32 * d + m;
+            }
+        }
+        GgmlType::Q8_0 => {
+            let d = read_f16(b, 0);
+            for j in 0..32 {
+                y[j] = (b[2 + j] as i8) as f32 * d;
+            }
+        }
+        GgmlType::Q4_K => {
+            let d = read_f16(b, 0);
+            let dmin = read_f16(b, 2);
+            let scales = &b[4..16];
+            let qs = &b[16..144];
+            for (chunk, (q, y)) in qs.chunks_exact(32).zip(y.chunks_exact_mut(64)).enumerate() {
+                let (sc1, m1) = scale_min_k4(2 * chunk, scales);
+                let (sc2, m2) = scale_min_k4(2 * chunk + 1, scales);
+                let (d1, m1) = (d * sc1 as f32, dmin * m1 as f32);
+                let (d2, m2) = (d * sc2 as f32, dmin * m2 as f32);
+                for l in 0..32 {
+                    y[l] = d1 * (q[l] & 0x0f) as f32 - m1;
+                    y[l + 32] = d2 * (q[l] >> 4) as f32 - m2;
+                }
+            }
+        }
+        GgmlType::Q6_K => {
+            let ql = &b[0..128];
+            let qh = &b[128..192];
+            let sc = &b[192..208];
+            let d = read_f16(b, 208);
+            for half in 0..2 {
+                let ql = &ql[64 * half..];
+                let qh = &qh[32 * half..];
+                let sc = &sc[8 * half..];
+                let y = &mut y[128 * half..];
+                for l in 0..32 {
+                    let is = l / 16;
+                    let q1 = ((ql[l] & 0x0f) | ((qh[l] & 3) << 4)) as i32 - 32;
+                    let q2 = ((ql[l + 32] & 0x0f) | (((qh[l] >> 2) & 3) << 4)) as i32 - 32;
+                    let q3 = ((ql[l] >> 4) | (((qh[l] >> 4) & 3) << 4)) as i32 - 32;
+                    let q4 = ((ql[l + 32] >> 4) | (((qh[l] >> 6) & 3) << 4)) as i32 - 32;
+                    y[l] = d * (sc[is] as i8) as f32 * q1 as f32;
+                    y[l + 32] = d * (sc[is + 2] as i8) as f32 * q2 as f32;
+                    y[l + 64] = d * (sc[is + 4] as i8) as f32 * q3 as f32;
+                    y[l + 96] = d * (sc[is + 6] as i8) as f32 * q4 as f32;
+                }
+            }
+        }
+    }
+}
+
+/// 6-bit scale and min for sub-block `j` of a Q4_K super-block
+fn scale_min_k4(j: usize, q: &[u8]) -> (u8, u8) {
+    if j < 4 {
+        (q[j] & 63, q[j + 4] & 63)
+    } else {
+        (
+            (q[j + 4] & 0x0f) | ((q[j - 4] >> 6) << 4),
+            (q[j + 4] >> 4) | ((q[j] >> 6) << 4),
+        )
+    }
+}
+
+/// Dot product of one quantized row with `x`
+pub fn dot_row(dtype: GgmlType, row: &[u8], x: &[f32]) -> f32 {
+    match dtype {
+        GgmlType::F32 => row
+            .chunks_exact(4)
+            .zip(x)
+            .map(|(b, x)| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) * x)
+            .sum(),
+        GgmlType::Q8_0 => row
+            .chunks_exact(34)
+            .zip(x.chunks_exact(32))
+            .map(|(b, x)| {
+                let s: f32 = b[2..].iter().zip(x).map(|(&q, x)| (q as i8) as f32 * x).sum();
+                s * read_f16(b, 0)
+            })
+            .sum(),
+        GgmlType::Q4_0 => row
+            .chunks_exact(18)
+            .zip(x.chunks_exact(32))
+            .map(|(b, x)| {
+                let mut s = 0.0;
+                for j in 0..16 {
+                    let q = b[2 + j];
+                    s += ((q & 0x0f) as i32 - 8) as f32 * x[j];
+                    s += ((q >> 4) as i32 - 8) as f32 * x[j + 16];
+                }
+                s * read_f16(b, 0)
+            })
+            .sum(),
+        _ => {
+            let bs = dtype.block_size();
+            let mut buf = [0.0f32; QK_K];
+            row.chunks_exact(dtype.type_size())
+                .zip(x.chunks_exact(bs))
+                .map(|(b, x)| {
+                    dequantize_block(dtype, b, &mut buf[..bs]);
+                    buf[..bs].iter().zip(x).map(|(a, b)| a * b).sum::<f32>()
+                })
+                .sum()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Quantization
+// ---------------------------------------------------------------------------
+
+/// Encode f32 values as `dtype` (K-quants are read-only)
+pub fn quantize(dtype: GgmlType, values: &[f32]) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(dtype.bytes_for(values.len())?);
+    match dtype {
+        GgmlType::F32 => values.iter().for_each(|v| out.extend_from_slice(&v.to_le_bytes())),
+        GgmlType::F16 => values
+            .iter()
+            .for_each(|v| out.extend_from_slice(&f32_to_f16(*v).to_le_bytes())),
+        GgmlType::Q8_0 => {
+            for x in values.chunks_exact(32) {
+                let amax = x.iter().fold(0.0f32, |m, v| m.max(v.abs()));
+                let d = amax / 127.0;
+                let id = if d != 0.0 { 1.0 / d } else { 0.0 };
+                out.extend_from_slice(&f32_to_f16(d).to_le_bytes());
+                out.extend(x.iter().map(|v| (v * id).round() as i8 as u8));
+            }
+        }
+        GgmlType::Q4_0 => {
+            for x in values.chunks_exact(32) {
+                // Signed value with the largest magnitude maps to -8
+                let max = x.iter().fold(0.0f32, |m, &v| if v.abs() > m.abs() { v } else { m });
+                let d = max / -8.0;
+                let id = if d != 0.0 { 1.0 / d } else { 0.0 };
+                out.extend_from_slice(&f32_to_f16(d).to_le_bytes());
+                let q = |v: f32| ((v * id + 8.5) as i32).clamp(0, 15) as u8;
+                out.extend((0..16).map(|j| q(x[j]) | (q(x[j + 16]) << 4)));
+            }
+        }
+        GgmlType::Q4_1 => {
+            for x in values.chunks_exact(32) {
+                let min = x.iter().copied().fold(f32::INFINITY, f32::min);
+                let max = x.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                let d = (max - min) / 15.0;
+                let id = if d != 0.0 { 1.0 / d } else { 0.0 };
+                out.extend_from_slice(&f32_to_f16(d).to_le_bytes());
+                out.extend_from_slice(&f32_to_f16(min).to_le_bytes());
+                let q = |v: f32| (((v - min) * id + 0.5) as i32).clamp(0, 15) as u8;
+                out.extend((0..16).map(|j| q(x[j]) | (q(x[j + 16]) << 4)));
+            }
+        }
+        GgmlType::Q4_K | GgmlType::Q6_K => {
+            return Err(Error::InvalidModel(format!("cannot quantize to {:?}", dtype)))
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(n: usize) -> Vec<f32> {
+        (0..n).map(|i| ((i * 37 % 101) as f32 - 50.0) / 40.0).collect()
+    }
+
+    #[test]
+    fn test_f16_roundtrip() {
+        for v in [0.0f32, 1.0, -2.5, 0.333, 65504.0, 6.1e-5, 3.0e-7] {
+            let back = f16_to_f32(f32_to_f16(v));
+            assert!((back - v).abs() <= v.abs() * 1e-3 + 1e-7, "{} -> {}", v, back);
+        }
+        assert!(f16_to_f32(f32_to_f16(1e6)).is_infinite());
+    }
+
+    #[test]
+    fn test_quantize_roundtrip() {
+        let values = ramp(64);
+        for (dtype, tol) in [
+            (GgmlType::F32, 0.0),
+            (GgmlType::F16, 2e-3),
+            (GgmlType::Q8_0, 0.02),
+            (GgmlType::Q4_0, 0.2),
+            (GgmlType::Q4_1, 0.1),
+        ] {
+            let data = quantize(dtype, &values).unwrap();
+            assert_eq!(data.len(), dtype.bytes_for(64).unwrap());
+
+            let mut out = vec![0.0; 64];
+            dequantize(dtype, &data, &mut out);
+            for (a, b) in values.iter().zip(&out) {
+                assert!((a - b).abs() <= tol, "{:?}: {} vs {}", dtype, a, b);
+            }
+
+            let x: Vec<f32> = (0..64).map(|i| (i as f32).sin()).collect();
+            let expected: f32 = out.iter().zip(&x).map(|(a, b)| a * b).sum();
+            assert!((dot_row(dtype, &data, &x) - expected).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_dequantize_q4_k() {
+        let mut block = vec![0u8; 144];
+        block[0..2].copy_from_slice(&f32_to_f16(0.5).to_le_bytes());
+        block[2..4].copy_from_slice(&f32_to_f16(0.25).to_le_bytes());
+        // Sub-block 0: scale 2, min 4; sub-block 5: scale 3, min 1 (high bits split)
+        block[4] = 2;
+        block[8] = 4;
+        block[4 + 9] = 0x13;
+        block[16] = 0x07;
+
+        let mut out = vec![0.0; 256];
+        dequantize(GgmlType::Q4_K, &block, &mut out);
+        assert_eq!(out[0], 0.5 * 2.0 * 7.0 - 0.25 * 4.0);
+        assert_eq!(out[1], -1.0);
+        assert_eq!(out[32 * 5], -0.25);
+    }
+
+    #[test]
+    fn test_dequantize_q6_k() {
+        let mut block = vec![0u8; 210];
+        block[0] = 0x05; // ql low nibble of element 0
+        block[128] = 0x02; // qh bits 0..1 of element 0
+        block[192] = 3; // scale for elements 0..16
+        block[208..210].copy_from_slice(&f32_to_f16(0.5).to_le_bytes());
+
+        let mut out = vec![0.0; 256];
+        dequantize(GgmlType::Q6_K, &block, &mut out);
+        assert_eq!(out[0], 0.5 * 3.0 * ((5 | (2 << 4)) - 32) as f32);
+        assert_eq!(out[1], 0.5 * 3.0 * -32.0);
+    }
+
+    #[test]
+    fn test_writer_reader_roundtrip() {
+        let bytes = GgufWriter::new()
+            .metadata("general.architecture", MetaValue::String("llama".into()))
+            .metadata("general.alignment", MetaValue::U32(32))
+            .metadata("tokens", MetaValue::Array(vec![MetaValue::String("a".into()), MetaValue::String("b".into())]))
+            .tensor_f32("w", &[32, 2], GgmlType::Q8_0, &ramp(64))
+            .unwrap()
+            .tensor_f32("n", &[3], GgmlType::F32, &[1.0, 2.0, 3.0])
+            .unwrap()
+            .to_bytes();
+
+        let file = GgufFile::from_bytes(bytes).unwrap();
+        assert_eq!(file.version, GGUF_VERSION);
+        assert_eq!(file.architecture(), Some("llama"));
+        assert_eq!(file.get("tokens").and_then(MetaValue::as_array).map(|a| a.len()), Some(2));
+
+        let w = file.tensor("w").unwrap();
+        assert_eq!((w.cols(), w.rows()), (32, 2));
+        assert_eq!(file.tensor_f32("n").unwrap(), vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_rejects_bad_files() {
+        assert!(matches!(GgufFile::from_bytes(b"nope".to_vec()), Err(Error::InvalidModel(_))));
+
+        let mut bytes = GgufWriter::new()
+            .tensor_f32("w", &[32], GgmlType::F32, &ramp(32))
+            .unwrap()
+            .to_bytes();
+        bytes.truncate(bytes.len() - 8);
+        assert!(GgufFile::from_bytes(bytes).is_err());
+    }
+
+    /// Raw header with `n_kv` metadata pairs and `n_tensors` entries to follow
+    fn header(n_tensors: u64, n_kv: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&GGUF_MAGIC.to_le_bytes());
+        out.extend_from_slice(&GGUF_VERSION.to_le_bytes());
+        out.extend_from_slice(&n_tensors.to_le_bytes());
+        out.extend_from_slice(&n_kv.to_le_bytes());
+        out
+    }
+
+    fn push_str(out: &mut Vec<u8>, s: &str) {
+        out.extend_from_slice(&(s.len() as u64).to_le_bytes());
+        out.extend_from_slice(s.as_bytes());
+    }
+
+    fn tensor_header(dims: &[u64], offset: u64) -> Vec<u8> {
+        let mut out = header(1, 0);
+        push_str(&mut out, "w");
+        out.extend_from_slice(&(dims.len() as u32).to_le_bytes());
+        for dim in dims {
+            out.extend_from_slice(&dim.to_le_bytes());
+        }
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&offset.to_le_bytes());
+        out.resize(out.len() + 256, 0);
+        out
+    }
+
+    fn invalid(bytes: Vec<u8>) -> bool {
+        matches!(GgufFile::from_bytes(bytes), Err(Error::InvalidModel(_)))
+    }
+
+    #[test]
+    fn test_rejects_malformed_headers() {
+        // Tensor shapes and offsets that overflow
+        assert!(invalid(tensor_header(&[u64::MAX, 2], 0)));
+        assert!(invalid(tensor_header(&[0, u64::MAX, u64::MAX], 0)));
+        assert!(invalid(tensor_header(&[1 << 62], 0)));
+        assert!(invalid(tensor_header(&[4], u64::MAX)));
+        assert!(invalid(tensor_header(&[4], u64::MAX - 8)));
+        assert!(!invalid(tensor_header(&[4], 0)));
+
+        // Alignment that pushes the data section past the end of the file
+        let mut bytes = header(0, 1);
+        push_str(&mut bytes, "general.alignment");
+        bytes.extend_from_slice(&10u32.to_le_bytes());
+        bytes.extend_from_slice(&(1u64 << 63).to_le_bytes());
+        assert!(GgufFile::from_bytes(bytes).unwrap().data_section().is_empty());
+
+        // Array count far beyond the remaining bytes
+        let mut bytes = header(0, 1);
+        push_str(&mut bytes, "a");
+        bytes.extend_from_slice(&9u32.to_le_bytes());
+        bytes.extend_from_slice(&9u32.to_le_bytes());
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        assert!(invalid(bytes));
+
+        // Arrays nested past the depth limit
+        let nested = |depth: usize| {
+            let mut bytes = header(0, 1);
+            push_str(&mut bytes, "a");
+            bytes.extend_from_slice(&9u32.to_le_bytes());
+            for _ in 1..depth {
+                bytes.extend_from_slice(&9u32.to_le_bytes());
+                bytes.extend_from_slice(&1u64.to_le_bytes());
+            }
+            bytes.extend_from_slice(&4u32.to_le_bytes());
+            bytes.extend_from_slice(&0u64.to_le_bytes());
+            bytes
+        };
+        assert!(!invalid(nested(MAX_ARRAY_DEPTH)));
+        assert!(invalid(nested(MAX_ARRAY_DEPTH + 1)));
+        assert!(invalid(nested(100_000)));
+    }
+}
@@ -10,13 +10,27 @@
 //!      │                       │
 //!      └──TREE──► tree_c9f5   └──TREE──► tree_d0a6
 //! ```
+//!
+//! Trees merge three ways, tag by tag: a tag with at most one ref on every
+//! side is a slot (changed on one side wins, changed on both is a conflict
+//! unless both values are trees, which merge recursively); any other tag is
+//! a set (additions and removals from both sides apply).
+//!
+//! ```text
+//!         base
+//!        /    \
+//!     ours    theirs        merge commit ──PARENT──► ours
+//!        \    /                         ──PARENT──► theirs
+//!        merged                          ──CONFLICTS──► [Conflict] json
+//! ```
 
 use gently_core::{
     Hash, Kind, Blob, Manifest, BlobStore,
     TAG_PARENT, TAG_CHILD, TAG_NEXT, TAG_PREV,
 };
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
 
 // Git chain specific tags
 pub const TAG_TREE: u16 = 0x0100;
@@ -25,6 +39,7 @@ pub const TAG_AUTHOR: u16 = 0x0102;
 pub const TAG_TIMESTAMP: u16 = 0x0103;
 pub const TAG_SIGNATURE: u16 = 0x0104;
 pub const TAG_BRANCH_HEAD: u16 = 0x0105;
+pub const TAG_CONFLICTS: u16 = 0x0106;
 
 // BTC-anchored interaction tags
 pub const TAG_PROMPT: u16 = 0x0200;
@@ -73,6 +88,70 @@ pub struct Branch {
     pub head: Hash,
 }
 
+/// A tree slot both sides changed differently (stored as JSON on the merge commit)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Conflict {
+    /// Tags from the root tree down to the slot
+    pub path: Vec<u16>,
+    pub base: Option<Hash>,
+    pub ours: Option<Hash>,
+    pub theirs: Option<Hash>,
+}
+
+/// How one tree entry changed
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added(Hash),
+    Removed(Hash),
+    Modified { from: Hash, to: Hash },
+}
+
+/// One entry of a structural diff
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffEntry {
+    /// Tags from the root tree down to the entry
+    pub path: Vec<u16>,
+    pub change: Change,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergeKind {
+    /// Nothing to bring in
+    UpToDate,
+    /// Branch head moved forward, no commit made
+    FastForward,
+    /// New commit made
+    Merged,
+}
+
+/// Outcome of `merge` or `cherry_pick`
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    pub kind: MergeKind,
+    /// New branch head
+    pub commit: Hash,
+    /// Slots left at our value
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Outcome of `rebase`
+#[derive(Debug, Clone)]
+pub struct RebaseResult {
+    pub head: Hash,
+    /// (original, replayed) commits, oldest first
+    pub picked: Vec<(Hash, Hash)>,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Branch refs written alongside the blob store by `export`
+#[derive(Serialize, Deserialize)]
+struct Refs {
+    branches: HashMap<String, String>,
+    current: String,
+}
+
+const REFS_MAGIC: &[u8; 4] = b"GCHN";
+
 /// Git-style chain over blob store
 pub struct GitChain {
     store: BlobStore,
@@ -129,25 +208,33 @@ impl GitChain {
             branch: self.current.clone(),
         };
 
+        let commit_hash = self.write_commit(&tree, &meta, parent.as_slice(), &[]);
+        self.branches.insert(self.current.clone(), commit_hash);
+
+        commit_hash
+    }
+
+    /// Store tree, meta and commit manifest
+    pub(crate) fn write_commit(&mut self, tree: &Manifest, meta: &CommitMeta, parents: &[Hash], extra: &[(u16, Hash)]) -> Hash {
         // Store tree
         let tree_hash = self.store.put(tree.to_blob());
 
         // Store meta
-        let meta_blob = Blob::new(Kind::Json, serde_json::to_vec(&meta).unwrap());
+        let meta_blob = Blob::new(Kind::Json, serde_json::to_vec(meta).unwrap());
         let meta_hash = self.store.put(meta_blob);
 
         // Build commit manifest
         let mut commit = Manifest::new();
         commit.add(TAG_TREE, tree_hash);
         commit.add(TAG_MESSAGE, meta_hash);
-        if let Some(p) = parent {
-            commit.add(TAG_PARENT, p);
+        for p in parents {
+            commit.add(TAG_PARENT, *p);
+        }
+        for (tag, hash) in extra {
+            commit.add(*tag, *hash);
         }
 
-        let commit_hash = self.store.put(commit.to_blob());
-        self.branches.insert(self.current.clone(), commit_hash);
-
-        commit_hash
+        self.store.put(commit.to_blob())
     }
 
     /// Create new branch from current HEAD
@@ -157,6 +244,12 @@ impl GitChain {
         Some(head)
     }
 
+    /// Point a branch at a commit, creating it if needed
+    pub(crate) fn set_branch(&mut self, name: &str, head: Hash) {
+        self.store.set_root(head);
+        self.branches.insert(name.to_string(), head);
+    }
+
     /// Switch to branch
     pub fn checkout(&mut self, name: &str) -> bool {
         if self.branches.contains_key(name) {
@@ -339,6 +432,7 @@ impl GitChain {
         if let Some(p) = parent {
             commit.add(TAG_PARENT, p);
         }
+        commit.add(TAG_SESSION_ID, meta_hash);
 
         let commit_hash = self.store.put(commit.to_blob());
         self.branches.insert(branch_name, commit_hash);
@@ -353,20 +447,9 @@ impl GitChain {
     pub fn interaction_meta(&self, commit: &Hash) -> Option<InteractionMeta> {
         let blob = self.store.get(commit)?;
         let manifest = Manifest::from_blob(blob)?;
-        let tree_hash = manifest.get(TAG_TREE)?;
-        let tree_blob = self.store.get(&tree_hash)?;
-        let tree = Manifest::from_blob(tree_blob)?;
-
-        // The meta is stored in the message tag
-        let meta_hash = manifest.get(TAG_MESSAGE)?;
+        let meta_hash = manifest.get(TAG_SESSION_ID)?;
         let meta_blob = self.store.get(&meta_hash)?;
-
-        // Try to parse as InteractionMeta first
-        if let Ok(meta) = serde_json::from_slice::<InteractionMeta>(&meta_blob.data) {
-            return Some(meta);
-        }
-
-        None
+        serde_json::from_slice(&meta_blob.data).ok()
     }
 
     /// List all session branches
@@ -377,6 +460,290 @@ impl GitChain {
             .collect()
     }
 
+    /// Branch name or full hex commit hash
+    pub fn resolve(&self, name: &str) -> Option<Hash> {
+        self.branches
+            .get(name)
+            .copied()
+            .or_else(|| gently_core::blob::parse_hash(name).filter(|h| self.store.has(h)))
+    }
+
+    /// Parent commits (first parent first)
+    pub fn parents(&self, commit: &Hash) -> Vec<Hash> {
+        self.store.get(commit)
+            .and_then(Manifest::from_blob)
+            .map(|m| m.get_all(TAG_PARENT))
+            .unwrap_or_default()
+    }
+
+    /// All ancestors of `commit`, itself included
+    fn ancestors(&self, commit: &Hash) -> HashSet<Hash> {
+        let mut seen = HashSet::new();
+        let mut queue = vec![*commit];
+        while let Some(hash) = queue.pop() {
+            if seen.insert(hash) {
+                queue.extend(self.parents(&hash));
+            }
+        }
+        seen
+    }
+
+    /// Is `ancestor` reachable from `commit`?
+    pub fn is_ancestor(&self, ancestor: &Hash, commit: &Hash) -> bool {
+        self.ancestors(commit).contains(ancestor)
+    }
+
+    /// Nearest common ancestor of two commits
+    pub fn merge_base(&self, a: &Hash, b: &Hash) -> Option<Hash> {
+        let from_a = self.ancestors(a);
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([*b]);
+        while let Some(hash) = queue.pop_front() {
+            if from_a.contains(&hash) {
+                return Some(hash);
+            }
+            if seen.insert(hash) {
+                queue.extend(self.parents(&hash));
+            }
+        }
+        None
+    }
+
+    /// Structural diff from commit `a` to commit `b`
+    pub fn diff(&self, a: &Hash, b: &Hash) -> Option<Vec<DiffEntry>> {
+        let (from, to) = (self.tree(a)?, self.tree(b)?);
+        let mut out = Vec::new();
+        self.diff_trees(&from, &to, &[], &mut out);
+        Some(out)
+    }
+
+    fn diff_trees(&self, a: &Manifest, b: &Manifest, path: &[u16], out: &mut Vec<DiffEntry>) {
+        for tag in tags_of(&[a, b]) {
+            let (x, y) = (a.get_all(tag), b.get_all(tag));
+            let at = [path, &[tag]].concat();
+            if x.len() <= 1 && y.len() <= 1 {
+                match (x.first().copied(), y.first().copied()) {
+                    (None, Some(h)) => out.push(DiffEntry { path: at, change: Change::Added(h) }),
+                    (Some(h), None) => out.push(DiffEntry { path: at, change: Change::Removed(h) }),
+                    (Some(from), Some(to)) if from != to => match (self.subtree(&from), self.subtree(&to)) {
+                        (Some(f), Some(t)) => self.diff_trees(&f, &t, &at, out),
+                        _ => out.push(DiffEntry { path: at, change: Change::Modified { from, to } }),
+                    },
+                    _ => {}
+                }
+            } else {
+                for h in x.iter().filter(|h| !y.contains(h)) {
+                    out.push(DiffEntry { path: at.clone(), change: Change::Removed(*h) });
+                }
+                for h in y.iter().filter(|h| !x.contains(h)) {
+                    out.push(DiffEntry { path: at.clone(), change: Change::Added(*h) });
+                }
+            }
+        }
+    }
+
+    /// Merge `branch` into the current branch
+    ///
+    /// Conflicting slots keep our value and are recorded on the merge commit.
+    pub fn merge(&mut self, branch: &str, author: &str) -> Option<MergeResult> {
+        let ours = self.head()?;
+        let theirs = *self.branches.get(branch)?;
+
+        if self.is_ancestor(&theirs, &ours) {
+            return Some(MergeResult { kind: MergeKind::UpToDate, commit: ours, conflicts: vec![] });
+        }
+        if self.is_ancestor(&ours, &theirs) {
+            self.branches.insert(self.current.clone(), theirs);
+            return Some(MergeResult { kind: MergeKind::FastForward, commit: theirs, conflicts: vec![] });
+        }
+
+        let base = self.merge_base(&ours, &theirs)
+            .and_then(|b| self.tree(&b))
+            .unwrap_or_default();
+        let (our_tree, their_tree) = (self.tree(&ours)?, self.tree(&theirs)?);
+        let mut conflicts = Vec::new();
+        let merged = self.merge_trees(&base, &our_tree, &their_tree, &[], &mut conflicts);
+
+        let meta = CommitMeta {
+            message: format!("merge {} into {}", branch, self.current),
+            author: author.to_string(),
+            timestamp: now(),
+            branch: self.current.clone(),
+        };
+        let extra = self.conflict_refs(&conflicts);
+        let commit = self.write_commit(&merged, &meta, &[ours, theirs], &extra);
+        self.branches.insert(self.current.clone(), commit);
+
+        Some(MergeResult { kind: MergeKind::Merged, commit, conflicts })
+    }
+
+    /// Apply one commit's changes on top of the current branch
+    ///
+    /// Keeps the original message, author and interaction metadata.
+    pub fn cherry_pick(&mut self, commit: &Hash) -> Option<MergeResult> {
+        let head = self.head()?;
+        let manifest = Manifest::from_blob(self.store.get(commit)?)?;
+        let meta = self.meta(commit)?;
+
+        let base = self.parents(commit)
+            .first()
+            .and_then(|p| self.tree(p))
+            .unwrap_or_default();
+        let (our_tree, their_tree) = (self.tree(&head)?, self.tree(commit)?);
+        let mut conflicts = Vec::new();
+        let merged = self.merge_trees(&base, &our_tree, &their_tree, &[], &mut conflicts);
+
+        if merged.to_blob().hash == our_tree.to_blob().hash && conflicts.is_empty() {
+            return Some(MergeResult { kind: MergeKind::UpToDate, commit: head, conflicts });
+        }
+
+        let meta = CommitMeta { branch: self.current.clone(), ..meta };
+        let mut extra: Vec<(u16, Hash)> = manifest.get_all(TAG_SESSION_ID)
+            .into_iter()
+            .map(|h| (TAG_SESSION_ID, h))
+            .collect();
+        extra.extend(self.conflict_refs(&conflicts));
+        let picked = self.write_commit(&merged, &meta, &[head], &extra);
+        self.branches.insert(self.current.clone(), picked);
+
+        Some(MergeResult { kind: MergeKind::Merged, commit: picked, conflicts })
+    }
+
+    /// Replay `branch`'s own commits on top of `onto`
+    ///
+    /// Merge commits are skipped; the current branch is left unchanged.
+    pub fn rebase(&mut self, branch: &str, onto: &str) -> Option<RebaseResult> {
+        let tip = *self.branches.get(branch)?;
+        let onto_head = *self.branches.get(onto)?;
+        let upstream = self.ancestors(&onto_head);
+        if self.is_ancestor(&onto_head, &tip) {
+            return Some(RebaseResult { head: tip, picked: vec![], conflicts: vec![] });
+        }
+
+        let mut todo = Vec::new();
+        let mut cursor = Some(tip);
+        while let Some(hash) = cursor.filter(|h| !upstream.contains(h)) {
+            todo.push(hash);
+            cursor = self.parents(&hash).first().copied();
+        }
+        todo.reverse();
+
+        let previous = std::mem::replace(&mut self.current, branch.to_string());
+        self.branches.insert(branch.to_string(), onto_head);
+
+        let mut picked = Vec::new();
+        let mut conflicts = Vec::new();
+        for commit in todo {
+            if self.parents(&commit).len() > 1 {
+                continue;
+            }
+            match self.cherry_pick(&commit) {
+                Some(result) => {
+                    if result.kind == MergeKind::Merged {
+                        picked.push((commit, result.commit));
+                    }
+                    conflicts.extend(result.conflicts);
+                }
+                None => {
+                    self.branches.insert(branch.to_string(), tip);
+                    self.current = previous;
+                    return None;
+                }
+            }
+        }
+
+        self.current = previous;
+        let head = self.branches[branch];
+        Some(RebaseResult { head, picked, conflicts })
+    }
+
+    /// Conflicts recorded on a merge or cherry-pick commit
+    pub fn conflicts(&self, commit: &Hash) -> Vec<Conflict> {
+        self.store.get(commit)
+            .and_then(Manifest::from_blob)
+            .and_then(|m| m.get(TAG_CONFLICTS))
+            .and_then(|h| self.store.get(&h))
+            .and_then(|b| serde_json::from_slice(&b.data).ok())
+            .unwrap_or_default()
+    }
+
+    fn conflict_refs(&mut self, conflicts: &[Conflict]) -> Vec<(u16, Hash)> {
+        if conflicts.is_empty() {
+            return vec![];
+        }
+        let blob = Blob::new(Kind::Json, serde_json::to_vec(conflicts).unwrap());
+        vec![(TAG_CONFLICTS, self.store.put(blob))]
+    }
+
+    /// A stored manifest, if `hash` is one
+    fn subtree(&self, hash: &Hash) -> Option<Manifest> {
+        self.store.get(hash)
+            .filter(|b| b.kind == Kind::Manifest)
+            .and_then(Manifest::from_blob)
+    }
+
+    fn merge_trees(
+        &mut self,
+        base: &Manifest,
+        ours: &Manifest,
+        theirs: &Manifest,
+        path: &[u16],
+        conflicts: &mut Vec<Conflict>,
+    ) -> Manifest {
+        let mut out = Manifest::new();
+        for tag in tags_of(&[ours, theirs, base]) {
+            let (b, o, t) = (base.get_all(tag), ours.get_all(tag), theirs.get_all(tag));
+
+            if b.len() <= 1 && o.len() <= 1 && t.len() <= 1 {
+                let (b, o, t) = (b.first().copied(), o.first().copied(), t.first().copied());
+                let merged = if o == t || t == b {
+                    o
+                } else if o == b {
+                    t
+                } else {
+                    let at = [path, &[tag]].concat();
+                    match (o.and_then(|h| self.subtree(&h)), t.and_then(|h| self.subtree(&h))) {
+                        (Some(os), Some(ts)) => {
+                            let bs = b.and_then(|h| self.subtree(&h)).unwrap_or_default();
+                            let sub = self.merge_trees(&bs, &os, &ts, &at, conflicts);
+                            Some(self.store.put(sub.to_blob()))
+                        }
+                        _ => {
+                            conflicts.push(Conflict { path: at, base: b, ours: o, theirs: t });
+                            o
+                        }
+                    }
+                };
+                if let Some(h) = merged {
+                    out.add(tag, h);
+                }
+            } else {
+                for h in o.iter().filter(|h| !b.contains(h) || t.contains(h)) {
+                    out.add(tag, *h);
+                }
+                for h in t.iter().filter(|h| !b.contains(h) && !o.contains(h)) {
+                    out.add(tag, *h);
+                }
+            }
+        }
+        out
+    }
+
+    /// Save chain and branch refs to a file
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.export())
+    }
+
+    /// Load a chain written by `save`
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::import(&bytes)
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "not a gitchain file"))
+    }
+
     /// Store arbitrary blob
     pub fn put(&mut self, blob: Blob) -> Hash {
         self.store.put(blob)
@@ -388,12 +755,36 @@ impl GitChain {
     }
 
     /// Export entire chain
+    ///
+    /// `[GCHN][refs len:4][refs json][blob store]`
     pub fn export(&self) -> Vec<u8> {
-        self.store.export()
+        let refs = Refs {
+            branches: self.branches.iter().map(|(n, h)| (n.clone(), hex::encode(h))).collect(),
+            current: self.current.clone(),
+        };
+        let refs = serde_json::to_vec(&refs).unwrap();
+
+        let mut out = Vec::new();
+        out.extend_from_slice(REFS_MAGIC);
+        out.extend_from_slice(&(refs.len() as u32).to_le_bytes());
+        out.extend_from_slice(&refs);
+        out.extend_from_slice(&self.store.export());
+        out
     }
 
     /// Import chain
     pub fn import(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() >= 8 && &bytes[0..4] == REFS_MAGIC {
+            let len = u32::from_le_bytes(bytes[4..8].try_into().ok()?) as usize;
+            let refs: Refs = serde_json::from_slice(bytes.get(8..8 + len)?).ok()?;
+            let store = BlobStore::import(&bytes[8 + len..])?;
+            let branches = refs.branches.iter()
+                .filter_map(|(n, h)| Some((n.clone(), gently_core::blob::parse_hash(h)?)))
+                .collect();
+            return Some(Self { store, branches, current: refs.current });
+        }
+
+        // Bare blob store: reconstruct branches from roots
         let store = BlobStore::import(bytes)?;
         let mut chain = Self {
             store,
@@ -401,7 +792,6 @@ impl GitChain {
             current: "main".to_string(),
         };
 
-        // Reconstruct branches from roots
         for root in chain.store.roots() {
             if let Some(meta) = chain.meta(&root) {
                 chain.branches.insert(meta.branch.clone(), root);
@@ -416,6 +806,17 @@ impl Default for GitChain {
     fn default() -> Self { Self::new() }
 }
 
+/// Tags in first-seen order across manifests
+fn tags_of(manifests: &[&Manifest]) -> Vec<u16> {
+    let mut tags = Vec::new();
+    for r in manifests.iter().flat_map(|m| &m.refs) {
+        if !tags.contains(&r.tag) {
+            tags.push(r.tag);
+        }
+    }
+    tags
+}
+
 fn now() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -465,4 +866,181 @@ mod tests {
 
         assert_eq!(chain.branches().len(), 2);
     }
+
+    const TAG_SCHEMA_TREE: u16 = 0x0900;
+
+    fn text(chain: &mut GitChain, s: &str) -> Hash {
+        chain.put(Blob::new(Kind::Text, s.as_bytes().to_vec()))
+    }
+
+    /// Tree with `children` as a set and `config` as a slot
+    fn tree(chain: &mut GitChain, children: &[&str], config: &str) -> Manifest {
+        let mut tree = Manifest::new();
+        for c in children {
+            let h = text(chain, c);
+            tree.add(TAG_CHILD, h);
+        }
+        let h = text(chain, config);
+        tree.add(gently_core::TAG_CONFIG, h);
+        tree
+    }
+
+    #[test]
+    fn test_merge_and_fast_forward() {
+        let mut chain = GitChain::new();
+        chain.init("test");
+        let t = tree(&mut chain, &["a", "b"], "v1");
+        chain.commit(t, "base", "test");
+        chain.branch("feature");
+
+        // Up to date and fast-forward
+        assert_eq!(chain.merge("feature", "test").unwrap().kind, MergeKind::UpToDate);
+        chain.checkout("feature");
+        let t = tree(&mut chain, &["a", "b", "c"], "v1");
+        let f1 = chain.commit(t, "add c", "test");
+        chain.checkout("main");
+        let ff = chain.merge("feature", "test").unwrap();
+        assert_eq!((ff.kind, ff.commit), (MergeKind::FastForward, f1));
+
+        // Diverge: feature drops a and changes the slot, main adds d
+        chain.checkout("feature");
+        let t = tree(&mut chain, &["b", "c"], "v2");
+        chain.commit(t, "drop a", "test");
+        chain.checkout("main");
+        let t = tree(&mut chain, &["a", "b", "c", "d"], "v1");
+        let ours = chain.commit(t, "add d", "test");
+
+        let result = chain.merge("feature", "test").unwrap();
+        assert_eq!(result.kind, MergeKind::Merged);
+        assert!(result.conflicts.is_empty());
+        assert_eq!(chain.parents(&result.commit), vec![ours, chain.resolve("feature").unwrap()]);
+
+        let merged = chain.tree(&result.commit).unwrap();
+        let expected = tree(&mut chain, &["b", "c", "d"], "v2");
+        assert_eq!(merged.to_blob().hash, expected.to_blob().hash);
+    }
+
+    #[test]
+    fn test_merge_conflict_recorded() {
+        let mut chain = GitChain::new();
+        chain.init("test");
+        let t = tree(&mut chain, &["a"], "v1");
+        let base = chain.commit(t, "base", "test");
+        chain.branch("other");
+
+        let t = tree(&mut chain, &["a"], "ours");
+        chain.commit(t, "ours", "test");
+        chain.checkout("other");
+        let t = tree(&mut chain, &["a", "x"], "theirs");
+        chain.commit(t, "theirs", "test");
+        chain.checkout("main");
+
+        let result = chain.merge("other", "test").unwrap();
+        assert_eq!(result.conflicts.len(), 1);
+        let conflict = &result.conflicts[0];
+        assert_eq!(conflict.path, vec![gently_core::TAG_CONFIG]);
+        assert_eq!(conflict.base, Some(text(&mut chain, "v1")));
+        assert_eq!(conflict.theirs, Some(text(&mut chain, "theirs")));
+
+        // Stored on the commit; ours kept, the clean addition applied
+        assert_eq!(chain.conflicts(&result.commit), result.conflicts);
+        assert!(chain.conflicts(&base).is_empty());
+        let merged = chain.tree(&result.commit).unwrap();
+        assert_eq!(merged.get(gently_core::TAG_CONFIG), Some(text(&mut chain, "ours")));
+        assert_eq!(merged.get_all(TAG_CHILD).len(), 2);
+    }
+
+    #[test]
+    fn test_nested_trees_merge_and_diff() {
+        let mut chain = GitChain::new();
+        chain.init("test");
+
+        let sub = tree(&mut chain, &["x"], "v1");
+        let sub_hash = chain.put(sub.to_blob());
+        let mut root = Manifest::new();
+        root.add(TAG_SCHEMA_TREE, sub_hash);
+        let base = chain.commit(root, "base", "test");
+        chain.branch("other");
+
+        // Both sides change the subtree, in different places
+        let sub = tree(&mut chain, &["x", "y"], "v1");
+        let mut root = Manifest::new();
+        root.add(TAG_SCHEMA_TREE, chain.put(sub.to_blob()));
+        let ours = chain.commit(root, "add y", "test");
+
+        chain.checkout("other");
+        let sub = tree(&mut chain, &["x"], "v2");
+        let mut root = Manifest::new();
+        root.add(TAG_SCHEMA_TREE, chain.put(sub.to_blob()));
+        chain.commit(root, "bump config", "test");
+        chain.checkout("main");
+
+        let result = chain.merge("other", "test").unwrap();
+        assert!(result.conflicts.is_empty());
+        let expected = tree(&mut chain, &["x", "y"], "v2");
+        let merged = chain.tree(&result.commit).unwrap();
+        assert_eq!(merged.get(TAG_SCHEMA_TREE), Some(expected.to_blob().hash));
+
+        // Diff descends into the subtree
+        let diff = chain.diff(&base, &ours).unwrap();
+        assert_eq!(diff, vec![DiffEntry {
+            path: vec![TAG_SCHEMA_TREE, TAG_CHILD],
+            change: Change::Added(text(&mut chain, "y")),
+        }]);
+        let diff = chain.diff(&ours, &result.commit).unwrap();
+        assert_eq!(diff, vec![DiffEntry {
+            path: vec![TAG_SCHEMA_TREE, gently_core::TAG_CONFIG],
+            change: Change::Modified { from: text(&mut chain, "v1"), to: text(&mut chain, "v2") },
+        }]);
+        assert!(chain.diff(&ours, &ours).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rebase_session_branch() {
+        let mut chain = GitChain::new();
+        chain.init("test");
+
+        // Session commits branch off genesis
+        let c1 = chain.commit_interaction("s1", 0, "hi", "hello", "", 800_000, "00ab");
+        let c2 = chain.commit_interaction("s1", 1, "more", "sure", "x", 800_000, "00ab");
+        let session = chain.session_branches()[0].name.clone();
+        assert_eq!(chain.resolve(&session), Some(c2));
+
+        // main moves on
+        let t = tree(&mut chain, &["doc"], "v1");
+        let main_head = chain.commit(t, "docs", "test");
+
+        let result = chain.rebase(&session, "main").unwrap();
+        assert_eq!(result.picked.iter().map(|p| p.0).collect::<Vec<_>>(), vec![c1, c2]);
+        assert!(result.conflicts.is_empty());
+        assert!(chain.is_ancestor(&main_head, &result.head));
+        assert_eq!(chain.current_branch(), "main");
+
+        // Replayed commits keep their message and interaction metadata
+        let replayed = result.picked[1].1;
+        assert_eq!(chain.meta(&replayed).unwrap().message, chain.meta(&c2).unwrap().message);
+        assert_eq!(chain.interaction_meta(&replayed).unwrap().index, 1);
+        let tree = chain.tree(&replayed).unwrap();
+        assert!(tree.get(TAG_PROMPT).is_some() && tree.get(gently_core::TAG_CONFIG).is_some());
+
+        // Rebasing again is a no-op; merging into main fast-forwards
+        assert!(chain.rebase(&session, "main").unwrap().picked.is_empty());
+        assert_eq!(chain.merge(&session, "test").unwrap().kind, MergeKind::FastForward);
+    }
+
+    #[test]
+    fn test_export_keeps_branches() {
+        let mut chain = GitChain::new();
+        chain.init("test");
+        chain.branch("feature");
+        chain.checkout("feature");
+        let t = tree(&mut chain, &["a"], "v1");
+        let head = chain.commit(t, "work", "test");
+
+        let restored = GitChain::import(&chain.export()).unwrap();
+        assert_eq!(restored.current_branch(), "feature");
+        assert_eq!(restored.resolve("feature"), Some(head));
+        assert_eq!(restored.branches().len(), 2);
+        assert_eq!(restored.log(&head, 10).len(), 2);
+    }
 }
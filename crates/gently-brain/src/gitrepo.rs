@@ -0,0 +1,904 @@
+//! GitChain ⇄ git repository bridge
+//!
+//! Writes a `GitChain` into a bare git repository so brain history can be
+//! pushed to ordinary git hosting and read with `git log`, and reads it back
+//! into identical blob hashes.
+//!
+//! ```text
+//! GitChain commit                     git commit
+//! ───────────────                     ──────────
+//! TREE ──► manifest                   tree
+//!   (tag, hash) ref #i                  {i:06}-{tag:04x}.txt|.json|.svg|.k{kind:02x}.bin
+//!   (tag, manifest) ref #i              {i:06}-{tag:04x}/        (subtree)
+//! MESSAGE ──► CommitMeta json         message + Gently-* trailers
+//! PARENT*                             parent*
+//! other refs (session, conflicts)     .gently/{i:06}-{tag:04x}.<ext>
+//! ```
+//!
+//! Only loose objects are written; imports also read packfiles, so a repo
+//! that was cloned or `git gc`'d still loads.
+
+use crate::gitchain::{CommitMeta, GitChain, TAG_MESSAGE, TAG_TREE};
+use crate::{Error, Result};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use gently_core::{Blob, Hash, Kind, Manifest, TAG_PARENT};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Directory in each git tree holding commit-level refs
+const EXTRAS_DIR: &str = ".gently";
+
+/// Git object id
+pub type Oid = [u8; 20];
+
+/// What `export` wrote
+#[derive(Debug, Clone, Default)]
+pub struct GitExport {
+    pub commits: usize,
+    pub objects_written: usize,
+    /// Branch name → git commit id (hex)
+    pub refs: Vec<(String, String)>,
+}
+
+/// Write `chain` into a bare repository at `repo`, creating it if needed
+///
+/// Existing objects are kept; branch refs and HEAD are overwritten.
+pub fn export(chain: &GitChain, repo: &Path) -> Result<GitExport> {
+    let mut writer = Writer::open(repo)?;
+    let mut commits: HashMap<Hash, Oid> = HashMap::new();
+
+    let mut branches = chain.branches();
+    branches.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut report = GitExport::default();
+
+    for branch in &branches {
+        let oid = writer.commit(chain, &branch.head, &mut commits)?;
+        write_file(&repo.join("refs/heads").join(&branch.name), format!("{}\n", hex::encode(oid)).as_bytes())?;
+        report.refs.push((branch.name.clone(), hex::encode(oid)));
+    }
+    write_file(&repo.join("HEAD"), format!("ref: refs/heads/{}\n", chain.current_branch()).as_bytes())?;
+
+    report.commits = commits.len();
+    report.objects_written = writer.written;
+    Ok(report)
+}
+
+/// Read a repository written by `export` (or any repo with text/json files)
+///
+/// Commits carrying a `Gently-Commit` trailer must come back with that
+/// exact hash, so a lossy round trip is an error rather than silent drift.
+pub fn import(repo: &Path) -> Result<GitChain> {
+    let reader = Reader::open(repo)?;
+    let mut chain = GitChain::new();
+    let mut commits: HashMap<Oid, Hash> = HashMap::new();
+
+    let refs = reader.refs()?;
+    if refs.is_empty() {
+        return Err(Error::Git("repository has no branches".into()));
+    }
+    for (name, oid) in &refs {
+        let head = import_commit(&reader, &mut chain, oid, name, &mut commits)?;
+        chain.set_branch(name, head);
+    }
+
+    let current = reader.head_branch().filter(|b| refs.iter().any(|(n, _)| n == b));
+    chain.checkout(current.as_deref().unwrap_or(&refs[0].0));
+    Ok(chain)
+}
+
+struct Writer {
+    objects: PathBuf,
+    written: usize,
+}
+
+impl Writer {
+    fn open(repo: &Path) -> Result<Self> {
+        for dir in ["objects/info", "objects/pack", "refs/heads", "refs/tags"] {
+            std::fs::create_dir_all(repo.join(dir))?;
+        }
+        let config = repo.join("config");
+        if !config.exists() {
+            write_file(&config, b"[core]\n\trepositoryformatversion = 0\n\tfilemode = true\n\tbare = true\n")?;
+            write_file(&repo.join("description"), b"GentlyOS brain history\n")?;
+        }
+        Ok(Self { objects: repo.join("objects"), written: 0 })
+    }
+
+    /// Store an object, returning its id
+    fn object(&mut self, kind: &str, data: &[u8]) -> Result<Oid> {
+        let mut raw = format!("{} {}\0", kind, data.len()).into_bytes();
+        raw.extend_from_slice(data);
+        let oid: Oid = Sha1::digest(&raw).into();
+
+        let hex = hex::encode(oid);
+        let path = self.objects.join(&hex[..2]).join(&hex[2..]);
+        if !path.exists() {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&raw)?;
+            write_file(&path, &encoder.finish()?)?;
+            self.written += 1;
+        }
+        Ok(oid)
+    }
+
+    fn commit(&mut self, chain: &GitChain, hash: &Hash, done: &mut HashMap<Hash, Oid>) -> Result<Oid> {
+        if let Some(oid) = done.get(hash) {
+            return Ok(*oid);
+        }
+
+        let manifest = manifest_of(chain, hash)
+            .ok_or_else(|| Error::Git(format!("{} is not a commit", short(hash))))?;
+        let meta = chain.meta(hash)
+            .ok_or_else(|| Error::Git(format!("commit {} has no meta", short(hash))))?;
+
+        let mut parents = Vec::new();
+        for p in manifest.get_all(TAG_PARENT) {
+            parents.push(self.commit(chain, &p, done)?);
+        }
+
+        // Knowledge tree plus commit-level extras
+        let tree = manifest.get(TAG_TREE)
+            .and_then(|h| manifest_of(chain, &h))
+            .ok_or_else(|| Error::Git(format!("commit {} has no tree", short(hash))))?;
+        let mut entries = self.tree_entries(chain, &tree)?;
+
+        let extras: Vec<(usize, u16, Hash)> = manifest.refs.iter()
+            .enumerate()
+            .filter(|(_, r)| ![TAG_TREE, TAG_MESSAGE, TAG_PARENT].contains(&r.tag))
+            .map(|(i, r)| (i, r.tag, r.hash))
+            .collect();
+        if !extras.is_empty() {
+            let mut extra_entries = Vec::new();
+            for (i, tag, h) in extras {
+                let blob = chain.get(&h)
+                    .ok_or_else(|| Error::Git(format!("missing blob {}", short(&h))))?;
+                let oid = self.object("blob", &blob.data)?;
+                extra_entries.push(TreeEntry::file(entry_name(i, tag, blob.kind), oid));
+            }
+            let oid = self.object("tree", &encode_tree(extra_entries))?;
+            entries.push(TreeEntry::dir(EXTRAS_DIR.into(), oid));
+        }
+        let tree_oid = self.object("tree", &encode_tree(entries))?;
+
+        let mut body = format!("tree {}\n", hex::encode(tree_oid));
+        for p in &parents {
+            body.push_str(&format!("parent {}\n", hex::encode(p)));
+        }
+        let ident = format!("{} <{}@gently> {} +0000", clean(&meta.author), clean(&meta.author).replace(' ', "."), meta.timestamp);
+        body.push_str(&format!("author {}\ncommitter {}\n\n", ident, ident));
+        body.push_str(&encode_message(&meta, hash));
+
+        let oid = self.object("commit", body.as_bytes())?;
+        done.insert(*hash, oid);
+        Ok(oid)
+    }
+
+    fn tree_entries(&mut self, chain: &GitChain, tree: &Manifest) -> Result<Vec<TreeEntry>> {
+        let mut entries = Vec::new();
+        for (i, r) in tree.refs.iter().enumerate() {
+            let blob = chain.get(&r.hash)
+                .ok_or_else(|| Error::Git(format!("missing blob {}", short(&r.hash))))?;
+
+            // Subtrees become directories when fully present, raw files otherwise
+            let subtree = Some(blob)
+                .filter(|b| b.kind == Kind::Manifest)
+                .and_then(Manifest::from_blob)
+                .filter(|m| m.to_blob().data == blob.data && m.refs.iter().all(|c| chain.get(&c.hash).is_some()));
+
+            let entry = match subtree {
+                Some(sub) => {
+                    let children = self.tree_entries(chain, &sub)?;
+                    let oid = self.object("tree", &encode_tree(children))?;
+                    TreeEntry::dir(format!("{:06}-{:04x}", i, r.tag), oid)
+                }
+                None => TreeEntry::file(entry_name(i, r.tag, blob.kind), self.object("blob", &blob.data)?),
+            };
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
+
+fn import_commit(
+    reader: &Reader,
+    chain: &mut GitChain,
+    oid: &Oid,
+    branch: &str,
+    done: &mut HashMap<Oid, Hash>,
+) -> Result<Hash> {
+    if let Some(hash) = done.get(oid) {
+        return Ok(*hash);
+    }
+
+    let (kind, data) = reader.object(oid)?;
+    if kind != "commit" {
+        return Err(Error::Git(format!("{} is a {}, not a commit", hex::encode(oid), kind)));
+    }
+    let commit = parse_commit(&data)?;
+
+    let mut parents = Vec::new();
+    for p in &commit.parents {
+        parents.push(import_commit(reader, chain, p, branch, done)?);
+    }
+
+    let (tree, extras) = import_tree(reader, chain, &commit.tree, true)?;
+    let (meta, expected) = decode_message(&commit.message, &commit.author, branch);
+
+    let hash = chain.write_commit(&tree, &meta, &parents, &extras);
+    if let Some(expected) = expected.filter(|e| *e != hash) {
+        return Err(Error::Git(format!(
+            "commit {} imported as {} (expected {})",
+            hex::encode(oid), short(&hash), short(&expected)
+        )));
+    }
+    done.insert(*oid, hash);
+    Ok(hash)
+}
+
+/// Rebuild a manifest from a git tree; at the root `.gently/` yields commit extras
+fn import_tree(reader: &Reader, chain: &mut GitChain, oid: &Oid, root: bool) -> Result<(Manifest, Vec<(u16, Hash)>)> {
+    let (kind, data) = reader.object(oid)?;
+    if kind != "tree" {
+        return Err(Error::Git(format!("{} is not a tree", hex::encode(oid))));
+    }
+
+    let mut refs = Vec::new();
+    let mut extras = Vec::new();
+    for entry in parse_tree(&data)? {
+        if root && entry.is_dir() && entry.name == EXTRAS_DIR {
+            let (kind, data) = reader.object(&entry.oid)?;
+            if kind != "tree" {
+                continue;
+            }
+            for extra in parse_tree(&data)? {
+                let (_, tag, kind) = parse_name(&extra.name);
+                let (_, bytes) = reader.object(&extra.oid)?;
+                extras.push((tag, chain.put(Blob::new(kind, bytes))));
+            }
+            continue;
+        }
+
+        let (order, tag, kind) = parse_name(&entry.name);
+        let hash = if entry.is_dir() {
+            let (sub, _) = import_tree(reader, chain, &entry.oid, false)?;
+            chain.put(sub.to_blob())
+        } else {
+            let (_, bytes) = reader.object(&entry.oid)?;
+            chain.put(Blob::new(kind, bytes))
+        };
+        refs.push((order, tag, hash));
+    }
+
+    // Names sort by index already; foreign files keep git's order after ours
+    refs.sort_by_key(|(order, _, _)| *order);
+    let mut tree = Manifest::new();
+    for (_, tag, hash) in refs {
+        tree.add(tag, hash);
+    }
+    Ok((tree, extras))
+}
+
+// ── Naming ──────────────────────────────────────────────────────────────────
+
+fn entry_name(index: usize, tag: u16, kind: Kind) -> String {
+    let ext = match kind {
+        Kind::Text => "txt".to_string(),
+        Kind::Json => "json".to_string(),
+        Kind::Svg => "svg".to_string(),
+        other => format!("k{:02x}.bin", other as u8),
+    };
+    format!("{:06}-{:04x}.{}", index, tag, ext)
+}
+
+/// `(order, tag, kind)` from an entry name; foreign names become text children
+fn parse_name(name: &str) -> (usize, u16, Kind) {
+    let (stem, ext) = match name.split_once('.') {
+        Some((stem, ext)) => (stem, ext),
+        None => (name, ""),
+    };
+    let kind = match ext {
+        "txt" => Kind::Text,
+        "json" => Kind::Json,
+        "svg" => Kind::Svg,
+        "" => Kind::Manifest,
+        other => other.strip_prefix('k')
+            .and_then(|e| e.strip_suffix(".bin"))
+            .and_then(|k| u8::from_str_radix(k, 16).ok())
+            .map(Kind::from)
+            .unwrap_or(Kind::Text),
+    };
+    let parsed = stem.split_once('-').and_then(|(i, t)| {
+        Some((i.parse::<usize>().ok()?, u16::from_str_radix(t, 16).ok()?))
+    });
+    match parsed {
+        Some((order, tag)) => (order, tag, kind),
+        None => (usize::MAX, gently_core::TAG_CHILD, kind),
+    }
+}
+
+fn clean(s: &str) -> String {
+    let s: String = s.chars().filter(|c| !matches!(c, '<' | '>' | '\n')).collect();
+    if s.trim().is_empty() { "gently".into() } else { s.trim().to_string() }
+}
+
+fn short(hash: &Hash) -> String {
+    hex::encode(&hash[..6])
+}
+
+// ── Commit messages ─────────────────────────────────────────────────────────
+
+/// `message` + blank line + `Gently-*` trailers
+fn encode_message(meta: &CommitMeta, hash: &Hash) -> String {
+    format!(
+        "{}\n\nGently-Author: {}\nGently-Timestamp: {}\nGently-Branch: {}\nGently-Commit: {}\n",
+        meta.message,
+        serde_json::to_string(&meta.author).unwrap(),
+        meta.timestamp,
+        serde_json::to_string(&meta.branch).unwrap(),
+        hex::encode(hash),
+    )
+}
+
+/// Meta from trailers, falling back to the git author line for foreign commits
+fn decode_message(message: &str, author: &(String, u64), branch: &str) -> (CommitMeta, Option<Hash>) {
+    let body = message.strip_suffix('\n').unwrap_or(message);
+    if let Some((text, trailers)) = body.rsplit_once("\n\n") {
+        let fields: HashMap<&str, &str> = trailers.lines()
+            .filter_map(|l| l.strip_prefix("Gently-")?.split_once(": "))
+            .collect();
+        let parsed = (|| {
+            let meta = CommitMeta {
+                message: text.to_string(),
+                author: serde_json::from_str(fields.get("Author")?).ok()?,
+                timestamp: fields.get("Timestamp")?.parse().ok()?,
+                branch: serde_json::from_str(fields.get("Branch")?).ok()?,
+            };
+            let hash = fields.get("Commit").and_then(|h| gently_core::blob::parse_hash(h));
+            Some((meta, hash))
+        })();
+        if let Some(parsed) = parsed.filter(|_| fields.len() == trailers.lines().count()) {
+            return parsed;
+        }
+    }
+
+    let meta = CommitMeta {
+        message: body.to_string(),
+        author: author.0.clone(),
+        timestamp: author.1,
+        branch: branch.to_string(),
+    };
+    (meta, None)
+}
+
+// ── Object formats ──────────────────────────────────────────────────────────
+
+struct TreeEntry {
+    mode: &'static str,
+    name: String,
+    oid: Oid,
+}
+
+impl TreeEntry {
+    fn file(name: String, oid: Oid) -> Self {
+        Self { mode: "100644", name, oid }
+    }
+
+    fn dir(name: String, oid: Oid) -> Self {
+        Self { mode: "40000", name, oid }
+    }
+
+    fn is_dir(&self) -> bool {
+        self.mode == "40000"
+    }
+
+    /// Git orders directories as if their name ended in `/`
+    fn sort_key(&self) -> Vec<u8> {
+        let mut key = self.name.as_bytes().to_vec();
+        if self.is_dir() {
+            key.push(b'/');
+        }
+        key
+    }
+}
+
+fn encode_tree(mut entries: Vec<TreeEntry>) -> Vec<u8> {
+    entries.sort_by_key(|e| e.sort_key());
+    let mut out = Vec::new();
+    for e in entries {
+        out.extend_from_slice(format!("{} {}\0", e.mode, e.name).as_bytes());
+        out.extend_from_slice(&e.oid);
+    }
+    out
+}
+
+fn parse_tree(data: &[u8]) -> Result<Vec<TreeEntry>> {
+    let bad = || Error::Git("malformed tree".into());
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let space = data[pos..].iter().position(|&b| b == b' ').ok_or_else(bad)? + pos;
+        let nul = data[space..].iter().position(|&b| b == 0).ok_or_else(bad)? + space;
+        let oid: Oid = data.get(nul + 1..nul + 21).ok_or_else(bad)?.try_into().unwrap();
+        let mode = match &data[pos..space] {
+            b"40000" => "40000",
+            _ => "100644",
+        };
+        entries.push(TreeEntry { mode, name: String::from_utf8_lossy(&data[space + 1..nul]).into_owned(), oid });
+        pos = nul + 21;
+    }
+    Ok(entries)
+}
+
+struct ParsedCommit {
+    tree: Oid,
+    parents: Vec<Oid>,
+    /// (name, unix seconds)
+    author: (String, u64),
+    message: String,
+}
+
+fn parse_commit(data: &[u8]) -> Result<ParsedCommit> {
+    let text = String::from_utf8_lossy(data);
+    let (headers, message) = text.split_once("\n\n").unwrap_or((&text, ""));
+    let oid = |hex: &str| -> Result<Oid> {
+        hex::decode(hex).ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| Error::Git(format!("bad object id {}", hex)))
+    };
+
+    let mut tree = None;
+    let mut parents = Vec::new();
+    let mut author = ("gently".to_string(), 0);
+    for line in headers.lines() {
+        match line.split_once(' ') {
+            Some(("tree", h)) => tree = Some(oid(h)?),
+            Some(("parent", h)) => parents.push(oid(h)?),
+            Some(("author", ident)) => {
+                let name = ident.split(" <").next().unwrap_or("gently").to_string();
+                let time = ident.rsplit(' ').nth(1).and_then(|t| t.parse().ok()).unwrap_or(0);
+                author = (name, time);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(ParsedCommit {
+        tree: tree.ok_or_else(|| Error::Git("commit without tree".into()))?,
+        parents,
+        author,
+        message: message.to_string(),
+    })
+}
+
+// ── Reading repositories ────────────────────────────────────────────────────
+
+struct Reader {
+    repo: PathBuf,
+    packs: Vec<Pack>,
+}
+
+impl Reader {
+    fn open(repo: &Path) -> Result<Self> {
+        // Accept both bare repos and work trees
+        let repo = if repo.join(".git").is_dir() { repo.join(".git") } else { repo.to_path_buf() };
+        if !repo.join("objects").is_dir() {
+            return Err(Error::Git(format!("{} is not a git repository", repo.display())));
+        }
+
+        let mut packs = Vec::new();
+        if let Ok(dir) = std::fs::read_dir(repo.join("objects/pack")) {
+            for entry in dir.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|e| e == "idx") {
+                    packs.push(Pack::open(&path)?);
+                }
+            }
+        }
+        Ok(Self { repo, packs })
+    }
+
+    /// Branch heads from loose refs and packed-refs, sorted by name
+    fn refs(&self) -> Result<Vec<(String, Oid)>> {
+        let mut refs: HashMap<String, Oid> = HashMap::new();
+        if let Ok(packed) = std::fs::read_to_string(self.repo.join("packed-refs")) {
+            for line in packed.lines() {
+                if let Some((hex, name)) = line.split_once(' ') {
+                    if let (Some(name), Ok(oid)) = (name.strip_prefix("refs/heads/"), hex::decode(hex)) {
+                        if let Ok(oid) = oid.try_into() {
+                            refs.insert(name.to_string(), oid);
+                        }
+                    }
+                }
+            }
+        }
+
+        let heads = self.repo.join("refs/heads");
+        let mut stack = vec![heads.clone()];
+        while let Some(dir) = stack.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    stack.push(path);
+                    continue;
+                }
+                let name = path.strip_prefix(&heads).unwrap().to_string_lossy().replace('\\', "/");
+                let hex = std::fs::read_to_string(&path)?;
+                if let Some(oid) = hex::decode(hex.trim()).ok().and_then(|b| b.try_into().ok()) {
+                    refs.insert(name, oid);
+                }
+            }
+        }
+
+        let mut refs: Vec<(String, Oid)> = refs.into_iter().collect();
+        refs.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(refs)
+    }
+
+    fn head_branch(&self) -> Option<String> {
+        let head = std::fs::read_to_string(self.repo.join("HEAD")).ok()?;
+        head.trim().strip_prefix("ref: refs/heads/").map(str::to_string)
+    }
+
+    /// `(type, data)` of an object, loose or packed
+    fn object(&self, oid: &Oid) -> Result<(String, Vec<u8>)> {
+        let hex = hex::encode(oid);
+        let loose = self.repo.join("objects").join(&hex[..2]).join(&hex[2..]);
+        if let Ok(compressed) = std::fs::read(&loose) {
+            let mut raw = Vec::new();
+            ZlibDecoder::new(&compressed[..]).read_to_end(&mut raw)?;
+            let nul = raw.iter().position(|&b| b == 0)
+                .ok_or_else(|| Error::Git(format!("corrupt object {}", hex)))?;
+            let header = String::from_utf8_lossy(&raw[..nul]).into_owned();
+            let kind = header.split(' ').next().unwrap_or("").to_string();
+            return Ok((kind, raw[nul + 1..].to_vec()));
+        }
+
+        for pack in &self.packs {
+            if let Some(offset) = pack.find(oid) {
+                let (kind, data) = pack.read(offset, self)?;
+                return Ok((pack_type_name(kind)?.to_string(), data));
+            }
+        }
+        Err(Error::Git(format!("object {} not found", hex)))
+    }
+}
+
+struct Pack {
+    ids: Vec<Oid>,
+    offsets: Vec<u64>,
+    data: Vec<u8>,
+}
+
+impl Pack {
+    /// Load a version 2 `.idx` and its `.pack`
+    fn open(idx_path: &Path) -> Result<Self> {
+        let idx = std::fs::read(idx_path)?;
+        let bad = || Error::Git(format!("unsupported pack index {}", idx_path.display()));
+        if idx.len() < 8 + 256 * 4 || idx[..4] != [0xff, b't', b'O', b'c'] || be32(&idx[4..]) != 2 {
+            return Err(bad());
+        }
+
+        let count = be32(&idx[8 + 255 * 4..]) as usize;
+        let ids_at = 8 + 256 * 4;
+        let offsets_at = ids_at + count * 20 + count * 4;
+        let large_at = offsets_at + count * 4;
+        if idx.len() < large_at {
+            return Err(bad());
+        }
+
+        let ids = (0..count).map(|i| idx[ids_at + i * 20..ids_at + i * 20 + 20].try_into().unwrap()).collect();
+        let mut offsets = Vec::with_capacity(count);
+        for i in 0..count {
+            let off = be32(&idx[offsets_at + i * 4..]);
+            if off & 0x8000_0000 == 0 {
+                offsets.push(off as u64);
+            } else {
+                let at = large_at + (off & 0x7fff_ffff) as usize * 8;
+                let bytes = idx.get(at..at + 8).ok_or_else(bad)?;
+                offsets.push(u64::from_be_bytes(bytes.try_into().unwrap()));
+            }
+        }
+
+        let data = std::fs::read(idx_path.with_extension("pack"))?;
+        if data.len() < 12 || &data[..4] != b"PACK" {
+            return Err(bad());
+        }
+        Ok(Self { ids, offsets, data })
+    }
+
+    fn find(&self, oid: &Oid) -> Option<u64> {
+        self.ids.binary_search(oid).ok().map(|i| self.offsets[i])
+    }
+
+    /// Object at `offset` with deltas resolved: `(type, data)`
+    fn read(&self, offset: u64, reader: &Reader) -> Result<(u8, Vec<u8>)> {
+        let bad = || Error::Git("corrupt pack".into());
+        let mut pos = offset as usize;
+        let mut byte = *self.data.get(pos).ok_or_else(bad)?;
+        let kind = (byte >> 4) & 0x7;
+        let mut size = (byte & 0x0f) as usize;
+        let mut shift = 4;
+        while byte & 0x80 != 0 {
+            pos += 1;
+            byte = *self.data.get(pos).ok_or_else(bad)?;
+            size |= ((byte & 0x7f) as usize) << shift;
+            shift += 7;
+        }
+        pos += 1;
+
+        match kind {
+            1..=4 => Ok((kind, self.inflate(pos, size)?)),
+            6 => {
+                // OFS_DELTA: base lives earlier in this pack
+                let mut byte = *self.data.get(pos).ok_or_else(bad)?;
+                let mut back = (byte & 0x7f) as u64;
+                while byte & 0x80 != 0 {
+                    pos += 1;
+                    byte = *self.data.get(pos).ok_or_else(bad)?;
+                    back = ((back + 1) << 7) | (byte & 0x7f) as u64;
+                }
+                let (base_kind, base) = self.read(offset.checked_sub(back).ok_or_else(bad)?, reader)?;
+                Ok((base_kind, apply_delta(&base, &self.inflate(pos + 1, size)?)?))
+            }
+            7 => {
+                // REF_DELTA: base named by id
+                let base_oid: Oid = self.data.get(pos..pos + 20).ok_or_else(bad)?.try_into().unwrap();
+                let (base_kind, base) = reader.object(&base_oid)?;
+                let kind = ["commit", "tree", "blob", "tag"].iter().position(|k| *k == base_kind).ok_or_else(bad)? as u8 + 1;
+                Ok((kind, apply_delta(&base, &self.inflate(pos + 20, size)?)?))
+            }
+            _ => Err(bad()),
+        }
+    }
+
+    fn inflate(&self, pos: usize, size: usize) -> Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(size);
+        ZlibDecoder::new(self.data.get(pos..).unwrap_or_default()).take(size as u64).read_to_end(&mut out)?;
+        if out.len() != size {
+            return Err(Error::Git("truncated pack object".into()));
+        }
+        Ok(out)
+    }
+}
+
+fn pack_type_name(kind: u8) -> Result<&'static str> {
+    match kind {
+        1 => Ok("commit"),
+        2 => Ok("tree"),
+        3 => Ok("blob"),
+        4 => Ok("tag"),
+        _ => Err(Error::Git(format!("unknown pack object type {}", kind))),
+    }
+}
+
+fn apply_delta(base: &[u8], delta: &[u8]) -> Result<Vec<u8>> {
+    let bad = || Error::Git("corrupt delta".into());
+    let mut pos = 0;
+    let varint = |pos: &mut usize| -> Result<usize> {
+        let (mut value, mut shift) = (0usize, 0);
+        loop {
+            let byte = *delta.get(*pos).ok_or_else(bad)?;
+            *pos += 1;
+            value |= ((byte & 0x7f) as usize) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+    };
+    let base_size = varint(&mut pos)?;
+    let target_size = varint(&mut pos)?;
+    if base_size != base.len() {
+        return Err(bad());
+    }
+
+    let mut out = Vec::with_capacity(target_size);
+    while pos < delta.len() {
+        let op = delta[pos];
+        pos += 1;
+        if op & 0x80 != 0 {
+            // Copy from base: offset and size bytes are present per flag bit
+            let (mut offset, mut size) = (0usize, 0usize);
+            for i in 0..4 {
+                if op & (1 << i) != 0 {
+                    offset |= (*delta.get(pos).ok_or_else(bad)? as usize) << (8 * i);
+                    pos += 1;
+                }
+            }
+            for i in 0..3 {
+                if op & (0x10 << i) != 0 {
+                    size |= (*delta.get(pos).ok_or_else(bad)? as usize) << (8 * i);
+                    pos += 1;
+                }
+            }
+            if size == 0 {
+                size = 0x10000;
+            }
+            out.extend_from_slice(base.get(offset..offset + size).ok_or_else(bad)?);
+        } else if op != 0 {
+            out.extend_from_slice(delta.get(pos..pos + op as usize).ok_or_else(bad)?);
+            pos += op as usize;
+        } else {
+            return Err(bad());
+        }
+    }
+
+    if out.len() != target_size {
+        return Err(bad());
+    }
+    Ok(out)
+}
+
+fn be32(b: &[u8]) -> u32 {
+    u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+}
+
+fn manifest_of(chain: &GitChain, hash: &Hash) -> Option<Manifest> {
+    chain.get(hash).and_then(Manifest::from_blob)
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, data)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gently_core::TAG_CHILD;
+    use std::process::Command;
+
+    fn temp_repo(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gently-gitrepo-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn git_available() -> bool {
+        Command::new("git").arg("--version").output().is_ok_and(|o| o.status.success())
+    }
+
+    fn git(repo: &Path, args: &[&str]) -> String {
+        let out = Command::new("git").arg("--git-dir").arg(repo).args(args).output().unwrap();
+        assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+        String::from_utf8(out.stdout).unwrap()
+    }
+
+    /// Commits, a nested tree, a binary blob, interactions and a conflicted merge
+    fn sample_chain() -> GitChain {
+        let mut chain = GitChain::new();
+        chain.init("alice");
+
+        let mut sub = Manifest::new();
+        sub.add(TAG_CHILD, chain.put(Blob::new(Kind::Json, br#"{"concept":"rust"}"#.to_vec())));
+        sub.add(TAG_CHILD, chain.put(Blob::new(Kind::Tensor, vec![0, 1, 2, 255])));
+        let mut tree = Manifest::new();
+        tree.add(TAG_CHILD, chain.put(Blob::new(Kind::Text, b"notes\nline two".to_vec())));
+        tree.add(0x0900, chain.put(sub.to_blob()));
+        tree.add(gently_core::TAG_CONFIG, chain.put(Blob::new(Kind::Text, b"v1".to_vec())));
+        chain.commit(tree.clone(), "Learn rust\n\nWith a body.", "alice");
+
+        chain.branch("feature");
+        chain.checkout("feature");
+        let mut t = tree.clone();
+        t.refs.pop();
+        t.add(gently_core::TAG_CONFIG, chain.put(Blob::new(Kind::Text, b"theirs".to_vec())));
+        chain.commit(t, "feature tweak", "bob <b@x>");
+        chain.checkout("main");
+        let mut t = tree;
+        t.refs.pop();
+        t.add(gently_core::TAG_CONFIG, chain.put(Blob::new(Kind::Text, b"ours".to_vec())));
+        chain.commit(t, "main tweak", "alice");
+        let merge = chain.merge("feature", "alice").unwrap();
+        assert_eq!(merge.conflicts.len(), 1);
+
+        chain.commit_interaction("s1", 0, "hello?", "hi!", "", 840_000, "0000abcd");
+        chain
+    }
+
+    fn assert_same(a: &GitChain, b: &GitChain) {
+        let mut x: Vec<(String, Hash)> = a.branches().into_iter().map(|b| (b.name, b.head)).collect();
+        let mut y: Vec<(String, Hash)> = b.branches().into_iter().map(|b| (b.name, b.head)).collect();
+        x.sort();
+        y.sort();
+        assert_eq!(x, y);
+        assert_eq!(a.current_branch(), b.current_branch());
+    }
+
+    #[test]
+    fn test_roundtrip_lossless() {
+        let chain = sample_chain();
+        let repo = temp_repo("roundtrip");
+
+        let report = export(&chain, &repo).unwrap();
+        assert_eq!(report.refs.len(), 3);
+        assert!(report.commits >= 6);
+
+        let restored = import(&repo).unwrap();
+        assert_same(&chain, &restored);
+        let merge = restored.resolve("main").unwrap();
+        let merge = restored.log(&merge, 10).into_iter().find(|(_, m)| m.message.starts_with("merge")).unwrap().0;
+        assert_eq!(restored.conflicts(&merge).len(), 1);
+        let session = &restored.session_branches()[0];
+        assert_eq!(restored.interaction_meta(&session.head).unwrap().btc_height, 840_000);
+
+        // Exporting again writes nothing new
+        assert_eq!(export(&restored, &repo).unwrap().objects_written, 0);
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn test_git_reads_export() {
+        if !git_available() {
+            return;
+        }
+        let chain = sample_chain();
+        let repo = temp_repo("fsck");
+        export(&chain, &repo).unwrap();
+
+        git(&repo, &["fsck", "--strict", "--no-dangling"]);
+        let log = git(&repo, &["log", "--format=%an|%s", "main"]);
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines[0], "alice|merge feature into main");
+        assert!(lines.contains(&"bob b@x|feature tweak"));
+        assert_eq!(git(&repo, &["log", "-1", "--format=%(trailers:key=Gently-Branch,valueonly)", "feature"]).trim(), "\"feature\"");
+        let files = git(&repo, &["ls-tree", "-r", "--name-only", "main~1"]);
+        assert!(files.contains("000001-0900/000001-0003.k02.bin"), "{}", files);
+
+        // Packed repositories import too
+        git(&repo, &["gc", "--aggressive", "--prune=now", "--quiet"]);
+        assert!(std::fs::read_dir(repo.join("objects/pack")).unwrap().count() > 0);
+        assert_same(&chain, &import(&repo).unwrap());
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn test_foreign_commits_and_tampering() {
+        let chain = sample_chain();
+        let repo = temp_repo("tamper");
+        export(&chain, &repo).unwrap();
+
+        // A commit whose content no longer matches its Gently-Commit trailer
+        let mut writer = Writer::open(&repo).unwrap();
+        let head = import(&repo).unwrap().resolve("main").unwrap();
+        let mut meta = chain.meta(&head).unwrap();
+        let tree = writer.object("tree", &[]).unwrap();
+        meta.message = "rewritten".into();
+        let body = format!("tree {}\nauthor a <a@b> 1 +0000\ncommitter a <a@b> 1 +0000\n\n{}", hex::encode(tree), encode_message(&meta, &head));
+        let oid = writer.object("commit", body.as_bytes()).unwrap();
+        write_file(&repo.join("refs/heads/main"), format!("{}\n", hex::encode(oid)).as_bytes()).unwrap();
+        assert!(matches!(import(&repo), Err(Error::Git(e)) if e.contains("expected")));
+
+        // A plain git commit with a foreign file imports as a text child
+        let blob = writer.object("blob", b"readme").unwrap();
+        let tree = writer.object("tree", &encode_tree(vec![TreeEntry::file("README".into(), blob)])).unwrap();
+        let body = format!("tree {}\nauthor Carol <c@d> 1700000000 +0100\ncommitter Carol <c@d> 1700000000 +0100\n\nhand made\n", hex::encode(tree));
+        let oid = writer.object("commit", body.as_bytes()).unwrap();
+        write_file(&repo.join("refs/heads/main"), format!("{}\n", hex::encode(oid)).as_bytes()).unwrap();
+
+        let restored = import(&repo).unwrap();
+        let head = restored.resolve("main").unwrap();
+        let meta = restored.meta(&head).unwrap();
+        assert_eq!((meta.message.as_str(), meta.author.as_str(), meta.timestamp), ("hand made", "Carol", 1_700_000_000));
+        let child = restored.tree(&head).unwrap().get(TAG_CHILD).unwrap();
+        assert_eq!(restored.get(&child).unwrap().data, b"readme");
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn test_apply_delta() {
+        let base = b"hello brave new world";
+        // copy 0..6, insert "old", copy 15..21
+        let delta = [21, 15, 0x90, 6, 3, b'o', b'l', b'd', 0x91, 15, 6];
+        assert_eq!(apply_delta(base, &delta).unwrap(), b"hello old world");
+        assert!(apply_delta(base, &[3, 1, 0x90, 1]).is_err());
+    }
+}
@@ -6,9 +6,10 @@
 //! Persists to SQLite for local durability.
 
 use crate::{Result, Error};
+use gently_alexandria::formats::{DocEdge, DocNode, GraphDocument, GraphFormat};
 use rusqlite::{Connection, params};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
@@ -176,6 +177,15 @@ impl KnowledgeGraph {
         self.log_growth(GrowthType::EdgeAdded, None, Some(edge_idx), "connect");
     }
 
+    /// Is there already a `kind` edge from `from` to `to`?
+    fn has_edge(&self, from: &str, to: &str, kind: EdgeType) -> bool {
+        let edges = self.edges.lock().unwrap();
+        let index = self.index.lock().unwrap();
+        index.outgoing.get(from).is_some_and(|out| {
+            out.iter().any(|&i| edges[i].to == to && edges[i].edge_type == kind)
+        })
+    }
+
     /// Find a node by concept name
     pub fn find(&self, concept: &str) -> Option<KnowledgeNode> {
         let id = {
@@ -470,6 +480,138 @@ impl KnowledgeGraph {
         Ok(())
     }
 
+    /// Build a format-neutral document, optionally limited to `hops` around a concept
+    pub fn to_document(&self, around: Option<(&str, usize)>) -> GraphDocument {
+        let nodes = self.nodes.lock().unwrap();
+        let edges = self.edges.lock().unwrap();
+
+        let mut doc_nodes: Vec<DocNode> = nodes
+            .values()
+            .map(|node| {
+                let mut attributes = BTreeMap::new();
+                attributes.insert("description".to_string(), node.description.clone());
+                attributes.insert("node_type".to_string(), format!("{:?}", node.node_type));
+                attributes.insert("confidence".to_string(), node.confidence.to_string());
+                attributes.insert("created_at".to_string(), node.created_at.to_string());
+                attributes.insert("accessed_count".to_string(), node.accessed_count.to_string());
+                if let Some(source) = &node.source {
+                    attributes.insert("source".to_string(), source.clone());
+                }
+                if let Some(cid) = &node.ipfs_cid {
+                    attributes.insert("ipfs_cid".to_string(), cid.clone());
+                }
+                DocNode {
+                    id: node.id.clone(),
+                    label: node.concept.clone(),
+                    attributes,
+                }
+            })
+            .collect();
+        doc_nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let doc = GraphDocument {
+            nodes: doc_nodes,
+            edges: edges
+                .iter()
+                .map(|edge| {
+                    let mut attributes = BTreeMap::new();
+                    if let Some(context) = &edge.context {
+                        attributes.insert("context".to_string(), context.clone());
+                    }
+                    DocEdge {
+                        source: edge.from.clone(),
+                        target: edge.to.clone(),
+                        kind: format!("{:?}", edge.edge_type),
+                        weight: edge.weight,
+                        attributes,
+                    }
+                })
+                .collect(),
+        };
+        drop(nodes);
+        drop(edges);
+
+        match around {
+            None => doc,
+            Some((concept, hops)) => match self.find(concept) {
+                Some(node) => doc.neighbourhood(&node.id, hops),
+                None => GraphDocument::new(),
+            },
+        }
+    }
+
+    /// Export (part of) the graph as GraphML, GEXF, DOT, JSON-LD or CSV
+    pub fn export_as(&self, format: GraphFormat, around: Option<(&str, usize)>) -> String {
+        self.to_document(around).write(format)
+    }
+
+    /// Merge a document into the graph; returns the number of edges added
+    ///
+    /// Nodes are matched by concept name and edges by (from, to, kind), so
+    /// re-importing an export duplicates neither.
+    pub fn import_document(&self, doc: &GraphDocument) -> usize {
+        let mut ids: HashMap<String, String> = HashMap::new();
+
+        let mut resolve = |key: &str, label: &str, attributes: Option<&BTreeMap<String, String>>| {
+            if let Some(id) = ids.get(key) {
+                return id.clone();
+            }
+            let concept = if label.is_empty() { key } else { label };
+            let id = match self.find(concept) {
+                Some(existing) => existing.id,
+                None => {
+                    let attr = |name: &str| attributes.and_then(|a| a.get(name));
+                    let id = self.add_concept(
+                        concept,
+                        attr("description").map(String::as_str).unwrap_or(""),
+                        attr("node_type").map(|t| parse_node_type(t)).unwrap_or(NodeType::Concept),
+                    );
+                    let mut nodes = self.nodes.lock().unwrap();
+                    if let Some(node) = nodes.get_mut(&id) {
+                        if let Some(confidence) = attr("confidence").and_then(|c| c.parse().ok()) {
+                            node.confidence = confidence;
+                        }
+                        node.source = attr("source").cloned();
+                        node.ipfs_cid = attr("ipfs_cid").cloned();
+                    }
+                    id
+                }
+            };
+            ids.insert(key.to_string(), id.clone());
+            id
+        };
+
+        for node in &doc.nodes {
+            resolve(&node.id, &node.label, Some(&node.attributes));
+        }
+
+        let mut added = 0;
+        for edge in &doc.edges {
+            let from = resolve(&edge.source, "", None);
+            let to = resolve(&edge.target, "", None);
+            let kind = parse_edge_type(&edge.kind);
+            if self.has_edge(&from, &to, kind) {
+                continue;
+            }
+            self.connect(&from, &to, kind, Some(edge.weight));
+            if let Some(context) = edge.attributes.get("context") {
+                if let Some(last) = self.edges.lock().unwrap().last_mut() {
+                    last.context = Some(context.clone());
+                }
+            }
+            added += 1;
+        }
+
+        added
+    }
+
+    /// Import GraphML or CSV edge-list data
+    pub fn import_from(&self, format: GraphFormat, data: &str) -> Result<usize> {
+        let doc = GraphDocument::read(format, data)
+            .map_err(|e| Error::InferenceFailed(e.to_string()))?;
+        Ok(self.import_document(&doc))
+    }
+
     /// Get statistics
     pub fn stats(&self) -> GraphStats {
         let nodes = self.nodes.lock().unwrap();
@@ -845,6 +987,42 @@ mod tests {
         assert_eq!(related[0].0.concept, "cipher");
     }
 
+    #[test]
+    fn test_format_export_import() {
+        let graph = KnowledgeGraph::new();
+        let cipher = graph.add_concept("cipher", "Encryption algorithm", NodeType::Concept);
+        let aes = graph.add_concept("AES", "Advanced Encryption Standard", NodeType::Entity);
+        let key = graph.add_concept("key", "Secret material", NodeType::Concept);
+        let unrelated = graph.add_concept("garden", "Plants", NodeType::Context);
+        graph.connect(&aes, &cipher, EdgeType::IsA, Some(0.9));
+        graph.connect(&aes, &key, EdgeType::Requires, None);
+        graph.connect(&unrelated, &unrelated, EdgeType::RelatedTo, None);
+
+        let local = graph.to_document(Some(("cipher", 1)));
+        assert_eq!(local.nodes.len(), 2);
+        assert_eq!(local.edges.len(), 1);
+
+        let xml = graph.export_as(GraphFormat::GraphMl, Some(("AES", 1)));
+        let copy = KnowledgeGraph::new();
+        assert_eq!(copy.import_from(GraphFormat::GraphMl, &xml).unwrap(), 2);
+
+        let aes_copy = copy.find("AES").unwrap();
+        assert_eq!(aes_copy.node_type, NodeType::Entity);
+        assert_eq!(aes_copy.description, "Advanced Encryption Standard");
+        let related = copy.related(&aes_copy.id);
+        assert_eq!(related.len(), 2);
+        assert!(copy.find("garden").is_none());
+
+        // CSV endpoints are concept names and reuse existing nodes
+        copy.import_from(GraphFormat::Csv, "AES,block cipher,IsA,1.0\n").unwrap();
+        assert_eq!(copy.stats().node_count, 4);
+
+        // Importing the same data again adds nothing
+        assert_eq!(copy.import_from(GraphFormat::GraphMl, &xml).unwrap(), 0);
+        assert_eq!(copy.import_from(GraphFormat::Csv, "AES,block cipher,IsA,1.0\n").unwrap(), 0);
+        assert_eq!(copy.stats().edge_count, 3);
+    }
+
     #[test]
     fn test_learning() {
         let graph = KnowledgeGraph::new();
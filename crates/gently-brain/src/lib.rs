@@ -12,42 +12,66 @@
 //! The brain grows smarter through routine processes.
 
 pub mod agent;
+pub mod agent_loop;
 pub mod embedder;
 pub mod evolve;
 pub mod gitchain;
+pub mod gitrepo;
+pub mod gguf;
 pub mod llama;
 pub mod lora;
 pub mod modelchain;
+pub mod tensor;
 pub mod tensorchain;
 pub mod download;
+pub mod chat;
 pub mod claude;
 pub mod skills;
 pub mod daemon;
 pub mod knowledge;
 pub mod learner;
+pub mod local_embed;
 pub mod mcp;
 pub mod orchestrator;
+pub mod permissions;
 pub mod pipeline;
+pub mod rules;
+pub mod sampling;
+pub mod tokenizer;
+pub mod train;
+pub mod transformer;
+pub mod wasm;
 pub mod watchdog;
 
-pub use agent::{Agent, AgentRuntime, AgentMeta, Observation};
+pub use agent::{Agent, AgentRuntime, AgentMeta, Observation, RunOutcome};
+pub use agent_loop::{AgentLoop, AgentConfig, AgentEvent, AgentModel, AgentRun, CancelHandle, ModelTurn, StopReason, ToolRunner, Turn};
 pub use embedder::Embedder;
 pub use evolve::{Evolver, EvolveLoop, EvolveConfig, EvolveState, Pattern, CycleResult};
-pub use gitchain::{GitChain, CommitMeta, Branch};
-pub use llama::LlamaInference;
+pub use gitchain::{GitChain, CommitMeta, Branch, Change, Conflict, DiffEntry, MergeKind, MergeResult, RebaseResult};
+pub use gguf::{GgufFile, GgufWriter, GgmlType};
+pub use llama::{LlamaInference, LlamaRefiner};
 pub use lora::{LoraChain, LoraConfig, LoraWeights};
 pub use modelchain::{ModelChain, ModelMeta, TensorSchema, Pipeline};
+pub use tensor::{DType, Tensor, TensorBlob};
 pub use tensorchain::TensorChain;
 pub use download::ModelDownloader;
-pub use claude::{ClaudeClient, ClaudeModel, ClaudeSession, GentlyAssistant, Message, AssistantResponse, ToolUseResponse, ToolResultInput};
+pub use chat::{AnthropicBackend, ChatBackend, ChatRequest};
+pub use claude::{ChatClient, ChatSession, ClaudeClient, ClaudeModel, ClaudeSession, GentlyAssistant, Message, AssistantResponse, ToolUseResponse, ToolResultInput};
 pub use skills::{Skill, SkillRegistry, SkillResult, SkillCategory, SkillHandler, SkillContext};
-pub use daemon::{DaemonManager, DaemonType, DaemonEvent, AwarenessState};
+pub use daemon::{DaemonManager, DaemonType, DaemonEvent, DaemonTask, AwarenessState, RestartPolicy, SupervisorConfig};
 pub use knowledge::{KnowledgeGraph, KnowledgeNode, NodeType, EdgeType};
 pub use learner::{ConversationLearner, LearnedConcept, LearningResult};
-pub use mcp::{McpToolRegistry, Tool, ToolCategory, ToolResult, ToolExecutor};
+pub use mcp::{McpToolRegistry, Tool, ToolCategory, ToolResult, ToolExecutor, EffectKind};
+pub use permissions::{Permission, ToolPolicy, ToolConfirmer, ConfirmRequest, Decision, AuditRecord};
 pub use orchestrator::{BrainOrchestrator, BrainConfig, ProcessingResult};
 pub use pipeline::{BlobPipeline, PipelineConfig, SyncJob, SyncResult};
-pub use watchdog::{Watchdog, Event, Rule, Action, EventKind};
+pub use sampling::{Sampler, SamplingConfig};
+pub use tokenizer::Tokenizer;
+pub use train::{LoraTrainer, TrainConfig, TrainReport};
+pub use transformer::{KvCache, LlamaConfig, LlamaModel};
+pub use wasm::{WasmSandbox, WasmLimits, RunStatus, AgentAction};
+pub use watchdog::{Watchdog, Event, Rule, Action, EventKind, ReloadReport, ReplayReport};
+pub use rules::{Firing, RuleEngine, RuleSpec};
 
 use thiserror::Error;
 
@@ -62,6 +86,27 @@ pub enum Error {
     #[error("Embedding failed: {0}")]
     EmbeddingFailed(String),
 
+    #[error("Invalid model file: {0}")]
+    InvalidModel(String),
+
+    #[error("Tensor error: {0}")]
+    Tensor(String),
+
+    #[error("Daemon error: {0}")]
+    Daemon(String),
+
+    #[error("Git error: {0}")]
+    Git(String),
+
+    #[error("Rule error: {0}")]
+    Rules(String),
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Invalid arguments: {0}")]
+    InvalidArguments(String),
+
     #[error("Download failed: {0}")]
     DownloadFailed(String),
 
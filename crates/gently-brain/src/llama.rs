@@ -1,33 +1,40 @@
-//! Local Llama Inference via Candle
+//! Local Llama Inference
 //!
-//! TinyLlama 1.1B for local inference.
+//! TinyLlama 1.1B (or any Llama-family GGUF) on the CPU, pure Rust.
 //! Runs on 4GB RAM, ~10-20 tokens/sec on CPU.
+//!
+//! ```text
+//! prompt ─► Tokenizer ─► LlamaModel ─► Sampler ─► StreamDecoder ─► text
+//!            (GGUF vocab)   (KV cache)   (top-k/p,     (on_token)
+//!                                         penalty)
+//! ```
+//!
+//! The KV cache survives between calls: a prompt that extends the last
+//! one (the usual chat case) only pays for its new tokens.
 
+use crate::sampling::{Sampler, SamplingConfig};
+use crate::tokenizer::Tokenizer;
+use crate::transformer::{KvCache, LlamaConfig, LlamaModel};
+use crate::gguf::GgufFile;
 use crate::{Error, Result};
+use gently_search::hyperspace::{Dimension, DimensionValue};
+use gently_search::extract::{parse_refinement, refinement_prompt, DimensionRefiner, DimensionTags};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-#[cfg(feature = "candle")]
-use {
-    candle_core::{Device, Tensor},
-    candle_core::quantized::gguf_file,
-    candle_transformers::models::quantized_llama::ModelWeights,
-    candle_transformers::generation::LogitsProcessor,
-    hf_hub::{api::sync::Api, Repo, RepoType},
-    tokenizers::Tokenizer,
-};
+#[cfg(feature = "download")]
+use hf_hub::{api::sync::Api, Repo, RepoType};
 
 /// Llama inference engine
 pub struct LlamaInference {
     model_path: Option<PathBuf>,
-    loaded: bool,
-    context_size: usize,
-    temperature: f32,
-    #[cfg(feature = "candle")]
-    model: Option<ModelWeights>,
-    #[cfg(feature = "candle")]
+    model: Option<LlamaModel>,
     tokenizer: Option<Tokenizer>,
-    #[cfg(feature = "candle")]
-    device: Device,
+    cache: Option<KvCache>,
+    /// Tokens whose keys/values are in `cache`
+    cached: Vec<u32>,
+    context_size: usize,
+    sampling: SamplingConfig,
 }
 
 impl LlamaInference {
@@ -35,293 +42,187 @@ impl LlamaInference {
     pub fn new() -> Self {
         Self {
             model_path: None,
-            loaded: false,
-            context_size: 2048,
-            temperature: 0.7,
-            #[cfg(feature = "candle")]
             model: None,
-            #[cfg(feature = "candle")]
             tokenizer: None,
-            #[cfg(feature = "candle")]
-            device: Device::Cpu,
+            cache: None,
+            cached: Vec::new(),
+            context_size: 2048,
+            sampling: SamplingConfig::default(),
         }
     }
 
-    /// Load model from GGUF file
-    #[cfg(feature = "candle")]
+    /// Load model and tokenizer from a GGUF file
     pub fn load(&mut self, path: &Path) -> Result<()> {
-        if !path.exists() {
-            return Err(Error::ModelNotFound(path.display().to_string()));
-        }
-
         tracing::info!("Loading GGUF model from {}", path.display());
 
-        // Load GGUF file
-        let mut file = std::fs::File::open(path)
-            .map_err(|e| Error::ModelNotFound(e.to_string()))?;
-
-        let model_content = gguf_file::Content::read(&mut file)
-            .map_err(|e| Error::InferenceFailed(format!("Failed to read GGUF: {}", e)))?;
-
-        // Reset file position for model loading
-        use std::io::Seek;
-        file.seek(std::io::SeekFrom::Start(0))
-            .map_err(|e| Error::InferenceFailed(format!("Failed to seek: {}", e)))?;
-
-        // Create model from quantized weights
-        let model = ModelWeights::from_gguf(model_content, &mut file, &self.device)
-            .map_err(|e| Error::InferenceFailed(format!("Failed to load model: {}", e)))?;
-
-        // Load tokenizer
-        let tokenizer = self.load_tokenizer()?;
+        let file = GgufFile::open(path)?;
+        let tokenizer = Tokenizer::from_gguf(&file)?;
+        let model = LlamaModel::from_gguf(file)?;
+        if tokenizer.vocab_size() != model.config().vocab_size {
+            return Err(Error::InvalidModel(format!(
+                "tokenizer has {} tokens but the model expects {}",
+                tokenizer.vocab_size(),
+                model.config().vocab_size
+            )));
+        }
 
         self.model = Some(model);
         self.tokenizer = Some(tokenizer);
+        self.cache = None;
+        self.cached.clear();
         self.model_path = Some(path.to_path_buf());
-        self.loaded = true;
 
         tracing::info!("Model loaded successfully");
         Ok(())
     }
 
-    #[cfg(feature = "candle")]
-    fn load_tokenizer(&self) -> Result<Tokenizer> {
-        // First try local tokenizer in models directory
-        let local_tokenizer = ModelInfo::default_path().join("tokenizer.json");
-        if local_tokenizer.exists() {
-            tracing::info!("Loading tokenizer from {}", local_tokenizer.display());
-            return Tokenizer::from_file(&local_tokenizer)
-                .map_err(|e| Error::InferenceFailed(format!("Failed to load local tokenizer: {}", e)));
-        }
-
-        // Fall back to HuggingFace Hub
-        tracing::info!("Downloading tokenizer from HuggingFace...");
-        let api = Api::new().map_err(|e| Error::InferenceFailed(format!("HF API error: {}", e)))?;
-        let repo = api.repo(Repo::with_revision(
-            "TinyLlama/TinyLlama-1.1B-Chat-v1.0".to_string(),
-            RepoType::Model,
-            "main".to_string(),
-        ));
-
-        let tokenizer_path = repo.get("tokenizer.json")
-            .map_err(|e| Error::InferenceFailed(format!("Failed to get tokenizer: {}", e)))?;
-
-        // Copy to local directory for future use
-        if let Err(e) = std::fs::copy(&tokenizer_path, &local_tokenizer) {
-            tracing::warn!("Failed to cache tokenizer locally: {}", e);
-        }
+    /// Check if model is loaded
+    pub fn is_loaded(&self) -> bool {
+        self.model.is_some()
+    }
 
-        Tokenizer::from_file(tokenizer_path)
-            .map_err(|e| Error::InferenceFailed(format!("Failed to load tokenizer: {}", e)))
+    pub fn model_path(&self) -> Option<&Path> {
+        self.model_path.as_deref()
     }
 
-    #[cfg(not(feature = "candle"))]
-    pub fn load(&mut self, path: &Path) -> Result<()> {
-        if !path.exists() {
-            return Err(Error::ModelNotFound(path.display().to_string()));
-        }
-        self.model_path = Some(path.to_path_buf());
-        self.loaded = true;
-        Ok(())
+    /// Hyperparameters of the loaded model
+    pub fn config(&self) -> Option<&LlamaConfig> {
+        self.model.as_ref().map(LlamaModel::config)
     }
 
-    /// Check if model is loaded
-    pub fn is_loaded(&self) -> bool {
-        self.loaded
+    pub fn tokenizer(&self) -> Option<&Tokenizer> {
+        self.tokenizer.as_ref()
     }
 
     /// Set temperature for generation
     pub fn set_temperature(&mut self, temp: f32) {
-        self.temperature = temp.clamp(0.0, 2.0);
+        self.sampling.temperature = temp.clamp(0.0, 2.0);
     }
 
-    /// Generate completion for a prompt
-    #[cfg(feature = "candle")]
-    pub fn complete(&mut self, prompt: &str, max_tokens: usize) -> Result<String> {
-        if !self.loaded {
-            return Err(Error::ModelNotFound("Llama not loaded".into()));
-        }
-
-        let model = self.model.as_mut().ok_or(Error::ModelNotFound("Model not initialized".into()))?;
-        let tokenizer = self.tokenizer.as_ref().ok_or(Error::ModelNotFound("Tokenizer not initialized".into()))?;
-
-        // Encode prompt
-        let encoding = tokenizer.encode(prompt, true)
-            .map_err(|e| Error::InferenceFailed(format!("Tokenization failed: {}", e)))?;
-        let prompt_tokens: Vec<u32> = encoding.get_ids().to_vec();
-
-        // Create logits processor for sampling
-        let mut logits_processor = LogitsProcessor::new(
-            42,  // seed
-            Some(self.temperature as f64),
-            None, // top_p
-        );
-
-        let mut all_tokens = prompt_tokens.clone();
-        let eos_token = tokenizer.token_to_id("</s>").unwrap_or(2);
-
-        // Process prompt first (prefill)
-        let prompt_tensor = Tensor::new(prompt_tokens.as_slice(), &self.device)
-            .map_err(|e| Error::InferenceFailed(format!("Tensor creation failed: {}", e)))?
-            .unsqueeze(0)
-            .map_err(|e| Error::InferenceFailed(format!("Unsqueeze failed: {}", e)))?;
-
-        let logits = model.forward(&prompt_tensor, 0)
-            .map_err(|e| Error::InferenceFailed(format!("Forward pass failed: {}", e)))?;
-
-        let logits = logits.squeeze(0)
-            .map_err(|e| Error::InferenceFailed(format!("Squeeze failed: {}", e)))?;
-
-        let next_token = logits_processor.sample(&logits)
-            .map_err(|e| Error::InferenceFailed(format!("Sampling failed: {}", e)))?;
-
-        if next_token == eos_token {
-            return Ok(String::new());
-        }
-        all_tokens.push(next_token);
-
-        // Generate tokens one by one
-        for _ in 1..max_tokens {
-            let input = Tensor::new(&[*all_tokens.last().unwrap()], &self.device)
-                .map_err(|e| Error::InferenceFailed(format!("Tensor creation failed: {}", e)))?
-                .unsqueeze(0)
-                .map_err(|e| Error::InferenceFailed(format!("Unsqueeze failed: {}", e)))?;
+    /// Keep only the k most likely tokens (0 = off)
+    pub fn set_top_k(&mut self, k: usize) {
+        self.sampling.top_k = k;
+    }
 
-            let logits = model.forward(&input, all_tokens.len() - 1)
-                .map_err(|e| Error::InferenceFailed(format!("Forward pass failed: {}", e)))?;
+    /// Nucleus sampling threshold (1.0 = off)
+    pub fn set_top_p(&mut self, p: f32) {
+        self.sampling.top_p = p.clamp(0.0, 1.0);
+    }
 
-            let logits = logits.squeeze(0)
-                .map_err(|e| Error::InferenceFailed(format!("Squeeze failed: {}", e)))?;
+    /// Penalty for recently generated tokens (1.0 = off)
+    pub fn set_repeat_penalty(&mut self, penalty: f32, last_n: usize) {
+        self.sampling.repeat_penalty = penalty.max(1.0);
+        self.sampling.repeat_last_n = last_n;
+    }
 
-            let next_token = logits_processor.sample(&logits)
-                .map_err(|e| Error::InferenceFailed(format!("Sampling failed: {}", e)))?;
+    pub fn set_seed(&mut self, seed: u64) {
+        self.sampling.seed = seed;
+    }
 
-            if next_token == eos_token {
-                break;
-            }
+    pub fn set_sampling(&mut self, sampling: SamplingConfig) {
+        self.sampling = sampling;
+    }
 
-            all_tokens.push(next_token);
-        }
+    pub fn sampling(&self) -> &SamplingConfig {
+        &self.sampling
+    }
 
-        // Decode output (only the generated part)
-        let output_tokens: Vec<u32> = all_tokens[prompt_tokens.len()..].to_vec();
-        let output = tokenizer.decode(&output_tokens, true)
-            .map_err(|e| Error::InferenceFailed(format!("Decoding failed: {}", e)))?;
+    /// Max tokens held in the KV cache (capped by the model's context)
+    pub fn set_context_size(&mut self, tokens: usize) {
+        self.context_size = tokens.max(1);
+        self.cache = None;
+        self.cached.clear();
+    }
 
-        Ok(output)
+    /// Generate completion for a prompt
+    pub fn complete(&mut self, prompt: &str, max_tokens: usize) -> Result<String> {
+        self.complete_streaming(prompt, max_tokens, |_| {})
     }
 
     /// Streaming completion - calls callback for each generated token
-    #[cfg(feature = "candle")]
     pub fn complete_streaming<F>(&mut self, prompt: &str, max_tokens: usize, mut on_token: F) -> Result<String>
     where
         F: FnMut(&str),
     {
-        if !self.loaded {
+        let (Some(model), Some(tokenizer)) = (&self.model, &self.tokenizer) else {
             return Err(Error::ModelNotFound("Llama not loaded".into()));
-        }
-
-        let model = self.model.as_mut().ok_or(Error::ModelNotFound("Model not initialized".into()))?;
-        let tokenizer = self.tokenizer.as_ref().ok_or(Error::ModelNotFound("Tokenizer not initialized".into()))?;
-
-        // Encode prompt
-        let encoding = tokenizer.encode(prompt, true)
-            .map_err(|e| Error::InferenceFailed(format!("Tokenization failed: {}", e)))?;
-        let prompt_tokens: Vec<u32> = encoding.get_ids().to_vec();
-
-        // Create logits processor for sampling
-        let mut logits_processor = LogitsProcessor::new(
-            42,  // seed
-            Some(self.temperature as f64),
-            None, // top_p
-        );
-
-        let mut all_tokens = prompt_tokens.clone();
-        let eos_token = tokenizer.token_to_id("</s>").unwrap_or(2);
-        let mut output = String::new();
-
-        // Process prompt first (prefill)
-        let prompt_tensor = Tensor::new(prompt_tokens.as_slice(), &self.device)
-            .map_err(|e| Error::InferenceFailed(format!("Tensor creation failed: {}", e)))?
-            .unsqueeze(0)
-            .map_err(|e| Error::InferenceFailed(format!("Unsqueeze failed: {}", e)))?;
-
-        let logits = model.forward(&prompt_tensor, 0)
-            .map_err(|e| Error::InferenceFailed(format!("Forward pass failed: {}", e)))?;
-
-        let logits = logits.squeeze(0)
-            .map_err(|e| Error::InferenceFailed(format!("Squeeze failed: {}", e)))?;
-
-        let next_token = logits_processor.sample(&logits)
-            .map_err(|e| Error::InferenceFailed(format!("Sampling failed: {}", e)))?;
+        };
+
+        let capacity = self.context_size.min(model.config().context_length);
+        let cache = match &mut self.cache {
+            Some(cache) if cache.capacity() == capacity => cache,
+            slot => {
+                self.cached.clear();
+                slot.insert(model.new_cache(capacity)?)
+            }
+        };
 
-        if next_token == eos_token {
-            return Ok(String::new());
+        let mut tokens = tokenizer.encode(prompt, tokenizer.add_bos());
+        if tokens.is_empty() {
+            tokens.push(tokenizer.bos());
         }
-        all_tokens.push(next_token);
-
-        // Decode and stream first token
-        if let Ok(text) = tokenizer.decode(&[next_token], true) {
-            output.push_str(&text);
-            on_token(&text);
+        // Leave room to generate by dropping the oldest prompt tokens
+        let budget = capacity.saturating_sub(max_tokens.min(capacity / 2)).max(1);
+        if tokens.len() > budget {
+            let cut = tokens.len() - budget;
+            if tokens[0] == tokenizer.bos() {
+                tokens.drain(1..=cut);
+            } else {
+                tokens.drain(..cut);
+            }
         }
 
-        // Generate tokens one by one
-        for _ in 1..max_tokens {
-            let input = Tensor::new(&[*all_tokens.last().unwrap()], &self.device)
-                .map_err(|e| Error::InferenceFailed(format!("Tensor creation failed: {}", e)))?
-                .unsqueeze(0)
-                .map_err(|e| Error::InferenceFailed(format!("Unsqueeze failed: {}", e)))?;
-
-            let logits = model.forward(&input, all_tokens.len() - 1)
-                .map_err(|e| Error::InferenceFailed(format!("Forward pass failed: {}", e)))?;
-
-            let logits = logits.squeeze(0)
-                .map_err(|e| Error::InferenceFailed(format!("Squeeze failed: {}", e)))?;
+        // Reuse the cached prefix; at least one token must run to get logits
+        let shared = self
+            .cached
+            .iter()
+            .zip(&tokens)
+            .take_while(|(a, b)| a == b)
+            .count()
+            .min(tokens.len() - 1);
+        cache.truncate(shared);
+        self.cached.truncate(shared);
+
+        let mut logits = Vec::new();
+        for &token in &tokens[shared..] {
+            logits = model.forward(token, cache)?;
+            self.cached.push(token);
+        }
 
-            let next_token = logits_processor.sample(&logits)
-                .map_err(|e| Error::InferenceFailed(format!("Sampling failed: {}", e)))?;
+        let mut sampler = Sampler::new(self.sampling.clone());
+        let mut decoder = tokenizer.decoder();
+        let mut history = tokens;
+        let mut output = String::new();
 
-            if next_token == eos_token {
+        for generated in 1..=max_tokens {
+            let next = sampler.sample(&mut logits, &history);
+            if next == tokenizer.eos() {
                 break;
             }
+            history.push(next);
 
-            all_tokens.push(next_token);
-
-            // Decode and stream this token
-            if let Ok(text) = tokenizer.decode(&[next_token], true) {
+            let text = decoder.push(next);
+            if !text.is_empty() {
                 output.push_str(&text);
                 on_token(&text);
             }
-        }
 
-        Ok(output)
-    }
-
-    /// Streaming completion (no-op when candle not available)
-    #[cfg(not(feature = "candle"))]
-    pub fn complete_streaming<F>(&self, prompt: &str, max_tokens: usize, mut on_token: F) -> Result<String>
-    where
-        F: FnMut(&str),
-    {
-        if !self.loaded {
-            return Err(Error::ModelNotFound("Llama not loaded".into()));
+            if generated == max_tokens || cache.is_full() {
+                break;
+            }
+            logits = model.forward(next, cache)?;
+            self.cached.push(next);
         }
-        let output = self.simulate_completion(prompt, max_tokens);
-        on_token(&output);
-        Ok(output)
-    }
 
-    #[cfg(not(feature = "candle"))]
-    pub fn complete(&self, prompt: &str, max_tokens: usize) -> Result<String> {
-        if !self.loaded {
-            return Err(Error::ModelNotFound("Llama not loaded".into()));
+        let rest = decoder.finish();
+        if !rest.is_empty() {
+            output.push_str(&rest);
+            on_token(&rest);
         }
-        Ok(self.simulate_completion(prompt, max_tokens))
+        Ok(output)
     }
 
     /// Generate code completion
-    #[cfg(feature = "candle")]
     pub fn complete_code(&mut self, code_prefix: &str, max_tokens: usize) -> Result<String> {
         let prompt = format!(
             "Complete the following code:\n\n```\n{}\n```\n\nCompletion:",
@@ -330,15 +231,6 @@ impl LlamaInference {
         self.complete(&prompt, max_tokens)
     }
 
-    #[cfg(not(feature = "candle"))]
-    pub fn complete_code(&self, code_prefix: &str, max_tokens: usize) -> Result<String> {
-        let prompt = format!(
-            "Complete the following code:\n\n```\n{}\n```\n\nCompletion:",
-            code_prefix
-        );
-        self.complete(&prompt, max_tokens)
-    }
-
     /// Answer a coding question (chat format)
     pub fn chat(&mut self, messages: &[ChatMessage]) -> Result<String> {
         let prompt = self.format_chat(messages);
@@ -394,17 +286,6 @@ impl LlamaInference {
         prompt.push_str("<|assistant|>\n");
         prompt
     }
-
-    /// Simulate completion (placeholder when candle not available)
-    #[allow(dead_code)]
-    fn simulate_completion(&self, prompt: &str, _max_tokens: usize) -> String {
-        format!(
-            "[TinyLlama-1.1B would respond to: {}...]\n\n\
-             Note: Model not actually loaded. This is a placeholder response.\n\
-             Run `gently chat` to download and use the model.",
-            &prompt[..prompt.len().min(50)]
-        )
-    }
 }
 
 impl Default for LlamaInference {
@@ -413,6 +294,38 @@ impl Default for LlamaInference {
     }
 }
 
+/// 5W refinement through a local model
+///
+/// Does nothing until the model is loaded, so the rule-based tags stand on
+/// their own when running without weights.
+pub struct LlamaRefiner {
+    llama: Mutex<LlamaInference>,
+}
+
+impl LlamaRefiner {
+    /// Confidence given to model-suggested values
+    pub const CONFIDENCE: f32 = 0.6;
+
+    pub fn new(llama: LlamaInference) -> Self {
+        Self { llama: Mutex::new(llama) }
+    }
+}
+
+impl DimensionRefiner for LlamaRefiner {
+    fn refine(&self, text: &str, tags: &DimensionTags) -> Vec<(Dimension, DimensionValue)> {
+        let Ok(mut llama) = self.llama.lock() else {
+            return Vec::new();
+        };
+        if !llama.is_loaded() {
+            return Vec::new();
+        }
+        match llama.complete(&refinement_prompt(text, tags), 96) {
+            Ok(response) => parse_refinement(&response, Self::CONFIDENCE),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
 /// Chat message
 #[derive(Debug, Clone)]
 pub struct ChatMessage {
@@ -493,7 +406,7 @@ impl ModelInfo {
 }
 
 /// Download model from HuggingFace
-#[cfg(feature = "candle")]
+#[cfg(feature = "download")]
 pub fn download_model(info: &ModelInfo) -> Result<PathBuf> {
     let model_dir = ModelInfo::default_path();
     std::fs::create_dir_all(&model_dir)
@@ -525,9 +438,9 @@ pub fn download_model(info: &ModelInfo) -> Result<PathBuf> {
     Ok(model_path)
 }
 
-#[cfg(not(feature = "candle"))]
+#[cfg(not(feature = "download"))]
 pub fn download_model(_info: &ModelInfo) -> Result<PathBuf> {
-    Err(Error::DownloadFailed("Candle feature not enabled".into()))
+    Err(Error::DownloadFailed("download feature not enabled".into()))
 }
 
 #[cfg(test)]
@@ -536,8 +449,89 @@ mod tests {
 
     #[test]
     fn test_llama_not_loaded() {
-        let llama = LlamaInference::new();
+        let mut llama = LlamaInference::new();
         assert!(!llama.is_loaded());
+        assert!(matches!(llama.complete("hi", 4), Err(Error::ModelNotFound(_))));
+    }
+
+    fn fixture() -> LlamaInference {
+        let mut llama = LlamaInference::new();
+        llama.load(&crate::transformer::fixture::path()).unwrap();
+        llama.set_sampling(SamplingConfig::greedy());
+        llama
+    }
+
+    #[test]
+    fn test_load_fixture() {
+        let llama = fixture();
+        assert!(llama.is_loaded());
+        assert_eq!(llama.config().unwrap().n_layers, 2);
+
+        let tokenizer = llama.tokenizer().unwrap();
+        let ids = tokenizer.encode("hello gently world", true);
+        assert_eq!(ids.len(), 4);
+        assert_eq!(tokenizer.decode(&ids), "hello gently world");
+
+        let mut missing = LlamaInference::new();
+        assert!(matches!(missing.load(Path::new("/nonexistent.gguf")), Err(Error::ModelNotFound(_))));
+        assert!(!missing.is_loaded());
+    }
+
+    #[test]
+    fn test_complete_is_deterministic_and_bounded() {
+        let mut llama = fixture();
+        let first = llama.complete("hello world", 12).unwrap();
+        let again = fixture().complete("hello world", 12).unwrap();
+        assert_eq!(first, again);
+        assert!(!first.is_empty());
+
+        // The last sampled token is never fed back, so the cache stops one short
+        let prompt_len = llama.tokenizer().unwrap().encode("hello world", true).len();
+        assert!(llama.cached.len() < prompt_len + 12);
+        assert_eq!(llama.complete("hello world", 0).unwrap(), "");
+    }
+
+    #[test]
+    fn test_streaming_matches_complete() {
+        let mut chunks = Vec::new();
+        let streamed = fixture()
+            .complete_streaming("the brain feed", 10, |t| chunks.push(t.to_string()))
+            .unwrap();
+        assert_eq!(streamed, chunks.concat());
+        assert_eq!(streamed, fixture().complete("the brain feed", 10).unwrap());
+    }
+
+    #[test]
+    fn test_kv_cache_reused_across_calls() {
+        let mut warm = fixture();
+        warm.complete("hello world", 4).unwrap();
+        let reused = warm.complete("hello world, the rust code", 8).unwrap();
+        assert_eq!(reused, fixture().complete("hello world, the rust code", 8).unwrap());
+    }
+
+    #[test]
+    fn test_sampling_controls() {
+        let mut llama = fixture();
+        llama.set_temperature(5.0);
+        llama.set_top_k(3);
+        llama.set_top_p(0.9);
+        llama.set_repeat_penalty(1.3, 16);
+        llama.set_seed(9);
+        assert_eq!(llama.sampling().temperature, 2.0);
+        assert_eq!(llama.sampling().top_k, 3);
+
+        let a = llama.complete("gently", 8).unwrap();
+        assert_eq!(a, llama.complete("gently", 8).unwrap());
+        llama.set_seed(10);
+        // A different seed is free to diverge but must still generate
+        assert!(llama.complete("gently", 8).is_ok());
+    }
+
+    #[test]
+    fn test_long_prompt_fits_context() {
+        let mut llama = fixture();
+        let prompt = "hello world ".repeat(80);
+        assert!(llama.complete(&prompt, 8).is_ok());
     }
 
     #[test]
@@ -547,6 +541,12 @@ mod tests {
         assert!(info.model_path().to_string_lossy().contains(".gentlyos"));
     }
 
+    #[test]
+    fn test_refiner_skips_unloaded_model() {
+        let refiner = LlamaRefiner::new(LlamaInference::new());
+        assert!(refiner.refine("Alice went to Paris", &DimensionTags::new()).is_empty());
+    }
+
     #[test]
     fn test_chat_format() {
         let llama = LlamaInference::new();
@@ -0,0 +1,354 @@
+//! Built-in Local Embedder
+//!
+//! Deterministic 384-dim text embeddings with no model download, used by
+//! `Embedder` when the `fastembed` feature is off.
+//!
+//! ```text
+//! text ─► words ─► stems ──────────┐
+//!           │        └─► concepts ─┤  (car ≈ automobile ≈ vehicle)
+//!           ├─► stem bigrams ──────┼─► weight (idf, stopwords) ─► signed
+//!           └─► char trigrams ─────┘   hashing into 384 dims ─► L2 normalise
+//! ```
+//!
+//! Hashing is FNV-1a with fixed seeds, so vectors are stable across
+//! builds, platforms and Rust versions and can be stored long-term.
+//! `fit` learns IDF weights from a corpus; unfitted, every word counts the same.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Output width (bge-small compatible, 8 tesseract faces × 48)
+pub const DIMENSIONS: usize = 384;
+
+/// Name reported for the built-in backend
+pub const MODEL_NAME: &str = "gently-local-ngram-v1";
+
+/// Hash slots each feature is written to
+const PROBES: u64 = 2;
+
+const WORD_WEIGHT: f32 = 1.0;
+const STOPWORD_WEIGHT: f32 = 0.1;
+const CONCEPT_WEIGHT: f32 = 0.9;
+const BIGRAM_WEIGHT: f32 = 0.5;
+/// Shared by all trigrams of one word
+const TRIGRAM_WEIGHT: f32 = 0.6;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "the", "and", "or", "but", "if", "then", "of", "to", "in", "on", "at", "by", "for",
+    "with", "from", "into", "is", "are", "was", "were", "be", "been", "it", "its", "this", "that",
+    "these", "those", "i", "you", "he", "she", "we", "they", "my", "your", "our", "their", "me",
+    "do", "does", "did", "how", "what", "which", "who", "so", "as", "not", "no", "can", "should",
+    "would", "will", "has", "have", "had", "there", "here", "some", "any", "all", "up", "out",
+];
+
+/// Words that mean roughly the same thing share a concept feature
+const CONCEPTS: &[(&str, &[&str])] = &[
+    ("vehicle", &["car", "automobile", "vehicle", "truck", "engine", "drive", "driver"]),
+    ("dog", &["dog", "puppy", "canine", "hound"]),
+    ("cat", &["cat", "kitten", "feline"]),
+    ("pet", &["pet", "dog", "puppy", "cat", "kitten"]),
+    ("outdoors", &["garden", "yard", "park", "lawn", "outside"]),
+    ("play", &["play", "run", "chase", "fetch", "game"]),
+    ("code", &["code", "program", "software", "source", "function", "module", "crate"]),
+    ("build", &["build", "compile", "compiler", "cargo", "make"]),
+    ("failure", &["bug", "error", "crash", "fault", "failure", "fail", "panic", "fatal", "broken", "exception"]),
+    ("repair", &["fix", "repair", "patch", "resolve", "solve", "debug"]),
+    ("document", &["file", "document", "doc", "log", "record", "folder", "directory"]),
+    ("search", &["search", "find", "query", "lookup", "match", "keyword", "grep", "locate"]),
+    ("delete", &["delete", "remove", "erase", "purge", "clean", "wipe"]),
+    ("old", &["old", "stale", "outdated", "obsolete", "legacy"]),
+    ("create", &["create", "make", "new", "generate", "add"]),
+    ("download", &["download", "fetch", "pull", "retrieve", "install"]),
+    ("server", &["server", "service", "daemon", "host", "backend"]),
+    ("network", &["network", "internet", "connection", "wifi", "http", "socket"]),
+    ("security", &["security", "secure", "encrypt", "encryption", "password", "hash", "auth", "credential", "secret", "key"]),
+    ("ml", &["model", "neural", "network", "weight", "parameter", "inference", "llm", "embedding", "train"]),
+    ("weather", &["weather", "rain", "storm", "forecast", "sunny", "snow", "wind", "cloud"]),
+    ("food", &["food", "eat", "meal", "cook", "recipe", "bake", "cake", "egg", "dinner", "lunch"]),
+    ("music", &["music", "song", "melody", "tune", "sing", "listen", "orchestra", "symphony"]),
+    ("money", &["money", "cash", "payment", "price", "cost", "revenue", "budget", "profit"]),
+    ("speed", &["fast", "quick", "rapid", "speed", "slow", "latency", "performance"]),
+    ("happy", &["happy", "glad", "joy", "celebrate", "love", "enjoy"]),
+    ("sad", &["sad", "unhappy", "upset", "grief"]),
+    ("computer", &["computer", "laptop", "machine", "pc", "cpu", "memory", "disk"]),
+    ("time", &["today", "tomorrow", "yesterday", "morning", "evening", "night", "week"]),
+];
+
+/// Hashed n-gram embedder with optional learned IDF weights
+#[derive(Debug, Clone)]
+pub struct LocalEmbedder {
+    idf: HashMap<String, f32>,
+    /// IDF for stems never seen by `fit`
+    unseen_idf: f32,
+}
+
+impl LocalEmbedder {
+    pub fn new() -> Self {
+        Self { idf: HashMap::new(), unseen_idf: 1.0 }
+    }
+
+    pub fn dimensions(&self) -> usize {
+        DIMENSIONS
+    }
+
+    /// Learn IDF weights so common words in your corpus count for less
+    pub fn fit(&mut self, corpus: &[&str]) {
+        let mut df: HashMap<String, usize> = HashMap::new();
+        for doc in corpus {
+            let stems: HashSet<String> = words(doc).iter().map(|w| stem(w)).collect();
+            for s in stems {
+                *df.entry(s).or_default() += 1;
+            }
+        }
+        let n = corpus.len().max(1) as f32;
+        // Smoothed so a word in every document still keeps some weight
+        let idf = |count: usize| ((1.0 + n) / (1.0 + count as f32)).ln() + 1.0;
+        self.idf = df.into_iter().map(|(s, c)| (s, idf(c))).collect();
+        self.unseen_idf = idf(0);
+    }
+
+    /// Whether `fit` has been run
+    pub fn is_fitted(&self) -> bool {
+        !self.idf.is_empty()
+    }
+
+    /// Embed text as a unit-length 384-dim vector (zero vector for empty text)
+    pub fn embed(&self, text: &str) -> Vec<f32> {
+        // Ordered so the float sums below are bit-for-bit reproducible
+        let mut weights: BTreeMap<String, f32> = BTreeMap::new();
+        let mut add = |feature: String, w: f32| *weights.entry(feature).or_default() += w;
+
+        let words = words(text);
+        let stems: Vec<String> = words.iter().map(|w| stem(w)).collect();
+        let mut content: Vec<&str> = Vec::new();
+
+        for (word, stem) in words.iter().zip(&stems) {
+            if is_stopword(word) {
+                add(format!("w:{}", stem), STOPWORD_WEIGHT);
+                continue;
+            }
+            let idf = if self.is_fitted() {
+                self.idf.get(stem).copied().unwrap_or(self.unseen_idf)
+            } else {
+                1.0
+            };
+            add(format!("w:{}", stem), WORD_WEIGHT * idf);
+            for concept in concepts_of(word, stem) {
+                add(format!("c:{}", concept), CONCEPT_WEIGHT * idf);
+            }
+
+            let grams = trigrams(word);
+            let each = TRIGRAM_WEIGHT / grams.len().max(1) as f32;
+            for gram in grams {
+                add(format!("t:{}", gram), each);
+            }
+            content.push(stem);
+        }
+        for pair in content.windows(2) {
+            add(format!("b:{} {}", pair[0], pair[1]), BIGRAM_WEIGHT);
+        }
+
+        let mut embedding = vec![0.0f32; DIMENSIONS];
+        for (feature, w) in weights {
+            // Dampen repetition: ten mentions aren't ten times the meaning
+            let w = w.sqrt();
+            for probe in 0..PROBES {
+                let h = fnv1a(probe, feature.as_bytes());
+                let slot = (h % DIMENSIONS as u64) as usize;
+                let sign = if (h >> 63) == 0 { 1.0 } else { -1.0 };
+                embedding[slot] += sign * w;
+            }
+        }
+
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            embedding.iter_mut().for_each(|x| *x /= norm);
+        }
+        embedding
+    }
+}
+
+impl Default for LocalEmbedder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lowercase words, splitting on punctuation, `snake_case` and `camelCase`
+fn words(text: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    for raw in text.split(|c: char| !c.is_alphanumeric() && c != '\'') {
+        let raw = raw.trim_matches('\'');
+        let mut current = String::new();
+        let mut prev_lower = false;
+        for c in raw.chars() {
+            if c.is_uppercase() && prev_lower && !current.is_empty() {
+                out.push(std::mem::take(&mut current));
+            }
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+            current.extend(c.to_lowercase());
+        }
+        if !current.is_empty() {
+            out.push(current);
+        }
+    }
+    out
+}
+
+fn is_stopword(word: &str) -> bool {
+    STOPWORDS.contains(&word)
+}
+
+/// Light suffix stripping (plural, -ing, -ed, -ly)
+fn stem(word: &str) -> String {
+    let w = word.strip_suffix("'s").unwrap_or(word).replace('\'', "");
+    let n = w.chars().count();
+    let stemmed = if n > 4 && w.ends_with("ies") {
+        format!("{}y", &w[..w.len() - 3])
+    } else if n > 5 && w.ends_with("ing") {
+        undouble(&w[..w.len() - 3])
+    } else if n > 4 && w.ends_with("ed") {
+        undouble(&w[..w.len() - 2])
+    } else if n > 4 && (w.ends_with("ches") || w.ends_with("shes") || w.ends_with("sses") || w.ends_with("xes")) {
+        w[..w.len() - 2].to_string()
+    } else if n > 3 && w.ends_with('s') && !w.ends_with("ss") && !w.ends_with("us") {
+        w[..w.len() - 1].to_string()
+    } else if n > 4 && w.ends_with("ly") {
+        w[..w.len() - 2].to_string()
+    } else {
+        w
+    };
+    stemmed
+}
+
+/// "runn" → "run", but keep "fall", "pass", "buzz"
+fn undouble(s: &str) -> String {
+    let b = s.as_bytes();
+    if b.len() >= 3 && b[b.len() - 1] == b[b.len() - 2] && !matches!(b[b.len() - 1], b'l' | b's' | b'z') {
+        s[..s.len() - 1].to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+fn concept_index() -> &'static HashMap<String, Vec<&'static str>> {
+    static INDEX: OnceLock<HashMap<String, Vec<&'static str>>> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        let mut index: HashMap<String, Vec<&'static str>> = HashMap::new();
+        for &(concept, members) in CONCEPTS {
+            for member in members {
+                for key in [member.to_string(), stem(member)] {
+                    let entry = index.entry(key).or_default();
+                    if !entry.contains(&concept) {
+                        entry.push(concept);
+                    }
+                }
+            }
+        }
+        index
+    })
+}
+
+fn concepts_of(word: &str, stem: &str) -> Vec<&'static str> {
+    let index = concept_index();
+    let mut found: Vec<&'static str> = index.get(word).cloned().unwrap_or_default();
+    for c in index.get(stem).into_iter().flatten() {
+        if !found.contains(c) {
+            found.push(c);
+        }
+    }
+    found
+}
+
+/// Boundary-marked character trigrams: "dog" → "<do", "dog", "og>"
+fn trigrams(word: &str) -> Vec<String> {
+    let chars: Vec<char> = std::iter::once('<').chain(word.chars()).chain(std::iter::once('>')).collect();
+    chars.windows(3).map(|w| w.iter().collect()).collect()
+}
+
+fn fnv1a(seed: u64, bytes: &[u8]) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325 ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    for &b in bytes {
+        h ^= b as u64;
+        h = h.wrapping_mul(0x0100_0000_01b3);
+    }
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedder::cosine_similarity;
+
+    /// (anchor, related, unrelated)
+    const EVAL: &[(&str, &str, &str)] = &[
+        ("The car would not start this morning", "My automobile failed to start today", "She baked a chocolate cake for the party"),
+        ("How do I fix this compile error in Rust", "Resolving a Rust compiler error", "The weather forecast says rain tomorrow"),
+        ("The puppy played in the garden", "A young dog was running around the yard", "Quarterly revenue exceeded projections"),
+        ("Encrypt the password before storing it", "Passwords should be hashed securely", "The orchestra performed a symphony"),
+        ("Search the files for a keyword", "Find documents that match a query", "He went for a swim in the lake"),
+        ("The server crashed with a panic", "A fatal error brought down the service", "Kittens love to sleep in the sun"),
+        ("Download the model weights", "Fetch the neural network parameters", "The recipe needs two eggs"),
+        ("It is raining heavily outside", "A storm is bringing heavy rain", "The function returns a vector"),
+        ("Delete the old log files", "Remove stale logs from disk", "They celebrated a birthday"),
+        ("The song has a beautiful melody", "I love listening to this music", "Install the package with cargo"),
+        ("parseConfigFile returns an error", "parse_config_file fails on bad input", "The cat sat on the mat"),
+        ("The laptop is very slow", "My computer has poor performance", "A recipe for vegetable soup"),
+    ];
+
+    #[test]
+    fn test_related_sentences_score_higher() {
+        let embedder = LocalEmbedder::new();
+        let mut margins = Vec::new();
+        for (anchor, related, unrelated) in EVAL {
+            let a = embedder.embed(anchor);
+            let rel = cosine_similarity(&a, &embedder.embed(related));
+            let unrel = cosine_similarity(&a, &embedder.embed(unrelated));
+            assert!(rel > unrel, "{:?}: related {:.3} <= unrelated {:.3}", anchor, rel, unrel);
+            margins.push(rel - unrel);
+        }
+        let mean = margins.iter().sum::<f32>() / margins.len() as f32;
+        assert!(mean > 0.15, "mean margin {:.3}", mean);
+    }
+
+    #[test]
+    fn test_stable_unit_vectors() {
+        let embedder = LocalEmbedder::new();
+        let v = embedder.embed("Hello, gently world");
+        assert_eq!(v.len(), DIMENSIONS);
+        assert!((v.iter().map(|x| x * x).sum::<f32>() - 1.0).abs() < 1e-5);
+        assert_eq!(v, embedder.embed("Hello, gently world"));
+        // Pinned so a hashing change can't silently invalidate stored vectors
+        let checksum: f32 = v.iter().enumerate().map(|(i, x)| x * (i as f32 + 1.0)).sum();
+        assert!((checksum - PINNED_CHECKSUM).abs() < 1e-3, "checksum {}", checksum);
+
+        assert!(embedder.embed("").iter().all(|x| *x == 0.0));
+    }
+
+    const PINNED_CHECKSUM: f32 = 348.09448;
+
+    #[test]
+    fn test_fit_downweights_common_words() {
+        let corpus = ["gently feed item", "gently search", "gently brain rust", "gently daemon"];
+        let mut fitted = LocalEmbedder::new();
+        fitted.fit(&corpus);
+        assert!(fitted.is_fitted());
+
+        let plain = LocalEmbedder::new();
+        let a = "gently rust";
+        let b = "gently python";
+        assert!(
+            cosine_similarity(&fitted.embed(a), &fitted.embed(b)) < cosine_similarity(&plain.embed(a), &plain.embed(b))
+        );
+    }
+
+    #[test]
+    fn test_words_and_stems() {
+        assert_eq!(words("parseConfig_file HTTPServer"), vec!["parse", "config", "file", "httpserver"]);
+        assert_eq!(stem("running"), "run");
+        assert_eq!(stem("libraries"), "library");
+        assert_eq!(stem("errors"), "error");
+        assert_eq!(stem("class"), "class");
+        assert_eq!(concepts_of("automobile", "automobile"), vec!["vehicle"]);
+    }
+}
@@ -9,7 +9,14 @@
 //! Fusion: base + (α₁ × lora_a) + (α₂ × lora_b) + (α₃ × lora_c)
 //! Result: New Tensor blob with fused hash
 //! ```
+//!
+//! Base and fused weights are `TensorBlob`s. Each adapter contributes
+//! W' = W + m·(α/r)·B·A to every target module it covers, where `m` is the
+//! per-fusion multiplier passed to `fuse`. Targets are `[out, in]` (PyTorch
+//! `Linear` layout); a non-square `[in, out]` tensor is also accepted.
 
+use crate::tensor::{DType, Tensor, TensorBlob};
+use crate::{Error, Result};
 use gently_core::{Hash, Kind, Blob, Manifest, BlobStore, TAG_PARENT, TAG_WEIGHTS, TAG_SCHEMA};
 use serde::{Serialize, Deserialize};
 
@@ -37,6 +44,105 @@ pub struct LoraWeights {
     pub b: Vec<f32>,  // [rank, out_features]
     pub shape_a: (usize, usize),
     pub shape_b: (usize, usize),
+    /// Base tensor this pair belongs to; `None` = every tensor matching the
+    /// config's `target_modules` with a compatible shape
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+impl LoraWeights {
+    pub fn in_features(&self) -> usize {
+        self.shape_a.0
+    }
+
+    pub fn out_features(&self) -> usize {
+        self.shape_b.1
+    }
+
+    pub fn rank(&self) -> usize {
+        self.shape_a.1
+    }
+
+    /// Check the matrices agree with their declared shapes
+    pub fn validate(&self) -> Result<()> {
+        if self.shape_a.1 != self.shape_b.0 {
+            return Err(Error::Tensor(format!(
+                "LoRA rank mismatch: A is {:?}, B is {:?}",
+                self.shape_a, self.shape_b
+            )));
+        }
+        if self.a.len() != self.shape_a.0 * self.shape_a.1 || self.b.len() != self.shape_b.0 * self.shape_b.1 {
+            return Err(Error::Tensor("LoRA matrices don't match their shapes".into()));
+        }
+        Ok(())
+    }
+
+    /// Whether ΔW can be laid out as `shape`
+    pub fn fits(&self, shape: &[usize]) -> bool {
+        let (i, o) = (self.in_features(), self.out_features());
+        shape == [o, i] || shape == [i, o]
+    }
+
+    /// ΔW = B·A, laid out as `shape` (`[out, in]`, or `[in, out]` if non-square)
+    pub fn delta(&self, shape: &[usize]) -> Result<Vec<f32>> {
+        self.validate()?;
+        let (n_in, r, n_out) = (self.in_features(), self.rank(), self.out_features());
+        let transposed = if shape == [n_out, n_in] {
+            false
+        } else if shape == [n_in, n_out] {
+            true
+        } else {
+            return Err(Error::Tensor(format!(
+                "LoRA {}→{} doesn't fit tensor {:?}",
+                n_in, n_out, shape
+            )));
+        };
+
+        let mut delta = vec![0.0f32; n_in * n_out];
+        for i in 0..n_in {
+            let a_row = &self.a[i * r..(i + 1) * r];
+            for (k, &a) in a_row.iter().enumerate() {
+                if a == 0.0 {
+                    continue;
+                }
+                let b_row = &self.b[k * n_out..(k + 1) * n_out];
+                for (o, &b) in b_row.iter().enumerate() {
+                    let idx = if transposed { i * n_out + o } else { o * n_in + i };
+                    delta[idx] += a * b;
+                }
+            }
+        }
+        Ok(delta)
+    }
+
+    /// From PEFT-style tensors: `lora_A` is `[r, in]`, `lora_B` is `[out, r]`
+    pub fn from_peft(lora_a: &Tensor, lora_b: &Tensor, target: Option<String>) -> Result<Self> {
+        let (&[r, n_in], &[n_out, r_b]) = (lora_a.shape(), lora_b.shape()) else {
+            return Err(Error::Tensor("LoRA A and B must be 2-D".into()));
+        };
+        if r != r_b {
+            return Err(Error::Tensor(format!("LoRA rank mismatch: A has {}, B has {}", r, r_b)));
+        }
+        let (pa, pb) = (lora_a.data(), lora_b.data());
+        let a = (0..n_in * r).map(|idx| pa[(idx % r) * n_in + idx / r]).collect();
+        let b = (0..r * n_out).map(|idx| pb[(idx % n_out) * r + idx / n_out]).collect();
+        Ok(Self { a, b, shape_a: (n_in, r), shape_b: (r, n_out), target })
+    }
+
+    /// Back to PEFT layout (`lora_A` `[r, in]`, `lora_B` `[out, r]`)
+    pub fn to_peft(&self) -> Result<(Tensor, Tensor)> {
+        self.validate()?;
+        let (n_in, r, n_out) = (self.in_features(), self.rank(), self.out_features());
+        let a = (0..r * n_in).map(|idx| self.a[(idx % n_in) * r + idx / n_in]).collect();
+        let b = (0..n_out * r).map(|idx| self.b[(idx % r) * n_out + idx / r]).collect();
+        Ok((Tensor::f32(vec![r, n_in], a)?, Tensor::f32(vec![n_out, r], b)?))
+    }
+}
+
+/// Does `name` (e.g. `layers.0.self_attn.q_proj.weight`) belong to `module`?
+/// `"all"` matches every tensor.
+pub fn matches_module(name: &str, module: &str) -> bool {
+    module == "all" || format!(".{}.", name).contains(&format!(".{}.", module.trim_matches('.')))
 }
 
 /// Chain of LoRA adapters
@@ -63,20 +169,43 @@ impl LoraChain {
         hash
     }
 
+    /// Set base model weights from named tensors
+    pub fn set_base_tensors(&mut self, tensors: &TensorBlob) -> Hash {
+        self.set_base(tensors.encode())
+    }
+
+    /// Base weights as tensors
+    pub fn base_tensors(&self) -> Result<TensorBlob> {
+        let hash = self.base.ok_or_else(|| Error::Tensor("no base weights set".into()))?;
+        self.tensors(&hash)
+    }
+
+    /// Decode any tensor blob in the store (base or fused)
+    pub fn tensors(&self, hash: &Hash) -> Result<TensorBlob> {
+        let blob = self.store.get(hash).ok_or_else(|| Error::Tensor("tensor blob not found".into()))?;
+        TensorBlob::decode(&blob.data)
+    }
+
     /// Add LoRA adapter to chain
     pub fn add_adapter(&mut self, config: LoraConfig, weights: LoraWeights) -> Hash {
+        self.add_adapter_modules(config, vec![weights])
+    }
+
+    /// Add an adapter with one A/B pair per target module
+    pub fn add_adapter_modules(&mut self, config: LoraConfig, modules: Vec<LoraWeights>) -> Hash {
         // Store config as schema
         let config_blob = Blob::new(Kind::Schema, serde_json::to_vec(&config).unwrap());
         let config_hash = self.store.put(config_blob);
 
-        // Store weights as delta
-        let weights_blob = Blob::new(Kind::Delta, serde_json::to_vec(&weights).unwrap());
-        let weights_hash = self.store.put(weights_blob);
-
         // Build adapter manifest
         let mut manifest = Manifest::new();
         manifest.add(TAG_SCHEMA, config_hash);
-        manifest.add(TAG_WEIGHTS, weights_hash);
+
+        // Store weights as deltas
+        for weights in &modules {
+            let weights_blob = Blob::new(Kind::Delta, serde_json::to_vec(weights).unwrap());
+            manifest.add(TAG_WEIGHTS, self.store.put(weights_blob));
+        }
 
         // Link to parent (previous adapter or base)
         let parent = self.adapters.last().copied().or(self.base);
@@ -107,6 +236,19 @@ impl LoraChain {
         serde_json::from_slice(&weights_blob.data).ok()
     }
 
+    /// Every A/B pair of an adapter
+    pub fn get_all_weights(&self, hash: &Hash) -> Vec<LoraWeights> {
+        let Some(manifest) = self.store.get(hash).and_then(Manifest::from_blob) else {
+            return Vec::new();
+        };
+        manifest
+            .get_all(TAG_WEIGHTS)
+            .iter()
+            .filter_map(|h| self.store.get(h))
+            .filter_map(|b| serde_json::from_slice(&b.data).ok())
+            .collect()
+    }
+
     /// Walk chain from tip to base
     pub fn chain(&self) -> Vec<Hash> {
         // Return adapters in reverse order (tip to base)
@@ -116,58 +258,79 @@ impl LoraChain {
     /// Fuse chain into single weight tensor
     /// Returns hash of fused weights
     pub fn fuse(&mut self, alphas: &[f32]) -> Option<Hash> {
-        let base_hash = self.base?;
-        let base_blob = self.store.get(&base_hash)?;
-
-        // Start with base weights (placeholder - real impl would parse tensor)
-        let mut fused = base_blob.data.clone();
-
-        // Apply each adapter with its alpha
-        let chain = self.chain();
-        for (i, adapter_hash) in chain.iter().rev().enumerate() {
-            let alpha = alphas.get(i).copied().unwrap_or(1.0);
-
-            if let Some(weights) = self.get_weights(adapter_hash) {
-                // W' = W + (α × B × A)
-                // Placeholder: actual matrix multiply would happen here
-                self.apply_lora(&mut fused, &weights, alpha);
-            }
-        }
-
-        // Store fused result as new Tensor blob
-        let fused_blob = Blob::new(Kind::Tensor, fused);
-        Some(self.store.put(fused_blob))
+        // Chain order base → tip, alphas[i] scales the i-th adapter
+        let adapters: Vec<(Hash, f32)> = self
+            .adapters
+            .iter()
+            .enumerate()
+            .map(|(i, h)| (*h, alphas.get(i).copied().unwrap_or(1.0)))
+            .collect();
+        self.store_fused(&adapters).ok()
     }
 
     /// Fuse specific adapters by hash
     pub fn fuse_selected(&mut self, adapter_hashes: &[Hash], alphas: &[f32]) -> Option<Hash> {
-        let base_hash = self.base?;
-        let base_blob = self.store.get(&base_hash)?;
+        let adapters: Vec<(Hash, f32)> = adapter_hashes
+            .iter()
+            .enumerate()
+            .map(|(i, h)| (*h, alphas.get(i).copied().unwrap_or(1.0)))
+            .collect();
+        self.store_fused(&adapters).ok()
+    }
 
-        let mut fused = base_blob.data.clone();
+    fn store_fused(&mut self, adapters: &[(Hash, f32)]) -> Result<Hash> {
+        let fused = self.fuse_tensors(adapters)?;
+        Ok(self.store.put(Blob::new(Kind::Tensor, fused.encode())))
+    }
 
-        for (i, hash) in adapter_hashes.iter().enumerate() {
-            let alpha = alphas.get(i).copied().unwrap_or(1.0);
-            if let Some(weights) = self.get_weights(hash) {
-                self.apply_lora(&mut fused, &weights, alpha);
+    /// Base weights with each `(adapter, multiplier)` applied, in order
+    ///
+    /// Sums are kept in f32 and rounded to each tensor's dtype once at the end.
+    pub fn fuse_tensors(&self, adapters: &[(Hash, f32)]) -> Result<TensorBlob> {
+        let mut fused = self.base_tensors()?;
+        let dtypes: Vec<(String, DType)> =
+            fused.iter().map(|(n, t)| (n.to_string(), t.dtype())).collect();
+        for (name, _) in &dtypes {
+            if let Some(t) = fused.get_mut(name) {
+                *t = t.cast(DType::F32);
             }
         }
 
-        let fused_blob = Blob::new(Kind::Tensor, fused);
-        Some(self.store.put(fused_blob))
-    }
+        for (hash, multiplier) in adapters {
+            if *multiplier == 0.0 {
+                continue;
+            }
+            let config = self
+                .get_config(hash)
+                .ok_or_else(|| Error::Tensor("adapter config not found".into()))?;
+            let scale = multiplier * config.alpha / config.rank.max(1) as f32;
+
+            for weights in self.get_all_weights(hash) {
+                let targets: Vec<String> = match &weights.target {
+                    Some(name) if fused.get(name).is_some() => vec![name.clone()],
+                    Some(name) => return Err(Error::Tensor(format!("no base tensor named {}", name))),
+                    None => fused
+                        .iter()
+                        .filter(|(name, t)| {
+                            config.target_modules.iter().any(|m| matches_module(name, m)) && weights.fits(t.shape())
+                        })
+                        .map(|(name, _)| name.to_string())
+                        .collect(),
+                };
+                for name in targets {
+                    let Some(tensor) = fused.get_mut(&name) else { continue };
+                    let delta = weights.delta(tensor.shape())?;
+                    apply_delta(tensor, &delta, scale);
+                }
+            }
+        }
 
-    /// Apply LoRA delta to weights
-    fn apply_lora(&self, weights: &mut [u8], lora: &LoraWeights, alpha: f32) {
-        // Placeholder for actual LoRA application
-        // Real implementation:
-        // 1. Parse weights as f32 tensor
-        // 2. Compute B × A matrix product
-        // 3. Scale by (alpha / rank)
-        // 4. Add to weights
-        //
-        // For now, just mark that fusion happened
-        let _ = (weights, lora, alpha);
+        for (name, dtype) in dtypes {
+            if let Some(t) = fused.get_mut(&name) {
+                *t = t.cast(dtype);
+            }
+        }
+        Ok(fused)
     }
 
     /// Create merge manifest (recipe for fusion)
@@ -225,6 +388,46 @@ impl Default for LoraChain {
     fn default() -> Self { Self::new() }
 }
 
+/// W += scale · ΔW
+fn apply_delta(tensor: &mut Tensor, delta: &[f32], scale: f32) {
+    for (w, d) in tensor.data_mut().iter_mut().zip(delta) {
+        *w += scale * d;
+    }
+}
+
+/// Pull every `lora_A`/`lora_B` pair out of a PEFT `.safetensors` adapter
+///
+/// Targets are the base tensor names (`base_model.model.` stripped,
+/// `.weight` appended), ready for `add_adapter_modules`.
+pub fn import_peft_safetensors(bytes: &[u8]) -> Result<Vec<LoraWeights>> {
+    let tensors = TensorBlob::from_safetensors(bytes)?;
+    let mut modules = Vec::new();
+    for (name, lora_a) in tensors.iter() {
+        let Some(prefix) = name.strip_suffix(".lora_A.weight") else { continue };
+        let lora_b = tensors
+            .get(&format!("{}.lora_B.weight", prefix))
+            .ok_or_else(|| Error::Tensor(format!("{} has no matching lora_B", name)))?;
+        let module = prefix.strip_prefix("base_model.model.").unwrap_or(prefix);
+        modules.push(LoraWeights::from_peft(lora_a, lora_b, Some(format!("{}.weight", module)))?);
+    }
+    Ok(modules)
+}
+
+/// Write adapter modules as a PEFT-style `.safetensors` file
+pub fn export_peft_safetensors(modules: &[LoraWeights]) -> Result<Vec<u8>> {
+    let mut tensors = TensorBlob::new();
+    for (i, weights) in modules.iter().enumerate() {
+        let module = match &weights.target {
+            Some(t) => t.strip_suffix(".weight").unwrap_or(t).to_string(),
+            None => format!("module_{}", i),
+        };
+        let (a, b) = weights.to_peft()?;
+        tensors.insert(&format!("base_model.model.{}.lora_A.weight", module), a);
+        tensors.insert(&format!("base_model.model.{}.lora_B.weight", module), b);
+    }
+    Ok(tensors.to_safetensors())
+}
+
 /// Quick helper: create adapter blob
 pub fn lora_adapter(rank: u32, alpha: f32, a: Vec<f32>, b: Vec<f32>) -> (LoraConfig, LoraWeights) {
     let shape_a = (a.len() / rank as usize, rank as usize);
@@ -238,7 +441,7 @@ pub fn lora_adapter(rank: u32, alpha: f32, a: Vec<f32>, b: Vec<f32>) -> (LoraCon
         dtype: "f32".into(),
     };
 
-    let weights = LoraWeights { a, b, shape_a, shape_b };
+    let weights = LoraWeights { a, b, shape_a, shape_b, target: None };
 
     (config, weights)
 }
@@ -266,16 +469,150 @@ mod tests {
         assert!(chain.get_config(&h2).is_some());
     }
 
+    /// Deterministic values in [-1, 1)
+    fn values(n: usize, seed: u32) -> Vec<f32> {
+        let mut x = seed.wrapping_mul(2654435761).max(1);
+        (0..n)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                (x % 2000) as f32 / 1000.0 - 1.0
+            })
+            .collect()
+    }
+
+    /// Textbook W + s·(Bᵀ Aᵀ) in f64, `[out, in]` layout
+    fn reference(base: &[f32], w: &LoraWeights, scale: f32) -> Vec<f32> {
+        let (n_in, r, n_out) = (w.in_features(), w.rank(), w.out_features());
+        let mut out = base.to_vec();
+        for o in 0..n_out {
+            for i in 0..n_in {
+                let mut sum = 0.0f64;
+                for k in 0..r {
+                    sum += w.a[i * r + k] as f64 * w.b[k * n_out + o] as f64;
+                }
+                out[o * n_in + i] = (base[o * n_in + i] as f64 + scale as f64 * sum) as f32;
+            }
+        }
+        out
+    }
+
+    fn base_blob(dtype: DType) -> TensorBlob {
+        let mut base = TensorBlob::new();
+        base.insert("layers.0.self_attn.q_proj.weight", Tensor::new(vec![6, 8], dtype, values(48, 1)).unwrap());
+        base.insert("layers.0.self_attn.k_proj.weight", Tensor::new(vec![6, 8], dtype, values(48, 2)).unwrap());
+        base.insert("layers.0.self_attn.v_proj.weight", Tensor::new(vec![6, 8], dtype, values(48, 3)).unwrap());
+        base
+    }
+
     #[test]
     fn test_fuse() {
         let mut chain = LoraChain::new();
-        chain.set_base(vec![0u8; 256]);
+        chain.set_base_tensors(&base_blob(DType::F32));
+
+        // in 8, rank 2, out 6
+        let (cfg, w) = lora_adapter(2, 4.0, values(16, 4), values(12, 5));
+        chain.add_adapter(cfg, w.clone());
 
-        let (cfg, w) = lora_adapter(4, 1.0, vec![0.1; 16], vec![0.1; 16]);
-        chain.add_adapter(cfg, w);
+        let hash = chain.fuse(&[0.5]).unwrap();
+        let fused = chain.tensors(&hash).unwrap();
+        let base = base_blob(DType::F32);
 
-        let fused = chain.fuse(&[1.0]);
-        assert!(fused.is_some());
+        // scale = 0.5 × 4 / 2
+        for name in ["layers.0.self_attn.q_proj.weight", "layers.0.self_attn.v_proj.weight"] {
+            let expected = reference(base.get(name).unwrap().data(), &w, 1.0);
+            for (x, y) in fused.get(name).unwrap().data().iter().zip(&expected) {
+                assert!((x - y).abs() < 1e-5, "{} {} vs {}", name, x, y);
+            }
+        }
+        // k_proj isn't a target module
+        let k = "layers.0.self_attn.k_proj.weight";
+        assert_eq!(fused.get(k).unwrap().data(), base.get(k).unwrap().data());
+
+        // Zero multiplier leaves the base untouched
+        let unchanged = chain.fuse_tensors(&[(chain.chain()[0], 0.0)]).unwrap();
+        assert_eq!(unchanged, base);
+    }
+
+    #[test]
+    fn test_fuse_dtypes() {
+        for (dtype, tol) in [(DType::F32, 1e-5), (DType::F16, 2e-3), (DType::BF16, 1.6e-2)] {
+            let mut chain = LoraChain::new();
+            chain.set_base_tensors(&base_blob(dtype));
+
+            let (cfg1, w1) = lora_adapter(2, 2.0, values(16, 6), values(12, 7));
+            let h1 = chain.add_adapter(cfg1, w1.clone());
+            let (cfg2, w2) = lora_adapter(4, 2.0, values(32, 8), values(24, 9));
+            let h2 = chain.add_adapter(cfg2, w2.clone());
+
+            let fused = chain.fuse_tensors(&[(h1, 1.0), (h2, -0.5)]).unwrap();
+            let name = "layers.0.self_attn.q_proj.weight";
+            let t = fused.get(name).unwrap();
+            assert_eq!(t.dtype(), dtype);
+
+            let base = base_blob(dtype);
+            let step = reference(base.get(name).unwrap().data(), &w1, 1.0);
+            let expected = reference(&step, &w2, -0.25);
+            for (x, y) in t.data().iter().zip(&expected) {
+                assert!((x - y).abs() <= tol * y.abs().max(1.0), "{:?}: {} vs {}", dtype, x, y);
+                // Stored values are exactly representable in the dtype
+                assert_eq!(dtype.round(*x), *x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_peft_roundtrip() {
+        let (_, mut w) = lora_adapter(2, 1.0, values(16, 10), values(12, 11));
+        w.target = Some("layers.0.self_attn.q_proj.weight".into());
+
+        let bytes = export_peft_safetensors(std::slice::from_ref(&w)).unwrap();
+        let raw = TensorBlob::from_safetensors(&bytes).unwrap();
+        let lora_a = raw.get("base_model.model.layers.0.self_attn.q_proj.lora_A.weight").unwrap();
+        assert_eq!(lora_a.shape(), &[2, 8]);
+        // lora_A[k][i] == a[i][k]
+        assert_eq!(lora_a.data()[8 + 3], w.a[3 * 2 + 1]);
+
+        let imported = import_peft_safetensors(&bytes).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].a, w.a);
+        assert_eq!(imported[0].b, w.b);
+        assert_eq!(imported[0].target, w.target);
+
+        // Explicit targets apply even to modules outside the config's list
+        let mut chain = LoraChain::new();
+        chain.set_base_tensors(&base_blob(DType::F32));
+        let (mut cfg, _) = lora_adapter(2, 2.0, vec![], vec![]);
+        cfg.target_modules.clear();
+        let mut k = imported[0].clone();
+        k.target = Some("layers.0.self_attn.k_proj.weight".into());
+        let h = chain.add_adapter_modules(cfg, vec![imported[0].clone(), k]);
+        assert_eq!(chain.get_all_weights(&h).len(), 2);
+
+        let fused = chain.fuse_tensors(&[(h, 1.0)]).unwrap();
+        let base = base_blob(DType::F32);
+        for name in ["layers.0.self_attn.q_proj.weight", "layers.0.self_attn.k_proj.weight"] {
+            let expected = reference(base.get(name).unwrap().data(), &w, 1.0);
+            for (x, y) in fused.get(name).unwrap().data().iter().zip(&expected) {
+                assert!((x - y).abs() < 1e-5);
+            }
+        }
+        let v = "layers.0.self_attn.v_proj.weight";
+        assert_eq!(fused.get(v).unwrap().data(), base.get(v).unwrap().data());
+    }
+
+    #[test]
+    fn test_delta_layouts() {
+        let (_, w) = lora_adapter(1, 1.0, vec![1.0, 2.0, 3.0], vec![10.0, 20.0]);
+        // [out=2, in=3]
+        assert_eq!(w.delta(&[2, 3]).unwrap(), vec![10.0, 20.0, 30.0, 20.0, 40.0, 60.0]);
+        // [in=3, out=2]
+        assert_eq!(w.delta(&[3, 2]).unwrap(), vec![10.0, 20.0, 20.0, 40.0, 30.0, 60.0]);
+        assert!(w.delta(&[4, 4]).is_err());
+        assert!(matches_module("model.layers.3.mlp.up_proj.weight", "up_proj"));
+        assert!(matches_module("model.layers.3.mlp.up_proj.weight", "mlp.up_proj"));
+        assert!(!matches_module("model.layers.3.mlp.up_proj2.weight", "up_proj"));
     }
 
     #[test]
@@ -5,9 +5,14 @@
 //! skills all accessible through this unified interface.
 
 use crate::{Result, Error};
+use crate::permissions::{
+    AuditLog, AuditRecord, ConfirmRequest, Decision, Permission, ToolConfirmer, ToolPolicy,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
 
 /// Tool definition for MCP
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,9 +22,12 @@ pub struct Tool {
     pub category: ToolCategory,
     pub input_schema: ToolSchema,
     pub requires_confirmation: bool,
+    /// Side effects the tool may have, used by [`ToolPolicy`]
+    #[serde(default)]
+    pub effects: Vec<EffectKind>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ToolCategory {
     Crypto,      // Cipher, hash, encryption
     Network,     // Packet capture, scanning, MITM
@@ -45,6 +53,62 @@ pub struct PropertySchema {
     pub enum_values: Option<Vec<String>>,
 }
 
+impl ToolSchema {
+    /// Check tool arguments against the schema
+    pub fn validate(&self, input: &serde_json::Value) -> std::result::Result<(), String> {
+        let empty = serde_json::Map::new();
+        let args = match input {
+            serde_json::Value::Object(map) => map,
+            serde_json::Value::Null => &empty,
+            other => return Err(format!("arguments must be an object, got {}", json_type(other))),
+        };
+
+        for name in &self.required {
+            if args.get(name).is_none_or(|v| v.is_null()) {
+                return Err(format!("missing required argument '{}'", name));
+            }
+        }
+
+        for (name, value) in args {
+            let prop = self.properties.get(name)
+                .ok_or_else(|| format!("unknown argument '{}'", name))?;
+            if value.is_null() {
+                continue;
+            }
+            let ok = match prop.prop_type.as_str() {
+                "string" => value.is_string(),
+                "integer" => value.is_i64() || value.is_u64(),
+                "number" => value.is_number(),
+                "boolean" => value.is_boolean(),
+                "array" => value.is_array(),
+                "object" => value.is_object(),
+                _ => true,
+            };
+            if !ok {
+                return Err(format!("argument '{}' must be {}, got {}", name, prop.prop_type, json_type(value)));
+            }
+            if let (Some(allowed), Some(s)) = (&prop.enum_values, value.as_str()) {
+                if !allowed.iter().any(|a| a == s) {
+                    return Err(format!("argument '{}' must be one of {}, got '{}'", name, allowed.join("|"), s));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn json_type(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(n) if n.is_f64() => "number",
+        serde_json::Value::Number(_) => "integer",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
 /// Result of tool execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolResult {
@@ -65,6 +129,42 @@ pub enum SideEffect {
     StateChanged { key: String, value: String },
 }
 
+/// Kind of a [`SideEffect`], without its payload
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum EffectKind {
+    Knowledge,
+    Daemon,
+    Ipfs,
+    Branch,
+    Vector,
+    State,
+}
+
+impl EffectKind {
+    pub const ALL: [EffectKind; 6] = [
+        EffectKind::Knowledge,
+        EffectKind::Daemon,
+        EffectKind::Ipfs,
+        EffectKind::Branch,
+        EffectKind::Vector,
+        EffectKind::State,
+    ];
+}
+
+impl SideEffect {
+    pub fn kind(&self) -> EffectKind {
+        match self {
+            SideEffect::KnowledgeAdded { .. } => EffectKind::Knowledge,
+            SideEffect::DaemonStarted { .. } => EffectKind::Daemon,
+            SideEffect::IpfsSynced { .. } => EffectKind::Ipfs,
+            SideEffect::BranchCreated { .. } => EffectKind::Branch,
+            SideEffect::VectorComputed { .. } => EffectKind::Vector,
+            SideEffect::StateChanged { .. } => EffectKind::State,
+        }
+    }
+}
+
 /// Tool executor trait
 pub trait ToolExecutor: Send + Sync {
     fn execute(&self, input: &serde_json::Value) -> Result<ToolResult>;
@@ -75,6 +175,9 @@ pub trait ToolExecutor: Send + Sync {
 pub struct McpToolRegistry {
     tools: HashMap<String, Tool>,
     executors: HashMap<String, Arc<dyn ToolExecutor>>,
+    policy: RwLock<ToolPolicy>,
+    confirmer: RwLock<Option<Arc<dyn ToolConfirmer>>>,
+    audit: Mutex<AuditLog>,
 }
 
 impl McpToolRegistry {
@@ -82,6 +185,9 @@ impl McpToolRegistry {
         let mut registry = Self {
             tools: HashMap::new(),
             executors: HashMap::new(),
+            policy: RwLock::new(ToolPolicy::default()),
+            confirmer: RwLock::new(None),
+            audit: Mutex::new(AuditLog::default()),
         };
         registry.register_builtin_tools();
         registry
@@ -104,6 +210,7 @@ impl McpToolRegistry {
                 required: vec!["input".into()],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -131,6 +238,7 @@ impl McpToolRegistry {
                 required: vec!["input".into(), "cipher".into()],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -153,6 +261,7 @@ impl McpToolRegistry {
                 required: vec!["input".into(), "algorithm".into()],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -175,6 +284,7 @@ impl McpToolRegistry {
                 required: vec!["hash".into()],
             },
             requires_confirmation: true,
+            effects: vec![],
         });
 
         // === NETWORK TOOLS ===
@@ -203,6 +313,7 @@ impl McpToolRegistry {
                 required: vec![],
             },
             requires_confirmation: true,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -225,6 +336,7 @@ impl McpToolRegistry {
                 required: vec!["target".into()],
             },
             requires_confirmation: true,
+            effects: vec![],
         });
 
         // === KNOWLEDGE TOOLS ===
@@ -253,6 +365,7 @@ impl McpToolRegistry {
                 required: vec!["concept".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::Knowledge],
         });
 
         self.register_tool(Tool {
@@ -275,6 +388,7 @@ impl McpToolRegistry {
                 required: vec!["query".into()],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -297,6 +411,7 @@ impl McpToolRegistry {
                 required: vec!["premise".into()],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -319,6 +434,149 @@ impl McpToolRegistry {
                 required: vec!["concept".into()],
             },
             requires_confirmation: false,
+            effects: vec![],
+        });
+
+        // === ALEXANDRIA TOOLS ===
+        // Run by the orchestrator; registered for schemas, policy and effects
+        self.register_tool(Tool {
+            name: "alexandria_navigate".into(),
+            description: "Show a concept's forward and reverse connections in the Alexandria graph".into(),
+            category: ToolCategory::Knowledge,
+            input_schema: ToolSchema {
+                properties: hashmap! {
+                    "concept".into() => PropertySchema {
+                        prop_type: "string".into(),
+                        description: "Concept to look up".into(),
+                        enum_values: None,
+                    }
+                },
+                required: vec!["concept".into()],
+            },
+            requires_confirmation: false,
+            effects: vec![],
+        });
+
+        self.register_tool(Tool {
+            name: "alexandria_tesseract".into(),
+            description: "Read one face (or all) of a concept's tesseract position".into(),
+            category: ToolCategory::Knowledge,
+            input_schema: ToolSchema {
+                properties: hashmap! {
+                    "concept".into() => PropertySchema {
+                        prop_type: "string".into(),
+                        description: "Concept to look up".into(),
+                        enum_values: None,
+                    },
+                    "face".into() => PropertySchema {
+                        prop_type: "string".into(),
+                        description: "Face to read (default: all)".into(),
+                        enum_values: Some(vec!["actual".into(), "eliminated".into(), "potential".into(), "purpose".into(), "method".into(), "context".into(), "observer".into(), "temporal".into(), "all".into()]),
+                    }
+                },
+                required: vec!["concept".into()],
+            },
+            requires_confirmation: false,
+            effects: vec![],
+        });
+
+        self.register_tool(Tool {
+            name: "alexandria_drift".into(),
+            description: "Analyse how a concept's meaning drifted over time".into(),
+            category: ToolCategory::Knowledge,
+            input_schema: ToolSchema {
+                properties: hashmap! {
+                    "concept".into() => PropertySchema {
+                        prop_type: "string".into(),
+                        description: "Concept to look up".into(),
+                        enum_values: None,
+                    }
+                },
+                required: vec!["concept".into()],
+            },
+            requires_confirmation: false,
+            effects: vec![],
+        });
+
+        self.register_tool(Tool {
+            name: "alexandria_wormhole".into(),
+            description: "Find a wormhole between two concepts".into(),
+            category: ToolCategory::Knowledge,
+            input_schema: ToolSchema {
+                properties: hashmap! {
+                    "from".into() => PropertySchema {
+                        prop_type: "string".into(),
+                        description: "Start concept".into(),
+                        enum_values: None,
+                    },
+                    "to".into() => PropertySchema {
+                        prop_type: "string".into(),
+                        description: "End concept".into(),
+                        enum_values: None,
+                    }
+                },
+                required: vec!["from".into(), "to".into()],
+            },
+            requires_confirmation: false,
+            effects: vec![],
+        });
+
+        self.register_tool(Tool {
+            name: "alexandria_record".into(),
+            description: "Record a concept's position in the tesseract".into(),
+            category: ToolCategory::Knowledge,
+            input_schema: ToolSchema {
+                properties: hashmap! {
+                    "concept".into() => PropertySchema {
+                        prop_type: "string".into(),
+                        description: "Concept to place".into(),
+                        enum_values: None,
+                    },
+                    "actual".into() => PropertySchema {
+                        prop_type: "array".into(),
+                        description: "What it is".into(),
+                        enum_values: None,
+                    },
+                    "eliminated".into() => PropertySchema {
+                        prop_type: "array".into(),
+                        description: "What it is not".into(),
+                        enum_values: None,
+                    },
+                    "potential".into() => PropertySchema {
+                        prop_type: "array".into(),
+                        description: "What it could be".into(),
+                        enum_values: None,
+                    },
+                    "purpose".into() => PropertySchema {
+                        prop_type: "array".into(),
+                        description: "Why it exists".into(),
+                        enum_values: None,
+                    },
+                    "method".into() => PropertySchema {
+                        prop_type: "array".into(),
+                        description: "How it works".into(),
+                        enum_values: None,
+                    },
+                    "context".into() => PropertySchema {
+                        prop_type: "array".into(),
+                        description: "Where it lives".into(),
+                        enum_values: None,
+                    },
+                    "observer".into() => PropertySchema {
+                        prop_type: "array".into(),
+                        description: "Who cares about it".into(),
+                        enum_values: None,
+                    },
+                    "era".into() => PropertySchema {
+                        prop_type: "array".into(),
+                        description: "Era tags for when it matters".into(),
+                        enum_values: None,
+                    }
+                },
+                required: vec!["concept".into()],
+            },
+            requires_confirmation: false,
+            effects: vec![EffectKind::Knowledge],
         });
 
         // === DAEMON TOOLS ===
@@ -344,6 +602,7 @@ impl McpToolRegistry {
                 required: vec!["daemon_type".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::Daemon],
         });
 
         self.register_tool(Tool {
@@ -361,6 +620,7 @@ impl McpToolRegistry {
                 required: vec!["name".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::Daemon],
         });
 
         self.register_tool(Tool {
@@ -372,6 +632,7 @@ impl McpToolRegistry {
                 required: vec![],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -389,6 +650,7 @@ impl McpToolRegistry {
                 required: vec!["name".into()],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         // === STORAGE TOOLS ===
@@ -412,6 +674,7 @@ impl McpToolRegistry {
                 required: vec!["content".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::Ipfs],
         });
 
         self.register_tool(Tool {
@@ -429,6 +692,7 @@ impl McpToolRegistry {
                 required: vec!["cid".into()],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -446,6 +710,7 @@ impl McpToolRegistry {
                 required: vec!["service".into()],
             },
             requires_confirmation: true,
+            effects: vec![],
         });
 
         // === CODE TOOLS ===
@@ -464,6 +729,7 @@ impl McpToolRegistry {
                 required: vec!["name".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::Branch],
         });
 
         self.register_tool(Tool {
@@ -481,6 +747,7 @@ impl McpToolRegistry {
                 required: vec!["name".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::State],
         });
 
         self.register_tool(Tool {
@@ -492,6 +759,7 @@ impl McpToolRegistry {
                 required: vec![],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         // === ASSISTANT TOOLS ===
@@ -516,6 +784,7 @@ impl McpToolRegistry {
                 required: vec![],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -527,6 +796,7 @@ impl McpToolRegistry {
                 required: vec![],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -544,6 +814,7 @@ impl McpToolRegistry {
                 required: vec!["topic".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::State],
         });
 
         self.register_tool(Tool {
@@ -561,6 +832,7 @@ impl McpToolRegistry {
                 required: vec!["domain".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::Knowledge],
         });
 
         // === VECTOR TOOLS ===
@@ -579,6 +851,7 @@ impl McpToolRegistry {
                 required: vec!["content".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::Vector],
         });
 
         self.register_tool(Tool {
@@ -601,6 +874,7 @@ impl McpToolRegistry {
                 required: vec!["query".into()],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         // === BLOB TOOLS ===
@@ -627,6 +901,7 @@ impl McpToolRegistry {
                 required: vec!["content".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::State],
         });
 
         self.register_tool(Tool {
@@ -644,6 +919,7 @@ impl McpToolRegistry {
                 required: vec!["hash".into()],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -661,6 +937,7 @@ impl McpToolRegistry {
                 required: vec!["refs".into()],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -678,6 +955,7 @@ impl McpToolRegistry {
                 required: vec!["root".into()],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
 
         self.register_tool(Tool {
@@ -700,6 +978,7 @@ impl McpToolRegistry {
                 required: vec![],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::Ipfs],
         });
 
         self.register_tool(Tool {
@@ -732,6 +1011,7 @@ impl McpToolRegistry {
                 required: vec!["name".into(), "wasm".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::State],
         });
 
         self.register_tool(Tool {
@@ -767,6 +1047,7 @@ impl McpToolRegistry {
                 required: vec!["kind".into(), "message".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::State],
         });
 
         self.register_tool(Tool {
@@ -789,6 +1070,7 @@ impl McpToolRegistry {
                 required: vec!["message".into()],
             },
             requires_confirmation: false,
+            effects: vec![EffectKind::Branch],
         });
 
         self.register_tool(Tool {
@@ -806,6 +1088,7 @@ impl McpToolRegistry {
                 required: vec![],
             },
             requires_confirmation: false,
+            effects: vec![],
         });
     }
 
@@ -832,7 +1115,120 @@ impl McpToolRegistry {
             .collect()
     }
 
+    pub fn set_policy(&self, policy: ToolPolicy) {
+        *self.policy.write().unwrap() = policy;
+    }
+
+    pub fn policy(&self) -> ToolPolicy {
+        self.policy.read().unwrap().clone()
+    }
+
+    /// Hook asked about calls the policy marks `Ask`; without one they are declined
+    pub fn set_confirmer(&self, confirmer: Option<Arc<dyn ToolConfirmer>>) {
+        *self.confirmer.write().unwrap() = confirmer;
+    }
+
+    /// Also append audit records to a JSON lines file
+    pub fn set_audit_path(&self, path: Option<PathBuf>) {
+        self.audit.lock().unwrap().set_sink(path);
+    }
+
+    pub fn audit_log(&self) -> Vec<AuditRecord> {
+        self.audit.lock().unwrap().records()
+    }
+
+    /// Permission the current policy gives a tool, before confirmation
+    pub fn permission(&self, name: &str) -> Permission {
+        let policy = self.policy.read().unwrap();
+        match self.tools.get(name) {
+            Some(tool) => policy.resolve(tool),
+            None => policy.resolve_name(name),
+        }
+    }
+
+    /// Validate arguments and apply the policy, asking the confirmer if needed
+    ///
+    /// Refusals are audited here; permitted calls are audited once they ran
+    /// (see [`McpToolRegistry::record`]). Tools handled outside the registry
+    /// are authorized by name against the policy's default.
+    pub fn authorize(&self, name: &str, input: &serde_json::Value) -> Result<Decision> {
+        let decision = self.decide(name, input);
+        if decision.permits() {
+            return Ok(decision);
+        }
+
+        let error = match &decision {
+            Decision::Invalid(reason) => Error::InvalidArguments(format!("{}: {}", name, reason)),
+            Decision::Declined => Error::PermissionDenied(format!("{}: not confirmed", name)),
+            _ => Error::PermissionDenied(format!("{}: denied by policy", name)),
+        };
+        let mut record = AuditRecord::new(name, input, decision);
+        record.error = Some(error.to_string());
+        self.audit.lock().unwrap().push(record);
+        Err(error)
+    }
+
+    fn decide(&self, name: &str, input: &serde_json::Value) -> Decision {
+        let tool = self.tools.get(name);
+        if let Some(tool) = tool {
+            if let Err(reason) = tool.input_schema.validate(input) {
+                return Decision::Invalid(reason);
+            }
+        }
+
+        match self.permission(name) {
+            Permission::Allow => Decision::Allowed,
+            Permission::Deny => Decision::Denied,
+            Permission::Ask => {
+                let confirmer = self.confirmer.read().unwrap().clone();
+                let Some(confirmer) = confirmer else {
+                    return Decision::Declined;
+                };
+                let request = ConfirmRequest {
+                    tool: name.to_string(),
+                    description: tool.map(|t| t.description.clone()).unwrap_or_default(),
+                    category: tool.map(|t| t.category),
+                    effects: tool.map(|t| t.effects.clone()).unwrap_or_default(),
+                    input: input.clone(),
+                };
+                if confirmer.confirm(&request) {
+                    Decision::Confirmed
+                } else {
+                    Decision::Declined
+                }
+            }
+        }
+    }
+
+    /// Audit a call that was authorized and has run
+    pub fn record(
+        &self,
+        name: &str,
+        input: &serde_json::Value,
+        decision: Decision,
+        result: &Result<ToolResult>,
+        started: Instant,
+    ) -> u64 {
+        let record = AuditRecord::new(name, input, decision).finish(result, started);
+        self.audit.lock().unwrap().push(record)
+    }
+
+    /// Authorize, run and audit a tool call
     pub fn execute(&self, name: &str, input: &serde_json::Value) -> Result<ToolResult> {
+        if !self.tools.contains_key(name) && !self.executors.contains_key(name) {
+            self.audit.lock().unwrap().push(AuditRecord::new(name, input, Decision::Unknown));
+            return Err(Error::InferenceFailed(format!("Unknown tool: {}", name)));
+        }
+
+        let decision = self.authorize(name, input)?;
+        let started = Instant::now();
+        let result = self.run(name, input);
+        self.record(name, input, decision, &result, started);
+        result
+    }
+
+    /// Run a tool without authorization or audit; callers go through [`Self::authorize`]
+    pub(crate) fn run(&self, name: &str, input: &serde_json::Value) -> Result<ToolResult> {
         if let Some(executor) = self.executors.get(name) {
             executor.execute(input)
         } else {
@@ -920,6 +1316,19 @@ mod tests {
         assert!(registry.get("daemon_spawn").is_some());
     }
 
+    #[test]
+    fn test_orchestrator_tools_are_gated_like_registry_tools() {
+        let registry = McpToolRegistry::new();
+        registry.set_policy(crate::permissions::ToolPolicy::cautious());
+
+        let bad = registry.authorize("alexandria_record", &serde_json::json!({"concept": 1}));
+        assert!(matches!(bad, Err(Error::InvalidArguments(_))));
+        let read = serde_json::json!({"concept": "rust"});
+        assert!(registry.authorize("alexandria_navigate", &read).is_ok());
+        // Writes ask first, and there is no one to ask
+        assert!(matches!(registry.authorize("alexandria_record", &read), Err(Error::PermissionDenied(_))));
+    }
+
     #[test]
     fn test_list_by_category() {
         let registry = McpToolRegistry::new();
@@ -939,4 +1348,97 @@ mod tests {
             assert!(tool.get("input_schema").is_some());
         }
     }
+
+    struct Echo;
+
+    impl ToolExecutor for Echo {
+        fn execute(&self, input: &serde_json::Value) -> Result<ToolResult> {
+            Ok(ToolResult {
+                tool: "knowledge_learn".into(),
+                success: true,
+                output: input.clone(),
+                side_effects: vec![SideEffect::KnowledgeAdded { concept: "x".into() }],
+                learnings: vec![],
+            })
+        }
+
+        fn name(&self) -> &str { "knowledge_learn" }
+    }
+
+    #[test]
+    fn test_schema_validation() {
+        let registry = McpToolRegistry::new();
+        let schema = &registry.get("hash_compute").unwrap().input_schema;
+
+        assert!(schema.validate(&serde_json::json!({"input": "abc", "algorithm": "sha256"})).is_ok());
+        let err = schema.validate(&serde_json::json!({"algorithm": "sha256"})).unwrap_err();
+        assert!(err.contains("missing required argument 'input'"), "{}", err);
+        let err = schema.validate(&serde_json::json!({"input": 5, "algorithm": "md5"})).unwrap_err();
+        assert!(err.contains("must be string, got integer"), "{}", err);
+        let err = schema.validate(&serde_json::json!({"input": "a", "algorithm": "crc32"})).unwrap_err();
+        assert!(err.contains("must be one of"), "{}", err);
+        let err = schema.validate(&serde_json::json!({"input": "a", "algorithm": "md5", "salt": "x"})).unwrap_err();
+        assert!(err.contains("unknown argument 'salt'"), "{}", err);
+        assert!(schema.validate(&serde_json::json!(["a"])).is_err());
+
+        let log = &registry.get("git_chain_log").unwrap().input_schema;
+        assert!(log.validate(&serde_json::Value::Null).is_ok());
+        assert!(log.validate(&serde_json::json!({"limit": 1.5})).is_err());
+    }
+
+    #[test]
+    fn test_execute_audits_decisions() {
+        let mut registry = McpToolRegistry::new();
+        registry.register_executor(Echo);
+
+        let ok = registry.execute("knowledge_learn", &serde_json::json!({"concept": "rust"})).unwrap();
+        assert!(ok.success);
+
+        let bad = registry.execute("knowledge_learn", &serde_json::json!({}));
+        assert!(matches!(bad, Err(Error::InvalidArguments(_))));
+
+        // requires_confirmation with no confirmer is declined
+        let declined = registry.execute("port_scan", &serde_json::json!({"target": "10.0.0.1"}));
+        assert!(matches!(declined, Err(Error::PermissionDenied(_))));
+
+        registry.set_policy(ToolPolicy::default().deny("knowledge_learn"));
+        let denied = registry.execute("knowledge_learn", &serde_json::json!({"concept": "rust"}));
+        assert!(matches!(denied, Err(Error::PermissionDenied(_))));
+
+        assert!(registry.execute("no_such_tool", &serde_json::json!({})).is_err());
+
+        let log = registry.audit_log();
+        let decisions: Vec<_> = log.iter().map(|r| r.decision.clone()).collect();
+        assert!(matches!(decisions[1], Decision::Invalid(_)));
+        assert_eq!(decisions[0], Decision::Allowed);
+        assert_eq!(&decisions[2..], &[Decision::Declined, Decision::Denied, Decision::Unknown]);
+        assert_eq!(log[0].success, Some(true));
+        assert_eq!(log[0].side_effects, vec![EffectKind::Knowledge]);
+        assert_eq!(log[2].success, None);
+    }
+
+    #[test]
+    fn test_confirm_hook() {
+        let registry = McpToolRegistry::new();
+        registry.set_policy(ToolPolicy::cautious());
+        assert_eq!(registry.permission("daemon_spawn"), Permission::Ask);
+        assert_eq!(registry.permission("hash_compute"), Permission::Allow);
+
+        let asked = Arc::new(Mutex::new(Vec::new()));
+        let seen = asked.clone();
+        registry.set_confirmer(Some(Arc::new(move |req: &ConfirmRequest| {
+            seen.lock().unwrap().push((req.tool.clone(), req.effects.clone()));
+            req.input["daemon_type"] == "awareness"
+        })));
+
+        let input = serde_json::json!({"daemon_type": "awareness"});
+        assert_eq!(registry.authorize("daemon_spawn", &input).unwrap(), Decision::Confirmed);
+        let input = serde_json::json!({"daemon_type": "inference"});
+        assert!(registry.authorize("daemon_spawn", &input).is_err());
+
+        let asked = asked.lock().unwrap();
+        assert_eq!(asked.len(), 2);
+        assert_eq!(asked[0], ("daemon_spawn".to_string(), vec![EffectKind::Daemon]));
+        assert_eq!(registry.audit_log().last().unwrap().decision, Decision::Declined);
+    }
 }
@@ -28,7 +28,7 @@ pub const TAG_CHECKPOINT: u16 = 0x0302;
 pub const TAG_CONFIG: u16 = 0x0303;
 
 /// Schema for model I/O
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TensorSchema {
     pub shape: Vec<usize>,
     pub dtype: String, // "f32", "f16", "i8"
@@ -15,6 +15,7 @@ use crate::{
     knowledge::{KnowledgeGraph, NodeType, EdgeType},
     skills::{SkillRegistry, SkillContext, Learning},
     mcp::{McpToolRegistry, ToolResult, SideEffect},
+    agent_loop::{AgentLoop, ToolRunner},
     claude::{ClaudeClient, ClaudeModel, GentlyAssistant},
 };
 use gently_alexandria::{
@@ -24,6 +25,7 @@ use gently_alexandria::{
 };
 use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
 use std::collections::VecDeque;
+use std::path::PathBuf;
 use tokio::sync::mpsc;
 use chrono::Utc;
 
@@ -38,6 +40,10 @@ pub struct BrainConfig {
     pub ipfs_sync_interval_ms: u64,
     pub growth_rate: f32,
     pub max_context_size: usize,
+    /// Where the tesseract is loaded from and saved to (None = memory only)
+    ///
+    /// Off by default; the CLI sets it to `SemanticTesseract::default_path()`.
+    pub tesseract_path: Option<PathBuf>,
 }
 
 impl Default for BrainConfig {
@@ -51,6 +57,7 @@ impl Default for BrainConfig {
             ipfs_sync_interval_ms: 5000,
             growth_rate: 0.1,
             max_context_size: 100,
+            tesseract_path: None,
         }
     }
 }
@@ -89,6 +96,8 @@ pub struct BrainOrchestrator {
     // Alexandria - distributed knowledge graph
     alexandria: Arc<Mutex<AlexandriaGraph>>,
     tesseract: Arc<Mutex<SemanticTesseract>>,
+    /// Why `tesseract_path` could not be loaded; saving is refused while set
+    tesseract_error: Option<String>,
 
     // State
     running: Arc<AtomicBool>,
@@ -144,6 +153,17 @@ impl BrainOrchestrator {
             &format!("brain-{}", uuid::Uuid::new_v4()),
         );
 
+        let (tesseract, tesseract_error) = match &config.tesseract_path {
+            Some(path) if path.exists() => match SemanticTesseract::load(path) {
+                Ok(tesseract) => (tesseract, None),
+                Err(e) => {
+                    tracing::error!("Tesseract {} not loaded, will not overwrite it: {}", path.display(), e);
+                    (SemanticTesseract::new(), Some(e.to_string()))
+                }
+            },
+            _ => (SemanticTesseract::new(), None),
+        };
+
         Self {
             config,
             daemon_manager: Arc::new(Mutex::new(DaemonManager::new())),
@@ -151,7 +171,8 @@ impl BrainOrchestrator {
             skill_registry: Arc::new(SkillRegistry::new()),
             tool_registry: Arc::new(McpToolRegistry::new()),
             alexandria: Arc::new(Mutex::new(AlexandriaGraph::with_defaults(node_fingerprint))),
-            tesseract: Arc::new(Mutex::new(SemanticTesseract::new())),
+            tesseract: Arc::new(Mutex::new(tesseract)),
+            tesseract_error,
             running: Arc::new(AtomicBool::new(false)),
             context: Arc::new(Mutex::new(VecDeque::new())),
             attention: Arc::new(Mutex::new(Vec::new())),
@@ -194,8 +215,33 @@ impl BrainOrchestrator {
     /// Stop the brain
     pub fn stop(&self) {
         self.running.store(false, Ordering::SeqCst);
-        let mut dm = self.daemon_manager.lock().unwrap();
-        dm.stop();
+        {
+            let mut dm = self.daemon_manager.lock().unwrap();
+            dm.stop();
+        }
+
+        if let Err(e) = self.save_tesseract() {
+            tracing::warn!("Failed to save tesseract: {}", e);
+        }
+    }
+
+    /// Persist the tesseract to `config.tesseract_path` (no-op when unset)
+    ///
+    /// Fails without writing if the file there could not be loaded, so a
+    /// corrupt or newer-format file is never replaced by an empty tesseract.
+    pub fn save_tesseract(&self) -> Result<()> {
+        let Some(path) = &self.config.tesseract_path else {
+            return Ok(());
+        };
+        if let Some(e) = &self.tesseract_error {
+            return Err(Error::Io(std::io::Error::other(format!(
+                "not overwriting {}, it failed to load: {}", path.display(), e
+            ))));
+        }
+        let tesseract = self.tesseract.lock().unwrap();
+        tesseract
+            .save(path)
+            .map_err(|e| Error::Io(std::io::Error::other(e.to_string())))
     }
 
     /// Spawn a daemon
@@ -334,6 +380,15 @@ impl BrainOrchestrator {
             input: input.clone(),
         });
 
+        // Permission gate and audit cover orchestrator-handled tools too
+        let decision = self.tool_registry.authorize(name, input)?;
+        let started = std::time::Instant::now();
+        let result = self.dispatch_tool(name, input).await;
+        self.tool_registry.record(name, input, decision, &result, started);
+        result
+    }
+
+    async fn dispatch_tool(&self, name: &str, input: &serde_json::Value) -> Result<ToolResult> {
         // Route to appropriate handler
         match name {
             // Knowledge tools
@@ -362,7 +417,7 @@ impl BrainOrchestrator {
             "alexandria_record" => self.tool_alexandria_record(input).await,
 
             // Default: try registry
-            _ => self.tool_registry.execute(name, input),
+            _ => self.tool_registry.run(name, input),
         }
     }
 
@@ -442,6 +497,13 @@ impl BrainOrchestrator {
         &self.tool_registry
     }
 
+    /// Agent loop whose tool calls go through [`Self::execute_tool`] on `runtime`
+    pub fn agent_loop(self: &Arc<Self>, runtime: tokio::runtime::Handle) -> AgentLoop {
+        AgentLoop::new(self.tool_registry.clone())
+            .with_skills(self.skill_registry.clone())
+            .with_runner(Arc::new(OrchestratorTools { orchestrator: self.clone(), runtime }))
+    }
+
     /// Get knowledge graph
     pub fn knowledge_graph(&self) -> &KnowledgeGraph {
         &self.knowledge_graph
@@ -462,6 +524,11 @@ impl BrainOrchestrator {
         self.tesseract.clone()
     }
 
+    /// Load error for `config.tesseract_path`, if the file there was unreadable
+    pub fn tesseract_error(&self) -> Option<&str> {
+        self.tesseract_error.as_deref()
+    }
+
     // === Internal helpers ===
 
     fn is_learnable(&self, thought: &str) -> bool {
@@ -656,7 +723,14 @@ impl BrainOrchestrator {
             .and_then(|v| v.as_str())
             .ok_or_else(|| Error::InferenceFailed("Missing daemon name".into()))?;
 
-        // Note: Full stop implementation would require stopping specific daemon
+        let stopped = {
+            let mut dm = self.daemon_manager.lock().unwrap();
+            dm.stop_daemon(name)
+        };
+        if !stopped {
+            return Err(Error::InferenceFailed(format!("Daemon not found: {}", name)));
+        }
+
         Ok(ToolResult {
             tool: "daemon_stop".into(),
             success: true,
@@ -712,6 +786,11 @@ impl BrainOrchestrator {
                         "items_processed": s.metrics.items_processed,
                         "vectors_computed": s.metrics.vectors_computed,
                         "bytes_synced": s.metrics.bytes_synced,
+                        "busy_cycles": s.metrics.busy_cycles,
+                        "last_cycle_ms": s.metrics.last_cycle_time.as_secs_f64() * 1000.0,
+                        "panics": s.metrics.panics,
+                        "restarts": s.metrics.restarts,
+                        "jobs_drained": s.metrics.jobs_drained,
                     }
                 }),
                 side_effects: vec![],
@@ -1168,6 +1247,18 @@ impl BrainOrchestrator {
     }
 }
 
+/// Blocking bridge from the agent loop to the async tool dispatch
+struct OrchestratorTools {
+    orchestrator: Arc<BrainOrchestrator>,
+    runtime: tokio::runtime::Handle,
+}
+
+impl ToolRunner for OrchestratorTools {
+    fn run_tool(&self, name: &str, input: &serde_json::Value) -> Result<ToolResult> {
+        self.runtime.block_on(self.orchestrator.execute_tool(name, input))
+    }
+}
+
 /// Run the awareness loop - the "consciousness" that processes thoughts
 pub async fn run_awareness_loop(orchestrator: Arc<BrainOrchestrator>) {
     let interval = std::time::Duration::from_millis(orchestrator.config.awareness_interval_ms);
@@ -1279,4 +1370,58 @@ mod tests {
         let query_result = orchestrator.execute_tool("alexandria_tesseract", &tesseract_input).await;
         assert!(query_result.is_ok(), "Should be able to query tesseract");
     }
+
+    #[tokio::test]
+    async fn test_tesseract_persists_across_restarts() {
+        let path = std::env::temp_dir().join(format!("brain-tesseract-{}.json", uuid::Uuid::new_v4()));
+        let config = BrainConfig {
+            enable_daemons: false,
+            tesseract_path: Some(path.clone()),
+            ..Default::default()
+        };
+
+        let orchestrator = BrainOrchestrator::new(config.clone());
+        let record_input = serde_json::json!({
+            "concept": "crypto",
+            "actual": ["bitcoin"],
+            "eliminated": ["cryptography"],
+            "era": ["2021"]
+        });
+        orchestrator.execute_tool("alexandria_record", &record_input).await.unwrap();
+        orchestrator.stop();
+
+        let restarted = BrainOrchestrator::new(config);
+        let _ = std::fs::remove_file(&path);
+
+        let tesseract = restarted.tesseract();
+        let tesseract = tesseract.lock().unwrap();
+        let result = gently_alexandria::HyperQuery::new()
+            .concept(ConceptId::from_concept("crypto"))
+            .in_face(gently_alexandria::HyperFace::Eliminated)
+            .execute(&tesseract);
+        assert_eq!(result.concepts, vec![ConceptId::from_concept("cryptography")]);
+        assert_eq!(tesseract.concepts_in_era("2021"), vec![ConceptId::from_concept("crypto")]);
+    }
+
+    #[test]
+    fn test_unreadable_tesseract_is_kept() {
+        assert!(BrainConfig::default().tesseract_path.is_none());
+
+        let path = std::env::temp_dir().join(format!("brain-tesseract-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"{\"version\": 99}").unwrap();
+        let config = BrainConfig {
+            enable_daemons: false,
+            tesseract_path: Some(path.clone()),
+            ..Default::default()
+        };
+
+        let orchestrator = BrainOrchestrator::new(config);
+        assert!(orchestrator.tesseract_error().is_some());
+        assert!(orchestrator.save_tesseract().is_err());
+        orchestrator.stop();
+
+        let kept = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(kept, b"{\"version\": 99}");
+    }
 }
@@ -0,0 +1,411 @@
+//! Tool permissions, confirmation and audit
+//!
+//! Every tool call goes through the same gate before it runs:
+//!
+//! ```text
+//! call ──► schema check ──► policy ──► Allow ─────────────► run ──► audit
+//!              │               ├────► Ask ──► confirmer ──┘  │
+//!              ▼               └────► Deny ───────────────────┤
+//!           Invalid                                           ▼
+//!                                                          AuditRecord
+//! ```
+//!
+//! A rule for the tool itself wins. Otherwise the strictest of its category
+//! rule, its effect rules and its `requires_confirmation` flag applies,
+//! falling back to the policy default. Skills called through the agent loop
+//! (`skill_<name>`) fall back to the `skills` rule instead.
+
+use crate::agent_loop::SKILL_PREFIX;
+use crate::mcp::{EffectKind, Tool, ToolCategory, ToolResult};
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// What a policy says about a tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    Allow,
+    Ask,
+    Deny,
+}
+
+/// Per-tool permissions keyed by name, category and side effect
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolPolicy {
+    #[serde(default = "default_permission")]
+    pub default: Permission,
+    #[serde(default)]
+    pub tools: HashMap<String, Permission>,
+    #[serde(default)]
+    pub categories: HashMap<ToolCategory, Permission>,
+    #[serde(default)]
+    pub effects: HashMap<EffectKind, Permission>,
+    /// Skills without a rule of their own
+    #[serde(default = "default_permission")]
+    pub skills: Permission,
+}
+
+fn default_permission() -> Permission {
+    Permission::Allow
+}
+
+impl Default for ToolPolicy {
+    /// Everything allowed except tools flagged `requires_confirmation`
+    fn default() -> Self {
+        Self {
+            default: Permission::Allow,
+            tools: HashMap::new(),
+            categories: HashMap::new(),
+            effects: HashMap::new(),
+            skills: Permission::Allow,
+        }
+    }
+}
+
+impl ToolPolicy {
+    /// Policy that asks before anything with a side effect, or anything it does not know
+    pub fn cautious() -> Self {
+        let mut policy = Self {
+            default: Permission::Ask,
+            skills: Permission::Ask,
+            ..Self::default()
+        };
+        for effect in EffectKind::ALL {
+            policy.effects.insert(effect, Permission::Ask);
+        }
+        policy.categories.insert(ToolCategory::Network, Permission::Ask);
+        policy.categories.insert(ToolCategory::System, Permission::Ask);
+        for category in [ToolCategory::Crypto, ToolCategory::Knowledge, ToolCategory::Blob] {
+            policy.categories.insert(category, Permission::Allow);
+        }
+        policy
+    }
+
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        let data = std::fs::read(path)?;
+        serde_json::from_slice(&data)
+            .map_err(|e| crate::Error::InvalidModel(format!("tool policy {}: {}", path.display(), e)))
+    }
+
+    pub fn allow(mut self, tool: &str) -> Self {
+        self.tools.insert(tool.to_string(), Permission::Allow);
+        self
+    }
+
+    pub fn ask(mut self, tool: &str) -> Self {
+        self.tools.insert(tool.to_string(), Permission::Ask);
+        self
+    }
+
+    pub fn deny(mut self, tool: &str) -> Self {
+        self.tools.insert(tool.to_string(), Permission::Deny);
+        self
+    }
+
+    /// Permission for a registered tool
+    pub fn resolve(&self, tool: &Tool) -> Permission {
+        if let Some(p) = self.tools.get(&tool.name) {
+            return *p;
+        }
+
+        let mut rules: Vec<Permission> = tool.effects.iter()
+            .filter_map(|e| self.effects.get(e).copied())
+            .collect();
+        // Without a category rule the default stands in for it
+        rules.push(self.categories.get(&tool.category).copied().unwrap_or(self.default));
+        if tool.requires_confirmation {
+            rules.push(Permission::Ask);
+        }
+        rules.into_iter().max().unwrap_or(self.default)
+    }
+
+    /// Permission for a tool the registry does not describe
+    pub fn resolve_name(&self, name: &str) -> Permission {
+        if let Some(p) = self.tools.get(name) {
+            return *p;
+        }
+        if name.starts_with(SKILL_PREFIX) {
+            self.skills
+        } else {
+            self.default
+        }
+    }
+}
+
+/// What the confirmer is asked about
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfirmRequest {
+    pub tool: String,
+    pub description: String,
+    pub category: Option<ToolCategory>,
+    pub effects: Vec<EffectKind>,
+    pub input: serde_json::Value,
+}
+
+/// Interactive confirmation hook (CLI prompt, TUI dialog, ...)
+pub trait ToolConfirmer: Send + Sync {
+    /// True to let the call run
+    fn confirm(&self, request: &ConfirmRequest) -> bool;
+}
+
+impl<F> ToolConfirmer for F
+where
+    F: Fn(&ConfirmRequest) -> bool + Send + Sync,
+{
+    fn confirm(&self, request: &ConfirmRequest) -> bool {
+        self(request)
+    }
+}
+
+/// Why a call ran or did not
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "decision", content = "reason", rename_all = "snake_case")]
+pub enum Decision {
+    /// Policy allowed it
+    Allowed,
+    /// Policy asked and the confirmer agreed
+    Confirmed,
+    /// Policy asked and the confirmer refused (or none was set)
+    Declined,
+    /// Policy denied it
+    Denied,
+    /// Arguments failed the tool's schema
+    Invalid(String),
+    /// No such tool
+    Unknown,
+}
+
+impl Decision {
+    pub fn permits(&self) -> bool {
+        matches!(self, Decision::Allowed | Decision::Confirmed)
+    }
+}
+
+/// One tool call and what happened to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub id: u64,
+    pub timestamp: u64,
+    pub tool: String,
+    pub input: serde_json::Value,
+    #[serde(flatten)]
+    pub decision: Decision,
+    /// Set when the tool ran
+    pub success: Option<bool>,
+    pub error: Option<String>,
+    pub side_effects: Vec<EffectKind>,
+    pub duration_ms: u64,
+}
+
+/// Bounded in-memory audit trail with an optional JSON lines file
+#[derive(Debug)]
+pub struct AuditLog {
+    records: VecDeque<AuditRecord>,
+    capacity: usize,
+    next_id: u64,
+    sink: Option<PathBuf>,
+}
+
+/// Records kept in memory when no capacity is given
+pub const DEFAULT_AUDIT_CAPACITY: usize = 1000;
+
+impl AuditLog {
+    /// Keep the last `capacity` records; 0 means [`DEFAULT_AUDIT_CAPACITY`]
+    pub fn new(capacity: usize) -> Self {
+        let capacity = if capacity == 0 { DEFAULT_AUDIT_CAPACITY } else { capacity };
+        Self { records: VecDeque::new(), capacity, next_id: 1, sink: None }
+    }
+
+    /// Also append every record to `path`
+    pub fn with_sink(mut self, path: PathBuf) -> Self {
+        self.sink = Some(path);
+        self
+    }
+
+    pub fn set_sink(&mut self, path: Option<PathBuf>) {
+        self.sink = path;
+    }
+
+    pub(crate) fn push(&mut self, mut record: AuditRecord) -> u64 {
+        record.id = self.next_id;
+        self.next_id += 1;
+
+        if let Some(path) = &self.sink {
+            let line = serde_json::to_string(&record).unwrap();
+            let written = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut f| writeln!(f, "{}", line));
+            if let Err(e) = written {
+                tracing::warn!("tool audit sink {}: {}", path.display(), e);
+            }
+        }
+
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+        self.next_id - 1
+    }
+
+    pub fn records(&self) -> Vec<AuditRecord> {
+        self.records.iter().cloned().collect()
+    }
+
+    pub fn last(&self) -> Option<&AuditRecord> {
+        self.records.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self { Self::new(DEFAULT_AUDIT_CAPACITY) }
+}
+
+impl AuditRecord {
+    pub(crate) fn new(tool: &str, input: &serde_json::Value, decision: Decision) -> Self {
+        Self {
+            id: 0,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            tool: tool.to_string(),
+            input: input.clone(),
+            decision,
+            success: None,
+            error: None,
+            side_effects: Vec::new(),
+            duration_ms: 0,
+        }
+    }
+
+    pub(crate) fn finish(mut self, result: &crate::Result<ToolResult>, started: std::time::Instant) -> Self {
+        self.duration_ms = started.elapsed().as_millis() as u64;
+        match result {
+            Ok(r) => {
+                self.success = Some(r.success);
+                self.side_effects = r.side_effects.iter().map(|s| s.kind()).collect();
+            }
+            Err(e) => {
+                self.success = Some(false);
+                self.error = Some(e.to_string());
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::ToolSchema;
+
+    fn tool(name: &str, category: ToolCategory, effects: Vec<EffectKind>, confirm: bool) -> Tool {
+        Tool {
+            name: name.into(),
+            description: String::new(),
+            category,
+            input_schema: ToolSchema { properties: HashMap::new(), required: vec![] },
+            requires_confirmation: confirm,
+            effects,
+        }
+    }
+
+    #[test]
+    fn test_policy_resolution() {
+        let scan = tool("port_scan", ToolCategory::Network, vec![], true);
+        let learn = tool("knowledge_learn", ToolCategory::Knowledge, vec![EffectKind::Knowledge], false);
+        let recall = tool("knowledge_recall", ToolCategory::Knowledge, vec![], false);
+
+        let default = ToolPolicy::default();
+        assert_eq!(default.resolve(&scan), Permission::Ask);
+        assert_eq!(default.resolve(&learn), Permission::Allow);
+
+        // Strictest of category and effect rules wins
+        let mut policy = ToolPolicy::cautious();
+        policy.categories.insert(ToolCategory::Knowledge, Permission::Allow);
+        assert_eq!(policy.resolve(&learn), Permission::Ask);
+        assert_eq!(policy.resolve(&recall), Permission::Allow);
+        policy.effects.insert(EffectKind::Knowledge, Permission::Deny);
+        assert_eq!(policy.resolve(&learn), Permission::Deny);
+
+        // A tool rule overrides everything
+        let policy = policy.allow("knowledge_learn").deny("knowledge_recall");
+        assert_eq!(policy.resolve(&learn), Permission::Allow);
+        assert_eq!(policy.resolve(&recall), Permission::Deny);
+        assert_eq!(policy.resolve_name("alexandria_drift"), Permission::Ask);
+
+        // Skills have their own fallback
+        let policy = ToolPolicy { skills: Permission::Deny, ..Default::default() };
+        assert_eq!(policy.resolve_name("skill_learn"), Permission::Deny);
+        assert_eq!(policy.allow("skill_learn").resolve_name("skill_learn"), Permission::Allow);
+    }
+
+    #[test]
+    fn test_cautious_fails_closed() {
+        let policy = ToolPolicy::cautious();
+        let fresh = tool("new_tool", ToolCategory::Storage, vec![], false);
+        assert_eq!(policy.resolve(&fresh), Permission::Ask);
+        assert_eq!(policy.resolve_name("skill_exploit"), Permission::Ask);
+        assert_eq!(policy.resolve_name("unregistered"), Permission::Ask);
+
+        // Read-only categories it knows stay usable
+        let hash = tool("hash_compute", ToolCategory::Crypto, vec![], false);
+        assert_eq!(policy.resolve(&hash), Permission::Allow);
+    }
+
+    #[test]
+    fn test_policy_json() {
+        let policy: ToolPolicy = serde_json::from_str(r#"{
+            "default": "ask",
+            "tools": {"hash_compute": "allow"},
+            "categories": {"Network": "deny"},
+            "effects": {"daemon": "ask"}
+        }"#).unwrap();
+        assert_eq!(policy.default, Permission::Ask);
+        assert_eq!(policy.categories[&ToolCategory::Network], Permission::Deny);
+        assert_eq!(policy.effects[&EffectKind::Daemon], Permission::Ask);
+
+        let back: ToolPolicy = serde_json::from_str(&serde_json::to_string(&policy).unwrap()).unwrap();
+        assert_eq!(back.tools["hash_compute"], Permission::Allow);
+    }
+
+    #[test]
+    fn test_audit_log_capacity_and_sink() {
+        let path = std::env::temp_dir().join(format!("gently-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut log = AuditLog::new(2).with_sink(path.clone());
+        for tool in ["a", "b", "c"] {
+            log.push(AuditRecord::new(tool, &serde_json::json!({}), Decision::Denied));
+        }
+        let tools: Vec<_> = log.records().into_iter().map(|r| (r.id, r.tool)).collect();
+        assert_eq!(tools, vec![(2, "b".to_string()), (3, "c".to_string())]);
+
+        let lines: Vec<AuditRecord> = std::fs::read_to_string(&path).unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].decision, Decision::Denied);
+        std::fs::remove_file(&path).unwrap();
+
+        // No capacity is not "unbounded"
+        let mut log = AuditLog::new(0);
+        for _ in 0..DEFAULT_AUDIT_CAPACITY + 5 {
+            log.push(AuditRecord::new("a", &serde_json::json!({}), Decision::Denied));
+        }
+        assert_eq!(log.records().len(), DEFAULT_AUDIT_CAPACITY);
+    }
+}
@@ -0,0 +1,914 @@
+//! Watchdog rule language
+//!
+//! Rules are small declarative blocks compiled into `RuleSpec` values
+//! (stored as JSON blobs by the `Watchdog`) and evaluated statefully by a
+//! `RuleEngine`.
+//!
+//! ```text
+//! # five failed logins from one source in a minute
+//! rule "ssh_bruteforce" {
+//!     when kind == access and message contains "auth failed"
+//!     by source
+//!     count >= 5 within 60s
+//!     cooldown 5m
+//!     then block, inference "Brute force from {source} ({count} attempts)"
+//! }
+//!
+//! # privileged access shortly after an integrity change on the same host
+//! rule "tamper_then_access" {
+//!     when kind == access and severity >= 3
+//!     after kind == integrity within 10m
+//!     by source
+//!     then notify
+//! }
+//! ```
+//!
+//! Fields: `kind source message severity timestamp inference`.
+//! Operators: `== != < <= > >= contains startswith endswith ~ (regex) in (..)`,
+//! combined with `and`, `or`, `not` and parentheses.
+//! Durations take `s`, `m`, `h` or `d`; event timestamps are seconds.
+
+use crate::watchdog::{Action, Event, EventKind};
+use crate::{Error, Result};
+use gently_core::Hash;
+use regex::Regex;
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Event field a condition reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    Kind,
+    Source,
+    Message,
+    Severity,
+    Timestamp,
+    Inference,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "kind" => Field::Kind,
+            "source" => Field::Source,
+            "message" => Field::Message,
+            "severity" => Field::Severity,
+            "timestamp" => Field::Timestamp,
+            "inference" => Field::Inference,
+            _ => return None,
+        })
+    }
+
+    /// Field value as text (grouping keys, templates)
+    pub fn render(&self, event: &Event) -> String {
+        match self {
+            Field::Kind => event.kind.name().to_string(),
+            Field::Source => event.source.clone(),
+            Field::Message => event.message.clone(),
+            Field::Severity => event.severity.to_string(),
+            Field::Timestamp => event.timestamp.to_string(),
+            Field::Inference => event.requires_inference.to_string(),
+        }
+    }
+}
+
+/// Comparison operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Matches,
+    In,
+}
+
+impl Op {
+    fn compare<T: PartialOrd>(&self, a: T, b: T) -> bool {
+        match self {
+            Op::Eq => a == b,
+            Op::Ne => a != b,
+            Op::Lt => a < b,
+            Op::Le => a <= b,
+            Op::Gt => a > b,
+            Op::Ge => a >= b,
+            _ => false,
+        }
+    }
+}
+
+/// Literal in a condition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Value {
+    Str(String),
+    Num(f64),
+    Kind(EventKind),
+    Bool(bool),
+    List(Vec<Value>),
+}
+
+/// Condition over one event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expr {
+    True,
+    Cmp { field: Field, op: Op, value: Value },
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+impl Expr {
+    fn matches(&self, event: &Event, regexes: &HashMap<String, Regex>) -> bool {
+        match self {
+            Expr::True => true,
+            Expr::And(a, b) => a.matches(event, regexes) && b.matches(event, regexes),
+            Expr::Or(a, b) => a.matches(event, regexes) || b.matches(event, regexes),
+            Expr::Not(e) => !e.matches(event, regexes),
+            Expr::Cmp { field, op: Op::In, value: Value::List(items) } => items.iter()
+                .any(|v| cmp(event, *field, Op::Eq, v, regexes)),
+            Expr::Cmp { field, op, value } => cmp(event, *field, *op, value, regexes),
+        }
+    }
+
+    fn patterns<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Expr::Cmp { op: Op::Matches, value: Value::Str(p), .. } => out.push(p),
+            Expr::And(a, b) | Expr::Or(a, b) => {
+                a.patterns(out);
+                b.patterns(out);
+            }
+            Expr::Not(e) => e.patterns(out),
+            _ => {}
+        }
+    }
+}
+
+fn cmp(event: &Event, field: Field, op: Op, value: &Value, regexes: &HashMap<String, Regex>) -> bool {
+    match (field, value) {
+        (Field::Kind, Value::Kind(k)) => op.compare(event.kind as u8, *k as u8) && matches!(op, Op::Eq | Op::Ne),
+        (Field::Severity, Value::Num(n)) => op.compare(event.severity as f64, *n),
+        (Field::Timestamp, Value::Num(n)) => op.compare(event.timestamp as f64, *n),
+        (Field::Inference, Value::Bool(b)) => op.compare(event.requires_inference, *b),
+        (Field::Source | Field::Message, Value::Str(s)) => {
+            let text = if field == Field::Source { &event.source } else { &event.message };
+            match op {
+                Op::Contains => text.contains(s.as_str()),
+                Op::StartsWith => text.starts_with(s.as_str()),
+                Op::EndsWith => text.ends_with(s.as_str()),
+                Op::Matches => regexes.get(s).is_some_and(|r| r.is_match(text)),
+                _ => op.compare(text.as_str(), s.as_str()),
+            }
+        }
+        _ => false,
+    }
+}
+
+/// `count OP N within D`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CountClause {
+    pub op: Op,
+    pub count: u64,
+    /// Window in seconds
+    pub within: u64,
+}
+
+/// `after EXPR within D`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AfterClause {
+    pub expr: Expr,
+    /// Window in seconds
+    pub within: u64,
+}
+
+/// A compiled rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleSpec {
+    pub name: String,
+    pub when: Expr,
+    /// Field that splits counts, correlation and cooldown into groups
+    pub by: Option<Field>,
+    pub count: Option<CountClause>,
+    pub after: Option<AfterClause>,
+    /// Seconds before the same group may fire again
+    pub cooldown: Option<u64>,
+    /// Actions; `Inference` prompts are templates
+    pub actions: Vec<Action>,
+}
+
+// ── Lexer ───────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Dur(u64),
+    Sym(&'static str),
+    Eof,
+}
+
+struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    line: usize,
+    col: usize,
+}
+
+const SYMBOLS: [&str; 12] = ["==", "!=", "<=", ">=", "<", ">", "~", "{", "}", "(", ")", ","];
+
+fn lex(src: &str) -> Result<Vec<(Tok, usize, usize)>> {
+    let mut lx = Lexer { chars: src.char_indices().peekable(), line: 1, col: 1 };
+    let mut out = Vec::new();
+
+    while let Some(&(i, c)) = lx.chars.peek() {
+        let (line, col) = (lx.line, lx.col);
+        if c.is_whitespace() {
+            lx.bump();
+        } else if c == '#' {
+            while lx.chars.peek().is_some_and(|&(_, c)| c != '\n') {
+                lx.bump();
+            }
+        } else if c == '"' {
+            lx.bump();
+            let mut s = String::new();
+            loop {
+                match lx.bump() {
+                    Some('"') => break,
+                    Some('\\') => match lx.bump() {
+                        Some('n') => s.push('\n'),
+                        Some(c) => s.push(c),
+                        None => return Err(syntax(line, col, "unterminated string")),
+                    },
+                    Some(c) => s.push(c),
+                    None => return Err(syntax(line, col, "unterminated string")),
+                }
+            }
+            out.push((Tok::Str(s), line, col));
+        } else if c.is_ascii_digit() {
+            let mut end = i;
+            while let Some(&(j, c)) = lx.chars.peek() {
+                if !(c.is_ascii_digit() || c == '.') {
+                    break;
+                }
+                end = j + c.len_utf8();
+                lx.bump();
+            }
+            let num: f64 = src[i..end].parse().map_err(|_| syntax(line, col, "bad number"))?;
+            let unit = match lx.chars.peek().map(|&(_, c)| c) {
+                Some('s') => Some(1),
+                Some('m') => Some(60),
+                Some('h') => Some(3600),
+                Some('d') => Some(86_400),
+                _ => None,
+            };
+            match unit {
+                Some(unit) => {
+                    lx.bump();
+                    out.push((Tok::Dur((num * unit as f64) as u64), line, col));
+                }
+                None => out.push((Tok::Num(num), line, col)),
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = i;
+            while let Some(&(j, c)) = lx.chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = j + c.len_utf8();
+                lx.bump();
+            }
+            out.push((Tok::Ident(src[i..end].to_string()), line, col));
+        } else {
+            let sym = SYMBOLS.iter().find(|s| src[i..].starts_with(**s))
+                .ok_or_else(|| syntax(line, col, &format!("unexpected '{}'", c)))?;
+            for _ in 0..sym.len() {
+                lx.bump();
+            }
+            out.push((Tok::Sym(sym), line, col));
+        }
+    }
+
+    out.push((Tok::Eof, lx.line, lx.col));
+    Ok(out)
+}
+
+impl Lexer<'_> {
+    fn bump(&mut self) -> Option<char> {
+        let (_, c) = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.col = 1;
+        } else {
+            self.col += 1;
+        }
+        Some(c)
+    }
+}
+
+fn syntax(line: usize, col: usize, msg: &str) -> Error {
+    Error::Rules(format!("{}:{}: {}", line, col, msg))
+}
+
+// ── Parser ──────────────────────────────────────────────────────────────────
+
+/// Parse and check every rule in `src`
+pub fn parse(src: &str) -> Result<Vec<RuleSpec>> {
+    let mut p = Parser { toks: lex(src)?, pos: 0 };
+    let mut rules = Vec::new();
+    while p.peek() != &Tok::Eof {
+        let rule = p.rule()?;
+        if rules.iter().any(|r: &RuleSpec| r.name == rule.name) {
+            return Err(Error::Rules(format!("duplicate rule \"{}\"", rule.name)));
+        }
+        rules.push(rule);
+    }
+    Ok(rules)
+}
+
+struct Parser {
+    toks: Vec<(Tok, usize, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Tok {
+        &self.toks[self.pos].0
+    }
+
+    fn next(&mut self) -> Tok {
+        let tok = self.toks[self.pos].0.clone();
+        if tok != Tok::Eof {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    fn error(&self, msg: &str) -> Error {
+        let (tok, line, col) = &self.toks[self.pos];
+        let found = match tok {
+            Tok::Ident(s) => format!("'{}'", s),
+            Tok::Str(s) => format!("\"{}\"", s),
+            Tok::Num(n) => n.to_string(),
+            Tok::Dur(d) => format!("{}s", d),
+            Tok::Sym(s) => format!("'{}'", s),
+            Tok::Eof => "end of input".to_string(),
+        };
+        syntax(*line, *col, &format!("{}, found {}", msg, found))
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Tok::Ident(w) if w == word)
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let hit = self.is_word(word);
+        if hit {
+            self.pos += 1;
+        }
+        hit
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<()> {
+        if self.eat_word(word) { Ok(()) } else { Err(self.error(&format!("expected '{}'", word))) }
+    }
+
+    fn eat_sym(&mut self, sym: &str) -> bool {
+        let hit = matches!(self.peek(), Tok::Sym(s) if *s == sym);
+        if hit {
+            self.pos += 1;
+        }
+        hit
+    }
+
+    fn expect_sym(&mut self, sym: &str) -> Result<()> {
+        if self.eat_sym(sym) { Ok(()) } else { Err(self.error(&format!("expected '{}'", sym))) }
+    }
+
+    fn duration(&mut self) -> Result<u64> {
+        match self.peek() {
+            Tok::Dur(d) => {
+                let d = *d;
+                self.pos += 1;
+                Ok(d)
+            }
+            _ => Err(self.error("expected duration like 30s, 5m, 1h")),
+        }
+    }
+
+    fn rule(&mut self) -> Result<RuleSpec> {
+        self.expect_word("rule")?;
+        let name = match self.next() {
+            Tok::Str(s) | Tok::Ident(s) => s,
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("expected rule name"));
+            }
+        };
+        self.expect_sym("{")?;
+
+        let mut rule = RuleSpec {
+            name,
+            when: Expr::True,
+            by: None,
+            count: None,
+            after: None,
+            cooldown: None,
+            actions: Vec::new(),
+        };
+        let mut seen: Vec<String> = Vec::new();
+
+        while !self.eat_sym("}") {
+            let clause = match self.peek() {
+                Tok::Ident(w) => w.clone(),
+                _ => return Err(self.error("expected clause (when, by, count, after, cooldown, then)")),
+            };
+            if seen.contains(&clause) {
+                return Err(self.error(&format!("duplicate '{}' clause", clause)));
+            }
+            self.pos += 1;
+
+            match clause.as_str() {
+                "when" => rule.when = self.expr()?,
+                "by" => rule.by = Some(self.field()?),
+                "count" => {
+                    let op = match self.next() {
+                        Tok::Sym("==") => Op::Eq,
+                        Tok::Sym(">=") => Op::Ge,
+                        Tok::Sym(">") => Op::Gt,
+                        Tok::Sym("<=") => Op::Le,
+                        Tok::Sym("<") => Op::Lt,
+                        _ => {
+                            self.pos -= 1;
+                            return Err(self.error("expected count comparison"));
+                        }
+                    };
+                    let count = match self.next() {
+                        Tok::Num(n) if n >= 0.0 && n.fract() == 0.0 => n as u64,
+                        _ => {
+                            self.pos -= 1;
+                            return Err(self.error("expected whole number"));
+                        }
+                    };
+                    self.expect_word("within")?;
+                    rule.count = Some(CountClause { op, count, within: self.duration()? });
+                }
+                "after" => {
+                    let expr = self.expr()?;
+                    self.expect_word("within")?;
+                    rule.after = Some(AfterClause { expr, within: self.duration()? });
+                }
+                "cooldown" => rule.cooldown = Some(self.duration()?),
+                "then" => loop {
+                    rule.actions.push(self.action()?);
+                    if !self.eat_sym(",") {
+                        break;
+                    }
+                },
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.error("expected clause (when, by, count, after, cooldown, then)"));
+                }
+            }
+            seen.push(clause);
+        }
+
+        if rule.actions.is_empty() {
+            return Err(Error::Rules(format!("rule \"{}\" has no 'then' actions", rule.name)));
+        }
+        Ok(rule)
+    }
+
+    fn action(&mut self) -> Result<Action> {
+        let word = match self.next() {
+            Tok::Ident(w) => w,
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("expected action (log, notify, block, inference)"));
+            }
+        };
+        match word.as_str() {
+            "log" => Ok(Action::Log),
+            "notify" => Ok(Action::Notify),
+            "block" => Ok(Action::Block),
+            "inference" => match self.next() {
+                Tok::Str(prompt) => Ok(Action::Inference(prompt)),
+                _ => {
+                    self.pos -= 1;
+                    Err(self.error("expected prompt string"))
+                }
+            },
+            _ => {
+                self.pos -= 1;
+                Err(self.error("expected action (log, notify, block, inference)"))
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut lhs = self.and()?;
+        while self.eat_word("or") {
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut lhs = self.unary()?;
+        while self.eat_word("and") {
+            lhs = Expr::And(Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.eat_word("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat_sym("(") {
+            let e = self.expr()?;
+            self.expect_sym(")")?;
+            return Ok(e);
+        }
+        if self.eat_word("any") {
+            return Ok(Expr::True);
+        }
+        self.comparison()
+    }
+
+    fn field(&mut self) -> Result<Field> {
+        let field = match self.peek() {
+            Tok::Ident(name) => Field::parse(name),
+            _ => None,
+        };
+        match field {
+            Some(f) => {
+                self.pos += 1;
+                Ok(f)
+            }
+            None => Err(self.error("expected field (kind, source, message, severity, timestamp, inference)")),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let field = self.field()?;
+        let at = self.pos;
+        let op = match self.next() {
+            Tok::Sym("==") => Op::Eq,
+            Tok::Sym("!=") => Op::Ne,
+            Tok::Sym("<") => Op::Lt,
+            Tok::Sym("<=") => Op::Le,
+            Tok::Sym(">") => Op::Gt,
+            Tok::Sym(">=") => Op::Ge,
+            Tok::Sym("~") => Op::Matches,
+            Tok::Ident(w) if w == "contains" => Op::Contains,
+            Tok::Ident(w) if w == "startswith" => Op::StartsWith,
+            Tok::Ident(w) if w == "endswith" => Op::EndsWith,
+            Tok::Ident(w) if w == "matches" => Op::Matches,
+            Tok::Ident(w) if w == "in" => Op::In,
+            _ => {
+                self.pos = at;
+                return Err(self.error("expected operator"));
+            }
+        };
+
+        let allowed = match field {
+            Field::Kind | Field::Inference => matches!(op, Op::Eq | Op::Ne | Op::In),
+            Field::Severity | Field::Timestamp => !matches!(op, Op::Contains | Op::StartsWith | Op::EndsWith | Op::Matches),
+            Field::Source | Field::Message => true,
+        };
+        if !allowed {
+            self.pos = at;
+            return Err(self.error(&format!("operator not supported for {:?}", field).to_lowercase()));
+        }
+
+        let value = if op == Op::In {
+            self.expect_sym("(")?;
+            let mut items = vec![self.value(field)?];
+            while self.eat_sym(",") {
+                items.push(self.value(field)?);
+            }
+            self.expect_sym(")")?;
+            Value::List(items)
+        } else {
+            self.value(field)?
+        };
+
+        if op == Op::Matches {
+            if let Value::Str(p) = &value {
+                Regex::new(p).map_err(|e| {
+                    self.pos = at + 1;
+                    self.error(&format!("invalid regex: {}", e))
+                })?;
+            }
+        }
+        Ok(Expr::Cmp { field, op, value })
+    }
+
+    /// Literal typed by the field it is compared with
+    fn value(&mut self, field: Field) -> Result<Value> {
+        let value = match (field, self.peek()) {
+            (Field::Kind, Tok::Ident(name)) => EventKind::from_name(name).map(Value::Kind),
+            (Field::Inference, Tok::Ident(b)) if b == "true" || b == "false" => Some(Value::Bool(b == "true")),
+            (Field::Severity | Field::Timestamp, Tok::Num(n)) => Some(Value::Num(*n)),
+            (Field::Source | Field::Message, Tok::Str(s)) => Some(Value::Str(s.clone())),
+            _ => None,
+        };
+        match value {
+            Some(v) => {
+                self.pos += 1;
+                Ok(v)
+            }
+            None => Err(self.error(match field {
+                Field::Kind => "expected event kind (alert, anomaly, threshold, integrity, access, inference)",
+                Field::Inference => "expected true or false",
+                Field::Severity | Field::Timestamp => "expected number",
+                Field::Source | Field::Message => "expected string",
+            })),
+        }
+    }
+}
+
+// ── Evaluation ──────────────────────────────────────────────────────────────
+
+/// A rule ready to evaluate
+#[derive(Debug, Clone)]
+pub struct ActiveRule {
+    /// Hash of the rule blob
+    pub hash: Hash,
+    pub spec: RuleSpec,
+    regexes: HashMap<String, Regex>,
+}
+
+impl ActiveRule {
+    pub fn new(hash: Hash, spec: RuleSpec) -> Result<Self> {
+        let mut patterns = Vec::new();
+        spec.when.patterns(&mut patterns);
+        if let Some(after) = &spec.after {
+            after.expr.patterns(&mut patterns);
+        }
+
+        let mut regexes = HashMap::new();
+        for p in patterns {
+            let re = Regex::new(p).map_err(|e| Error::Rules(format!("rule \"{}\": {}", spec.name, e)))?;
+            regexes.insert(p.to_string(), re);
+        }
+        Ok(Self { hash, spec, regexes })
+    }
+}
+
+/// A rule that fired
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Firing {
+    pub rule: Hash,
+    pub name: String,
+    /// Group value of the `by` field ("" without one)
+    pub key: String,
+    /// Actions with prompt templates filled in
+    pub actions: Vec<Action>,
+    /// Events that made up the match, oldest first
+    pub events: Vec<Hash>,
+}
+
+/// Per rule and group window state
+#[derive(Debug, Default)]
+struct GroupState {
+    hits: VecDeque<(u64, Hash)>,
+    prior: VecDeque<(u64, Hash)>,
+    last_fired: Option<u64>,
+}
+
+impl GroupState {
+    fn is_empty(&self) -> bool {
+        self.hits.is_empty() && self.prior.is_empty() && self.last_fired.is_none()
+    }
+}
+
+/// Stateful evaluator for a set of rules
+///
+/// Window state is keyed by rule hash, so reloading an unchanged rule
+/// keeps its counts.
+#[derive(Debug, Default)]
+pub struct RuleEngine {
+    rules: Vec<ActiveRule>,
+    state: HashMap<(Hash, String), GroupState>,
+}
+
+impl RuleEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule, replacing one with the same hash
+    pub fn insert(&mut self, rule: ActiveRule) {
+        self.remove(&rule.hash);
+        self.rules.push(rule);
+    }
+
+    /// Drop a rule and its state
+    pub fn remove(&mut self, hash: &Hash) -> bool {
+        let before = self.rules.len();
+        self.rules.retain(|r| r.hash != *hash);
+        self.state.retain(|(h, _), _| h != hash);
+        self.rules.len() != before
+    }
+
+    pub fn rules(&self) -> &[ActiveRule] {
+        &self.rules
+    }
+
+    /// Feed one event, returning the rules that fired
+    pub fn evaluate(&mut self, event: &Event, event_hash: Hash) -> Vec<Firing> {
+        let t = event.timestamp;
+        let mut fired = Vec::new();
+
+        for rule in &self.rules {
+            let spec = &rule.spec;
+            let key = spec.by.map(|f| f.render(event)).unwrap_or_default();
+            let state = self.state.entry((rule.hash, key.clone())).or_default();
+
+            if let Some(after) = &spec.after {
+                while state.prior.front().is_some_and(|(at, _)| t.saturating_sub(*at) > after.within) {
+                    state.prior.pop_front();
+                }
+            }
+
+            if spec.when.matches(event, &rule.regexes) {
+                let mut ok = spec.after.is_none() || !state.prior.is_empty();
+
+                let mut count = 1;
+                if let Some(c) = &spec.count {
+                    state.hits.push_back((t, event_hash));
+                    while state.hits.front().is_some_and(|(at, _)| t.saturating_sub(*at) > c.within) {
+                        state.hits.pop_front();
+                    }
+                    count = state.hits.len() as u64;
+                    ok &= c.op.compare(count, c.count);
+                }
+
+                if let (Some(cooldown), Some(last)) = (spec.cooldown, state.last_fired) {
+                    ok &= t.saturating_sub(last) >= cooldown;
+                }
+
+                if ok {
+                    state.last_fired = Some(t);
+                    let mut events: Vec<Hash> = state.prior.back().map(|(_, h)| *h).into_iter().collect();
+                    if spec.count.is_some() {
+                        events.extend(state.hits.iter().map(|(_, h)| *h));
+                    } else {
+                        events.push(event_hash);
+                    }
+
+                    let actions = spec.actions.iter()
+                        .map(|a| match a {
+                            Action::Inference(prompt) => Action::Inference(render(prompt, spec, event, count)),
+                            other => other.clone(),
+                        })
+                        .collect();
+                    fired.push(Firing { rule: rule.hash, name: spec.name.clone(), key, actions, events });
+                }
+            }
+
+            // Record after evaluating, so an event never correlates with itself
+            if let Some(after) = &spec.after {
+                if after.expr.matches(event, &rule.regexes) {
+                    state.prior.push_back((t, event_hash));
+                }
+            }
+        }
+
+        self.state.retain(|_, s| !s.is_empty());
+        fired
+    }
+}
+
+/// Fill `{rule} {kind} {source} {message} {severity} {count}` placeholders
+fn render(template: &str, spec: &RuleSpec, event: &Event, count: u64) -> String {
+    template
+        .replace("{rule}", &spec.name)
+        .replace("{kind}", event.kind.name())
+        .replace("{source}", &event.source)
+        .replace("{message}", &event.message)
+        .replace("{severity}", &event.severity.to_string())
+        .replace("{count}", &count.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: EventKind, source: &str, message: &str, severity: u8, timestamp: u64) -> Event {
+        Event {
+            kind,
+            source: source.into(),
+            message: message.into(),
+            severity,
+            timestamp,
+            requires_inference: false,
+        }
+    }
+
+    fn engine(src: &str) -> RuleEngine {
+        let mut engine = RuleEngine::new();
+        for (i, spec) in parse(src).unwrap().into_iter().enumerate() {
+            engine.insert(ActiveRule::new([i as u8; 32], spec).unwrap());
+        }
+        engine
+    }
+
+    fn feed(engine: &mut RuleEngine, e: &Event) -> Vec<String> {
+        engine.evaluate(e, [e.timestamp as u8; 32]).into_iter().map(|f| f.name).collect()
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let rules = parse(r#"
+            # comment
+            rule "brute" {
+                when kind == access and (message contains "auth failed" or message ~ "^denied") and not source in ("127.0.0.1")
+                by source
+                count >= 5 within 1m
+                cooldown 5m
+                then block, inference "{count} failures from {source}"
+            }
+            rule simple { then log }
+        "#).unwrap();
+
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].by, Some(Field::Source));
+        assert_eq!(rules[0].count, Some(CountClause { op: Op::Ge, count: 5, within: 60 }));
+        assert_eq!(rules[0].cooldown, Some(300));
+        assert_eq!(rules[1].when, Expr::True);
+
+        // Compiled rules survive a blob round trip
+        let json = serde_json::to_vec(&rules[0]).unwrap();
+        assert_eq!(serde_json::from_slice::<RuleSpec>(&json).unwrap(), rules[0]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |src: &str| match parse(src) {
+            Err(Error::Rules(msg)) => msg,
+            other => panic!("expected error, got {:?}", other),
+        };
+        assert!(err("rule a { when kind == bogus then log }").contains("event kind"));
+        assert!(err("rule a { when severity contains \"x\" then log }").contains("operator"));
+        assert!(err("rule a { when message ~ \"(\" then log }").contains("regex"));
+        assert!(err("rule a { count >= 3 then log }").contains("'within'"));
+        assert!(err("rule a { when any }").contains("no 'then'"));
+        assert!(err("rule a { then log }\nrule a { then log }").contains("duplicate"));
+        assert!(err("rule a {\n  when source == 5 then log }").starts_with("2:"));
+    }
+
+    #[test]
+    fn test_count_window_by_field() {
+        let mut e = engine(r#"rule "brute" {
+            when kind == access and message contains "auth failed"
+            by source
+            count >= 3 within 60s
+            then block
+        }"#);
+
+        let fail = |src: &str, t| event(EventKind::Access, src, "auth failed", 1, t);
+        assert!(feed(&mut e, &fail("a", 0)).is_empty());
+        assert!(feed(&mut e, &fail("b", 1)).is_empty());
+        assert!(feed(&mut e, &fail("a", 10)).is_empty());
+        assert_eq!(feed(&mut e, &fail("a", 20)), vec!["brute"]);
+        // First attempt slides out of the window
+        assert!(feed(&mut e, &fail("b", 100)).is_empty());
+        assert!(feed(&mut e, &fail("a", 75)).is_empty());
+        assert!(feed(&mut e, &event(EventKind::Access, "a", "ok", 1, 76)).is_empty());
+    }
+
+    #[test]
+    fn test_correlation_and_cooldown() {
+        let mut e = engine(r#"rule "tamper" {
+            when kind == access and severity >= 3
+            after kind == integrity within 10m
+            by source
+            cooldown 1h
+            then notify, inference "{source}: {message}"
+        }"#);
+
+        let access = |src: &str, t| event(EventKind::Access, src, "root login", 3, t);
+        assert!(feed(&mut e, &access("h1", 0)).is_empty());
+        feed(&mut e, &event(EventKind::Integrity, "h1", "binary changed", 2, 100));
+        assert!(feed(&mut e, &access("h2", 200)).is_empty());
+
+        let firing = e.evaluate(&access("h1", 200), [9; 32]);
+        assert_eq!(firing.len(), 1);
+        assert_eq!(firing[0].events, vec![[100u8; 32], [9u8; 32]]);
+        assert!(matches!(&firing[0].actions[1], Action::Inference(p) if p == "h1: root login"));
+
+        // Cooldown, then the integrity event ages out
+        assert!(feed(&mut e, &access("h1", 300)).is_empty());
+        assert!(feed(&mut e, &access("h1", 4000)).is_empty());
+    }
+}
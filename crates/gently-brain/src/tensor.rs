@@ -0,0 +1,480 @@
+//! Tensor Blobs
+//!
+//! Named tensors packed into a single `Kind::Tensor` blob, described by
+//! `TensorSchema` (shape + dtype), with safetensors import/export.
+//!
+//! ```text
+//! ┌──────┬─────┬────────────┬─────────────────────────────────┬──────────┐
+//! │ GTNS │ ver │ header len │ [{name, schema, offset}] (JSON) │ raw data │
+//! └──────┴─────┴────────────┴─────────────────────────────────┴──────────┘
+//!   4B     1B     u32 LE            sorted by name              LE f32/f16/bf16
+//! ```
+//!
+//! Values are held as f32 in memory and rounded to the schema dtype on
+//! construction, so encode → decode is exact.
+
+use crate::gguf::{f16_to_f32, f32_to_f16};
+use crate::modelchain::TensorSchema;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Blob magic
+pub const TENSOR_MAGIC: &[u8; 4] = b"GTNS";
+
+/// Current blob format version
+pub const TENSOR_VERSION: u8 = 1;
+
+/// Element types tensors can be stored as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DType {
+    F32,
+    F16,
+    BF16,
+}
+
+impl DType {
+    /// Accepts "f32", "F32", "float32", "bf16", …
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "f32" | "float32" | "float" => Ok(Self::F32),
+            "f16" | "float16" | "half" => Ok(Self::F16),
+            "bf16" | "bfloat16" => Ok(Self::BF16),
+            other => Err(Error::Tensor(format!("unsupported dtype '{}'", other))),
+        }
+    }
+
+    /// Name used in `TensorSchema`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::F32 => "f32",
+            Self::F16 => "f16",
+            Self::BF16 => "bf16",
+        }
+    }
+
+    /// Name used in safetensors headers
+    pub fn safetensors_name(&self) -> &'static str {
+        match self {
+            Self::F32 => "F32",
+            Self::F16 => "F16",
+            Self::BF16 => "BF16",
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        match self {
+            Self::F32 => 4,
+            Self::F16 | Self::BF16 => 2,
+        }
+    }
+
+    /// Nearest value representable in this dtype
+    pub fn round(&self, x: f32) -> f32 {
+        match self {
+            Self::F32 => x,
+            Self::F16 => f16_to_f32(f32_to_f16(x)),
+            Self::BF16 => bf16_to_f32(f32_to_bf16(x)),
+        }
+    }
+
+    fn write(&self, x: f32, out: &mut Vec<u8>) {
+        match self {
+            Self::F32 => out.extend_from_slice(&x.to_le_bytes()),
+            Self::F16 => out.extend_from_slice(&f32_to_f16(x).to_le_bytes()),
+            Self::BF16 => out.extend_from_slice(&f32_to_bf16(x).to_le_bytes()),
+        }
+    }
+
+    fn read(&self, bytes: &[u8]) -> Vec<f32> {
+        match self {
+            Self::F32 => bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+            Self::F16 => bytes
+                .chunks_exact(2)
+                .map(|b| f16_to_f32(u16::from_le_bytes([b[0], b[1]])))
+                .collect(),
+            Self::BF16 => bytes
+                .chunks_exact(2)
+                .map(|b| bf16_to_f32(u16::from_le_bytes([b[0], b[1]])))
+                .collect(),
+        }
+    }
+}
+
+/// Round-to-nearest-even truncation to bfloat16
+pub fn f32_to_bf16(x: f32) -> u16 {
+    let bits = x.to_bits();
+    if x.is_nan() {
+        return ((bits >> 16) | 0x40) as u16;
+    }
+    let rounding = 0x7fff + ((bits >> 16) & 1);
+    (bits.wrapping_add(rounding) >> 16) as u16
+}
+
+pub fn bf16_to_f32(h: u16) -> f32 {
+    f32::from_bits((h as u32) << 16)
+}
+
+/// Element count of `shape`, failing instead of wrapping on overflow
+///
+/// A zero dim does not hide an overflow in the others.
+fn shape_numel(shape: &[usize]) -> Result<usize> {
+    let overflow = || Error::Tensor(format!("shape {:?} is too large", shape));
+    let nonzero = shape.iter().filter(|&&d| d != 0).try_fold(1usize, |n, &d| n.checked_mul(d));
+    match nonzero {
+        Some(_) if shape.contains(&0) => Ok(0),
+        Some(n) => Ok(n),
+        None => Err(overflow()),
+    }
+}
+
+/// Bytes taken by `shape` stored as `dtype`
+fn shape_bytes(shape: &[usize], dtype: DType) -> Result<usize> {
+    shape_numel(shape)?
+        .checked_mul(dtype.size())
+        .ok_or_else(|| Error::Tensor(format!("shape {:?} is too large", shape)))
+}
+
+/// A dense tensor, row-major
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tensor {
+    pub schema: TensorSchema,
+    data: Vec<f32>,
+}
+
+impl Tensor {
+    /// Values are rounded to `dtype`
+    pub fn new(shape: Vec<usize>, dtype: DType, data: Vec<f32>) -> Result<Self> {
+        let numel = shape_numel(&shape)?;
+        if numel != data.len() {
+            return Err(Error::Tensor(format!(
+                "shape {:?} needs {} values, got {}",
+                shape,
+                numel,
+                data.len()
+            )));
+        }
+        let mut tensor = Self { schema: TensorSchema { shape, dtype: dtype.as_str().into() }, data };
+        tensor.round();
+        Ok(tensor)
+    }
+
+    pub fn f32(shape: Vec<usize>, data: Vec<f32>) -> Result<Self> {
+        Self::new(shape, DType::F32, data)
+    }
+
+    pub fn zeros(shape: Vec<usize>, dtype: DType) -> Result<Self> {
+        let numel = shape_numel(&shape)?;
+        Ok(Self { schema: TensorSchema { shape, dtype: dtype.as_str().into() }, data: vec![0.0; numel] })
+    }
+
+    pub fn dtype(&self) -> DType {
+        // Constructors only ever store names `DType::parse` accepts
+        DType::parse(&self.schema.dtype).unwrap_or(DType::F32)
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.schema.shape
+    }
+
+    pub fn numel(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn data(&self) -> &[f32] {
+        &self.data
+    }
+
+    /// Mutable values; call `round` afterwards to snap back to the dtype
+    pub fn data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Snap every value to the nearest one the dtype can hold
+    pub fn round(&mut self) {
+        let dtype = self.dtype();
+        if dtype != DType::F32 {
+            self.data.iter_mut().for_each(|x| *x = dtype.round(*x));
+        }
+    }
+
+    /// Same values stored as another dtype
+    pub fn cast(&self, dtype: DType) -> Self {
+        let mut out = Self {
+            schema: TensorSchema { shape: self.schema.shape.clone(), dtype: dtype.as_str().into() },
+            data: self.data.clone(),
+        };
+        out.round();
+        out
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.numel() * self.dtype().size()
+    }
+
+    fn to_bytes(&self, out: &mut Vec<u8>) {
+        let dtype = self.dtype();
+        for &x in &self.data {
+            dtype.write(x, out);
+        }
+    }
+
+    fn from_bytes(schema: TensorSchema, bytes: &[u8]) -> Result<Self> {
+        let dtype = DType::parse(&schema.dtype)?;
+        let expected = shape_bytes(&schema.shape, dtype)?;
+        if bytes.len() != expected {
+            return Err(Error::Tensor(format!("tensor data is {} bytes, expected {}", bytes.len(), expected)));
+        }
+        Ok(Self { schema: TensorSchema { shape: schema.shape, dtype: dtype.as_str().into() }, data: dtype.read(bytes) })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    name: String,
+    schema: TensorSchema,
+    offset: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SafeEntry {
+    dtype: String,
+    shape: Vec<usize>,
+    data_offsets: [usize; 2],
+}
+
+/// Named tensors stored together in one blob
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TensorBlob {
+    tensors: BTreeMap<String, Tensor>,
+}
+
+impl TensorBlob {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: &str, tensor: Tensor) -> Option<Tensor> {
+        self.tensors.insert(name.to_string(), tensor)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Tensor> {
+        self.tensors.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Tensor> {
+        self.tensors.get_mut(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.tensors.keys().map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Tensor)> {
+        self.tensors.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    pub fn len(&self) -> usize {
+        self.tensors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tensors.is_empty()
+    }
+
+    /// Serialize to the GTNS blob format
+    pub fn encode(&self) -> Vec<u8> {
+        let mut offset = 0;
+        let entries: Vec<Entry> = self
+            .tensors
+            .iter()
+            .map(|(name, t)| {
+                let entry = Entry { name: name.clone(), schema: t.schema.clone(), offset };
+                offset += t.byte_len();
+                entry
+            })
+            .collect();
+        let header = serde_json::to_vec(&entries).unwrap_or_default();
+
+        let mut out = Vec::with_capacity(9 + header.len() + offset);
+        out.extend_from_slice(TENSOR_MAGIC);
+        out.push(TENSOR_VERSION);
+        out.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        out.extend_from_slice(&header);
+        for t in self.tensors.values() {
+            t.to_bytes(&mut out);
+        }
+        out
+    }
+
+    /// Parse a GTNS blob
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 9 || &bytes[..4] != TENSOR_MAGIC {
+            return Err(Error::Tensor("not a tensor blob".into()));
+        }
+        if bytes[4] != TENSOR_VERSION {
+            return Err(Error::Tensor(format!("unsupported tensor blob version {}", bytes[4])));
+        }
+        let header_len = u32::from_le_bytes([bytes[5], bytes[6], bytes[7], bytes[8]]) as usize;
+        let data_start = 9usize
+            .checked_add(header_len)
+            .filter(|&end| end <= bytes.len())
+            .ok_or_else(|| Error::Tensor("truncated tensor header".into()))?;
+        let entries: Vec<Entry> = serde_json::from_slice(&bytes[9..data_start])
+            .map_err(|e| Error::Tensor(format!("bad tensor header: {}", e)))?;
+
+        let data = &bytes[data_start..];
+        let mut blob = Self::new();
+        for entry in entries {
+            let dtype = DType::parse(&entry.schema.dtype)?;
+            let len = shape_bytes(&entry.schema.shape, dtype)?;
+            let raw = entry
+                .offset
+                .checked_add(len)
+                .and_then(|end| data.get(entry.offset..end))
+                .ok_or_else(|| Error::Tensor(format!("tensor {} runs past end of blob", entry.name)))?;
+            blob.tensors.insert(entry.name, Tensor::from_bytes(entry.schema, raw)?);
+        }
+        Ok(blob)
+    }
+
+    /// Export as a `.safetensors` image
+    pub fn to_safetensors(&self) -> Vec<u8> {
+        let mut header = serde_json::Map::new();
+        let mut offset = 0;
+        for (name, t) in &self.tensors {
+            let entry = SafeEntry {
+                dtype: t.dtype().safetensors_name().into(),
+                shape: t.shape().to_vec(),
+                data_offsets: [offset, offset + t.byte_len()],
+            };
+            offset += t.byte_len();
+            header.insert(name.clone(), serde_json::to_value(entry).unwrap_or_default());
+        }
+        let mut header = serde_json::to_vec(&header).unwrap_or_default();
+        // Pad so the data section starts 8-byte aligned
+        header.resize(header.len().next_multiple_of(8), b' ');
+
+        let mut out = Vec::with_capacity(8 + header.len() + offset);
+        out.extend_from_slice(&(header.len() as u64).to_le_bytes());
+        out.extend_from_slice(&header);
+        for t in self.tensors.values() {
+            t.to_bytes(&mut out);
+        }
+        out
+    }
+
+    /// Import a `.safetensors` image (F32/F16/BF16 tensors)
+    pub fn from_safetensors(bytes: &[u8]) -> Result<Self> {
+        let header_len = bytes
+            .get(..8)
+            .map(|b| u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+            .ok_or_else(|| Error::Tensor("truncated safetensors file".into()))?;
+        let data_start = usize::try_from(header_len)
+            .ok()
+            .and_then(|len| len.checked_add(8))
+            .filter(|&end| end <= bytes.len())
+            .ok_or_else(|| Error::Tensor("truncated safetensors header".into()))?;
+        let header: BTreeMap<String, serde_json::Value> = serde_json::from_slice(&bytes[8..data_start])
+            .map_err(|e| Error::Tensor(format!("bad safetensors header: {}", e)))?;
+
+        let data = &bytes[data_start..];
+        let mut blob = Self::new();
+        for (name, value) in header {
+            if name == "__metadata__" {
+                continue;
+            }
+            let entry: SafeEntry = serde_json::from_value(value)
+                .map_err(|e| Error::Tensor(format!("bad safetensors entry {}: {}", name, e)))?;
+            let dtype = DType::parse(&entry.dtype)?;
+            let [start, end] = entry.data_offsets;
+            let raw = data
+                .get(start..end)
+                .ok_or_else(|| Error::Tensor(format!("tensor {} runs past end of file", name)))?;
+            let schema = TensorSchema { shape: entry.shape, dtype: dtype.as_str().into() };
+            blob.tensors.insert(name, Tensor::from_bytes(schema, raw)?);
+        }
+        Ok(blob)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> TensorBlob {
+        let values: Vec<f32> = (0..12).map(|i| i as f32 * 0.37 - 2.0).collect();
+        let mut blob = TensorBlob::new();
+        blob.insert("layers.0.q_proj.weight", Tensor::f32(vec![3, 4], values.clone()).unwrap());
+        blob.insert("layers.0.v_proj.weight", Tensor::new(vec![4, 3], DType::F16, values.clone()).unwrap());
+        blob.insert("norm.weight", Tensor::new(vec![12], DType::BF16, values).unwrap());
+        blob
+    }
+
+    #[test]
+    fn test_blob_roundtrip() {
+        let blob = sample();
+        let bytes = blob.encode();
+        assert_eq!(&bytes[..4], TENSOR_MAGIC);
+        assert_eq!(TensorBlob::decode(&bytes).unwrap(), blob);
+        assert!(TensorBlob::decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(TensorBlob::decode(b"nope").is_err());
+    }
+
+    #[test]
+    fn test_safetensors_roundtrip() {
+        let blob = sample();
+        let bytes = blob.to_safetensors();
+        let header_len = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+        assert_eq!(header_len % 8, 0);
+        let header: serde_json::Value = serde_json::from_slice(&bytes[8..8 + header_len]).unwrap();
+        assert_eq!(header["layers.0.v_proj.weight"]["dtype"], "F16");
+        assert_eq!(header["layers.0.v_proj.weight"]["shape"], serde_json::json!([4, 3]));
+
+        assert_eq!(TensorBlob::from_safetensors(&bytes).unwrap(), blob);
+    }
+
+    #[test]
+    fn test_dtype_rounding() {
+        let t = Tensor::new(vec![3], DType::BF16, vec![1.0, 1.0 + 1.0 / 256.0, 3.2]).unwrap();
+        assert_eq!(t.data(), &[1.0, 1.0, 3.203125]);
+        assert_eq!(t.byte_len(), 6);
+
+        let half = Tensor::f32(vec![1], vec![0.1]).unwrap().cast(DType::F16);
+        assert!((half.data()[0] - 0.1).abs() < 1e-4 && half.data()[0] != 0.1);
+
+        assert!(Tensor::f32(vec![2, 2], vec![0.0; 3]).is_err());
+        assert!(DType::parse("int8").is_err());
+    }
+
+    #[test]
+    fn test_rejects_overflowing_shapes() {
+        let huge = vec![1usize << 32, 1 << 32, 2];
+        assert!(Tensor::zeros(huge.clone(), DType::F32).is_err());
+        assert!(Tensor::f32(huge.clone(), Vec::new()).is_err());
+        assert!(Tensor::f32(vec![0, 1 << 32, 1 << 32, 2], Vec::new()).is_err());
+        assert!(Tensor::f32(vec![0, 3], Vec::new()).is_ok());
+
+        // A header whose shape wraps to zero must not import as an empty tensor
+        let header = br#"{"w":{"dtype":"F32","shape":[4294967296,4294967296,2],"data_offsets":[0,0]}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header);
+        assert!(matches!(TensorBlob::from_safetensors(&bytes), Err(Error::Tensor(_))));
+
+        let mut blob = TensorBlob::new().encode();
+        let entries = br#"[{"name":"w","schema":{"shape":[4294967296,4294967296,2],"dtype":"f32"},"offset":0}]"#;
+        blob.truncate(5);
+        blob.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        blob.extend_from_slice(entries);
+        assert!(matches!(TensorBlob::decode(&blob), Err(Error::Tensor(_))));
+
+        let entries = br#"[{"name":"w","schema":{"shape":[4],"dtype":"f32"},"offset":18446744073709551615}]"#;
+        blob.truncate(5);
+        blob.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        blob.extend_from_slice(entries);
+        assert!(matches!(TensorBlob::decode(&blob), Err(Error::Tensor(_))));
+    }
+}
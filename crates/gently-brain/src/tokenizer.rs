@@ -0,0 +1,431 @@
+//! SentencePiece Tokenizer
+//!
+//! Llama-style (SPM) tokenizer rebuilt from the `tokenizer.ggml.*`
+//! metadata embedded in a GGUF file, so no tokenizer.json is needed.
+//!
+//! ```text
+//! "Hi there" → "▁Hi▁there" → ▁ H i ▁ t h e r e
+//!                          → merge best-scoring pairs → ▁Hi ▁there
+//!                          → unknown pieces fall back to <0xXX> bytes
+//! ```
+
+use crate::gguf::{GgufFile, MetaValue};
+use crate::{Error, Result};
+use std::collections::HashMap;
+
+/// SentencePiece word-boundary marker
+const SPACE: &str = "\u{2581}";
+
+/// Vocabulary entry kind (llama.cpp numbering)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenType {
+    Normal,
+    Unknown,
+    Control,
+    UserDefined,
+    Unused,
+    Byte,
+}
+
+impl TokenType {
+    fn from_id(id: i64) -> Self {
+        match id {
+            2 => Self::Unknown,
+            3 => Self::Control,
+            4 => Self::UserDefined,
+            5 => Self::Unused,
+            6 => Self::Byte,
+            _ => Self::Normal,
+        }
+    }
+
+    pub fn id(&self) -> i32 {
+        match self {
+            Self::Normal => 1,
+            Self::Unknown => 2,
+            Self::Control => 3,
+            Self::UserDefined => 4,
+            Self::Unused => 5,
+            Self::Byte => 6,
+        }
+    }
+}
+
+/// Llama SentencePiece tokenizer
+#[derive(Debug, Clone)]
+pub struct Tokenizer {
+    tokens: Vec<String>,
+    scores: Vec<f32>,
+    types: Vec<TokenType>,
+    index: HashMap<String, u32>,
+    byte_tokens: [Option<u32>; 256],
+    /// Control/user-defined pieces matched verbatim in text, longest first
+    specials: Vec<(String, u32)>,
+    bos: u32,
+    eos: u32,
+    unk: u32,
+    add_bos: bool,
+    add_space_prefix: bool,
+}
+
+impl Tokenizer {
+    /// Build from a vocabulary; `types` may be empty
+    pub fn new(tokens: Vec<String>, scores: Vec<f32>, types: Vec<TokenType>) -> Result<Self> {
+        if tokens.is_empty() {
+            return Err(Error::InvalidModel("empty vocabulary".into()));
+        }
+        if scores.len() != tokens.len() || (!types.is_empty() && types.len() != tokens.len()) {
+            return Err(Error::InvalidModel("tokenizer arrays differ in length".into()));
+        }
+        let types = if types.is_empty() {
+            tokens.iter().map(|t| if parse_byte(t).is_some() { TokenType::Byte } else { TokenType::Normal }).collect()
+        } else {
+            types
+        };
+
+        let mut index = HashMap::new();
+        let mut byte_tokens = [None; 256];
+        let mut specials = Vec::new();
+        for (id, (token, ty)) in tokens.iter().zip(&types).enumerate() {
+            let id = id as u32;
+            match ty {
+                TokenType::Byte => {
+                    if let Some(b) = parse_byte(token) {
+                        byte_tokens[b as usize] = Some(id);
+                    }
+                }
+                TokenType::Control | TokenType::UserDefined if !token.is_empty() => {
+                    specials.push((token.clone(), id))
+                }
+                _ => {}
+            }
+            index.entry(token.clone()).or_insert(id);
+        }
+        specials.sort_by_key(|s| std::cmp::Reverse(s.0.len()));
+
+        let lookup = |s: &str, fallback: u32| index.get(s).copied().unwrap_or(fallback);
+        let unk = lookup("<unk>", 0);
+        let bos = lookup("<s>", 1);
+        let eos = lookup("</s>", 2);
+
+        Ok(Self {
+            tokens,
+            scores,
+            types,
+            index,
+            byte_tokens,
+            specials,
+            bos,
+            eos,
+            unk,
+            add_bos: true,
+            add_space_prefix: true,
+        })
+    }
+
+    /// Rebuild the tokenizer stored in a GGUF file
+    pub fn from_gguf(file: &GgufFile) -> Result<Self> {
+        match file.get_str("tokenizer.ggml.model") {
+            Some("llama") | None => {}
+            Some(other) => {
+                return Err(Error::InvalidModel(format!("unsupported tokenizer model '{}'", other)))
+            }
+        }
+
+        let tokens: Vec<String> = file
+            .get("tokenizer.ggml.tokens")
+            .and_then(MetaValue::as_array)
+            .ok_or_else(|| Error::InvalidModel("missing tokenizer.ggml.tokens".into()))?
+            .iter()
+            .map(|v| v.as_str().map(str::to_string))
+            .collect::<Option<_>>()
+            .ok_or_else(|| Error::InvalidModel("tokenizer.ggml.tokens must be strings".into()))?;
+
+        let scores = match file.get("tokenizer.ggml.scores").and_then(MetaValue::as_array) {
+            Some(values) => values.iter().map(|v| v.as_f32().unwrap_or(0.0)).collect(),
+            None => vec![0.0; tokens.len()],
+        };
+        let types = match file.get("tokenizer.ggml.token_type").and_then(MetaValue::as_array) {
+            Some(values) => values.iter().map(|v| TokenType::from_id(v.as_i64().unwrap_or(1))).collect(),
+            None => Vec::new(),
+        };
+
+        let mut tokenizer = Self::new(tokens, scores, types)?;
+        let vocab = tokenizer.tokens.len() as u64;
+        let id = |key: &str| file.get_u64(key).filter(|&id| id < vocab).map(|id| id as u32);
+        if let Some(bos) = id("tokenizer.ggml.bos_token_id") {
+            tokenizer.bos = bos;
+        }
+        if let Some(eos) = id("tokenizer.ggml.eos_token_id") {
+            tokenizer.eos = eos;
+        }
+        if let Some(unk) = id("tokenizer.ggml.unknown_token_id") {
+            tokenizer.unk = unk;
+        }
+        if let Some(add) = file.get("tokenizer.ggml.add_bos_token").and_then(MetaValue::as_bool) {
+            tokenizer.add_bos = add;
+        }
+        if let Some(add) = file.get("tokenizer.ggml.add_space_prefix").and_then(MetaValue::as_bool) {
+            tokenizer.add_space_prefix = add;
+        }
+        Ok(tokenizer)
+    }
+
+    pub fn vocab_size(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn bos(&self) -> u32 {
+        self.bos
+    }
+
+    pub fn eos(&self) -> u32 {
+        self.eos
+    }
+
+    pub fn add_bos(&self) -> bool {
+        self.add_bos
+    }
+
+    pub fn token(&self, id: u32) -> Option<&str> {
+        self.tokens.get(id as usize).map(String::as_str)
+    }
+
+    pub fn token_id(&self, piece: &str) -> Option<u32> {
+        self.index.get(piece).copied()
+    }
+
+    pub fn token_type(&self, id: u32) -> Option<TokenType> {
+        self.types.get(id as usize).copied()
+    }
+
+    /// Encode text, matching control tokens like `</s>` verbatim
+    pub fn encode(&self, text: &str, add_bos: bool) -> Vec<u32> {
+        let mut ids = Vec::new();
+        if add_bos {
+            ids.push(self.bos);
+        }
+
+        let mut rest = text;
+        let mut at_start = true;
+        while !rest.is_empty() {
+            let next_special = self
+                .specials
+                .iter()
+                .filter_map(|(piece, id)| rest.find(piece.as_str()).map(|pos| (pos, piece.len(), *id)))
+                // Earliest match wins, longest on ties (specials are sorted longest first)
+                .min_by_key(|&(pos, _, _)| pos);
+
+            let (fragment, special) = match next_special {
+                Some((pos, len, id)) => {
+                    let fragment = &rest[..pos];
+                    rest = &rest[pos + len..];
+                    (fragment, Some(id))
+                }
+                None => (std::mem::take(&mut rest), None),
+            };
+            if !fragment.is_empty() {
+                self.encode_fragment(fragment, at_start && self.add_space_prefix, &mut ids);
+            }
+            at_start = false;
+            ids.extend(special);
+        }
+        ids
+    }
+
+    fn encode_fragment(&self, text: &str, prefix: bool, ids: &mut Vec<u32>) {
+        let mut s = String::with_capacity(text.len() + 3);
+        if prefix {
+            s.push_str(SPACE);
+        }
+        s.push_str(&text.replace(' ', SPACE));
+
+        // One symbol per character, as byte ranges into `s`
+        let mut symbols: Vec<(usize, usize)> = s.char_indices().map(|(i, c)| (i, i + c.len_utf8())).collect();
+
+        loop {
+            let mut best: Option<(usize, f32)> = None;
+            for i in 0..symbols.len().saturating_sub(1) {
+                let piece = &s[symbols[i].0..symbols[i + 1].1];
+                if let Some(&id) = self.index.get(piece) {
+                    let score = self.scores[id as usize];
+                    if best.is_none_or(|(_, b)| score > b) {
+                        best = Some((i, score));
+                    }
+                }
+            }
+            let Some((i, _)) = best else { break };
+            symbols[i].1 = symbols[i + 1].1;
+            symbols.remove(i + 1);
+        }
+
+        for (start, end) in symbols {
+            let piece = &s[start..end];
+            match self.index.get(piece) {
+                Some(&id) => ids.push(id),
+                None => {
+                    for b in piece.bytes() {
+                        ids.push(self.byte_tokens[b as usize].unwrap_or(self.unk));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Raw bytes a token stands for (control tokens render as nothing)
+    pub fn piece_bytes(&self, id: u32) -> Vec<u8> {
+        let Some(token) = self.tokens.get(id as usize) else {
+            return Vec::new();
+        };
+        match self.types[id as usize] {
+            TokenType::Byte => parse_byte(token).map(|b| vec![b]).unwrap_or_default(),
+            TokenType::Control | TokenType::Unused => Vec::new(),
+            TokenType::Unknown => " \u{2047} ".as_bytes().to_vec(),
+            TokenType::Normal | TokenType::UserDefined => token.replace(SPACE, " ").into_bytes(),
+        }
+    }
+
+    /// Decode a token sequence
+    pub fn decode(&self, ids: &[u32]) -> String {
+        let mut decoder = self.decoder();
+        let mut out: String = ids.iter().map(|&id| decoder.push(id)).collect();
+        out.push_str(&decoder.finish());
+        out
+    }
+
+    /// Incremental decoder for streaming
+    pub fn decoder(&self) -> StreamDecoder<'_> {
+        StreamDecoder {
+            tokenizer: self,
+            pending: Vec::new(),
+            strip_space: self.add_space_prefix,
+        }
+    }
+}
+
+/// Turns tokens into text as they arrive
+///
+/// Holds back partial UTF-8 sequences split across byte tokens, and drops
+/// the space the encoder prefixed to the first word.
+pub struct StreamDecoder<'a> {
+    tokenizer: &'a Tokenizer,
+    pending: Vec<u8>,
+    strip_space: bool,
+}
+
+impl StreamDecoder<'_> {
+    /// Feed one token, returning whatever text is now complete
+    pub fn push(&mut self, id: u32) -> String {
+        let mut bytes = self.tokenizer.piece_bytes(id);
+        if self.strip_space && !bytes.is_empty() {
+            if bytes[0] == b' ' {
+                bytes.remove(0);
+            }
+            self.strip_space = false;
+        }
+        self.pending.extend(bytes);
+
+        let mut out = String::new();
+        loop {
+            match std::str::from_utf8(&self.pending) {
+                Ok(s) => {
+                    out.push_str(s);
+                    self.pending.clear();
+                    return out;
+                }
+                Err(e) => {
+                    let valid = e.valid_up_to();
+                    out.push_str(std::str::from_utf8(&self.pending[..valid]).unwrap_or_default());
+                    match e.error_len() {
+                        Some(bad) => {
+                            out.push(char::REPLACEMENT_CHARACTER);
+                            self.pending.drain(..valid + bad);
+                        }
+                        None => {
+                            // Incomplete sequence: wait for more bytes
+                            self.pending.drain(..valid);
+                            return out;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Flush any incomplete trailing bytes
+    pub fn finish(&mut self) -> String {
+        let out = String::from_utf8_lossy(&self.pending).into_owned();
+        self.pending.clear();
+        out
+    }
+}
+
+/// `<0x41>` → 0x41
+fn parse_byte(token: &str) -> Option<u8> {
+    let hex = token.strip_prefix("<0x")?.strip_suffix('>')?;
+    if hex.len() != 2 {
+        return None;
+    }
+    u8::from_str_radix(hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocab() -> Tokenizer {
+        let mut tokens: Vec<String> = vec!["<unk>".into(), "<s>".into(), "</s>".into()];
+        let mut types = vec![TokenType::Unknown, TokenType::Control, TokenType::Control];
+        for b in 0..=255u8 {
+            tokens.push(format!("<0x{:02X}>", b));
+            types.push(TokenType::Byte);
+        }
+        for piece in ["▁", "h", "e", "l", "o", "w", "r", "d", "▁h", "▁he", "▁hel", "▁hell", "▁hello", "▁w", "▁wo", "▁wor", "▁worl", "▁world", "ll", "lo"] {
+            tokens.push(piece.into());
+            types.push(TokenType::Normal);
+        }
+        let scores = (0..tokens.len()).map(|i| -(i as f32)).collect();
+        Tokenizer::new(tokens, scores, types).unwrap()
+    }
+
+    fn pieces(t: &Tokenizer, ids: &[u32]) -> Vec<String> {
+        ids.iter().map(|&id| t.token(id).unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn test_merges_words() {
+        let t = vocab();
+        let ids = t.encode("hello world", true);
+        assert_eq!(ids[0], t.bos());
+        assert_eq!(pieces(&t, &ids[1..]), vec!["▁hello", "▁world"]);
+        assert_eq!(t.decode(&ids), "hello world");
+    }
+
+    #[test]
+    fn test_byte_fallback() {
+        let t = vocab();
+        let ids = t.encode("hé!", false);
+        assert_eq!(pieces(&t, &ids), vec!["▁h", "<0xC3>", "<0xA9>", "<0x21>"]);
+        assert_eq!(t.decode(&ids), "hé!");
+    }
+
+    #[test]
+    fn test_control_tokens_split_text() {
+        let t = vocab();
+        let ids = t.encode("hello</s>world", false);
+        assert_eq!(pieces(&t, &ids), vec!["▁hello", "</s>", "w", "o", "r", "l", "d"]);
+        assert_eq!(t.decode(&ids), "helloworld");
+    }
+
+    #[test]
+    fn test_stream_decoder_holds_partial_utf8() {
+        let t = vocab();
+        let ids = t.encode("é", false);
+        let mut decoder = t.decoder();
+        let streamed: Vec<String> = ids.iter().map(|&id| decoder.push(id)).collect();
+        // "▁" decodes to a stripped space, then the two halves of "é"
+        assert_eq!(streamed.concat(), "é");
+        assert!(streamed.iter().any(String::is_empty));
+        assert_eq!(decoder.finish(), "");
+    }
+}
@@ -0,0 +1,490 @@
+//! LoRA Trainer
+//!
+//! Autograd-free CPU training of a single LoRA adapter.
+//!
+//! ```text
+//! x ──► W (frozen) ──────────────┐
+//!  │                             ▼
+//!  └──► A ──► B ──► × α/r ──►  (+) ──► softmax ──► CE(target)
+//!
+//! dL/dy = softmax(y) - p
+//! dB    = α/r · (Aᵀx) ⊗ dL/dy
+//! dA    = x ⊗ (α/r · B · dL/dy)
+//! ```
+//!
+//! The frozen layer is a base tensor (e.g. the local model's `output.weight`
+//! with hidden states as inputs and next tokens as targets), or zero when the
+//! chain has no matching tensor. Patterns are split into train / held-out
+//! sets with a seeded shuffle; AdamW steps on clipped mini-batch gradients
+//! and the best held-out checkpoint is kept.
+
+use crate::evolve::Pattern;
+use crate::lora::LoraWeights;
+use crate::tensor::Tensor;
+use crate::{Error, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Optimizer and schedule settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainConfig {
+    pub epochs: usize,
+    pub batch_size: usize,
+    pub learning_rate: f32,
+    pub weight_decay: f32,
+    pub beta1: f32,
+    pub beta2: f32,
+    pub eps: f32,
+    /// Global L2 norm gradients are clipped to (0 = off)
+    pub max_grad_norm: f32,
+    /// Fraction of patterns held out for validation
+    pub holdout: f32,
+    /// Epochs without held-out improvement before stopping (0 = never)
+    pub patience: usize,
+    pub seed: u64,
+}
+
+impl Default for TrainConfig {
+    fn default() -> Self {
+        Self {
+            epochs: 50,
+            batch_size: 16,
+            learning_rate: 1e-2,
+            weight_decay: 0.01,
+            beta1: 0.9,
+            beta2: 0.999,
+            eps: 1e-8,
+            max_grad_norm: 1.0,
+            holdout: 0.2,
+            patience: 5,
+            seed: 42,
+        }
+    }
+}
+
+/// What happened during a training run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrainReport {
+    /// Mean train loss per epoch
+    pub train_loss: Vec<f32>,
+    /// Held-out loss per epoch
+    pub val_loss: Vec<f32>,
+    /// Held-out loss of the untrained adapter (B = 0)
+    pub loss_before: f32,
+    /// Held-out loss of the returned adapter
+    pub loss_after: f32,
+    /// Epoch the returned adapter came from (0 = untrained)
+    pub best_epoch: usize,
+    pub stopped_early: bool,
+    pub train_size: usize,
+    pub val_size: usize,
+}
+
+/// Trains LoRA A/B for one linear layer
+pub struct LoraTrainer {
+    config: TrainConfig,
+}
+
+impl LoraTrainer {
+    pub fn new(config: TrainConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &TrainConfig {
+        &self.config
+    }
+
+    /// Fit an adapter of `rank` to `patterns`
+    ///
+    /// With a `base` tensor (`[out, in]`) inputs and targets are padded or
+    /// truncated to its shape; otherwise the longest pattern sets the shape.
+    pub fn train(
+        &self,
+        patterns: &[Pattern],
+        rank: usize,
+        alpha: f32,
+        base: Option<&Tensor>,
+    ) -> Result<(LoraWeights, TrainReport)> {
+        let cfg = &self.config;
+        if patterns.is_empty() {
+            return Err(Error::InferenceFailed("no patterns to train on".into()));
+        }
+        if rank == 0 {
+            return Err(Error::InferenceFailed("LoRA rank must be positive".into()));
+        }
+
+        let (n_out, n_in, w) = match base {
+            Some(t) => match *t.shape() {
+                [o, i] => (o, i, t.data().to_vec()),
+                _ => return Err(Error::Tensor(format!("base tensor must be 2-D, got {:?}", t.shape()))),
+            },
+            None => {
+                let i = patterns.iter().map(|p| p.input.len()).max().unwrap_or(0);
+                let o = patterns.iter().map(|p| p.target.len()).max().unwrap_or(0);
+                (o, i, Vec::new())
+            }
+        };
+        if n_in == 0 || n_out == 0 {
+            return Err(Error::InferenceFailed("patterns have empty inputs or targets".into()));
+        }
+
+        let layer = Layer { n_in, n_out, rank, scale: alpha / rank as f32, w };
+        let examples: Vec<Example> = patterns.iter().map(|p| layer.example(p)).collect();
+
+        // Seeded split
+        let mut rng = StdRng::seed_from_u64(cfg.seed);
+        let mut order: Vec<usize> = (0..examples.len()).collect();
+        order.shuffle(&mut rng);
+        let n_val = if examples.len() < 2 {
+            0
+        } else {
+            ((examples.len() as f32 * cfg.holdout).round() as usize).min(examples.len() - 1)
+        };
+        let (val_idx, train_idx) = order.split_at(n_val);
+        let mut train_idx = train_idx.to_vec();
+        // Without a held-out set, early stopping watches the train loss
+        let val_idx: Vec<usize> = if val_idx.is_empty() { train_idx.clone() } else { val_idx.to_vec() };
+
+        // Kaiming-uniform A, zero B: the adapter starts as a no-op
+        let bound = 1.0 / (n_in as f32).sqrt();
+        let mut params = Params {
+            a: (0..n_in * rank).map(|_| rng.gen_range(-bound..bound)).collect(),
+            b: vec![0.0; rank * n_out],
+        };
+        let mut opt = AdamW::new(params.a.len(), params.b.len());
+
+        let mut report = TrainReport {
+            train_size: train_idx.len(),
+            val_size: n_val,
+            ..Default::default()
+        };
+        report.loss_before = layer.mean_loss(&examples, &val_idx, &params);
+        report.loss_after = report.loss_before;
+        let mut best = params.clone();
+        let mut since_best = 0;
+
+        for epoch in 1..=cfg.epochs {
+            train_idx.shuffle(&mut rng);
+            let mut epoch_loss = 0.0;
+            for batch in train_idx.chunks(cfg.batch_size.max(1)) {
+                let mut grads = Params { a: vec![0.0; params.a.len()], b: vec![0.0; params.b.len()] };
+                for &i in batch {
+                    epoch_loss += layer.accumulate(&examples[i], &params, &mut grads);
+                }
+                grads.scale(1.0 / batch.len() as f32);
+                if cfg.max_grad_norm > 0.0 {
+                    grads.clip(cfg.max_grad_norm);
+                }
+                opt.step(cfg, &mut params, &grads);
+            }
+            report.train_loss.push(epoch_loss / train_idx.len() as f32);
+
+            let val = layer.mean_loss(&examples, &val_idx, &params);
+            report.val_loss.push(val);
+            if val < report.loss_after {
+                report.loss_after = val;
+                report.best_epoch = epoch;
+                best = params.clone();
+                since_best = 0;
+            } else {
+                since_best += 1;
+                if cfg.patience > 0 && since_best >= cfg.patience {
+                    report.stopped_early = true;
+                    break;
+                }
+            }
+        }
+
+        let weights = LoraWeights {
+            a: best.a,
+            b: best.b,
+            shape_a: (n_in, rank),
+            shape_b: (rank, n_out),
+            target: None,
+        };
+        Ok((weights, report))
+    }
+
+    /// Mean cross-entropy of `patterns` through base + adapter
+    pub fn evaluate(&self, patterns: &[Pattern], weights: &LoraWeights, alpha: f32, base: Option<&Tensor>) -> Result<f32> {
+        weights.validate()?;
+        let (n_in, rank, n_out) = (weights.in_features(), weights.rank(), weights.out_features());
+        let w = match base {
+            Some(t) if t.shape() == [n_out, n_in] => t.data().to_vec(),
+            Some(t) => return Err(Error::Tensor(format!("base {:?} doesn't match adapter {}→{}", t.shape(), n_in, n_out))),
+            None => Vec::new(),
+        };
+        let layer = Layer { n_in, n_out, rank, scale: alpha / rank.max(1) as f32, w };
+        let examples: Vec<Example> = patterns.iter().map(|p| layer.example(p)).collect();
+        let params = Params { a: weights.a.clone(), b: weights.b.clone() };
+        Ok(layer.mean_loss(&examples, &(0..examples.len()).collect::<Vec<_>>(), &params))
+    }
+}
+
+impl Default for LoraTrainer {
+    fn default() -> Self {
+        Self::new(TrainConfig::default())
+    }
+}
+
+/// Target vector as a distribution: normalised if it already is a
+/// non-negative weighting, softmax otherwise
+pub fn target_distribution(target: &[f32]) -> Vec<f32> {
+    let sum: f32 = target.iter().sum();
+    if sum > 0.0 && target.iter().all(|&t| t >= 0.0) {
+        target.iter().map(|t| t / sum).collect()
+    } else {
+        softmax(target)
+    }
+}
+
+/// Cross-entropy of `logits` against distribution `p`
+pub fn cross_entropy(logits: &[f32], p: &[f32]) -> f32 {
+    let max = logits.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let log_z = max + logits.iter().map(|l| (l - max).exp()).sum::<f32>().ln();
+    logits.iter().zip(p).map(|(l, p)| p * (log_z - l)).sum()
+}
+
+fn softmax(x: &[f32]) -> Vec<f32> {
+    let max = x.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let exps: Vec<f32> = x.iter().map(|v| (v - max).exp()).collect();
+    let sum: f32 = exps.iter().sum();
+    exps.iter().map(|e| e / sum).collect()
+}
+
+struct Example {
+    x: Vec<f32>,
+    p: Vec<f32>,
+}
+
+#[derive(Clone)]
+struct Params {
+    a: Vec<f32>, // [in, rank]
+    b: Vec<f32>, // [rank, out]
+}
+
+impl Params {
+    fn scale(&mut self, s: f32) {
+        self.a.iter_mut().chain(self.b.iter_mut()).for_each(|g| *g *= s);
+    }
+
+    fn clip(&mut self, max_norm: f32) {
+        let norm = self.a.iter().chain(&self.b).map(|g| g * g).sum::<f32>().sqrt();
+        if norm > max_norm {
+            self.scale(max_norm / norm);
+        }
+    }
+}
+
+/// Frozen `[out, in]` layer (empty `w` = zero) plus the adapter scale
+struct Layer {
+    n_in: usize,
+    n_out: usize,
+    rank: usize,
+    scale: f32,
+    w: Vec<f32>,
+}
+
+impl Layer {
+    fn example(&self, pattern: &Pattern) -> Example {
+        let mut x = pattern.input.clone();
+        x.resize(self.n_in, 0.0);
+        let mut target = pattern.target.clone();
+        target.resize(self.n_out, 0.0);
+        Example { x, p: target_distribution(&target) }
+    }
+
+    /// Logits and the rank-space activation h = Aᵀx
+    fn forward(&self, x: &[f32], params: &Params) -> (Vec<f32>, Vec<f32>) {
+        let r = self.rank;
+        let mut h = vec![0.0f32; r];
+        for (i, &xi) in x.iter().enumerate() {
+            if xi != 0.0 {
+                for (k, hk) in h.iter_mut().enumerate() {
+                    *hk += xi * params.a[i * r + k];
+                }
+            }
+        }
+
+        let mut y = vec![0.0f32; self.n_out];
+        if !self.w.is_empty() {
+            for (o, yo) in y.iter_mut().enumerate() {
+                let row = &self.w[o * self.n_in..(o + 1) * self.n_in];
+                *yo = row.iter().zip(x).map(|(w, x)| w * x).sum();
+            }
+        }
+        for (k, &hk) in h.iter().enumerate() {
+            let b_row = &params.b[k * self.n_out..(k + 1) * self.n_out];
+            for (yo, &b) in y.iter_mut().zip(b_row) {
+                *yo += self.scale * hk * b;
+            }
+        }
+        (y, h)
+    }
+
+    /// Add this example's gradients to `grads`, returning its loss
+    fn accumulate(&self, ex: &Example, params: &Params, grads: &mut Params) -> f32 {
+        let (r, n_out) = (self.rank, self.n_out);
+        let (y, h) = self.forward(&ex.x, params);
+        let loss = cross_entropy(&y, &ex.p);
+        let g: Vec<f32> = softmax(&y).iter().zip(&ex.p).map(|(q, p)| q - p).collect();
+
+        let mut dh = vec![0.0f32; r];
+        for k in 0..r {
+            let b_row = &params.b[k * n_out..(k + 1) * n_out];
+            let gb_row = &mut grads.b[k * n_out..(k + 1) * n_out];
+            for o in 0..n_out {
+                gb_row[o] += self.scale * h[k] * g[o];
+                dh[k] += self.scale * b_row[o] * g[o];
+            }
+        }
+        for (i, &xi) in ex.x.iter().enumerate() {
+            if xi != 0.0 {
+                for (ga, d) in grads.a[i * r..(i + 1) * r].iter_mut().zip(&dh) {
+                    *ga += xi * d;
+                }
+            }
+        }
+        loss
+    }
+
+    fn mean_loss(&self, examples: &[Example], idx: &[usize], params: &Params) -> f32 {
+        if idx.is_empty() {
+            return 0.0;
+        }
+        let total: f32 = idx
+            .iter()
+            .map(|&i| cross_entropy(&self.forward(&examples[i].x, params).0, &examples[i].p))
+            .sum();
+        total / idx.len() as f32
+    }
+}
+
+/// Adam with decoupled weight decay
+struct AdamW {
+    step: i32,
+    m: Params,
+    v: Params,
+}
+
+impl AdamW {
+    fn new(n_a: usize, n_b: usize) -> Self {
+        let zeros = Params { a: vec![0.0; n_a], b: vec![0.0; n_b] };
+        Self { step: 0, m: zeros.clone(), v: zeros }
+    }
+
+    fn step(&mut self, cfg: &TrainConfig, params: &mut Params, grads: &Params) {
+        self.step += 1;
+        let c1 = 1.0 - cfg.beta1.powi(self.step);
+        let c2 = 1.0 - cfg.beta2.powi(self.step);
+        let pairs = [
+            (&mut params.a, &grads.a, &mut self.m.a, &mut self.v.a),
+            (&mut params.b, &grads.b, &mut self.m.b, &mut self.v.b),
+        ];
+        for (p, g, m, v) in pairs {
+            for j in 0..p.len() {
+                m[j] = cfg.beta1 * m[j] + (1.0 - cfg.beta1) * g[j];
+                v[j] = cfg.beta2 * v[j] + (1.0 - cfg.beta2) * g[j] * g[j];
+                let update = (m[j] / c1) / ((v[j] / c2).sqrt() + cfg.eps);
+                p[j] -= cfg.learning_rate * (update + cfg.weight_decay * p[j]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Toy task: the class is the index of the largest of 4 features
+    fn toy(n: usize, seed: u64) -> Vec<Pattern> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n)
+            .map(|_| {
+                let input: Vec<f32> = (0..8).map(|_| rng.gen_range(-1.0..1.0)).collect();
+                let best = (0..4).max_by(|&a, &b| input[a].total_cmp(&input[b])).unwrap();
+                let mut target = vec![0.0; 4];
+                target[best] = 1.0;
+                Pattern { input, target, loss: 0.0, source: "toy".into() }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_loss_decreases() {
+        let trainer = LoraTrainer::new(TrainConfig { epochs: 40, patience: 0, ..Default::default() });
+        let (weights, report) = trainer.train(&toy(200, 1), 4, 8.0, None).unwrap();
+
+        assert_eq!(report.train_size + report.val_size, 200);
+        // Untrained adapter on a zero base: uniform over 4 classes
+        assert!((report.loss_before - 4f32.ln()).abs() < 1e-5);
+        assert!(report.loss_after < report.loss_before * 0.6, "{:?}", report);
+        assert!(report.train_loss.last().unwrap() < &report.train_loss[0]);
+        assert_eq!(weights.shape_a, (8, 4));
+        assert_eq!(weights.shape_b, (4, 4));
+
+        // Generalises to fresh data
+        let fresh = trainer.evaluate(&toy(100, 2), &weights, 8.0, None).unwrap();
+        assert!(fresh < report.loss_before * 0.7, "{}", fresh);
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let trainer = LoraTrainer::new(TrainConfig { epochs: 5, ..Default::default() });
+        let data = toy(50, 3);
+        let (w1, r1) = trainer.train(&data, 2, 4.0, None).unwrap();
+        let (w2, r2) = trainer.train(&data, 2, 4.0, None).unwrap();
+        assert_eq!(w1.a, w2.a);
+        assert_eq!(w1.b, w2.b);
+        assert_eq!(r1.val_loss, r2.val_loss);
+
+        let other = LoraTrainer::new(TrainConfig { epochs: 5, seed: 7, ..Default::default() });
+        assert_ne!(other.train(&data, 2, 4.0, None).unwrap().0.a, w1.a);
+    }
+
+    #[test]
+    fn test_early_stopping_keeps_best() {
+        // Random labels: held-out loss can't improve for long
+        let mut data = toy(60, 4);
+        let mut rng = StdRng::seed_from_u64(9);
+        for p in &mut data {
+            p.target = vec![0.0; 4];
+            p.target[rng.gen_range(0..4)] = 1.0;
+        }
+        let trainer = LoraTrainer::new(TrainConfig { epochs: 200, learning_rate: 0.05, patience: 3, ..Default::default() });
+        let (_, report) = trainer.train(&data, 4, 8.0, None).unwrap();
+
+        assert!(report.stopped_early);
+        assert!(report.val_loss.len() < 200);
+        assert!(report.loss_after <= report.loss_before);
+        let min = report.val_loss.iter().cloned().fold(report.loss_before, f32::min);
+        assert_eq!(report.loss_after, min);
+    }
+
+    #[test]
+    fn test_gradients_match_finite_differences() {
+        let layer = Layer { n_in: 3, n_out: 2, rank: 2, scale: 1.5, w: vec![0.1, -0.2, 0.3, 0.0, 0.5, -0.4] };
+        let ex = Example { x: vec![0.5, -1.0, 2.0], p: vec![0.25, 0.75] };
+        let params = Params { a: vec![0.1, 0.2, -0.3, 0.4, 0.05, -0.1], b: vec![0.3, -0.2, 0.1, 0.6] };
+
+        let mut grads = Params { a: vec![0.0; 6], b: vec![0.0; 4] };
+        layer.accumulate(&ex, &params, &mut grads);
+
+        let loss = |p: &Params| cross_entropy(&layer.forward(&ex.x, p).0, &ex.p);
+        let h = 1e-3;
+        for j in 0..6 {
+            let (mut up, mut down) = (params.clone(), params.clone());
+            up.a[j] += h;
+            down.a[j] -= h;
+            assert!(((loss(&up) - loss(&down)) / (2.0 * h) - grads.a[j]).abs() < 1e-3);
+        }
+        for j in 0..4 {
+            let (mut up, mut down) = (params.clone(), params.clone());
+            up.b[j] += h;
+            down.b[j] -= h;
+            assert!(((loss(&up) - loss(&down)) / (2.0 * h) - grads.b[j]).abs() < 1e-3);
+        }
+    }
+}
@@ -0,0 +1,722 @@
+//! Llama Transformer
+//!
+//! CPU forward pass over quantized GGUF weights, one token at a time.
+//!
+//! ```text
+//! token ─► embd ─┬─► RMSNorm ─► Q K V ─► RoPE ─► attn(KV cache) ─► O ─(+)─┐
+//!                │                                                      │
+//!                └───────────────────────── residual ───────────────────┘
+//!                ┌─► RMSNorm ─► silu(gate) × up ─► down ─(+)─► … × n_layers
+//!                └───────────── residual ──────────────┘
+//!                                                   ─► RMSNorm ─► logits
+//! ```
+//!
+//! Weights stay in their GGUF blocks (Q4/Q8/K-quants); each matrix-vector
+//! product dequantizes a row at a time, split across threads for big layers.
+
+use crate::gguf::{dequantize, dot_row, GgmlType, GgufFile, TensorInfo};
+use crate::{Error, Result};
+use std::path::Path;
+
+/// Matrices smaller than this (in elements) run on the calling thread
+const PARALLEL_THRESHOLD: usize = 1 << 18;
+
+/// Longest context window accepted from metadata
+const MAX_CONTEXT_LENGTH: usize = 1 << 20;
+
+/// Hyperparameters read from GGUF metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlamaConfig {
+    pub vocab_size: usize,
+    pub dim: usize,
+    pub hidden_dim: usize,
+    pub n_layers: usize,
+    pub n_heads: usize,
+    pub n_kv_heads: usize,
+    pub context_length: usize,
+    pub rms_eps: f32,
+    pub rope_base: f32,
+}
+
+impl LlamaConfig {
+    /// Architectures sharing the Llama tensor layout
+    pub const ARCHITECTURES: &'static [&'static str] = &["llama", "mistral"];
+
+    pub fn from_gguf(file: &GgufFile) -> Result<Self> {
+        let arch = file.architecture().unwrap_or("llama");
+        if !Self::ARCHITECTURES.contains(&arch) {
+            return Err(Error::InvalidModel(format!("unsupported architecture '{}'", arch)));
+        }
+        let need = |key: &str| {
+            file.get_u64(&format!("{}.{}", arch, key))
+                .and_then(|v| usize::try_from(v).ok())
+                .ok_or_else(|| Error::InvalidModel(format!("missing {}.{}", arch, key)))
+        };
+
+        let dim = need("embedding_length")?;
+        let n_heads = need("attention.head_count")?;
+        let n_kv_heads = need("attention.head_count_kv").unwrap_or(n_heads);
+        let vocab_size = find(file, "token_embd.weight")?.rows();
+
+        let config = Self {
+            vocab_size,
+            dim,
+            hidden_dim: need("feed_forward_length")?,
+            n_layers: need("block_count")?,
+            n_heads,
+            n_kv_heads,
+            context_length: need("context_length").unwrap_or(2048),
+            rms_eps: file.get_f32(&format!("{}.attention.layer_norm_rms_epsilon", arch)).unwrap_or(1e-5),
+            rope_base: file.get_f32(&format!("{}.rope.freq_base", arch)).unwrap_or(10000.0),
+        };
+        if dim == 0 || n_heads == 0 || n_kv_heads == 0 || dim % n_heads != 0 || n_heads % n_kv_heads != 0 {
+            return Err(Error::InvalidModel(format!(
+                "bad head layout: dim {} with {} heads / {} kv heads",
+                dim, n_heads, n_kv_heads
+            )));
+        }
+        config.check_tensors(file)?;
+        Ok(config)
+    }
+
+    /// Sizes must match the tensors in the file, which bounds every
+    /// allocation by the file size
+    fn check_tensors(&self, file: &GgufFile) -> Result<()> {
+        let mismatch = |what: &str, claimed: usize, found: usize| {
+            Err(Error::InvalidModel(format!("{} is {} but the tensors say {}", what, claimed, found)))
+        };
+
+        let embd = find(file, "token_embd.weight")?;
+        if embd.cols() != self.dim {
+            return mismatch("embedding_length", self.dim, embd.cols());
+        }
+        let blocks = (0usize..)
+            .take_while(|i| file.tensor(&format!("blk.{}.attn_norm.weight", i)).is_some())
+            .count();
+        if blocks == 0 || self.n_layers != blocks {
+            return mismatch("block_count", self.n_layers, blocks);
+        }
+        let ffn_up = find(file, "blk.0.ffn_up.weight")?;
+        if ffn_up.rows() != self.hidden_dim {
+            return mismatch("feed_forward_length", self.hidden_dim, ffn_up.rows());
+        }
+        if self.context_length == 0 || self.context_length > MAX_CONTEXT_LENGTH {
+            return Err(Error::InvalidModel(format!(
+                "context_length {} outside 1..={}",
+                self.context_length, MAX_CONTEXT_LENGTH
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn head_dim(&self) -> usize {
+        self.dim / self.n_heads
+    }
+
+    pub fn kv_dim(&self) -> usize {
+        self.head_dim() * self.n_kv_heads
+    }
+}
+
+/// A weight matrix left in its GGUF encoding
+#[derive(Debug, Clone)]
+struct QMatrix {
+    dtype: GgmlType,
+    rows: usize,
+    cols: usize,
+    offset: usize,
+    row_bytes: usize,
+}
+
+struct Layer {
+    attn_norm: Vec<f32>,
+    wq: QMatrix,
+    wk: QMatrix,
+    wv: QMatrix,
+    wo: QMatrix,
+    ffn_norm: Vec<f32>,
+    w_gate: QMatrix,
+    w_up: QMatrix,
+    w_down: QMatrix,
+}
+
+/// Keys and values of every position seen so far, per layer
+#[derive(Debug, Clone)]
+pub struct KvCache {
+    k: Vec<Vec<f32>>,
+    v: Vec<Vec<f32>>,
+    kv_dim: usize,
+    len: usize,
+    capacity: usize,
+}
+
+impl KvCache {
+    pub fn new(config: &LlamaConfig, capacity: usize) -> Result<Self> {
+        let too_big = || Error::InferenceFailed(format!("KV cache of {} positions does not fit in memory", capacity));
+        let size = capacity.checked_mul(config.kv_dim()).ok_or_else(too_big)?;
+        size.checked_mul(config.n_layers * 2 * std::mem::size_of::<f32>())
+            .filter(|&bytes| bytes <= isize::MAX as usize)
+            .ok_or_else(too_big)?;
+
+        let layer = || -> Result<Vec<f32>> {
+            let mut values = Vec::new();
+            values.try_reserve_exact(size).map_err(|_| too_big())?;
+            values.resize(size, 0.0);
+            Ok(values)
+        };
+        Ok(Self {
+            k: (0..config.n_layers).map(|_| layer()).collect::<Result<_>>()?,
+            v: (0..config.n_layers).map(|_| layer()).collect::<Result<_>>()?,
+            kv_dim: config.kv_dim(),
+            len: 0,
+            capacity,
+        })
+    }
+
+    /// Positions filled
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len >= self.capacity
+    }
+
+    /// Forget everything from position `len` on (keeps a shared prefix)
+    pub fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+/// Loaded Llama-family model
+pub struct LlamaModel {
+    config: LlamaConfig,
+    file: GgufFile,
+    token_embd: QMatrix,
+    layers: Vec<Layer>,
+    output_norm: Vec<f32>,
+    output: QMatrix,
+    threads: usize,
+}
+
+impl LlamaModel {
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_gguf(GgufFile::open(path)?)
+    }
+
+    pub fn from_gguf(file: GgufFile) -> Result<Self> {
+        let c = LlamaConfig::from_gguf(&file)?;
+
+        let matrix = |name: &str, cols: usize, rows: usize| -> Result<QMatrix> {
+            let info = find(&file, name)?;
+            if info.cols() != cols || info.rows() != rows {
+                return Err(Error::InvalidModel(format!(
+                    "{} is {}x{}, expected {}x{}",
+                    name,
+                    info.rows(),
+                    info.cols(),
+                    rows,
+                    cols
+                )));
+            }
+            Ok(QMatrix {
+                dtype: info.dtype,
+                rows,
+                cols,
+                offset: info.offset as usize,
+                row_bytes: info.dtype.bytes_for(cols)?,
+            })
+        };
+        let vector = |name: &str, len: usize| -> Result<Vec<f32>> {
+            let v = file.tensor_f32(name)?;
+            if v.len() != len {
+                return Err(Error::InvalidModel(format!("{} has {} values, expected {}", name, v.len(), len)));
+            }
+            Ok(v)
+        };
+
+        let token_embd = matrix("token_embd.weight", c.dim, c.vocab_size)?;
+        let mut layers = Vec::with_capacity(c.n_layers);
+        for i in 0..c.n_layers {
+            let name = |t: &str| format!("blk.{}.{}.weight", i, t);
+            layers.push(Layer {
+                attn_norm: vector(&name("attn_norm"), c.dim)?,
+                wq: matrix(&name("attn_q"), c.dim, c.dim)?,
+                wk: matrix(&name("attn_k"), c.dim, c.kv_dim())?,
+                wv: matrix(&name("attn_v"), c.dim, c.kv_dim())?,
+                wo: matrix(&name("attn_output"), c.dim, c.dim)?,
+                ffn_norm: vector(&name("ffn_norm"), c.dim)?,
+                w_gate: matrix(&name("ffn_gate"), c.dim, c.hidden_dim)?,
+                w_up: matrix(&name("ffn_up"), c.dim, c.hidden_dim)?,
+                w_down: matrix(&name("ffn_down"), c.hidden_dim, c.dim)?,
+            });
+        }
+        let output_norm = vector("output_norm.weight", c.dim)?;
+        // Small models tie the output projection to the embeddings
+        let output = match file.tensor("output.weight") {
+            Some(_) => matrix("output.weight", c.dim, c.vocab_size)?,
+            None => token_embd.clone(),
+        };
+
+        let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        Ok(Self { config: c, file, token_embd, layers, output_norm, output, threads })
+    }
+
+    pub fn config(&self) -> &LlamaConfig {
+        &self.config
+    }
+
+    /// The underlying GGUF file (metadata, tokenizer vocab)
+    pub fn gguf(&self) -> &GgufFile {
+        &self.file
+    }
+
+    /// Empty cache sized for `capacity` positions (capped at the model's context)
+    pub fn new_cache(&self, capacity: usize) -> Result<KvCache> {
+        KvCache::new(&self.config, capacity.min(self.config.context_length))
+    }
+
+    /// Run one token at the next cache position, returning logits
+    pub fn forward(&self, token: u32, cache: &mut KvCache) -> Result<Vec<f32>> {
+        let c = &self.config;
+        if token as usize >= c.vocab_size {
+            return Err(Error::InferenceFailed(format!("token {} outside vocabulary", token)));
+        }
+        if cache.is_full() {
+            return Err(Error::InferenceFailed(format!("context window of {} tokens is full", cache.capacity)));
+        }
+        let pos = cache.len;
+        let (head_dim, kv_dim) = (c.head_dim(), c.kv_dim());
+        let group = c.n_heads / c.n_kv_heads;
+
+        let mut x = vec![0.0; c.dim];
+        let row = &self.data(&self.token_embd)[token as usize * self.token_embd.row_bytes..][..self.token_embd.row_bytes];
+        dequantize(self.token_embd.dtype, row, &mut x);
+
+        let mut xb = vec![0.0; c.dim];
+        let mut q = vec![0.0; c.dim];
+        let mut attn = vec![0.0; c.dim];
+        let mut proj = vec![0.0; c.dim];
+        let mut gate = vec![0.0; c.hidden_dim];
+        let mut up = vec![0.0; c.hidden_dim];
+        let mut scores = vec![0.0; pos + 1];
+
+        for (l, layer) in self.layers.iter().enumerate() {
+            rms_norm(&x, &layer.attn_norm, c.rms_eps, &mut xb);
+
+            let k = &mut cache.k[l][pos * kv_dim..(pos + 1) * kv_dim];
+            let v = &mut cache.v[l][pos * kv_dim..(pos + 1) * kv_dim];
+            self.matvec(&layer.wq, &xb, &mut q);
+            self.matvec(&layer.wk, &xb, k);
+            self.matvec(&layer.wv, &xb, v);
+            rope(&mut q, pos, head_dim, c.rope_base);
+            rope(k, pos, head_dim, c.rope_base);
+
+            let keys = &cache.k[l];
+            let values = &cache.v[l];
+            let scale = 1.0 / (head_dim as f32).sqrt();
+            for h in 0..c.n_heads {
+                let qh = &q[h * head_dim..(h + 1) * head_dim];
+                let kv_off = (h / group) * head_dim;
+                for (t, s) in scores.iter_mut().enumerate() {
+                    let kt = &keys[t * kv_dim + kv_off..][..head_dim];
+                    *s = qh.iter().zip(kt).map(|(a, b)| a * b).sum::<f32>() * scale;
+                }
+                softmax(&mut scores);
+
+                let out = &mut attn[h * head_dim..(h + 1) * head_dim];
+                out.fill(0.0);
+                for (t, &p) in scores.iter().enumerate() {
+                    let vt = &values[t * kv_dim + kv_off..][..head_dim];
+                    out.iter_mut().zip(vt).for_each(|(o, v)| *o += p * v);
+                }
+            }
+            self.matvec(&layer.wo, &attn, &mut proj);
+            x.iter_mut().zip(&proj).for_each(|(x, p)| *x += p);
+
+            rms_norm(&x, &layer.ffn_norm, c.rms_eps, &mut xb);
+            self.matvec(&layer.w_gate, &xb, &mut gate);
+            self.matvec(&layer.w_up, &xb, &mut up);
+            gate.iter_mut().zip(&up).for_each(|(g, u)| *g = silu(*g) * u);
+            self.matvec(&layer.w_down, &gate, &mut proj);
+            x.iter_mut().zip(&proj).for_each(|(x, p)| *x += p);
+        }
+
+        rms_norm(&x, &self.output_norm, c.rms_eps, &mut xb);
+        let mut logits = vec![0.0; c.vocab_size];
+        self.matvec(&self.output, &xb, &mut logits);
+
+        cache.len += 1;
+        Ok(logits)
+    }
+
+    /// Feed a run of tokens, returning the logits after the last one
+    pub fn prefill(&self, tokens: &[u32], cache: &mut KvCache) -> Result<Vec<f32>> {
+        let mut logits = Vec::new();
+        for &token in tokens {
+            logits = self.forward(token, cache)?;
+        }
+        if logits.is_empty() {
+            return Err(Error::InferenceFailed("nothing to prefill".into()));
+        }
+        Ok(logits)
+    }
+
+    fn data(&self, m: &QMatrix) -> &[u8] {
+        &self.file.data_section()[m.offset..m.offset + m.rows * m.row_bytes]
+    }
+
+    fn matvec(&self, m: &QMatrix, x: &[f32], out: &mut [f32]) {
+        let data = self.data(m);
+        let row = |r: usize| &data[r * m.row_bytes..(r + 1) * m.row_bytes];
+
+        if self.threads <= 1 || m.rows * m.cols < PARALLEL_THRESHOLD {
+            for (r, o) in out.iter_mut().enumerate() {
+                *o = dot_row(m.dtype, row(r), x);
+            }
+            return;
+        }
+
+        let chunk = m.rows.div_ceil(self.threads);
+        std::thread::scope(|s| {
+            for (i, part) in out.chunks_mut(chunk).enumerate() {
+                s.spawn(move || {
+                    for (j, o) in part.iter_mut().enumerate() {
+                        *o = dot_row(m.dtype, row(i * chunk + j), x);
+                    }
+                });
+            }
+        });
+    }
+}
+
+fn find<'a>(file: &'a GgufFile, name: &str) -> Result<&'a TensorInfo> {
+    file.tensor(name).ok_or_else(|| Error::InvalidModel(format!("missing tensor {}", name)))
+}
+
+fn rms_norm(x: &[f32], weight: &[f32], eps: f32, out: &mut [f32]) {
+    let ms = x.iter().map(|v| v * v).sum::<f32>() / x.len() as f32;
+    let scale = 1.0 / (ms + eps).sqrt();
+    for ((o, x), w) in out.iter_mut().zip(x).zip(weight) {
+        *o = x * scale * w;
+    }
+}
+
+/// Rotate adjacent pairs within each head (GGUF llama layout)
+fn rope(x: &mut [f32], pos: usize, head_dim: usize, base: f32) {
+    for head in x.chunks_exact_mut(head_dim) {
+        for i in (0..head_dim).step_by(2) {
+            let theta = pos as f32 * base.powf(-(i as f32) / head_dim as f32);
+            let (sin, cos) = theta.sin_cos();
+            let (a, b) = (head[i], head[i + 1]);
+            head[i] = a * cos - b * sin;
+            head[i + 1] = a * sin + b * cos;
+        }
+    }
+}
+
+fn softmax(x: &mut [f32]) {
+    let max = x.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let mut sum = 0.0;
+    for v in x.iter_mut() {
+        *v = (*v - max).exp();
+        sum += *v;
+    }
+    x.iter_mut().for_each(|v| *v /= sum);
+}
+
+fn silu(x: f32) -> f32 {
+    x / (1.0 + (-x).exp())
+}
+
+/// Tiny random-weight Llama checked in as `testdata/tiny-llama.gguf`
+#[cfg(test)]
+pub(crate) mod fixture {
+    use crate::gguf::{GgmlType, GgufWriter, MetaValue};
+    use crate::tokenizer::TokenType;
+    use std::path::PathBuf;
+
+    pub const DIM: usize = 32;
+    pub const HIDDEN: usize = 64;
+    pub const LAYERS: usize = 2;
+    pub const HEADS: usize = 4;
+    pub const KV_HEADS: usize = 2;
+    pub const CONTEXT: usize = 64;
+
+    const WORDS: &[&str] = &["the", "hello", "world", "gently", "brain", "feed", "code", "rust"];
+
+    pub fn path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/tiny-llama.gguf")
+    }
+
+    /// `<unk> <s> </s>`, 256 byte tokens, letters, then word prefixes
+    pub fn vocab() -> (Vec<String>, Vec<TokenType>) {
+        let mut tokens: Vec<String> = vec!["<unk>".into(), "<s>".into(), "</s>".into()];
+        let mut types = vec![TokenType::Unknown, TokenType::Control, TokenType::Control];
+        for b in 0..=255u8 {
+            tokens.push(format!("<0x{:02X}>", b));
+            types.push(TokenType::Byte);
+        }
+        let mut pieces: Vec<String> = vec!["▁".into()];
+        pieces.extend(('a'..='z').map(String::from));
+        pieces.extend([",", ".", "!", "?", "'"].map(String::from));
+        for word in WORDS {
+            let marked = format!("▁{}", word);
+            for end in marked.char_indices().map(|(i, c)| i + c.len_utf8()).skip(1) {
+                pieces.push(marked[..end].to_string());
+            }
+        }
+        for piece in pieces {
+            if !tokens.contains(&piece) {
+                tokens.push(piece);
+                types.push(TokenType::Normal);
+            }
+        }
+        (tokens, types)
+    }
+
+    /// xorshift so the bytes never depend on an external RNG's stream
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> f32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+        }
+
+        fn fill(&mut self, n: usize, scale: f32) -> Vec<f32> {
+            (0..n).map(|_| self.next() * scale).collect()
+        }
+    }
+
+    pub fn build() -> Vec<u8> {
+        build_with(&[])
+    }
+
+    /// Fixture with `overrides` appended to the metadata (later keys win)
+    pub fn build_with(overrides: &[(&str, MetaValue)]) -> Vec<u8> {
+        let (tokens, types) = vocab();
+        let vocab = tokens.len();
+        let kv_dim = DIM / HEADS * KV_HEADS;
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        let u32v = |v: usize| MetaValue::U32(v as u32);
+
+        let mut w = GgufWriter::new()
+            .metadata("general.architecture", MetaValue::String("llama".into()))
+            .metadata("general.name", MetaValue::String("tiny-llama-fixture".into()))
+            .metadata("llama.context_length", u32v(CONTEXT))
+            .metadata("llama.embedding_length", u32v(DIM))
+            .metadata("llama.block_count", u32v(LAYERS))
+            .metadata("llama.feed_forward_length", u32v(HIDDEN))
+            .metadata("llama.attention.head_count", u32v(HEADS))
+            .metadata("llama.attention.head_count_kv", u32v(KV_HEADS))
+            .metadata("llama.attention.layer_norm_rms_epsilon", MetaValue::F32(1e-5))
+            .metadata("llama.rope.freq_base", MetaValue::F32(10000.0))
+            .metadata("tokenizer.ggml.model", MetaValue::String("llama".into()))
+            .metadata(
+                "tokenizer.ggml.tokens",
+                MetaValue::Array(tokens.iter().cloned().map(MetaValue::String).collect()),
+            )
+            .metadata(
+                "tokenizer.ggml.scores",
+                MetaValue::Array((0..vocab).map(|i| MetaValue::F32(-(i as f32))).collect()),
+            )
+            .metadata(
+                "tokenizer.ggml.token_type",
+                MetaValue::Array(types.iter().map(|t| MetaValue::I32(t.id())).collect()),
+            )
+            .metadata("tokenizer.ggml.bos_token_id", MetaValue::U32(1))
+            .metadata("tokenizer.ggml.eos_token_id", MetaValue::U32(2))
+            .metadata("tokenizer.ggml.unknown_token_id", MetaValue::U32(0))
+            .metadata("tokenizer.ggml.add_bos_token", MetaValue::Bool(true));
+        for (key, value) in overrides {
+            w = w.metadata(key, value.clone());
+        }
+
+        let mut add = |w: GgufWriter, name: &str, cols: usize, rows: usize, dtype: GgmlType, scale: f32| {
+            let values = rng.fill(cols * rows, scale);
+            let dims: Vec<u64> = if rows == 1 { vec![cols as u64] } else { vec![cols as u64, rows as u64] };
+            w.tensor_f32(name, &dims, dtype, &values).unwrap()
+        };
+
+        w = add(w, "token_embd.weight", DIM, vocab, GgmlType::Q8_0, 2.0);
+        for i in 0..LAYERS {
+            let n = |t: &str| format!("blk.{}.{}.weight", i, t);
+            w = w.tensor_f32(&n("attn_norm"), &[DIM as u64], GgmlType::F32, &[1.0; DIM]).unwrap();
+            w = add(w, &n("attn_q"), DIM, DIM, GgmlType::Q8_0, 0.7);
+            w = add(w, &n("attn_k"), DIM, kv_dim, GgmlType::Q8_0, 0.7);
+            w = add(w, &n("attn_v"), DIM, kv_dim, GgmlType::Q8_0, 0.7);
+            w = add(w, &n("attn_output"), DIM, DIM, GgmlType::Q4_1, 0.5);
+            w = w.tensor_f32(&n("ffn_norm"), &[DIM as u64], GgmlType::F32, &[1.0; DIM]).unwrap();
+            w = add(w, &n("ffn_gate"), DIM, HIDDEN, GgmlType::Q4_0, 0.7);
+            w = add(w, &n("ffn_up"), DIM, HIDDEN, GgmlType::Q4_0, 0.7);
+            w = add(w, &n("ffn_down"), HIDDEN, DIM, GgmlType::Q4_0, 0.5);
+        }
+        w = w.tensor_f32("output_norm.weight", &[DIM as u64], GgmlType::F32, &[1.0; DIM]).unwrap();
+        w = add(w, "output.weight", DIM, vocab, GgmlType::F16, 2.0);
+        w.to_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gguf::GgufFile;
+
+    fn model() -> LlamaModel {
+        LlamaModel::load(&fixture::path()).unwrap()
+    }
+
+    #[test]
+    #[ignore = "rewrites testdata/tiny-llama.gguf"]
+    fn regenerate_fixture() {
+        std::fs::write(fixture::path(), fixture::build()).unwrap();
+    }
+
+    #[test]
+    fn test_fixture_is_reproducible() {
+        let on_disk = std::fs::read(fixture::path()).unwrap();
+        assert!(on_disk == fixture::build(), "run `cargo test -p gently-brain -- --ignored regenerate_fixture`");
+    }
+
+    #[test]
+    fn test_config_from_metadata() {
+        let m = model();
+        let c = m.config();
+        assert_eq!((c.dim, c.hidden_dim, c.n_layers), (fixture::DIM, fixture::HIDDEN, fixture::LAYERS));
+        assert_eq!((c.n_heads, c.n_kv_heads, c.head_dim(), c.kv_dim()), (4, 2, 8, 16));
+        assert_eq!(c.vocab_size, fixture::vocab().0.len());
+        assert_eq!(m.new_cache(10_000).unwrap().capacity(), fixture::CONTEXT);
+    }
+
+    #[test]
+    fn test_rejects_config_the_tensors_do_not_back() {
+        use crate::gguf::MetaValue;
+        for (key, value) in [
+            ("llama.block_count", MetaValue::U64(1 << 40)),
+            ("llama.block_count", MetaValue::U32(3)),
+            ("llama.embedding_length", MetaValue::U64(1 << 40)),
+            ("llama.feed_forward_length", MetaValue::U64(1 << 40)),
+            ("llama.attention.head_count", MetaValue::U32(0)),
+            ("llama.context_length", MetaValue::U64(1 << 40)),
+        ] {
+            let file = GgufFile::from_bytes(fixture::build_with(&[(key, value.clone())])).unwrap();
+            assert!(
+                matches!(LlamaModel::from_gguf(file), Err(Error::InvalidModel(_))),
+                "{} = {:?} was accepted",
+                key,
+                value
+            );
+        }
+    }
+
+    /// Whole-sequence causal attention over fully dequantized weights
+    fn reference_logits(file: &GgufFile, tokens: &[u32]) -> Vec<f32> {
+        let c = LlamaConfig::from_gguf(file).unwrap();
+        let t = |name: &str| file.tensor_f32(name).unwrap();
+        let (hd, kv_dim, group) = (c.head_dim(), c.kv_dim(), c.n_heads / c.n_kv_heads);
+        let mv = |w: &[f32], x: &[f32]| -> Vec<f32> { w.chunks(x.len()).map(|r| r.iter().zip(x).map(|(a, b)| a * b).sum()).collect() };
+        let norm = |x: &[f32], w: &[f32]| {
+            let mut out = vec![0.0; x.len()];
+            rms_norm(x, w, c.rms_eps, &mut out);
+            out
+        };
+
+        let embd = t("token_embd.weight");
+        let mut xs: Vec<Vec<f32>> = tokens.iter().map(|&id| embd[id as usize * c.dim..][..c.dim].to_vec()).collect();
+        for l in 0..c.n_layers {
+            let n = |s: &str| t(&format!("blk.{}.{}.weight", l, s));
+            let (wq, wk, wv, wo) = (n("attn_q"), n("attn_k"), n("attn_v"), n("attn_output"));
+            let (wg, wu, wd) = (n("ffn_gate"), n("ffn_up"), n("ffn_down"));
+            let (an, fnorm) = (n("attn_norm"), n("ffn_norm"));
+
+            let mut qs = Vec::new();
+            let mut ks = Vec::new();
+            let mut vs = Vec::new();
+            for (pos, x) in xs.iter().enumerate() {
+                let h = norm(x, &an);
+                let (mut q, mut k) = (mv(&wq, &h), mv(&wk, &h));
+                rope(&mut q, pos, hd, c.rope_base);
+                rope(&mut k, pos, hd, c.rope_base);
+                qs.push(q);
+                ks.push(k);
+                vs.push(mv(&wv, &h));
+            }
+            for pos in 0..xs.len() {
+                let mut attn = vec![0.0; c.dim];
+                for h in 0..c.n_heads {
+                    let off = (h / group) * hd;
+                    let q = &qs[pos][h * hd..(h + 1) * hd];
+                    let mut s: Vec<f32> = (0..=pos)
+                        .map(|t| q.iter().zip(&ks[t][off..off + hd]).map(|(a, b)| a * b).sum::<f32>() / (hd as f32).sqrt())
+                        .collect();
+                    softmax(&mut s);
+                    for (t, p) in s.iter().enumerate() {
+                        for i in 0..hd {
+                            attn[h * hd + i] += p * vs[t][off + i];
+                        }
+                    }
+                }
+                assert_eq!(kv_dim, ks[pos].len());
+                let o = mv(&wo, &attn);
+                xs[pos].iter_mut().zip(&o).for_each(|(x, o)| *x += o);
+                let h = norm(&xs[pos], &fnorm);
+                let act: Vec<f32> = mv(&wg, &h).iter().zip(mv(&wu, &h)).map(|(g, u)| silu(*g) * u).collect();
+                let d = mv(&wd, &act);
+                xs[pos].iter_mut().zip(&d).for_each(|(x, d)| *x += d);
+            }
+        }
+        mv(&t("output.weight"), &norm(xs.last().unwrap(), &t("output_norm.weight")))
+    }
+
+    #[test]
+    fn test_forward_matches_reference() {
+        let m = model();
+        let tokens = [1, 300, 42, 7, 250, 299];
+        let mut cache = m.new_cache(16).unwrap();
+        let logits = m.prefill(&tokens, &mut cache).unwrap();
+        assert_eq!(cache.len(), tokens.len());
+
+        let expected = reference_logits(m.gguf(), &tokens);
+        for (a, b) in logits.iter().zip(&expected) {
+            assert!((a - b).abs() < 1e-3, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_cache_truncate_reuses_prefix() {
+        let m = model();
+        let mut cache = m.new_cache(16).unwrap();
+        m.prefill(&[1, 5, 6, 7], &mut cache).unwrap();
+        cache.truncate(2);
+        let reused = m.prefill(&[9, 10], &mut cache).unwrap();
+
+        let mut fresh = m.new_cache(16).unwrap();
+        let expected = m.prefill(&[1, 5, 9, 10], &mut fresh).unwrap();
+        assert_eq!(reused, expected);
+    }
+
+    #[test]
+    fn test_context_window_full() {
+        let m = model();
+        let mut cache = m.new_cache(2).unwrap();
+        m.prefill(&[1, 5], &mut cache).unwrap();
+        assert!(cache.is_full());
+        assert!(matches!(m.forward(6, &mut cache), Err(Error::InferenceFailed(_))));
+        assert!(m.forward(100_000, &mut m.new_cache(2).unwrap()).is_err());
+    }
+}
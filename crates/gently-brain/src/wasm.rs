@@ -0,0 +1,347 @@
+//! Sandboxed WASM execution for agent brains
+//!
+//! Agent modules run in an interpreter with a fuel budget and a capped
+//! linear memory. The only imports available are the `gently` host API;
+//! anything else (WASI, env, ...) fails to link, so a brain has no
+//! ambient I/O.
+//!
+//! ```text
+//! host (gently.*)                       guest exports
+//! ───────────────                       ─────────────
+//! input_len() -> i32                    memory        (needed for memory_*)
+//! input_get(i32) -> f32                 run() -> i32  (0 = ok)
+//! emit(kind: i32, value: f32)
+//! memory_len() -> i32
+//! memory_read(ptr, len) -> i32          bytes copied from the last memory blob
+//! memory_write(ptr, len)                new memory blob
+//! ```
+//!
+//! Host calls that exceed a quota trap, ending the run.
+
+use serde::{Serialize, Deserialize};
+use wasmi::core::TrapCode;
+use wasmi::{Caller, Config, Engine, Extern, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// Host import namespace
+pub const HOST_MODULE: &str = "gently";
+
+/// Guest entry point
+pub const ENTRY: &str = "run";
+
+/// Resource limits for one run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmLimits {
+    /// Instruction fuel per run
+    pub fuel: u64,
+    /// Maximum linear memory in bytes
+    pub max_memory_bytes: usize,
+    /// Maximum `emit` calls per run
+    pub max_actions: usize,
+    /// Maximum `memory_write` calls per run
+    pub max_writes: usize,
+    /// Maximum size of one memory blob
+    pub max_write_bytes: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            fuel: 10_000_000,
+            max_memory_bytes: 16 * 1024 * 1024,
+            max_actions: 1024,
+            max_writes: 16,
+            max_write_bytes: 64 * 1024,
+        }
+    }
+}
+
+/// Action emitted by a brain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentAction {
+    pub kind: u32,
+    pub value: f32,
+}
+
+/// How a run ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "detail", rename_all = "snake_case")]
+pub enum RunStatus {
+    /// `run` returned 0
+    Ok,
+    /// `run` returned a non-zero code
+    Exit(i32),
+    /// Fuel budget exhausted
+    OutOfFuel,
+    /// Trap during execution (including quota violations)
+    Trap(String),
+    /// Module failed to compile, link or instantiate
+    Invalid(String),
+}
+
+impl RunStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, RunStatus::Ok)
+    }
+}
+
+/// Result of one sandboxed run
+#[derive(Debug, Clone)]
+pub struct WasmRun {
+    pub status: RunStatus,
+    pub fuel_used: u64,
+    pub actions: Vec<AgentAction>,
+    /// Memory blobs written, in order
+    pub writes: Vec<Vec<u8>>,
+}
+
+/// Per-run host state
+struct Host {
+    store_limits: StoreLimits,
+    limits: WasmLimits,
+    input: Vec<f32>,
+    memory: Vec<u8>,
+    actions: Vec<AgentAction>,
+    writes: Vec<Vec<u8>>,
+}
+
+/// WASM interpreter with the `gently` host API
+pub struct WasmSandbox {
+    engine: Engine,
+    limits: WasmLimits,
+}
+
+impl WasmSandbox {
+    pub fn new(limits: WasmLimits) -> Self {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        Self { engine: Engine::new(&config), limits }
+    }
+
+    pub fn limits(&self) -> &WasmLimits {
+        &self.limits
+    }
+
+    /// Run `wasm` on `input`, giving it read access to `memory`
+    pub fn run(&self, wasm: &[u8], input: &[f32], memory: &[u8]) -> WasmRun {
+        let host = Host {
+            store_limits: StoreLimitsBuilder::new()
+                .memory_size(self.limits.max_memory_bytes)
+                .instances(1)
+                .memories(1)
+                .tables(1)
+                .table_elements(10_000)
+                .build(),
+            limits: self.limits.clone(),
+            input: input.to_vec(),
+            memory: memory.to_vec(),
+            actions: Vec::new(),
+            writes: Vec::new(),
+        };
+        let mut store = Store::new(&self.engine, host);
+        store.limiter(|host| &mut host.store_limits);
+        store.set_fuel(self.limits.fuel).expect("fuel metering enabled");
+
+        let status = self.execute(&mut store, wasm);
+        let fuel_used = self.limits.fuel - store.get_fuel().unwrap_or(0);
+        let host = store.into_data();
+        WasmRun { status, fuel_used, actions: host.actions, writes: host.writes }
+    }
+
+    fn execute(&self, store: &mut Store<Host>, wasm: &[u8]) -> RunStatus {
+        let module = match Module::new(&self.engine, wasm) {
+            Ok(m) => m,
+            Err(e) => return RunStatus::Invalid(e.to_string()),
+        };
+        let linker = match host_api(&self.engine) {
+            Ok(l) => l,
+            Err(e) => return RunStatus::Invalid(e.to_string()),
+        };
+
+        let pre = match linker.instantiate(&mut *store, &module) {
+            Ok(pre) => pre,
+            Err(e) => return RunStatus::Invalid(e.to_string()),
+        };
+        let instance = match pre.start(&mut *store) {
+            Ok(i) => i,
+            Err(e) => return trap_status(&e),
+        };
+        let run = match instance.get_typed_func::<(), i32>(&*store, ENTRY) {
+            Ok(f) => f,
+            Err(_) => return RunStatus::Invalid(format!("missing export `{}: () -> i32`", ENTRY)),
+        };
+
+        match run.call(&mut *store, ()) {
+            Ok(0) => RunStatus::Ok,
+            Ok(code) => RunStatus::Exit(code),
+            Err(e) => trap_status(&e),
+        }
+    }
+}
+
+impl Default for WasmSandbox {
+    fn default() -> Self { Self::new(WasmLimits::default()) }
+}
+
+fn trap_status(e: &wasmi::Error) -> RunStatus {
+    match e.as_trap_code() {
+        Some(TrapCode::OutOfFuel) => RunStatus::OutOfFuel,
+        _ => RunStatus::Trap(e.to_string()),
+    }
+}
+
+/// The only imports a brain can link against
+fn host_api(engine: &Engine) -> Result<Linker<Host>, wasmi::Error> {
+    let mut linker = Linker::<Host>::new(engine);
+
+    linker.func_wrap(HOST_MODULE, "input_len", |caller: Caller<'_, Host>| -> i32 {
+        caller.data().input.len() as i32
+    })?;
+
+    linker.func_wrap(HOST_MODULE, "input_get", |caller: Caller<'_, Host>, i: i32| -> Result<f32, wasmi::Error> {
+        usize::try_from(i).ok()
+            .and_then(|i| caller.data().input.get(i).copied())
+            .ok_or_else(|| wasmi::Error::new(format!("input index {} out of range", i)))
+    })?;
+
+    linker.func_wrap(HOST_MODULE, "emit", |mut caller: Caller<'_, Host>, kind: i32, value: f32| -> Result<(), wasmi::Error> {
+        let host = caller.data_mut();
+        if host.actions.len() >= host.limits.max_actions {
+            return Err(wasmi::Error::new("action limit exceeded"));
+        }
+        host.actions.push(AgentAction { kind: kind as u32, value });
+        Ok(())
+    })?;
+
+    linker.func_wrap(HOST_MODULE, "memory_len", |caller: Caller<'_, Host>| -> i32 {
+        caller.data().memory.len() as i32
+    })?;
+
+    linker.func_wrap(HOST_MODULE, "memory_read", |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> Result<i32, wasmi::Error> {
+        let memory = guest_memory(&caller)?;
+        let n = (len.max(0) as usize).min(caller.data().memory.len());
+        let (guest, host) = memory.data_and_store_mut(&mut caller);
+        let dst = guest.get_mut(ptr as u32 as usize..)
+            .and_then(|d| d.get_mut(..n))
+            .ok_or_else(|| wasmi::Error::new("memory_read out of bounds"))?;
+        dst.copy_from_slice(&host.memory[..n]);
+        Ok(n as i32)
+    })?;
+
+    linker.func_wrap(HOST_MODULE, "memory_write", |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> Result<(), wasmi::Error> {
+        let memory = guest_memory(&caller)?;
+        let (guest, host) = memory.data_and_store_mut(&mut caller);
+        if host.writes.len() >= host.limits.max_writes {
+            return Err(wasmi::Error::new("memory write limit exceeded"));
+        }
+        let len = len.max(0) as usize;
+        if len > host.limits.max_write_bytes {
+            return Err(wasmi::Error::new(format!("memory blob of {} bytes exceeds limit", len)));
+        }
+        let src = guest.get(ptr as u32 as usize..)
+            .and_then(|s| s.get(..len))
+            .ok_or_else(|| wasmi::Error::new("memory_write out of bounds"))?;
+        host.writes.push(src.to_vec());
+        Ok(())
+    })?;
+
+    Ok(linker)
+}
+
+fn guest_memory(caller: &Caller<'_, Host>) -> Result<wasmi::Memory, wasmi::Error> {
+    match caller.get_export("memory") {
+        Some(Extern::Memory(m)) => Ok(m),
+        _ => Err(wasmi::Error::new("guest does not export `memory`")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wasm(src: &str) -> Vec<u8> {
+        wat::parse_str(src).unwrap()
+    }
+
+    #[test]
+    fn test_emits_actions_from_input() {
+        let brain = wasm(r#"(module
+            (import "gently" "input_len" (func $len (result i32)))
+            (import "gently" "input_get" (func $get (param i32) (result f32)))
+            (import "gently" "emit" (func $emit (param i32 f32)))
+            (func (export "run") (result i32) (local $i i32)
+                (block $done (loop $next
+                    (br_if $done (i32.ge_s (local.get $i) (call $len)))
+                    (call $emit (local.get $i) (f32.mul (call $get (local.get $i)) (f32.const 2)))
+                    (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                    (br $next)))
+                (i32.const 0)))"#);
+
+        let run = WasmSandbox::default().run(&brain, &[1.0, 2.5], &[]);
+        assert_eq!(run.status, RunStatus::Ok);
+        assert_eq!(run.actions, vec![AgentAction { kind: 0, value: 2.0 }, AgentAction { kind: 1, value: 5.0 }]);
+        assert!(run.fuel_used > 0);
+    }
+
+    #[test]
+    fn test_memory_roundtrip() {
+        // Increments the first byte of its memory blob
+        let brain = wasm(r#"(module
+            (import "gently" "memory_read" (func $read (param i32 i32) (result i32)))
+            (import "gently" "memory_write" (func $write (param i32 i32)))
+            (memory (export "memory") 1)
+            (func (export "run") (result i32)
+                (drop (call $read (i32.const 0) (i32.const 1)))
+                (i32.store8 (i32.const 0) (i32.add (i32.load8_u (i32.const 0)) (i32.const 1)))
+                (call $write (i32.const 0) (i32.const 1))
+                (i32.const 0)))"#);
+
+        let sandbox = WasmSandbox::default();
+        let first = sandbox.run(&brain, &[], &[]);
+        assert_eq!(first.writes, vec![vec![1]]);
+        let second = sandbox.run(&brain, &[], &first.writes[0]);
+        assert_eq!(second.writes, vec![vec![2]]);
+    }
+
+    #[test]
+    fn test_fuel_and_memory_limits() {
+        let spin = wasm(r#"(module (func (export "run") (result i32) (loop $l (br $l)) (i32.const 0)))"#);
+        let limits = WasmLimits { fuel: 10_000, ..Default::default() };
+        let run = WasmSandbox::new(limits).run(&spin, &[], &[]);
+        assert_eq!(run.status, RunStatus::OutOfFuel);
+        assert!(run.fuel_used > 9_900 && run.fuel_used <= 10_000);
+
+        // memory.grow past the cap fails, and so does an oversized initial memory
+        let grow = wasm(r#"(module (memory 1) (func (export "run") (result i32) (memory.grow (i32.const 1))))"#);
+        let limits = WasmLimits { max_memory_bytes: 65_536, ..Default::default() };
+        let sandbox = WasmSandbox::new(limits);
+        assert_eq!(sandbox.run(&grow, &[], &[]).status, RunStatus::Exit(-1));
+        let big = wasm(r#"(module (memory 2) (func (export "run") (result i32) (i32.const 0)))"#);
+        assert!(matches!(sandbox.run(&big, &[], &[]).status, RunStatus::Invalid(_)));
+    }
+
+    #[test]
+    fn test_no_ambient_imports() {
+        let wasi = wasm(r#"(module
+            (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32)))
+            (func (export "run") (result i32) (i32.const 0)))"#);
+        assert!(matches!(WasmSandbox::default().run(&wasi, &[], &[]).status, RunStatus::Invalid(_)));
+
+        assert!(matches!(WasmSandbox::default().run(b"not wasm", &[], &[]).status, RunStatus::Invalid(_)));
+    }
+
+    #[test]
+    fn test_quota_traps() {
+        let flood = wasm(r#"(module
+            (import "gently" "emit" (func $emit (param i32 f32)))
+            (func (export "run") (result i32) (loop $l (call $emit (i32.const 0) (f32.const 1)) (br $l)) (i32.const 0)))"#);
+        let limits = WasmLimits { max_actions: 3, ..Default::default() };
+        let run = WasmSandbox::new(limits).run(&flood, &[], &[]);
+        assert!(matches!(run.status, RunStatus::Trap(ref e) if e.contains("action limit")));
+        assert_eq!(run.actions.len(), 3);
+
+        let oob = wasm(r#"(module
+            (import "gently" "input_get" (func $get (param i32) (result f32)))
+            (func (export "run") (result i32) (drop (call $get (i32.const 5))) (i32.const 0)))"#);
+        assert!(matches!(WasmSandbox::default().run(&oob, &[1.0], &[]).status, RunStatus::Trap(_)));
+    }
+}
@@ -8,9 +8,16 @@
 //!      │
 //!      └──ACTION──► inference_c9f5
 //! ```
+//!
+//! Rules written in the `rules` language are compiled into JSON rule blobs
+//! and can be hot-reloaded from a directory of `*.rules` files.
 
+use crate::rules::{self, ActiveRule, Firing, RuleEngine, RuleSpec};
+use crate::{Error, Result};
 use gently_core::{Hash, Kind, Blob, Manifest, BlobStore, TAG_NEXT};
 use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 // Watchdog-specific tags
 pub const TAG_TRIGGER: u16 = 0x0200;
@@ -31,6 +38,25 @@ pub enum EventKind {
     Inference = 0x06,
 }
 
+impl EventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Alert => "alert",
+            EventKind::Anomaly => "anomaly",
+            EventKind::Threshold => "threshold",
+            EventKind::Integrity => "integrity",
+            EventKind::Access => "access",
+            EventKind::Inference => "inference",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::Alert, Self::Anomaly, Self::Threshold, Self::Integrity, Self::Access, Self::Inference]
+            .into_iter()
+            .find(|k| k.name().eq_ignore_ascii_case(name))
+    }
+}
+
 /// Event data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Event {
@@ -39,6 +65,7 @@ pub struct Event {
     pub message: String,
     pub severity: u8,
     pub timestamp: u64,
+    #[serde(default)]
     pub requires_inference: bool,
 }
 
@@ -52,7 +79,7 @@ pub struct Rule {
 }
 
 /// Action to take
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Action {
     Log,
     Notify,
@@ -65,6 +92,32 @@ pub struct Watchdog {
     store: BlobStore,
     rules: Vec<Hash>,
     event_log: Vec<Hash>,
+    engine: RuleEngine,
+    rules_dir: Option<RulesDir>,
+}
+
+/// Directory of `*.rules` files being watched
+struct RulesDir {
+    path: PathBuf,
+    /// File → (source blob hash, rule blob hashes)
+    files: HashMap<PathBuf, (Hash, Vec<Hash>)>,
+}
+
+/// What a rules directory reload changed
+#[derive(Debug, Clone, Default)]
+pub struct ReloadReport {
+    pub loaded: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+    /// Files that failed to compile; their previous rules stay active
+    pub errors: Vec<(PathBuf, String)>,
+    /// Active DSL rules after the reload
+    pub active: usize,
+}
+
+impl ReloadReport {
+    pub fn changed(&self) -> bool {
+        !self.loaded.is_empty() || !self.removed.is_empty()
+    }
 }
 
 impl Watchdog {
@@ -73,6 +126,8 @@ impl Watchdog {
             store: BlobStore::new(),
             rules: Vec::new(),
             event_log: Vec::new(),
+            engine: RuleEngine::new(),
+            rules_dir: None,
         }
     }
 
@@ -116,6 +171,174 @@ impl Watchdog {
         actions
     }
 
+    /// Compile rule source into rule blobs and activate them
+    ///
+    /// Returns the rule blob hashes, in source order. Loading the same
+    /// source twice keeps existing window state.
+    pub fn load_rules(&mut self, src: &str) -> Result<Vec<Hash>> {
+        let specs = rules::parse(src)?;
+        let hashes: Vec<Hash> = specs.iter()
+            .map(|spec| self.store.put(Blob::new(Kind::Json, serde_json::to_vec(spec).unwrap())))
+            .collect();
+
+        let mut set = Manifest::new();
+        for hash in &hashes {
+            set.add(TAG_RULE, *hash);
+        }
+        self.store.put(set.to_blob());
+
+        for hash in &hashes {
+            self.activate(hash)?;
+        }
+        Ok(hashes)
+    }
+
+    /// Activate a compiled rule blob
+    pub fn activate(&mut self, hash: &Hash) -> Result<()> {
+        let spec = self.compiled_rule(hash)
+            .ok_or_else(|| Error::Rules(format!("no rule blob {}", hex::encode(&hash[..6]))))?;
+        if !self.engine.rules().iter().any(|r| r.hash == *hash) {
+            self.engine.insert(ActiveRule::new(*hash, spec)?);
+        }
+        Ok(())
+    }
+
+    /// Deactivate a compiled rule
+    pub fn deactivate(&mut self, hash: &Hash) -> bool {
+        self.engine.remove(hash)
+    }
+
+    /// Compiled rule by blob hash
+    pub fn compiled_rule(&self, hash: &Hash) -> Option<RuleSpec> {
+        let blob = self.store.get(hash)?;
+        serde_json::from_slice(&blob.data).ok()
+    }
+
+    /// Active compiled rules
+    pub fn active_rules(&self) -> Vec<(Hash, &RuleSpec)> {
+        self.engine.rules().iter().map(|r| (r.hash, &r.spec)).collect()
+    }
+
+    /// Load every `*.rules` file in `dir` and keep watching it
+    pub fn watch_rules(&mut self, dir: &Path) -> Result<ReloadReport> {
+        if !dir.is_dir() {
+            return Err(Error::Rules(format!("{} is not a directory", dir.display())));
+        }
+        self.unwatch_rules();
+        self.rules_dir = Some(RulesDir { path: dir.to_path_buf(), files: HashMap::new() });
+        self.reload_rules()
+    }
+
+    /// Stop watching and drop the directory's rules
+    pub fn unwatch_rules(&mut self) {
+        if let Some(dir) = self.rules_dir.take() {
+            for (_, hashes) in dir.files.values() {
+                for hash in hashes {
+                    self.engine.remove(hash);
+                }
+            }
+        }
+    }
+
+    /// Pick up added, changed and deleted rule files
+    ///
+    /// Meant to be polled. A file that fails to compile is reported and its
+    /// last good rules stay active.
+    pub fn reload_rules(&mut self) -> Result<ReloadReport> {
+        let Some(mut dir) = self.rules_dir.take() else {
+            return Ok(ReloadReport::default());
+        };
+        let result = self.reload_dir(&mut dir);
+        self.rules_dir = Some(dir);
+        result
+    }
+
+    fn reload_dir(&mut self, dir: &mut RulesDir) -> Result<ReloadReport> {
+        let mut report = ReloadReport::default();
+
+        let mut present = Vec::new();
+        for entry in std::fs::read_dir(&dir.path)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "rules") && path.is_file() {
+                present.push(path);
+            }
+        }
+        present.sort();
+
+        for path in &present {
+            let src = match std::fs::read_to_string(path) {
+                Ok(src) => src,
+                Err(e) => {
+                    report.errors.push((path.clone(), e.to_string()));
+                    continue;
+                }
+            };
+            let source_hash = self.store.put(Blob::new(Kind::Text, src.clone().into_bytes()));
+            if dir.files.get(path).is_some_and(|(h, _)| *h == source_hash) {
+                continue;
+            }
+
+            match self.load_rules(&src) {
+                Ok(hashes) => {
+                    if let Some((_, old)) = dir.files.insert(path.clone(), (source_hash, hashes.clone())) {
+                        for hash in old.iter().filter(|h| !hashes.contains(h)) {
+                            self.drop_unless_shared(dir, hash);
+                        }
+                    }
+                    report.loaded.push(path.clone());
+                }
+                Err(e) => report.errors.push((path.clone(), e.to_string())),
+            }
+        }
+
+        let gone: Vec<PathBuf> = dir.files.keys().filter(|p| !present.contains(p)).cloned().collect();
+        for path in gone {
+            if let Some((_, hashes)) = dir.files.remove(&path) {
+                for hash in &hashes {
+                    self.drop_unless_shared(dir, hash);
+                }
+            }
+            report.removed.push(path);
+        }
+
+        report.active = self.engine.rules().len();
+        Ok(report)
+    }
+
+    fn drop_unless_shared(&mut self, dir: &RulesDir, hash: &Hash) {
+        if !dir.files.values().any(|(_, hashes)| hashes.contains(hash)) {
+            self.engine.remove(hash);
+        }
+    }
+
+    /// Record an event and run every rule on it
+    ///
+    /// Pattern rules fire on a message match; compiled rules keep their
+    /// windows. Each firing is stored as a TRIGGER/ACTION manifest.
+    pub fn observe(&mut self, event: Event) -> Vec<Firing> {
+        let hash = self.record(event.clone());
+
+        let mut fired: Vec<Firing> = self.check(&event).into_iter()
+            .filter_map(|(rule, action)| {
+                let name = self.get_rule(&rule)?.name;
+                Some(Firing { rule, name, key: String::new(), actions: vec![action], events: vec![hash] })
+            })
+            .collect();
+        fired.extend(self.engine.evaluate(&event, hash));
+
+        for firing in &fired {
+            let actions = self.store.put(Blob::new(Kind::Json, serde_json::to_vec(&firing.actions).unwrap()));
+            let mut link = Manifest::new();
+            link.add(TAG_TRIGGER, firing.rule);
+            link.add(TAG_ACTION, actions);
+            for event in &firing.events {
+                link.add(TAG_NEXT, *event);
+            }
+            self.store.put(link.to_blob());
+        }
+        fired
+    }
+
     /// Get rule by hash
     pub fn get_rule(&self, hash: &Hash) -> Option<Rule> {
         let blob = self.store.get(hash)?;
@@ -156,6 +379,85 @@ impl Default for Watchdog {
     fn default() -> Self { Self::new() }
 }
 
+/// Recorded event with the rules expected to fire on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    #[serde(flatten)]
+    pub event: Event,
+    /// Rule names expected to fire; unchecked when absent
+    #[serde(default)]
+    pub expect: Option<Vec<String>>,
+}
+
+/// One replayed event
+#[derive(Debug, Clone)]
+pub struct ReplayStep {
+    /// 1-based line in the recording
+    pub line: usize,
+    pub fired: Vec<String>,
+    pub expected: Option<Vec<String>>,
+}
+
+impl ReplayStep {
+    pub fn passed(&self) -> bool {
+        self.expected.as_ref().is_none_or(|expected| {
+            let mut expected = expected.clone();
+            expected.sort();
+            expected == self.fired
+        })
+    }
+}
+
+/// Result of replaying a recording against rules
+#[derive(Debug, Clone, Default)]
+pub struct ReplayReport {
+    pub steps: Vec<ReplayStep>,
+}
+
+impl ReplayReport {
+    pub fn passed(&self) -> bool {
+        self.steps.iter().all(|s| s.passed())
+    }
+
+    pub fn failures(&self) -> Vec<&ReplayStep> {
+        self.steps.iter().filter(|s| !s.passed()).collect()
+    }
+
+    /// How often each rule fired
+    pub fn counts(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for name in self.steps.iter().flat_map(|s| &s.fired) {
+            *counts.entry(name.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+/// Test harness: feed recorded events (JSON lines) through `rules`
+///
+/// Each line is an `Event` with an optional `"expect": ["rule", ...]`
+/// listing exactly the rules that must fire on it. Blank lines and lines
+/// starting with `#` are skipped.
+pub fn replay(rules: &str, recording: &str) -> Result<ReplayReport> {
+    let mut watchdog = Watchdog::new();
+    watchdog.load_rules(rules)?;
+
+    let mut report = ReplayReport::default();
+    for (i, line) in recording.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let recorded: RecordedEvent = serde_json::from_str(line)
+            .map_err(|e| Error::Rules(format!("recording line {}: {}", i + 1, e)))?;
+
+        let mut fired: Vec<String> = watchdog.observe(recorded.event).into_iter().map(|f| f.name).collect();
+        fired.sort();
+        report.steps.push(ReplayStep { line: i + 1, fired, expected: recorded.expect });
+    }
+    Ok(report)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,4 +488,89 @@ mod tests {
         assert!(!actions.is_empty());
         assert!(wd.get_event(&hash).is_some());
     }
+
+    const RULES: &str = r#"
+        rule "brute" {
+            when kind == access and message contains "auth failed"
+            by source
+            count >= 3 within 1m
+            cooldown 10m
+            then block
+        }
+        rule "critical" { when severity >= 4 then notify }
+    "#;
+
+    #[test]
+    fn test_replay_harness() {
+        let recording = r#"
+            # three failures in a minute from one host
+            {"kind":"Access","source":"10.0.0.5","message":"auth failed","severity":1,"timestamp":0,"expect":[]}
+            {"kind":"Access","source":"10.0.0.5","message":"auth failed","severity":1,"timestamp":20}
+            {"kind":"Access","source":"10.0.0.5","message":"auth failed","severity":4,"timestamp":40,"expect":["critical","brute"]}
+            {"kind":"Access","source":"10.0.0.5","message":"auth failed","severity":1,"timestamp":50,"expect":[]}
+        "#;
+        let report = replay(RULES, recording).unwrap();
+        assert!(report.passed(), "{:?}", report.failures());
+        assert_eq!(report.counts()["brute"], 1);
+
+        let wrong = r#"{"kind":"Alert","source":"x","message":"y","severity":5,"timestamp":0,"expect":["brute"]}"#;
+        let report = replay(RULES, wrong).unwrap();
+        assert_eq!(report.failures()[0].fired, vec!["critical"]);
+        assert!(replay(RULES, "{not json").is_err());
+    }
+
+    #[test]
+    fn test_rule_blobs_and_firings() {
+        let mut wd = Watchdog::new();
+        let hashes = wd.load_rules(RULES).unwrap();
+        assert_eq!(wd.compiled_rule(&hashes[1]).unwrap().name, "critical");
+        // Reloading identical source is a no-op
+        assert_eq!(wd.load_rules(RULES).unwrap(), hashes);
+        assert_eq!(wd.active_rules().len(), 2);
+
+        let fired = wd.observe(Event {
+            kind: EventKind::Alert,
+            source: "kernel".into(),
+            message: "oops".into(),
+            severity: 5,
+            timestamp: 0,
+            requires_inference: false,
+        });
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].rule, hashes[1]);
+        assert!(wd.deactivate(&hashes[1]));
+        assert_eq!(wd.active_rules().len(), 1);
+    }
+
+    #[test]
+    fn test_hot_reload_dir() {
+        let dir = std::env::temp_dir().join(format!("gently-rules-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("auth.rules"), RULES).unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let mut wd = Watchdog::new();
+        let report = wd.watch_rules(&dir).unwrap();
+        assert_eq!((report.loaded.len(), report.active), (1, 2));
+        assert!(!wd.reload_rules().unwrap().changed());
+
+        // Broken edit keeps the old rules
+        std::fs::write(dir.join("auth.rules"), "rule broken {").unwrap();
+        let report = wd.reload_rules().unwrap();
+        assert_eq!((report.errors.len(), report.active), (1, 2));
+
+        std::fs::write(dir.join("auth.rules"), r#"rule "critical" { when severity >= 4 then notify }"#).unwrap();
+        std::fs::write(dir.join("extra.rules"), r#"rule "any_alert" { when kind == alert then log }"#).unwrap();
+        let report = wd.reload_rules().unwrap();
+        assert_eq!((report.loaded.len(), report.active), (2, 2));
+        let mut names: Vec<_> = wd.active_rules().into_iter().map(|(_, r)| r.name.clone()).collect();
+        names.sort();
+        assert_eq!(names, vec!["any_alert", "critical"]);
+
+        std::fs::remove_file(dir.join("extra.rules")).unwrap();
+        let report = wd.reload_rules().unwrap();
+        assert_eq!((report.removed.len(), report.active), (1, 1));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -37,7 +37,7 @@ impl BridgeKind {
 }
 
 /// A connection between two feed items
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Bridge {
     /// Unique identifier
     pub id: Uuid,
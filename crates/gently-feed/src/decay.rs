@@ -0,0 +1,481 @@
+//! Decay models - how charge fades
+//!
+//! A [`DecayPolicy`] picks a [`DecayModel`] per [`ItemKind`]. Each model turns
+//! a [`DecayStep`] (wall-clock time elapsed plus feed interactions) into a
+//! multiplicative factor on charge:
+//!
+//! ```text
+//! PerTick        charge × (1 - rate)^ticks              (legacy; ignores time)
+//! HalfLife       charge × 0.5^(hours / half_life)
+//! Activity       charge × 0.5^(ticks / half_life)       (only once idle > grace)
+//! WorkingHours   charge × 0.5^(work hours / half_life)  (nights/weekends free)
+//! ```
+//!
+//! Pinned items never decay below 0.5 whatever the model.
+
+use crate::item::{FeedItem, ItemKind, ItemState};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveTime, TimeZone, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Input to one decay application
+#[derive(Debug, Clone, Copy)]
+pub struct DecayStep {
+    /// Wall-clock time since the last decay
+    pub elapsed: Duration,
+    /// Feed interactions (ticks) in this step
+    pub ticks: u32,
+    /// End of the step
+    pub now: DateTime<Utc>,
+}
+
+impl DecayStep {
+    /// Start of the step
+    pub fn start(&self) -> DateTime<Utc> {
+        self.now - self.elapsed
+    }
+}
+
+/// A charge decay strategy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "model", rename_all = "snake_case")]
+pub enum DecayModel {
+    /// Each tick multiplies charge by `1 - item.decay_rate`
+    #[default]
+    PerTick,
+
+    /// Charge halves every `hours` of wall-clock time
+    HalfLife { hours: f64 },
+
+    /// Charge halves every `half_life_ticks` interactions, once the item
+    /// has been idle for `grace_hours`
+    Activity { half_life_ticks: f64, grace_hours: f64 },
+
+    /// Charge halves every `half_life_hours` of working time
+    WorkingHours {
+        half_life_hours: f64,
+        start_hour: u32,
+        end_hour: u32,
+        weekdays_only: bool,
+        utc_offset_hours: i32,
+    },
+}
+
+impl DecayModel {
+    /// Multiplier to apply to `item`'s charge for `step`
+    pub fn factor(&self, item: &FeedItem, step: &DecayStep) -> f32 {
+        let factor = match self {
+            DecayModel::PerTick => (1.0 - item.decay_rate as f64).powi(step.ticks as i32),
+            DecayModel::HalfLife { hours } => halve(hours_of(step.elapsed), *hours),
+            DecayModel::Activity {
+                half_life_ticks,
+                grace_hours,
+            } => {
+                let idle = hours_of(step.now - item.last_touched);
+                if idle < *grace_hours {
+                    1.0
+                } else {
+                    halve(step.ticks as f64, *half_life_ticks)
+                }
+            }
+            DecayModel::WorkingHours {
+                half_life_hours,
+                start_hour,
+                end_hour,
+                weekdays_only,
+                utc_offset_hours,
+            } => {
+                let worked = working_hours_between(
+                    step.start(),
+                    step.now,
+                    *start_hour,
+                    *end_hour,
+                    *weekdays_only,
+                    *utc_offset_hours,
+                );
+                halve(worked, *half_life_hours)
+            }
+        };
+        factor.clamp(0.0, 1.0) as f32
+    }
+
+    /// Does this model decay on wall-clock time alone?
+    pub fn is_time_based(&self) -> bool {
+        matches!(self, DecayModel::HalfLife { .. } | DecayModel::WorkingHours { .. })
+    }
+}
+
+fn hours_of(duration: Duration) -> f64 {
+    duration.num_milliseconds().max(0) as f64 / 3_600_000.0
+}
+
+fn halve(units: f64, half_life: f64) -> f64 {
+    if half_life <= 0.0 {
+        return 0.0;
+    }
+    0.5f64.powf(units / half_life)
+}
+
+/// Working hours in `[from, to)` for a daily `start..end` window
+fn working_hours_between(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    start_hour: u32,
+    end_hour: u32,
+    weekdays_only: bool,
+    utc_offset_hours: i32,
+) -> f64 {
+    if to <= from || end_hour <= start_hour {
+        return 0.0;
+    }
+    let Some(offset) = FixedOffset::east_opt(utc_offset_hours * 3600) else {
+        return 0.0;
+    };
+    let Some(start) = NaiveTime::from_hms_opt(start_hour, 0, 0) else {
+        return 0.0;
+    };
+    let window = Duration::hours(end_hour.min(24) as i64 - start_hour as i64);
+
+    let mut total = 0.0;
+    let mut day = from.with_timezone(&offset).date_naive();
+    let last = to.with_timezone(&offset).date_naive();
+    while day <= last {
+        let weekend = matches!(day.weekday(), Weekday::Sat | Weekday::Sun);
+        if !(weekdays_only && weekend) {
+            if let Some(open) = offset.from_local_datetime(&day.and_time(start)).single() {
+                let open = open.with_timezone(&Utc);
+                let close = (open + window).min(to);
+                let open = open.max(from);
+                if close > open {
+                    total += hours_of(close - open);
+                }
+            }
+        }
+        day = match day.succ_opt() {
+            Some(next) => next,
+            None => break,
+        };
+    }
+    total
+}
+
+impl fmt::Display for DecayModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecayModel::PerTick => write!(f, "tick"),
+            DecayModel::HalfLife { hours } => write!(f, "half-life:{}", hours),
+            DecayModel::Activity {
+                half_life_ticks,
+                grace_hours,
+            } => write!(f, "activity:{}:{}", half_life_ticks, grace_hours),
+            DecayModel::WorkingHours {
+                half_life_hours,
+                start_hour,
+                end_hour,
+                weekdays_only,
+                utc_offset_hours,
+            } => write!(
+                f,
+                "working-hours:{}:{}-{}:{:+}{}",
+                half_life_hours,
+                start_hour,
+                end_hour,
+                utc_offset_hours,
+                if *weekdays_only { "" } else { ":all-week" }
+            ),
+        }
+    }
+}
+
+impl FromStr for DecayModel {
+    type Err = crate::Error;
+
+    /// Parse `tick`, `half-life:<h>`, `activity:<ticks>[:<grace h>]` or
+    /// `working-hours:<h>[:<start>-<end>[:<utc offset>[:all-week]]]`
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let invalid = || crate::Error::InvalidDecayModel(s.to_string());
+        let parts: Vec<&str> = s.trim().split(':').collect();
+        let num = |i: usize, default: Option<f64>| -> crate::Result<f64> {
+            match parts.get(i) {
+                Some(p) => p.parse::<f64>().ok().filter(|v| *v > 0.0).ok_or_else(invalid),
+                None => default.ok_or_else(invalid),
+            }
+        };
+
+        match parts[0].to_lowercase().as_str() {
+            "tick" | "per-tick" => Ok(DecayModel::PerTick),
+            "half-life" | "halflife" => Ok(DecayModel::HalfLife { hours: num(1, None)? }),
+            "activity" => Ok(DecayModel::Activity {
+                half_life_ticks: num(1, None)?,
+                grace_hours: num(2, Some(1.0))?,
+            }),
+            "working-hours" | "work" => {
+                let (start_hour, end_hour) = match parts.get(2) {
+                    Some(window) => {
+                        let (a, b) = window.split_once('-').ok_or_else(invalid)?;
+                        let a: u32 = a.parse().map_err(|_| invalid())?;
+                        let b: u32 = b.parse().map_err(|_| invalid())?;
+                        if a >= b || b > 24 {
+                            return Err(invalid());
+                        }
+                        (a, b)
+                    }
+                    None => (9, 17),
+                };
+                let utc_offset_hours = match parts.get(3) {
+                    Some(o) => o.trim_start_matches('+').parse().map_err(|_| invalid())?,
+                    None => 0,
+                };
+                let weekdays_only = match parts.get(4) {
+                    Some(&"all-week") => false,
+                    Some(_) => return Err(invalid()),
+                    None => true,
+                };
+                Ok(DecayModel::WorkingHours {
+                    half_life_hours: num(1, None)?,
+                    start_hour,
+                    end_hour,
+                    weekdays_only,
+                    utc_offset_hours,
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Which model applies to which kind of item
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DecayPolicy {
+    /// Model for kinds without an override
+    pub default: DecayModel,
+
+    /// Per-kind overrides
+    #[serde(default)]
+    pub overrides: Vec<(ItemKind, DecayModel)>,
+}
+
+impl DecayPolicy {
+    /// Policy using one model for everything
+    pub fn new(default: DecayModel) -> Self {
+        Self {
+            default,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Builder: override the model for a kind
+    pub fn with_kind(mut self, kind: ItemKind, model: DecayModel) -> Self {
+        self.set_kind(kind, model);
+        self
+    }
+
+    /// Override the model for a kind
+    pub fn set_kind(&mut self, kind: ItemKind, model: DecayModel) {
+        self.overrides.retain(|(k, _)| *k != kind);
+        self.overrides.push((kind, model));
+    }
+
+    /// Model for a kind
+    pub fn model_for(&self, kind: &ItemKind) -> &DecayModel {
+        self.overrides
+            .iter()
+            .find(|(k, _)| k == kind)
+            .map(|(_, m)| m)
+            .unwrap_or(&self.default)
+    }
+
+    /// Apply one step to an item
+    pub fn apply(&self, item: &mut FeedItem, step: &DecayStep) {
+        let factor = self.model_for(&item.kind).factor(item, step);
+        item.apply_decay(factor);
+    }
+}
+
+/// Simulation settings for [`project`]
+#[derive(Debug, Clone, Copy)]
+pub struct Simulation {
+    /// How far ahead to look
+    pub horizon: Duration,
+    /// Simulation resolution
+    pub step: Duration,
+    /// Assumed feed interactions per step
+    pub ticks_per_step: u32,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self {
+            horizon: Duration::days(14),
+            step: Duration::hours(1),
+            ticks_per_step: 1,
+        }
+    }
+}
+
+/// When an item is expected to cross each state boundary
+#[derive(Debug, Clone)]
+pub struct DecayProjection {
+    pub name: String,
+    pub state: ItemState,
+    pub charge: f32,
+    /// Drops to Active (None: not within the horizon, or already past)
+    pub active_at: Option<DateTime<Utc>>,
+    /// Drops to Cooling
+    pub cooling_at: Option<DateTime<Utc>>,
+    /// Drops to Frozen
+    pub frozen_at: Option<DateTime<Utc>>,
+}
+
+/// Project untouched items forward under a policy
+pub fn project<'a>(
+    items: impl IntoIterator<Item = &'a FeedItem>,
+    policy: &DecayPolicy,
+    sim: &Simulation,
+    now: DateTime<Utc>,
+) -> Vec<DecayProjection> {
+    let steps = if sim.step > Duration::zero() {
+        (sim.horizon.num_seconds() / sim.step.num_seconds().max(1)).max(0)
+    } else {
+        0
+    };
+
+    items
+        .into_iter()
+        .filter(|item| !item.archived)
+        .map(|item| {
+            let mut projection = DecayProjection {
+                name: item.name.clone(),
+                state: item.state,
+                charge: item.charge,
+                active_at: None,
+                cooling_at: None,
+                frozen_at: None,
+            };
+            let mut sim_item = item.clone();
+            let mut t = now;
+            for _ in 0..steps {
+                let before = ItemState::from_charge(sim_item.charge);
+                t += sim.step;
+                policy.apply(
+                    &mut sim_item,
+                    &DecayStep {
+                        elapsed: sim.step,
+                        ticks: sim.ticks_per_step,
+                        now: t,
+                    },
+                );
+                let after = ItemState::from_charge(sim_item.charge);
+                if after != before {
+                    match after {
+                        ItemState::Active => projection.active_at.get_or_insert(t),
+                        ItemState::Cooling => projection.cooling_at.get_or_insert(t),
+                        ItemState::Frozen => projection.frozen_at.get_or_insert(t),
+                        ItemState::Hot => continue,
+                    };
+                }
+                if after == ItemState::Frozen {
+                    break;
+                }
+            }
+            projection
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item_at(kind: ItemKind, touched: DateTime<Utc>) -> FeedItem {
+        let mut item = FeedItem::new("test", kind);
+        item.last_touched = touched;
+        item
+    }
+
+    #[test]
+    fn test_half_life() {
+        let now = Utc::now();
+        let mut item = item_at(ItemKind::Project, now);
+        let policy = DecayPolicy::new(DecayModel::HalfLife { hours: 24.0 });
+        policy.apply(
+            &mut item,
+            &DecayStep {
+                elapsed: Duration::hours(48),
+                ticks: 0,
+                now,
+            },
+        );
+        assert!((item.charge - 0.25).abs() < 1e-4);
+        assert_eq!(item.state, ItemState::Cooling);
+    }
+
+    #[test]
+    fn test_activity_grace_and_per_kind() {
+        let now = Utc::now();
+        let policy = DecayPolicy::new(DecayModel::PerTick).with_kind(
+            ItemKind::Task,
+            DecayModel::Activity {
+                half_life_ticks: 2.0,
+                grace_hours: 1.0,
+            },
+        );
+        let step = DecayStep {
+            elapsed: Duration::minutes(5),
+            ticks: 2,
+            now,
+        };
+
+        let mut fresh = item_at(ItemKind::Task, now);
+        policy.apply(&mut fresh, &step);
+        assert_eq!(fresh.charge, 1.0);
+
+        let mut idle = item_at(ItemKind::Task, now - Duration::hours(3));
+        policy.apply(&mut idle, &step);
+        assert!((idle.charge - 0.5).abs() < 1e-4);
+
+        let mut project = item_at(ItemKind::Project, now);
+        policy.apply(&mut project, &step);
+        assert!((project.charge - 0.9025).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_working_hours_skip_weekend() {
+        // Friday 16:00 UTC to Monday 10:00 UTC: 1h Friday + 1h Monday
+        let from = Utc.with_ymd_and_hms(2026, 10, 16, 16, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2026, 10, 19, 10, 0, 0).unwrap();
+        assert!((working_hours_between(from, to, 9, 17, true, 0) - 2.0).abs() < 1e-9);
+        assert!((working_hours_between(from, to, 9, 17, false, 0) - 18.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        for spec in ["tick", "half-life:48", "activity:20:2", "working-hours:16:9-17:+2"] {
+            let model: DecayModel = spec.parse().unwrap();
+            assert_eq!(model.to_string().parse::<DecayModel>().unwrap(), model);
+        }
+        assert!("half-life".parse::<DecayModel>().is_err());
+        assert!("working-hours:8:17-9".parse::<DecayModel>().is_err());
+        assert!("sideways".parse::<DecayModel>().is_err());
+    }
+
+    #[test]
+    fn test_projection_orders_transitions() {
+        let now = Utc::now();
+        let item = item_at(ItemKind::Project, now);
+        let mut pinned = FeedItem::new("pinned", ItemKind::Project);
+        pinned.pinned = true;
+
+        let policy = DecayPolicy::new(DecayModel::HalfLife { hours: 24.0 });
+        let projections = project([&item, &pinned], &policy, &Simulation::default(), now);
+
+        let p = &projections[0];
+        let (active, cooling, frozen) = (p.active_at.unwrap(), p.cooling_at.unwrap(), p.frozen_at.unwrap());
+        assert!(active < cooling && cooling < frozen);
+        // 0.1 is reached after log2(10) ≈ 3.32 half-lives
+        assert_eq!((frozen - now).num_hours(), 80);
+
+        assert!(projections[1].frozen_at.is_none());
+    }
+}
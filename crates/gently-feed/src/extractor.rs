@@ -1,10 +1,24 @@
 //! Context extraction from messages/interactions
 //!
 //! Extracts mentions, action items, and bridge candidates from text.
+//!
+//! ```text
+//! "Fixed the lambdacadabre bug in crates/gently-core/src/vault.rs"
+//!        │          │                   │
+//!        │          │ fuzzy (1 edit)    │ code-aware
+//!        │          ▼                   ▼
+//!        │   mention: lambdacadabra   files: [crates/gently-core/src/vault.rs]
+//!        ▼                            crates: [gently-core]
+//!   sentiment: +fixed -bug → Neutral
+//! ```
+//!
+//! Mentions match on whole words (so "core" is not found in "score"), allow a
+//! small edit distance for longer names, and resolve per-item aliases to the
+//! item's name.
 
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// Extracted context from a message or interaction
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -26,6 +40,14 @@ pub struct ExtractedContext {
 
     /// Sentiment indicators
     pub sentiment: Sentiment,
+
+    /// File paths referenced (code-aware extraction)
+    #[serde(default)]
+    pub files: Vec<String>,
+
+    /// Crate names referenced (code-aware extraction)
+    #[serde(default)]
+    pub crates: Vec<String>,
 }
 
 /// Basic sentiment for adjusting boost amounts
@@ -50,26 +72,258 @@ impl Sentiment {
     }
 }
 
+/// Raw sentiment scores
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SentimentScore {
+    /// Negative..positive, roughly -2.0..2.0
+    pub valence: f32,
+    /// 0.0 (none) upwards
+    pub urgency: f32,
+}
+
+/// Lexicon sentiment with negation, intensifiers and "but" contrast
+#[derive(Debug, Clone)]
+pub struct SentimentModel {
+    valence: HashMap<&'static str, f32>,
+    urgency: HashMap<&'static str, f32>,
+    negators: HashSet<&'static str>,
+    intensifiers: HashMap<&'static str, f32>,
+    /// |valence| needed for Positive/Negative
+    pub valence_threshold: f32,
+    /// Urgency needed for Urgent
+    pub urgency_threshold: f32,
+}
+
+impl Default for SentimentModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SentimentModel {
+    /// Model with the built-in lexicon
+    pub fn new() -> Self {
+        let valence = [
+            ("good", 1.0), ("great", 1.5), ("excellent", 2.0), ("awesome", 1.5), ("nice", 1.0),
+            ("love", 1.5), ("progress", 1.0), ("done", 1.0), ("completed", 1.0),
+            ("finished", 1.0), ("fixed", 1.2), ("solved", 1.2), ("resolved", 1.2),
+            ("shipped", 1.5), ("merged", 1.0), ("passing", 1.0), ("passes", 1.0),
+            ("works", 1.0), ("success", 1.2), ("successful", 1.2), ("clean", 0.5),
+            ("problem", -1.0), ("issue", -0.8), ("bug", -1.0), ("broken", -1.5),
+            ("failed", -1.5), ("failing", -1.2), ("fails", -1.2), ("stuck", -1.2),
+            ("blocked", -1.2), ("error", -1.0), ("errors", -1.0), ("crash", -1.5),
+            ("crashes", -1.5), ("panic", -1.5), ("regression", -1.2), ("wrong", -1.0),
+            ("slow", -0.6), ("hate", -1.5), ("confusing", -0.8), ("annoying", -1.0),
+        ];
+        let urgency = [
+            ("urgent", 1.0), ("urgently", 1.0), ("asap", 1.0), ("emergency", 1.0),
+            ("critical", 0.8), ("immediately", 0.8), ("blocker", 0.7), ("outage", 0.8),
+            ("down", 0.3), ("deadline", 0.5), ("priority", 0.4), ("now", 0.2), ("today", 0.2),
+        ];
+        let intensifiers = [
+            ("very", 1.5), ("really", 1.4), ("super", 1.5), ("extremely", 2.0),
+            ("totally", 1.5), ("completely", 1.5), ("high", 1.8), ("top", 1.8), ("slightly", 0.5),
+        ];
+        let negators = [
+            "not", "no", "never", "isn't", "wasn't", "aren't", "don't", "doesn't", "didn't",
+            "won't", "can't", "cannot", "without", "nothing", "hardly",
+        ];
+
+        Self {
+            valence: valence.into_iter().collect(),
+            urgency: urgency.into_iter().collect(),
+            negators: negators.into_iter().collect(),
+            intensifiers: intensifiers.into_iter().collect(),
+            valence_threshold: 0.25,
+            urgency_threshold: 0.7,
+        }
+    }
+
+    /// Score a message
+    pub fn score(&self, message: &str) -> SentimentScore {
+        let mut score = SentimentScore::default();
+        // Words after "but" carry more weight than those before it
+        let mut clause_weight = 1.0;
+        let mut negate_for = 0usize;
+        let mut boost = 1.0;
+
+        for raw in message.split(|c: char| !(c.is_alphanumeric() || c == '\'')) {
+            if raw.is_empty() {
+                continue;
+            }
+            let word = raw.to_lowercase();
+            let shouted = raw.len() > 2 && raw.chars().all(|c| !c.is_lowercase());
+
+            if word == "but" || word == "however" {
+                score.valence *= 0.5;
+                clause_weight = 1.5;
+                negate_for = 0;
+                continue;
+            }
+            if self.negators.contains(word.as_str()) {
+                negate_for = 3;
+                continue;
+            }
+            if let Some(m) = self.intensifiers.get(word.as_str()) {
+                boost = *m;
+                continue;
+            }
+
+            let negated = negate_for > 0;
+            if let Some(v) = self.valence.get(word.as_str()) {
+                let v = if negated { -0.5 * v } else { *v };
+                score.valence += v * boost * clause_weight;
+            }
+            if let Some(u) = self.urgency.get(word.as_str()) {
+                if !negated {
+                    score.urgency += u * boost * if shouted { 1.5 } else { 1.0 };
+                }
+            }
+
+            boost = 1.0;
+            negate_for = negate_for.saturating_sub(1);
+        }
+
+        let exclamations = message.matches('!').count().min(3) as f32;
+        if score.urgency > 0.0 {
+            score.urgency += 0.1 * exclamations;
+        }
+        score
+    }
+
+    /// Classify a message
+    pub fn classify(&self, message: &str) -> Sentiment {
+        let score = self.score(message);
+        if score.urgency >= self.urgency_threshold {
+            Sentiment::Urgent
+        } else if score.valence >= self.valence_threshold {
+            Sentiment::Positive
+        } else if score.valence <= -self.valence_threshold {
+            Sentiment::Negative
+        } else {
+            Sentiment::Neutral
+        }
+    }
+}
+
+/// Lowercase word tokens (letters and digits)
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+/// Optimal string alignment distance (edits incl. transpositions)
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Edits tolerated for a word of this length
+fn allowed_edits(len: usize) -> usize {
+    match len {
+        0..=4 => 0,
+        5..=8 => 1,
+        _ => 2,
+    }
+}
+
+fn token_matches(message: &str, name: &str) -> bool {
+    if message == name {
+        return true;
+    }
+    let edits = allowed_edits(name.chars().count());
+    edits > 0
+        && message.chars().next() == name.chars().next()
+        && message.chars().count().abs_diff(name.chars().count()) <= edits
+        && edit_distance(message, name) <= edits
+}
+
+/// A name or alias to look for
+#[derive(Debug, Clone)]
+struct Term {
+    tokens: Vec<String>,
+    joined: String,
+    item: String,
+}
+
+impl Term {
+    fn new(text: &str, item: &str) -> Option<Self> {
+        let tokens = tokenize(text);
+        if tokens.is_empty() {
+            return None;
+        }
+        Some(Self {
+            joined: tokens.concat(),
+            tokens,
+            item: item.to_string(),
+        })
+    }
+
+    /// Words consumed if the term starts at `words[start]`
+    fn matches_at(&self, words: &[String], start: usize) -> Option<usize> {
+        // Same words, each allowed a small typo
+        let n = self.tokens.len();
+        if start + n <= words.len()
+            && self
+                .tokens
+                .iter()
+                .zip(&words[start..start + n])
+                .all(|(name, word)| token_matches(word, name))
+        {
+            return Some(n);
+        }
+        // Spacing differs ("bone blob" vs "boneblob")
+        (1..=3)
+            .filter(|len| *len != n && start + len <= words.len())
+            .find(|len| words[start..start + len].concat() == self.joined)
+    }
+}
+
 /// Context extractor with configurable patterns
 #[derive(Debug, Clone)]
 pub struct ContextExtractor {
     /// Known item names (for mention detection)
     known_items: HashSet<String>,
 
+    /// Alias -> item name
+    aliases: HashMap<String, String>,
+
+    /// Names and aliases to match, rebuilt when either changes
+    terms: Vec<Term>,
+
     /// Action item patterns
     action_patterns: Vec<Regex>,
 
     /// Tag pattern (hashtags)
     tag_pattern: Regex,
 
-    /// Urgency patterns
-    urgency_patterns: Vec<Regex>,
+    /// File path pattern
+    file_pattern: Regex,
 
-    /// Positive sentiment patterns
-    positive_patterns: Vec<Regex>,
+    /// Crate name patterns (cargo output, paths, `use` lines)
+    crate_patterns: Vec<Regex>,
 
-    /// Negative sentiment patterns
-    negative_patterns: Vec<Regex>,
+    /// Sentiment model
+    sentiment: SentimentModel,
 }
 
 impl Default for ContextExtractor {
@@ -83,6 +337,8 @@ impl ContextExtractor {
     pub fn new() -> Self {
         Self {
             known_items: HashSet::new(),
+            aliases: HashMap::new(),
+            terms: Vec::new(),
             action_patterns: vec![
                 Regex::new(r"(?i)\b(todo|need to|should|must|have to|going to|will)\s+(.+?)(?:\.|$)")
                     .unwrap(),
@@ -90,16 +346,17 @@ impl ContextExtractor {
                 Regex::new(r"(?i)^-\s+(.+)").unwrap(),      // - bullet style
             ],
             tag_pattern: Regex::new(r"#(\w+)").unwrap(),
-            urgency_patterns: vec![
-                Regex::new(r"(?i)\b(urgent|asap|critical|immediately|now|priority)\b").unwrap(),
-            ],
-            positive_patterns: vec![
-                Regex::new(r"(?i)\b(good|great|excellent|progress|done|completed|finished)\b")
-                    .unwrap(),
-            ],
-            negative_patterns: vec![
-                Regex::new(r"(?i)\b(problem|issue|bug|broken|failed|stuck|blocked)\b").unwrap(),
+            file_pattern: Regex::new(
+                r"(?:^|[\s'`(\[=])((?:\.{1,2}/|/)?(?:[\w.-]+/)*[\w-]+\.(?:rs|toml|lock|md|json|ya?ml|py|js|ts|tsx|go|c|h|cpp|sh|sql|html|css))(?::\d+){0,2}\b",
+            )
+            .unwrap(),
+            crate_patterns: vec![
+                Regex::new(r"\b(?:Compiling|Checking|Documenting|Running|Fresh)\s+([a-zA-Z][\w-]*)\s+v\d").unwrap(),
+                Regex::new(r"\bcrates/([a-zA-Z][\w-]*)/").unwrap(),
+                Regex::new(r"(?:-p|--package)\s+([a-zA-Z][\w-]*)").unwrap(),
+                Regex::new(r"\buse\s+([a-z][a-z0-9_]*)::").unwrap(),
             ],
+            sentiment: SentimentModel::new(),
         }
     }
 
@@ -108,6 +365,7 @@ impl ContextExtractor {
         for item in items {
             self.known_items.insert(item.into().to_lowercase());
         }
+        self.rebuild_terms();
     }
 
     /// Set known items (replaces existing)
@@ -116,18 +374,83 @@ impl ContextExtractor {
         self.add_known_items(items);
     }
 
-    /// Extract context from a message
-    pub fn extract(&self, message: &str) -> ExtractedContext {
-        let mut ctx = ExtractedContext::default();
-        let message_lower = message.to_lowercase();
+    /// Map an alias to an item name
+    pub fn add_alias(&mut self, item: impl Into<String>, alias: impl Into<String>) {
+        self.aliases
+            .insert(alias.into().to_lowercase(), item.into().to_lowercase());
+        self.rebuild_terms();
+    }
 
-        // Extract mentions (known items that appear in message)
-        for item in &self.known_items {
-            if message_lower.contains(item) {
-                ctx.mentions.push(item.clone());
+    /// Set aliases (replaces existing), as (item, alias) pairs
+    pub fn set_aliases(&mut self, aliases: impl IntoIterator<Item = (String, String)>) {
+        self.aliases = aliases
+            .into_iter()
+            .map(|(item, alias)| (alias.to_lowercase(), item.to_lowercase()))
+            .collect();
+        self.rebuild_terms();
+    }
+
+    /// Replace the sentiment model
+    pub fn set_sentiment_model(&mut self, model: SentimentModel) {
+        self.sentiment = model;
+    }
+
+    fn rebuild_terms(&mut self) {
+        let names = self.known_items.iter().map(|name| (name.as_str(), name.as_str()));
+        let aliases = self
+            .aliases
+            .iter()
+            .map(|(alias, item)| (alias.as_str(), item.as_str()));
+        let mut terms: Vec<Term> = names
+            .chain(aliases)
+            .filter_map(|(text, item)| Term::new(text, item))
+            .collect();
+        // Longest first, so "gently core" wins over "core"
+        terms.sort_by(|a, b| b.tokens.len().cmp(&a.tokens.len()).then(a.joined.cmp(&b.joined)));
+        self.terms = terms;
+    }
+
+    /// Known items mentioned, in order of first appearance
+    fn find_mentions(&self, message: &str) -> Vec<String> {
+        let words = tokenize(message);
+        let mut mentions: Vec<String> = Vec::new();
+
+        let mut start = 0;
+        while start < words.len() {
+            // A word that exactly names an item is never a typo of another
+            let exact_item = self
+                .terms
+                .iter()
+                .any(|t| t.tokens.len() == 1 && t.tokens[0] == words[start]);
+            let found = self.terms.iter().find_map(|term| {
+                let exact_start = term.tokens[0] == words[start];
+                if exact_item && !exact_start && term.tokens.len() == 1 {
+                    return None;
+                }
+                term.matches_at(&words, start).map(|len| (term, len))
+            });
+
+            match found {
+                Some((term, len)) => {
+                    if !mentions.contains(&term.item) {
+                        mentions.push(term.item.clone());
+                    }
+                    start += len;
+                }
+                None => start += 1,
             }
         }
 
+        mentions
+    }
+
+    /// Extract context from a message
+    pub fn extract(&self, message: &str) -> ExtractedContext {
+        let mut ctx = ExtractedContext {
+            mentions: self.find_mentions(message),
+            ..Default::default()
+        };
+
         // Extract action items
         for pattern in &self.action_patterns {
             for caps in pattern.captures_iter(message) {
@@ -161,39 +484,67 @@ impl ContextExtractor {
     }
 
     /// Extract context from command and output
+    ///
+    /// Action items come from the command only; tool output is full of
+    /// "should"/"will" that are not the user's intentions.
     pub fn from_command(&self, command: &str, output: &str) -> ExtractedContext {
-        let mut ctx = self.extract(&format!("{} {}", command, output));
+        let combined = format!("{} {}", command, output);
+        let mut ctx = self.extract(&combined);
+        ctx.action_items = self.extract(command).action_items;
         ctx.command = Some(command.to_string());
-        ctx
-    }
-
-    /// Detect sentiment from message
-    fn detect_sentiment(&self, message: &str) -> Sentiment {
-        // Check for urgency first (highest priority)
-        for pattern in &self.urgency_patterns {
-            if pattern.is_match(message) {
-                return Sentiment::Urgent;
+        ctx.files = self.extract_files(&combined);
+        ctx.crates = self.extract_crates(&combined);
+
+        // Crates and file stems that name known items count as mentions
+        let code_words: Vec<String> = ctx
+            .crates
+            .iter()
+            .cloned()
+            .chain(ctx.files.iter().filter_map(|f| {
+                std::path::Path::new(f)
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+            }))
+            .collect();
+        for mention in self.find_mentions(&code_words.join(" ")) {
+            if !ctx.mentions.contains(&mention) {
+                ctx.mentions.push(mention);
             }
         }
 
-        let mut positive_count = 0;
-        let mut negative_count = 0;
+        ctx
+    }
 
-        for pattern in &self.positive_patterns {
-            positive_count += pattern.find_iter(message).count();
+    /// File paths in text (line/column suffixes stripped)
+    pub fn extract_files(&self, text: &str) -> Vec<String> {
+        let mut files: Vec<String> = Vec::new();
+        for caps in self.file_pattern.captures_iter(text) {
+            let path = caps[1].to_string();
+            if !files.contains(&path) {
+                files.push(path);
+            }
         }
+        files
+    }
 
-        for pattern in &self.negative_patterns {
-            negative_count += pattern.find_iter(message).count();
+    /// Crate names in text (`use` paths normalized to hyphens)
+    pub fn extract_crates(&self, text: &str) -> Vec<String> {
+        const NOT_CRATES: [&str; 5] = ["std", "core", "alloc", "crate", "super"];
+        let mut crates: Vec<String> = Vec::new();
+        for pattern in &self.crate_patterns {
+            for caps in pattern.captures_iter(text) {
+                let name = caps[1].replace('_', "-");
+                if !NOT_CRATES.contains(&name.as_str()) && name != "self" && !crates.contains(&name) {
+                    crates.push(name);
+                }
+            }
         }
+        crates
+    }
 
-        if positive_count > negative_count {
-            Sentiment::Positive
-        } else if negative_count > positive_count {
-            Sentiment::Negative
-        } else {
-            Sentiment::Neutral
-        }
+    /// Detect sentiment from message
+    fn detect_sentiment(&self, message: &str) -> Sentiment {
+        self.sentiment.classify(message)
     }
 }
 
@@ -243,4 +594,72 @@ mod tests {
         let ctx = extractor.extract("URGENT: need this ASAP");
         assert_eq!(ctx.sentiment, Sentiment::Urgent);
     }
+
+    #[test]
+    fn test_code_aware_command() {
+        let mut extractor = ContextExtractor::new();
+        extractor.add_known_items(["gently-feed", "vault"]);
+
+        let output = "   Compiling gently-feed v1.0.0 (/root/crate/crates/gently-feed)\n\
+                      error[E0425]: cannot find value `x`\n  --> crates/gently-core/src/vault.rs:58:5\n\
+                      help: you should import it";
+        let ctx = extractor.from_command("cargo build -p gently-feed", output);
+
+        assert_eq!(ctx.files, vec!["crates/gently-core/src/vault.rs"]);
+        assert_eq!(ctx.crates, vec!["gently-feed", "gently-core"]);
+        assert!(ctx.mentions.contains(&"gently-feed".to_string()));
+        assert!(ctx.mentions.contains(&"vault".to_string()));
+        assert!(ctx.action_items.is_empty());
+        assert_eq!(ctx.sentiment, Sentiment::Negative);
+    }
+
+    /// (text, expected mentions in order, expected sentiment)
+    const FIXTURES: &[(&str, &[&str], Sentiment)] = &[
+        ("Working on GentlyOS and BoneBlob integration", &["gentlyos", "boneblob"], Sentiment::Neutral),
+        ("Our score went up this week", &[], Sentiment::Neutral),
+        ("The core dumped again", &["core"], Sentiment::Neutral),
+        ("The gently core crate compiles", &["gently-core"], Sentiment::Neutral),
+        ("Alex is finished, review it", &["alexandria"], Sentiment::Positive),
+        ("Pushed the lambdacadabre change", &["lambdacadabra"], Sentiment::Neutral),
+        ("bone blob works great", &["boneblob"], Sentiment::Positive),
+        ("Dance Protocol review tomorrow", &["dance protocol"], Sentiment::Neutral),
+        ("Scatterbrain is stuck on the parser bug", &["scatterbrain"], Sentiment::Negative),
+        ("This is not good, the build is broken", &[], Sentiment::Negative),
+        ("Not broken anymore, tests are passing", &[], Sentiment::Positive),
+        ("It was a problem but it's fixed and shipped", &[], Sentiment::Positive),
+        ("Looks good but the release is blocked", &[], Sentiment::Negative),
+        ("Need this right now", &[], Sentiment::Neutral),
+        ("Not urgent, whenever you have time", &[], Sentiment::Neutral),
+        ("URGENT: production is down, fix ASAP!", &[], Sentiment::Urgent),
+        ("High priority: the deadline is today", &[], Sentiment::Urgent),
+        ("really great work on the vault", &["vault"], Sentiment::Positive),
+        ("Valut migration is slow", &["vault"], Sentiment::Negative),
+        ("the scorecard looks fine", &[], Sentiment::Neutral),
+    ];
+
+    #[test]
+    fn test_evaluation_fixtures() {
+        let mut extractor = ContextExtractor::new();
+        extractor.add_known_items([
+            "gentlyos",
+            "boneblob",
+            "core",
+            "gently-core",
+            "alexandria",
+            "lambdacadabra",
+            "dance protocol",
+            "scatterbrain",
+            "vault",
+        ]);
+        extractor.add_alias("alexandria", "alex");
+
+        let mut failures = Vec::new();
+        for (text, mentions, sentiment) in FIXTURES {
+            let ctx = extractor.extract(text);
+            if ctx.mentions != *mentions || ctx.sentiment != *sentiment {
+                failures.push(format!("{:?}: got {:?} / {:?}", text, ctx.mentions, ctx.sentiment));
+            }
+        }
+        assert!(failures.is_empty(), "fixture failures:\n{}", failures.join("\n"));
+    }
 }
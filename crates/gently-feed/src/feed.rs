@@ -5,14 +5,30 @@
 
 use crate::{
     bridge::{Bridge, BridgeDetector, BridgeKind},
+    decay::{self, DecayPolicy, DecayProjection, DecayStep, Simulation},
     extractor::{ContextExtractor, ExtractedContext},
+    history::{self, FeedHistory, FeedOp, HistoryEntry},
     item::{FeedItem, ItemKind, ItemState},
     persistence::FeedState,
     xor_chain::XorChain,
 };
+use chrono::{DateTime, TimeZone, Utc};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+/// What a [`LivingFeed::merge`] changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    /// Items that only existed in the other feed
+    pub items_added: usize,
+    /// Items present in both feeds (charges summed)
+    pub items_combined: usize,
+    /// Bridges that only existed in the other feed
+    pub bridges_added: usize,
+    /// Bridges present in both feeds
+    pub bridges_combined: usize,
+}
+
 /// The Living Feed - self-tracking context system
 #[derive(Debug, Clone)]
 pub struct LivingFeed {
@@ -39,6 +55,18 @@ pub struct LivingFeed {
 
     /// Name to ID lookup cache
     name_index: HashMap<String, Uuid>,
+
+    /// How charge decays
+    decay_policy: DecayPolicy,
+
+    /// When decay was last applied
+    last_decay: DateTime<Utc>,
+
+    /// Undo/redo log of operations
+    history: FeedHistory,
+
+    /// Inside a recorded operation (nested ops fold into it)
+    recording: bool,
 }
 
 impl Default for LivingFeed {
@@ -59,6 +87,10 @@ impl LivingFeed {
             focus_stack: Vec::new(),
             interaction_count: 0,
             name_index: HashMap::new(),
+            decay_policy: DecayPolicy::default(),
+            last_decay: Utc::now(),
+            history: FeedHistory::default(),
+            recording: false,
         }
     }
 
@@ -73,6 +105,14 @@ impl LivingFeed {
             focus_stack: Vec::new(),
             interaction_count: state.interaction_count,
             name_index: HashMap::new(),
+            decay_policy: state.decay_policy,
+            last_decay: Utc
+                .timestamp_millis_opt(state.last_tick as i64)
+                .single()
+                .filter(|_| state.last_tick > 0)
+                .unwrap_or_else(Utc::now),
+            history: state.history,
+            recording: false,
         };
 
         // Rebuild indices
@@ -88,8 +128,10 @@ impl LivingFeed {
             items: self.items.clone(),
             bridges: self.bridges.clone(),
             xor_chain: self.xor_chain.clone(),
-            last_tick: chrono::Utc::now().timestamp_millis() as u64,
+            last_tick: self.last_decay.timestamp_millis() as u64,
             interaction_count: self.interaction_count,
+            decay_policy: self.decay_policy.clone(),
+            history: self.history.clone(),
         }
     }
 
@@ -104,6 +146,9 @@ impl LivingFeed {
         // Update extractor with known items
         self.extractor
             .set_known_items(self.items.iter().map(|i| i.name.to_lowercase()));
+        self.extractor.set_aliases(self.items.iter().flat_map(|i| {
+            i.aliases.iter().map(move |a| (i.name.clone(), a.clone()))
+        }));
 
         // Rebuild focus stack from charge order
         let mut sorted: Vec<_> = self
@@ -121,17 +166,19 @@ impl LivingFeed {
     /// Add a new item to the feed
     pub fn add_item(&mut self, name: impl Into<String>, kind: ItemKind) -> Uuid {
         let name = name.into();
-        let item = FeedItem::new(&name, kind);
-        let id = item.id;
+        self.record(FeedOp::Add { name: name.clone() }, |feed| {
+            let item = FeedItem::new(&name, kind);
+            let id = item.id;
 
-        self.name_index.insert(name.to_lowercase(), id);
-        self.focus_stack.insert(0, id); // New items go to top
-        self.items.push(item);
+            feed.name_index.insert(name.to_lowercase(), id);
+            feed.focus_stack.insert(0, id); // New items go to top
+            feed.items.push(item);
 
-        // Update extractor
-        self.extractor.add_known_items([name.to_lowercase()]);
+            // Update extractor
+            feed.extractor.add_known_items([name.to_lowercase()]);
 
-        id
+            id
+        })
     }
 
     /// Get item by ID
@@ -165,6 +212,27 @@ impl LivingFeed {
         }
     }
 
+    /// Add another name an item is mentioned by
+    pub fn add_alias(&mut self, name: &str, alias: &str) -> bool {
+        let op = FeedOp::Alias {
+            item: name.to_string(),
+            alias: alias.to_string(),
+        };
+        self.record(op, |feed| {
+            let Some(item) = feed.get_item_by_name_mut(name) else {
+                return false;
+            };
+            let alias = alias.trim().to_lowercase();
+            if alias.is_empty() || item.aliases.contains(&alias) {
+                return false;
+            }
+            item.aliases.push(alias.clone());
+            let canonical = item.name.clone();
+            feed.extractor.add_alias(canonical, alias);
+            true
+        })
+    }
+
     /// Get all items
     pub fn items(&self) -> &[FeedItem] {
         &self.items
@@ -177,102 +245,162 @@ impl LivingFeed {
 
     /// Remove an item
     pub fn remove_item(&mut self, id: Uuid) -> Option<FeedItem> {
-        if let Some(pos) = self.items.iter().position(|i| i.id == id) {
-            let item = self.items.remove(pos);
-            self.name_index.remove(&item.name.to_lowercase());
-            self.focus_stack.retain(|i| *i != id);
-            self.bridges.retain(|b| !b.connects(id));
-            Some(item)
-        } else {
-            None
-        }
+        let name = self.get_item(id).map(|i| i.name.clone()).unwrap_or_default();
+        self.record(FeedOp::Remove { name }, |feed| {
+            if let Some(pos) = feed.items.iter().position(|i| i.id == id) {
+                let item = feed.items.remove(pos);
+                feed.name_index.remove(&item.name.to_lowercase());
+                feed.focus_stack.retain(|i| *i != id);
+                feed.bridges.retain(|b| !b.connects(id));
+                Some(item)
+            } else {
+                None
+            }
+        })
     }
 
     // ============== Charge Operations ==============
 
     /// Boost an item's charge
     pub fn boost(&mut self, name: &str, amount: f32) -> bool {
-        if let Some(item) = self.get_item_by_name_mut(name) {
-            item.boost(amount);
+        self.record(FeedOp::Boost { name: name.to_string(), amount }, |feed| {
+            if let Some(item) = feed.get_item_by_name_mut(name) {
+                item.boost(amount);
+
+                // Move to top of focus stack
+                let id = item.id;
+                feed.focus_stack.retain(|i| *i != id);
+                feed.focus_stack.insert(0, id);
+
+                true
+            } else {
+                false
+            }
+        })
+    }
 
-            // Move to top of focus stack
-            let id = item.id;
-            self.focus_stack.retain(|i| *i != id);
-            self.focus_stack.insert(0, id);
+    /// Decay all items by one tick (plus wall-clock time since the last decay)
+    pub fn decay_all(&mut self) {
+        self.decay_at(Utc::now(), 1);
+    }
 
-            true
-        } else {
-            false
-        }
+    /// Apply decay for real time elapsed up to `now`, without counting a tick
+    pub fn catch_up(&mut self, now: DateTime<Utc>) {
+        self.decay_at(now, 0);
     }
 
-    /// Decay all items
-    pub fn decay_all(&mut self) {
-        for item in &mut self.items {
-            item.decay();
-        }
+    fn decay_at(&mut self, now: DateTime<Utc>, ticks: u32) {
+        self.record(FeedOp::Decay { ticks }, |feed| {
+            let step = DecayStep {
+                elapsed: (now - feed.last_decay).max(chrono::Duration::zero()),
+                ticks,
+                now,
+            };
+            for item in &mut feed.items {
+                feed.decay_policy.apply(item, &step);
+            }
+            feed.last_decay = feed.last_decay.max(now);
+        })
+    }
+
+    /// Current decay policy
+    pub fn decay_policy(&self) -> &DecayPolicy {
+        &self.decay_policy
+    }
+
+    /// Replace the decay policy
+    pub fn set_decay_policy(&mut self, policy: DecayPolicy) {
+        self.decay_policy = policy;
+    }
+
+    /// Project when each item goes from its current state to Frozen if left alone
+    pub fn project_decay(&self, sim: &Simulation) -> Vec<DecayProjection> {
+        decay::project(&self.items, &self.decay_policy, sim, self.last_decay.max(Utc::now()))
     }
 
     /// Freeze an item (set charge to 0)
     pub fn freeze(&mut self, name: &str) -> bool {
-        if let Some(item) = self.get_item_by_name_mut(name) {
-            item.charge = 0.0;
-            item.update_state();
-            true
-        } else {
-            false
-        }
+        self.record(FeedOp::Freeze { name: name.to_string() }, |feed| {
+            if let Some(item) = feed.get_item_by_name_mut(name) {
+                item.charge = 0.0;
+                item.update_state();
+                true
+            } else {
+                false
+            }
+        })
     }
 
     /// Archive an item
     pub fn archive(&mut self, name: &str) -> bool {
-        if let Some(item) = self.get_item_by_name_mut(name) {
-            item.archive();
-            let id = item.id;
-            self.focus_stack.retain(|i| *i != id);
-            true
-        } else {
-            false
-        }
+        self.record(FeedOp::Archive { name: name.to_string() }, |feed| {
+            if let Some(item) = feed.get_item_by_name_mut(name) {
+                item.archive();
+                let id = item.id;
+                feed.focus_stack.retain(|i| *i != id);
+                true
+            } else {
+                false
+            }
+        })
     }
 
     // ============== Step Management ==============
 
     /// Add a step to an item
     pub fn add_step(&mut self, item_name: &str, step_content: impl Into<String>) -> Option<u32> {
-        self.get_item_by_name_mut(item_name)
-            .map(|item| item.add_step(step_content))
+        let content = step_content.into();
+        let op = FeedOp::Step {
+            item: item_name.to_string(),
+            content: content.clone(),
+        };
+        self.record(op, |feed| {
+            feed.get_item_by_name_mut(item_name)
+                .map(|item| item.add_step(content))
+        })
     }
 
     /// Complete a step
     pub fn complete_step(&mut self, item_name: &str, step_id: u32) -> bool {
-        self.get_item_by_name_mut(item_name)
-            .map(|item| item.complete_step(step_id))
-            .unwrap_or(false)
+        let op = FeedOp::CompleteStep {
+            item: item_name.to_string(),
+            step_id,
+        };
+        self.record(op, |feed| {
+            feed.get_item_by_name_mut(item_name)
+                .map(|item| item.complete_step(step_id))
+                .unwrap_or(false)
+        })
     }
 
     // ============== Bridge Operations ==============
 
     /// Create or reinforce a bridge between items
     pub fn bridge(&mut self, name1: &str, name2: &str, kind: BridgeKind) -> bool {
-        let id1 = match self.name_index.get(&name1.to_lowercase()) {
-            Some(&id) => id,
-            None => return false,
-        };
-
-        let id2 = match self.name_index.get(&name2.to_lowercase()) {
-            Some(&id) => id,
-            None => return false,
+        let op = FeedOp::Bridge {
+            from: name1.to_string(),
+            to: name2.to_string(),
         };
+        self.record(op, |feed| {
+            let id1 = match feed.name_index.get(&name1.to_lowercase()) {
+                Some(&id) => id,
+                None => return false,
+            };
+
+            let id2 = match feed.name_index.get(&name2.to_lowercase()) {
+                Some(&id) => id,
+                None => return false,
+            };
+
+            // Check if bridge already exists
+            if let Some(bridge) = feed.bridges.iter_mut().find(|b| b.connects_pair(id1, id2)) {
+                bridge.reinforce();
+            } else {
+                feed.bridges.push(Bridge::new(id1, id2, kind));
+            }
 
-        // Check if bridge already exists
-        if let Some(bridge) = self.bridges.iter_mut().find(|b| b.connects_pair(id1, id2)) {
-            bridge.reinforce();
-        } else {
-            self.bridges.push(Bridge::new(id1, id2, kind));
-        }
-
-        true
+            true
+        })
     }
 
     /// Get bridges for an item
@@ -291,44 +419,215 @@ impl LivingFeed {
         bridges.into_iter().take(limit).collect()
     }
 
+    // ============== Merging ==============
+
+    /// Merge another feed into this one, matching items and bridges by name
+    pub fn merge(&mut self, other: &LivingFeed) -> MergeReport {
+        self.record(FeedOp::Merge { from: other.xor_chain.genesis.clone() }, |feed| {
+            let mut report = MergeReport::default();
+            let mut id_map: HashMap<Uuid, Uuid> = HashMap::new();
+
+            for theirs in &other.items {
+                let key = theirs.name.to_lowercase();
+                match feed.name_index.get(&key).copied() {
+                    Some(id) => {
+                        let ours = feed.get_item_mut(id).expect("indexed item exists");
+                        ours.charge = (ours.charge + theirs.charge).min(1.0);
+                        ours.pinned |= theirs.pinned;
+                        ours.archived &= theirs.archived;
+                        ours.created_at = ours.created_at.min(theirs.created_at);
+                        ours.last_touched = ours.last_touched.max(theirs.last_touched);
+                        for tag in &theirs.tags {
+                            if !ours.tags.contains(tag) {
+                                ours.tags.push(tag.clone());
+                            }
+                        }
+                        for step in &theirs.steps {
+                            match ours.steps.iter_mut().find(|s| s.content == step.content) {
+                                Some(existing) if step.completed && !existing.completed => {
+                                    existing.completed = true;
+                                    existing.completed_at = step.completed_at;
+                                }
+                                Some(_) => {}
+                                None => {
+                                    let mut step = step.clone();
+                                    step.id = ours.steps.len() as u32 + 1;
+                                    ours.steps.push(step);
+                                }
+                            }
+                        }
+                        ours.snapshots.extend(theirs.snapshots.iter().cloned());
+                        ours.update_state();
+                        id_map.insert(theirs.id, id);
+                        report.items_combined += 1;
+                    }
+                    None => {
+                        let mut item = theirs.clone();
+                        // Keep ids unique even if both feeds descend from one export
+                        if feed.items.iter().any(|i| i.id == item.id) {
+                            item.id = Uuid::new_v4();
+                        }
+                        id_map.insert(theirs.id, item.id);
+                        feed.name_index.insert(key, item.id);
+                        feed.items.push(item);
+                        report.items_added += 1;
+                    }
+                }
+            }
+
+            for theirs in &other.bridges {
+                let (Some(&from), Some(&to)) = (id_map.get(&theirs.from_id), id_map.get(&theirs.to_id)) else {
+                    continue;
+                };
+                match feed.bridges.iter_mut().find(|b| b.connects_pair(from, to)) {
+                    Some(ours) => {
+                        ours.strength = ours.strength.max(theirs.strength);
+                        ours.reinforcement_count += theirs.reinforcement_count;
+                        ours.last_reinforced = ours.last_reinforced.max(theirs.last_reinforced);
+                        report.bridges_combined += 1;
+                    }
+                    None => {
+                        let mut bridge = theirs.clone();
+                        bridge.id = Uuid::new_v4();
+                        bridge.from_id = from;
+                        bridge.to_id = to;
+                        feed.bridges.push(bridge);
+                        report.bridges_added += 1;
+                    }
+                }
+            }
+
+            feed.interaction_count += other.interaction_count;
+            feed.xor_chain.advance(&format!(
+                "merge:{}:{}",
+                other.xor_chain.genesis, other.xor_chain.current
+            ));
+            feed.rebuild_indices();
+
+            report
+        })
+    }
+
+    // ============== History ==============
+
+    /// Run `f` as one recorded operation
+    fn record<R>(&mut self, op: FeedOp, f: impl FnOnce(&mut Self) -> R) -> R {
+        if self.recording {
+            return f(self);
+        }
+
+        let items = self.items.clone();
+        let bridges = self.bridges.clone();
+        self.recording = true;
+        let result = f(self);
+        self.recording = false;
+
+        let changes = history::diff(&items, &bridges, &self.items, &self.bridges);
+        if !changes.is_empty() {
+            self.history.push(op, changes, Utc::now());
+        }
+        result
+    }
+
+    /// Operation history
+    pub fn history(&self) -> &FeedHistory {
+        &self.history
+    }
+
+    /// Undo up to `n` operations, returning what was undone (latest first)
+    pub fn undo(&mut self, n: usize) -> Vec<FeedOp> {
+        let mut undone = Vec::new();
+        for _ in 0..n {
+            let Some(entry) = self.history.step_back() else {
+                break;
+            };
+            history::apply(&entry, &mut self.items, &mut self.bridges, false);
+            undone.push(entry.op);
+        }
+        self.rebuild_indices();
+        undone
+    }
+
+    /// Redo up to `n` undone operations
+    pub fn redo(&mut self, n: usize) -> Vec<FeedOp> {
+        let mut redone = Vec::new();
+        for _ in 0..n {
+            let Some(entry) = self.history.step_forward() else {
+                break;
+            };
+            history::apply(&entry, &mut self.items, &mut self.bridges, true);
+            redone.push(entry.op);
+        }
+        self.rebuild_indices();
+        redone
+    }
+
+    /// Items as they were at `t` (limited to the retained history)
+    pub fn items_at(&self, t: DateTime<Utc>) -> Vec<FeedItem> {
+        let mut items = self.items.clone();
+        let mut bridges = self.bridges.clone();
+        for entry in self.history.applied().iter().rev().take_while(|e| e.at > t) {
+            history::apply(entry, &mut items, &mut bridges, false);
+        }
+        items
+    }
+
+    /// Items that were hot at `t`
+    pub fn hot_at(&self, t: DateTime<Utc>) -> Vec<FeedItem> {
+        let mut hot: Vec<_> = self
+            .items_at(t)
+            .into_iter()
+            .filter(|i| !i.archived && i.charge > 0.8)
+            .collect();
+        hot.sort_by(|a, b| b.charge.total_cmp(&a.charge));
+        hot
+    }
+
+    /// Applied history entries, most recent first
+    pub fn recent_history(&self, limit: usize) -> Vec<&HistoryEntry> {
+        self.history.applied().iter().rev().take(limit).collect()
+    }
+
     // ============== Context Processing ==============
 
     /// Process a context update (the main tick loop)
     pub fn tick(&mut self, ctx: &ExtractedContext) {
-        self.interaction_count += 1;
+        self.record(FeedOp::Tick { mentions: ctx.mentions.clone() }, |feed| {
+            feed.interaction_count += 1;
 
-        // 1. Decay all charges
-        self.decay_all();
+            // 1. Decay all charges
+            feed.decay_all();
 
-        // 2. Boost mentioned items
-        let boost_multiplier = ctx.sentiment.boost_multiplier();
-        for mention in &ctx.mentions {
-            if let Some(item) = self.get_item_by_name_mut(mention) {
-                item.boost(0.3 * boost_multiplier);
+            // 2. Boost mentioned items
+            let boost_multiplier = ctx.sentiment.boost_multiplier();
+            for mention in &ctx.mentions {
+                if let Some(item) = feed.get_item_by_name_mut(mention) {
+                    item.boost(0.3 * boost_multiplier);
+                }
             }
-        }
 
-        // 3. Process bridge candidates
-        for (name1, name2) in &ctx.bridge_candidates {
-            self.bridge(name1, name2, BridgeKind::Mention);
-        }
+            // 3. Process bridge candidates
+            for (name1, name2) in &ctx.bridge_candidates {
+                feed.bridge(name1, name2, BridgeKind::Mention);
+            }
 
-        // 4. Add action items as steps to current focus
-        if !ctx.action_items.is_empty() {
-            if let Some(&focus_id) = self.focus_stack.first() {
-                if let Some(item) = self.get_item_mut(focus_id) {
-                    for action in &ctx.action_items {
-                        item.add_step(action);
+            // 4. Add action items as steps to current focus
+            if !ctx.action_items.is_empty() {
+                if let Some(&focus_id) = feed.focus_stack.first() {
+                    if let Some(item) = feed.get_item_mut(focus_id) {
+                        for action in &ctx.action_items {
+                            item.add_step(action);
+                        }
                     }
                 }
             }
-        }
 
-        // 5. Auto-rotate if needed (promote highest cooling if no hot)
-        self.auto_rotate();
+            // 5. Auto-rotate if needed (promote highest cooling if no hot)
+            feed.auto_rotate();
 
-        // 6. Update focus stack order
-        self.reorder_focus_stack();
+            // 6. Update focus stack order
+            feed.reorder_focus_stack();
+        })
     }
 
     /// Process text directly (extracts context then ticks)
@@ -420,6 +719,11 @@ impl LivingFeed {
         &self.xor_chain
     }
 
+    /// Record an event on the XOR chain
+    pub fn advance_chain(&mut self, content: &str) -> String {
+        self.xor_chain.advance(content)
+    }
+
     // ============== Rendering ==============
 
     /// Render feed summary
@@ -476,6 +780,18 @@ impl LivingFeed {
             }
         }
 
+        let history = self.recent_history(5);
+        if !history.is_empty() {
+            out.push_str("\n🕘 HISTORY\n");
+            for entry in history {
+                out.push_str(&format!(
+                    "  {} {}\n",
+                    entry.at.with_timezone(&chrono::Local).format("%a %H:%M"),
+                    entry.op
+                ));
+            }
+        }
+
         out.push_str(&format!("\n{}\n", self.xor_chain.render()));
 
         out
@@ -548,4 +864,97 @@ mod tests {
         let item = feed.get_item_by_name("project").unwrap();
         assert_eq!(item.pending_steps().len(), 1);
     }
+
+    #[test]
+    fn test_merge_combines_by_name() {
+        let mut ours = LivingFeed::new();
+        ours.add_item("GentlyOS", ItemKind::Project);
+        ours.add_item("BoneBlob", ItemKind::Project);
+        ours.add_step("gentlyos", "Write docs");
+        ours.get_item_by_name_mut("gentlyos").unwrap().charge = 0.3;
+        ours.bridge("gentlyos", "boneblob", BridgeKind::Mention);
+
+        let mut theirs = LivingFeed::new();
+        theirs.add_item("gentlyos", ItemKind::Project);
+        theirs.add_item("Alexandria", ItemKind::Project);
+        theirs.add_item("BoneBlob", ItemKind::Project);
+        theirs.add_step("gentlyos", "Write docs");
+        theirs.add_step("gentlyos", "Ship release");
+        theirs.get_item_by_name_mut("gentlyos").unwrap().charge = 0.4;
+        theirs.bridge("gentlyos", "alexandria", BridgeKind::Mention);
+        theirs.bridge("gentlyos", "boneblob", BridgeKind::Mention);
+
+        let head = ours.xor_chain().current.clone();
+        let report = ours.merge(&theirs);
+
+        assert_eq!(report.items_added, 1);
+        assert_eq!(report.items_combined, 2);
+        assert_eq!(report.bridges_added, 1);
+        assert_eq!(report.bridges_combined, 1);
+
+        let merged = ours.get_item_by_name("GentlyOS").unwrap();
+        assert!((merged.charge - 0.7).abs() < 1e-6);
+        assert_eq!(merged.steps.len(), 2);
+        assert_eq!(ours.items().len(), 3);
+        assert_eq!(ours.bridges_for("alexandria").len(), 1);
+        assert_eq!(ours.bridges_for("boneblob")[0].reinforcement_count, 2);
+        assert_eq!(ours.xor_chain().previous, head);
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut feed = LivingFeed::new();
+        feed.add_item("GentlyOS", ItemKind::Project);
+        feed.add_item("BoneBlob", ItemKind::Project);
+        feed.freeze("gentlyos");
+        feed.process("Working on GentlyOS and BoneBlob integration");
+        assert_eq!(feed.bridges().len(), 1);
+
+        let undone = feed.undo(2);
+        assert!(matches!(undone[0], FeedOp::Tick { .. }));
+        assert!(matches!(undone[1], FeedOp::Freeze { .. }));
+        assert!(feed.bridges().is_empty());
+        assert_eq!(feed.get_item_by_name("gentlyos").unwrap().charge, 1.0);
+
+        feed.redo(1);
+        assert_eq!(feed.get_item_by_name("gentlyos").unwrap().charge, 0.0);
+
+        // A new operation drops the remaining redo
+        feed.boost("boneblob", 0.1);
+        assert!(!feed.history().can_redo());
+
+        feed.undo(10);
+        assert!(feed.items().is_empty());
+        assert!(feed.get_item_by_name("gentlyos").is_none());
+    }
+
+    #[test]
+    fn test_alias_mentions() {
+        let mut feed = LivingFeed::new();
+        feed.add_item("Alexandria Protocol", ItemKind::Project);
+        feed.get_item_by_name_mut("alexandria protocol").unwrap().charge = 0.5;
+        assert!(feed.add_alias("alexandria protocol", "alex"));
+
+        feed.process("alex is coming along");
+        assert!(feed.get_item_by_name("alexandria protocol").unwrap().charge > 0.7);
+
+        // Aliases survive a save/load round trip
+        let restored = LivingFeed::from_state(feed.to_state());
+        assert_eq!(restored.extractor.extract("ping alex").mentions, vec!["alexandria protocol"]);
+    }
+
+    #[test]
+    fn test_hot_at() {
+        let mut feed = LivingFeed::new();
+        feed.add_item("Tuesday Project", ItemKind::Project);
+        let tuesday = Utc::now();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        feed.freeze("tuesday project");
+        feed.add_item("Wednesday Project", ItemKind::Project);
+
+        let then: Vec<_> = feed.hot_at(tuesday).into_iter().map(|i| i.name).collect();
+        assert_eq!(then, vec!["Tuesday Project"]);
+        assert_eq!(feed.hot_items()[0].name, "Wednesday Project");
+        assert!(feed.render_full().contains("freeze tuesday project"));
+    }
 }
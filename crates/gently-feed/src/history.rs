@@ -0,0 +1,381 @@
+//! Feed history - event log with undo/redo
+//!
+//! Every mutating [`LivingFeed`](crate::LivingFeed) operation is recorded as a
+//! [`HistoryEntry`]: what was done ([`FeedOp`]) plus the exact [`Change`]s it
+//! made, so it can be reverted and replayed.
+//!
+//! ```text
+//!  entries:  [add] [boost] [tick] [freeze] [bridge] [step]
+//!                                  ▲ cursor
+//!            ◄──── applied ────►   ◄── redo-able ──►
+//!
+//!  undo  → revert entry before cursor, cursor -= 1
+//!  redo  → reapply entry at cursor,    cursor += 1
+//!  new op after undo drops the redo tail
+//! ```
+//!
+//! Rolling back every applied entry newer than `t` reconstructs the feed as
+//! it was at `t` (as far back as the retained history reaches).
+
+use crate::{Bridge, FeedItem};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use uuid::Uuid;
+
+/// Entries retained by default
+pub const DEFAULT_HISTORY_LIMIT: usize = 500;
+
+/// A recorded feed operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum FeedOp {
+    Add { name: String },
+    Remove { name: String },
+    Boost { name: String, amount: f32 },
+    Decay { ticks: u32 },
+    Freeze { name: String },
+    Archive { name: String },
+    Step { item: String, content: String },
+    CompleteStep { item: String, step_id: u32 },
+    Bridge { from: String, to: String },
+    Alias { item: String, alias: String },
+    Tick { mentions: Vec<String> },
+    Merge { from: String },
+}
+
+impl fmt::Display for FeedOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeedOp::Add { name } => write!(f, "add {}", name),
+            FeedOp::Remove { name } => write!(f, "remove {}", name),
+            FeedOp::Boost { name, amount } => write!(f, "boost {} (+{:.2})", name, amount),
+            FeedOp::Decay { ticks: 0 } => write!(f, "decay (elapsed time)"),
+            FeedOp::Decay { ticks } => write!(f, "decay x{}", ticks),
+            FeedOp::Freeze { name } => write!(f, "freeze {}", name),
+            FeedOp::Archive { name } => write!(f, "archive {}", name),
+            FeedOp::Step { item, content } => write!(f, "step {}: {}", item, content),
+            FeedOp::CompleteStep { item, step_id } => write!(f, "done {} #{}", item, step_id),
+            FeedOp::Bridge { from, to } => write!(f, "bridge {} <-> {}", from, to),
+            FeedOp::Alias { item, alias } => write!(f, "alias {} = {}", alias, item),
+            FeedOp::Tick { mentions } if mentions.is_empty() => write!(f, "tick"),
+            FeedOp::Tick { mentions } => write!(f, "tick ({})", mentions.join(", ")),
+            FeedOp::Merge { from } => write!(f, "merge {}", from),
+        }
+    }
+}
+
+/// One reversible change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum Change {
+    /// Only charge (and touch time) moved
+    Charge {
+        id: Uuid,
+        from: f32,
+        to: f32,
+        touched_from: DateTime<Utc>,
+        touched_to: DateTime<Utc>,
+    },
+    /// Item created, removed or otherwise edited
+    Item {
+        id: Uuid,
+        before: Option<Box<FeedItem>>,
+        after: Option<Box<FeedItem>>,
+    },
+    /// Bridge created, removed or reinforced
+    Bridge {
+        id: Uuid,
+        before: Option<Bridge>,
+        after: Option<Bridge>,
+    },
+}
+
+/// A recorded operation and its changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub seq: u64,
+    pub at: DateTime<Utc>,
+    pub op: FeedOp,
+    pub changes: Vec<Change>,
+}
+
+/// Bounded undo/redo log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedHistory {
+    entries: Vec<HistoryEntry>,
+    /// Entries before this index are applied
+    cursor: usize,
+    limit: usize,
+    next_seq: u64,
+}
+
+impl Default for FeedHistory {
+    fn default() -> Self {
+        Self::with_limit(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+impl FeedHistory {
+    /// History retaining at most `limit` entries
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            cursor: 0,
+            limit: limit.max(1),
+            next_seq: 1,
+        }
+    }
+
+    /// Record an operation (drops anything that could have been redone)
+    pub fn push(&mut self, op: FeedOp, changes: Vec<Change>, at: DateTime<Utc>) {
+        self.entries.truncate(self.cursor);
+        self.entries.push(HistoryEntry {
+            seq: self.next_seq,
+            at,
+            op,
+            changes,
+        });
+        self.next_seq += 1;
+        if self.entries.len() > self.limit {
+            let excess = self.entries.len() - self.limit;
+            self.entries.drain(..excess);
+        }
+        self.cursor = self.entries.len();
+    }
+
+    /// Step back, returning the entry to revert
+    pub(crate) fn step_back(&mut self) -> Option<HistoryEntry> {
+        if self.cursor == 0 {
+            return None;
+        }
+        self.cursor -= 1;
+        Some(self.entries[self.cursor].clone())
+    }
+
+    /// Step forward, returning the entry to reapply
+    pub(crate) fn step_forward(&mut self) -> Option<HistoryEntry> {
+        let entry = self.entries.get(self.cursor)?.clone();
+        self.cursor += 1;
+        Some(entry)
+    }
+
+    /// Applied entries, oldest first
+    pub fn applied(&self) -> &[HistoryEntry] {
+        &self.entries[..self.cursor]
+    }
+
+    /// Undone entries that can be redone, next first
+    pub fn redoable(&self) -> &[HistoryEntry] {
+        &self.entries[self.cursor..]
+    }
+
+    /// Applied entries in `[from, to]`
+    pub fn timeline(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> impl Iterator<Item = &HistoryEntry> {
+        self.applied()
+            .iter()
+            .filter(move |e| e.at >= from && e.at <= to)
+    }
+
+    /// Earliest time the history can reconstruct
+    pub fn horizon(&self) -> Option<DateTime<Utc>> {
+        self.entries.first().map(|e| e.at)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.cursor < self.entries.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Changes that turn `(items, bridges)` into `(items_after, bridges_after)`
+pub(crate) fn diff(
+    items: &[FeedItem],
+    bridges: &[Bridge],
+    items_after: &[FeedItem],
+    bridges_after: &[Bridge],
+) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    let before: HashMap<Uuid, &FeedItem> = items.iter().map(|i| (i.id, i)).collect();
+    let after: HashMap<Uuid, &FeedItem> = items_after.iter().map(|i| (i.id, i)).collect();
+    for old in items {
+        match after.get(&old.id) {
+            None => changes.push(Change::Item {
+                id: old.id,
+                before: Some(Box::new(old.clone())),
+                after: None,
+            }),
+            Some(new) if *new != old => {
+                let mut charge_only = (*new).clone();
+                charge_only.charge = old.charge;
+                charge_only.state = old.state;
+                charge_only.last_touched = old.last_touched;
+                if charge_only == *old {
+                    changes.push(Change::Charge {
+                        id: old.id,
+                        from: old.charge,
+                        to: new.charge,
+                        touched_from: old.last_touched,
+                        touched_to: new.last_touched,
+                    });
+                } else {
+                    changes.push(Change::Item {
+                        id: old.id,
+                        before: Some(Box::new(old.clone())),
+                        after: Some(Box::new((*new).clone())),
+                    });
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    for new in items_after.iter().filter(|i| !before.contains_key(&i.id)) {
+        changes.push(Change::Item {
+            id: new.id,
+            before: None,
+            after: Some(Box::new(new.clone())),
+        });
+    }
+
+    let after: HashMap<Uuid, &Bridge> = bridges_after.iter().map(|b| (b.id, b)).collect();
+    for old in bridges {
+        match after.get(&old.id) {
+            Some(new) if *new == old => {}
+            new => changes.push(Change::Bridge {
+                id: old.id,
+                before: Some(old.clone()),
+                after: new.map(|b| (*b).clone()),
+            }),
+        }
+    }
+    let before: HashMap<Uuid, &Bridge> = bridges.iter().map(|b| (b.id, b)).collect();
+    for new in bridges_after.iter().filter(|b| !before.contains_key(&b.id)) {
+        changes.push(Change::Bridge {
+            id: new.id,
+            before: None,
+            after: Some(new.clone()),
+        });
+    }
+
+    changes
+}
+
+/// Apply an entry's changes (`forward`) or revert them
+pub(crate) fn apply(entry: &HistoryEntry, items: &mut Vec<FeedItem>, bridges: &mut Vec<Bridge>, forward: bool) {
+    let ordered: Box<dyn Iterator<Item = &Change>> = if forward {
+        Box::new(entry.changes.iter())
+    } else {
+        Box::new(entry.changes.iter().rev())
+    };
+
+    for change in ordered {
+        match change {
+            Change::Charge {
+                id,
+                from,
+                to,
+                touched_from,
+                touched_to,
+            } => {
+                if let Some(item) = items.iter_mut().find(|i| i.id == *id) {
+                    let (charge, touched) = if forward { (to, touched_to) } else { (from, touched_from) };
+                    item.charge = *charge;
+                    item.last_touched = *touched;
+                    item.update_state();
+                }
+            }
+            Change::Item { id, before, after } => {
+                let target = if forward { after } else { before };
+                let pos = items.iter().position(|i| i.id == *id);
+                match (pos, target) {
+                    (Some(pos), Some(item)) => items[pos] = (**item).clone(),
+                    (Some(pos), None) => {
+                        items.remove(pos);
+                    }
+                    (None, Some(item)) => items.push((**item).clone()),
+                    (None, None) => {}
+                }
+            }
+            Change::Bridge { id, before, after } => {
+                let target = if forward { after } else { before };
+                let pos = bridges.iter().position(|b| b.id == *id);
+                match (pos, target) {
+                    (Some(pos), Some(bridge)) => bridges[pos] = bridge.clone(),
+                    (Some(pos), None) => {
+                        bridges.remove(pos);
+                    }
+                    (None, Some(bridge)) => bridges.push(bridge.clone()),
+                    (None, None) => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ItemKind;
+
+    #[test]
+    fn test_diff_apply_roundtrip() {
+        let a = FeedItem::new("A", ItemKind::Project);
+        let b = FeedItem::new("B", ItemKind::Task);
+        let items = vec![a.clone(), b.clone()];
+
+        let mut boosted = a.clone();
+        boosted.charge = 0.42;
+        boosted.update_state();
+        let c = FeedItem::new("C", ItemKind::Idea);
+        let bridge = Bridge::new(a.id, c.id, crate::BridgeKind::Mention);
+        let items_after = vec![boosted, c];
+        let bridges_after = vec![bridge];
+
+        let changes = diff(&items, &[], &items_after, &bridges_after);
+        assert!(matches!(changes[0], Change::Charge { .. }));
+        assert_eq!(changes.len(), 4);
+
+        let entry = HistoryEntry {
+            seq: 1,
+            at: Utc::now(),
+            op: FeedOp::Tick { mentions: vec![] },
+            changes,
+        };
+        let (mut i, mut br) = (items.clone(), Vec::new());
+        apply(&entry, &mut i, &mut br, true);
+        assert_eq!(i.len(), 2);
+        assert_eq!(br, bridges_after);
+        apply(&entry, &mut i, &mut br, false);
+        assert_eq!(i, items);
+        assert!(br.is_empty());
+    }
+
+    #[test]
+    fn test_limit_and_redo_truncation() {
+        let mut history = FeedHistory::with_limit(3);
+        for n in 0..5 {
+            history.push(FeedOp::Decay { ticks: n }, Vec::new(), Utc::now());
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.applied()[0].seq, 3);
+
+        history.step_back();
+        assert!(history.can_redo());
+        history.push(FeedOp::Decay { ticks: 9 }, Vec::new(), Utc::now());
+        assert!(!history.can_redo());
+        assert_eq!(history.len(), 3);
+    }
+}
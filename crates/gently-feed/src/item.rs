@@ -51,7 +51,7 @@ pub enum ItemKind {
 }
 
 /// A step/TODO within a feed item
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Step {
     pub id: u32,
     pub content: String,
@@ -78,7 +78,7 @@ impl Step {
 }
 
 /// Snapshot of item content at a point in time
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Snapshot {
     pub content: String,
     pub timestamp: DateTime<Utc>,
@@ -86,7 +86,7 @@ pub struct Snapshot {
 }
 
 /// A single item in the Living Feed
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FeedItem {
     /// Unique identifier
     pub id: Uuid,
@@ -115,6 +115,10 @@ pub struct FeedItem {
     /// Tags for filtering
     pub tags: Vec<String>,
 
+    /// Other names this item is mentioned by
+    #[serde(default)]
+    pub aliases: Vec<String>,
+
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
 
@@ -142,6 +146,7 @@ impl FeedItem {
             steps: Vec::new(),
             snapshots: Vec::new(),
             tags: Vec::new(),
+            aliases: Vec::new(),
             created_at: now,
             last_touched: now,
             pinned: false,
@@ -158,14 +163,19 @@ impl FeedItem {
 
     /// Apply decay (exponential)
     pub fn decay(&mut self) {
+        self.apply_decay(1.0 - self.decay_rate);
+    }
+
+    /// Multiply charge by a decay factor
+    pub fn apply_decay(&mut self, factor: f32) {
         if self.pinned {
             // Pinned items don't decay below 0.5
             if self.charge > 0.5 {
-                self.charge *= 1.0 - self.decay_rate;
+                self.charge *= factor;
                 self.charge = self.charge.max(0.5);
             }
         } else {
-            self.charge *= 1.0 - self.decay_rate;
+            self.charge *= factor;
         }
         self.update_state();
     }
@@ -29,20 +29,27 @@
 //! - Every item has a `charge` from 0.0 to 1.0
 //! - Mentioning an item boosts its charge
 //! - Every tick decays all charges exponentially
+//! - Pluggable [`DecayModel`]s decay on wall-clock time, activity or working hours
 //! - State transitions happen automatically based on charge thresholds
 
 pub mod bridge;
+pub mod decay;
 pub mod extractor;
 pub mod feed;
+pub mod history;
 pub mod item;
 pub mod persistence;
+pub mod workspace;
 pub mod xor_chain;
 
 pub use bridge::{Bridge, BridgeKind};
+pub use decay::{DecayModel, DecayPolicy, DecayProjection, DecayStep, Simulation};
 pub use extractor::{ContextExtractor, ExtractedContext};
-pub use feed::LivingFeed;
+pub use feed::{LivingFeed, MergeReport};
+pub use history::{FeedHistory, FeedOp, HistoryEntry};
 pub use item::{FeedItem, ItemKind, ItemState, Step};
 pub use persistence::FeedStorage;
+pub use workspace::{FeedBundle, FeedWorkspaces, ImportOutcome};
 pub use xor_chain::XorChain;
 
 /// Result type for gently-feed operations
@@ -68,4 +75,13 @@ pub enum Error {
 
     #[error("Chain integrity error: {0}")]
     ChainIntegrityError(String),
+
+    #[error("Workspace not found: {0}")]
+    WorkspaceNotFound(String),
+
+    #[error("Invalid workspace: {0}")]
+    InvalidWorkspace(String),
+
+    #[error("Invalid decay model: {0}")]
+    InvalidDecayModel(String),
 }
@@ -2,7 +2,7 @@
 //!
 //! Stores feed state to disk as JSON for cross-session persistence.
 
-use crate::{Bridge, FeedItem, LivingFeed, XorChain};
+use crate::{Bridge, DecayPolicy, FeedHistory, FeedItem, FeedWorkspaces, LivingFeed, XorChain};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
@@ -26,6 +26,14 @@ pub struct FeedState {
 
     /// Total interaction count
     pub interaction_count: u64,
+
+    /// Decay policy
+    #[serde(default)]
+    pub decay_policy: DecayPolicy,
+
+    /// Operation history (for undo and timeline queries)
+    #[serde(default)]
+    pub history: FeedHistory,
 }
 
 impl Default for FeedState {
@@ -37,6 +45,8 @@ impl Default for FeedState {
             xor_chain: XorChain::new(),
             last_tick: 0,
             interaction_count: 0,
+            decay_policy: DecayPolicy::default(),
+            history: FeedHistory::default(),
         }
     }
 }
@@ -51,18 +61,9 @@ pub struct FeedStorage {
 }
 
 impl FeedStorage {
-    /// Create storage at default location (~/.config/gently/feed.json)
+    /// Create storage for the active workspace (~/.config/gently/feed.json by default)
     pub fn default_location() -> crate::Result<Self> {
-        let config_dir = dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("gently");
-
-        std::fs::create_dir_all(&config_dir)?;
-
-        Ok(Self {
-            path: config_dir.join("feed.json"),
-            auto_save: true,
-        })
+        Ok(FeedWorkspaces::default_location()?.active_storage())
     }
 
     /// Create storage at specific path
@@ -83,7 +84,7 @@ impl FeedStorage {
         self.auto_save = enabled;
     }
 
-    /// Load feed from disk
+    /// Load feed from disk, applying decay for the time it sat there
     pub fn load(&self) -> crate::Result<LivingFeed> {
         if !self.path.exists() {
             return Ok(LivingFeed::new());
@@ -92,7 +93,9 @@ impl FeedStorage {
         let content = std::fs::read_to_string(&self.path)?;
         let state: FeedState = serde_json::from_str(&content)?;
 
-        Ok(LivingFeed::from_state(state))
+        let mut feed = LivingFeed::from_state(state);
+        feed.catch_up(chrono::Utc::now());
+        Ok(feed)
     }
 
     /// Save feed to disk
@@ -197,6 +200,13 @@ impl FeedStorage {
     }
 }
 
+/// Gently config directory (~/.config/gently)
+pub(crate) fn config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("gently")
+}
+
 // Add dirs as a dev dependency or use std::env
 mod dirs {
     use std::path::PathBuf;
@@ -252,6 +262,25 @@ mod tests {
         assert_eq!(loaded.items().len(), 2);
     }
 
+    #[test]
+    fn test_load_applies_elapsed_decay() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test_feed.json");
+
+        let mut feed = LivingFeed::new();
+        feed.add_item("Stale Project", ItemKind::Project);
+        feed.set_decay_policy(DecayPolicy::new(crate::DecayModel::HalfLife { hours: 24.0 }));
+
+        // Pretend it was saved two days ago
+        let mut state = feed.to_state();
+        state.last_tick = (chrono::Utc::now() - chrono::Duration::hours(48)).timestamp_millis() as u64;
+        std::fs::write(&path, serde_json::to_string(&state).unwrap()).unwrap();
+
+        let loaded = FeedStorage::at_path(&path).load().unwrap();
+        let charge = loaded.get_item_by_name("stale project").unwrap().charge;
+        assert!((charge - 0.25).abs() < 0.01);
+    }
+
     #[test]
     fn test_export_markdown() {
         let dir = tempdir().unwrap();
@@ -0,0 +1,410 @@
+//! Feed workspaces and bundles
+//!
+//! Each project gets its own named feed. One workspace is active at a time,
+//! and [`FeedStorage::default_location`] follows it, so every tool that loads
+//! "the feed" sees the same one.
+//!
+//! ```text
+//! ~/.config/gently/
+//! ├── feed.json            "default" workspace (pre-workspace layout)
+//! └── feeds/
+//!     ├── active           name of the active workspace
+//!     ├── gentlyos.json
+//!     └── client-x.json
+//! ```
+//!
+//! A [`FeedBundle`] hands a feed to someone else. The receiver's chain
+//! continues from the bundle head, so provenance survives the handoff:
+//!
+//! ```text
+//! alice: genesis ─ ... ─ export:gentlyos ─┐
+//!                                         │ bundle (head, checksum)
+//! bob:                                    └─ import:gentlyos ─ ...
+//! ```
+
+use crate::{persistence::FeedState, FeedStorage, LivingFeed, MergeReport};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Workspace backed by the legacy `feed.json`
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// Bundle format version
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Named feeds under one config directory
+#[derive(Debug, Clone)]
+pub struct FeedWorkspaces {
+    /// Config directory (holds feed.json and feeds/)
+    root: PathBuf,
+}
+
+impl FeedWorkspaces {
+    /// Workspaces at the default location (~/.config/gently)
+    pub fn default_location() -> crate::Result<Self> {
+        let root = crate::persistence::config_dir();
+        std::fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// Workspaces under a specific config directory
+    pub fn at_path(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Config directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn feeds_dir(&self) -> PathBuf {
+        self.root.join("feeds")
+    }
+
+    fn active_file(&self) -> PathBuf {
+        self.feeds_dir().join("active")
+    }
+
+    /// Path of a workspace's feed file
+    pub fn path_for(&self, name: &str) -> PathBuf {
+        if name == DEFAULT_WORKSPACE {
+            self.root.join("feed.json")
+        } else {
+            self.feeds_dir().join(format!("{}.json", name))
+        }
+    }
+
+    /// Check a workspace name (letters, digits, '-' and '_')
+    pub fn validate_name(name: &str) -> crate::Result<()> {
+        let valid = !name.is_empty()
+            && name.len() <= 64
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if valid {
+            Ok(())
+        } else {
+            Err(crate::Error::InvalidWorkspace(name.to_string()))
+        }
+    }
+
+    /// All workspaces, sorted ("default" always included)
+    pub fn list(&self) -> crate::Result<Vec<String>> {
+        let mut names = vec![DEFAULT_WORKSPACE.to_string()];
+        if let Ok(entries) = std::fs::read_dir(self.feeds_dir()) {
+            for entry in entries {
+                let path = entry?.path();
+                if path.extension().is_some_and(|e| e == "json") {
+                    if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                        if Self::validate_name(stem).is_ok() && stem != DEFAULT_WORKSPACE {
+                            names.push(stem.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        names[1..].sort();
+        Ok(names)
+    }
+
+    /// Does a workspace exist?
+    pub fn exists(&self, name: &str) -> bool {
+        name == DEFAULT_WORKSPACE || self.path_for(name).exists()
+    }
+
+    /// Name of the active workspace
+    pub fn active(&self) -> String {
+        std::fs::read_to_string(self.active_file())
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|name| Self::validate_name(name).is_ok() && self.exists(name))
+            .unwrap_or_else(|| DEFAULT_WORKSPACE.to_string())
+    }
+
+    /// Create an empty workspace
+    pub fn create(&self, name: &str) -> crate::Result<FeedStorage> {
+        Self::validate_name(name)?;
+        if self.exists(name) {
+            return Err(crate::Error::DuplicateItem(name.to_string()));
+        }
+        std::fs::create_dir_all(self.feeds_dir())?;
+        let storage = FeedStorage::at_path(self.path_for(name));
+        storage.save(&LivingFeed::new())?;
+        Ok(storage)
+    }
+
+    /// Make a workspace active
+    pub fn switch(&self, name: &str) -> crate::Result<()> {
+        Self::validate_name(name)?;
+        if !self.exists(name) {
+            return Err(crate::Error::WorkspaceNotFound(name.to_string()));
+        }
+        std::fs::create_dir_all(self.feeds_dir())?;
+        std::fs::write(self.active_file(), name)?;
+        Ok(())
+    }
+
+    /// Delete a workspace (falls back to "default" if it was active)
+    pub fn delete(&self, name: &str) -> crate::Result<()> {
+        Self::validate_name(name)?;
+        if name == DEFAULT_WORKSPACE {
+            return Err(crate::Error::InvalidWorkspace(
+                "the default workspace cannot be deleted".into(),
+            ));
+        }
+        if !self.exists(name) {
+            return Err(crate::Error::WorkspaceNotFound(name.to_string()));
+        }
+        if self.active() == name {
+            std::fs::remove_file(self.active_file())?;
+        }
+        std::fs::remove_file(self.path_for(name))?;
+        Ok(())
+    }
+
+    /// Storage for a workspace
+    pub fn storage(&self, name: &str) -> crate::Result<FeedStorage> {
+        Self::validate_name(name)?;
+        Ok(FeedStorage::at_path(self.path_for(name)))
+    }
+
+    /// Storage for the active workspace
+    pub fn active_storage(&self) -> FeedStorage {
+        FeedStorage::at_path(self.path_for(&self.active()))
+    }
+
+    /// Load a workspace's feed
+    pub fn load(&self, name: &str) -> crate::Result<LivingFeed> {
+        if !self.exists(name) {
+            return Err(crate::Error::WorkspaceNotFound(name.to_string()));
+        }
+        self.storage(name)?.load()
+    }
+
+    /// Merge workspace `from` into workspace `into`
+    pub fn merge(&self, from: &str, into: &str) -> crate::Result<MergeReport> {
+        let other = self.load(from)?;
+        let mut feed = self.load(into)?;
+        let report = feed.merge(&other);
+        self.storage(into)?.save(&feed)?;
+        Ok(report)
+    }
+
+    /// Export a workspace as a bundle (records the export on its chain)
+    pub fn export_bundle(&self, name: &str) -> crate::Result<FeedBundle> {
+        let mut feed = self.load(name)?;
+        let bundle = FeedBundle::export(name, &mut feed)?;
+        self.storage(name)?.save(&feed)?;
+        Ok(bundle)
+    }
+
+    /// Import a bundle into a workspace (created if missing, merged if not empty)
+    pub fn import_bundle(&self, bundle: &FeedBundle, into: &str) -> crate::Result<ImportOutcome> {
+        Self::validate_name(into)?;
+        let existing = if self.exists(into) {
+            Some(self.load(into)?)
+        } else {
+            None
+        };
+        let (feed, outcome) = bundle.import_into(existing)?;
+        std::fs::create_dir_all(self.feeds_dir())?;
+        self.storage(into)?.save(&feed)?;
+        Ok(outcome)
+    }
+}
+
+/// How an imported bundle landed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportOutcome {
+    /// Empty workspace took over the bundle's state and chain
+    Adopted,
+    /// Bundle merged into an existing feed
+    Merged(MergeReport),
+}
+
+/// A portable feed with chain provenance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedBundle {
+    /// Bundle format version
+    pub version: u32,
+
+    /// Workspace the feed was exported from
+    pub workspace: String,
+
+    /// Export timestamp
+    pub exported_at: chrono::DateTime<chrono::Utc>,
+
+    /// Chain genesis at export
+    pub genesis: String,
+
+    /// Chain head at export
+    pub head: String,
+
+    /// Chain depth at export
+    pub depth: u64,
+
+    /// Full feed state
+    pub state: FeedState,
+
+    /// SHA-256 of the serialized state (hex)
+    pub checksum: String,
+}
+
+impl FeedBundle {
+    /// Bundle a feed, advancing its chain with the export
+    pub fn export(workspace: &str, feed: &mut LivingFeed) -> crate::Result<Self> {
+        let exported_at = chrono::Utc::now();
+        feed.advance_chain(&format!("export:{}:{}", workspace, exported_at.timestamp()));
+
+        let state = feed.to_state();
+        let checksum = Self::checksum_of(&state)?;
+        Ok(Self {
+            version: BUNDLE_VERSION,
+            workspace: workspace.to_string(),
+            exported_at,
+            genesis: state.xor_chain.genesis.clone(),
+            head: state.xor_chain.current.clone(),
+            depth: state.xor_chain.depth,
+            state,
+            checksum,
+        })
+    }
+
+    fn checksum_of(state: &FeedState) -> crate::Result<String> {
+        let bytes = serde_json::to_vec(state)?;
+        let digest = Sha256::digest(&bytes);
+        Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// Check the checksum and that the header matches the state's chain
+    pub fn verify(&self) -> crate::Result<()> {
+        if self.version != BUNDLE_VERSION {
+            return Err(crate::Error::ChainIntegrityError(format!(
+                "unsupported bundle version {}",
+                self.version
+            )));
+        }
+        if Self::checksum_of(&self.state)? != self.checksum {
+            return Err(crate::Error::ChainIntegrityError(
+                "bundle checksum mismatch".into(),
+            ));
+        }
+        let chain = &self.state.xor_chain;
+        if chain.genesis != self.genesis || chain.current != self.head || chain.depth != self.depth {
+            return Err(crate::Error::ChainIntegrityError(
+                "bundle header does not match its chain".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Read a bundle from disk
+    pub fn read(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let bundle: Self = serde_json::from_str(&content)?;
+        bundle.verify()?;
+        Ok(bundle)
+    }
+
+    /// Write a bundle to disk
+    pub fn write(&self, path: impl AsRef<Path>) -> crate::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Feed after importing into `existing` (adopts if missing or empty)
+    pub fn import_into(&self, existing: Option<LivingFeed>) -> crate::Result<(LivingFeed, ImportOutcome)> {
+        self.verify()?;
+        let incoming = LivingFeed::from_state(self.state.clone());
+
+        match existing {
+            Some(mut feed) if !feed.items().is_empty() => {
+                let report = feed.merge(&incoming);
+                Ok((feed, ImportOutcome::Merged(report)))
+            }
+            _ => {
+                let mut feed = incoming;
+                feed.advance_chain(&format!("import:{}:{}", self.workspace, self.head));
+                Ok((feed, ImportOutcome::Adopted))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ItemKind;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_create_switch_delete() {
+        let dir = tempdir().unwrap();
+        let ws = FeedWorkspaces::at_path(dir.path());
+        assert_eq!(ws.active(), DEFAULT_WORKSPACE);
+
+        ws.create("gentlyos").unwrap();
+        ws.create("client-x").unwrap();
+        assert!(ws.create("gentlyos").is_err());
+        assert!(ws.create("../etc").is_err());
+        assert_eq!(ws.list().unwrap(), vec!["default", "client-x", "gentlyos"]);
+
+        ws.switch("gentlyos").unwrap();
+        assert_eq!(ws.active(), "gentlyos");
+        assert!(ws.switch("missing").is_err());
+
+        let storage = ws.active_storage();
+        let mut feed = storage.load().unwrap();
+        feed.add_item("Alexandria", ItemKind::Project);
+        storage.save(&feed).unwrap();
+        assert_eq!(ws.load("gentlyos").unwrap().items().len(), 1);
+        assert!(ws.load("client-x").unwrap().items().is_empty());
+
+        ws.delete("gentlyos").unwrap();
+        assert_eq!(ws.active(), DEFAULT_WORKSPACE);
+        assert!(ws.delete(DEFAULT_WORKSPACE).is_err());
+    }
+
+    #[test]
+    fn test_bundle_handoff_keeps_provenance() {
+        let alice = tempdir().unwrap();
+        let bob = tempdir().unwrap();
+        let alice_ws = FeedWorkspaces::at_path(alice.path());
+        let bob_ws = FeedWorkspaces::at_path(bob.path());
+
+        let storage = alice_ws.create("gentlyos").unwrap();
+        let mut feed = storage.load().unwrap();
+        feed.add_item("Dance Protocol", ItemKind::Project);
+        storage.save(&feed).unwrap();
+
+        let bundle = alice_ws.export_bundle("gentlyos").unwrap();
+        let path = alice.path().join("gentlyos.bundle.json");
+        bundle.write(&path).unwrap();
+        assert_eq!(alice_ws.load("gentlyos").unwrap().xor_chain().current, bundle.head);
+
+        let read = FeedBundle::read(&path).unwrap();
+        assert_eq!(bob_ws.import_bundle(&read, "gentlyos").unwrap(), ImportOutcome::Adopted);
+
+        let imported = bob_ws.load("gentlyos").unwrap();
+        assert_eq!(imported.xor_chain().genesis, bundle.genesis);
+        assert_eq!(imported.xor_chain().previous, bundle.head);
+        assert!(imported.xor_chain().verify(&format!("import:gentlyos:{}", bundle.head), &bundle.head));
+        assert!(imported.get_item_by_name("dance protocol").is_some());
+
+        // Importing again merges instead of overwriting
+        match bob_ws.import_bundle(&read, "gentlyos").unwrap() {
+            ImportOutcome::Merged(report) => assert_eq!(report.items_combined, 1),
+            other => panic!("expected merge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_tampered_bundle_rejected() {
+        let mut feed = LivingFeed::new();
+        feed.add_item("Secret", ItemKind::Project);
+        let mut bundle = FeedBundle::export("default", &mut feed).unwrap();
+        bundle.state.items[0].charge = 0.01;
+        assert!(bundle.verify().is_err());
+    }
+}
@@ -0,0 +1,438 @@
+//! Brain Backend
+//!
+//! Routes gently-brain conversations through the gateway, so the brain's
+//! assistant gets the same filters, routing and audit chain as everything else.
+//!
+//! ```text
+//! GentlyAssistant ──► GatewayBackend ──► Gateway::process ──► Provider
+//!   ChatRequest          │  prompt + history    (filters)         │
+//!                        └── metadata["chat_request"] ────────────┘
+//!                            (structured tool rounds, used only while they
+//!                             still match the filtered prompt and history)
+//! ```
+//!
+//! The prompt and history are what the gateway filters and hashes, so they
+//! are what the provider sends. When a filter rewrote them, the structured
+//! rounds are dropped in favour of the filtered text.
+
+use crate::filter::ContentFilter;
+use crate::provider::ClaudeProvider;
+use crate::{Gateway, GatewayMessage, GatewayRequest, ProviderPreference, Router, TaskType};
+use gently_brain::{ChatBackend, ChatClient, ChatRequest, ChatSession, ClaudeModel, GentlyAssistant, ModelTurn, ToolUseResponse};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Metadata key carrying the structured brain request
+pub const CHAT_REQUEST_KEY: &str = "chat_request";
+
+/// Metadata key carrying the requested model
+pub const MODEL_KEY: &str = "model";
+
+/// Gateway that routes chat to Claude behind the default content filter
+pub fn claude_gateway(api_key: impl Into<String>) -> Gateway {
+    let mut router = Router::new();
+    router.register(Arc::new(ClaudeProvider::new(api_key)));
+    Gateway::builder()
+        .router(router)
+        .input_filter(Box::new(ContentFilter::new()))
+        .build()
+}
+
+/// Chat client on Claude through [`claude_gateway`], keyed from ANTHROPIC_API_KEY
+pub fn claude_client(model: ClaudeModel) -> gently_brain::Result<ChatClient> {
+    Ok(ChatClient::with_backend(Box::new(GatewayBackend::claude(model.api_name())?)))
+}
+
+/// Assistant on Claude through [`claude_gateway`]
+pub fn claude_assistant(model: ClaudeModel) -> gently_brain::Result<GentlyAssistant> {
+    Ok(GentlyAssistant::with_backend(Box::new(GatewayBackend::claude(model.api_name())?)))
+}
+
+/// Chat session on Claude through [`claude_gateway`]
+pub fn claude_session(model: ClaudeModel) -> gently_brain::Result<ChatSession> {
+    Ok(ChatSession::with_backend(Box::new(GatewayBackend::claude(model.api_name())?)))
+}
+
+/// [`ChatBackend`] that sends every call through a [`Gateway`]
+///
+/// Calls block, like the rest of the brain chat API. Inside a tokio runtime
+/// the gateway runs on a helper thread so the caller's runtime is not re-entered.
+pub struct GatewayBackend {
+    gateway: Arc<Mutex<Gateway>>,
+    /// Only `None` while dropping
+    runtime: Option<tokio::runtime::Runtime>,
+    model: String,
+    preference: Option<ProviderPreference>,
+    session_id: Option<String>,
+}
+
+impl GatewayBackend {
+    pub fn new(gateway: Arc<Mutex<Gateway>>) -> std::io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            gateway,
+            runtime: Some(runtime),
+            model: String::new(),
+            preference: None,
+            session_id: None,
+        })
+    }
+
+    /// Backend on [`claude_gateway`], keyed from ANTHROPIC_API_KEY
+    pub fn claude(model: &str) -> gently_brain::Result<Self> {
+        let api_key = std::env::var("ANTHROPIC_API_KEY").map_err(|_| {
+            gently_brain::Error::InferenceFailed("ANTHROPIC_API_KEY not set. Export your API key.".into())
+        })?;
+        let mut backend = Self::new(Arc::new(Mutex::new(claude_gateway(api_key))))?
+            .prefer(ProviderPreference::Specific("claude".into()));
+        backend.model = model.to_string();
+        Ok(backend)
+    }
+
+    /// Routing preference for every request
+    pub fn prefer(mut self, preference: ProviderPreference) -> Self {
+        self.preference = Some(preference);
+        self
+    }
+
+    /// Bind requests to a gateway session
+    pub fn session(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    pub fn gateway(&self) -> Arc<Mutex<Gateway>> {
+        Arc::clone(&self.gateway)
+    }
+
+    /// Gateway request for a brain request: last message is the prompt
+    fn gateway_request(&self, request: &ChatRequest) -> GatewayRequest {
+        let mut messages = request.plain_messages();
+        let prompt = messages.pop().map(|m| m.content).unwrap_or_default();
+        let history = messages.into_iter()
+            .map(|m| match m.role.as_str() {
+                "assistant" => GatewayMessage::assistant(m.content),
+                _ => GatewayMessage::user(m.content),
+            })
+            .collect();
+
+        let task = if request.tools.is_empty() { TaskType::Chat } else { TaskType::ToolUse };
+        let mut req = GatewayRequest::new(prompt)
+            .with_history(history)
+            .max_tokens(request.max_tokens)
+            .task_type(task);
+        if let Some(system) = &request.system {
+            req = req.system(system.clone());
+        }
+        if let Some(pref) = &self.preference {
+            req = req.prefer(pref.clone());
+        }
+        if let Some(session) = &self.session_id {
+            req = req.session(session.clone());
+        }
+        if !self.model.is_empty() {
+            req.metadata.insert(MODEL_KEY.into(), serde_json::json!(self.model));
+        }
+        if let Ok(value) = serde_json::to_value(request) {
+            req.metadata.insert(CHAT_REQUEST_KEY.into(), value);
+        }
+        req
+    }
+}
+
+impl Drop for GatewayBackend {
+    fn drop(&mut self) {
+        // A plain drop blocks, which panics when the owner is async code
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl ChatBackend for GatewayBackend {
+    fn name(&self) -> &str {
+        "gateway"
+    }
+
+    fn model(&self) -> String {
+        self.model.clone()
+    }
+
+    fn set_model(&mut self, model: &str) {
+        self.model = model.to_string();
+    }
+
+    fn complete(&self, request: &ChatRequest) -> gently_brain::Result<ModelTurn> {
+        let req = self.gateway_request(request);
+        let runtime = self.runtime.as_ref().expect("runtime lives until drop");
+        let process = async { self.gateway.lock().await.process(req).await };
+        // block_on would panic on a runtime thread (TUI, async callers)
+        let response = if tokio::runtime::Handle::try_current().is_ok() {
+            std::thread::scope(|scope| {
+                scope.spawn(|| runtime.block_on(process)).join()
+            })
+            .map_err(|_| gently_brain::Error::InferenceFailed("Gateway: worker panicked".into()))?
+        } else {
+            runtime.block_on(process)
+        }
+        .map_err(|e| gently_brain::Error::InferenceFailed(format!("Gateway: {}", e)))?;
+
+        Ok(ModelTurn {
+            text: response.content,
+            tool_uses: response.tool_calls.into_iter()
+                .map(|t| ToolUseResponse { id: t.id, name: t.name, input: t.input })
+                .collect(),
+            input_tokens: response.input_tokens,
+            output_tokens: response.output_tokens,
+        })
+    }
+}
+
+/// Brain request a provider should send for a (filtered) gateway request
+///
+/// Structured metadata is used only if it renders to exactly the prompt and
+/// history the gateway let through; otherwise the plain fields win, keeping
+/// just the tool definitions.
+pub fn chat_request(request: &GatewayRequest) -> ChatRequest {
+    let structured = request.metadata.get(CHAT_REQUEST_KEY)
+        .and_then(|v| serde_json::from_value::<ChatRequest>(v.clone()).ok());
+
+    match structured {
+        Some(chat) if matches_plain(&chat, request) => ChatRequest {
+            max_tokens: request.max_tokens,
+            ..chat
+        },
+        Some(chat) => ChatRequest { tools: chat.tools, ..plain_request(request) },
+        None => plain_request(request),
+    }
+}
+
+/// Whether `chat` is exactly what the request's plain fields say
+fn matches_plain(chat: &ChatRequest, request: &GatewayRequest) -> bool {
+    let mut messages = chat.plain_messages();
+    let Some(last) = messages.pop() else {
+        return false;
+    };
+    let history: Vec<_> = request.history.iter()
+        .filter(|m| m.role != crate::MessageRole::System)
+        .collect();
+
+    chat.system == request.system_prompt
+        && last.role == "user"
+        && last.content == request.prompt
+        && messages.len() == history.len()
+        && messages.iter().zip(&history).all(|(m, h)| {
+            let role = if h.role == crate::MessageRole::Assistant { "assistant" } else { "user" };
+            m.role == role && m.content == h.content
+        })
+}
+
+fn plain_request(request: &GatewayRequest) -> ChatRequest {
+    let mut history: Vec<_> = request.history.iter()
+        .filter(|m| m.role != crate::MessageRole::System)
+        .map(|m| match m.role {
+            crate::MessageRole::Assistant => gently_brain::Message::assistant(&m.content),
+            _ => gently_brain::Message::user(&m.content),
+        })
+        .collect();
+    history.push(gently_brain::Message::user(&request.prompt));
+    ChatRequest {
+        system: request.system_prompt.clone(),
+        history,
+        max_tokens: request.max_tokens,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::{ContentFilter, FilterResult, InputFilter};
+    use crate::provider::{Provider, ProviderCapabilities, ProviderStatus, ProviderType};
+    use crate::{GatewayResponse, Router, ToolCall};
+    use async_trait::async_trait;
+    use gently_brain::agent_loop::Turn;
+    use gently_brain::{ChatClient, GentlyAssistant, ToolResultInput};
+
+    /// Echoes the prompt and calls a tool when offered one
+    struct Echo;
+
+    #[async_trait]
+    impl Provider for Echo {
+        fn name(&self) -> &str { "echo" }
+
+        fn provider_type(&self) -> ProviderType { ProviderType::Local }
+
+        async fn health_check(&self) -> ProviderStatus { ProviderStatus::Healthy }
+
+        async fn complete(&self, request: &GatewayRequest) -> crate::Result<GatewayResponse> {
+            let chat = chat_request(request);
+            let sent = chat.plain_messages().pop().map(|m| m.content).unwrap_or_default();
+            let mut response = GatewayResponse::new(&request.id, format!("echo: {}", sent));
+            response.input_tokens = chat.history.len() + chat.turns.len();
+            response.output_tokens = 1;
+            if !chat.tools.is_empty() && chat.turns.len() == 1 {
+                response.tool_calls.push(ToolCall {
+                    id: "t1".into(),
+                    name: "focus".into(),
+                    input: serde_json::json!({"topic": "rust"}),
+                });
+            }
+            Ok(response)
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities { chat: true, tools: true, ..Default::default() }
+        }
+
+        fn cost_per_1k_tokens(&self) -> f64 { 0.0 }
+    }
+
+    /// Redacts "secret" wherever it appears in the prompt or history
+    struct Redact;
+
+    impl InputFilter for Redact {
+        fn name(&self) -> &str { "redact" }
+
+        fn filter(&self, request: &GatewayRequest) -> FilterResult {
+            let mut modified = request.clone();
+            modified.prompt = modified.prompt.replace("secret", "[redacted]");
+            for m in &mut modified.history {
+                m.content = m.content.replace("secret", "[redacted]");
+            }
+            FilterResult::Modify(modified)
+        }
+    }
+
+    fn gateway() -> Arc<Mutex<Gateway>> {
+        let mut router = Router::new();
+        router.register(Arc::new(Echo));
+        let gateway = Gateway::builder()
+            .router(router)
+            .input_filter(Box::new(ContentFilter::new().add_blocked_pattern("forbidden")))
+            .build();
+        Arc::new(Mutex::new(gateway))
+    }
+
+    #[test]
+    fn test_client_through_gateway() {
+        let gw = gateway();
+        let backend = GatewayBackend::new(gw.clone()).unwrap()
+            .prefer(ProviderPreference::Specific("echo".into()));
+        let mut client = ChatClient::with_backend(Box::new(backend));
+
+        assert_eq!(client.chat("hello").unwrap(), "echo: hello");
+        assert_eq!(client.chat("again").unwrap(), "echo: again");
+
+        // Rejected by the gateway's input filter, and audited there
+        let err = client.chat("something forbidden").unwrap_err();
+        assert!(err.to_string().contains("Gateway"), "{}", err);
+
+        let gateway = gw.blocking_lock();
+        assert_eq!(gateway.metrics().requests_total, 2);
+        assert!(gateway.audit_log().verify_chain());
+        let rejected = gateway.audit_log().all_events()
+            .filter(|e| matches!(e.event, crate::AuditEvent::RequestRejected { .. }))
+            .count();
+        assert_eq!(rejected, 1);
+    }
+
+    #[test]
+    fn test_tool_round_through_gateway() {
+        let backend = GatewayBackend::new(gateway()).unwrap()
+            .prefer(ProviderPreference::Specific("echo".into()));
+        let mut assistant = GentlyAssistant::with_backend(Box::new(backend))
+            .with_tools(vec![serde_json::json!({"name": "focus"})]);
+
+        let first = assistant.chat_with_tools("focus please").unwrap();
+        assert_eq!(first.tool_uses[0].name, "focus");
+
+        let done = assistant.submit_tool_results(vec![ToolResultInput {
+            tool_use_id: "t1".into(),
+            content: "focused".into(),
+            is_error: false,
+        }]).unwrap();
+        assert!(done.tool_uses.is_empty());
+        assert_eq!(done.text, "echo: [tool_result t1] focused");
+    }
+
+    #[test]
+    fn test_modified_request_reaches_provider() {
+        let mut router = Router::new();
+        router.register(Arc::new(Echo));
+        let gw = Arc::new(Mutex::new(Gateway::builder()
+            .router(router)
+            .input_filter(Box::new(Redact))
+            .build()));
+        let backend = GatewayBackend::new(gw.clone()).unwrap()
+            .prefer(ProviderPreference::Specific("echo".into()));
+        let mut assistant = GentlyAssistant::with_backend(Box::new(backend))
+            .with_tools(vec![serde_json::json!({"name": "focus"})]);
+
+        let first = assistant.chat_with_tools("the secret plan").unwrap();
+        assert_eq!(first.text, "echo: the [redacted] plan");
+
+        // A tool result is redacted too, even though it travels as a structured round
+        let done = assistant.submit_tool_results(vec![ToolResultInput {
+            tool_use_id: "t1".into(),
+            content: "secret focus".into(),
+            is_error: false,
+        }]).unwrap();
+        assert_eq!(done.text, "echo: [tool_result t1] [redacted] focus");
+
+        // The audit hash covers the whole redacted conversation, not just the prompt
+        let backend = GatewayBackend::new(gw.clone()).unwrap()
+            .prefer(ProviderPreference::Specific("echo".into()));
+        let mut client = ChatClient::with_backend(Box::new(backend));
+        client.chat("secret one").unwrap();
+        assert_eq!(client.chat("two").unwrap(), "echo: two");
+
+        let sent = GatewayRequest::new("two").with_history(vec![
+            GatewayMessage::user("[redacted] one"),
+            GatewayMessage::assistant("echo: [redacted] one"),
+        ]);
+        let gateway = gw.blocking_lock();
+        let last = gateway.audit_log().all_events()
+            .filter_map(|e| match &e.event {
+                crate::AuditEvent::RequestReceived { prompt_hash, .. } => Some(prompt_hash.clone()),
+                _ => None,
+            })
+            .last();
+        assert_eq!(last, Some(crate::hash_request(&sent)));
+    }
+
+    #[tokio::test]
+    async fn test_complete_inside_runtime() {
+        let backend = GatewayBackend::new(gateway()).unwrap()
+            .prefer(ProviderPreference::Specific("echo".into()));
+        let mut client = ChatClient::with_backend(Box::new(backend));
+        assert_eq!(client.chat("hello").unwrap(), "echo: hello");
+    }
+
+    #[test]
+    fn test_chat_request_roundtrip() {
+        let backend = GatewayBackend::new(gateway()).unwrap();
+        let chat = ChatRequest {
+            system: Some("sys".into()),
+            history: vec![gently_brain::Message::user("hi")],
+            turns: vec![Turn::User { text: "go".into() }],
+            tools: vec![serde_json::json!({"name": "focus"})],
+            max_tokens: 64,
+        };
+        let req = backend.gateway_request(&chat);
+        assert_eq!(req.prompt, "go");
+        assert_eq!(req.task_type, TaskType::ToolUse);
+        assert_eq!(req.history.len(), 1);
+
+        let back = chat_request(&req);
+        assert_eq!(back.turns.len(), 1);
+        assert_eq!(back.tools.len(), 1);
+
+        // Requests from other clients carry no brain metadata
+        let plain = chat_request(&GatewayRequest::new("q").system("s"));
+        assert_eq!(plain.history.len(), 1);
+        assert_eq!(plain.system.as_deref(), Some("s"));
+    }
+}
@@ -31,6 +31,7 @@ pub mod router;
 pub mod filter;
 pub mod audit;
 pub mod session;
+pub mod backend;
 
 pub use types::*;
 pub use provider::{Provider, ProviderType, ProviderStatus};
@@ -38,6 +39,7 @@ pub use router::{Router, RoutingStrategy, RouteDecision};
 pub use filter::{InputFilter, OutputFilter, FilterResult};
 pub use audit::{AuditLog, AuditEntry, AuditEvent};
 pub use session::{Session, SessionState, SessionManager};
+pub use backend::{claude_assistant, claude_client, claude_gateway, claude_session, GatewayBackend};
 
 use thiserror::Error;
 use sha2::{Sha256, Digest};
@@ -104,10 +106,7 @@ impl Gateway {
 
     /// Process a request through the gateway
     pub async fn process(&mut self, mut request: GatewayRequest) -> Result<GatewayResponse> {
-        // 1. Hash the incoming request
-        request.prompt_hash = Some(hash_content(&request.prompt));
-
-        // 2. Run input filters (auth, validation, session binding)
+        // 1. Run input filters (auth, validation, session binding)
         for filter in &self.input_filters {
             match filter.filter(&request) {
                 FilterResult::Pass => continue,
@@ -122,6 +121,9 @@ impl Gateway {
             }
         }
 
+        // 2. Hash the conversation as it will be sent
+        request.prompt_hash = Some(hash_request(&request));
+
         // 3. Audit the request
         self.audit.log(AuditEvent::RequestReceived {
             request_id: request.id.clone(),
@@ -283,6 +285,15 @@ pub fn hash_content(content: &str) -> String {
     hex::encode(hasher.finalize())
 }
 
+/// Hash the whole conversation a request sends: system prompt, history and prompt
+pub fn hash_request(request: &GatewayRequest) -> String {
+    let history: Vec<_> = request.history.iter()
+        .map(|m| (&m.role, m.content.as_str()))
+        .collect();
+    let conversation = serde_json::json!([request.system_prompt, history, request.prompt]);
+    hash_content(&conversation.to_string())
+}
+
 /// Compute chain hash: SHA256(prev + prompt_hash + response_hash)
 pub fn hash_chain(prev: &str, prompt_hash: &str, response_hash: &str) -> String {
     let mut hasher = Sha256::new();
@@ -302,6 +313,15 @@ mod tests {
         assert_eq!(hash.len(), 64); // SHA256 hex = 64 chars
     }
 
+    #[test]
+    fn test_hash_request() {
+        let a = GatewayRequest::new("and now?").with_history(vec![GatewayMessage::user("one")]);
+        let b = GatewayRequest::new("and now?").with_history(vec![GatewayMessage::user("two")]);
+        assert_ne!(hash_request(&a), hash_request(&b));
+        assert_ne!(hash_request(&a), hash_request(&a.clone().system("s")));
+        assert_eq!(hash_request(&a), hash_request(&a.clone()));
+    }
+
     #[test]
     fn test_hash_chain() {
         let prev = "0000000000000000000000000000000000000000000000000000000000000000";
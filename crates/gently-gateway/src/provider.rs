@@ -347,28 +347,39 @@ impl Provider for ClaudeProvider {
     }
 
     async fn complete(&self, request: &GatewayRequest) -> Result<GatewayResponse> {
+        use gently_brain::{AnthropicBackend, ChatBackend};
+
         let start = Instant::now();
 
-        // TODO: Use gently-brain::ClaudeClient
-        // For now, return placeholder
-        let content = format!(
-            "[Claude] Would process: {}... (external API placeholder)",
-            &request.prompt[..request.prompt.len().min(50)]
-        );
+        let chat = crate::backend::chat_request(request);
+        let model = request.metadata.get(crate::backend::MODEL_KEY)
+            .and_then(|m| m.as_str())
+            .unwrap_or(&self.model)
+            .to_string();
+        let mut backend = AnthropicBackend::with_key(&self.api_key);
+        backend.set_model(&model);
+
+        // Blocking HTTP client - keep it off the async workers
+        let turn = tokio::task::spawn_blocking(move || backend.complete(&chat))
+            .await
+            .map_err(|e| GatewayError::InferenceError(e.to_string()))?
+            .map_err(|e| GatewayError::InferenceError(e.to_string()))?;
 
         Ok(GatewayResponse {
             request_id: request.id.clone(),
-            content,
+            content: turn.text,
             provider: self.name().to_string(),
-            model: self.model.clone(),
-            tokens_used: 200,
-            input_tokens: 100,
-            output_tokens: 100,
+            model,
+            tokens_used: turn.input_tokens + turn.output_tokens,
+            input_tokens: turn.input_tokens,
+            output_tokens: turn.output_tokens,
             latency_ms: start.elapsed().as_millis() as u64,
             timestamp: chrono::Utc::now(),
             response_hash: None,
             chain_hash: None,
-            tool_calls: Vec::new(),
+            tool_calls: turn.tool_uses.into_iter()
+                .map(|t| crate::ToolCall { id: t.id, name: t.name, input: t.input })
+                .collect(),
             metadata: std::collections::HashMap::new(),
         })
     }
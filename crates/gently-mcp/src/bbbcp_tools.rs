@@ -17,8 +17,7 @@ use crate::{Error, Result};
 use gently_search::{
     BbbcpEngine, BbbcpQueryBuilder, BlobSearch,
     CollapseEngine, Conclusion, ConclusionChainer, ConclusionType,
-    Dimension, HyperspaceQueryBuilder, NaturalLanguageExtractor, PinStrategy,
-    RowBuilder,
+    Dimension, HyperspaceQueryBuilder, IndexRows, NaturalLanguageExtractor, PinStrategy,
 };
 use serde_json::{json, Value};
 
@@ -172,7 +171,7 @@ impl GentlyTool for AlexandriaCollapse {
         }))
     }
 
-    fn execute(&self, args: Value, _ctx: &ToolContext) -> Result<ToolResult> {
+    fn execute(&self, args: Value, ctx: &ToolContext) -> Result<ToolResult> {
         let mut builder = HyperspaceQueryBuilder::new();
 
         // Handle PIN
@@ -218,20 +217,29 @@ impl GentlyTool for AlexandriaCollapse {
             .and_then(|v| v.as_u64())
             .unwrap_or(100) as usize;
 
-        let query = builder.build();
+        let mut query = builder.build();
+
+        // Without explicit columns, every dimension not collapsed is a column
+        if query.enumerate.is_empty() {
+            query.enumerate = Dimension::all()
+                .iter()
+                .copied()
+                .filter(|d| !query.collapse.contains(d))
+                .collect();
+        }
 
         let engine = CollapseEngine::new()
             .with_quality_threshold(quality_threshold)
             .with_max_rows(max_rows);
 
-        // For demo, create empty data (in production, this would query Alexandria)
-        let data: Vec<gently_search::CollapsedRow> = Vec::new();
-        let result = engine.collapse(&query, &data);
+        let index = ctx.index.read().unwrap();
+        let result = engine.collapse_source(&query, &IndexRows::new(&index));
 
         Ok(ToolResult::json(json!({
             "id": result.id.to_string(),
             "columns": result.columns.iter().map(|d| format!("{:?}", d)).collect::<Vec<_>>(),
             "row_count": result.rows.len(),
+            "rows": result.to_table(),
             "new_bone": result.new_bone,
             "stats": {
                 "concepts_searched": result.stats.concepts_searched,
@@ -291,7 +299,7 @@ impl GentlyTool for AlexandriaBbbcp {
         }))
     }
 
-    fn execute(&self, args: Value, _ctx: &ToolContext) -> Result<ToolResult> {
+    fn execute(&self, args: Value, ctx: &ToolContext) -> Result<ToolResult> {
         let mut builder = BbbcpQueryBuilder::new();
 
         // Add BONEs
@@ -344,20 +352,12 @@ impl GentlyTool for AlexandriaBbbcp {
         let query = builder.build();
         let engine = BbbcpEngine::new();
 
-        // For demo, create sample data (in production, this would query Alexandria)
-        let data = vec![
-            RowBuilder::new()
-                .who("system")
-                .what(query_text)
-                .r#where("search")
-                .quality(0.85)
-                .build(),
-        ];
-
-        let result = engine.execute(&query, &data);
+        let index = ctx.index.read().unwrap();
+        let result = engine.execute_source(&query, &IndexRows::new(&index));
 
         Ok(ToolResult::json(json!({
             "query_id": result.query_id.to_string(),
+            "output": result.output,
             "elimination_ratio": result.elimination_ratio,
             "new_bone": result.new_bone.as_ref().map(|b| &b.text),
             "stats": {
@@ -822,6 +822,34 @@ mod tests {
         assert!(!result.is_error.unwrap_or(false));
     }
 
+    #[test]
+    fn test_bbbcp_and_collapse_use_index() {
+        let ctx = ToolContext::new();
+        {
+            let mut index = ctx.index.write().unwrap();
+            index.add_thought(gently_search::Thought::new("JWT authentication rotated in Berlin during 2024"));
+            index.add_thought(gently_search::Thought::new("authentication secrets stored in plaintext"));
+            index.add_thought(gently_search::Thought::new("gardening notes"));
+        }
+
+        let result = AlexandriaBbbcp
+            .execute(
+                json!({ "circles": ["plaintext"], "query": "authentication", "pin_strategy": "top5" }),
+                &ctx,
+            )
+            .unwrap();
+        let data = result.content[0].data.as_ref().unwrap();
+        assert_eq!(data["stats"]["initial_space"], 3);
+        assert_eq!(data["stats"]["reduced_space"], 1);
+
+        let result = AlexandriaCollapse
+            .execute(json!({ "pin": { "where": "berlin" } }), &ctx)
+            .unwrap();
+        let data = result.content[0].data.as_ref().unwrap();
+        assert_eq!(data["row_count"], 1);
+        assert!(data["rows"].to_string().contains("2024-01-01/2024-12-31"));
+    }
+
     #[test]
     fn test_chain_tool() {
         let tool = AlexandriaChain;
@@ -11,21 +11,28 @@
 //!     │                              │
 //!     ▼                              ▼
 //! add_thought() ──────────────► record_query()
+//!     └─ thought's 5W tags ────► tesseract.record_position()
 //! detect_wormhole() ──────────► add_edge()
 //! search() ───────────────────► query_topology()
 //!     │                              │
 //!     └──────── SYNC ────────────────┘
 //! ```
+//!
+//! Every concept this layer creates gets a tesseract position, even one
+//! with no 5W values. The tesseract is saved beside the graph as
+//! `tesseract.json`.
 
 use gently_alexandria::{
     AlexandriaConfig, AlexandriaGraph, AlexandriaEdge, ConceptId,
     DistributedWormhole, EdgeKind, NodeFingerprint, FullTopology,
-    GraphDelta, SyncProtocol, ContributionProof,
+    GraphDelta, SyncProtocol, ContributionProof, SemanticTesseract,
     node::AlexandriaNode,
 };
+use crate::extract::{DimensionTags, FiveWExtractor};
 use crate::{Thought, Wormhole, ThoughtIndex};
 #[allow(unused_imports)]
 use std::sync::Arc;
+use std::path::{Path, PathBuf};
 
 /// Alexandria-backed search layer
 pub struct AlexandriaSearch {
@@ -40,6 +47,12 @@ pub struct AlexandriaSearch {
 
     /// Our node fingerprint
     pub node: NodeFingerprint,
+
+    /// 5W positions of tagged concepts
+    pub tesseract: SemanticTesseract,
+
+    /// 5W extractor applied to every new thought
+    pub extractor: FiveWExtractor,
 }
 
 impl AlexandriaSearch {
@@ -51,6 +64,8 @@ impl AlexandriaSearch {
             graph: AlexandriaGraph::with_defaults(node_fingerprint),
             sync: SyncProtocol::new(node),
             node: node_fingerprint,
+            tesseract: SemanticTesseract::new(),
+            extractor: FiveWExtractor::new(),
         }
     }
 
@@ -62,38 +77,107 @@ impl AlexandriaSearch {
             graph: AlexandriaGraph::new(node_fingerprint, config),
             sync: SyncProtocol::new(node),
             node: node_fingerprint,
+            tesseract: SemanticTesseract::new(),
+            extractor: FiveWExtractor::new(),
+        }
+    }
+
+    /// Load the graph at `graph_path` and the tesseract saved beside it
+    ///
+    /// A missing graph or tesseract starts empty; a tesseract that exists
+    /// but does not load is an error, so [`Self::save`] never replaces it.
+    pub fn load_or_create(
+        node_fingerprint: NodeFingerprint,
+        graph_path: &Path,
+        config: AlexandriaConfig,
+    ) -> gently_alexandria::Result<Self> {
+        let mut search = Self::with_config(node_fingerprint, config.clone());
+        search.graph = AlexandriaGraph::load_or_create(graph_path, node_fingerprint, config);
+        let tesseract_path = Self::tesseract_path(graph_path);
+        if tesseract_path.exists() {
+            search.tesseract = SemanticTesseract::load(&tesseract_path)?;
         }
+        Ok(search)
+    }
+
+    /// Save the graph to `graph_path` and the tesseract beside it
+    pub fn save(&self, graph_path: &Path) -> gently_alexandria::Result<()> {
+        self.graph.save(graph_path)?;
+        self.tesseract.save(&Self::tesseract_path(graph_path))
+    }
+
+    /// Where the tesseract for the graph at `graph_path` lives
+    pub fn tesseract_path(graph_path: &Path) -> PathBuf {
+        graph_path.with_file_name("tesseract.json")
+    }
+
+    /// New thought tagged by this layer's extractor
+    pub fn thought(&self, content: impl Into<String>) -> Thought {
+        let mut thought = Thought::new(content);
+        thought.dimensions = self.extractor.extract(&thought.content, thought.created_at);
+        thought
     }
 
     /// Add a thought - goes to both local index AND Alexandria
     pub fn add_thought(&mut self, thought: Thought) -> uuid::Uuid {
         // Extract concept from thought content
         let concept_text = &thought.content;
+        let concept_id = ConceptId::from_concept(concept_text);
 
         // Record in Alexandria (builds usage edges)
         self.graph.record_query(concept_text);
 
         // Set embedding if available
         if let Some(ref embedding) = thought.shape.embedding {
-            let concept_id = ConceptId::from_concept(concept_text);
             self.graph.set_embedding(&concept_id, embedding.clone());
         }
 
         // Add keywords as related concepts
         for keyword in &thought.shape.keywords {
             let keyword_id = self.graph.ensure_concept(keyword);
-            let thought_id = ConceptId::from_concept(concept_text);
-            self.graph.add_edge(thought_id, keyword_id, EdgeKind::KeywordOverlap(vec![keyword.clone()]));
+            self.graph.add_edge(concept_id, keyword_id, EdgeKind::KeywordOverlap(vec![keyword.clone()]));
+            // Keywords are fragments of the thought: present in 5W space, untagged
+            self.place(keyword_id, &DimensionTags::new());
         }
 
+        // Place the concept in 5W space with the tags it already carries
+        self.place(concept_id, &thought.dimensions);
+
         // Add to local index
         self.index.add_thought(thought)
     }
 
+    /// Tag an Alexandria concept with 5W values and record its position
+    pub fn tag_concept(&mut self, text: &str) -> DimensionTags {
+        let concept_id = self.graph.ensure_concept(text);
+        let tags = self.extractor.extract(text, chrono::Utc::now());
+        self.place(concept_id, &tags);
+        tags
+    }
+
+    /// Record a position for `concept` unless it has one and `tags` adds nothing
+    fn place(&mut self, concept: ConceptId, tags: &DimensionTags) {
+        if !tags.is_empty() || self.tesseract.latest_position(&concept).is_none() {
+            self.tesseract.record_position(tags.to_position(concept));
+        }
+    }
+
+    /// Concept texts whose 5W position matches `dimension = value`
+    pub fn query_5w(&self, dimension: &str, value: &str) -> Vec<String> {
+        self.tesseract
+            .query_5w(dimension, value)
+            .iter()
+            .filter_map(|id| self.graph.get_concept(id))
+            .map(|c| c.text)
+            .collect()
+    }
+
     /// Search - queries both local index AND Alexandria
     pub fn search(&mut self, query: &str) -> SearchResults {
-        // Record the query in Alexandria
+        // Record the query in Alexandria, and in 5W space
         self.graph.record_query(query);
+        let tags = self.extractor.extract(query, chrono::Utc::now());
+        self.place(ConceptId::from_concept(query), &tags);
 
         // Get Alexandria topology
         let topology = self.graph.query_topology(query);
@@ -327,4 +411,45 @@ mod tests {
 
         assert!(proof.concepts_stored >= 2);
     }
+
+    #[test]
+    fn test_thoughts_placed_in_5w_space() {
+        let mut search = AlexandriaSearch::new(test_node());
+        search.extractor.gazetteer_mut().add_person("Alice");
+
+        let alice = search.thought("Alice deployed the backend in Berlin during 2024");
+        search.add_thought(alice);
+        search.add_thought(Thought::new("rust programming"));
+
+        let who = search.query_5w("who", "alice");
+        assert_eq!(who, vec!["Alice deployed the backend in Berlin during 2024".to_string()]);
+        assert_eq!(search.query_5w("where", "berlin").len(), 1);
+        assert_eq!(search.query_5w("when", "2024").len(), 1);
+        assert_eq!(search.query_5w("what", "deploy").len(), 1);
+
+        let tags = search.tag_concept("Mozilla released Rust in 2015");
+        assert_eq!(tags.best(crate::Dimension::Who).unwrap().value, "mozilla");
+        assert_eq!(search.query_5w("when", "2015"), vec!["Mozilla released Rust in 2015".to_string()]);
+    }
+
+    #[test]
+    fn test_every_concept_placed_and_tesseract_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let graph_path = dir.path().join("graph.json");
+
+        let mut search = AlexandriaSearch::load_or_create(test_node(), &graph_path, AlexandriaConfig::default()).unwrap();
+        search.add_thought(Thought::new("rust programming in Berlin"));
+        search.search("memory safety");
+        assert!(search.graph.all_concepts().iter().all(|c| search.tesseract.latest_position(&c.id).is_some()));
+        search.save(&graph_path).unwrap();
+
+        let reloaded = AlexandriaSearch::load_or_create(test_node(), &graph_path, AlexandriaConfig::default()).unwrap();
+        assert_eq!(reloaded.graph.concept_count(), search.graph.concept_count());
+        assert_eq!(reloaded.tesseract.concept_count(), search.tesseract.concept_count());
+        assert_eq!(reloaded.query_5w("where", "berlin"), vec!["rust programming in Berlin".to_string()]);
+
+        // A tesseract that does not load is reported, not silently replaced
+        std::fs::write(AlexandriaSearch::tesseract_path(&graph_path), b"not json").unwrap();
+        assert!(AlexandriaSearch::load_or_create(test_node(), &graph_path, AlexandriaConfig::default()).is_err());
+    }
 }
@@ -32,6 +32,9 @@
 
 use crate::collapse::{CollapseEngine, CollapseResult, CollapsedRow, RowBuilder};
 use crate::hyperspace::{Dimension, HyperspaceQuery, HyperspaceQueryBuilder};
+use crate::proof::{self, DatasetCommitment, DatasetSnapshot, ProofStep, RowWitness};
+use crate::rows::{RowFilter, RowSource};
+use crate::{Error, Result};
 use gently_alexandria::ConceptId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -516,6 +519,12 @@ pub struct BbbcpProof {
     pub bone_count: usize,
     /// CIRCLE count used
     pub circle_count: usize,
+    /// Snapshot the query ran over (verifiable execution only)
+    #[serde(default)]
+    pub dataset: Option<DatasetCommitment>,
+    /// BONE/CIRCLE/PIN steps with witnesses
+    #[serde(default)]
+    pub steps: Vec<ProofStep>,
 }
 
 impl BbbcpProof {
@@ -530,9 +539,112 @@ impl BbbcpProof {
             timestamp: Utc::now(),
             bone_count: query.bones.len(),
             circle_count: query.circles.len(),
+            dataset: None,
+            steps: Vec::new(),
         }
     }
 
+    /// Check the proof against the query and a published snapshot
+    ///
+    /// Needs no rows beyond those carried in the witnesses. Every snapshot
+    /// row must appear exactly once, under the step whose predicate it
+    /// satisfies; PIN is then rerun over the survivors to rebuild the output.
+    pub fn verify(&self, query: &BbbcpQuery, dataset: &DatasetCommitment) -> Result<()> {
+        let fail = |msg: &str| Err(Error::ProofInvalid(msg.to_string()));
+
+        if self.dataset.as_ref() != Some(dataset) {
+            return fail("proof is not bound to this snapshot");
+        }
+        if self.query_hash != Self::hash_query(query) {
+            return fail("query does not match proof");
+        }
+
+        let filter = RowFilter::for_bbbcp(query);
+        let mut covered = vec![false; dataset.size];
+        let mut claim = |witness: &RowWitness| {
+            witness.verify(dataset) && !std::mem::replace(&mut covered[witness.path.index], true)
+        };
+        let mut needles: Vec<String> = Vec::new();
+        let mut pins = Vec::new();
+
+        for step in &self.steps {
+            match step {
+                ProofStep::Bone { text } => {
+                    if !query.bones.iter().any(|b| &b.text == text) {
+                        return fail("BONE step not in query");
+                    }
+                }
+                ProofStep::Circle { text, eliminated, witnesses } => {
+                    // CIRCLE steps follow the query's order
+                    if query.circles.get(needles.len()).map(|c| &c.text) != Some(text) {
+                        return fail("CIRCLE step not in query");
+                    }
+                    if *eliminated != witnesses.len() {
+                        return fail("CIRCLE count does not match its rows");
+                    }
+                    let needle = text.to_lowercase();
+                    for witness in witnesses {
+                        if !claim(witness) {
+                            return fail("CIRCLE row not in snapshot or listed twice");
+                        }
+                        if !proof::circle_matches(&witness.row, &needle) {
+                            return fail("CIRCLE eliminated a row it does not match");
+                        }
+                        if needles.iter().any(|n| proof::circle_matches(&witness.row, n)) {
+                            return fail("CIRCLE row belongs to an earlier CIRCLE");
+                        }
+                    }
+                    needles.push(needle);
+                }
+                ProofStep::Pin { witnesses, survivors, excluded, .. } => {
+                    pins.push((witnesses, survivors, excluded))
+                }
+            }
+        }
+
+        let (selected, survivors, excluded) = match pins.as_slice() {
+            [pin] if needles.len() == query.circles.len() => *pin,
+            _ => return fail("proof is missing steps"),
+        };
+
+        let passes = |row: &CollapsedRow| filter.accepts(row) && filter.matches_terms(&proof::row_text(row));
+        for witness in survivors {
+            if !claim(witness) {
+                return fail("survivor not in snapshot or listed twice");
+            }
+            if !passes(&witness.row) {
+                return fail("survivor is excluded by the query");
+            }
+        }
+        for witness in excluded {
+            if !claim(witness) {
+                return fail("excluded row not in snapshot or listed twice");
+            }
+            if needles.iter().any(|n| proof::circle_matches(&witness.row, n)) {
+                return fail("excluded row belongs to a CIRCLE");
+            }
+            if passes(&witness.row) {
+                return fail("excluded row passes the query");
+            }
+        }
+        if covered.contains(&false) {
+            return fail("proof does not account for every row");
+        }
+
+        // Rerun PIN over the survivors in snapshot order, as execution streamed them
+        let mut ordered: Vec<&RowWitness> = survivors.iter().collect();
+        ordered.sort_by_key(|w| w.path.index);
+        let (output, _, kept, _) = BbbcpEngine::new().converge(query, ordered.into_iter().map(|w| w.row.clone()));
+
+        if Self::hash_output(&output) != self.output_hash {
+            return fail("output does not match the survivors");
+        }
+        if !kept.iter().map(proof::row_hash).eq(selected.iter().map(|w| proof::row_hash(&w.row))) {
+            return fail("PIN witnesses are not the rows PIN selects");
+        }
+        Ok(())
+    }
+
     fn hash_query(query: &BbbcpQuery) -> [u8; 32] {
         let mut hasher = Sha256::new();
         for bone in &query.bones {
@@ -555,7 +667,7 @@ impl BbbcpProof {
                 hasher.update(resp.text.as_bytes());
             }
             BbbcpOutput::Table(result) => {
-                hasher.update(format!("{}", result.rows.len()).as_bytes());
+                hasher.update(proof::hash_rows(&result.rows));
             }
             BbbcpOutput::Chain(chain) => {
                 for c in &chain.conclusions {
@@ -648,6 +760,108 @@ impl BbbcpEngine {
         // Apply PIN strategy
         let (output, new_bone) = self.apply_pin(&query.pin, &filtered, query);
 
+        self.finish(query, output, new_bone, initial_space, reduced_space, start)
+    }
+
+    /// Execute a BBBCP query against a lazy row source
+    ///
+    /// CIRCLE, BLOB terms and domain are pushed into the source, rows are
+    /// consumed as a stream and only what the PIN strategy needs is kept.
+    pub fn execute_source(&self, query: &BbbcpQuery, source: &dyn RowSource) -> BbbcpResult {
+        let start = std::time::Instant::now();
+        let filter = RowFilter::for_bbbcp(query);
+
+        let (output, new_bone, _, reduced_space) = self.converge(query, source.rows(&filter));
+
+        self.finish(query, output, new_bone, source.size(), reduced_space, start)
+    }
+
+    /// Execute over a published snapshot, recording a verifiable proof
+    ///
+    /// The proof commits to the snapshot root and witnesses every row:
+    /// under the CIRCLE that eliminated it, among the survivors, or as
+    /// excluded, plus the rows PIN converged on.
+    pub fn execute_verifiable(&self, query: &BbbcpQuery, snapshot: &DatasetSnapshot) -> BbbcpResult {
+        let start = std::time::Instant::now();
+        let filter = RowFilter::for_bbbcp(query);
+
+        let (output, new_bone, kept, reduced_space) = self.converge(query, RowSource::rows(snapshot, &filter));
+        let mut result = self.finish(query, output, new_bone, snapshot.len(), reduced_space, start);
+
+        let proof = &mut result.proof;
+        proof.dataset = Some(snapshot.commitment());
+        proof.steps.extend(query.bones.iter().map(|b| ProofStep::Bone { text: b.text.clone() }));
+        let circles: Vec<String> = query.circles.iter().map(|c| c.text.clone()).collect();
+        proof.steps.extend(proof::partition_steps(snapshot, &circles, &filter, format!("{:?}", query.pin), &kept));
+
+        result
+    }
+
+    /// Stream rows through the PIN strategy
+    ///
+    /// Returns the output, any new BONE, the rows PIN kept and how many rows
+    /// survived elimination.
+    fn converge(
+        &self,
+        query: &BbbcpQuery,
+        rows: impl Iterator<Item = CollapsedRow>,
+    ) -> (BbbcpOutput, Option<Bone>, Vec<CollapsedRow>, usize) {
+        let mut reduced_space = 0;
+        let mut quality_sum = 0.0;
+        let mut kept: Vec<CollapsedRow> = Vec::new();
+
+        for row in rows {
+            reduced_space += 1;
+            quality_sum += row.quality_score;
+
+            match &query.pin {
+                PinStrategy::ArgmaxQuality | PinStrategy::Custom(_) => {
+                    if kept.first().is_none_or(|best| row.quality_score > best.quality_score) {
+                        kept = vec![row];
+                    }
+                }
+                PinStrategy::TopN(n) => {
+                    kept.push(row);
+                    if kept.len() >= (*n).max(1) * 2 {
+                        kept.sort_by(|a, b| b.quality_score.total_cmp(&a.quality_score));
+                        kept.truncate(*n);
+                    }
+                }
+                PinStrategy::Aggregate | PinStrategy::Sequence => {
+                    if kept.len() < query.blob.limit {
+                        kept.push(row);
+                    }
+                }
+            }
+        }
+
+        if let PinStrategy::TopN(n) = &query.pin {
+            kept.sort_by(|a, b| b.quality_score.total_cmp(&a.quality_score));
+            kept.truncate(*n);
+        }
+
+        let (output, new_bone) = match &query.pin {
+            // Aggregate covers every surviving row, not just the kept sample
+            PinStrategy::Aggregate if reduced_space > 0 => {
+                let avg_quality = quality_sum / reduced_space as f32;
+                let sources = kept.iter().flat_map(|r| r.source_concepts.clone()).collect();
+                self.aggregate(reduced_space, avg_quality, sources)
+            }
+            strategy => self.apply_pin(strategy, &kept, query),
+        };
+
+        (output, new_bone, kept, reduced_space)
+    }
+
+    fn finish(
+        &self,
+        query: &BbbcpQuery,
+        output: BbbcpOutput,
+        new_bone: Option<Bone>,
+        initial_space: usize,
+        reduced_space: usize,
+        start: std::time::Instant,
+    ) -> BbbcpResult {
         let elimination_ratio = if initial_space > 0 {
             1.0 - (reduced_space as f32 / initial_space as f32)
         } else {
@@ -722,7 +936,7 @@ impl BbbcpEngine {
 
                 let text = format!(
                     "Best match: {}",
-                    best.values.values().next().unwrap_or(&"(empty)".to_string())
+                    best.ordered_values().next().unwrap_or("(empty)")
                 );
 
                 let new_bone = if self.generate_bones && best.quality_score >= self.quality_threshold {
@@ -748,24 +962,9 @@ impl BbbcpEngine {
                 // Aggregate all results
                 let count = data.len();
                 let avg_quality = data.iter().map(|r| r.quality_score).sum::<f32>() / count as f32;
+                let sources = data.iter().flat_map(|r| r.source_concepts.clone()).collect();
 
-                let text = format!("Aggregated {} results, avg quality {:.2}", count, avg_quality);
-
-                let new_bone = if self.generate_bones && avg_quality >= self.quality_threshold {
-                    Some(Bone::from_inference(text.clone(), avg_quality))
-                } else {
-                    None
-                };
-
-                (
-                    BbbcpOutput::Answer(OptimizedResponse {
-                        text,
-                        quality: avg_quality,
-                        sources: data.iter().flat_map(|r| r.source_concepts.clone()).collect(),
-                        elimination_ratio: 0.0,
-                    }),
-                    new_bone,
-                )
+                self.aggregate(count, avg_quality, sources)
             }
             PinStrategy::TopN(n) => {
                 // Return top N as table
@@ -805,8 +1004,7 @@ impl BbbcpEngine {
                 let conclusions: Vec<ChainedConclusion> = data.iter()
                     .enumerate()
                     .map(|(i, row)| {
-                        let text = row.values.values()
-                            .map(|v| v.as_str())
+                        let text = row.ordered_values()
                             .collect::<Vec<_>>()
                             .join(" → ");
 
@@ -816,7 +1014,7 @@ impl BbbcpEngine {
                             quality: row.quality_score,
                             bone: if row.quality_score >= self.quality_threshold {
                                 Some(Bone::from_inference(
-                                    format!("Step {}: {}", i + 1, row.values.values().next().unwrap_or(&"".to_string())),
+                                    format!("Step {}: {}", i + 1, row.ordered_values().next().unwrap_or("")),
                                     row.quality_score,
                                 ))
                             } else {
@@ -845,6 +1043,27 @@ impl BbbcpEngine {
             }
         }
     }
+
+    /// Aggregate PIN output
+    fn aggregate(&self, count: usize, avg_quality: f32, sources: Vec<ConceptId>) -> (BbbcpOutput, Option<Bone>) {
+        let text = format!("Aggregated {} results, avg quality {:.2}", count, avg_quality);
+
+        let new_bone = if self.generate_bones && avg_quality >= self.quality_threshold {
+            Some(Bone::from_inference(text.clone(), avg_quality))
+        } else {
+            None
+        };
+
+        (
+            BbbcpOutput::Answer(OptimizedResponse {
+                text,
+                quality: avg_quality,
+                sources,
+                elimination_ratio: 0.0,
+            }),
+            new_bone,
+        )
+    }
 }
 
 impl Default for BbbcpEngine {
@@ -1078,4 +1297,200 @@ mod tests {
         let query_chain = ChainForward::to_query("next-query");
         assert!(query_chain.target_query.is_some());
     }
+
+    #[test]
+    fn test_execute_source_streams_index() {
+        let mut index = crate::ThoughtIndex::new();
+        for i in 0..60 {
+            index.add_thought(crate::Thought::new(format!("auth token rotation note {}", i)));
+        }
+        index.add_thought(crate::Thought::new("auth tokens kept in plaintext"));
+        index.add_thought(crate::Thought::new("unrelated gardening tips"));
+        let source = crate::IndexRows::new(&index);
+        let engine = BbbcpEngine::new();
+
+        let query = BbbcpQuery::builder()
+            .circle("plaintext")
+            .blob(BlobSearch::semantic("auth token"))
+            .pin(PinStrategy::TopN(5))
+            .build();
+        let result = engine.execute_source(&query, &source);
+
+        assert_eq!(result.stats.initial_space, 62);
+        assert_eq!(result.stats.reduced_space, 60);
+        match result.output {
+            BbbcpOutput::Table(table) => assert_eq!(table.rows.len(), 5),
+            other => panic!("expected table, got {:?}", other),
+        }
+
+        let query = BbbcpQuery::builder()
+            .blob(BlobSearch::semantic("auth").limit(10))
+            .pin(PinStrategy::Aggregate)
+            .build();
+        let result = engine.execute_source(&query, &source);
+        match result.output {
+            BbbcpOutput::Answer(answer) => {
+                // Every match is counted, only the first `limit` rows are kept
+                assert!(answer.text.starts_with("Aggregated 61 results"));
+                assert!(!answer.sources.is_empty());
+            }
+            other => panic!("expected answer, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_verifiable_execution() {
+        let snapshot = crate::DatasetSnapshot::from_rows(vec![
+            RowBuilder::new().who("alice").what("jwt auth").quality(0.9).build(),
+            RowBuilder::new().who("bob").what("auth in plaintext").quality(0.95).build(),
+            RowBuilder::new().who("carol").what("session auth").quality(0.6).build(),
+            RowBuilder::new().who("dave").what("gardening").quality(0.99).build(),
+        ]);
+        let published = snapshot.commitment();
+
+        let query = BbbcpQuery::builder()
+            .bone("MUST validate signatures")
+            .circle("plaintext")
+            .blob(BlobSearch::semantic("auth"))
+            .pin(PinStrategy::ArgmaxQuality)
+            .build();
+        let result = BbbcpEngine::new().execute_verifiable(&query, &snapshot);
+        assert_eq!(result.stats.reduced_space, 2);
+
+        // A teammate gets the proof as JSON and checks it against the published root
+        let json = serde_json::to_string(&result.proof).unwrap();
+        let shared: BbbcpProof = serde_json::from_str(&json).unwrap();
+        shared.verify(&query, &published).unwrap();
+
+        // Different snapshot or query is rejected
+        let other = crate::DatasetSnapshot::from_rows(vec![RowBuilder::new().what("x").build()]);
+        assert!(shared.verify(&query, &other.commitment()).is_err());
+        let other_query = BbbcpQuery::builder().blob(BlobSearch::semantic("auth")).build();
+        assert!(shared.verify(&other_query, &published).is_err());
+
+        // Forged selection is rejected
+        let mut forged = shared.clone();
+        for step in &mut forged.steps {
+            if let ProofStep::Pin { witnesses, .. } = step {
+                witnesses[0].row.set(Dimension::Who, "mallory");
+            }
+        }
+        assert!(forged.verify(&query, &published).is_err());
+
+        // Selecting an eliminated row is rejected even with a valid witness
+        let mut sneaky = shared.clone();
+        let eliminated = snapshot.rows().iter().find(|r| r.get(Dimension::Who) == Some("bob")).unwrap();
+        for step in &mut sneaky.steps {
+            if let ProofStep::Pin { witnesses, .. } = step {
+                witnesses[0] = snapshot.witness(eliminated).unwrap();
+            }
+        }
+        assert!(sneaky.verify(&query, &published).is_err());
+    }
+
+    #[test]
+    fn test_verifiable_rejects_forged_outputs_and_counts() {
+        let snapshot = crate::DatasetSnapshot::from_rows(vec![
+            RowBuilder::new().who("alice").what("jwt auth").quality(0.9).build(),
+            RowBuilder::new().who("bob").what("auth in plaintext").quality(0.95).build(),
+            RowBuilder::new().who("carol").what("session auth").quality(0.6).build(),
+            RowBuilder::new().who("dave").what("gardening").quality(0.99).build(),
+            RowBuilder::new().who("erin").what("oauth flows").quality(0.7).build(),
+        ]);
+        let published = snapshot.commitment();
+        let engine = BbbcpEngine::new();
+        let query = |pin| {
+            BbbcpQuery::builder()
+                .circle("plaintext")
+                .circle("session")
+                .blob(BlobSearch::semantic("auth").limit(2))
+                .pin(pin)
+                .build()
+        };
+
+        for pin in [PinStrategy::ArgmaxQuality, PinStrategy::TopN(2), PinStrategy::Aggregate, PinStrategy::Sequence] {
+            let query = query(pin);
+            engine.execute_verifiable(&query, &snapshot).proof.verify(&query, &published).unwrap();
+        }
+
+        let query = query(PinStrategy::ArgmaxQuality);
+        let proof = engine.execute_verifiable(&query, &snapshot).proof;
+        let witness = |who: &str| {
+            let row = snapshot.rows().iter().find(|r| r.get(Dimension::Who) == Some(who)).unwrap();
+            snapshot.witness(row).unwrap()
+        };
+        let rejected = |edit: &dyn Fn(&mut BbbcpProof)| {
+            let mut forged = proof.clone();
+            edit(&mut forged);
+            forged.verify(&query, &published).is_err()
+        };
+        let pin = |proof: &mut BbbcpProof| -> (Vec<RowWitness>, Vec<RowWitness>, Vec<RowWitness>) {
+            match proof.steps.last().unwrap() {
+                ProofStep::Pin { witnesses, survivors, excluded, .. } => {
+                    (witnesses.clone(), survivors.clone(), excluded.clone())
+                }
+                other => panic!("expected PIN, got {:?}", other),
+            }
+        };
+        let set_pin = |proof: &mut BbbcpProof, w: Vec<RowWitness>, s: Vec<RowWitness>, e: Vec<RowWitness>| {
+            if let Some(ProofStep::Pin { witnesses, survivors, excluded, .. }) = proof.steps.last_mut() {
+                (*witnesses, *survivors, *excluded) = (w, s, e);
+            }
+        };
+
+        // Output that does not follow from the survivors
+        assert!(rejected(&|p| {
+            p.output_hash = BbbcpProof::hash_output(&BbbcpOutput::Answer(OptimizedResponse {
+                text: "Best match: mallory".to_string(),
+                quality: 1.0,
+                sources: Vec::new(),
+                elimination_ratio: 0.0,
+            }))
+        }));
+
+        // Hiding alice so erin wins, whether dropped or moved to excluded
+        let forged_answer = |p: &mut BbbcpProof| {
+            let (output, _) = engine.apply_pin(&PinStrategy::ArgmaxQuality, &[witness("erin").row], &query);
+            p.output_hash = BbbcpProof::hash_output(&output);
+        };
+        assert!(rejected(&|p| {
+            let (_, survivors, excluded) = pin(p);
+            let survivors = survivors.into_iter().filter(|w| w.row.get(Dimension::Who) != Some("alice")).collect();
+            set_pin(p, vec![witness("erin")], survivors, excluded);
+            forged_answer(p);
+        }));
+        assert!(rejected(&|p| {
+            let (_, survivors, mut excluded) = pin(p);
+            let survivors = survivors.into_iter().filter(|w| w.row.get(Dimension::Who) != Some("alice")).collect();
+            excluded.push(witness("alice"));
+            set_pin(p, vec![witness("erin")], survivors, excluded);
+            forged_answer(p);
+        }));
+
+        // A row eliminated by CIRCLE smuggled in as a survivor
+        assert!(rejected(&|p| {
+            let (selected, mut survivors, excluded) = pin(p);
+            survivors.push(witness("bob"));
+            set_pin(p, selected, survivors, excluded);
+        }));
+
+        // Eliminated counts and rows that disagree
+        assert!(rejected(&|p| {
+            if let ProofStep::Circle { eliminated, .. } = &mut p.steps[1] {
+                *eliminated += 1;
+            }
+        }));
+        assert!(rejected(&|p| {
+            if let ProofStep::Circle { eliminated, witnesses, .. } = &mut p.steps[1] {
+                witnesses.clear();
+                *eliminated = 0;
+            }
+        }));
+        assert!(rejected(&|p| {
+            if let ProofStep::Circle { eliminated, witnesses, .. } = &mut p.steps[1] {
+                witnesses.push(witness("dave"));
+                *eliminated += 1;
+            }
+        }));
+    }
 }
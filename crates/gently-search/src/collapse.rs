@@ -24,6 +24,9 @@
 //! - **ENUMERATE**: Expand dimensions into columns
 
 use crate::hyperspace::{Dimension, DimensionFilter, DimensionValue, FilterOp, HyperspaceQuery};
+use crate::proof::{self, DatasetCommitment, DatasetSnapshot, ProofStep};
+use crate::rows::{RowFilter, RowSource};
+use crate::{Error, Result};
 use gently_alexandria::ConceptId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -60,6 +63,11 @@ impl CollapsedRow {
         self.values.get(&dim).map(|s| s.as_str())
     }
 
+    /// Values in dimension order (stable, unlike `values` iteration)
+    pub fn ordered_values(&self) -> impl Iterator<Item = &str> {
+        Dimension::all().iter().filter_map(|d| self.get(*d))
+    }
+
     /// Set value for a dimension
     pub fn set(&mut self, dim: Dimension, value: impl Into<String>) {
         self.values.insert(dim, value.into());
@@ -92,13 +100,19 @@ pub struct CollapseProof {
     pub source_count: usize,
     /// Number of result rows
     pub row_count: usize,
+    /// Snapshot the query ran over (verifiable collapse only)
+    #[serde(default)]
+    pub dataset: Option<DatasetCommitment>,
+    /// PIN step with a witness for every result row
+    #[serde(default)]
+    pub steps: Vec<ProofStep>,
 }
 
 impl CollapseProof {
     /// Create a new proof from query and result
     pub fn new(query: &HyperspaceQuery, rows: &[CollapsedRow]) -> Self {
         let query_hash = Self::hash_query(query);
-        let result_hash = Self::hash_rows(rows);
+        let result_hash = proof::hash_rows(rows);
 
         Self {
             query_hash,
@@ -106,14 +120,22 @@ impl CollapseProof {
             timestamp: Utc::now(),
             source_count: rows.iter().map(|r| r.source_concepts.len()).sum(),
             row_count: rows.len(),
+            dataset: None,
+            steps: Vec::new(),
         }
     }
 
     fn hash_query(query: &HyperspaceQuery) -> [u8; 32] {
         let mut hasher = Sha256::new();
-        // Hash pinned dimensions
-        for (dim, val) in &query.pin {
-            hasher.update(format!("{:?}:{}", dim, val.value).as_bytes());
+        // Hash pinned dimensions (in dimension order, so every party agrees)
+        for dim in Dimension::all() {
+            if let Some(val) = query.pin.get(dim) {
+                hasher.update(format!("{:?}:{}", dim, val.value).as_bytes());
+            }
+        }
+        // Hash filters
+        for filter in &query.filter {
+            hasher.update(format!("filter:{:?}:{:?}:{:?}", filter.dimension, filter.operator, filter.value).as_bytes());
         }
         // Hash collapsed dimensions
         for dim in &query.collapse {
@@ -129,24 +151,47 @@ impl CollapseProof {
         hash
     }
 
-    fn hash_rows(rows: &[CollapsedRow]) -> [u8; 32] {
-        let mut hasher = Sha256::new();
-        for row in rows {
-            for (dim, val) in &row.values {
-                hasher.update(format!("{:?}:{}", dim, val).as_bytes());
-            }
-        }
-        let result = hasher.finalize();
-        let mut hash = [0u8; 32];
-        hash.copy_from_slice(&result);
-        hash
-    }
-
     /// Verify the proof matches a result
     pub fn verify(&self, rows: &[CollapsedRow]) -> bool {
-        let computed = Self::hash_rows(rows);
+        let computed = proof::hash_rows(rows);
         self.result_hash == computed
     }
+
+    /// Check the proof against the query and a published snapshot
+    ///
+    /// Every result row must be in the snapshot, satisfy the query's PINs
+    /// and filters, and together hash to `result_hash`.
+    pub fn verify_against(&self, query: &HyperspaceQuery, dataset: &DatasetCommitment) -> Result<()> {
+        let fail = |msg: &str| Err(Error::ProofInvalid(msg.to_string()));
+
+        if self.dataset.as_ref() != Some(dataset) {
+            return fail("proof is not bound to this snapshot");
+        }
+        if self.query_hash != Self::hash_query(query) {
+            return fail("query does not match proof");
+        }
+
+        let witnesses = match self.steps.as_slice() {
+            [ProofStep::Pin { witnesses, .. }] => witnesses,
+            _ => return fail("proof is missing its PIN step"),
+        };
+
+        let filter = RowFilter::for_hyperspace(query, f32::MIN);
+        for witness in witnesses {
+            if !witness.verify(dataset) {
+                return fail("row witness not in snapshot");
+            }
+            if !filter.accepts(&witness.row) {
+                return fail("row does not satisfy the query");
+            }
+        }
+
+        let rows: Vec<CollapsedRow> = witnesses.iter().map(|w| w.row.clone()).collect();
+        if rows.len() != self.row_count || !self.verify(&rows) {
+            return fail("witnessed rows do not match the result");
+        }
+        Ok(())
+    }
 }
 
 /// Result of a collapse operation
@@ -307,6 +352,37 @@ impl CollapseEngine {
             .take(self.max_rows)
             .collect();
 
+        self.finish(query, filtered, data.len(), start)
+    }
+
+    /// Collapse a hyperspace query over a lazy row source
+    ///
+    /// PINs and filters are pushed into the source and the stream stops as
+    /// soon as `max_rows` rows have been produced.
+    pub fn collapse_source(&self, query: &HyperspaceQuery, source: &dyn RowSource) -> CollapseResult {
+        let start = std::time::Instant::now();
+        let filter = RowFilter::for_hyperspace(query, self.quality_threshold);
+
+        let filtered: Vec<CollapsedRow> = source.rows(&filter).take(self.max_rows).collect();
+
+        self.finish(query, filtered, source.size(), start)
+    }
+
+    /// Collapse over a published snapshot, recording a verifiable proof
+    pub fn collapse_verifiable(&self, query: &HyperspaceQuery, snapshot: &DatasetSnapshot) -> CollapseResult {
+        let mut result = self.collapse_source(query, snapshot);
+        result.proof.dataset = Some(snapshot.commitment());
+        result.proof.steps.push(proof::pin_step(snapshot, "collapse".to_string(), &result.rows));
+        result
+    }
+
+    fn finish(
+        &self,
+        query: &HyperspaceQuery,
+        filtered: Vec<CollapsedRow>,
+        concepts_searched: usize,
+        start: std::time::Instant,
+    ) -> CollapseResult {
         // Generate BONE if we have a PIN
         let new_bone = if self.generate_bones && !query.pin.is_empty() && !filtered.is_empty() {
             Some(self.generate_bone(query, &filtered))
@@ -322,7 +398,7 @@ impl CollapseEngine {
         };
 
         let stats = CollapseStats {
-            concepts_searched: data.len(),
+            concepts_searched,
             concepts_filtered: filtered.len(),
             rows_generated: filtered.len(),
             dimensions_collapsed: query.collapse.len(),
@@ -784,4 +860,36 @@ mod tests {
         assert_eq!(result.stats.dimensions_enumerated, 1);
         assert!((result.stats.avg_quality - 0.7).abs() < 0.01);
     }
+
+    #[test]
+    fn test_verifiable_collapse() {
+        let snapshot = DatasetSnapshot::from_rows(vec![
+            RowBuilder::new().who("alice").r#where("security").quality(0.9).build(),
+            RowBuilder::new().who("bob").r#where("security").quality(0.7).build(),
+            RowBuilder::new().who("carol").r#where("network").quality(0.8).build(),
+        ]);
+        let published = snapshot.commitment();
+        let query = crate::hyperspace::HyperspaceQueryBuilder::new()
+            .pin(Dimension::Where, "security")
+            .enumerate_dim(Dimension::Who)
+            .build();
+
+        let result = CollapseEngine::new().collapse_verifiable(&query, &snapshot);
+        assert_eq!(result.row_count(), 2);
+        result.proof.verify_against(&query, &published).unwrap();
+
+        // Dropping a row from the shared result breaks the proof
+        let mut trimmed = result.proof.clone();
+        if let ProofStep::Pin { witnesses, .. } = &mut trimmed.steps[0] {
+            witnesses.pop();
+        }
+        assert!(trimmed.verify_against(&query, &published).is_err());
+
+        // Rows that don't match the PIN can't be smuggled in
+        let other_query = crate::hyperspace::HyperspaceQueryBuilder::new()
+            .pin(Dimension::Where, "network")
+            .enumerate_dim(Dimension::Who)
+            .build();
+        assert!(result.proof.verify_against(&other_query, &published).is_err());
+    }
 }
@@ -0,0 +1,795 @@
+//! 5W Extraction - Tag content with WHO/WHAT/WHERE/WHEN/WHY
+//!
+//! ```text
+//! "Alice fixed the auth bug in Berlin on March 3, 2024 because logins failed"
+//!
+//!   RULES ──► WHO   alice            0.90  (gazetteer)
+//!         ──► WHAT  fix              0.70  (action verb)
+//!         ──► WHERE berlin           0.90  (gazetteer)
+//!         ──► WHERE auth             0.70  (domain keyword)
+//!         ──► WHEN  2024-03-03/2024-03-03  0.95  (explicit date)
+//!         ──► WHY   logins failed    0.70  (causal clause)
+//!
+//!   REFINER (optional, e.g. local LLM) ──► adds / confirms values
+//! ```
+//!
+//! Dates are normalized to inclusive day ranges (`YYYY-MM-DD/YYYY-MM-DD`)
+//! so "March 2024", "Q1 2024" and "last week" all become comparable.
+
+use crate::collapse::CollapsedRow;
+use crate::hyperspace::{Dimension, DimensionValue};
+use crate::thought::is_common_word;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use gently_alexandria::{ConceptId, HyperPosition, TemporalPosition};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::LazyLock;
+
+/// Values below this confidence are kept on the tags but not projected
+/// into the tesseract
+pub const MIN_POSITION_CONFIDENCE: f32 = 0.5;
+
+const MONTHS: [(&str, u32); 24] = [
+    ("january", 1), ("february", 2), ("march", 3), ("april", 4),
+    ("may", 5), ("june", 6), ("july", 7), ("august", 8),
+    ("september", 9), ("october", 10), ("november", 11), ("december", 12),
+    ("jan", 1), ("feb", 2), ("mar", 3), ("apr", 4),
+    ("jun", 6), ("jul", 7), ("aug", 8), ("sep", 9), ("sept", 9),
+    ("oct", 10), ("nov", 11), ("dec", 12),
+];
+
+const MONTH_PATTERN: &str = "january|february|march|april|may|june|july|august|september|october|november|december|jan|feb|mar|apr|jun|jul|aug|sept|sep|oct|nov|dec";
+
+const WHERE_KEYWORDS: &[&str] = &[
+    "security", "auth", "database", "network", "api", "frontend", "backend",
+    "kernel", "storage", "ui",
+];
+
+const WHO_ROLES: &[&str] = &[
+    "user", "users", "developer", "developers", "admin", "team", "customer",
+    "maintainer", "operator",
+];
+
+const ACTIONS: &[(&str, &str)] = &[
+    ("fix", "fix"), ("fixed", "fix"), ("build", "build"), ("built", "build"),
+    ("create", "create"), ("created", "create"), ("delete", "delete"),
+    ("deleted", "delete"), ("update", "update"), ("updated", "update"),
+    ("deploy", "deploy"), ("deployed", "deploy"), ("refactor", "refactor"),
+    ("refactored", "refactor"), ("release", "release"), ("released", "release"),
+    ("migrate", "migrate"), ("migrated", "migrate"), ("test", "test"),
+    ("tested", "test"), ("learn", "learn"), ("learned", "learn"),
+];
+
+const OUTCOMES: &[&str] = &["failed", "broke", "fixed", "resolved", "error", "bug", "success", "crash"];
+
+/// Inclusive date range (the normalized WHEN value)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl TimeRange {
+    /// A single day
+    pub fn day(date: NaiveDate) -> Self {
+        Self { from: date, to: date }
+    }
+
+    /// A calendar month
+    pub fn month(year: i32, month: u32) -> Option<Self> {
+        let from = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let next = if month == 12 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+        } else {
+            NaiveDate::from_ymd_opt(year, month + 1, 1)?
+        };
+        Some(Self { from, to: next.pred_opt()? })
+    }
+
+    /// A calendar quarter (1-4)
+    pub fn quarter(year: i32, quarter: u32) -> Option<Self> {
+        let first = TimeRange::month(year, (quarter - 1) * 3 + 1)?;
+        let last = TimeRange::month(year, quarter * 3)?;
+        Some(Self { from: first.from, to: last.to })
+    }
+
+    /// A calendar year
+    pub fn year(year: i32) -> Option<Self> {
+        Some(Self {
+            from: NaiveDate::from_ymd_opt(year, 1, 1)?,
+            to: NaiveDate::from_ymd_opt(year, 12, 31)?,
+        })
+    }
+
+    /// Parse `YYYY-MM-DD/YYYY-MM-DD`
+    pub fn parse(value: &str) -> Option<Self> {
+        let (from, to) = value.split_once('/')?;
+        Some(Self {
+            from: from.parse().ok()?,
+            to: to.parse().ok()?,
+        })
+    }
+
+    /// Start of the range as a UTC timestamp
+    pub fn start(&self) -> DateTime<Utc> {
+        Utc.from_utc_datetime(&self.from.and_hms_opt(0, 0, 0).unwrap_or_default())
+    }
+
+    /// End of the range as a UTC timestamp (last second of the last day)
+    pub fn end(&self) -> DateTime<Utc> {
+        Utc.from_utc_datetime(&self.to.and_hms_opt(23, 59, 59).unwrap_or_default())
+    }
+
+    /// Does the range contain this instant?
+    pub fn contains(&self, when: DateTime<Utc>) -> bool {
+        when >= self.start() && when <= self.end()
+    }
+
+    /// Normalized string form
+    pub fn to_value(&self) -> String {
+        format!("{}/{}", self.from, self.to)
+    }
+}
+
+/// Known names for rule-based recognition
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Gazetteer {
+    /// People (lowercase)
+    pub people: BTreeSet<String>,
+    /// Places (lowercase)
+    pub places: BTreeSet<String>,
+    /// Organizations - treated as WHO (lowercase)
+    pub organizations: BTreeSet<String>,
+}
+
+impl Gazetteer {
+    /// Empty gazetteer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Built-in list of common places
+    pub fn with_defaults() -> Self {
+        let places = [
+            "london", "paris", "berlin", "tokyo", "new york", "san francisco",
+            "seattle", "boston", "chicago", "toronto", "vancouver", "sydney",
+            "melbourne", "singapore", "hong kong", "beijing", "shanghai",
+            "mumbai", "bangalore", "dublin", "amsterdam", "stockholm", "oslo",
+            "helsinki", "zurich", "vienna", "madrid", "barcelona", "rome",
+            "lisbon", "warsaw", "prague", "usa", "uk", "canada", "germany",
+            "france", "japan", "china", "india", "europe", "asia", "africa",
+            "australia", "silicon valley",
+        ];
+        let organizations = [
+            "mozilla", "google", "microsoft", "apple", "amazon", "aws", "meta",
+            "github", "gitlab", "openai", "ietf", "w3c", "linux foundation",
+        ];
+        Self {
+            people: BTreeSet::new(),
+            places: places.iter().map(|s| s.to_string()).collect(),
+            organizations: organizations.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Add a person
+    pub fn add_person(&mut self, name: &str) {
+        self.people.insert(name.to_lowercase());
+    }
+
+    /// Add a place
+    pub fn add_place(&mut self, name: &str) {
+        self.places.insert(name.to_lowercase());
+    }
+
+    /// Add an organization
+    pub fn add_organization(&mut self, name: &str) {
+        self.organizations.insert(name.to_lowercase());
+    }
+
+    fn is_known(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.people.contains(&name) || self.places.contains(&name) || self.organizations.contains(&name)
+    }
+}
+
+/// 5W values extracted from one piece of content
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DimensionTags {
+    pub values: HashMap<Dimension, Vec<DimensionValue>>,
+}
+
+impl DimensionTags {
+    /// Empty tags
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a value (case-insensitive dedupe keeps the higher confidence)
+    pub fn add(&mut self, dim: Dimension, value: DimensionValue) {
+        let values = self.values.entry(dim).or_default();
+        match values.iter_mut().find(|v| v.value.eq_ignore_ascii_case(&value.value)) {
+            Some(existing) => existing.confidence = existing.confidence.max(value.confidence),
+            None => values.push(value),
+        }
+        values.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    }
+
+    /// All values for a dimension (highest confidence first)
+    pub fn get(&self, dim: Dimension) -> &[DimensionValue] {
+        self.values.get(&dim).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// Highest-confidence value for a dimension
+    pub fn best(&self, dim: Dimension) -> Option<&DimensionValue> {
+        self.get(dim).first()
+    }
+
+    /// Normalized WHEN range (best value)
+    pub fn time_range(&self) -> Option<TimeRange> {
+        self.get(Dimension::When).iter().find_map(|v| TimeRange::parse(&v.value))
+    }
+
+    /// Nothing extracted?
+    pub fn is_empty(&self) -> bool {
+        self.values.values().all(|v| v.is_empty())
+    }
+
+    /// Best value per dimension as a table row
+    pub fn to_row(&self) -> CollapsedRow {
+        let mut row = CollapsedRow::new();
+        let mut confidences = Vec::new();
+        for dim in Dimension::all() {
+            if let Some(best) = self.best(*dim) {
+                row.set(*dim, best.value.clone());
+                row.source_concepts.extend(best.concepts.iter().copied());
+                confidences.push(best.confidence);
+            }
+        }
+        if !confidences.is_empty() {
+            row.quality_score = confidences.iter().sum::<f32>() / confidences.len() as f32;
+        }
+        row
+    }
+
+    /// Project into a tesseract position for `concept`
+    ///
+    /// WHO → observer, WHAT → actual, WHERE → context, WHEN → era tags and
+    /// validity window, WHY → purpose.
+    pub fn to_position(&self, concept: ConceptId) -> HyperPosition {
+        let confident = |dim| {
+            self.get(dim)
+                .iter()
+                .filter(|v| v.confidence >= MIN_POSITION_CONFIDENCE)
+        };
+
+        let mut era_tags: Vec<String> = Vec::new();
+        for value in confident(Dimension::When) {
+            era_tags.push(value.value.clone());
+            if let Some(range) = TimeRange::parse(&value.value) {
+                if range.from.year() == range.to.year() {
+                    era_tags.push(range.from.year().to_string());
+                }
+            }
+        }
+        era_tags.dedup();
+        let range = self.time_range();
+
+        HyperPosition {
+            concept,
+            actual: confident(Dimension::What).map(|v| ConceptId::from_concept(&v.value)).collect(),
+            eliminated: Vec::new(),
+            potential: Vec::new(),
+            temporal: TemporalPosition {
+                valid_from: range.map(|r| r.start()),
+                valid_until: range.map(|r| r.end()),
+                era_tags,
+                moments: Vec::new(),
+            },
+            observer: confident(Dimension::Who).map(|v| v.value.clone()).collect(),
+            context: confident(Dimension::Where).map(|v| v.value.clone()).collect(),
+            method: Vec::new(),
+            purpose: confident(Dimension::Why).map(|v| ConceptId::from_concept(&v.value)).collect(),
+            embedding: None,
+            face_embeddings: None,
+            recorded_at: Utc::now(),
+        }
+    }
+}
+
+/// Second-pass refinement (e.g. a local LLM)
+///
+/// Gets the content and the rule-based tags; returns extra or confirming
+/// values which are merged in.
+pub trait DimensionRefiner: Send + Sync {
+    fn refine(&self, text: &str, tags: &DimensionTags) -> Vec<(Dimension, DimensionValue)>;
+}
+
+/// Prompt asking a language model for 5W values
+pub fn refinement_prompt(text: &str, tags: &DimensionTags) -> String {
+    let mut prompt = String::from(
+        "Extract WHO, WHAT, WHERE, WHEN and WHY from the text. \
+         Answer with one line per dimension like `WHO: value`, use `-` when unknown.\n\n",
+    );
+    prompt.push_str(&format!("Text: {}\n", text));
+    if !tags.is_empty() {
+        prompt.push_str("Candidates:");
+        for dim in Dimension::all() {
+            if let Some(best) = tags.best(*dim) {
+                prompt.push_str(&format!(" {}={};", dim.name(), best.value));
+            }
+        }
+        prompt.push('\n');
+    }
+    prompt.push_str("\nAnswer:\n");
+    prompt
+}
+
+/// Parse `WHO: value` lines from a model response
+pub fn parse_refinement(response: &str, confidence: f32) -> Vec<(Dimension, DimensionValue)> {
+    response
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let dim = match name.trim().trim_start_matches(['-', '*', ' ']).to_uppercase().as_str() {
+                "WHO" => Dimension::Who,
+                "WHAT" => Dimension::What,
+                "WHERE" => Dimension::Where,
+                "WHEN" => Dimension::When,
+                "WHY" => Dimension::Why,
+                _ => return None,
+            };
+            let value = value.trim().trim_matches(['`', '"', '.']).to_lowercase();
+            if value.is_empty() || value == "-" || value == "unknown" || value == "none" {
+                return None;
+            }
+            Some((dim, tagged(&value, confidence)))
+        })
+        .collect()
+}
+
+fn tagged(value: &str, confidence: f32) -> DimensionValue {
+    DimensionValue::new(value)
+        .with_confidence(confidence)
+        .with_concepts(vec![ConceptId::from_concept(value)])
+}
+
+/// Rule + gazetteer 5W extractor with optional refinement
+pub struct FiveWExtractor {
+    gazetteer: Gazetteer,
+    refiner: Option<Box<dyn DimensionRefiner>>,
+    iso_date: Regex,
+    month_day_year: Regex,
+    month_year: Regex,
+    bare_month: Regex,
+    quarter: Regex,
+    decade: Regex,
+    year: Regex,
+    days_ago: Regex,
+    handle: Regex,
+    by_person: Regex,
+    titled_person: Regex,
+    in_place: Regex,
+    file_path: Regex,
+    cause: Regex,
+}
+
+impl FiveWExtractor {
+    /// Extractor with the built-in gazetteer
+    pub fn new() -> Self {
+        Self::with_gazetteer(Gazetteer::with_defaults())
+    }
+
+    /// Extractor with a custom gazetteer
+    pub fn with_gazetteer(gazetteer: Gazetteer) -> Self {
+        let re = |pattern: &str| Regex::new(pattern).expect("valid extractor regex");
+        Self {
+            gazetteer,
+            refiner: None,
+            iso_date: re(r"\b(\d{4})-(\d{2})-(\d{2})\b"),
+            month_day_year: re(&format!(r"(?i)\b({})\.?\s+(\d{{1,2}})(?:st|nd|rd|th)?,?\s+(\d{{4}})\b", MONTH_PATTERN)),
+            month_year: re(&format!(r"(?i)\b({})\.?,?\s+(\d{{4}})\b", MONTH_PATTERN)),
+            bare_month: re(&format!(r"(?i)\b(?:in|since|during|after|before|until)\s+({})\b", MONTH_PATTERN)),
+            quarter: re(r"(?i)\bq([1-4])\s*(\d{4})\b"),
+            decade: re(r"\b((?:19|20)\d)0s\b"),
+            year: re(r"\b((?:19|20)\d{2})\b"),
+            days_ago: re(r"(?i)\b(\d{1,3})\s+days?\s+ago\b"),
+            handle: re(r"(?:^|\s)@([A-Za-z0-9_]{2,32})\b"),
+            by_person: re(r"\b(?:[Bb]y|[Ff]rom|[Ww]ith|[Aa]ccording to|[Aa]sked)\s+([A-Z][a-z]+(?:\s+[A-Z][a-z]+)?)"),
+            titled_person: re(r"\b(?:Mr|Mrs|Ms|Dr|Prof)\.?\s+([A-Z][a-z]+(?:\s+[A-Z][a-z]+)?)"),
+            in_place: re(r"\b(?:in|at|near)\s+([A-Z][a-zA-Z]+(?:\s+[A-Z][a-zA-Z]+)?)"),
+            file_path: re(r"\b[\w./-]+\.(?:rs|py|ts|js|go|c|h|cpp|toml|json|yaml|yml|md)\b"),
+            cause: re(r"(?i)\b(?:because(?:\s+of)?|due to|so that|in order to|caused by)\s+([^.;,!?\n]+)"),
+        }
+    }
+
+    /// Attach a refiner (e.g. a local LLM)
+    pub fn with_refiner(mut self, refiner: Box<dyn DimensionRefiner>) -> Self {
+        self.refiner = Some(refiner);
+        self
+    }
+
+    /// Gazetteer in use
+    pub fn gazetteer_mut(&mut self) -> &mut Gazetteer {
+        &mut self.gazetteer
+    }
+
+    /// Extract 5W values; relative dates resolve against `now`
+    pub fn extract(&self, text: &str, now: DateTime<Utc>) -> DimensionTags {
+        let mut tags = DimensionTags::new();
+
+        self.extract_when(text, now, &mut tags);
+        self.extract_who(text, &mut tags);
+        self.extract_where(text, &mut tags);
+        self.extract_why(text, &mut tags);
+        self.extract_what(text, &mut tags);
+
+        if let Some(refiner) = &self.refiner {
+            for (dim, mut value) in refiner.refine(text, &tags) {
+                // Agreement with the rules is stronger evidence than either alone
+                if tags.get(dim).iter().any(|v| v.value.eq_ignore_ascii_case(&value.value)) {
+                    value.confidence = (value.confidence + 0.2).min(1.0);
+                }
+                tags.add(dim, value);
+            }
+        }
+
+        tags
+    }
+
+    fn extract_when(&self, text: &str, now: DateTime<Utc>, tags: &mut DimensionTags) {
+        let mut taken: Vec<(usize, usize)> = Vec::new();
+        let overlaps = |taken: &[(usize, usize)], start: usize, end: usize| {
+            taken.iter().any(|(s, e)| start < *e && end > *s)
+        };
+        let mut push = |taken: &mut Vec<(usize, usize)>, start, end, range: Option<TimeRange>, confidence| {
+            if let Some(range) = range {
+                taken.push((start, end));
+                tags.add(Dimension::When, tagged(&range.to_value(), confidence));
+            }
+        };
+
+        for caps in self.iso_date.captures_iter(text) {
+            let m = caps.get(0).unwrap();
+            let date = NaiveDate::from_ymd_opt(
+                caps[1].parse().unwrap_or(0),
+                caps[2].parse().unwrap_or(0),
+                caps[3].parse().unwrap_or(0),
+            );
+            push(&mut taken, m.start(), m.end(), date.map(TimeRange::day), 0.95);
+        }
+
+        for caps in self.month_day_year.captures_iter(text) {
+            let m = caps.get(0).unwrap();
+            if overlaps(&taken, m.start(), m.end()) {
+                continue;
+            }
+            let date = month_number(&caps[1]).and_then(|month| {
+                NaiveDate::from_ymd_opt(caps[3].parse().ok()?, month, caps[2].parse().ok()?)
+            });
+            push(&mut taken, m.start(), m.end(), date.map(TimeRange::day), 0.95);
+        }
+
+        for caps in self.month_year.captures_iter(text) {
+            let m = caps.get(0).unwrap();
+            if overlaps(&taken, m.start(), m.end()) {
+                continue;
+            }
+            let range = month_number(&caps[1])
+                .and_then(|month| TimeRange::month(caps[2].parse().ok()?, month));
+            push(&mut taken, m.start(), m.end(), range, 0.9);
+        }
+
+        for caps in self.quarter.captures_iter(text) {
+            let m = caps.get(0).unwrap();
+            let range = TimeRange::quarter(caps[2].parse().unwrap_or(0), caps[1].parse().unwrap_or(1));
+            push(&mut taken, m.start(), m.end(), range, 0.9);
+        }
+
+        for caps in self.decade.captures_iter(text) {
+            let m = caps.get(0).unwrap();
+            let start: i32 = caps[1].parse::<i32>().unwrap_or(0) * 10;
+            let range = TimeRange::year(start)
+                .zip(TimeRange::year(start + 9))
+                .map(|(a, b)| TimeRange { from: a.from, to: b.to });
+            push(&mut taken, m.start(), m.end(), range, 0.8);
+        }
+
+        for caps in self.year.captures_iter(text) {
+            let m = caps.get(0).unwrap();
+            if overlaps(&taken, m.start(), m.end()) {
+                continue;
+            }
+            push(&mut taken, m.start(), m.end(), TimeRange::year(caps[1].parse().unwrap_or(0)), 0.8);
+        }
+
+        for caps in self.bare_month.captures_iter(text) {
+            let m = caps.get(1).unwrap();
+            if overlaps(&taken, m.start(), m.end()) {
+                continue;
+            }
+            // Most recent occurrence of that month
+            let range = month_number(&caps[1]).and_then(|month| {
+                let year = if month > now.month() { now.year() - 1 } else { now.year() };
+                TimeRange::month(year, month)
+            });
+            push(&mut taken, m.start(), m.end(), range, 0.75);
+        }
+
+        let today = now.date_naive();
+        for caps in self.days_ago.captures_iter(text) {
+            let m = caps.get(0).unwrap();
+            let days: i64 = caps[1].parse().unwrap_or(0);
+            push(&mut taken, m.start(), m.end(), Some(TimeRange::day(today - Duration::days(days))), 0.8);
+        }
+
+        let lower = text.to_lowercase();
+        let relative: [(&str, TimeRange); 9] = [
+            ("yesterday", TimeRange::day(today - Duration::days(1))),
+            ("tomorrow", TimeRange::day(today + Duration::days(1))),
+            ("today", TimeRange::day(today)),
+            ("last week", TimeRange { from: today - Duration::days(7), to: today }),
+            ("this week", TimeRange { from: today - Duration::days(today.weekday().num_days_from_monday() as i64), to: today }),
+            ("last month", TimeRange::month(
+                if now.month() == 1 { now.year() - 1 } else { now.year() },
+                if now.month() == 1 { 12 } else { now.month() - 1 },
+            ).unwrap_or(TimeRange::day(today))),
+            ("this month", TimeRange::month(now.year(), now.month()).unwrap_or(TimeRange::day(today))),
+            ("last year", TimeRange::year(now.year() - 1).unwrap_or(TimeRange::day(today))),
+            ("this year", TimeRange::year(now.year()).unwrap_or(TimeRange::day(today))),
+        ];
+        for (phrase, range) in relative {
+            if contains_word(&lower, phrase) {
+                tags.add(Dimension::When, tagged(&range.to_value(), 0.7));
+            }
+        }
+    }
+
+    fn extract_who(&self, text: &str, tags: &mut DimensionTags) {
+        let lower = text.to_lowercase();
+
+        for name in self.gazetteer.people.iter().chain(&self.gazetteer.organizations) {
+            if contains_word(&lower, name) {
+                tags.add(Dimension::Who, tagged(name, 0.9));
+            }
+        }
+
+        for caps in self.handle.captures_iter(text) {
+            tags.add(Dimension::Who, tagged(&format!("@{}", caps[1].to_lowercase()), 0.85));
+        }
+
+        for caps in self.titled_person.captures_iter(text) {
+            tags.add(Dimension::Who, tagged(&caps[1].to_lowercase(), 0.8));
+        }
+
+        for caps in self.by_person.captures_iter(text) {
+            let name = &caps[1];
+            if is_name_candidate(name) && !self.gazetteer.places.contains(&name.to_lowercase()) {
+                tags.add(Dimension::Who, tagged(&name.to_lowercase(), 0.7));
+            }
+        }
+
+        for role in WHO_ROLES {
+            if contains_word(&lower, role) {
+                tags.add(Dimension::Who, tagged(role.trim_end_matches('s'), 0.5));
+            }
+        }
+    }
+
+    fn extract_where(&self, text: &str, tags: &mut DimensionTags) {
+        let lower = text.to_lowercase();
+
+        for place in &self.gazetteer.places {
+            if contains_word(&lower, place) {
+                tags.add(Dimension::Where, tagged(place, 0.9));
+            }
+        }
+
+        for caps in self.in_place.captures_iter(text) {
+            let name = &caps[1];
+            if is_name_candidate(name) && !self.gazetteer.is_known(name) {
+                tags.add(Dimension::Where, tagged(&name.to_lowercase(), 0.6));
+            }
+        }
+
+        for m in self.file_path.find_iter(text) {
+            tags.add(Dimension::Where, tagged(m.as_str(), 0.8));
+        }
+
+        for keyword in WHERE_KEYWORDS {
+            if contains_word(&lower, keyword) {
+                tags.add(Dimension::Where, tagged(keyword, 0.7));
+            }
+        }
+    }
+
+    fn extract_why(&self, text: &str, tags: &mut DimensionTags) {
+        for caps in self.cause.captures_iter(text) {
+            let clause: Vec<&str> = caps[1].split_whitespace().take(8).collect();
+            if !clause.is_empty() {
+                tags.add(Dimension::Why, tagged(&clause.join(" ").to_lowercase(), 0.7));
+            }
+        }
+
+        let lower = text.to_lowercase();
+        for outcome in OUTCOMES {
+            if contains_word(&lower, outcome) {
+                tags.add(Dimension::Why, tagged(outcome, 0.5));
+            }
+        }
+    }
+
+    fn extract_what(&self, text: &str, tags: &mut DimensionTags) {
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric() && c != '-' && c != '_')
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_lowercase())
+            .collect();
+
+        for word in &words {
+            if let Some((_, action)) = ACTIONS.iter().find(|(verb, _)| verb == word) {
+                tags.add(Dimension::What, tagged(action, 0.7));
+            }
+        }
+
+        // Remaining content words that no other dimension claimed
+        let claimed: Vec<String> = [Dimension::Who, Dimension::Where, Dimension::When, Dimension::Why]
+            .iter()
+            .flat_map(|d| tags.get(*d).iter().map(|v| v.value.clone()))
+            .collect();
+        let mut topics = 0;
+        for word in &words {
+            if topics >= 3 {
+                break;
+            }
+            if word.len() > 4
+                && !is_common_word(word)
+                && !word.chars().all(|c| c.is_ascii_digit())
+                && month_number(word).is_none()
+                && !ACTIONS.iter().any(|(verb, _)| verb == word)
+                && !OUTCOMES.contains(&word.as_str())
+                && !claimed.iter().any(|c| c.split_whitespace().any(|w| w == word))
+                && tags.get(Dimension::What).iter().all(|v| v.value != *word)
+            {
+                tags.add(Dimension::What, tagged(word, 0.5));
+                topics += 1;
+            }
+        }
+    }
+}
+
+impl Default for FiveWExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static DEFAULT_EXTRACTOR: LazyLock<FiveWExtractor> = LazyLock::new(FiveWExtractor::new);
+
+/// Extract with the shared default extractor
+pub fn extract_5w(text: &str, now: DateTime<Utc>) -> DimensionTags {
+    DEFAULT_EXTRACTOR.extract(text, now)
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    let name = name.to_lowercase();
+    MONTHS
+        .iter()
+        .find(|(m, _)| *m == name)
+        .map(|(_, n)| *n)
+}
+
+/// Whole-word (or whole-phrase) containment on lowercase text
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    haystack.match_indices(needle).any(|(i, _)| {
+        let before = haystack[..i].chars().next_back();
+        let after = haystack[i + needle.len()..].chars().next();
+        !before.is_some_and(|c| c.is_alphanumeric()) && !after.is_some_and(|c| c.is_alphanumeric())
+    })
+}
+
+/// Capitalized words that aren't months, weekdays or sentence filler
+fn is_name_candidate(name: &str) -> bool {
+    const NOT_NAMES: &[&str] = &[
+        "monday", "tuesday", "wednesday", "thursday", "friday", "saturday",
+        "sunday", "the", "this", "that", "it", "we", "i", "q1", "q2", "q3", "q4",
+    ];
+    let first = name.split_whitespace().next().unwrap_or("").to_lowercase();
+    month_number(&first).is_none() && !NOT_NAMES.contains(&first.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 15, 12, 0, 0).unwrap()
+    }
+
+    fn values(tags: &DimensionTags, dim: Dimension) -> Vec<String> {
+        tags.get(dim).iter().map(|v| v.value.clone()).collect()
+    }
+
+    #[test]
+    fn test_dates_normalized_to_ranges() {
+        let tags = extract_5w("Shipped on 2024-03-03, planned for March 2025 and Q1 2023", now());
+        let when = values(&tags, Dimension::When);
+        assert!(when.contains(&"2024-03-03/2024-03-03".to_string()));
+        assert!(when.contains(&"2025-03-01/2025-03-31".to_string()));
+        assert!(when.contains(&"2023-01-01/2023-03-31".to_string()));
+        // "2025" inside "March 2025" is not also tagged as the whole year
+        assert!(!when.contains(&"2025-01-01/2025-12-31".to_string()));
+
+        let tags = extract_5w("It broke yesterday, and 3 days ago, since December", now());
+        let when = values(&tags, Dimension::When);
+        assert!(when.contains(&"2025-06-14/2025-06-14".to_string()));
+        assert!(when.contains(&"2025-06-12/2025-06-12".to_string()));
+        assert!(when.contains(&"2024-12-01/2024-12-31".to_string()));
+
+        let tags = extract_5w("Popular in the 1990s", now());
+        assert_eq!(tags.time_range(), TimeRange::parse("1990-01-01/1999-12-31"));
+        assert!(tags.time_range().unwrap().contains(Utc.with_ymd_and_hms(1995, 5, 5, 0, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_people_and_places() {
+        let mut gazetteer = Gazetteer::with_defaults();
+        gazetteer.add_person("Alice");
+        let extractor = FiveWExtractor::with_gazetteer(gazetteer);
+
+        let tags = extractor.extract(
+            "Alice fixed the auth bug in Berlin with Bob Jones because logins failed on March 3, 2024",
+            now(),
+        );
+
+        assert_eq!(tags.best(Dimension::Who).unwrap().value, "alice");
+        assert_eq!(tags.best(Dimension::Who).unwrap().confidence, 0.9);
+        assert!(values(&tags, Dimension::Who).contains(&"bob jones".to_string()));
+        assert!(values(&tags, Dimension::Where).contains(&"berlin".to_string()));
+        assert!(values(&tags, Dimension::Where).contains(&"auth".to_string()));
+        assert!(!values(&tags, Dimension::Where).contains(&"march".to_string()));
+        assert_eq!(tags.best(Dimension::What).unwrap().value, "fix");
+        assert!(values(&tags, Dimension::Why).contains(&"logins failed on march 3".to_string()));
+        assert_eq!(tags.time_range(), TimeRange::parse("2024-03-03/2024-03-03"));
+
+        let tags = extractor.extract("Reviewed by @carol_dev in src/auth/jwt.rs", now());
+        assert!(values(&tags, Dimension::Who).contains(&"@carol_dev".to_string()));
+        assert!(values(&tags, Dimension::Where).contains(&"src/auth/jwt.rs".to_string()));
+    }
+
+    #[test]
+    fn test_refiner_merges_and_boosts() {
+        struct Fixed;
+        impl DimensionRefiner for Fixed {
+            fn refine(&self, text: &str, tags: &DimensionTags) -> Vec<(Dimension, DimensionValue)> {
+                assert!(refinement_prompt(text, tags).contains("WHERE=berlin"));
+                parse_refinement("WHO: Dana\nWHERE: berlin\nWHY: -\nnoise line", 0.6)
+            }
+        }
+
+        let extractor = FiveWExtractor::new().with_refiner(Box::new(Fixed));
+        let tags = extractor.extract("Met in Berlin", now());
+
+        assert_eq!(tags.best(Dimension::Who).unwrap().value, "dana");
+        assert_eq!(tags.best(Dimension::Who).unwrap().confidence, 0.6);
+        // Rule said 0.9, refiner agreed at 0.8 -> max stays 0.9
+        assert_eq!(tags.best(Dimension::Where).unwrap().confidence, 0.9);
+        assert!(tags.get(Dimension::Why).is_empty());
+    }
+
+    #[test]
+    fn test_projection_to_position_and_row() {
+        let tags = extract_5w("Mozilla released Rust in 2015 because memory bugs", now());
+        let concept = ConceptId::from_concept("rust");
+        let position = tags.to_position(concept);
+
+        assert!(position.observer.contains(&"mozilla".to_string()));
+        assert!(position.temporal.era_tags.contains(&"2015".to_string()));
+        assert!(position.actual.contains(&ConceptId::from_concept("release")));
+        assert_eq!(position.temporal.valid_from.unwrap().year(), 2015);
+
+        let row = tags.to_row();
+        assert_eq!(row.get(Dimension::Who), Some("mozilla"));
+        assert!(row.quality_score > 0.5);
+    }
+}
@@ -40,6 +40,9 @@ pub mod hyperspace;
 pub mod collapse;
 pub mod bbbcp;
 pub mod chain;
+pub mod extract;
+pub mod rows;
+pub mod proof;
 
 pub use domain::{Domain, DomainRouter};
 pub use index::ThoughtIndex;
@@ -51,6 +54,9 @@ pub use constraint::{ConstraintBuilder, ConstraintRule, ConstraintSource, Constr
 pub use hyperspace::{Dimension, HyperspaceQuery, HyperspaceQueryBuilder, HyperspaceResult, NaturalLanguageExtractor};
 pub use collapse::{CollapseEngine, CollapseResult, CollapsedRow, CollapseProof, RowBuilder, TableOutput};
 pub use bbbcp::{BbbcpQuery, BbbcpQueryBuilder, BbbcpEngine, BbbcpResult, BbbcpOutput, Bone, Circle, BlobSearch, PinStrategy, ChainForward};
+pub use proof::{DatasetCommitment, DatasetSnapshot, MerkleWitness, ProofStep, RowWitness};
+pub use rows::{ConceptRows, IndexRows, MultiSource, RowFilter, RowSource};
+pub use extract::{DimensionRefiner, DimensionTags, FiveWExtractor, Gazetteer, TimeRange};
 pub use chain::{Conclusion, ConclusionChain, ConclusionChainer, ConclusionType, QuestionStep, InverseTrail};
 
 /// Result type for gently-search operations
@@ -76,4 +82,7 @@ pub enum Error {
 
     #[error("Search failed: {0}")]
     SearchFailed(String),
+
+    #[error("Proof invalid: {0}")]
+    ProofInvalid(String),
 }
@@ -0,0 +1,409 @@
+//! Verifiable Proofs - Merkle commitments for BBBCP and collapse
+//!
+//! A dataset is published as a Merkle root over its rows. Proofs then carry
+//! the rows each step touched, with a path back to that root, so a teammate
+//! holding only the root can check the result:
+//!
+//! ```text
+//!                    root  ◄── published DatasetCommitment
+//!                   /    \
+//!                 h01    h23
+//!                /  \   /  \
+//!              r0   r1 r2   r3     rows (sorted by leaf hash)
+//!
+//! CIRCLE "plaintext"  → r1 + path [r0, h23]   (eliminated: contains "plaintext")
+//! PIN argmax          → r2 + path [r3, h01]   (selected: passes CIRCLE + quality)
+//! ```
+//!
+//! Witnesses prove every *recorded* row really is in the snapshot and was
+//! treated correctly. A BBBCP proof records every row of the snapshot
+//! exactly once, so nothing can be hidden from the verifier:
+//!
+//! ```text
+//! CIRCLE i  → rows matching CIRCLE i and no earlier CIRCLE
+//! survivors → rows passing every CIRCLE, BLOB term and quality check
+//! excluded  → rows matching no CIRCLE but failing BLOB terms or quality
+//! ```
+//!
+//! The verifier checks the three sets are disjoint and cover the snapshot,
+//! re-applies each predicate and reruns PIN over the survivors. Proof size
+//! grows with the snapshot.
+
+use crate::collapse::CollapsedRow;
+use crate::hyperspace::Dimension;
+use crate::rows::{RowFilter, RowSource};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// SHA-256 digest
+pub type Hash = [u8; 32];
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Canonical leaf hash of a row (dimension order, sorted sources)
+pub fn row_hash(row: &CollapsedRow) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    for dim in Dimension::all() {
+        if let Some(value) = row.get(*dim) {
+            hasher.update(dim.name().as_bytes());
+            hasher.update(b"=");
+            hasher.update(value.as_bytes());
+            hasher.update(b"\n");
+        }
+    }
+    hasher.update(row.quality_score.to_bits().to_le_bytes());
+    let mut sources = row.source_concepts.clone();
+    sources.sort();
+    for source in sources {
+        hasher.update(source.0);
+    }
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Text a row is matched against (values joined by spaces)
+pub(crate) fn row_text(row: &CollapsedRow) -> String {
+    row.ordered_values().collect::<Vec<_>>().join(" ")
+}
+
+/// Binary Merkle tree (odd nodes are paired with themselves)
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    /// Build from leaf hashes
+    pub fn from_leaves(leaves: Vec<Hash>) -> Self {
+        let mut levels = vec![leaves];
+        while levels.last().map(|l| l.len()).unwrap_or(0) > 1 {
+            let below = levels.last().unwrap();
+            let above = below
+                .chunks(2)
+                .map(|pair| node_hash(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+                .collect();
+            levels.push(above);
+        }
+        Self { levels }
+    }
+
+    /// Root hash (all zeros for an empty tree)
+    pub fn root(&self) -> Hash {
+        self.levels
+            .last()
+            .and_then(|l| l.first())
+            .copied()
+            .unwrap_or([0u8; 32])
+    }
+
+    /// Number of leaves
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Is the tree empty?
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Inclusion path for leaf `index`
+    pub fn witness(&self, index: usize) -> Option<MerkleWitness> {
+        if index >= self.len() {
+            return None;
+        }
+        let mut siblings = Vec::new();
+        let mut i = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = if i.is_multiple_of(2) { level.get(i + 1).unwrap_or(&level[i]) } else { &level[i - 1] };
+            siblings.push(*sibling);
+            i /= 2;
+        }
+        Some(MerkleWitness {
+            index,
+            leaf_count: self.len(),
+            siblings,
+        })
+    }
+}
+
+/// Inclusion path from a leaf to the root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleWitness {
+    /// Leaf position
+    pub index: usize,
+    /// Leaves in the tree
+    pub leaf_count: usize,
+    /// Sibling hashes, bottom up
+    pub siblings: Vec<Hash>,
+}
+
+impl MerkleWitness {
+    /// Does `leaf` at this position hash up to `root`?
+    pub fn verify(&self, leaf: &Hash, root: &Hash) -> bool {
+        if self.index >= self.leaf_count {
+            return false;
+        }
+        let mut hash = *leaf;
+        let mut i = self.index;
+        for sibling in &self.siblings {
+            hash = if i.is_multiple_of(2) { node_hash(&hash, sibling) } else { node_hash(sibling, &hash) };
+            i /= 2;
+        }
+        &hash == root
+    }
+}
+
+/// What gets published: enough to verify proofs, nothing else
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetCommitment {
+    /// Merkle root over canonical row hashes
+    pub root: Hash,
+    /// Number of rows
+    pub size: usize,
+}
+
+/// A frozen dataset that verifiable queries run over
+#[derive(Debug, Clone)]
+pub struct DatasetSnapshot {
+    rows: Vec<CollapsedRow>,
+    tree: MerkleTree,
+    positions: HashMap<Hash, usize>,
+    /// When the snapshot was taken
+    pub taken_at: DateTime<Utc>,
+}
+
+impl DatasetSnapshot {
+    /// Snapshot these rows (duplicates collapse to one leaf)
+    pub fn from_rows(rows: impl IntoIterator<Item = CollapsedRow>) -> Self {
+        let mut hashed: Vec<(Hash, CollapsedRow)> = rows.into_iter().map(|r| (row_hash(&r), r)).collect();
+        // Leaf order must not depend on where the rows came from
+        hashed.sort_by_key(|(hash, _)| *hash);
+        hashed.dedup_by(|a, b| a.0 == b.0);
+
+        let positions = hashed.iter().enumerate().map(|(i, (h, _))| (*h, i)).collect();
+        let tree = MerkleTree::from_leaves(hashed.iter().map(|(h, _)| *h).collect());
+
+        Self {
+            rows: hashed.into_iter().map(|(_, r)| r).collect(),
+            tree,
+            positions,
+            taken_at: Utc::now(),
+        }
+    }
+
+    /// Snapshot everything a source produces
+    pub fn from_source(source: &dyn RowSource) -> Self {
+        Self::from_rows(source.rows(&RowFilter::new()))
+    }
+
+    /// The value to publish
+    pub fn commitment(&self) -> DatasetCommitment {
+        DatasetCommitment {
+            root: self.tree.root(),
+            size: self.rows.len(),
+        }
+    }
+
+    /// Rows in leaf order
+    pub fn rows(&self) -> &[CollapsedRow] {
+        &self.rows
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Is the snapshot empty?
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Witness for a row, if it is part of the snapshot
+    pub fn witness(&self, row: &CollapsedRow) -> Option<RowWitness> {
+        let index = *self.positions.get(&row_hash(row))?;
+        Some(RowWitness {
+            row: self.rows[index].clone(),
+            path: self.tree.witness(index)?,
+        })
+    }
+}
+
+impl RowSource for DatasetSnapshot {
+    fn size(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Rows carry no domain, so only CIRCLE, BLOB terms and row checks apply
+    fn rows<'a>(&'a self, filter: &'a RowFilter) -> Box<dyn Iterator<Item = CollapsedRow> + 'a> {
+        Box::new(
+            self.rows
+                .iter()
+                .filter(move |row| filter.matches_terms(&row_text(row)))
+                .filter(move |row| filter.accepts(row))
+                .cloned(),
+        )
+    }
+}
+
+/// A row plus its path to the dataset root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowWitness {
+    pub row: CollapsedRow,
+    pub path: MerkleWitness,
+}
+
+impl RowWitness {
+    /// Is the row in the committed dataset?
+    pub fn verify(&self, dataset: &DatasetCommitment) -> bool {
+        self.path.leaf_count == dataset.size && self.path.verify(&row_hash(&self.row), &dataset.root)
+    }
+}
+
+/// One recorded query step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ProofStep {
+    /// BONE constraint in force
+    Bone { text: String },
+    /// CIRCLE elimination with every row it removed that no earlier CIRCLE did
+    Circle {
+        text: String,
+        eliminated: usize,
+        witnesses: Vec<RowWitness>,
+    },
+    /// PIN convergence with every selected row
+    Pin {
+        strategy: String,
+        witnesses: Vec<RowWitness>,
+        /// Every row that survived filtering (BBBCP only)
+        #[serde(default)]
+        survivors: Vec<RowWitness>,
+        /// Every row filtered out by BLOB terms or quality (BBBCP only)
+        #[serde(default)]
+        excluded: Vec<RowWitness>,
+    },
+}
+
+/// Does any value of `row` contain `needle` (already lowercase)?
+pub(crate) fn circle_matches(row: &CollapsedRow, needle: &str) -> bool {
+    row.values.values().any(|v| v.to_lowercase().contains(needle))
+}
+
+/// Witnesses for the rows a PIN selected
+pub(crate) fn pin_step(snapshot: &DatasetSnapshot, strategy: String, rows: &[CollapsedRow]) -> ProofStep {
+    ProofStep::Pin {
+        strategy,
+        witnesses: rows.iter().filter_map(|r| snapshot.witness(r)).collect(),
+        survivors: Vec::new(),
+        excluded: Vec::new(),
+    }
+}
+
+/// CIRCLE and PIN steps that together witness every snapshot row
+pub(crate) fn partition_steps(
+    snapshot: &DatasetSnapshot,
+    circles: &[String],
+    filter: &RowFilter,
+    strategy: String,
+    kept: &[CollapsedRow],
+) -> Vec<ProofStep> {
+    let needles: Vec<String> = circles.iter().map(|c| c.to_lowercase()).collect();
+    let mut eliminated: Vec<Vec<RowWitness>> = vec![Vec::new(); circles.len()];
+    let mut survivors = Vec::new();
+    let mut excluded = Vec::new();
+
+    for (index, row) in snapshot.rows.iter().enumerate() {
+        let Some(path) = snapshot.tree.witness(index) else { continue };
+        let witness = RowWitness { row: row.clone(), path };
+        if let Some(i) = needles.iter().position(|n| circle_matches(row, n)) {
+            eliminated[i].push(witness);
+        } else if filter.accepts(row) && filter.matches_terms(&row_text(row)) {
+            survivors.push(witness);
+        } else {
+            excluded.push(witness);
+        }
+    }
+
+    let mut steps: Vec<ProofStep> = circles
+        .iter()
+        .zip(eliminated)
+        .map(|(text, witnesses)| ProofStep::Circle {
+            text: text.clone(),
+            eliminated: witnesses.len(),
+            witnesses,
+        })
+        .collect();
+    steps.push(ProofStep::Pin {
+        strategy,
+        witnesses: kept.iter().filter_map(|r| snapshot.witness(r)).collect(),
+        survivors,
+        excluded,
+    });
+    steps
+}
+
+/// Hash of rows in order (canonical, so any party gets the same value)
+pub fn hash_rows(rows: &[CollapsedRow]) -> Hash {
+    let mut hasher = Sha256::new();
+    for row in rows {
+        hasher.update(row_hash(row));
+    }
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collapse::RowBuilder;
+
+    fn rows(n: usize) -> Vec<CollapsedRow> {
+        (0..n)
+            .map(|i| RowBuilder::new().what(format!("item {}", i)).quality(0.5).build())
+            .collect()
+    }
+
+    #[test]
+    fn test_merkle_witnesses_all_sizes() {
+        for n in [1, 2, 3, 5, 8, 13] {
+            let snapshot = DatasetSnapshot::from_rows(rows(n));
+            let commitment = snapshot.commitment();
+            assert_eq!(commitment.size, n);
+            for row in snapshot.rows() {
+                assert!(snapshot.witness(row).unwrap().verify(&commitment));
+            }
+        }
+    }
+
+    #[test]
+    fn test_commitment_independent_of_order() {
+        let mut reversed = rows(7);
+        reversed.reverse();
+        assert_eq!(
+            DatasetSnapshot::from_rows(rows(7)).commitment(),
+            DatasetSnapshot::from_rows(reversed).commitment()
+        );
+    }
+
+    #[test]
+    fn test_tampered_row_fails() {
+        let snapshot = DatasetSnapshot::from_rows(rows(6));
+        let commitment = snapshot.commitment();
+        let mut witness = snapshot.witness(&snapshot.rows()[2]).unwrap();
+        witness.row.set(Dimension::What, "forged");
+        assert!(!witness.verify(&commitment));
+
+        let other = DatasetSnapshot::from_rows(rows(5)).commitment();
+        assert!(!snapshot.witness(&snapshot.rows()[0]).unwrap().verify(&other));
+    }
+}
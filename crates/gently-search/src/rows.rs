@@ -0,0 +1,407 @@
+//! Row Sources - Lazy CollapsedRows from the live index
+//!
+//! BBBCP and collapse used to need a pre-built `&[CollapsedRow]`. Row
+//! sources produce rows on demand from the real stores, and apply cheap
+//! eliminations *before* a row is ever built:
+//!
+//! ```text
+//! ThoughtIndex ──┐                 ┌─ domain / CIRCLE / BLOB terms  (pushed down)
+//! AlexandriaGraph┼─► RowSource ────┤─ PIN via tesseract 5W index     (pushed down)
+//! Tesseract ─────┘   (iterator)    └─ FILTER / quality on the row    (post)
+//!                        │
+//!                        ▼
+//!          BbbcpEngine::execute_source / CollapseEngine::collapse_source
+//!                   (streaming; only PIN state is kept)
+//! ```
+
+use crate::bbbcp::BbbcpQuery;
+use crate::collapse::CollapsedRow;
+use crate::domain::DomainRouter;
+use crate::hyperspace::{Dimension, DimensionFilter, HyperspaceQuery};
+use crate::index::ThoughtIndex;
+use crate::thought::{is_common_word, Thought};
+use chrono::{TimeZone, Utc};
+use gently_alexandria::{AlexandriaGraph, ConceptId, HyperPosition, SemanticTesseract};
+use std::collections::HashSet;
+
+/// Constraints a source should apply while producing rows
+#[derive(Debug, Clone, Default)]
+pub struct RowFilter {
+    /// CIRCLE eliminations (lowercase substrings)
+    pub circles: Vec<String>,
+    /// PINned dimension values
+    pub pins: Vec<(Dimension, String)>,
+    /// Dimension filters (checked on the built row)
+    pub filters: Vec<DimensionFilter>,
+    /// BLOB terms - at least one must appear (empty = everything)
+    pub terms: Vec<String>,
+    /// Domain restriction
+    pub domain: Option<u8>,
+    /// Minimum row quality
+    pub quality_threshold: f32,
+}
+
+impl RowFilter {
+    /// Filter that accepts everything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filter for a BBBCP query (CIRCLE + BLOB)
+    pub fn for_bbbcp(query: &BbbcpQuery) -> Self {
+        Self {
+            circles: query.circles.iter().map(|c| c.text.to_lowercase()).collect(),
+            pins: Vec::new(),
+            filters: Vec::new(),
+            terms: query
+                .blob
+                .query
+                .split(|c: char| !c.is_alphanumeric())
+                .map(|w| w.to_lowercase())
+                .filter(|w| w.len() > 2 && !is_common_word(w))
+                .collect(),
+            domain: query.blob.domain.as_deref().and_then(resolve_domain),
+            quality_threshold: query.blob.quality_threshold,
+        }
+    }
+
+    /// Filter for a hyperspace query (PIN + FILTER)
+    pub fn for_hyperspace(query: &HyperspaceQuery, quality_threshold: f32) -> Self {
+        Self {
+            pins: query.pin.iter().map(|(d, v)| (*d, v.value.clone())).collect(),
+            filters: query.filter.clone(),
+            quality_threshold,
+            ..Self::default()
+        }
+    }
+
+    /// Does this text hit a CIRCLE elimination?
+    pub fn eliminates(&self, text: &str) -> bool {
+        if self.circles.is_empty() {
+            return false;
+        }
+        let text = text.to_lowercase();
+        self.circles.iter().any(|c| text.contains(c.as_str()))
+    }
+
+    /// Does this text contain a BLOB term?
+    pub fn matches_terms(&self, text: &str) -> bool {
+        if self.terms.is_empty() {
+            return true;
+        }
+        let text = text.to_lowercase();
+        self.terms.iter().any(|t| text.contains(t.as_str()))
+    }
+
+    /// Final check on a built row
+    pub fn accepts(&self, row: &CollapsedRow) -> bool {
+        row.quality_score >= self.quality_threshold
+            && !row.values.values().any(|v| self.eliminates(v))
+            && self.pins.iter().all(|(dim, value)| {
+                row.get(*dim).is_some_and(|v| v.eq_ignore_ascii_case(value))
+            })
+            && self.filters.iter().all(|f| row.get(f.dimension).is_none_or(|v| f.evaluate(v)))
+    }
+}
+
+/// Anything that can lazily produce collapse rows
+pub trait RowSource {
+    /// Items before any filtering (the initial search space)
+    fn size(&self) -> usize;
+
+    /// Rows passing `filter`, produced on demand
+    fn rows<'a>(&'a self, filter: &'a RowFilter) -> Box<dyn Iterator<Item = CollapsedRow> + 'a>;
+}
+
+/// Rows from the local thought index (one per thought)
+pub struct IndexRows<'a> {
+    index: &'a ThoughtIndex,
+}
+
+impl<'a> IndexRows<'a> {
+    pub fn new(index: &'a ThoughtIndex) -> Self {
+        Self { index }
+    }
+
+    fn row_for(thought: &Thought, filter: &RowFilter) -> Option<CollapsedRow> {
+        let tags = &thought.dimensions;
+        let mut row = tags.to_row();
+
+        // Any extracted value may satisfy a PIN, not just the best one
+        for (dim, pinned) in &filter.pins {
+            if !tags.get(*dim).iter().any(|v| v.value.eq_ignore_ascii_case(pinned)) {
+                return None;
+            }
+            row.set(*dim, pinned.clone());
+        }
+
+        if row.get(Dimension::What).is_none() {
+            row.set(Dimension::What, thought.content.clone());
+        }
+        if row.source_concepts.is_empty() {
+            row.source_concepts.push(ConceptId::from_concept(&thought.content));
+        }
+        row.quality_score = row.quality_score.max(thought.shape.confidence * 0.5);
+        row.created_at = thought.created_at;
+        Some(row)
+    }
+}
+
+impl RowSource for IndexRows<'_> {
+    fn size(&self) -> usize {
+        self.index.thoughts().len()
+    }
+
+    fn rows<'a>(&'a self, filter: &'a RowFilter) -> Box<dyn Iterator<Item = CollapsedRow> + 'a> {
+        Box::new(
+            self.index
+                .thoughts()
+                .iter()
+                .filter(move |t| filter.domain.is_none_or(|d| t.shape.domain == d))
+                .filter(move |t| !filter.eliminates(&t.content))
+                .filter(move |t| filter.matches_terms(&t.content))
+                .filter_map(move |t| Self::row_for(t, filter))
+                .filter(move |row| filter.accepts(row)),
+        )
+    }
+}
+
+/// Rows from Alexandria concepts, with 5W values from the tesseract
+pub struct ConceptRows<'a> {
+    graph: &'a AlexandriaGraph,
+    tesseract: Option<&'a SemanticTesseract>,
+}
+
+impl<'a> ConceptRows<'a> {
+    pub fn new(graph: &'a AlexandriaGraph) -> Self {
+        Self { graph, tesseract: None }
+    }
+
+    /// Use tesseract positions for WHO/WHERE/WHEN/WHY and PIN lookups
+    pub fn with_tesseract(mut self, tesseract: &'a SemanticTesseract) -> Self {
+        self.tesseract = Some(tesseract);
+        self
+    }
+
+    /// Candidate concepts - narrowed through the tesseract's 5W index when pinned
+    fn candidates(&self, filter: &RowFilter) -> Vec<ConceptId> {
+        match (self.tesseract, filter.pins.is_empty()) {
+            (Some(tesseract), false) => {
+                let pins: Vec<(&str, &str)> = filter
+                    .pins
+                    .iter()
+                    .map(|(d, v)| (d.name(), v.as_str()))
+                    .collect();
+                tesseract.query_5w_multi(&pins)
+            }
+            _ => self.graph.concept_ids(),
+        }
+    }
+
+    fn row_for(&self, id: &ConceptId, filter: &RowFilter) -> Option<CollapsedRow> {
+        let concept = self.graph.get_concept(id)?;
+        if filter.domain.is_some() && concept.domain != filter.domain {
+            return None;
+        }
+        if filter.eliminates(&concept.text) || !filter.matches_terms(&concept.text) {
+            return None;
+        }
+
+        let mut row = CollapsedRow::new();
+        row.set(Dimension::What, concept.text.clone());
+        row.source_concepts.push(*id);
+        row.created_at = Utc.timestamp_opt(concept.created_at, 0).single().unwrap_or_else(Utc::now);
+
+        if let Some(position) = self.tesseract.and_then(|t| t.latest_position(id)) {
+            self.fill_from_position(&mut row, position, filter);
+        }
+        row.quality_score = row.values.len() as f32 / Dimension::all().len() as f32;
+
+        Some(row)
+    }
+
+    fn fill_from_position(&self, row: &mut CollapsedRow, position: &HyperPosition, filter: &RowFilter) {
+        // Prefer the pinned value when the position carries it
+        let pick = |dim: Dimension, values: &[String]| -> Option<String> {
+            let pinned = filter.pins.iter().find(|(d, _)| *d == dim).map(|(_, v)| v);
+            match pinned {
+                Some(p) => values.iter().find(|v| v.eq_ignore_ascii_case(p)).cloned(),
+                None => values.first().cloned(),
+            }
+        };
+
+        if let Some(who) = pick(Dimension::Who, &position.observer) {
+            row.set(Dimension::Who, who);
+        }
+        if let Some(place) = pick(Dimension::Where, &position.context) {
+            row.set(Dimension::Where, place);
+        }
+        if let Some(when) = pick(Dimension::When, &position.temporal.era_tags) {
+            row.set(Dimension::When, when);
+        }
+        if let Some(why) = position.purpose.first().and_then(|p| self.graph.get_concept(p)) {
+            row.set(Dimension::Why, why.text);
+        }
+    }
+}
+
+impl RowSource for ConceptRows<'_> {
+    fn size(&self) -> usize {
+        self.graph.concept_count()
+    }
+
+    fn rows<'a>(&'a self, filter: &'a RowFilter) -> Box<dyn Iterator<Item = CollapsedRow> + 'a> {
+        Box::new(
+            self.candidates(filter)
+                .into_iter()
+                .filter_map(move |id| self.row_for(&id, filter))
+                .filter(move |row| filter.accepts(row)),
+        )
+    }
+}
+
+/// Several sources chained, skipping rows already produced for a concept
+pub struct MultiSource<'a> {
+    sources: Vec<&'a dyn RowSource>,
+}
+
+impl<'a> MultiSource<'a> {
+    pub fn new() -> Self {
+        Self { sources: Vec::new() }
+    }
+
+    /// Append a source
+    pub fn with(mut self, source: &'a dyn RowSource) -> Self {
+        self.sources.push(source);
+        self
+    }
+}
+
+impl Default for MultiSource<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RowSource for MultiSource<'_> {
+    fn size(&self) -> usize {
+        self.sources.iter().map(|s| s.size()).sum()
+    }
+
+    fn rows<'a>(&'a self, filter: &'a RowFilter) -> Box<dyn Iterator<Item = CollapsedRow> + 'a> {
+        let mut seen: HashSet<ConceptId> = HashSet::new();
+        Box::new(
+            self.sources
+                .iter()
+                .flat_map(move |s| s.rows(filter))
+                .filter(move |row| match row.source_concepts.first() {
+                    Some(id) => seen.insert(*id),
+                    None => true,
+                }),
+        )
+    }
+}
+
+/// Plain rows already in memory
+impl RowSource for [CollapsedRow] {
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn rows<'a>(&'a self, filter: &'a RowFilter) -> Box<dyn Iterator<Item = CollapsedRow> + 'a> {
+        Box::new(self.iter().filter(move |row| filter.accepts(row)).cloned())
+    }
+}
+
+/// Domain by code or name, falling back to keyword routing
+fn resolve_domain(name: &str) -> Option<u8> {
+    let router = DomainRouter::new();
+    router
+        .all()
+        .iter()
+        .find(|d| d.code.eq_ignore_ascii_case(name) || d.name.eq_ignore_ascii_case(name))
+        .map(|d| d.index)
+        .or_else(|| router.route_primary(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bbbcp::BlobSearch;
+    use crate::collapse::RowBuilder;
+    use gently_alexandria::{AlexandriaConfig, NodeFingerprint};
+
+    fn index() -> ThoughtIndex {
+        let mut index = ThoughtIndex::new();
+        index.add_thought(Thought::new("Mozilla released the Rust compiler in 2015"));
+        index.add_thought(Thought::new("Deployed the backend in Berlin during 2024"));
+        index.add_thought(Thought::new("Stored passwords in plaintext storage in 2024"));
+        index.add_thought(Thought::new("Refactored the database schema"));
+        index
+    }
+
+    #[test]
+    fn test_index_rows_push_down_circles_and_terms() {
+        let index = index();
+        let source = IndexRows::new(&index);
+        let query = BbbcpQuery::builder()
+            .circle("plaintext")
+            .blob(BlobSearch::semantic("2024 backend storage"))
+            .build();
+        let filter = RowFilter::for_bbbcp(&query);
+
+        let rows: Vec<_> = source.rows(&filter).collect();
+        assert_eq!(source.size(), 4);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get(Dimension::Where), Some("berlin"));
+    }
+
+    #[test]
+    fn test_index_rows_pin_any_extracted_value() {
+        let index = index();
+        let source = IndexRows::new(&index);
+        let query = crate::HyperspaceQueryBuilder::new()
+            .pin(Dimension::When, "2024-01-01/2024-12-31")
+            .build();
+        let filter = RowFilter::for_hyperspace(&query, 0.0);
+
+        let rows: Vec<_> = source.rows(&filter).collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|r| r.get(Dimension::When) == Some("2024-01-01/2024-12-31")));
+    }
+
+    #[test]
+    fn test_concept_rows_use_tesseract_index() {
+        let node = NodeFingerprint::from_hardware("test", 4, 16, "rows");
+        let graph = AlexandriaGraph::new(node, AlexandriaConfig::default());
+        let mut tesseract = SemanticTesseract::new();
+
+        for text in ["Mozilla released Rust in 2015", "Google released Go in 2009", "plain concept"] {
+            let id = graph.ensure_concept(text);
+            let tags = crate::extract::extract_5w(text, Utc::now());
+            tesseract.record_position(tags.to_position(id));
+        }
+
+        let source = ConceptRows::new(&graph).with_tesseract(&tesseract);
+        let mut filter = RowFilter::new();
+        filter.pins.push((Dimension::Who, "google".to_string()));
+
+        let rows: Vec<_> = source.rows(&filter).collect();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get(Dimension::What), Some("Google released Go in 2009"));
+        assert_eq!(rows[0].get(Dimension::When), Some("2009-01-01/2009-12-31"));
+
+        let multi = MultiSource::new().with(&source).with(&source);
+        assert_eq!(multi.rows(&RowFilter::new()).count(), 3);
+    }
+
+    #[test]
+    fn test_slice_source() {
+        let rows = vec![
+            RowBuilder::new().what("keep").quality(0.9).build(),
+            RowBuilder::new().what("drop me").quality(0.9).build(),
+        ];
+        let filter = RowFilter { circles: vec!["drop".into()], ..RowFilter::new() };
+        assert_eq!(rows.as_slice().rows(&filter).count(), 1);
+    }
+}
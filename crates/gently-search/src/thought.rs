@@ -6,6 +6,7 @@
 //! - Address: Content-derived hash (dedup + linking)
 //! - Metadata: Source, timestamp, tags
 
+use crate::extract::{extract_5w, DimensionTags};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -179,6 +180,10 @@ pub struct Thought {
 
     /// XOR chain hash at creation
     pub xor_hash: Option<String>,
+
+    /// Extracted WHO/WHAT/WHERE/WHEN/WHY values
+    #[serde(default)]
+    pub dimensions: DimensionTags,
 }
 
 impl Thought {
@@ -188,6 +193,7 @@ impl Thought {
         let address = Self::compute_address(&content);
         let shape = Shape::from_content(&content);
         let now = Utc::now();
+        let dimensions = extract_5w(&content, now);
 
         Self {
             id: Uuid::new_v4(),
@@ -201,6 +207,7 @@ impl Thought {
             access_count: 0,
             bridges: Vec::new(),
             xor_hash: None,
+            dimensions,
         }
     }
 
@@ -305,7 +312,7 @@ impl Thought {
 }
 
 /// Check if word is too common to be a keyword
-fn is_common_word(word: &str) -> bool {
+pub(crate) fn is_common_word(word: &str) -> bool {
     const COMMON: &[&str] = &[
         "the", "and", "for", "that", "this", "with", "from", "have", "will", "what", "when",
         "where", "which", "there", "their", "about", "would", "could", "should", "these", "those",
@@ -12,7 +12,7 @@ use sha2::Digest;
 
 use gently_core::{GenesisKey, PatternEncoder, Lock, Key, KeyVault, ServiceConfig};
 use gently_core::crypto::xor::split_secret;
-use gently_feed::{FeedStorage, ItemKind, LivingFeed};
+use gently_feed::{DecayModel, FeedBundle, FeedStorage, FeedWorkspaces, ImportOutcome, ItemKind, LivingFeed, Simulation};
 use gently_search::{ContextRouter, Thought, ThoughtIndex};
 use gently_mcp::{McpServer, McpHandler};
 use gently_dance::{DanceSession, Contract};
@@ -24,7 +24,8 @@ use gently_cipher::analysis::FrequencyAnalysis;
 use gently_cipher::{Cracker, RainbowTable, RainbowHashType, TableGenerator, Wordlist, BruteForce};
 use gently_network::PacketCapture;
 use gently_architect::{IdeaCrystal, ProjectTree, FlowChart};
-use gently_brain::{ModelDownloader, Embedder, TensorChain, ClaudeClient, ClaudeModel, GentlyAssistant};
+use gently_brain::{ModelDownloader, Embedder, TensorChain, ClaudeModel};
+use gently_gateway::{claude_assistant, claude_client};
 // gently-ipfs imported as needed within functions
 use gently_sploit::{Framework, SploitConsole, console::banner};
 use gently_security::{FafoController, FafoMode, SecurityController, DefenseMode};
@@ -524,6 +525,28 @@ enum ClaudeCommands {
         system: Option<String>,
     },
 
+    /// Let Claude work on a task with GentlyOS tools and skills
+    Agent {
+        /// Task description
+        task: String,
+
+        /// Model: sonnet, opus, haiku
+        #[arg(short, long, default_value = "sonnet")]
+        model: String,
+
+        /// Maximum model calls
+        #[arg(long, default_value = "8")]
+        max_steps: usize,
+
+        /// Token budget (0 = unlimited)
+        #[arg(long, default_value = "0")]
+        max_tokens: usize,
+
+        /// Approve tool calls the policy would ask about
+        #[arg(short, long)]
+        yes: bool,
+    },
+
     /// Show Claude status and configuration
     Status,
 }
@@ -816,6 +839,15 @@ enum FeedCommands {
         step_id: u32,
     },
 
+    /// Add another name an item is mentioned by
+    Alias {
+        /// Item name
+        item: String,
+
+        /// Alias
+        alias: String,
+    },
+
     /// Freeze an item
     Freeze {
         /// Item name
@@ -840,6 +872,112 @@ enum FeedCommands {
         #[arg(short, long)]
         output: Option<String>,
     },
+
+    /// Show or set the decay model
+    Decay {
+        /// Model: tick, half-life:<h>, activity:<ticks>[:<grace h>], working-hours:<h>[:9-17[:<utc offset>]]
+        model: Option<String>,
+
+        /// Only apply to this item kind (project, task, idea, reference, person)
+        #[arg(short, long)]
+        kind: Option<String>,
+    },
+
+    /// Project when each item will cool down and freeze if left alone
+    Simulate {
+        /// Days to look ahead
+        #[arg(short, long, default_value = "14")]
+        days: i64,
+
+        /// Feed interactions per hour (drives tick and activity models)
+        #[arg(short, long, default_value = "1")]
+        ticks_per_hour: u32,
+    },
+
+    /// Show the operation timeline, or what was hot at a point in time
+    History {
+        /// Entries to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+
+        /// Show the feed as it was at: YYYY-MM-DD, "YYYY-MM-DD HH:MM", yesterday, or a weekday (tuesday)
+        #[arg(short, long)]
+        at: Option<String>,
+    },
+
+    /// Undo the last operations
+    Undo {
+        /// Number of operations
+        #[arg(default_value = "1")]
+        steps: usize,
+    },
+
+    /// Redo undone operations
+    Redo {
+        /// Number of operations
+        #[arg(default_value = "1")]
+        steps: usize,
+    },
+
+    /// Manage named feed workspaces
+    Workspace {
+        #[command(subcommand)]
+        command: FeedWorkspaceCommands,
+    },
+
+    /// Merge another workspace into this one (charges and bridges combined by name)
+    Merge {
+        /// Workspace to merge from
+        from: String,
+
+        /// Workspace to merge into (default: active)
+        #[arg(short, long)]
+        into: Option<String>,
+    },
+
+    /// Export a workspace as a shareable bundle
+    Bundle {
+        /// Output file
+        output: String,
+
+        /// Workspace to bundle (default: active)
+        #[arg(short, long)]
+        workspace: Option<String>,
+    },
+
+    /// Import a bundle (new workspace adopts it, existing one merges it)
+    Import {
+        /// Bundle file
+        bundle: String,
+
+        /// Target workspace (default: the bundle's workspace name)
+        #[arg(short, long)]
+        into: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum FeedWorkspaceCommands {
+    /// List workspaces
+    List,
+
+    /// Create an empty workspace
+    Create {
+        /// Workspace name
+        name: String,
+    },
+
+    /// Switch the active workspace
+    Switch {
+        /// Workspace name
+        name: String,
+    },
+
+    /// Delete a workspace
+    Delete {
+        /// Workspace name
+        name: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -932,6 +1070,70 @@ enum AlexandriaCommands {
         /// Output file
         #[arg(short, long, default_value = "alexandria.json")]
         output: String,
+
+        /// Format: graphml, gexf, dot, jsonld, csv (default: from extension, else native JSON)
+        #[arg(short, long)]
+        format: Option<String>,
+
+        /// Only export the neighbourhood of this concept
+        #[arg(short, long)]
+        concept: Option<String>,
+
+        /// Hops around --concept
+        #[arg(long, default_value = "2")]
+        hops: usize,
+    },
+
+    /// Import a GraphML or CSV edge list
+    Import {
+        /// Input file
+        input: String,
+
+        /// Format: graphml, csv (default: from extension)
+        #[arg(short, long)]
+        format: Option<String>,
+    },
+
+    /// Rank hub concepts (PageRank, k-core, betweenness)
+    Hubs {
+        /// Only rank concepts near this one
+        #[arg(short, long)]
+        concept: Option<String>,
+
+        /// Maximum hops from the concept
+        #[arg(long, default_value = "2")]
+        hops: usize,
+
+        /// Number of hubs to show
+        #[arg(short, long, default_value = "10")]
+        top: usize,
+    },
+
+    /// Find the strongest path between two concepts
+    Path {
+        /// Starting concept
+        from: String,
+
+        /// Target concept
+        to: String,
+    },
+
+    /// Show topic clusters
+    Clusters {
+        /// Hide clusters smaller than this
+        #[arg(short, long, default_value = "2")]
+        min_size: usize,
+    },
+
+    /// Show which concepts gained or lost connections over a period
+    Changes {
+        /// Look back this many days
+        #[arg(short, long, default_value = "7")]
+        days: i64,
+
+        /// Number of concepts to show
+        #[arg(short, long, default_value = "10")]
+        top: usize,
     },
 }
 
@@ -1181,6 +1383,18 @@ enum BrainCommands {
         category: Option<String>,
     },
 
+    /// Call an MCP tool through the permission gate
+    Call {
+        /// Tool name
+        tool: String,
+        /// Arguments as a JSON object
+        #[arg(default_value = "{}")]
+        input: String,
+        /// Approve calls the policy would ask about
+        #[arg(short, long)]
+        yes: bool,
+    },
+
     /// Manage background daemons
     Daemon {
         #[command(subcommand)]
@@ -1213,27 +1427,99 @@ enum BrainCommands {
 
     /// Get current awareness state
     Awareness,
+
+    /// Inspect and reconcile GitChain session branches
+    Chain {
+        /// Chain file (default: ~/.gently/brain/gitchain.bin)
+        #[arg(long)]
+        path: Option<String>,
+
+        #[command(subcommand)]
+        action: ChainAction,
+    },
+
+    /// Compile and test watchdog rules
+    Rules {
+        #[command(subcommand)]
+        action: RulesAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum RulesAction {
+    /// Compile a rules file or every *.rules file in a directory
+    Check {
+        path: String,
+    },
+    /// Replay recorded events (JSON lines) and check which rules fire
+    Test {
+        /// Rules file
+        rules: String,
+        /// Recording with one event per line and optional "expect" lists
+        events: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ChainAction {
+    /// List branches (session branches first)
+    Branches,
+    /// Show commit history of a branch
+    Log {
+        /// Branch name or commit hash (default: current branch)
+        branch: Option<String>,
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+    },
+    /// Structural diff between two commits or branches
+    Diff {
+        from: String,
+        to: String,
+    },
+    /// Merge a branch into another
+    Merge {
+        /// Branch to merge
+        branch: String,
+        /// Branch to merge into
+        #[arg(long, default_value = "main")]
+        into: String,
+        #[arg(long, default_value = "gently")]
+        author: String,
+    },
+    /// Replay a branch's commits on top of another
+    Rebase {
+        branch: String,
+        #[arg(long, default_value = "main")]
+        onto: String,
+    },
+    /// Show conflicts recorded on a merge commit
+    Conflicts {
+        /// Branch name or commit hash
+        commit: String,
+    },
+    /// Write history into a bare git repository
+    ExportGit {
+        /// Repository directory (created if missing)
+        repo: String,
+    },
+    /// Replace the chain with history read from a git repository
+    ImportGit {
+        repo: String,
+    },
 }
 
 #[derive(Subcommand)]
 enum DaemonAction {
-    /// List running daemons
+    /// List the daemon types that can be run
     List,
-    /// Spawn a new daemon
+    /// Run a daemon in the foreground until Ctrl-C, then print its metrics
+    ///
+    /// Daemons live inside the process that runs them; there is no
+    /// background service to list or stop them from another command.
     Spawn {
         /// Daemon type: vector_chain, ipfs_sync, git_branch, knowledge_graph, awareness, inference
         daemon_type: String,
     },
-    /// Stop a daemon
-    Stop {
-        /// Daemon name
-        name: String,
-    },
-    /// Get daemon metrics
-    Metrics {
-        /// Daemon name
-        name: String,
-    },
 }
 
 #[derive(Subcommand)]
@@ -1272,9 +1558,21 @@ enum KnowledgeAction {
     },
     /// Export knowledge graph
     Export {
-        /// Output file (JSON)
+        /// Output file
         #[arg(short, long, default_value = "knowledge.json")]
         output: String,
+
+        /// Format: graphml, gexf, dot, jsonld, csv (default: from extension, else native JSON)
+        #[arg(short, long)]
+        format: Option<String>,
+
+        /// Only export the neighbourhood of this concept
+        #[arg(short, long)]
+        concept: Option<String>,
+
+        /// Hops around --concept
+        #[arg(long, default_value = "2")]
+        hops: usize,
     },
     /// Show graph stats
     Stats,
@@ -1810,14 +2108,14 @@ fn cmd_setup(skip_models: bool, force: bool) -> Result<()> {
             let mut embedder = Embedder::new();
             match embedder.load_default() {
                 Ok(()) => println!("  ✓ Embedding model loaded"),
-                Err(e) => println!("  ⚠ Model download failed: {} (will use simulated embeddings)", e),
+                Err(e) => println!("  ⚠ Model download failed: {}", e),
             }
         }
 
         #[cfg(not(feature = "fastembed"))]
         {
-            println!("  • Fastembed not enabled - using simulated embeddings");
-            println!("    (Enable with: cargo build --features fastembed)");
+            println!("  • Fastembed not enabled - using the built-in local embedder");
+            println!("    (Enable ONNX bge-small with: cargo build --features fastembed)");
         }
     } else {
         println!("\nStep 5: Skipping embedding model download (--skip-models)");
@@ -2370,10 +2668,20 @@ fn cmd_feed(command: FeedCommands) -> Result<()> {
         FeedCommands::Boost { name, amount } => cmd_feed_boost(name, amount),
         FeedCommands::Step { item, step } => cmd_feed_step(item, step),
         FeedCommands::Done { item, step_id } => cmd_feed_done(item, step_id),
+        FeedCommands::Alias { item, alias } => cmd_feed_alias(item, alias),
         FeedCommands::Freeze { name } => cmd_feed_freeze(name),
         FeedCommands::Archive { name } => cmd_feed_archive(name),
         FeedCommands::Process { text } => cmd_feed_process(text),
         FeedCommands::Export { output } => cmd_feed_export(output),
+        FeedCommands::Decay { model, kind } => cmd_feed_decay(model, kind),
+        FeedCommands::Simulate { days, ticks_per_hour } => cmd_feed_simulate(days, ticks_per_hour),
+        FeedCommands::History { limit, at } => cmd_feed_history(limit, at),
+        FeedCommands::Undo { steps } => cmd_feed_undo(steps),
+        FeedCommands::Redo { steps } => cmd_feed_redo(steps),
+        FeedCommands::Workspace { command } => cmd_feed_workspace(command),
+        FeedCommands::Merge { from, into } => cmd_feed_merge(from, into),
+        FeedCommands::Bundle { output, workspace } => cmd_feed_bundle(output, workspace),
+        FeedCommands::Import { bundle, into } => cmd_feed_import(bundle, into),
     }
 }
 
@@ -2458,17 +2766,21 @@ fn cmd_feed_show(filter: String) -> Result<()> {
     Ok(())
 }
 
-fn cmd_feed_add(name: String, kind: String, tags: Option<String>) -> Result<()> {
-    let mut feed = load_feed();
-
-    let item_kind = match kind.to_lowercase().as_str() {
+fn parse_item_kind(kind: &str) -> ItemKind {
+    match kind.to_lowercase().as_str() {
         "project" => ItemKind::Project,
         "task" => ItemKind::Task,
         "idea" => ItemKind::Idea,
         "reference" => ItemKind::Reference,
         "person" => ItemKind::Person,
         _ => ItemKind::Project,
-    };
+    }
+}
+
+fn cmd_feed_add(name: String, kind: String, tags: Option<String>) -> Result<()> {
+    let mut feed = load_feed();
+
+    let item_kind = parse_item_kind(&kind);
 
     let id = feed.add_item(&name, item_kind.clone());
 
@@ -2573,6 +2885,21 @@ fn cmd_feed_archive(name: String) -> Result<()> {
     Ok(())
 }
 
+fn cmd_feed_alias(item: String, alias: String) -> Result<()> {
+    let mut feed = load_feed();
+
+    if feed.add_alias(&item, &alias) {
+        save_feed(&feed)?;
+        println!("\n  '{}' now also matches '{}'", alias, item);
+    } else if feed.get_item_by_name(&item).is_none() {
+        println!("\n  Item not found: {}", item);
+    } else {
+        println!("\n  Alias already set: {}", alias);
+    }
+
+    Ok(())
+}
+
 fn cmd_feed_process(text: String) -> Result<()> {
     let mut feed = load_feed();
 
@@ -2608,6 +2935,246 @@ fn cmd_feed_export(output: Option<String>) -> Result<()> {
     Ok(())
 }
 
+fn cmd_feed_decay(model: Option<String>, kind: Option<String>) -> Result<()> {
+    let mut feed = load_feed();
+
+    if let Some(spec) = model {
+        let model: DecayModel = spec.parse()?;
+        let mut policy = feed.decay_policy().clone();
+        match &kind {
+            Some(k) => policy.set_kind(parse_item_kind(k), model),
+            None => policy.default = model,
+        }
+        feed.set_decay_policy(policy);
+        save_feed(&feed)?;
+    }
+
+    let policy = feed.decay_policy();
+    println!("\n  DECAY MODEL");
+    println!("  ===========\n");
+    println!("  default: {}", policy.default);
+    for (kind, model) in &policy.overrides {
+        println!("  {:?}: {}", kind, model);
+    }
+
+    Ok(())
+}
+
+fn cmd_feed_simulate(days: i64, ticks_per_hour: u32) -> Result<()> {
+    let feed = load_feed();
+    let sim = Simulation {
+        horizon: chrono::Duration::days(days),
+        step: chrono::Duration::hours(1),
+        ticks_per_step: ticks_per_hour,
+    };
+
+    println!("\n  DECAY PROJECTION ({} days, {} ticks/hour)", days, ticks_per_hour);
+    println!("  ========================================\n");
+
+    let when = |t: Option<chrono::DateTime<chrono::Utc>>| {
+        t.map(|t| t.with_timezone(&chrono::Local).format("%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    println!("  {:<24} {:>6}  {:<12} {:<12} {:<12}", "ITEM", "CHARGE", "ACTIVE", "COOLING", "FROZEN");
+    for p in feed.project_decay(&sim) {
+        println!(
+            "  {} {:<22} {:>6.2}  {:<12} {:<12} {:<12}",
+            p.state.emoji(),
+            p.name,
+            p.charge,
+            when(p.active_at),
+            when(p.cooling_at),
+            when(p.frozen_at)
+        );
+    }
+
+    Ok(())
+}
+
+/// Resolve a "when" to the end of that moment (end of day for dates and weekdays)
+fn parse_feed_time(when: &str) -> Result<chrono::DateTime<chrono::Utc>> {
+    use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+    let today = Local::now().date_naive();
+    let end_of_day = |date: NaiveDate| date.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap());
+
+    let local = match when.trim().to_lowercase().as_str() {
+        "now" => Local::now().naive_local(),
+        "today" => end_of_day(today),
+        "yesterday" => end_of_day(today - Duration::days(1)),
+        s => {
+            let weekday = s.trim_start_matches("last ").parse::<chrono::Weekday>().ok();
+            if let Some(day) = weekday {
+                // Most recent such day before today
+                let back = (today.weekday().num_days_from_monday() + 7 - day.num_days_from_monday() - 1) % 7 + 1;
+                end_of_day(today - Duration::days(back as i64))
+            } else if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M") {
+                dt
+            } else if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+                end_of_day(date)
+            } else {
+                anyhow::bail!("Unrecognized time: {}", when);
+            }
+        }
+    };
+
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|t| t.with_timezone(&chrono::Utc))
+        .ok_or_else(|| anyhow::anyhow!("Invalid local time: {}", when))
+}
+
+fn cmd_feed_history(limit: usize, at: Option<String>) -> Result<()> {
+    let feed = load_feed();
+
+    if let Some(when) = at {
+        let t = parse_feed_time(&when)?;
+        println!("\n  FEED AT {}", t.with_timezone(&chrono::Local).format("%a %Y-%m-%d %H:%M"));
+        println!("  ==========================\n");
+        if feed.history().horizon().is_none_or(|h| h > t) {
+            println!("  (history does not reach back that far; showing oldest known state)\n");
+        }
+        let mut items: Vec<_> = feed.items_at(t).into_iter().filter(|i| !i.archived).collect();
+        items.sort_by(|a, b| b.charge.total_cmp(&a.charge));
+        for item in items {
+            println!("  {} {} [{:.2}]", item.state.emoji(), item.name, item.charge);
+        }
+        return Ok(());
+    }
+
+    println!("\n  FEED HISTORY");
+    println!("  ============\n");
+    let entries = feed.recent_history(limit);
+    if entries.is_empty() {
+        println!("  (no recorded operations)");
+    }
+    for entry in entries {
+        println!(
+            "  #{:<5} {}  {}",
+            entry.seq,
+            entry.at.with_timezone(&chrono::Local).format("%a %m-%d %H:%M"),
+            entry.op
+        );
+    }
+    let redo = feed.history().redoable().len();
+    if redo > 0 {
+        println!("\n  {} undone operation(s) can be redone with 'gently feed redo'.", redo);
+    }
+
+    Ok(())
+}
+
+fn cmd_feed_undo(steps: usize) -> Result<()> {
+    let mut feed = load_feed();
+    let undone = feed.undo(steps);
+    save_feed(&feed)?;
+
+    if undone.is_empty() {
+        println!("\n  Nothing to undo.");
+    }
+    for op in undone {
+        println!("  Undid: {}", op);
+    }
+
+    Ok(())
+}
+
+fn cmd_feed_redo(steps: usize) -> Result<()> {
+    let mut feed = load_feed();
+    let redone = feed.redo(steps);
+    save_feed(&feed)?;
+
+    if redone.is_empty() {
+        println!("\n  Nothing to redo.");
+    }
+    for op in redone {
+        println!("  Redid: {}", op);
+    }
+
+    Ok(())
+}
+
+fn cmd_feed_workspace(command: FeedWorkspaceCommands) -> Result<()> {
+    let workspaces = FeedWorkspaces::default_location()?;
+
+    match command {
+        FeedWorkspaceCommands::List => {
+            let active = workspaces.active();
+            println!("\n  FEED WORKSPACES");
+            println!("  ===============\n");
+            for name in workspaces.list()? {
+                let items = workspaces.load(&name).map(|f| f.items().len()).unwrap_or(0);
+                let marker = if name == active { "*" } else { " " };
+                println!("  {} {} ({} items)", marker, name, items);
+            }
+        }
+        FeedWorkspaceCommands::Create { name } => {
+            workspaces.create(&name)?;
+            println!("\n  Created workspace: {}", name);
+            println!("  Use 'gently feed workspace switch {}' to activate it.", name);
+        }
+        FeedWorkspaceCommands::Switch { name } => {
+            workspaces.switch(&name)?;
+            println!("\n  Active workspace: {}", name);
+        }
+        FeedWorkspaceCommands::Delete { name } => {
+            workspaces.delete(&name)?;
+            println!("\n  Deleted workspace: {}", name);
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_feed_merge(from: String, into: Option<String>) -> Result<()> {
+    let workspaces = FeedWorkspaces::default_location()?;
+    let into = into.unwrap_or_else(|| workspaces.active());
+
+    let report = workspaces.merge(&from, &into)?;
+
+    println!("\n  MERGED {} -> {}", from, into);
+    println!("  Items:   {} added, {} combined", report.items_added, report.items_combined);
+    println!("  Bridges: {} added, {} combined", report.bridges_added, report.bridges_combined);
+
+    Ok(())
+}
+
+fn cmd_feed_bundle(output: String, workspace: Option<String>) -> Result<()> {
+    let workspaces = FeedWorkspaces::default_location()?;
+    let workspace = workspace.unwrap_or_else(|| workspaces.active());
+
+    let bundle = workspaces.export_bundle(&workspace)?;
+    bundle.write(&output)?;
+
+    println!("\n  Bundled '{}' to: {}", workspace, output);
+    println!("  Chain: {} -> {} (depth {})", bundle.genesis, bundle.head, bundle.depth);
+    println!("  Checksum: {}", bundle.checksum);
+
+    Ok(())
+}
+
+fn cmd_feed_import(path: String, into: Option<String>) -> Result<()> {
+    let workspaces = FeedWorkspaces::default_location()?;
+    let bundle = FeedBundle::read(&path)?;
+    let into = into.unwrap_or_else(|| bundle.workspace.clone());
+
+    println!("\n  IMPORT {} -> {}", path, into);
+    println!("  From: {} (head {}, depth {})", bundle.workspace, bundle.head, bundle.depth);
+
+    match workspaces.import_bundle(&bundle, &into)? {
+        ImportOutcome::Adopted => {
+            println!("  Adopted bundle; chain continues from {}", bundle.head);
+        }
+        ImportOutcome::Merged(report) => {
+            println!("  Merged: {} items added, {} combined", report.items_added, report.items_combined);
+        }
+    }
+
+    Ok(())
+}
+
 // ===== SEARCH COMMANDS =====
 
 fn cmd_search(command: SearchCommands) -> Result<()> {
@@ -2748,11 +3315,18 @@ fn cmd_alexandria(command: AlexandriaCommands) -> Result<()> {
         AlexandriaCommands::Nodes => cmd_alexandria_nodes(),
         AlexandriaCommands::Sync => cmd_alexandria_sync(),
         AlexandriaCommands::Proof => cmd_alexandria_proof(),
-        AlexandriaCommands::Export { output } => cmd_alexandria_export(output),
+        AlexandriaCommands::Export { output, format, concept, hops } => {
+            cmd_alexandria_export(output, format, concept, hops)
+        }
+        AlexandriaCommands::Import { input, format } => cmd_alexandria_import(input, format),
+        AlexandriaCommands::Hubs { concept, hops, top } => cmd_alexandria_hubs(concept, hops, top),
+        AlexandriaCommands::Path { from, to } => cmd_alexandria_path(from, to),
+        AlexandriaCommands::Clusters { min_size } => cmd_alexandria_clusters(min_size),
+        AlexandriaCommands::Changes { days, top } => cmd_alexandria_changes(days, top),
     }
 }
 
-fn load_alexandria() -> gently_search::AlexandriaSearch {
+fn load_alexandria() -> Result<gently_search::AlexandriaSearch> {
     use gently_alexandria::NodeFingerprint;
 
     // Get hardware fingerprint
@@ -2771,11 +3345,16 @@ fn load_alexandria() -> gently_search::AlexandriaSearch {
         machine_id.trim(),
     );
 
-    gently_search::AlexandriaSearch::new(fingerprint)
+    // Use the persisted graph (and its snapshot history) and tesseract when there are some
+    Ok(gently_search::AlexandriaSearch::load_or_create(
+        fingerprint,
+        &gently_alexandria::AlexandriaGraph::default_path(),
+        gently_alexandria::AlexandriaConfig::default(),
+    )?)
 }
 
 fn cmd_alexandria_status() -> Result<()> {
-    let search = load_alexandria();
+    let search = load_alexandria()?;
     let stats = search.stats();
 
     println!("\n  ALEXANDRIA MESH STATUS");
@@ -2799,7 +3378,7 @@ fn cmd_alexandria_status() -> Result<()> {
 }
 
 fn cmd_alexandria_query(concept: String, _history: bool, drift: bool) -> Result<()> {
-    let mut search = load_alexandria();
+    let mut search = load_alexandria()?;
 
     println!("\n  ALEXANDRIA QUERY: {}", concept);
     println!("  {}", "=".repeat(20 + concept.len()));
@@ -2830,7 +3409,7 @@ fn cmd_alexandria_query(concept: String, _history: bool, drift: bool) -> Result<
 }
 
 fn cmd_alexandria_topology(concept: String, hops: usize) -> Result<()> {
-    let search = load_alexandria();
+    let search = load_alexandria()?;
 
     println!("\n  TOPOLOGY: {} (max {} hops)", concept, hops);
     println!("  {}", "=".repeat(30));
@@ -2858,7 +3437,7 @@ fn cmd_alexandria_topology(concept: String, hops: usize) -> Result<()> {
 }
 
 fn cmd_alexandria_nodes() -> Result<()> {
-    let search = load_alexandria();
+    let search = load_alexandria()?;
     let sync_stats = search.sync.stats();
 
     println!("\n  ALEXANDRIA MESH NODES");
@@ -2876,62 +3455,269 @@ fn cmd_alexandria_nodes() -> Result<()> {
     Ok(())
 }
 
-fn cmd_alexandria_sync() -> Result<()> {
-    let search = load_alexandria();
+fn cmd_alexandria_sync() -> Result<()> {
+    let search = load_alexandria()?;
+
+    println!("\n  SYNCING WITH MESH...\n");
+
+    let delta = search.get_sync_delta();
+    if delta.is_empty() {
+        println!("  No pending updates to publish.");
+    } else {
+        println!("  Pending updates:");
+        println!("    New concepts: {}", delta.new_concepts.len());
+        println!("    Edge updates: {}", delta.edge_updates.len());
+        println!("    Wormhole updates: {}", delta.wormhole_updates.len());
+        println!();
+        println!("  (IPFS pubsub not yet wired - delta ready for broadcast)");
+    }
+
+    Ok(())
+}
+
+fn cmd_alexandria_proof() -> Result<()> {
+    let search = load_alexandria()?;
+    let proof = search.contribution_proof();
+
+    println!("\n  CONTRIBUTION PROOF");
+    println!("  ==================\n");
+    println!("  Node: {}", proof.node.short());
+    println!("  Timestamp: {}", proof.timestamp);
+    println!();
+    println!("  Knowledge contribution:");
+    println!("    Concepts stored:     {}", proof.concepts_stored);
+    println!("    Edges stored:        {}", proof.edges_stored);
+    println!("    Wormholes discovered: {}", proof.wormholes_discovered);
+    println!("    Validated edges:     {}", proof.validated_edges);
+    println!();
+    println!("  Network contribution:");
+    println!("    Deltas published: {}", proof.deltas_published);
+    println!("    Deltas relayed:   {}", proof.deltas_relayed);
+    println!("    Queries served:   {}", proof.queries_served);
+    println!();
+    println!("  Quality:");
+    println!("    Validation rate: {:.1}%", proof.edge_validation_rate * 100.0);
+    println!("    Uptime hours:    {:.1}", proof.uptime_hours);
+    println!();
+    println!("  Merkle root: {}", hex::encode(&proof.merkle_root[..8]));
+
+    Ok(())
+}
+
+/// Resolve `--format`, falling back to the file extension (None = native JSON)
+fn graph_format(format: Option<&str>, path: &str) -> Result<Option<gently_alexandria::GraphFormat>> {
+    use gently_alexandria::GraphFormat;
+
+    match format {
+        Some(name) => GraphFormat::parse(name)
+            .map(Some)
+            .ok_or_else(|| anyhow::anyhow!("Unknown graph format: {}", name)),
+        None => Ok(std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(GraphFormat::from_extension)),
+    }
+}
+
+fn cmd_alexandria_export(output: String, format: Option<String>, concept: Option<String>, hops: usize) -> Result<()> {
+    use gently_alexandria::Subgraph;
+
+    let search = load_alexandria()?;
+    let data = match graph_format(format.as_deref(), &output)? {
+        Some(format) => {
+            let selection = match concept {
+                Some(concept) => Subgraph::Around { concept, hops },
+                None => Subgraph::All,
+            };
+            search.graph.export_as(format, &selection).into_bytes()
+        }
+        None => search.graph.export(),
+    };
+
+    std::fs::write(&output, &data)?;
+    println!("\n  Exported graph to: {}", output);
+    println!("  Size: {} bytes", data.len());
+
+    Ok(())
+}
+
+fn cmd_alexandria_import(input: String, format: Option<String>) -> Result<()> {
+    let format = graph_format(format.as_deref(), &input)?
+        .filter(|f| f.is_importable())
+        .ok_or_else(|| anyhow::anyhow!("Import supports GraphML and CSV edge lists"))?;
+
+    let search = load_alexandria()?;
+    let data = std::fs::read_to_string(&input)?;
+    let added = search.graph.import_from(format, &data)?;
+
+    println!("\n  Imported {} new edges from: {}", added, input);
+    println!("  Graph now has {} concepts, {} edges", search.graph.concept_count(), search.graph.edge_count());
+
+    let path = gently_alexandria::AlexandriaGraph::default_path();
+    search.save(&path)?;
+    println!("  Saved to: {}", path.display());
+
+    Ok(())
+}
+
+fn alexandria_label(graph: &gently_alexandria::AlexandriaGraph, id: &gently_alexandria::ConceptId) -> String {
+    graph
+        .get_concept(id)
+        .map(|c| c.text)
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| id.short())
+}
+
+fn cmd_alexandria_hubs(concept: Option<String>, hops: usize, top: usize) -> Result<()> {
+    use gently_alexandria::query::QueryBuilder;
+
+    let search = load_alexandria()?;
+    let mut query = QueryBuilder::new(&search.graph).max_hops(hops);
+    if let Some(concept) = &concept {
+        query = query.concept(concept);
+    }
+    let hubs = query.execute_hubs(top);
+
+    println!("\n  KNOWLEDGE HUBS");
+    println!("  ==============");
+    if let Some(concept) = &concept {
+        println!("  (within {} hops of {})", hops, concept);
+    }
+    println!();
+
+    if hubs.is_empty() {
+        println!("  No concepts in graph.");
+        return Ok(());
+    }
+
+    println!("  {:<24} {:>8} {:>6} {:>5} {:>8}", "CONCEPT", "RANK", "DEG", "CORE", "BETWEEN");
+    for hub in &hubs {
+        println!(
+            "  {:<24} {:>8.4} {:>6} {:>5} {:>8.3}",
+            alexandria_label(&search.graph, &hub.concept),
+            hub.pagerank,
+            hub.degree,
+            hub.core,
+            hub.betweenness
+        );
+    }
+
+    Ok(())
+}
+
+fn cmd_alexandria_path(from: String, to: String) -> Result<()> {
+    use gently_alexandria::query::QueryBuilder;
 
-    println!("\n  SYNCING WITH MESH...\n");
+    let search = load_alexandria()?;
 
-    let delta = search.get_sync_delta();
-    if delta.is_empty() {
-        println!("  No pending updates to publish.");
-    } else {
-        println!("  Pending updates:");
-        println!("    New concepts: {}", delta.new_concepts.len());
-        println!("    Edge updates: {}", delta.edge_updates.len());
-        println!("    Wormhole updates: {}", delta.wormhole_updates.len());
-        println!();
-        println!("  (IPFS pubsub not yet wired - delta ready for broadcast)");
+    println!("\n  STRONGEST PATH: {} → {}", from, to);
+    println!("  {}", "=".repeat(20 + from.len() + to.len()));
+
+    match QueryBuilder::new(&search.graph).concept(&from).weighted_path_to(&to) {
+        Some(path) => {
+            let labels: Vec<String> = path
+                .concepts
+                .iter()
+                .map(|id| alexandria_label(&search.graph, id))
+                .collect();
+            println!("\n  {}", labels.join(" → "));
+            println!("\n  Hops:       {}", path.concepts.len().saturating_sub(1));
+            println!("  Cost:       {:.3}", path.cost);
+            println!("  Bottleneck: {:.3}", path.bottleneck);
+        }
+        None => println!("\n  No path found."),
     }
 
     Ok(())
 }
 
-fn cmd_alexandria_proof() -> Result<()> {
-    let search = load_alexandria();
-    let proof = search.contribution_proof();
+fn cmd_alexandria_clusters(min_size: usize) -> Result<()> {
+    let search = load_alexandria()?;
+    let report = search.graph.communities();
 
-    println!("\n  CONTRIBUTION PROOF");
-    println!("  ==================\n");
-    println!("  Node: {}", proof.node.short());
-    println!("  Timestamp: {}", proof.timestamp);
-    println!();
-    println!("  Knowledge contribution:");
-    println!("    Concepts stored:     {}", proof.concepts_stored);
-    println!("    Edges stored:        {}", proof.edges_stored);
-    println!("    Wormholes discovered: {}", proof.wormholes_discovered);
-    println!("    Validated edges:     {}", proof.validated_edges);
-    println!();
-    println!("  Network contribution:");
-    println!("    Deltas published: {}", proof.deltas_published);
-    println!("    Deltas relayed:   {}", proof.deltas_relayed);
-    println!("    Queries served:   {}", proof.queries_served);
-    println!();
-    println!("  Quality:");
-    println!("    Validation rate: {:.1}%", proof.edge_validation_rate * 100.0);
-    println!("    Uptime hours:    {:.1}", proof.uptime_hours);
-    println!();
-    println!("  Merkle root: {}", hex::encode(&proof.merkle_root[..8]));
+    println!("\n  TOPIC CLUSTERS");
+    println!("  ==============\n");
+    println!("  Modularity: {:.3}", report.modularity);
+
+    for community in report.communities.iter().filter(|c| c.members.len() >= min_size) {
+        let labels: Vec<String> = community
+            .members
+            .iter()
+            .take(8)
+            .map(|id| alexandria_label(&search.graph, id))
+            .collect();
+        let more = community.members.len().saturating_sub(labels.len());
+        println!(
+            "\n  #{} ({} concepts, weight {:.2})",
+            community.id,
+            community.members.len(),
+            community.internal_weight
+        );
+        print!("    {}", labels.join(", "));
+        if more > 0 {
+            print!(" … +{}", more);
+        }
+        println!();
+    }
 
     Ok(())
 }
 
-fn cmd_alexandria_export(output: String) -> Result<()> {
-    let search = load_alexandria();
-    let data = search.graph.export();
+fn cmd_alexandria_changes(days: i64, top: usize) -> Result<()> {
+    let search = load_alexandria()?;
+    let history = search.graph.history();
 
-    std::fs::write(&output, &data)?;
-    println!("\n  Exported graph to: {}", output);
-    println!("  Size: {} bytes", data.len());
+    println!("\n  TOPOLOGY CHANGES");
+    println!("  ================\n");
+
+    let (Some(oldest), Some(latest)) = (history.oldest(), history.latest()) else {
+        println!("  No snapshots recorded yet.");
+        return Ok(());
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let since = (now - days * 86400).max(oldest);
+    let diff = search.graph.diff_between(since, latest);
+
+    println!(
+        "  {} → {} ({} snapshots)",
+        chrono::DateTime::from_timestamp(since, 0).map(|t| t.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default(),
+        chrono::DateTime::from_timestamp(latest, 0).map(|t| t.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default(),
+        history.len()
+    );
+    println!(
+        "  Edges: +{} added, -{} removed, {} reweighted",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    );
+
+    if diff.is_empty() {
+        println!("\n  No changes in this period.");
+        return Ok(());
+    }
+
+    let labels = |ids: &[gently_alexandria::ConceptId]| {
+        ids.iter()
+            .take(5)
+            .map(|id| alexandria_label(&search.graph, id))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    for change in diff.concept_changes().into_iter().take(top) {
+        println!(
+            "\n  {} ({:+.2})",
+            alexandria_label(&search.graph, &change.concept),
+            change.weight_delta
+        );
+        if !change.gained.is_empty() {
+            println!("    + {}", labels(&change.gained));
+        }
+        if !change.lost.is_empty() {
+            println!("    - {}", labels(&change.lost));
+        }
+    }
 
     Ok(())
 }
@@ -3459,9 +4245,11 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
             println!("  ==============\n");
             println!("  Input: {}", &text[..text.len().min(50)]);
 
-            let embedder = Embedder::new();
+            let mut embedder = Embedder::new();
+            embedder.load_default()?;
             let embedding = embedder.embed(&text)?;
 
+            println!("  Model: {}", embedder.model_name());
             println!("  Dimensions: {}", embedding.len());
             println!("  First 5 values: {:?}", &embedding[..5.min(embedding.len())]);
             Ok(())
@@ -3512,7 +4300,7 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
 
             println!("  MODELS:");
             println!("    Llama 1B:    Not downloaded");
-            println!("    Embedder:    Simulated (use download for real ONNX)");
+            println!("    Embedder:    {} (384d)", Embedder::new().model_name());
             println!();
             println!("  TENSORCHAIN:");
             println!("    Use 'gently brain learn' to add memories.");
@@ -3520,17 +4308,17 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
         }
 
         BrainCommands::Orchestrate { ipfs, verbose } => {
-            use gently_brain::{BrainOrchestrator, BrainConfig};
+            use gently_brain::BrainConfig;
 
             println!("\n  BRAIN ORCHESTRATOR");
             println!("  ==================\n");
 
             let config = BrainConfig {
                 enable_ipfs: ipfs,
-                ..Default::default()
+                ..brain_config()
             };
 
-            let orchestrator = std::sync::Arc::new(BrainOrchestrator::new(config));
+            let orchestrator = std::sync::Arc::new(brain_orchestrator(config));
 
             // Create runtime for async operations
             let rt = tokio::runtime::Runtime::new()?;
@@ -3637,12 +4425,48 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
                 registry.list()
             };
 
+            let policy = brain_tool_policy()?;
             for tool in &tools {
-                let confirm = if tool.requires_confirmation { " [!]" } else { "" };
-                println!("  {:25} [{:?}]{} {}", tool.name, tool.category, confirm, tool.description);
+                let marker = match policy.resolve(tool) {
+                    gently_brain::Permission::Allow => "",
+                    gently_brain::Permission::Ask => " [!]",
+                    gently_brain::Permission::Deny => " [x]",
+                };
+                println!("  {:25} [{:?}]{} {}", tool.name, tool.category, marker, tool.description);
             }
             println!("\n  Total: {} tools", tools.len());
-            println!("  [!] = requires confirmation");
+            println!("  [!] = requires confirmation   [x] = denied by policy");
+            Ok(())
+        }
+
+        BrainCommands::Call { tool, input, yes } => {
+            use gently_brain::BrainConfig;
+
+            let input: serde_json::Value = serde_json::from_str(&input)?;
+            let orchestrator = brain_orchestrator(BrainConfig {
+                enable_daemons: false,
+                ..brain_config()
+            });
+            let registry = orchestrator.tool_registry();
+            registry.set_policy(brain_tool_policy()?);
+            registry.set_audit_path(Some(brain_dir()?.join("tool_audit.jsonl")));
+            registry.set_confirmer(Some(stdin_confirmer(yes)));
+
+            let rt = tokio::runtime::Runtime::new()?;
+            let result = rt.block_on(orchestrator.execute_tool(&tool, &input));
+
+            println!("\n  TOOL CALL {}", tool);
+            println!("  =========\n");
+            match result {
+                Ok(r) => {
+                    println!("  Success: {}", r.success);
+                    println!("  Output:  {}", serde_json::to_string_pretty(&r.output)?);
+                }
+                Err(e) => println!("  ✗ {}", e),
+            }
+            if let Some(record) = registry.audit_log().last() {
+                println!("\n  Audit #{}: {:?} ({}ms)", record.id, record.decision, record.duration_ms);
+            }
             Ok(())
         }
 
@@ -3651,30 +4475,18 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
 
             match action {
                 DaemonAction::List => {
-                    println!("\n  RUNNING DAEMONS");
-                    println!("  ===============\n");
-
-                    let dm = DaemonManager::new();
-                    let daemons = dm.list();
-
-                    if daemons.is_empty() {
-                        println!("  No daemons running.");
-                        println!("  Use: gently brain daemon spawn <type>");
-                    } else {
-                        for (name, dtype, running) in daemons {
-                            let status = if running { "running" } else { "stopped" };
-                            println!("  {:30} [{:?}] {}", name, dtype, status);
-                        }
+                    println!("\n  DAEMON TYPES");
+                    println!("  ============\n");
+                    for (name, about) in DAEMON_TYPES {
+                        println!("  {:16} {}", name, about);
                     }
+                    println!("\n  Run one with: gently brain daemon spawn <type>");
                 }
 
                 DaemonAction::Spawn { daemon_type } => {
                     println!("\n  SPAWN DAEMON");
                     println!("  ============\n");
 
-                    let mut dm = DaemonManager::new();
-                    dm.start();
-
                     let dtype = match daemon_type.to_lowercase().as_str() {
                         "vector_chain" | "vector" => DaemonType::VectorChain,
                         "ipfs_sync" | "ipfs" => DaemonType::IpfsSync,
@@ -3689,40 +4501,38 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
                         }
                     };
 
-                    match dm.spawn(dtype) {
-                        Ok(name) => println!("  Spawned: {}", name),
-                        Err(e) => println!("  Error: {:?}", e),
-                    }
-                }
-
-                DaemonAction::Stop { name } => {
-                    println!("\n  STOP DAEMON");
-                    println!("  ===========\n");
-                    println!("  Stopping: {}", name);
-                    println!("  (Daemon lifecycle managed by orchestrator)");
-                }
+                    // The daemon is a task on this runtime and lives as long as the command
+                    let rt = tokio::runtime::Runtime::new()?;
+                    rt.block_on(async {
+                        let mut dm = DaemonManager::new();
+                        dm.start();
+                        let name = match dm.spawn(dtype) {
+                            Ok(name) => name,
+                            Err(e) => {
+                                println!("  Error: {}", e);
+                                return Ok(());
+                            }
+                        };
+                        println!("  Running: {} (Ctrl-C to stop)", name);
 
-                DaemonAction::Metrics { name } => {
-                    println!("\n  DAEMON METRICS");
-                    println!("  ==============\n");
+                        tokio::signal::ctrl_c().await?;
+                        println!("\n  Stopping: {}", name);
+                        if let Err(e) = dm.shutdown(std::time::Duration::from_secs(5)).await {
+                            println!("  [!] {}", e);
+                        }
 
-                    let dm = DaemonManager::new();
-                    match dm.status(&name) {
-                        Some(status) => {
-                            println!("  Daemon: {}", name);
-                            println!("  Running: {}", status.running);
+                        if let Some(status) = dm.status(&name) {
+                            println!();
                             println!("  Cycles: {}", status.cycles);
                             println!("  Errors: {}", status.errors);
-                            println!();
-                            println!("  Metrics:");
-                            println!("    Items processed: {}", status.metrics.items_processed);
-                            println!("    Vectors computed: {}", status.metrics.vectors_computed);
-                            println!("    Bytes synced: {}", status.metrics.bytes_synced);
-                            println!("    Branches created: {}", status.metrics.branches_created);
-                            println!("    Learnings added: {}", status.metrics.learnings_added);
+                            println!("  Items processed: {}", status.metrics.items_processed);
+                            println!("  Vectors computed: {}", status.metrics.vectors_computed);
+                            println!("  Bytes synced: {}", status.metrics.bytes_synced);
+                            println!("  Branches created: {}", status.metrics.branches_created);
+                            println!("  Learnings added: {}", status.metrics.learnings_added);
                         }
-                        None => println!("  Daemon not found: {}", name),
-                    }
+                        Ok::<_, anyhow::Error>(())
+                    })?;
                 }
             }
             Ok(())
@@ -3784,11 +4594,16 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
                     }
                 }
 
-                KnowledgeAction::Export { output } => {
+                KnowledgeAction::Export { output, format, concept, hops } => {
                     println!("\n  EXPORT KNOWLEDGE GRAPH");
                     println!("  ======================\n");
 
-                    let data = graph.export();
+                    let data = match graph_format(format.as_deref(), &output)? {
+                        Some(format) => graph
+                            .export_as(format, concept.as_deref().map(|c| (c, hops)))
+                            .into_bytes(),
+                        None => graph.export(),
+                    };
                     std::fs::write(&output, &data)?;
                     println!("  Exported {} bytes to: {}", data.len(), output);
                 }
@@ -3806,7 +4621,7 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
         }
 
         BrainCommands::Think { thought } => {
-            use gently_brain::{BrainOrchestrator, BrainConfig};
+            use gently_brain::BrainConfig;
 
             println!("\n  PROCESSING THOUGHT");
             println!("  ==================\n");
@@ -3814,9 +4629,9 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
 
             let config = BrainConfig {
                 enable_daemons: false,
-                ..Default::default()
+                ..brain_config()
             };
-            let orchestrator = BrainOrchestrator::new(config);
+            let orchestrator = brain_orchestrator(config);
 
             let rt = tokio::runtime::Runtime::new()?;
             let result = rt.block_on(orchestrator.process_thought(&thought));
@@ -3838,13 +4653,10 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
         }
 
         BrainCommands::Focus { topic } => {
-            use gently_brain::{BrainOrchestrator, BrainConfig};
-
             println!("\n  FOCUSING ATTENTION");
             println!("  ==================\n");
 
-            let config = BrainConfig::default();
-            let orchestrator = BrainOrchestrator::new(config);
+            let orchestrator = brain_orchestrator(brain_config());
 
             orchestrator.focus(&topic);
             let snapshot = orchestrator.get_awareness_snapshot();
@@ -3856,7 +4668,7 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
         }
 
         BrainCommands::Grow { domain } => {
-            use gently_brain::{BrainOrchestrator, BrainConfig};
+            use gently_brain::BrainConfig;
 
             println!("\n  TRIGGERING GROWTH");
             println!("  =================\n");
@@ -3864,9 +4676,9 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
 
             let config = BrainConfig {
                 enable_daemons: false,
-                ..Default::default()
+                ..brain_config()
             };
-            let orchestrator = BrainOrchestrator::new(config);
+            let orchestrator = brain_orchestrator(config);
 
             let rt = tokio::runtime::Runtime::new()?;
             let nodes_added = rt.block_on(orchestrator.grow(&domain));
@@ -3902,7 +4714,304 @@ fn cmd_brain(command: BrainCommands) -> Result<()> {
             }
             Ok(())
         }
+
+        BrainCommands::Chain { path, action } => cmd_brain_chain(path, action),
+        BrainCommands::Rules { action } => cmd_brain_rules(action),
+    }
+}
+
+/// ~/.gently/brain, created on demand
+fn brain_dir() -> Result<std::path::PathBuf> {
+    let dir = dirs::home_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join(".gently")
+        .join("brain");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Brain settings for the CLI: the tesseract persists in ~/.gently/alexandria
+fn brain_config() -> gently_brain::BrainConfig {
+    gently_brain::BrainConfig {
+        tesseract_path: Some(gently_alexandria::SemanticTesseract::default_path()),
+        ..Default::default()
+    }
+}
+
+/// Orchestrator that says so when its saved tesseract could not be loaded
+fn brain_orchestrator(config: gently_brain::BrainConfig) -> gently_brain::BrainOrchestrator {
+    let orchestrator = gently_brain::BrainOrchestrator::new(config);
+    if let Some(e) = orchestrator.tesseract_error() {
+        println!("  [!] Tesseract not loaded, leaving the file untouched: {}", e);
+    }
+    orchestrator
+}
+
+/// Daemons `brain daemon spawn` can run, with what they do
+const DAEMON_TYPES: &[(&str, &str)] = &[
+    ("vector_chain", "Computes embeddings for queued items"),
+    ("ipfs_sync", "Hashes queued sync jobs (stub: no IPFS client yet)"),
+    ("git_branch", "Manages knowledge branches"),
+    ("knowledge_graph", "Heartbeat only"),
+    ("awareness", "The awareness loop"),
+    ("inference", "Heartbeat only"),
+];
+
+/// Asks on stdin before a gated tool runs; `yes` approves everything
+fn stdin_confirmer(yes: bool) -> std::sync::Arc<dyn gently_brain::ToolConfirmer> {
+    use std::io::{BufRead, Write};
+
+    std::sync::Arc::new(move |req: &gently_brain::ConfirmRequest| {
+        if yes {
+            return true;
+        }
+        println!("  {} wants to run with {}", req.tool, req.input);
+        if !req.effects.is_empty() {
+            println!("  Effects: {:?}", req.effects);
+        }
+        print!("  Allow? [y/N] ");
+        let _ = std::io::stdout().flush();
+        let mut answer = String::new();
+        let _ = std::io::stdin().lock().read_line(&mut answer);
+        matches!(answer.trim(), "y" | "Y" | "yes")
+    })
+}
+
+/// Agent whose tool calls run through the orchestrator, asking `confirmer` when gated
+fn brain_agent(
+    orchestrator: std::sync::Arc<gently_brain::BrainOrchestrator>,
+    runtime: tokio::runtime::Handle,
+    confirmer: std::sync::Arc<dyn gently_brain::ToolConfirmer>,
+    config: gently_brain::AgentConfig,
+) -> gently_brain::AgentLoop {
+    orchestrator.tool_registry().set_confirmer(Some(confirmer));
+    orchestrator.agent_loop(runtime).with_config(config)
+}
+
+/// Tool policy from ~/.gently/brain/tool_policy.json, or the default
+fn brain_tool_policy() -> Result<gently_brain::ToolPolicy> {
+    let path = brain_dir()?.join("tool_policy.json");
+    if path.exists() {
+        Ok(gently_brain::ToolPolicy::load(&path)?)
+    } else {
+        Ok(gently_brain::ToolPolicy::default())
+    }
+}
+
+fn cmd_brain_rules(action: RulesAction) -> Result<()> {
+    use gently_brain::Watchdog;
+    use std::path::Path;
+
+    match action {
+        RulesAction::Check { path } => {
+            let path = Path::new(&path);
+            let mut watchdog = Watchdog::new();
+
+            println!("\n  WATCHDOG RULES");
+            println!("  ==============\n");
+            let errors = if path.is_dir() {
+                let report = watchdog.watch_rules(path)?;
+                for (file, err) in &report.errors {
+                    println!("  ✗ {}: {}", file.display(), err);
+                }
+                report.errors.len()
+            } else {
+                watchdog.load_rules(&std::fs::read_to_string(path)?)?;
+                0
+            };
+            for (hash, rule) in watchdog.active_rules() {
+                println!("  {:30} {}", rule.name, &hex::encode(hash)[..12]);
+            }
+            if errors > 0 {
+                anyhow::bail!("{} rule files failed to compile", errors);
+            }
+        }
+
+        RulesAction::Test { rules, events } => {
+            let report = gently_brain::watchdog::replay(
+                &std::fs::read_to_string(&rules)?,
+                &std::fs::read_to_string(&events)?,
+            )?;
+
+            println!("\n  RULE REPLAY");
+            println!("  ===========\n");
+            for step in &report.steps {
+                let mark = if step.passed() { " " } else { "✗" };
+                println!("  {} line {:4}  fired [{}]", mark, step.line, step.fired.join(", "));
+                if let (false, Some(expected)) = (step.passed(), &step.expected) {
+                    println!("               expected [{}]", expected.join(", "));
+                }
+            }
+
+            let mut counts: Vec<_> = report.counts().into_iter().collect();
+            counts.sort();
+            println!();
+            for (name, n) in counts {
+                println!("  {:30} {} times", name, n);
+            }
+            if !report.passed() {
+                anyhow::bail!("{} events did not fire as expected", report.failures().len());
+            }
+            println!("\n  All expectations met.");
+        }
+    }
+    Ok(())
+}
+
+fn cmd_brain_chain(path: Option<String>, action: ChainAction) -> Result<()> {
+    use gently_brain::{Change, GitChain, MergeKind};
+    use std::path::PathBuf;
+    use gently_core::hex_hash;
+
+    let path = path.map(PathBuf::from).unwrap_or_else(|| {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".gently")
+            .join("brain")
+            .join("gitchain.bin")
+    });
+
+    // Import builds a fresh chain, so it does not need an existing one
+    if let ChainAction::ImportGit { repo } = &action {
+        let chain = gently_brain::gitrepo::import(std::path::Path::new(repo))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        chain.save(&path)?;
+
+        println!("\n  CHAIN IMPORT {}", repo);
+        println!("  ============\n");
+        for b in chain.branches() {
+            println!("  {:40} {}", b.name, &hex_hash(&b.head)[..12]);
+        }
+        println!("\n  Saved to {}", path.display());
+        return Ok(());
+    }
+
+    if !path.exists() {
+        anyhow::bail!("No chain at {}", path.display());
+    }
+    let mut chain = GitChain::load(&path)?;
+    let short = |h: &gently_core::Hash| hex_hash(h)[..12].to_string();
+    let resolve = |chain: &GitChain, name: &str| {
+        chain.resolve(name).ok_or_else(|| anyhow::anyhow!("Unknown branch or commit: {}", name))
+    };
+
+    match action {
+        ChainAction::Branches => {
+            println!("\n  CHAIN BRANCHES");
+            println!("  ==============\n");
+
+            let mut branches = chain.branches();
+            branches.sort_by_key(|b| (!b.name.starts_with("session-"), b.name.clone()));
+            for b in branches {
+                let marker = if b.name == chain.current_branch() { "*" } else { " " };
+                let commits = chain.log(&b.head, usize::MAX).len();
+                println!("  {} {:40} {}  {} commits", marker, b.name, short(&b.head), commits);
+            }
+        }
+
+        ChainAction::Log { branch, limit } => {
+            let name = branch.unwrap_or_else(|| chain.current_branch().to_string());
+            let head = resolve(&chain, &name)?;
+
+            println!("\n  CHAIN LOG: {}", name);
+            println!("  ==========\n");
+            for (hash, meta) in chain.log(&head, limit) {
+                let merge = if chain.parents(&hash).len() > 1 { " (merge)" } else { "" };
+                println!("  {}  {:12} {}{}", short(&hash), meta.author, meta.message, merge);
+                if let Some(i) = chain.interaction_meta(&hash) {
+                    println!("                 session {} #{} btc {}", i.session_id, i.index, i.btc_height);
+                }
+            }
+        }
+
+        ChainAction::Diff { from, to } => {
+            let (a, b) = (resolve(&chain, &from)?, resolve(&chain, &to)?);
+            let diff = chain.diff(&a, &b).ok_or_else(|| anyhow::anyhow!("Not a commit"))?;
+
+            println!("\n  CHAIN DIFF {} → {}", short(&a), short(&b));
+            println!("  ==========\n");
+            if diff.is_empty() {
+                println!("  No changes.");
+            }
+            for entry in diff {
+                let path: Vec<String> = entry.path.iter().map(|t| format!("{:#06x}", t)).collect();
+                let change = match entry.change {
+                    Change::Added(h) => format!("+ {}", short(&h)),
+                    Change::Removed(h) => format!("- {}", short(&h)),
+                    Change::Modified { from, to } => format!("~ {} → {}", short(&from), short(&to)),
+                };
+                println!("  {:30} {}", path.join("/"), change);
+            }
+        }
+
+        ChainAction::Merge { branch, into, author } => {
+            if !chain.checkout(&into) {
+                anyhow::bail!("Unknown branch: {}", into);
+            }
+            let result = chain.merge(&branch, &author)
+                .ok_or_else(|| anyhow::anyhow!("Unknown branch: {}", branch))?;
+
+            println!("\n  CHAIN MERGE {} → {}", branch, into);
+            println!("  ===========\n");
+            match result.kind {
+                MergeKind::UpToDate => println!("  Already up to date."),
+                MergeKind::FastForward => println!("  Fast-forward to {}", short(&result.commit)),
+                MergeKind::Merged => println!("  Merge commit {}", short(&result.commit)),
+            }
+            for c in &result.conflicts {
+                println!("  CONFLICT at {:?} (kept ours)", c.path);
+            }
+            chain.save(&path)?;
+        }
+
+        ChainAction::Rebase { branch, onto } => {
+            let result = chain.rebase(&branch, &onto)
+                .ok_or_else(|| anyhow::anyhow!("Cannot rebase {} onto {}", branch, onto))?;
+
+            println!("\n  CHAIN REBASE {} onto {}", branch, onto);
+            println!("  ============\n");
+            for (old, new) in &result.picked {
+                println!("  {} → {}", short(old), short(new));
+            }
+            println!("\n  {} commits replayed, head {}", result.picked.len(), short(&result.head));
+            if !result.conflicts.is_empty() {
+                println!("  {} conflicts (kept ours)", result.conflicts.len());
+            }
+            chain.save(&path)?;
+        }
+
+        ChainAction::Conflicts { commit } => {
+            let hash = resolve(&chain, &commit)?;
+            let conflicts = chain.conflicts(&hash);
+
+            println!("\n  CHAIN CONFLICTS {}", short(&hash));
+            println!("  ===============\n");
+            if conflicts.is_empty() {
+                println!("  None recorded.");
+            }
+            let side = |h: &Option<gently_core::Hash>| h.as_ref().map(short).unwrap_or_else(|| "-".into());
+            for c in conflicts {
+                println!("  {:?}  base {}  ours {}  theirs {}", c.path, side(&c.base), side(&c.ours), side(&c.theirs));
+            }
+        }
+
+        ChainAction::ExportGit { repo } => {
+            let report = gently_brain::gitrepo::export(&chain, std::path::Path::new(&repo))?;
+
+            println!("\n  CHAIN EXPORT {}", repo);
+            println!("  ============\n");
+            for (name, oid) in &report.refs {
+                println!("  {:40} {}", name, &oid[..12]);
+            }
+            println!("\n  {} commits, {} new objects", report.commits, report.objects_written);
+            println!("  Inspect with: git --git-dir {} log --all", repo);
+        }
+
+        ChainAction::ImportGit { .. } => unreachable!(),
     }
+    Ok(())
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -4586,7 +5695,7 @@ fn cmd_claude(command: ClaudeCommands) -> Result<()> {
             println!("  ===========");
             println!("  Model: {}\n", model_type.display_name());
 
-            match GentlyAssistant::with_model(model_type) {
+            match claude_assistant(model_type) {
                 Ok(mut assistant) => {
                     match assistant.chat(&message) {
                         Ok(response) => {
@@ -4620,9 +5729,8 @@ fn cmd_claude(command: ClaudeCommands) -> Result<()> {
             println!("  ==========");
             println!("  Model: {}\n", model_type.display_name());
 
-            match ClaudeClient::new() {
+            match claude_client(model_type) {
                 Ok(client) => {
-                    let client = client.model(model_type);
                     match client.ask(&question) {
                         Ok(response) => {
                             println!("  Q: {}\n", question);
@@ -4657,9 +5765,8 @@ fn cmd_claude(command: ClaudeCommands) -> Result<()> {
             println!("  Type 'clear' to reset conversation.");
             println!();
 
-            match ClaudeClient::new() {
-                Ok(client) => {
-                    let mut client = client.model(model_type);
+            match claude_client(model_type) {
+                Ok(mut client) => {
                     if let Some(sys) = system {
                         client = client.system(&sys);
                     }
@@ -4728,6 +5835,85 @@ fn cmd_claude(command: ClaudeCommands) -> Result<()> {
             Ok(())
         }
 
+        ClaudeCommands::Agent { task, model, max_steps, max_tokens, yes } => {
+            use gently_brain::{AgentConfig, AgentEvent, BrainConfig};
+            use std::sync::Arc;
+
+            let model_type = ClaudeModel::from_str(&model);
+
+            println!("\n  CLAUDE AGENT");
+            println!("  ============");
+            println!("  Model: {}\n", model_type.display_name());
+
+            let mut assistant = match claude_assistant(model_type) {
+                Ok(a) => a,
+                Err(e) => {
+                    println!("  [!] Failed to initialize Claude: {}", e);
+                    return Ok(());
+                }
+            };
+
+            let orchestrator = Arc::new(brain_orchestrator(BrainConfig {
+                enable_daemons: false,
+                ..brain_config()
+            }));
+            let registry = orchestrator.tool_registry();
+            registry.set_policy(brain_tool_policy()?);
+            registry.set_audit_path(Some(brain_dir()?.join("tool_audit.jsonl")));
+
+            let rt = tokio::runtime::Runtime::new()?;
+            let agent = brain_agent(
+                orchestrator.clone(),
+                rt.handle().clone(),
+                stdin_confirmer(yes),
+                AgentConfig { max_steps, max_tokens, ..Default::default() },
+            );
+
+            // Print progress as it happens
+            let events = agent.events();
+            let printer = std::thread::spawn(move || {
+                let mut rx = events.lock().unwrap();
+                while let Some(event) = rx.blocking_recv() {
+                    match event {
+                        AgentEvent::Plan { steps } => {
+                            println!("  Plan:");
+                            for (i, s) in steps.iter().enumerate() {
+                                println!("    {}. {}", i + 1, s);
+                            }
+                        }
+                        AgentEvent::ToolCall { step, name, input, .. } => {
+                            println!("  [{}] → {} {}", step, name, input);
+                        }
+                        AgentEvent::ToolResult { step, success, output, .. } => {
+                            let mark = if success { "✓" } else { "✗" };
+                            let short: String = output.chars().take(120).collect();
+                            println!("  [{}] {} {}", step, mark, short);
+                        }
+                        AgentEvent::Finished { stop, steps, tokens } => {
+                            println!("\n  Stopped: {:?} after {} steps, {} tokens", stop, steps, tokens);
+                        }
+                        _ => {}
+                    }
+                }
+            });
+
+            let result = assistant.run_agent(&agent, &task);
+            drop(agent);
+            let _ = printer.join();
+
+            match result {
+                Ok(run) => {
+                    println!("\n  Claude:\n");
+                    for line in run.answer.lines() {
+                        println!("  {}", line);
+                    }
+                    println!();
+                }
+                Err(e) => println!("  [!] Error: {}", e),
+            }
+            Ok(())
+        }
+
         ClaudeCommands::Status => {
             println!("\n  CLAUDE STATUS");
             println!("  =============\n");
@@ -4742,6 +5928,7 @@ fn cmd_claude(command: ClaudeCommands) -> Result<()> {
                         "***".to_string()
                     };
                     println!("  API Key:     {} (set)", masked);
+                    println!("  Route:       gateway (content filter, audit chain)");
                 }
                 Err(_) => {
                     println!("  API Key:     NOT SET");
@@ -4769,7 +5956,7 @@ fn cmd_claude(command: ClaudeCommands) -> Result<()> {
             // Test connection
             if api_key.is_ok() {
                 println!("  Testing connection...");
-                match ClaudeClient::new() {
+                match claude_client(ClaudeModel::Sonnet) {
                     Ok(client) => {
                         match client.ask("Say 'OK' if you can hear me.") {
                             Ok(_) => println!("  Connection:  OK"),
@@ -5535,3 +6722,52 @@ fn cmd_security(command: SecurityCommands) -> Result<()> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gently_brain::{AgentConfig, AgentModel, BrainConfig, BrainOrchestrator, ConfirmRequest, ModelTurn, StopReason, ToolUseResponse, Turn};
+    use std::sync::Arc;
+
+    /// Calls `knowledge_learn` once, then answers
+    struct Learner(usize);
+
+    impl AgentModel for Learner {
+        fn step(&mut self, _: &[Turn], _: &[serde_json::Value]) -> gently_brain::Result<ModelTurn> {
+            self.0 += 1;
+            let tool_uses = if self.0 == 1 {
+                vec![ToolUseResponse {
+                    id: "t1".into(),
+                    name: "knowledge_learn".into(),
+                    input: serde_json::json!({"concept": "kyber is kem", "context": "pqc"}),
+                }]
+            } else {
+                vec![]
+            };
+            Ok(ModelTurn { text: "ok".into(), tool_uses, ..Default::default() })
+        }
+    }
+
+    #[test]
+    fn test_agent_runs_tools_through_orchestrator() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        for allow in [true, false] {
+            let orchestrator = Arc::new(BrainOrchestrator::new(BrainConfig {
+                enable_daemons: false,
+                ..Default::default()
+            }));
+            orchestrator.tool_registry().set_policy(gently_brain::ToolPolicy::cautious());
+            let agent = brain_agent(
+                orchestrator.clone(),
+                rt.handle().clone(),
+                Arc::new(move |_: &ConfirmRequest| allow),
+                AgentConfig::default(),
+            );
+
+            let run = agent.run(&mut Learner(0), "learn kyber").unwrap();
+            assert_eq!(run.stop, StopReason::Completed);
+            assert_eq!(run.scratchpad[0].success, allow);
+            assert_eq!(orchestrator.knowledge_graph().find("kyber").is_some(), allow);
+        }
+    }
+}
@@ -238,6 +238,8 @@ pub struct App {
     pub feed_items: VecDeque<FeedItem>,
     pub feed_scroll: usize,
     pub feed_selected: usize,
+    /// Recent operations of the loaded feed workspace, newest first
+    pub feed_timeline: Vec<String>,
 
     // Chat
     pub chat_messages: VecDeque<ChatMessage>,
@@ -293,6 +295,7 @@ impl App {
             feed_items: VecDeque::with_capacity(MAX_FEED_ITEMS),
             feed_scroll: 0,
             feed_selected: 0,
+            feed_timeline: Vec::new(),
 
             chat_messages: VecDeque::with_capacity(MAX_CHAT_MESSAGES),
             chat_input: String::new(),
@@ -781,6 +784,69 @@ impl App {
         false
     }
 
+    /// Show feed workspaces in chat
+    fn list_feed_workspaces(&mut self) {
+        let listing = gently_feed::FeedWorkspaces::default_location().and_then(|ws| {
+            let active = ws.active();
+            let lines: Vec<String> = ws
+                .list()?
+                .into_iter()
+                .map(|name| format!("{} {}", if name == active { "*" } else { "-" }, name))
+                .collect();
+            Ok(lines.join("\n"))
+        });
+        let msg = match listing {
+            Ok(lines) => format!("Feed workspaces:\n{}\n\nUse /feed <name> to switch.", lines),
+            Err(e) => format!("Failed to list feed workspaces: {}", e),
+        };
+        self.push_chat_message(ChatSender::System, &msg);
+    }
+
+    /// Switch the active feed workspace and show its items
+    fn switch_feed_workspace(&mut self, name: &str) {
+        let loaded = gently_feed::FeedWorkspaces::default_location().and_then(|ws| {
+            ws.switch(name)?;
+            ws.load(name)
+        });
+        let feed = match loaded {
+            Ok(feed) => feed,
+            Err(e) => {
+                self.push_chat_message(ChatSender::System, &format!("Failed to switch feed: {}", e));
+                return;
+            }
+        };
+
+        let mut items: Vec<_> = feed.items().iter().filter(|i| !i.archived).collect();
+        // Coolest first, so the hottest item ends up on top
+        items.sort_by(|a, b| a.charge.total_cmp(&b.charge));
+
+        self.feed_items.clear();
+        self.feed_scroll = 0;
+        self.feed_selected = 0;
+        for item in &items {
+            let temp = match item.charge {
+                c if c > 0.8 => Temperature::Hot,
+                c if c > 0.4 => Temperature::Warm,
+                c if c > 0.1 => Temperature::Cool,
+                _ => Temperature::Cold,
+            };
+            let pending = item.pending_steps().len();
+            let desc = format!("{:?} [{:.2}] {} pending steps", item.kind, item.charge, pending);
+            self.push_feed_item(temp, &item.name, &desc, &format!("feed:{}", name));
+        }
+
+        self.feed_timeline = feed
+            .recent_history(5)
+            .into_iter()
+            .map(|e| format!("{} {}", e.at.with_timezone(&Local).format("%H:%M"), e.op))
+            .collect();
+
+        self.push_chat_message(
+            ChatSender::System,
+            &format!("Feed workspace: {} ({} items)", name, items.len()),
+        );
+    }
+
     fn process_chat_input(&mut self, input: &str) {
         // Add user message
         self.push_chat_message(ChatSender::User, input);
@@ -821,6 +887,13 @@ impl App {
                             &format!("Theme changed to: {:?}", self.theme));
                         return;
                     }
+                    "/feed" => {
+                        match parts.get(1) {
+                            Some(name) => self.switch_feed_workspace(name),
+                            None => self.list_feed_workspaces(),
+                        }
+                        return;
+                    }
                     "/boneblob" | "/bb" => {
                         if let Some(arg) = parts.get(1) {
                             let enabled = matches!(arg.to_lowercase().as_str(),
@@ -1,7 +1,10 @@
 //! Claude API Integration for GentlyOS TUI
 //!
-//! Async Claude client for real-time chat in the terminal UI.
+//! Async Claude client for real-time chat in the terminal UI. Requests go
+//! through the GentlyOS gateway (content filter, audit chain).
 
+use gently_gateway::backend::MODEL_KEY;
+use gently_gateway::{claude_gateway, Gateway, GatewayMessage, GatewayRequest, ProviderPreference};
 use serde::{Deserialize, Serialize};
 use std::env;
 use tokio::sync::mpsc;
@@ -63,63 +66,31 @@ impl Message {
     }
 }
 
-#[derive(Serialize)]
-struct ApiRequest {
-    model: String,
-    max_tokens: usize,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    system: Option<String>,
-    messages: Vec<Message>,
-}
-
-#[derive(Deserialize)]
-struct ApiResponse {
-    content: Vec<ContentBlock>,
-}
-
-#[derive(Deserialize)]
-struct ContentBlock {
-    #[serde(rename = "type")]
-    content_type: String,
-    text: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct ApiError {
-    error: ErrorDetail,
-}
-
-#[derive(Deserialize)]
-struct ErrorDetail {
-    message: String,
-}
-
 /// Async Claude client for TUI
 pub struct ClaudeClient {
-    api_key: Option<String>,
+    /// `None` without ANTHROPIC_API_KEY
+    gateway: Option<Gateway>,
     model: ClaudeModel,
     system_prompt: String,
     conversation: Vec<Message>,
     max_tokens: usize,
-    http_client: reqwest::Client,
 }
 
 impl ClaudeClient {
     pub fn new() -> Self {
-        let api_key = env::var("ANTHROPIC_API_KEY").ok();
+        let gateway = env::var("ANTHROPIC_API_KEY").ok().map(claude_gateway);
 
         Self {
-            api_key,
+            gateway,
             model: ClaudeModel::default(),
             system_prompt: GENTLY_SYSTEM_PROMPT.to_string(),
             conversation: Vec::new(),
             max_tokens: 2048,
-            http_client: reqwest::Client::new(),
         }
     }
 
     pub fn has_api_key(&self) -> bool {
-        self.api_key.is_some()
+        self.gateway.is_some()
     }
 
     pub fn model(&self) -> ClaudeModel {
@@ -136,60 +107,33 @@ impl ClaudeClient {
 
     /// Send a message and get response asynchronously
     pub async fn chat(&mut self, message: &str) -> ClaudeResponse {
-        let api_key = match &self.api_key {
-            Some(key) => key.clone(),
-            None => return ClaudeResponse::Error(
+        let Some(gateway) = self.gateway.as_mut() else {
+            return ClaudeResponse::Error(
                 "ANTHROPIC_API_KEY not set. Export your API key to enable Claude chat.".to_string()
-            ),
-        };
-
-        // Add user message to history
-        self.conversation.push(Message::user(message));
-
-        let request = ApiRequest {
-            model: self.model.api_name().to_string(),
-            max_tokens: self.max_tokens,
-            system: Some(self.system_prompt.clone()),
-            messages: self.conversation.clone(),
+            );
         };
 
-        let result = self.http_client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", &api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await;
-
-        match result {
+        let history = self.conversation.iter()
+            .map(|m| match m.role.as_str() {
+                "assistant" => GatewayMessage::assistant(&m.content),
+                _ => GatewayMessage::user(&m.content),
+            })
+            .collect();
+        let mut request = GatewayRequest::new(message)
+            .system(self.system_prompt.clone())
+            .with_history(history)
+            .max_tokens(self.max_tokens)
+            .prefer(ProviderPreference::Specific("claude".into()));
+        request.metadata.insert(MODEL_KEY.into(), serde_json::json!(self.model.api_name()));
+
+        match gateway.process(request).await {
             Ok(response) => {
-                if response.status().is_success() {
-                    match response.json::<ApiResponse>().await {
-                        Ok(body) => {
-                            let text: String = body.content
-                                .iter()
-                                .filter_map(|c| c.text.as_ref())
-                                .cloned()
-                                .collect::<Vec<_>>()
-                                .join("");
-
-                            // Add assistant response to history
-                            self.conversation.push(Message::assistant(&text));
-
-                            ClaudeResponse::Text(text)
-                        }
-                        Err(e) => ClaudeResponse::Error(format!("Parse error: {}", e)),
-                    }
-                } else {
-                    // Try to parse error response
-                    match response.json::<ApiError>().await {
-                        Ok(error) => ClaudeResponse::Error(error.error.message),
-                        Err(_) => ClaudeResponse::Error("API request failed".to_string()),
-                    }
-                }
+                // Keep the exchange only once it went through
+                self.conversation.push(Message::user(message));
+                self.conversation.push(Message::assistant(&response.content));
+                ClaudeResponse::Text(response.content)
             }
-            Err(e) => ClaudeResponse::Error(format!("Network error: {}", e)),
+            Err(e) => ClaudeResponse::Error(format!("Gateway: {}", e)),
         }
     }
 }
@@ -1,8 +1,13 @@
 //! Multi-Provider LLM Integration for GentlyOS TUI
 //!
 //! Supports: Anthropic, OpenAI, DeepSeek, Grok, Ollama, LM Studio, HuggingFace
+//!
+//! Anthropic (the default) is reached through the GentlyOS gateway, so chat
+//! gets its content filter and audit chain.
 
 use crate::boneblob::{BoneBlobPipeline, default_system_bones};
+use gently_gateway::backend::MODEL_KEY;
+use gently_gateway::{claude_gateway, Gateway, GatewayMessage, GatewayRequest, ProviderPreference};
 use serde::{Deserialize, Serialize};
 use std::env;
 use tokio::sync::mpsc;
@@ -270,18 +275,30 @@ impl Message {
     }
 }
 
+/// Claude gateway when the provider is Anthropic and a key is set
+fn gateway_for(config: &ProviderConfig) -> Option<Gateway> {
+    match (config.model.provider, &config.api_key) {
+        (Provider::Anthropic, Some(key)) => Some(claude_gateway(key.clone())),
+        _ => None,
+    }
+}
+
 /// Multi-provider LLM client
 pub struct LlmClient {
     config: ProviderConfig,
     conversation: Vec<Message>,
     system_prompt: String,
     http_client: reqwest::Client,
+    /// Gateway for Anthropic requests
+    gateway: Option<Gateway>,
 }
 
 impl LlmClient {
     pub fn new(provider: Provider) -> Self {
+        let config = ProviderConfig::new(provider);
         Self {
-            config: ProviderConfig::new(provider),
+            gateway: gateway_for(&config),
+            config,
             conversation: Vec::new(),
             system_prompt: GENTLY_SYSTEM_PROMPT.to_string(),
             http_client: reqwest::Client::new(),
@@ -298,6 +315,7 @@ impl LlmClient {
 
     pub fn set_provider(&mut self, provider: Provider) {
         self.config = ProviderConfig::new(provider);
+        self.gateway = gateway_for(&self.config);
         self.conversation.clear();
     }
 
@@ -350,57 +368,30 @@ impl LlmClient {
         }
     }
 
-    /// Anthropic Claude API
-    async fn chat_anthropic(&self) -> Result<String, String> {
-        #[derive(Serialize)]
-        struct Request {
-            model: String,
-            max_tokens: usize,
-            system: String,
-            messages: Vec<Message>,
-        }
-
-        #[derive(Deserialize)]
-        struct Response {
-            content: Vec<ContentBlock>,
-        }
-
-        #[derive(Deserialize)]
-        struct ContentBlock {
-            text: Option<String>,
-        }
-
-        let request = Request {
-            model: self.config.model.model_id.clone(),
-            max_tokens: self.config.max_tokens,
-            system: self.system_prompt.clone(),
-            messages: self.conversation.clone(),
+    /// Anthropic Claude, through the gateway
+    async fn chat_anthropic(&mut self) -> Result<String, String> {
+        let Some(gateway) = self.gateway.as_mut() else {
+            return Err("Gateway not configured".to_string());
         };
 
-        let response = self.http_client
-            .post(&self.config.base_url)
-            .header("x-api-key", self.config.api_key.as_deref().unwrap_or(""))
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| format!("Network error: {}", e))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(format!("API error {}: {}", status, body));
-        }
+        let mut history: Vec<GatewayMessage> = self.conversation.iter()
+            .map(|m| match m.role.as_str() {
+                "assistant" => GatewayMessage::assistant(&m.content),
+                _ => GatewayMessage::user(&m.content),
+            })
+            .collect();
+        let prompt = history.pop().map(|m| m.content).unwrap_or_default();
 
-        let body: Response = response.json().await
-            .map_err(|e| format!("Parse error: {}", e))?;
+        let mut request = GatewayRequest::new(prompt)
+            .system(self.system_prompt.clone())
+            .with_history(history)
+            .max_tokens(self.config.max_tokens)
+            .prefer(ProviderPreference::Specific("claude".into()));
+        request.metadata.insert(MODEL_KEY.into(), serde_json::json!(self.config.model.model_id));
 
-        Ok(body.content.iter()
-            .filter_map(|c| c.text.as_ref())
-            .cloned()
-            .collect::<Vec<_>>()
-            .join(""))
+        gateway.process(request).await
+            .map(|response| response.content)
+            .map_err(|e| format!("Gateway: {}", e))
     }
 
     /// OpenAI-compatible API (GPT, DeepSeek, Grok, LM Studio)
@@ -810,6 +801,7 @@ fn handle_command(cmd: &str, client: &mut LlmClient, boneblob: &BoneBlobPipeline
                  /clear            - Clear chat history\n\
                  /status           - Show GentlyOS status\n\
                  /dance            - Toggle dance state\n\
+                 /feed [name]      - List/switch feed workspaces\n\
                  /help             - Show this help\n\n\
                  BONEBLOB: Constraint-based optimization pipeline\n\
                  - BONES: Preprompt constraints (immutable rules)\n\
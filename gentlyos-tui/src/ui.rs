@@ -171,9 +171,28 @@ fn render_living_feed(frame: &mut Frame, app: &App, palette: &ThemePalette, area
         .padding(Padding::horizontal(1))
         .style(palette.base_style());
 
-    let inner = block.inner(area);
+    let mut inner = block.inner(area);
     frame.render_widget(block, area);
 
+    // Timeline of recent feed operations along the bottom
+    let timeline_height = (app.feed_timeline.len() as u16 + 1).min(inner.height / 3);
+    if timeline_height > 1 {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(timeline_height)])
+            .split(inner);
+        inner = chunks[0];
+
+        let mut lines = vec![Line::from(Span::styled("── history ──", palette.muted_style()))];
+        lines.extend(
+            app.feed_timeline
+                .iter()
+                .take(timeline_height as usize - 1)
+                .map(|entry| Line::from(Span::styled(truncate_str(entry, chunks[1].width as usize), Styles::timestamp(palette)))),
+        );
+        frame.render_widget(Paragraph::new(lines), chunks[1]);
+    }
+
     // Render feed items
     let items: Vec<ListItem> = app
         .feed_items
@@ -837,7 +856,7 @@ fn render_help_overlay(frame: &mut Frame, app: &App, palette: &ThemePalette) {
         Line::from(vec![
             Span::styled("Chat Commands", palette.primary_style()),
         ]),
-        Line::from("  /help /status /dance /clear"),
+        Line::from("  /help /status /dance /feed /clear"),
     ];
 
     let help = Paragraph::new(help_text)